@@ -1,6 +1,7 @@
 use eframe::egui;
 use log::{info, warn};
 
+use crate::audio_player::AudioPlayer;
 use crate::models::AppState;
 use crate::ui::UIComponents;
 use crate::threading::ThreadedTaskProcessor;
@@ -15,19 +16,35 @@ pub struct ZeusMusicApp {
     task_processor: ThreadedTaskProcessor,
     /// 生命周期管理器
     lifecycle: lifecycle::AppLifecycle,
+    /// 上一帧是否有转换工具对话框打开（用于在关闭时保存工具设置）
+    tool_dialogs_open: bool,
+    /// 最近一次收到任务消息的时间（任务看门狗使用）
+    last_task_message: std::time::Instant,
+    /// 播放列表播放器
+    audio_player: AudioPlayer,
 }
 
+/// 任务线程退出后等待完成消息的时间，超时则视为线程意外结束
+const TASK_WATCHDOG_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 impl ZeusMusicApp {
-    pub fn new() -> Self {
+    pub fn new(safe_mode: bool) -> Self {
         info!("初始化Zeus Music Mod Generator");
         
-        // 从配置文件加载状态
-        let state = AppState::load_config();
+        // 从配置文件加载状态（安全模式下跳过）
+        let state = if safe_mode {
+            AppState::safe_mode_state()
+        } else {
+            AppState::load_config()
+        };
         
         let mut app = Self {
             state,
             task_processor: ThreadedTaskProcessor::new(),
             lifecycle: lifecycle::AppLifecycle::new(),
+            tool_dialogs_open: false,
+            last_task_message: std::time::Instant::now(),
+            audio_player: AudioPlayer::new(),
         };
         
         // 强制刷新FFmpeg配置，确保下载后的路径被正确识别
@@ -56,7 +73,38 @@ impl ZeusMusicApp {
 }
 
 impl eframe::App for ZeusMusicApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        crate::i18n::set_language(self.state.language);
+
+        // 应用界面主题：只替换配色，setup_custom_fonts 设置的字体和文本样式不受影响
+        let system_dark = frame.info().system_theme.map(|theme| theme == eframe::Theme::Dark);
+        if let Some(dark) = self.state.theme.dark_mode(system_dark) {
+            if ctx.style().visuals.dark_mode != dark {
+                ctx.set_visuals(if dark { egui::Visuals::dark() } else { egui::Visuals::light() });
+            }
+        }
+
+        // 拦截窗口关闭请求：有运行中的任务或未导出的修改时先确认
+        if ctx.input(|i| i.viewport().close_requested())
+            && self.state.confirm_on_close
+            && !self.state.close_confirmed
+            && !self.state.close_warnings().is_empty()
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.state.show_close_confirm = true;
+        }
+
+        // 处理拖入窗口的文件
+        let dropped_files: Vec<std::path::PathBuf> = ctx.input(|i| {
+            i.raw.dropped_files.iter().filter_map(|file| file.path.clone()).collect()
+        });
+        if !dropped_files.is_empty() {
+            self.handle_dropped_files(dropped_files);
+        }
+
+        self.handle_shortcuts(ctx);
+        self.remember_window_geometry(ctx);
+
         // 渲染菜单栏
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             UIComponents::render_menu_bar(ui, &mut self.state);
@@ -85,6 +133,21 @@ impl eframe::App for ZeusMusicApp {
                 // 显示轨道列表
                 UIComponents::render_track_list(ui, &mut self.state);
             });
+
+            // 文件拖动到窗口上方时高亮提示
+            if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+                let rect = ui.max_rect();
+                let painter = ui.painter();
+                painter.rect_filled(rect, 4.0, egui::Color32::from_rgba_unmultiplied(60, 120, 200, 40));
+                painter.rect_stroke(rect, 4.0, egui::Stroke::new(2.0, egui::Color32::from_rgb(60, 120, 200)));
+                painter.text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    "松开以添加 OGG 轨道或加载项目文件",
+                    egui::FontId::proportional(18.0),
+                    ui.visuals().strong_text_color(),
+                );
+            }
         });
 
         // 渲染底部按钮
@@ -98,6 +161,7 @@ impl eframe::App for ZeusMusicApp {
         // 显示对话框
         UIComponents::show_project_settings_dialog(ctx, &mut self.state);
         UIComponents::show_export_dialog(ctx, &mut self.state);
+        UIComponents::show_cfg_preview_dialog(ctx, &mut self.state);
         let uptime = self.get_uptime();
         UIComponents::show_about_dialog(ctx, &mut self.state, uptime);
         UIComponents::show_user_guide_dialog(ctx, &mut self.state);
@@ -113,8 +177,24 @@ impl eframe::App for ZeusMusicApp {
         UIComponents::show_audio_convert_result_dialog(ctx, &mut self.state);
         UIComponents::show_video_converter_dialog(ctx, &mut self.state);
         UIComponents::show_video_convert_result_dialog(ctx, &mut self.state);
-        UIComponents::show_ffmpeg_download_dialog(ctx, &mut self.state);
+        UIComponents::show_conversion_history_dialog(ctx, &mut self.state);
+        UIComponents::show_similar_tracks_dialog(ctx, &mut self.state);
+        UIComponents::show_ingame_order_dialog(ctx, &mut self.state);
+        UIComponents::show_duration_sync_result_dialog(ctx, &mut self.state);
+        UIComponents::show_batch_tag_dialog(ctx, &mut self.state);
+        UIComponents::show_decibel_ramp_dialog(ctx, &mut self.state);
+        UIComponents::show_decibel_adjust_dialog(ctx, &mut self.state);
+        UIComponents::show_audio_player_dialog(ctx, &mut self.state, &mut self.audio_player);
+        UIComponents::show_chapter_split_dialog(ctx, &mut self.state);
+        UIComponents::show_folder_import_dialog(ctx, &mut self.state);
+        UIComponents::show_container_mod_dialog(ctx, &mut self.state);
+        UIComponents::show_config_recovery_dialog(ctx, &mut self.state);
+        UIComponents::show_project_compare_dialog(ctx, &mut self.state);
+        UIComponents::show_close_confirm_dialog(ctx, &mut self.state);
+        UIComponents::show_project_load_confirm_dialog(ctx, &mut self.state);
+        UIComponents::show_ffmpeg_download_dialog(ctx, &mut self.state, &self.task_processor);
         UIComponents::show_ffmpeg_plugin_dialog(ctx, &mut self.state);
+        UIComponents::show_performance_settings_dialog(ctx, &mut self.state);
         UIComponents::show_manual_path_selection_dialog(ctx, &mut self.state);
         UIComponents::show_progress_dialog(ctx, &mut self.state, &mut self.task_processor);
         
@@ -123,34 +203,134 @@ impl eframe::App for ZeusMusicApp {
             if let Some(ref output_dir) = self.state.audio_decrypt_output_directory {
                 let output_dir = output_dir.clone();
                 let selected_files = self.state.audio_decrypt_selected_files.clone();
+                self.state.audio_decrypt_result_dir = Some(output_dir.clone());
                 self.start_audio_decrypt_task(selected_files, output_dir);
             }
             self.state.should_decrypt_audio = false;
             self.state.show_audio_decrypt = false;
         }
         
+        // 检查是否需要执行解密→转换→添加流水线
+        if self.state.should_run_decrypt_pipeline {
+            if let Some(output_dir) = self.state.audio_decrypt_output_directory.clone() {
+                let selected_files = self.state.audio_decrypt_selected_files.clone();
+                self.state.audio_decrypt_result_dir = Some(output_dir.clone());
+                self.start_decrypt_pipeline(selected_files, output_dir);
+            }
+            self.state.should_run_decrypt_pipeline = false;
+            self.state.show_audio_decrypt = false;
+        }
+        
         // 检查是否需要执行音频转换
         if self.state.should_convert_audio {
             if let Some(ref output_dir) = self.state.audio_convert_output_directory {
                 let output_dir = output_dir.clone();
                 let selected_files = self.state.audio_convert_selected_files.clone();
+                self.state.audio_convert_result_dir = Some(output_dir.clone());
                 self.start_audio_convert_task(selected_files, output_dir);
             }
             self.state.should_convert_audio = false;
             self.state.show_audio_converter = false;
         }
         
+        // 检查是否需要执行相似轨道分析
+        if self.state.should_scan_similar_tracks {
+            self.state.should_scan_similar_tracks = false;
+            self.start_similar_track_scan();
+        }
+        
+        // 检查是否需要从文件夹导入
+        if self.state.should_import_folder {
+            self.state.should_import_folder = false;
+            self.start_folder_import();
+        }
+        
+        // 检查是否需要按章节分割
+        if self.state.should_split_chapters {
+            self.state.should_split_chapters = false;
+            self.start_chapter_split();
+        }
+        
+        // 检查是否需要同步全部轨道时长
+        if self.state.should_sync_durations {
+            self.state.should_sync_durations = false;
+            self.start_duration_sync();
+        }
+        
+        // 视频转换对话框请求的时长在后台读取
+        let duration_requests = self.state.video_durations.take_requests();
+        if !duration_requests.is_empty() {
+            self.task_processor.probe_video_durations(duration_requests);
+        }
+        
+        // 项目设置对话框请求的封面检查在后台进行
+        let cover_requests = self.state.cover_art_cache.take_requests();
+        if !cover_requests.is_empty() {
+            self.task_processor.probe_cover_art(cover_requests);
+        }
+        
+        // 按章节分割对话框请求的章节在后台读取
+        let chapter_requests = self.state.chapter_cache.take_requests();
+        if !chapter_requests.is_empty() {
+            self.task_processor.read_chapters(chapter_requests);
+        }
+        
+        // 轨道编辑器请求的波形在后台分析
+        let waveform_requests = self.state.waveform_cache.take_requests();
+        if !waveform_requests.is_empty() {
+            self.task_processor.compute_waveforms(waveform_requests);
+        }
+        
+        // 新导入轨道的时长等信息在后台补全
+        let import_jobs = std::mem::take(&mut self.state.pending_import_jobs);
+        if !import_jobs.is_empty() {
+            self.task_processor.process_track_import_jobs(import_jobs);
+        }
+        
+        // 检查是否需要为 Zeus 准备并导出
+        if self.state.should_prepare_for_zeus {
+            self.state.should_prepare_for_zeus = false;
+            self.start_zeus_preparation();
+        }
+        
+        // 检查是否有已通过检查、等待在后台执行的模组导出
+        if let Some(job) = self.state.pending_mod_export.take() {
+            self.start_mod_export(job);
+        }
+        
+        // 检查是否需要分析当前编辑轨道的音量
+        if self.state.should_analyze_loudness {
+            self.state.should_analyze_loudness = false;
+            self.start_loudness_analysis();
+        }
+        
         // 检查是否需要执行视频转换
         if self.state.should_convert_video {
             if let Some(ref output_dir) = self.state.video_convert_output_directory {
                 let output_dir = output_dir.clone();
                 let selected_files = self.state.video_convert_selected_files.clone();
+                self.state.video_convert_result_dir = Some(output_dir.clone());
                 self.start_video_convert_task(selected_files, output_dir);
             }
             self.state.should_convert_video = false;
             self.state.show_video_converter = false;
         }
         
+        // 转换工具对话框关闭时保存设置，使工具选项跨会话保留
+        let tool_dialogs_open = self.state.show_paa_converter
+            || self.state.show_audio_decrypt
+            || self.state.show_audio_converter
+            || self.state.show_video_converter
+            || self.state.show_ffmpeg_plugin
+            || self.state.show_performance_settings
+            || self.state.show_folder_import;
+        if self.tool_dialogs_open && !tool_dialogs_open {
+            if let Err(e) = self.state.save_config() {
+                warn!("保存工具设置失败: {}", e);
+            }
+        }
+        self.tool_dialogs_open = tool_dialogs_open;
+        
         // 检查是否需要下载 FFmpeg
         if self.state.is_downloading_ffmpeg && !self.state.ffmpeg_download_started {
             self.start_ffmpeg_download_task();
@@ -169,8 +349,10 @@ impl eframe::App for ZeusMusicApp {
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
         info!("程序开始快速关闭...");
         
-        // 1. 立即取消所有正在运行的任务
+        // 1. 立即取消所有正在运行的任务和下载并停止播放
         self.task_processor.cancel_task();
+        self.task_processor.cancel_ffmpeg_download();
+        self.audio_player.stop();
         
         // 2. 快速保存配置文件（异步，不等待完成）
         // 注意：由于 std::process::exit(0) 会立即终止所有线程，
@@ -209,26 +391,259 @@ impl eframe::App for ZeusMusicApp {
 }
 
 impl ZeusMusicApp {
+    /// 记录当前窗口位置和大小，退出时随配置一起保存
+    ///
+    /// `on_exit` 无法访问 `ctx`，因此每帧记录；最小化时不更新，最大化时保留最大化前的位置和大小，
+    /// 没有最大化前的记录时不记录（避免把最大化尺寸当作还原尺寸）。
+    fn remember_window_geometry(&mut self, ctx: &egui::Context) {
+        let (outer_rect, inner_rect, minimized, maximized, scale) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (
+                viewport.outer_rect,
+                viewport.inner_rect,
+                viewport.minimized.unwrap_or(false),
+                viewport.maximized.unwrap_or(false),
+                i.pixels_per_point,
+            )
+        });
+        if minimized {
+            return;
+        }
+        let geometry = match (maximized, self.state.window_geometry) {
+            (true, Some(previous)) => crate::models::WindowGeometry { maximized: true, ..previous },
+            (true, None) => return,
+            (false, _) => {
+                let Some(inner_rect) = inner_rect else { return };
+                crate::models::WindowGeometry {
+                    position: outer_rect.map(|rect| [rect.min.x, rect.min.y]),
+                    size: [inner_rect.width(), inner_rect.height()],
+                    maximized,
+                    scale,
+                }
+            }
+        };
+        if self.state.window_geometry != Some(geometry) {
+            self.state.window_geometry = Some(geometry);
+        }
+    }
+
+    /// 处理键盘快捷键：Ctrl+O 添加文件，Ctrl+E 导出，Ctrl+S 保存项目，Delete 删除选中项
+    ///
+    /// 文本框获得焦点（避免在轨道名称中按 Delete 删除轨道）或进度对话框打开时忽略快捷键。
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() || self.state.task_manager.show_progress {
+            return;
+        }
+
+        use crate::utils::constants::shortcuts::{ADD_FILES, DELETE, EXPORT, SAVE_PROJECT, UNDO};
+
+        let is_music = self.state.project.mod_type == crate::models::ModType::Music;
+
+        if ctx.input_mut(|i| i.consume_shortcut(&ADD_FILES)) {
+            if is_music {
+                UIComponents::add_audio_files(ctx, &mut self.state);
+            } else {
+                UIComponents::add_video_files(ctx, &mut self.state);
+            }
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&EXPORT)) {
+            self.state.show_export_dialog = true;
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&SAVE_PROJECT)) {
+            UIComponents::save_project_as(&mut self.state);
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&DELETE)) {
+            if is_music {
+                self.state.remove_selected_track();
+            } else {
+                self.state.remove_selected_video();
+            }
+            self.state.file_operation_message = None; // 清除提示信息
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&UNDO)) && is_music {
+            UIComponents::undo_track_change(&mut self.state);
+        }
+    }
+
+    /// 处理拖入窗口的文件：.zmm/.json 作为项目文件加载，.ogg 作为轨道添加，其他文件忽略
+    fn handle_dropped_files(&mut self, files: Vec<std::path::PathBuf>) {
+        use crate::utils::FileUtils;
+
+        let is_project = |path: &std::path::PathBuf| {
+            path.extension().is_some_and(|ext| {
+                ext.eq_ignore_ascii_case(crate::utils::constants::file_ops::PROJECT_FILE_EXTENSION)
+                    || ext.eq_ignore_ascii_case("json")
+            })
+        };
+        if self.state.task_manager.is_running() {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+
+        if let Some(project_path) = files.iter().find(|path| is_project(path)) {
+            if files.iter().filter(|path| is_project(path)).count() > 1 {
+                warn!("拖入了多个项目文件，仅加载第一个: {:?}", project_path);
+            }
+            UIComponents::request_project_load(&mut self.state, project_path.clone());
+            return;
+        }
+
+        let (audio_files, ignored): (Vec<_>, Vec<_>) = files
+            .into_iter()
+            .partition(|path| FileUtils::is_supported_audio_file(path));
+        for path in &ignored {
+            info!("忽略拖入的非 OGG 文件: {:?}", path);
+        }
+        let ignored_note = if ignored.is_empty() {
+            String::new()
+        } else {
+            format!("；忽略了 {} 个非 OGG 文件", ignored.len())
+        };
+        if audio_files.is_empty() {
+            self.state.file_operation_message = Some(format!("拖入的文件中没有 OGG 文件{}", ignored_note));
+            return;
+        }
+
+        match crate::file_ops::FileOperations::load_audio_files(
+            audio_files,
+            &self.state.project.class_name,
+            &self.state.track_defaults,
+        ) {
+            Ok(tracks) => {
+                let (added_count, duplicate_count) = self.state.add_tracks_with_duplicate_check(tracks);
+                info!("拖放添加了 {} 个轨道，跳过了 {} 个重复", added_count, duplicate_count);
+                self.state.file_operation_message = Some(format!(
+                    "拖放添加了 {} 个轨道，跳过了 {} 个重复文件{}",
+                    added_count, duplicate_count, ignored_note
+                ));
+            }
+            Err(e) => {
+                warn!("加载拖入的音频文件失败: {}", e);
+                self.state.file_operation_message = Some(format!("加载音频文件失败: {}", e));
+            }
+        }
+    }
+
     /// 处理多线程任务进度更新
     pub fn process_threaded_tasks(&mut self) {
         use crate::threading::TaskMessage;
         
+        // 先检查线程状态再接收消息，确保线程退出前发送的消息都已处理
+        let worker_finished = self.task_processor.worker_finished();
+        
         // 处理所有待处理的进度消息
-        while let Ok(message) = self.task_processor.get_progress_receiver().try_recv() {
+        while let Some(message) = self.task_processor.try_recv_message() {
+            self.last_task_message = std::time::Instant::now();
             match message {
                 TaskMessage::UpdateProgress { current_file, filename } => {
                     if let Some(ref mut _task) = self.state.task_manager.current_task {
                         self.state.task_manager.update_progress(current_file, &filename);
                     }
                 }
+                TaskMessage::FileProgress { task_id, fraction } => {
+                    self.state.task_manager.update_file_progress(task_id, fraction);
+                }
                 TaskMessage::ParallelProgressUpdate(update) => {
                     self.handle_parallel_progress_update(update);
                 }
                 TaskMessage::ChunkProgressUpdate(update) => {
                     self.handle_chunk_progress_update(update);
                 }
-                TaskMessage::FFmpegDownloadProgress { progress, status } => {
+                TaskMessage::OutputProduced { input_path, output_path } => {
+                    self.record_conversion_output(input_path, output_path);
+                }
+                TaskMessage::ConversionFailed { input_path } => {
+                    self.record_failed_conversion(input_path);
+                }
+                TaskMessage::FileFailed => {
+                    self.state.task_manager.record_file_result(false);
+                }
+                TaskMessage::SimilarTracksFound { groups, undecodable_count } => {
+                    self.state.task_manager.complete_task();
+                    // 对话框显示文件大小，在结果更新时读取一次（而不是每帧读取）
+                    let paths: std::collections::HashSet<&std::path::PathBuf> = groups.iter().flat_map(|group| &group.paths).collect();
+                    self.state.tracks.iter_mut().filter(|t| paths.contains(&t.path)).for_each(crate::models::Track::refresh_file_size);
+                    self.state.similar_track_groups = Some(groups);
+                    self.state.similar_track_undecodable = undecodable_count;
+                    self.state.show_similar_tracks = true;
+                }
+                TaskMessage::FolderImported { result } => {
+                    self.state.task_manager.complete_task();
+                    UIComponents::finish_folder_import(&mut self.state, result);
+                }
+                TaskMessage::ChaptersSplit { outputs, failed } => {
+                    self.state.task_manager.complete_task();
+                    self.add_split_chapter_tracks(outputs, failed);
+                }
+                TaskMessage::DecryptPipelineFinished { files, cancelled } => {
+                    self.state.task_manager.complete_task();
+                    self.add_decrypt_pipeline_tracks(files, cancelled);
+                }
+                TaskMessage::LoudnessMeasured { track_index, path, result } => match result {
+                    Ok(lufs) => {
+                        self.state.task_manager.complete_task();
+                        if self.state.apply_measured_loudness(track_index, &path, lufs) {
+                            let track = &self.state.tracks[track_index];
+                            self.state.file_operation_message = Some(format!(
+                                "音量分析完成：{:.1} LUFS，分贝已设为 {} dB",
+                                lufs, track.decibels
+                            ));
+                        } else {
+                            self.state.file_operation_message = Some("分析期间轨道已被移动或删除，音量分析结果未应用".to_string());
+                        }
+                    }
+                    Err(e) => {
+                        self.state.task_manager.fail_task(e.clone());
+                        self.state.file_operation_message = Some(format!("音量分析失败: {}", e));
+                    }
+                },
+                TaskMessage::VideoDurationProbed { path, duration } => {
+                    self.state.video_durations.insert(path, duration);
+                }
+                TaskMessage::CoverArtProbed { path, has_cover } => {
+                    self.state.cover_art_cache.insert(path, has_cover);
+                }
+                TaskMessage::ChaptersRead { path, chapters } => {
+                    self.state.chapter_cache.insert(path, chapters);
+                }
+                TaskMessage::WaveformComputed { path, waveform } => {
+                    self.state.waveform_cache.insert(path, waveform);
+                }
+                TaskMessage::TrackImportExtrasLoaded { extras } => {
+                    self.state.apply_import_extras(&extras);
+                }
+                TaskMessage::ZeusTracksProbed { probes } => {
+                    self.state.task_manager.complete_task();
+                    UIComponents::finish_zeus_preparation(&mut self.state, &probes);
+                }
+                TaskMessage::ModExportFinished { outcome } => {
+                    // 取消后任务已结束，不能误结束之后开始的其他任务
+                    if self.state.task_manager.get_current_progress().is_some_and(|task| task.task_type == crate::models::TaskType::ModExport) {
+                        if outcome.cancelled {
+                            self.state.task_manager.cancel_task();
+                        } else {
+                            self.state.task_manager.complete_task();
+                        }
+                    }
+                    UIComponents::finish_mod_export(&mut self.state, outcome);
+                }
+                TaskMessage::DurationsMeasured { durations, failed } => {
+                    self.state.task_manager.complete_task();
+                    let changed = self.state.apply_measured_durations(&durations);
+                    let mut summary = format!(
+                        "已测量 {} 个轨道，其中 {} 个轨道的时长已更新",
+                        durations.len(),
+                        changed
+                    );
+                    if !failed.is_empty() {
+                        summary.push_str(&format!("\n\n{} 个文件无法解码，时长保持不变：\n{}", failed.len(), failed.join("\n")));
+                    }
+                    self.state.duration_sync_result = Some(summary);
+                    self.state.show_duration_sync_result = true;
+                }
+                TaskMessage::FFmpegDownloadProgress { progress, status, downloaded_bytes, total_bytes } => {
                     self.state.ffmpeg_download_progress = progress;
+                    self.state.ffmpeg_download_stats.record(downloaded_bytes, total_bytes, progress, std::time::Instant::now());
                     // 添加调试日志
                     log::info!("FFmpeg 下载进度更新: {:.1}% - {}", progress, status);
                     self.state.ffmpeg_download_status = status;
@@ -240,82 +655,215 @@ impl ZeusMusicApp {
                     self.state.is_downloading_ffmpeg = false;
                     self.state.ffmpeg_download_started = false;
                     self.state.ffmpeg_download_progress = if success { 100.0 } else { 0.0 };
-                    
                     if success {
+                        self.state.track_editor_ffmpeg_available = None;
+                    }
+                    
+                    let switch_mirror = std::mem::take(&mut self.state.ffmpeg_download_switch_pending);
+                    if switch_mirror && message == crate::utils::constants::app::FFMPEG_DOWNLOAD_CANCELLED {
+                        // 连接停滞时切换镜像：由 update() 从新的镜像重新启动下载
+                        self.state.is_downloading_ffmpeg = true;
+                        self.state.ffmpeg_download_status = "准备下载...".to_string();
+                    } else if message == crate::utils::constants::app::FFMPEG_DOWNLOAD_CANCELLED {
+                        // 用户主动取消，不弹出失败提示
+                        self.state.ffmpeg_download_status = message;
+                    } else if success {
                         self.state.ffmpeg_download_status = "下载完成！".to_string();
                         self.state.audio_convert_result = Some(message);
+                        self.state.audio_convert_result_dir = None;
                         self.state.show_audio_convert_result = true;
                         // 不立即关闭下载对话框，让用户看到完成状态
                     } else {
                         self.state.ffmpeg_download_status = "下载失败！".to_string();
                         self.state.audio_convert_result = Some(message);
+                        self.state.audio_convert_result_dir = None;
                         self.state.show_audio_convert_result = true;
                     }
                 }
                 TaskMessage::TaskCompleted { success_count, error_count, results } => {
-                    self.state.task_manager.complete_task();
-                    
-                    // 根据任务类型处理结果
-                    if let Some(ref task) = self.state.task_manager.task_history.last() {
-                        match task.task_type {
-                            crate::models::TaskType::AudioDecrypt => {
-                                self.state.audio_decrypt_result = Some(format!(
-                                    "音频解密完成！\n\n成功: {}\n失败: {}\n\n详细结果:\n{}",
-                                    success_count,
-                                    error_count,
-                                    results.join("\n")
-                                ));
-                                self.state.show_audio_decrypt_result = true;
-                            }
-                            crate::models::TaskType::PaaConvert => {
-                                self.state.paa_result = Some(format!(
-                                    "PAA转换完成！\n\n成功: {}\n失败: {}\n\n详细结果:\n{}",
-                                    success_count,
-                                    error_count,
-                                    results.join("\n")
-                                ));
-                                self.state.show_paa_result = true;
-                            }
-                            crate::models::TaskType::AudioConvert => {
-                                self.state.audio_convert_result = Some(format!(
-                                    "音频转换完成！\n\n成功: {}\n失败: {}\n\n详细结果:\n{}",
-                                    success_count,
-                                    error_count,
-                                    results.join("\n")
-                                ));
-                                self.state.show_audio_convert_result = true;
-                            }
-                            crate::models::TaskType::VideoConvert => {
-                                self.state.video_convert_result = Some(format!(
-                                    "视频转换完成！\n\n成功: {}\n失败: {}\n\n详细结果:\n{}",
-                                    success_count,
-                                    error_count,
-                                    results.join("\n")
-                                ));
-                                self.state.show_video_convert_result = true;
-                            }
-                            _ => {}
-                        }
-                    }
+                    self.handle_task_completed(success_count, error_count, results);
+                }
+            }
+        }
+        
+        // 看门狗：任务线程已退出却没有发送完成消息（如线程 panic），用已有进度生成部分结果，
+        // 避免界面一直停留在“处理中”
+        if !self.state.task_manager.is_running() {
+            let _ = self.task_processor.take_finished_worker();
+            self.last_task_message = std::time::Instant::now();
+        } else if worker_finished && self.last_task_message.elapsed() >= TASK_WATCHDOG_TIMEOUT {
+            if let Some(exit) = self.task_processor.take_finished_worker() {
+                self.handle_dead_worker(exit);
+            }
+        }
+    }
+
+    /// 处理任务完成消息，按任务类型显示结果
+    fn handle_task_completed(&mut self, success_count: usize, error_count: usize, results: Vec<String>) {
+        self.state.task_manager.complete_task();
+        
+        // 根据任务类型处理结果
+        if let Some(ref task) = self.state.task_manager.task_history.last() {
+            match task.task_type {
+                crate::models::TaskType::AudioDecrypt => {
+                    self.state.audio_decrypt_result = Some(format!(
+                        "音频解密完成！\n\n成功: {}\n失败: {}\n\n详细结果:\n{}",
+                        success_count,
+                        error_count,
+                        results.join("\n")
+                    ));
+                    self.state.show_audio_decrypt_result = true;
+                }
+                crate::models::TaskType::PaaConvert => {
+                    self.state.paa_result = Some(format!(
+                        "PAA转换完成！\n\n成功: {}\n失败: {}\n\n详细结果:\n{}",
+                        success_count,
+                        error_count,
+                        results.join("\n")
+                    ));
+                    self.state.show_paa_result = true;
+                }
+                crate::models::TaskType::AudioConvert => {
+                    self.state.audio_convert_result = Some(format!(
+                        "音频转换完成！\n\n成功: {}\n失败: {}\n\n详细结果:\n{}",
+                        success_count,
+                        error_count,
+                        results.join("\n")
+                    ));
+                    self.state.show_audio_convert_result = true;
+                }
+                crate::models::TaskType::VideoConvert => {
+                    self.state.video_convert_result = Some(format!(
+                        "视频转换完成！\n\n成功: {}\n失败: {}\n\n详细结果:\n{}",
+                        success_count,
+                        error_count,
+                        results.join("\n")
+                    ));
+                    self.state.show_video_convert_result = true;
+                }
+                crate::models::TaskType::ModExport => {
+                    // 导出结束时由 ModExportFinished 报告详细结果，这里处理取消和工作线程异常退出
+                    self.state.export_result = Some(format!(
+                        "模组导出未完成！\n\n{}\n\n已复制的文件保留在输出目录中，但可能尚未生成配置文件，请重新导出。",
+                        results.join("\n")
+                    ));
+                    self.state.export_result_dir = None;
+                    self.state.show_export_result = true;
                 }
+                _ => {}
             }
         }
     }
 
+    /// 任务线程意外结束：以已处理的进度生成部分结果，并将任务标记为失败
+    fn handle_dead_worker(&mut self, exit: std::result::Result<(), String>) {
+        let reason = match exit {
+            Ok(()) => "任务线程已结束但未报告完成".to_string(),
+            Err(panic) => format!("任务线程崩溃: {}", panic),
+        };
+        let (succeeded, failed, total) = self
+            .state
+            .task_manager
+            .get_current_progress()
+            .map(|task| (task.succeeded_files, task.failed_files, task.total_files))
+            .unwrap_or_default();
+        let unfinished = total.saturating_sub(succeeded + failed);
+        let summary = format!("成功 {} 个，失败 {} 个，未完成 {} 个（共 {} 个文件）", succeeded, failed, unfinished, total);
+        warn!("{}（{}）", reason, summary);
+
+        // 未完成的文件也没有产生结果，与失败一起计入失败数
+        self.handle_task_completed(
+            succeeded,
+            total.saturating_sub(succeeded),
+            vec![format!("⚠️ {}，以下为部分结果：{}", reason, summary)],
+        );
+        if let Some(task) = self.state.task_manager.task_history.last_mut() {
+            task.status = crate::models::TaskStatus::Failed(reason.clone());
+        }
+        self.state.file_operation_message = Some(format!("⚠️ {}，{}", reason, summary));
+    }
+
     /// 开始音频解密任务
     pub fn start_audio_decrypt_task(&mut self, files: Vec<std::path::PathBuf>, output_dir: std::path::PathBuf) {
-        self.state.task_manager.start_task(crate::models::TaskType::AudioDecrypt, files.len());
+        if !self.state.task_manager.start_task(crate::models::TaskType::AudioDecrypt, files.len()) {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
         self.task_processor.reset_cancel_flag();
         
-        if let Err(e) = self.task_processor.process_audio_decrypt(files, output_dir) {
+        let max_concurrency = self.state.audio_decrypt_options.max_concurrency;
+        let organize_by_format = self.state.audio_decrypt_options.organize_by_format;
+        if let Err(e) = self.task_processor.process_audio_decrypt(files, output_dir, max_concurrency, organize_by_format) {
             self.state.task_manager.fail_task(format!("启动音频解密任务失败: {}", e));
         }
     }
 
+    /// 开始解密→转换→添加流水线任务
+    pub fn start_decrypt_pipeline(&mut self, files: Vec<std::path::PathBuf>, output_dir: std::path::PathBuf) {
+        if !self.state.task_manager.start_task(crate::models::TaskType::DecryptPipeline, files.len()) {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        self.task_processor.reset_cancel_flag();
+
+        let options = self.state.audio_convert_options;
+        if let Err(e) = self.task_processor.process_decrypt_pipeline(files, output_dir, options) {
+            self.state.task_manager.fail_task(format!("启动解密流水线失败: {}", e));
+        }
+    }
+
+    /// 将流水线转换出的 OGG 逐个添加为轨道，并汇总每个文件各阶段的结果
+    fn add_decrypt_pipeline_tracks(&mut self, files: Vec<crate::threading::PipelineFileResult>, cancelled: bool) {
+        let mut success_count = 0;
+        let mut lines = Vec::with_capacity(files.len());
+        for mut file in files {
+            if let Some(output) = file.output.take() {
+                let stage = match crate::file_ops::FileOperations::load_audio_files(
+                    vec![output],
+                    &self.state.project.class_name,
+                    &self.state.track_defaults,
+                ) {
+                    Ok(tracks) => match self.state.add_tracks_with_duplicate_check(tracks) {
+                        (0, 0) => "添加 ✗ 无法加载转换结果".to_string(),
+                        (0, _) => "添加 ✗ 已存在相同文件".to_string(),
+                        _ => {
+                            success_count += 1;
+                            "添加 ✓".to_string()
+                        }
+                    },
+                    Err(e) => format!("添加 ✗ {}", e),
+                };
+                file.stages.push(stage);
+            }
+            lines.push(format!("{}: {}", file.filename, file.stages.join(" → ")));
+        }
+
+        let error_count = lines.len() - success_count;
+        let mut summary = format!(
+            "解密并添加完成！\n\n成功: {}\n失败: {}\n\n详细结果:\n{}",
+            success_count,
+            error_count,
+            lines.join("\n")
+        );
+        if cancelled {
+            summary.push_str("\n\n任务被用户取消，其余文件未处理");
+        }
+        self.state.file_operation_message = Some(format!("解密并添加了 {} 个轨道", success_count));
+        self.state.audio_decrypt_result = Some(summary);
+        self.state.show_audio_decrypt_result = true;
+    }
+
     /// 开始音频转换任务
     pub fn start_audio_convert_task(&mut self, files: Vec<std::path::PathBuf>, output_dir: std::path::PathBuf) {
-        self.state.task_manager.start_task(crate::models::TaskType::AudioConvert, files.len());
+        if !self.state.task_manager.start_task(crate::models::TaskType::AudioConvert, files.len()) {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
         self.task_processor.reset_cancel_flag();
+        self.task_processor.set_max_threads_override(self.state.max_threads_override);
+        self.state.last_failed_conversions.clear();
+        let options = self.state.audio_convert_options;
+        self.state.task_manager.set_workload(crate::utils::FileUtils::total_size(&files) as f64 * options.encode_cost());
         
         // 优先使用并行转换，如果文件数量较少则使用串行转换
         if files.len() > 3 {
@@ -324,21 +872,201 @@ impl ZeusMusicApp {
             // 延迟启动并行转换，确保进度对话框先显示
             std::thread::sleep(std::time::Duration::from_millis(100));
             
-            if let Err(e) = self.task_processor.process_audio_convert_parallel(files, output_dir) {
+            if let Err(e) = self.task_processor.process_audio_convert_parallel(files, output_dir, options) {
                 self.state.task_manager.fail_task(format!("启动并行音频转换任务失败: {}", e));
             }
         } else {
             info!("使用串行转换处理 {} 个音频文件", files.len());
-            if let Err(e) = self.task_processor.process_audio_convert(files, output_dir) {
+            if let Err(e) = self.task_processor.process_audio_convert(files, output_dir, options) {
                 self.state.task_manager.fail_task(format!("启动音频转换任务失败: {}", e));
             }
         }
     }
 
+    /// 开始相似轨道分析任务
+    pub fn start_similar_track_scan(&mut self) {
+        let tracks: Vec<_> = self.state.tracks
+            .iter()
+            .map(|t| (t.path.clone(), t.track_name.clone(), t.duration))
+            .collect();
+        if !self.state.task_manager.start_task(crate::models::TaskType::SimilarTrackScan, tracks.len()) {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        self.task_processor.reset_cancel_flag();
+        self.state.similar_track_groups = None;
+        
+        if let Err(e) = self.task_processor.process_similar_track_scan(tracks) {
+            self.state.task_manager.fail_task(format!("启动相似轨道分析失败: {}", e));
+        }
+    }
+
+    /// 开始从文件夹导入任务（扫描可能较慢，在后台进行）
+    pub fn start_folder_import(&mut self) {
+        let Some(dir) = self.state.folder_import_dir.clone() else {
+            return;
+        };
+        if !self.state.task_manager.start_task(crate::models::TaskType::AudioLoad, 0) {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        self.task_processor.reset_cancel_flag();
+
+        if let Err(e) = self.task_processor.process_folder_import(
+            dir,
+            self.state.import_filter.clone(),
+            self.state.project.class_name.clone(),
+            self.state.track_defaults.clone(),
+        ) {
+            self.state.task_manager.fail_task(format!("启动文件夹导入失败: {}", e));
+        }
+    }
+
+    /// 开始按章节分割任务
+    pub fn start_chapter_split(&mut self) {
+        let chapters = self.state.chapter_split_source.as_ref().and_then(|path| self.state.chapter_cache.get(path));
+        let (Some(source), Some(Ok(chapters)), Some(output_dir)) = (
+            self.state.chapter_split_source.clone(),
+            chapters.cloned(),
+            self.state.chapter_split_output_dir.clone(),
+        ) else {
+            return;
+        };
+        if !self.state.task_manager.start_task(crate::models::TaskType::ChapterSplit, chapters.len()) {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        self.task_processor.reset_cancel_flag();
+        self.state.show_chapter_split = false;
+        self.state.audio_convert_result_dir = Some(output_dir.clone());
+
+        if let Err(e) = self.task_processor.process_chapter_split(source, chapters, output_dir) {
+            self.state.task_manager.fail_task(format!("启动章节分割失败: {}", e));
+        }
+    }
+
+    /// 将章节分割结果作为轨道加入项目，轨道名称使用章节标题
+    fn add_split_chapter_tracks(&mut self, outputs: Vec<(std::path::PathBuf, String)>, failed: Vec<String>) {
+        let paths: Vec<_> = outputs.iter().map(|(path, _)| path.clone()).collect();
+        let mut added_count = 0;
+        match crate::file_ops::FileOperations::load_audio_files(paths, &self.state.project.class_name, &self.state.track_defaults) {
+            Ok(mut tracks) => {
+                for track in &mut tracks {
+                    if let Some((_, title)) = outputs.iter().find(|(path, _)| path == &track.path) {
+                        track.track_name = title.clone();
+                    }
+                }
+                added_count = self.state.add_tracks_with_duplicate_check(tracks).0;
+            }
+            Err(e) => warn!("加载分割后的音频文件失败: {}", e),
+        }
+
+        self.state.file_operation_message = Some(format!(
+            "按章节分割完成：生成 {} 个文件，已添加 {} 个轨道",
+            outputs.len(),
+            added_count
+        ));
+        if !failed.is_empty() {
+            self.state.audio_convert_result = Some(format!(
+                "按章节分割完成！\n\n成功: {}\n失败: {}\n\n详细结果:\n{}",
+                outputs.len(),
+                failed.len(),
+                failed.join("\n")
+            ));
+            self.state.show_audio_convert_result = true;
+        }
+    }
+
+    /// 开始同步全部轨道时长任务
+    pub fn start_duration_sync(&mut self) {
+        let tracks: Vec<_> = self.state.tracks.iter().map(|t| t.path.clone()).enumerate().collect();
+        if !self.state.task_manager.start_task(crate::models::TaskType::DurationSync, tracks.len()) {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        self.task_processor.reset_cancel_flag();
+
+        if let Err(e) = self.task_processor.process_duration_sync(tracks) {
+            self.state.task_manager.fail_task(format!("启动时长同步失败: {}", e));
+        }
+    }
+
+    /// 开始为 Zeus 准备：在后台检查轨道的实际编码和时长，完成后修复项目并导出
+    pub fn start_zeus_preparation(&mut self) {
+        if self.state.project.mod_type != crate::models::ModType::Music {
+            self.state.export_result = Some(
+                "为 Zeus 准备失败！\n\nZeus 音乐分类仅适用于音乐模组，请先在“工具”菜单中切换到音乐模组。".to_string(),
+            );
+            self.state.show_export_result = true;
+            return;
+        }
+        if self.state.tracks.is_empty() {
+            self.state.export_result = Some("为 Zeus 准备失败！\n\n没有音频轨道可以导出".to_string());
+            self.state.show_export_result = true;
+            return;
+        }
+
+        let tracks: Vec<_> = self.state.tracks
+            .iter()
+            .enumerate()
+            .map(|(index, track)| (index, track.path.clone(), track.duration == 0))
+            .collect();
+        if !self.state.task_manager.start_task(crate::models::TaskType::ZeusPreparation, tracks.len()) {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        self.task_processor.reset_cancel_flag();
+
+        if let Err(e) = self.task_processor.process_zeus_probe(tracks) {
+            self.state.task_manager.fail_task(format!("启动 Zeus 准备失败: {}", e));
+        }
+    }
+
+    /// 开始模组导出任务（导出前的检查已在界面线程完成）
+    pub fn start_mod_export(&mut self, job: crate::models::ModExportJob) {
+        if !self.state.task_manager.start_task(crate::models::TaskType::ModExport, job.item_count()) {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        self.task_processor.reset_cancel_flag();
+
+        if let Err(e) = self.task_processor.process_mod_export(job) {
+            self.state.task_manager.fail_task(format!("启动模组导出失败: {}", e));
+            self.state.export_result = Some(format!("模组导出失败！\n\n启动导出任务失败: {}", e));
+            self.state.show_export_result = true;
+        }
+    }
+
+    /// 开始分析轨道编辑器中当前轨道的音量（编辑器内显示进度，不弹出进度对话框）
+    pub fn start_loudness_analysis(&mut self) {
+        let Some(track_index) = self.state.selected_track.filter(|&i| i < self.state.tracks.len()) else {
+            return;
+        };
+        if !self.state.task_manager.start_task(crate::models::TaskType::LoudnessAnalysis, 1) {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        self.state.task_manager.show_progress = false;
+        self.task_processor.reset_cancel_flag();
+
+        let path = self.state.tracks[track_index].path.clone();
+        if let Err(e) = self.task_processor.process_loudness_analysis(track_index, path) {
+            self.state.task_manager.fail_task(format!("启动音量分析失败: {}", e));
+        }
+    }
+
     /// 开始视频转换任务
     pub fn start_video_convert_task(&mut self, files: Vec<std::path::PathBuf>, output_dir: std::path::PathBuf) {
-        self.state.task_manager.start_task(crate::models::TaskType::VideoConvert, files.len());
+        if !self.state.task_manager.start_task(crate::models::TaskType::VideoConvert, files.len()) {
+            self.state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
         self.task_processor.reset_cancel_flag();
+        self.task_processor.set_max_threads_override(self.state.max_threads_override);
+        self.state.last_failed_conversions.clear();
+        let format = self.state.video_output_format;
+        let options = self.state.video_convert_options;
+        self.state.task_manager.set_workload(crate::utils::FileUtils::total_size(&files) as f64 * format.encode_cost() * options.encode_cost());
         
         // 智能选择转换策略
         let total_files = files.len();
@@ -351,8 +1079,9 @@ impl ZeusMusicApp {
             
             // 延迟启动分片转换，确保进度对话框先显示
             std::thread::sleep(std::time::Duration::from_millis(100));
+            let chunk_config = self.state.video_chunk_config.clone();
             
-            if let Err(e) = self.task_processor.process_video_convert_chunked(files, output_dir) {
+            if let Err(e) = self.task_processor.process_video_convert_chunked(files, output_dir, format, options, chunk_config) {
                 self.state.task_manager.fail_task(format!("启动分片并行视频转换任务失败: {}", e));
             }
         } else if total_files > 2 {
@@ -361,12 +1090,12 @@ impl ZeusMusicApp {
             // 延迟启动并行转换，确保进度对话框先显示
             std::thread::sleep(std::time::Duration::from_millis(100));
             
-            if let Err(e) = self.task_processor.process_video_convert_parallel(files, output_dir) {
+            if let Err(e) = self.task_processor.process_video_convert_parallel(files, output_dir, format, options) {
                 self.state.task_manager.fail_task(format!("启动并行视频转换任务失败: {}", e));
             }
         } else {
             info!("使用串行转换处理 {} 个视频文件", total_files);
-            if let Err(e) = self.task_processor.process_video_convert(files, output_dir) {
+            if let Err(e) = self.task_processor.process_video_convert(files, output_dir, format, options) {
                 self.state.task_manager.fail_task(format!("启动视频转换任务失败: {}", e));
             }
         }
@@ -390,14 +1119,19 @@ impl ZeusMusicApp {
     pub fn start_ffmpeg_download_task(&mut self) {
         // 标记下载任务已启动
         self.state.ffmpeg_download_started = true;
-        self.task_processor.reset_cancel_flag();
+        self.state.ffmpeg_download_stats = crate::models::DownloadStats::start(std::time::Instant::now());
         
-        if let Err(e) = self.task_processor.process_ffmpeg_download() {
+        if let Err(e) = self.task_processor.process_ffmpeg_download(
+            self.state.http_settings.clone(),
+            self.state.ffmpeg_mirror_source.clone(),
+            self.state.ffmpeg_download_mirror_index,
+        ) {
             // 启动失败时才重置状态
             self.state.is_downloading_ffmpeg = false;
             self.state.ffmpeg_download_started = false;
             self.state.ffmpeg_download_status = format!("启动下载任务失败: {}", e);
             self.state.audio_convert_result = Some(format!("FFmpeg 下载失败: {}", e));
+            self.state.audio_convert_result_dir = None;
             self.state.show_audio_convert_result = true;
         }
     }
@@ -408,6 +1142,7 @@ impl ZeusMusicApp {
         
         // 清理任务处理器
         self.task_processor.cancel_task();
+        self.task_processor.cancel_ffmpeg_download();
         
         // 清理状态 - 清空所有列表和选择
         self.state.tracks.clear();
@@ -415,12 +1150,12 @@ impl ZeusMusicApp {
         self.state.selected_track = None;
         self.state.selected_video = None;
         
-        // 清理路径缓存
-        self.state.track_paths.clear();
-        self.state.video_paths.clear();
+        // 重建路径缓存（列表已清空）
+        self.state.rebuild_path_caches();
         
         // 清理PAA相关状态
         self.state.paa_selected_files.clear();
+        self.state.paa_source_dimensions.clear();
         self.state.paa_output_directory = None;
         self.state.paa_result = None;
         
@@ -475,25 +1210,28 @@ impl ZeusMusicApp {
                     // 任务开始时进度保持不变，等待任务完成时再更新
                 }
             }
+            ProgressUpdate::FileProgress { task_id, fraction } => {
+                self.state.task_manager.update_file_progress(task_id, fraction);
+            }
             ProgressUpdate::TaskCompleted { task_id, result, completed_count, total_tasks } => {
                 info!("并行任务完成: {} ({}), 已完成: {}/{}", task_id, result.input_path().display(), completed_count, total_tasks);
+                let mut produced_output = None;
+                let mut failed_input = None;
                 
                 // 更新进度
+                self.state.task_manager.finish_file(task_id, completed_count);
                 if let Some(ref mut task) = self.state.task_manager.current_task {
                     task.current_file = completed_count;
-                    task.progress = if total_tasks > 0 {
-                        completed_count as f32 / total_tasks as f32
-                    } else {
-                        0.0
-                    };
                     
                     // 根据结果更新任务信息
                     match result {
-                        crate::parallel_converter::ConversionResult::Success { message, .. } => {
+                        crate::parallel_converter::ConversionResult::Success { message, input_path, output_path, .. } => {
                             task.current_filename = format!("✓ {}", message);
+                            produced_output = Some((input_path, output_path));
                         }
-                        crate::parallel_converter::ConversionResult::Error { error, .. } => {
+                        crate::parallel_converter::ConversionResult::Error { error, input_path, .. } => {
                             task.current_filename = format!("✗ {}", error);
+                            failed_input = Some(input_path);
                         }
                     }
                     
@@ -513,6 +1251,13 @@ impl ZeusMusicApp {
                         }
                     }
                 }
+
+                if let Some((input_path, output_path)) = produced_output {
+                    self.record_conversion_output(input_path, output_path);
+                }
+                if let Some(input_path) = failed_input {
+                    self.record_failed_conversion(input_path);
+                }
             }
             ProgressUpdate::AllTasksCompleted { success_count, error_count, total_duration, results } => {
                 info!("所有并行任务完成: 成功={}, 失败={}, 耗时={:.2}秒", 
@@ -553,6 +1298,23 @@ impl ZeusMusicApp {
         }
     }
 
+    /// 将转换输出记录到本次会话的转换历史
+    fn record_conversion_output(&mut self, input_path: std::path::PathBuf, output_path: std::path::PathBuf) {
+        if let Some(task) = self.state.task_manager.current_task.as_ref() {
+            let task_type = task.task_type.clone();
+            self.state.record_conversion_output(task_type, input_path, output_path);
+        }
+        self.state.task_manager.record_file_result(true);
+    }
+
+    /// 记录转换失败的输入文件，供结果对话框“重试失败项”使用
+    fn record_failed_conversion(&mut self, input_path: std::path::PathBuf) {
+        self.state.task_manager.record_file_result(false);
+        if !self.state.last_failed_conversions.contains(&input_path) {
+            self.state.last_failed_conversions.push(input_path);
+        }
+    }
+
     /// 处理分片转换进度更新
     fn handle_chunk_progress_update(&mut self, update: crate::video_chunk_parallel_processor::ChunkProgressUpdate) {
         use crate::video_chunk_parallel_processor::ChunkProgressUpdate;
@@ -604,6 +1366,12 @@ impl ZeusMusicApp {
                         task.current_filename = result.result.get_error_message();
                     }
                 }
+
+                if result.result.success {
+                    self.record_conversion_output(result.input_path.clone(), result.result.output_path.clone());
+                } else {
+                    self.record_failed_conversion(result.input_path.clone());
+                }
             }
             ChunkProgressUpdate::AllTasksCompleted { success_count, error_count, total_duration, results } => {
                 info!("所有分片转换任务完成: 成功={}, 失败={}, 耗时={:.2}秒", 
@@ -621,7 +1389,7 @@ impl ZeusMusicApp {
                                 success_count,
                                 error_count,
                                 total_duration.as_secs_f64(),
-                                results.iter().map(|r| format!("• {}", r.result.get_success_message())).collect::<Vec<_>>().join("\n")
+                                results.iter().map(|r| format!("• {}", r.result.get_summary_line())).collect::<Vec<_>>().join("\n")
                             );
                             self.state.video_convert_result = Some(result_message);
                             self.state.show_video_convert_result = true;
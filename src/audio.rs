@@ -1,10 +1,15 @@
 use anyhow::{Context, Result};
 use log::debug;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
-use symphonia::core::formats::FormatOptions;
+use std::ops::ControlFlow;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{Decoder, DecoderOptions};
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
 use symphonia::core::io::MediaSourceStream;
-use symphonia::core::meta::MetadataOptions;
-use symphonia::core::probe::Hint;
+use symphonia::core::meta::{MetadataOptions, StandardTagKey};
+use symphonia::core::probe::{Hint, ProbeResult};
+use symphonia::core::units::Time;
 
 /// 音频文件信息
 #[derive(Debug, Clone)]
@@ -12,6 +17,111 @@ pub struct AudioInfo {
     pub duration: u32,
 }
 
+/// 音频电平（峰值和均方根振幅，0.0-1.0）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioLevels {
+    pub peak: f32,
+    pub rms: f32,
+}
+
+impl AudioLevels {
+    /// 均方根电平（dBFS），静音时为 None
+    pub fn rms_dbfs(&self) -> Option<f32> {
+        (self.rms > 0.0).then(|| 20.0 * self.rms.log10())
+    }
+}
+
+/// 音频章节（来自 FLAC cuesheet 或 Vorbis 注释中的 CHAPTERxxx 标记）
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioChapter {
+    /// 章节标题（元数据中没有标题时为 "章节 N"）
+    pub title: String,
+    /// 起始时间（秒）
+    pub start_seconds: f64,
+    /// 结束时间（秒），最后一个章节为 None 表示直到文件结束
+    pub end_seconds: Option<f64>,
+}
+
+/// 音频文本标签（来自 Vorbis 注释等元数据）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudioTags {
+    /// 标题（TITLE）
+    pub title: Option<String>,
+    /// 艺术家（ARTIST）
+    pub artist: Option<String>,
+}
+
+/// 波形概要（每个时间段的峰值振幅）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Waveform {
+    /// 每段的峰值振幅（0.0-1.0），每段时长为 `SEGMENT_SECONDS`
+    pub peaks: Vec<f32>,
+    /// 音频总时长（秒）
+    pub duration_secs: f64,
+}
+
+impl Waveform {
+    /// 每段时长（秒）
+    pub const SEGMENT_SECONDS: f64 = 0.05;
+    /// 静音阈值（约 -40 dBFS）
+    pub const SILENCE_THRESHOLD: f32 = 0.01;
+
+    /// 建议的裁剪点 (起始秒, 结束秒)：跳过开头和结尾的静音，全部静音时返回 None
+    pub fn suggest_trim(&self) -> Option<(f64, f64)> {
+        let first = self.peaks.iter().position(|&p| p > Self::SILENCE_THRESHOLD)?;
+        let last = self.peaks.iter().rposition(|&p| p > Self::SILENCE_THRESHOLD)?;
+        let start = first as f64 * Self::SEGMENT_SECONDS;
+        let end = ((last + 1) as f64 * Self::SEGMENT_SECONDS).min(self.duration_secs);
+        Some((start, end))
+    }
+}
+
+/// 简易音频指纹（基于短时能量变化，可识别不同码率的重新编码）
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioFingerprint {
+    /// 每帧一位：该帧能量是否高于前一帧
+    pub bits: Vec<bool>,
+    /// 已分析的音频时长（秒）
+    pub analyzed_seconds: f32,
+}
+
+impl AudioFingerprint {
+    /// 每帧时长（秒）
+    pub const FRAME_SECONDS: f32 = 0.1;
+    /// 最多分析的音频时长（秒）
+    pub const MAX_SECONDS: f32 = 90.0;
+    /// 比较时允许的最大帧偏移（应对片头静音差异）
+    const MAX_OFFSET_FRAMES: usize = 20;
+
+    /// 计算与另一指纹的相似度（0.0-1.0），取允许偏移范围内的最佳对齐
+    pub fn similarity(&self, other: &AudioFingerprint) -> f32 {
+        let mut best = 0.0f32;
+        for offset in 0..=Self::MAX_OFFSET_FRAMES {
+            best = best
+                .max(Self::aligned_similarity(&self.bits, &other.bits, offset))
+                .max(Self::aligned_similarity(&other.bits, &self.bits, offset));
+        }
+        best
+    }
+
+    fn aligned_similarity(a: &[bool], b: &[bool], offset: usize) -> f32 {
+        if offset >= a.len() {
+            return 0.0;
+        }
+        let len = (a.len() - offset).min(b.len());
+        // 重叠太短时结果不可靠
+        if len < 50 {
+            return 0.0;
+        }
+        let matches = a[offset..offset + len]
+            .iter()
+            .zip(&b[..len])
+            .filter(|(x, y)| x == y)
+            .count();
+        matches as f32 / len as f32
+    }
+}
+
 /// 音频处理工具
 pub struct AudioProcessor;
 
@@ -21,6 +131,401 @@ impl AudioProcessor {
         let path = path.as_ref();
         debug!("Reading audio file: {:?}", path);
 
+        let probed = Self::probe_file(path)?;
+
+        // 查找第一个音频轨道
+        let track = probed
+            .format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)
+            .ok_or_else(|| anyhow::anyhow!("No audio track found"))?;
+
+        // 计算时长：优先使用文件头信息，都没有时流式解码整个文件
+        let duration = match Self::header_duration(path, &track.codec_params)? {
+            Some(duration) => duration,
+            None => {
+                debug!("无法获取音频帧数，解码文件测量时长: {:?}", path);
+                Self::measure_duration(path)?
+            }
+        };
+
+        Ok(AudioInfo {
+            duration,
+        })
+    }
+
+    /// 只从文件头读取时长（秒），不解码音频；文件头没有时长信息时返回 None
+    ///
+    /// 用于导入等需要快速返回的场景，返回 None 的文件可稍后用 `measure_duration` 在后台测量。
+    pub fn read_header_duration<P: AsRef<Path>>(path: P) -> Result<Option<u32>> {
+        let path = path.as_ref();
+        let probed = Self::probe_file(path)?;
+        let track = probed
+            .format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)
+            .ok_or_else(|| anyhow::anyhow!("No audio track found"))?;
+        Self::header_duration(path, &track.codec_params)
+    }
+
+    /// 优先使用容器给出的帧数，其次读取 OGG 最后一页的 granule position
+    fn header_duration(path: &Path, codec_params: &symphonia::core::codecs::CodecParameters) -> Result<Option<u32>> {
+        let sample_rate = codec_params.sample_rate.unwrap_or(44100);
+        if let Some(n_frames) = codec_params.n_frames {
+            return Ok(Some(Self::frames_to_seconds(n_frames, sample_rate)));
+        }
+        if let Some(granule) = Self::read_ogg_last_granule(path)? {
+            debug!("从 OGG granule position 计算时长: {:?}", path);
+            return Ok(Some(Self::frames_to_seconds(granule, sample_rate)));
+        }
+        Ok(None)
+    }
+
+    /// 首个音频流是否为 Vorbis 编码（按实际编码判断，不看扩展名）
+    pub fn is_vorbis<P: AsRef<Path>>(path: P) -> Result<bool> {
+        let probed = Self::probe_file(path.as_ref())?;
+        let track = probed
+            .format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)
+            .ok_or_else(|| anyhow::anyhow!("No audio track found"))?;
+        Ok(track.codec_params.codec == symphonia::core::codecs::CODEC_TYPE_VORBIS)
+    }
+
+    /// 读取 OGG 文件最后一页的 granule position（即总采样帧数），非 OGG 文件返回 None
+    ///
+    /// 只读取文件头和末尾，不解码音频。
+    fn read_ogg_last_granule(path: &Path) -> Result<Option<u64>> {
+        // OGG 页最大约 64KB，读取两页的长度可保证包含最后一页的页头
+        const TAIL_LEN: u64 = 128 * 1024;
+
+        let mut file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open file: {:?}", path))?;
+        let mut magic = [0u8; 4];
+        if file.read_exact(&mut magic).is_err() || &magic != b"OggS" {
+            return Ok(None);
+        }
+        let len = file.metadata().with_context(|| format!("无法读取文件信息: {:?}", path))?.len();
+        let start = len.saturating_sub(TAIL_LEN);
+        file.seek(SeekFrom::Start(start)).with_context(|| format!("无法定位到文件末尾: {:?}", path))?;
+        let mut tail = Vec::with_capacity((len - start) as usize);
+        file.read_to_end(&mut tail).with_context(|| format!("无法读取文件末尾: {:?}", path))?;
+        Ok(Self::last_granule_in(&tail))
+    }
+
+    /// 在数据中从后向前查找最后一个带有效 granule position 的 OGG 页头
+    fn last_granule_in(data: &[u8]) -> Option<u64> {
+        // 页头: "OggS" | 版本(1) | 标志(1) | granule position(8, 小端)
+        (0..data.len().saturating_sub(13)).rev().find_map(|i| {
+            if &data[i..i + 4] != b"OggS" || data[i + 4] != 0 {
+                return None;
+            }
+            let granule = i64::from_le_bytes(data[i + 6..i + 14].try_into().ok()?);
+            // -1 表示该页没有数据包在此结束
+            (granule > 0).then_some(granule as u64)
+        })
+    }
+
+    /// 流式解码音频文件并测量峰值和均方根电平（不会一次性载入整个文件）
+    pub fn measure_levels<P: AsRef<Path>>(path: P) -> Result<AudioLevels> {
+        let path = path.as_ref();
+        let mut peak = 0.0f32;
+        let mut sum_squares = 0.0f64;
+        let mut sample_count: u64 = 0;
+
+        Self::for_each_decoded_chunk(path, |samples, _, _| {
+            for &sample in samples {
+                peak = peak.max(sample.abs());
+                sum_squares += (sample as f64) * (sample as f64);
+            }
+            sample_count += samples.len() as u64;
+            ControlFlow::Continue(())
+        })?;
+
+        if sample_count == 0 {
+            return Err(anyhow::anyhow!("未解码到任何音频数据: {:?}", path));
+        }
+        Ok(AudioLevels {
+            peak: peak.min(1.0),
+            rms: (sum_squares / sample_count as f64).sqrt().min(1.0) as f32,
+        })
+    }
+
+    /// 读取音频文件内嵌的封面图片（如有），返回原始图片数据
+    pub fn extract_cover_art<P: AsRef<Path>>(path: P) -> Result<Option<Vec<u8>>> {
+        let path = path.as_ref();
+        let mut probed = Self::probe_file(path)?;
+
+        // 容器内的元数据（如 OGG 的 Vorbis 注释 METADATA_BLOCK_PICTURE）
+        let container_visual = probed
+            .format
+            .metadata()
+            .current()
+            .and_then(|revision| revision.visuals().first().map(|visual| visual.data.to_vec()));
+        if container_visual.is_some() {
+            debug!("从容器元数据读取封面: {:?}", path);
+            return Ok(container_visual);
+        }
+
+        // 探测阶段读取的元数据（如 ID3 标签）
+        let probed_visual = probed.metadata.get().and_then(|metadata| {
+            metadata
+                .current()
+                .and_then(|revision| revision.visuals().first().map(|visual| visual.data.to_vec()))
+        });
+        if probed_visual.is_some() {
+            debug!("从标签元数据读取封面: {:?}", path);
+        }
+        Ok(probed_visual)
+    }
+
+    /// 完整解码音频文件并计算波形概要
+    pub fn compute_waveform<P: AsRef<Path>>(path: P) -> Result<Waveform> {
+        let mut peaks = Vec::new();
+        let mut segment_peak = 0.0f32;
+        let mut segment_samples = 0usize;
+        let mut total_frames: u64 = 0;
+        let mut sample_rate = 44100;
+
+        Self::for_each_decoded_chunk(path.as_ref(), |samples, channels, rate| {
+            sample_rate = rate;
+            let segment_len = ((rate as f64 * Waveform::SEGMENT_SECONDS) as usize).max(1);
+            for frame in samples.chunks(channels) {
+                let peak = frame.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+                segment_peak = segment_peak.max(peak);
+                segment_samples += 1;
+                total_frames += 1;
+                if segment_samples >= segment_len {
+                    peaks.push(segment_peak.min(1.0));
+                    segment_peak = 0.0;
+                    segment_samples = 0;
+                }
+            }
+            ControlFlow::Continue(())
+        })?;
+        if segment_samples > 0 {
+            peaks.push(segment_peak.min(1.0));
+        }
+
+        Ok(Waveform {
+            peaks,
+            duration_secs: total_frames as f64 / sample_rate as f64,
+        })
+    }
+
+    /// 读取音频文件中的章节信息（没有章节时返回空列表）
+    pub fn read_chapters<P: AsRef<Path>>(path: P) -> Result<Vec<AudioChapter>> {
+        let path = path.as_ref();
+        let mut probed = Self::probe_file(path)?;
+        let tags = Self::collect_text_tags(&mut probed);
+
+        // 优先使用容器的 cue 信息（如 FLAC cuesheet）
+        let track = probed.format.default_track();
+        let time_base = track.and_then(|t| t.codec_params.time_base);
+        let sample_rate = track.and_then(|t| t.codec_params.sample_rate).unwrap_or(44100);
+        let mut starts: Vec<(f64, Option<String>)> = probed
+            .format
+            .cues()
+            .iter()
+            .map(|cue| {
+                let start = match time_base {
+                    Some(tb) => {
+                        let time = tb.calc_time(cue.start_ts);
+                        time.seconds as f64 + time.frac
+                    }
+                    None => cue.start_ts as f64 / sample_rate as f64,
+                };
+                let title = cue
+                    .tags
+                    .iter()
+                    .find(|tag| tag.std_key == Some(StandardTagKey::TrackTitle) || tag.key.eq_ignore_ascii_case("TITLE"))
+                    .map(|tag| tag.value.to_string());
+                (start, title)
+            })
+            .collect();
+
+        if starts.is_empty() {
+            starts = Self::chapter_starts_from_tags(&tags);
+        }
+        debug!("读取到 {} 个章节: {:?}", starts.len(), path);
+        Ok(Self::build_chapters(starts))
+    }
+
+    /// 读取音频文件的标题和艺术家标签（OGG 文件来自 Vorbis 注释头）
+    pub fn read_tags<P: AsRef<Path>>(path: P) -> Result<AudioTags> {
+        let path = path.as_ref();
+        let mut probed = Self::probe_file(path)?;
+        let tags = Self::tags_from_pairs(&Self::collect_text_tags(&mut probed));
+        debug!("读取到标签 {:?}: {:?}", tags, path);
+        Ok(tags)
+    }
+
+    /// 收集容器与探测阶段的文本标签 (键, 值)
+    fn collect_text_tags(probed: &mut ProbeResult) -> Vec<(String, String)> {
+        let mut tags: Vec<(String, String)> = Vec::new();
+        if let Some(revision) = probed.format.metadata().current() {
+            tags.extend(revision.tags().iter().map(|tag| (tag.key.clone(), tag.value.to_string())));
+        }
+        if let Some(metadata) = probed.metadata.get() {
+            if let Some(revision) = metadata.current() {
+                tags.extend(revision.tags().iter().map(|tag| (tag.key.clone(), tag.value.to_string())));
+            }
+        }
+        tags
+    }
+
+    /// 从文本标签中取出标题和艺术家（键不区分大小写，忽略空白值，取第一个有效值）
+    fn tags_from_pairs(tags: &[(String, String)]) -> AudioTags {
+        let find = |name: &str| {
+            tags.iter()
+                .filter(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.trim())
+                .find(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        AudioTags {
+            title: find("TITLE"),
+            artist: find("ARTIST"),
+        }
+    }
+
+    /// 解析 Vorbis 注释章节扩展（CHAPTER001=00:00:00.000，CHAPTER001NAME=标题）
+    fn chapter_starts_from_tags(tags: &[(String, String)]) -> Vec<(f64, Option<String>)> {
+        let mut chapters: Vec<(String, f64)> = tags
+            .iter()
+            .filter_map(|(key, value)| {
+                let key = key.to_ascii_uppercase();
+                let number = key.strip_prefix("CHAPTER")?;
+                if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+                Some((number.to_string(), Self::parse_chapter_timestamp(value)?))
+            })
+            .collect();
+        chapters.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        chapters
+            .into_iter()
+            .map(|(number, start)| {
+                let name_key = format!("CHAPTER{}NAME", number);
+                let title = tags
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(&name_key))
+                    .map(|(_, value)| value.clone());
+                (start, title)
+            })
+            .collect()
+    }
+
+    /// 解析章节时间戳（HH:MM:SS.mmm 或 MM:SS.mmm）
+    fn parse_chapter_timestamp(value: &str) -> Option<f64> {
+        let mut seconds = 0.0;
+        let parts: Vec<&str> = value.trim().split(':').collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            return None;
+        }
+        for part in parts {
+            seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+        }
+        Some(seconds)
+    }
+
+    /// 由起始时间构建章节列表，下一章节的起点即为上一章节的终点
+    fn build_chapters(mut starts: Vec<(f64, Option<String>)>) -> Vec<AudioChapter> {
+        starts.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let ends: Vec<Option<f64>> = starts.iter().skip(1).map(|(start, _)| Some(*start)).chain([None]).collect();
+        starts
+            .into_iter()
+            .zip(ends)
+            .enumerate()
+            .map(|(i, ((start, title), end))| AudioChapter {
+                title: title
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .unwrap_or_else(|| format!("章节 {}", i + 1)),
+                start_seconds: start,
+                end_seconds: end,
+            })
+            .collect()
+    }
+
+    /// 解码音频开头部分并计算简易指纹
+    pub fn compute_fingerprint<P: AsRef<Path>>(path: P) -> Result<AudioFingerprint> {
+        let max_frames = (AudioFingerprint::MAX_SECONDS / AudioFingerprint::FRAME_SECONDS) as usize;
+        let mut energies: Vec<f32> = Vec::with_capacity(max_frames);
+        let mut frame_energy = 0.0f32;
+        let mut frame_samples = 0usize;
+
+        Self::for_each_decoded_chunk(path.as_ref(), |samples, channels, rate| {
+            let frame_len = ((rate as f32 * AudioFingerprint::FRAME_SECONDS) as usize).max(1);
+            // 混合为单声道并按帧累计能量
+            for frame in samples.chunks(channels) {
+                let mono = frame.iter().sum::<f32>() / channels as f32;
+                frame_energy += mono * mono;
+                frame_samples += 1;
+                if frame_samples >= frame_len {
+                    energies.push(frame_energy / frame_len as f32);
+                    frame_energy = 0.0;
+                    frame_samples = 0;
+                    if energies.len() >= max_frames {
+                        return ControlFlow::Break(());
+                    }
+                }
+            }
+            ControlFlow::Continue(())
+        })?;
+
+        let bits = energies.windows(2).map(|pair| pair[1] > pair[0]).collect();
+        Ok(AudioFingerprint {
+            bits,
+            analyzed_seconds: energies.len() as f32 * AudioFingerprint::FRAME_SECONDS,
+        })
+    }
+
+    /// 完整解码音频文件并测量实际时长（秒，向上取整以避免游戏内截断）
+    pub fn measure_duration<P: AsRef<Path>>(path: P) -> Result<u32> {
+        let path = path.as_ref();
+        let mut total_frames: u64 = 0;
+        let mut sample_rate = 44100;
+
+        Self::for_each_decoded_chunk(path, |samples, channels, rate| {
+            sample_rate = rate;
+            total_frames += (samples.len() / channels) as u64;
+            ControlFlow::Continue(())
+        })?;
+
+        if total_frames == 0 {
+            return Err(anyhow::anyhow!("未解码到任何音频数据: {:?}", path));
+        }
+        Ok(Self::frames_to_seconds(total_frames, sample_rate))
+    }
+
+    /// 采样帧数换算为秒数（向上取整，文件头和解码测量使用相同的取整方式）
+    fn frames_to_seconds(frames: u64, sample_rate: u32) -> u32 {
+        frames.div_ceil(sample_rate.max(1) as u64) as u32
+    }
+
+    /// 流式解码首个音频流，对每个数据包的交错采样调用 `on_chunk(采样, 声道数, 采样率)`
+    ///
+    /// 回调返回 `ControlFlow::Break` 时停止解码；损坏的数据包被跳过。
+    fn for_each_decoded_chunk<F>(path: &Path, mut on_chunk: F) -> Result<()>
+    where
+        F: FnMut(&[f32], usize, u32) -> ControlFlow<()>,
+    {
+        let mut stream = AudioStream::open(path)?;
+        while let Some((samples, channels, sample_rate)) = stream.next_chunk()? {
+            if on_chunk(samples, channels, sample_rate).is_break() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// 打开并探测音频文件格式
+    pub(crate) fn probe_file(path: &Path) -> Result<ProbeResult> {
         // 打开文件
         let file = std::fs::File::open(path)
             .with_context(|| format!("Failed to open file: {:?}", path))?;
@@ -34,38 +539,213 @@ impl AudioProcessor {
         }
 
         // 探测格式
-        let probed = symphonia::default::get_probe()
+        symphonia::default::get_probe()
             .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
-            .with_context(|| "Failed to probe audio format")?;
+            .with_context(|| "Failed to probe audio format")
+    }
 
-        // 查找第一个音频轨道
-        let track = probed
-            .format
+
+
+}
+
+/// 首个音频流的流式解码器，按数据包输出交错的 f32 采样（分析函数和播放器共用）
+pub(crate) struct AudioStream {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    sample_buf: Option<SampleBuffer<f32>>,
+}
+
+impl AudioStream {
+    /// 打开文件并创建首个音频流的解码器
+    pub(crate) fn open(path: &Path) -> Result<Self> {
+        let format = AudioProcessor::probe_file(path)?.format;
+        let track = format
             .tracks()
             .iter()
             .find(|t| t.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)
             .ok_or_else(|| anyhow::anyhow!("No audio track found"))?;
+        let track_id = track.id;
+        let decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .with_context(|| format!("无法创建音频解码器: {:?}", path))?;
+        Ok(Self {
+            format,
+            decoder,
+            track_id,
+            sample_buf: None,
+        })
+    }
 
-        // 获取音频信息
-        let codec_params = &track.codec_params;
-        let sample_rate = codec_params.sample_rate.unwrap_or(44100);
+    /// 精确定位到指定时间（秒）
+    pub(crate) fn seek(&mut self, seconds: f64) -> Result<()> {
+        let time = Time::new(seconds.trunc() as u64, seconds.fract());
+        self.format
+            .seek(SeekMode::Accurate, SeekTo::Time { time, track_id: Some(self.track_id) })
+            .context("无法定位音频位置")?;
+        self.decoder.reset();
+        Ok(())
+    }
+
+    /// 解码下一个非空数据包，返回 (交错采样, 声道数, 采样率)；文件结束时返回 None
+    pub(crate) fn next_chunk(&mut self) -> Result<Option<(&[f32], usize, u32)>> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(_) => return Ok(None), // 文件结束或无法继续读取
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                Err(symphonia::core::errors::Error::DecodeError(e)) => {
+                    debug!("跳过无法解码的数据包: {}", e);
+                    continue;
+                }
+                Err(e) => return Err(e).context("音频解码失败"),
+            };
+            if decoded.frames() == 0 {
+                continue;
+            }
+
+            let spec = *decoded.spec();
+            let channels = spec.channels.count().max(1);
+            let buf = self.sample_buf.get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, spec));
+            if buf.capacity() < decoded.capacity() * channels {
+                *buf = SampleBuffer::new(decoded.capacity() as u64, spec);
+            }
+            buf.copy_interleaved_ref(decoded);
+            return Ok(Some((buf.samples(), channels, spec.rate)));
+        }
+    }
+}
 
-        // 计算时长
-        let duration = if let Some(n_frames) = codec_params.n_frames {
-            (n_frames as f64 / sample_rate as f64) as u32
-        } else {
-            // 如果无法直接获取帧数，尝试从元数据获取
-            // 如果仍然无法获取，返回一个合理的默认值
-            debug!("无法获取音频帧数，使用默认时长");
-            180 // 默认3分钟
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_granule_in_ogg_pages() {
+        let page = |granule: i64| {
+            let mut page = b"OggS\0\x04".to_vec();
+            page.extend_from_slice(&granule.to_le_bytes());
+            page.extend_from_slice(&[0u8; 16]);
+            page
         };
+        let mut data = page(0);
+        data.extend(page(441_000));
+        data.extend(page(-1));
+        assert_eq!(AudioProcessor::last_granule_in(&data), Some(441_000));
+        assert_eq!(AudioProcessor::last_granule_in(b"not an ogg file"), None);
+    }
 
-        Ok(AudioInfo {
-            duration,
-        })
+    #[test]
+    fn test_rms_dbfs() {
+        let levels = AudioLevels { peak: 1.0, rms: 0.1 };
+        assert!((levels.rms_dbfs().unwrap() + 20.0).abs() < 1e-4);
+        assert_eq!(AudioLevels { peak: 0.0, rms: 0.0 }.rms_dbfs(), None);
     }
 
+    #[test]
+    fn test_chapter_starts_from_vorbis_tags() {
+        let tags = vec![
+            ("CHAPTER002".to_string(), "00:03:30.500".to_string()),
+            ("CHAPTER002NAME".to_string(), "第二首".to_string()),
+            ("CHAPTER001".to_string(), "00:00:00.000".to_string()),
+            ("CHAPTER001NAME".to_string(), "Intro".to_string()),
+            ("ARTIST".to_string(), "Someone".to_string()),
+        ];
+        let chapters = AudioProcessor::build_chapters(AudioProcessor::chapter_starts_from_tags(&tags));
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].title, "Intro");
+        assert_eq!(chapters[0].end_seconds, Some(210.5));
+        assert_eq!(chapters[1].title, "第二首");
+        assert_eq!(chapters[1].end_seconds, None);
+    }
 
+    #[test]
+    fn test_tags_from_vorbis_comments() {
+        let tags = vec![
+            ("title".to_string(), "  ".to_string()),
+            ("TITLE".to_string(), " 行军曲 ".to_string()),
+            ("Artist".to_string(), "Band".to_string()),
+        ];
+        let parsed = AudioProcessor::tags_from_pairs(&tags);
+        assert_eq!(parsed.title.as_deref(), Some("行军曲"));
+        assert_eq!(parsed.artist.as_deref(), Some("Band"));
+        assert_eq!(AudioProcessor::tags_from_pairs(&[]), AudioTags::default());
+    }
 
-}
+    #[test]
+    fn test_build_chapters_default_titles() {
+        let chapters = AudioProcessor::build_chapters(vec![(60.0, None), (0.0, Some(" ".to_string()))]);
+        assert_eq!(chapters[0].title, "章节 1");
+        assert_eq!(chapters[0].start_seconds, 0.0);
+        assert_eq!(chapters[1].title, "章节 2");
+    }
+
+    #[test]
+    fn test_waveform_suggest_trim_skips_silence() {
+        let mut peaks = vec![0.0; 20];
+        peaks.extend([0.5; 40]);
+        peaks.extend([0.005; 10]);
+        let waveform = Waveform { peaks, duration_secs: 3.5 };
+        let (start, end) = waveform.suggest_trim().expect("应有非静音部分");
+        assert!((start - 1.0).abs() < 1e-9);
+        assert!((end - 3.0).abs() < 1e-9);
+
+        let silent = Waveform { peaks: vec![0.0; 10], duration_secs: 0.5 };
+        assert_eq!(silent.suggest_trim(), None);
+    }
+
+    #[test]
+    fn test_parse_chapter_timestamp() {
+        assert_eq!(AudioProcessor::parse_chapter_timestamp("01:02:03.5"), Some(3723.5));
+        assert_eq!(AudioProcessor::parse_chapter_timestamp("02:03"), Some(123.0));
+        assert_eq!(AudioProcessor::parse_chapter_timestamp("abc"), None);
+    }
+
+    /// 写入单声道 16 位 PCM WAV 文件（半幅正弦波）
+    fn write_test_wav(path: &Path, sample_rate: u32, frames: u32) {
+        let mut data = Vec::new();
+        for i in 0..frames {
+            let sample = ((i as f32 * 0.05).sin() * i16::MAX as f32 * 0.5) as i16;
+            data.extend_from_slice(&sample.to_le_bytes());
+        }
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&data);
+        std::fs::write(path, wav).unwrap();
+    }
 
+    #[test]
+    fn test_decoded_analyses_share_duration_rounding() {
+        let path = std::env::temp_dir().join(format!("zmm_audio_analysis_test_{}.wav", std::process::id()));
+        write_test_wav(&path, 8000, 12000);
+
+        // 1.5 秒：文件头和完整解码都向上取整为 2 秒
+        assert_eq!(AudioProcessor::read_header_duration(&path).unwrap(), Some(2));
+        assert_eq!(AudioProcessor::measure_duration(&path).unwrap(), 2);
+
+        let levels = AudioProcessor::measure_levels(&path).unwrap();
+        assert!((levels.peak - 0.5).abs() < 0.01, "{:?}", levels);
+        let waveform = AudioProcessor::compute_waveform(&path).unwrap();
+        assert!((waveform.duration_secs - 1.5).abs() < 1e-9);
+        assert!(!waveform.peaks.is_empty());
+        assert!(AudioProcessor::compute_fingerprint(&path).unwrap().analyzed_seconds > 1.0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
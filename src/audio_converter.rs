@@ -1,8 +1,9 @@
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use log::{info, error};
+use log::{info, error, warn};
 use crate::ffmpeg_plugin::FFmpegPlugin;
+use crate::models::{AudioConvertOptions, TrackOutputFormat, TrackTrim};
 
 /// FFmpeg 音频转换器
 pub struct AudioConverter {
@@ -10,6 +11,9 @@ pub struct AudioConverter {
 }
 
 impl AudioConverter {
+    /// 输入已是 Vorbis 编码、直接复制音频流时的结果说明
+    pub const STREAM_COPY_MESSAGE: &'static str = "已是OGG，已复制";
+
     /// 创建新的音频转换器实例
     pub fn new() -> Result<Self> {
         Self::new_with_plugin(&FFmpegPlugin::new()?)
@@ -30,13 +34,278 @@ impl AudioConverter {
     
     
     
-    /// 将音频文件转换为 OGG 格式（支持取消检查）
+    /// 将音频文件转换为 OGG 格式（支持取消检查），编码质量与响度归一化由 `options` 决定
     pub fn convert_to_ogg_with_cancel<F>(
         &self,
         input_path: &Path,
         output_path: &Path,
+        options: AudioConvertOptions,
+        should_cancel: &F,
+    ) -> Result<String>
+    where
+        F: Fn() -> bool + ?Sized,
+    {
+        self.convert_to_ogg_with_progress(input_path, output_path, options, should_cancel, &|_| {})
+    }
+
+    /// 将音频文件转换为 OGG 格式，并通过 `on_progress` 报告当前文件的进度（0.0-1.0）
+    pub fn convert_to_ogg_with_progress<F>(
+        &self,
+        input_path: &Path,
+        output_path: &Path,
+        options: AudioConvertOptions,
+        should_cancel: &F,
+        on_progress: &(dyn Fn(f32) + Sync),
+    ) -> Result<String>
+    where
+        F: Fn() -> bool + ?Sized,
+    {
+        // 已是 Vorbis 编码且不需要响度归一化时直接复制音频流，避免重新编码损失音质
+        if !options.normalize_loudness && self.is_vorbis_input(input_path) {
+            info!("输入已是 Vorbis 编码，直接复制音频流: {:?}", input_path);
+            self.run_ffmpeg_with_cancel(input_path, output_path, &["-vn", "-c:a", "copy"], should_cancel, on_progress)?;
+            return Ok(Self::STREAM_COPY_MESSAGE.to_string());
+        }
+
+        let codec_args = Self::ogg_codec_args(options);
+        let codec_args: Vec<&str> = codec_args.iter().map(String::as_str).collect();
+        self.run_ffmpeg_with_cancel(input_path, output_path, &codec_args, should_cancel, on_progress)
+    }
+
+    /// 输入文件的首个音频流是否为 Vorbis 编码（探测失败时视为否，按原流程重新编码）
+    fn is_vorbis_input(&self, input_path: &Path) -> bool {
+        match self.probe_audio_codec(input_path) {
+            Ok(codec) => codec.eq_ignore_ascii_case("vorbis"),
+            Err(e) => {
+                warn!("无法探测音频编码，将重新编码 {:?}: {}", input_path, e);
+                false
+            }
+        }
+    }
+
+    /// 探测首个音频流的编码名称（如 `vorbis`），优先使用 ffprobe，找不到时读取 `ffmpeg -i` 的输出
+    pub fn probe_audio_codec(&self, input_path: &Path) -> Result<String> {
+        let input_str = input_path.to_str()
+            .ok_or_else(|| anyhow::anyhow!("输入路径包含无效字符: {:?}", input_path))?;
+
+        let (mut cmd, use_ffprobe) = match self.ffprobe_path() {
+            Some(ffprobe) => {
+                let mut cmd = Command::new(ffprobe);
+                cmd.args([
+                    "-v", "error",
+                    "-select_streams", "a:0",
+                    "-show_entries", "stream=codec_name",
+                    "-of", "default=noprint_wrappers=1",
+                    input_str,
+                ]);
+                (cmd, true)
+            }
+            None => {
+                // 不指定输出时 FFmpeg 打印输入信息后立即退出
+                let mut cmd = Command::new(&self.ffmpeg_path);
+                cmd.args(["-hide_banner", "-i", input_str]);
+                (cmd, false)
+            }
+        };
+
+        // 在Windows上隐藏命令行窗口
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let output = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .with_context(|| format!("无法探测音频编码: {:?}", input_path))?;
+
+        let codec = if use_ffprobe {
+            Self::parse_ffprobe_codec(&String::from_utf8_lossy(&output.stdout))
+        } else {
+            Self::parse_ffmpeg_audio_codec(&String::from_utf8_lossy(&output.stderr))
+        };
+        codec.ok_or_else(|| anyhow::anyhow!("未找到音频流: {:?}", input_path))
+    }
+
+    /// 与 FFmpeg 同目录的 ffprobe，其次从 PATH 查找
+    fn ffprobe_path(&self) -> Option<PathBuf> {
+        let name = format!("ffprobe{}", std::env::consts::EXE_SUFFIX);
+        self.ffmpeg_path
+            .parent()
+            .map(|dir| dir.join(&name))
+            .filter(|path| path.is_file())
+            .or_else(|| which::which("ffprobe").ok())
+    }
+
+    /// 解析 ffprobe 输出中的 `codec_name=vorbis`
+    fn parse_ffprobe_codec(output: &str) -> Option<String> {
+        output.lines().find_map(|line| {
+            let codec = line.trim().strip_prefix("codec_name=")?.trim();
+            (!codec.is_empty()).then(|| codec.to_string())
+        })
+    }
+
+    /// 解析 FFmpeg 输入信息中的 `Stream #0:0: Audio: vorbis, 44100 Hz, ...`
+    fn parse_ffmpeg_audio_codec(output: &str) -> Option<String> {
+        output.lines().find_map(|line| {
+            let rest = line.split_once("Audio: ")?.1;
+            let codec = rest.split([',', ' ']).next()?.trim();
+            (!codec.is_empty()).then(|| codec.to_string())
+        })
+    }
+
+    /// 构建 OGG 编码参数；启用响度归一化时追加单遍 `loudnorm` 滤镜
+    ///
+    /// `loudnorm` 内部会将音频上采样到 192kHz，因此同时指定 44.1kHz 输出采样率。
+    fn ogg_codec_args(options: AudioConvertOptions) -> Vec<String> {
+        let mut args = Vec::with_capacity(8);
+        if options.normalize_loudness {
+            let target = options.target_lufs.clamp(
+                *AudioConvertOptions::TARGET_LUFS_RANGE.start(),
+                *AudioConvertOptions::TARGET_LUFS_RANGE.end(),
+            );
+            args.extend([
+                "-af".to_string(),
+                format!("loudnorm=I={}:TP=-1.5:LRA=11", target),
+                "-ar".to_string(),
+                "44100".to_string(),
+            ]);
+        }
+        args.extend([
+            "-c:a".to_string(), "libvorbis".to_string(),  // 使用 Vorbis 编码器
+            "-q:a".to_string(), options.quality.min(10).to_string(),
+        ]);
+        args
+    }
+    
+    /// 按轨道的输出格式覆盖和裁剪范围转换导出文件
+    pub fn convert_track_output<F>(
+        &self,
+        input_path: &Path,
+        output_path: &Path,
+        format: TrackOutputFormat,
+        trim: Option<TrackTrim>,
+        should_cancel: &F,
+    ) -> Result<()>
+    where
+        F: Fn() -> bool + ?Sized,
+    {
+        let mut codec_args: Vec<String> = Vec::new();
+        if let Some(trim) = trim {
+            codec_args.extend([
+                "-ss".to_string(), format!("{:.3}", trim.start_secs),
+                "-to".to_string(), format!("{:.3}", trim.end_secs),
+            ]);
+        }
+        let format_args: &[&str] = match format {
+            // 仅裁剪时直接复制音频流，避免重新编码损失音质
+            TrackOutputFormat::ProjectDefault if trim.is_some() => &["-c:a", "copy"],
+            TrackOutputFormat::ProjectDefault => {
+                return Err(anyhow::anyhow!("项目默认格式无需转换: {:?}", input_path));
+            }
+            TrackOutputFormat::Ogg { quality } => {
+                codec_args.extend(["-c:a".to_string(), "libvorbis".to_string(), "-q:a".to_string(), quality.min(10).to_string()]);
+                &[]
+            }
+            TrackOutputFormat::Wav => &["-c:a", "pcm_s16le"],  // 16位 PCM，无压缩
+        };
+        codec_args.extend(format_args.iter().map(|arg| arg.to_string()));
+        let codec_args: Vec<&str> = codec_args.iter().map(String::as_str).collect();
+        self.run_ffmpeg_with_cancel(input_path, output_path, &codec_args, should_cancel, &|_| {})?;
+        Ok(())
+    }
+    
+    /// 截取音频片段并转换为 OGG（用于按章节分割，end 为 None 表示直到文件结束）
+    pub fn extract_segment_to_ogg<F>(
+        &self,
+        input_path: &Path,
+        output_path: &Path,
+        start_seconds: f64,
+        end_seconds: Option<f64>,
         should_cancel: &F,
     ) -> Result<String>
+    where
+        F: Fn() -> bool + ?Sized,
+    {
+        let start = format!("{:.3}", start_seconds);
+        let end = end_seconds.map(|end| format!("{:.3}", end));
+        let mut codec_args = vec!["-ss", start.as_str()];
+        if let Some(ref end) = end {
+            codec_args.extend(["-to", end.as_str()]);
+        }
+        codec_args.extend([
+            "-vn",                // 丢弃封面等视频流
+            "-c:a", "libvorbis",
+            "-q:a", "5",
+        ]);
+        self.run_ffmpeg_with_cancel(input_path, output_path, &codec_args, should_cancel, &|_| {})
+    }
+    
+    /// 使用 FFmpeg 的 `ebur128` 滤镜分析音频的综合响度（LUFS）
+    pub fn measure_loudness<F>(&self, input_path: &Path, should_cancel: &F) -> Result<f32>
+    where
+        F: Fn() -> bool + ?Sized,
+    {
+        if !input_path.exists() {
+            return Err(anyhow::anyhow!("输入文件不存在: {:?}", input_path));
+        }
+        let input_str = input_path.to_str()
+            .ok_or_else(|| anyhow::anyhow!("输入路径包含无效字符: {:?}", input_path))?;
+
+        let mut cmd = Command::new(&self.ffmpeg_path);
+        cmd.args(["-hide_banner", "-i", input_str, "-vn", "-af", "ebur128"]);
+        cmd.args(crate::ffmpeg_progress::PROGRESS_ARGS);
+        cmd.args(["-f", "null", "-"]);  // 只分析，不写输出文件
+
+        // 在Windows上隐藏命令行窗口
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let mut child = cmd
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("启动 FFmpeg 失败")?;
+        let (status, output) =
+            crate::ffmpeg_progress::wait_with_progress(&mut child, should_cancel, &|_| {})?;
+
+        if !status.success() {
+            error!("FFmpeg 响度分析失败: {}", output);
+            return Err(anyhow::anyhow!("FFmpeg 响度分析失败: {}", output.lines().last().unwrap_or_default()));
+        }
+        Self::parse_integrated_loudness(&output)
+            .ok_or_else(|| anyhow::anyhow!("无法从 FFmpeg 输出中读取综合响度: {:?}", input_path))
+    }
+
+    /// 从 `ebur128` 的汇总输出（`Summary:` 之后的 `I: -23.0 LUFS` 行）中解析综合响度
+    fn parse_integrated_loudness(output: &str) -> Option<f32> {
+        let summary = &output[output.rfind("Summary:")?..];
+        summary.lines().find_map(|line| {
+            line.trim()
+                .strip_prefix("I:")?
+                .trim()
+                .strip_suffix("LUFS")?
+                .trim()
+                .parse()
+                .ok()
+        })
+    }
+
+    /// 使用给定编码参数执行 FFmpeg 转换（支持取消检查）
+    fn run_ffmpeg_with_cancel<F>(
+        &self,
+        input_path: &Path,
+        output_path: &Path,
+        codec_args: &[&str],
+        should_cancel: &F,
+        on_progress: &(dyn Fn(f32) + Sync),
+    ) -> Result<String>
     where
         F: Fn() -> bool + ?Sized,
     {
@@ -64,10 +333,10 @@ impl AudioConverter {
             .ok_or_else(|| anyhow::anyhow!("输出路径包含无效字符: {:?}", output_path))?;
             
         let mut cmd = Command::new(&self.ffmpeg_path);
+        cmd.args(["-i", input_str]);
+        cmd.args(codec_args);
+        cmd.args(crate::ffmpeg_progress::PROGRESS_ARGS);
         cmd.args([
-            "-i", input_str,
-            "-c:a", "libvorbis",  // 使用 Vorbis 编码器
-            "-q:a", "5",          // 质量设置 (0-10, 5 是平衡点)
             "-y",                 // 覆盖输出文件
             output_str,
         ]);
@@ -97,36 +366,14 @@ impl AudioConverter {
             }
         }
         
-        // 等待完成并检查取消
-        let result = loop {
-            match child.try_wait() {
-                Ok(Some(status)) => break Ok(status),
-                Ok(None) => {
-                    // 进程还在运行，检查是否应该取消
-                    if should_cancel() {
-                        // 尝试终止进程
-                        let _ = child.kill();
-                        return Err(anyhow::anyhow!("转换任务被取消"));
-                    }
-                    // 短暂等待，减少CPU占用
-                    std::thread::sleep(std::time::Duration::from_millis(50));
-                }
-                Err(e) => break Err(e),
-            }
-        };
-        
-        let status = result.context("FFmpeg 执行失败")?;
+        // 等待完成，期间解析进度并检查取消
+        let (status, error_msg) =
+            crate::ffmpeg_progress::wait_with_progress(&mut child, should_cancel, on_progress)?;
         
         if status.success() {
             info!("转换成功: {:?}", output_path);
             Ok("转换成功".to_string())
         } else {
-            // 获取错误输出
-            let error_msg = if let Ok(output) = child.wait_with_output() {
-                String::from_utf8_lossy(&output.stderr).to_string()
-            } else {
-                "FFmpeg execution failed".to_string()
-            };
             error!("FFmpeg 转换失败: {}", error_msg);
             Err(anyhow::anyhow!("FFmpeg 转换失败: {}", error_msg))
         }
@@ -148,3 +395,53 @@ impl Default for AudioConverter {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ogg_codec_args_add_loudnorm_only_when_enabled() {
+        let mut options = AudioConvertOptions::default();
+        let args = AudioConverter::ogg_codec_args(options);
+        assert!(!args.iter().any(|arg| arg.starts_with("loudnorm")));
+        assert_eq!(args, ["-c:a", "libvorbis", "-q:a", "5"]);
+
+        options.normalize_loudness = true;
+        options.target_lufs = -14;
+        let args = AudioConverter::ogg_codec_args(options);
+        assert_eq!(args[0], "-af");
+        assert_eq!(args[1], "loudnorm=I=-14:TP=-1.5:LRA=11");
+
+        options.target_lufs = -99;
+        assert!(AudioConverter::ogg_codec_args(options)[1].starts_with("loudnorm=I=-30:"));
+    }
+
+    #[test]
+    fn test_parse_probed_audio_codec() {
+        assert_eq!(AudioConverter::parse_ffprobe_codec("codec_name=vorbis\n").as_deref(), Some("vorbis"));
+        assert_eq!(AudioConverter::parse_ffprobe_codec(""), None);
+
+        let stderr = "Input #0, ogg, from 'a.ogg':\n  Duration: 00:03:00.00, start: 0.000000, bitrate: 128 kb/s\n    Stream #0:0: Audio: vorbis, 44100 Hz, stereo, fltp, 128 kb/s\n";
+        assert_eq!(AudioConverter::parse_ffmpeg_audio_codec(stderr).as_deref(), Some("vorbis"));
+        let stderr = "    Stream #0:0: Audio: mp3 (mp3float), 44100 Hz, stereo\n";
+        assert_eq!(AudioConverter::parse_ffmpeg_audio_codec(stderr).as_deref(), Some("mp3"));
+        assert_eq!(AudioConverter::parse_ffmpeg_audio_codec("Stream #0:0: Video: theora"), None);
+    }
+
+    #[test]
+    fn test_parse_integrated_loudness() {
+        let output = "\
+[Parsed_ebur128_0 @ 0x1] t: 0.3  TARGET:-23 LUFS    M: -20.1 S:-120.7     I: -19.8 LUFS       LRA:   0.0 LU
+[Parsed_ebur128_0 @ 0x1] Summary:
+
+  Integrated loudness:
+    I:         -14.3 LUFS
+    Threshold: -24.6 LUFS
+
+  Loudness range:
+    LRA:         6.1 LU
+";
+        assert_eq!(AudioConverter::parse_integrated_loudness(output), Some(-14.3));
+        assert_eq!(AudioConverter::parse_integrated_loudness("t: 0.3 I: -19.8 LUFS"), None);
+    }
+}
@@ -1,9 +1,9 @@
-use std::io::{Read, Write};
+use std::io::{BufWriter, Read, Write};
 use std::path::Path;
 use std::sync::LazyLock;
 use xz2::read::XzDecoder;
 use infer::Infer;
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
 use std::ffi::CString;
 use std::os::raw::c_char;
 use crate::embedded::EMBEDDED_RESOURCES;
@@ -75,55 +75,72 @@ impl<'a> KuGouDecoder<'a> {
     where 
         F: Fn() -> bool
     {
-        let mut output_file = std::fs::File::create(output_path)?;
-        // 增大缓冲区大小以提高I/O效率
-        let mut buf = [0; 64 * 1024]; // 64KB 缓冲区
-        
-        // 读取文件头用于格式检测
-        let mut head_buffer = [0; 128];
-        self.read(&mut head_buffer)?;
-        
-        // 检测音频格式（使用静态实例避免重复创建）
-        static INFER: std::sync::LazyLock<Infer> = std::sync::LazyLock::new(|| Infer::new());
-        let info = &*INFER;
-        let ext = if let Some(kind) = info.get(&head_buffer) {
-            match kind.mime_type() {
-                "audio/midi" => "midi",
-                "audio/opus" => "opus", 
-                "audio/flac" => "flac",
-                "audio/webm" => "weba",
-                "audio/wav" => "wav",
-                "audio/ogg" => "ogg",
-                "audio/aac" => "aac",
-                _ => "mp3",
+        let output_file = std::fs::File::create(output_path)
+            .with_context(|| format!("无法创建输出文件: {:?}", output_path))?;
+        let writer = BufWriter::with_capacity(audio_decrypt::DECRYPT_CHUNK_SIZE, output_file);
+        let ext = self.decrypt_to_writer_with_cancel(writer, should_cancel)?;
+        Ok(ext.to_string())
+    }
+
+    /// 分块解密并写入 `writer`，返回识别出的音频扩展名
+    ///
+    /// 只缓存开头 `FORMAT_SNIFF_LEN` 字节用于识别格式，其余数据按 `DECRYPT_CHUNK_SIZE` 分块处理，
+    /// 内存占用与文件大小无关；每块之间检查取消。
+    pub fn decrypt_to_writer_with_cancel<W, F>(&mut self, mut writer: W, should_cancel: &F) -> Result<&'static str>
+    where
+        W: Write,
+        F: Fn() -> bool
+    {
+        let mut head = vec![0; audio_decrypt::FORMAT_SNIFF_LEN];
+        let head_len = read_up_to(self, &mut head)?;
+        let ext = detect_audio_extension(&head[..head_len]);
+        writer.write_all(&head[..head_len])?;
+
+        let mut buf = vec![0; audio_decrypt::DECRYPT_CHUNK_SIZE];
+        loop {
+            if should_cancel() {
+                return Err(anyhow!("解密任务被取消"));
             }
-        } else {
-            "mp3"
-        };
-        
-        // 写入文件头
-        output_file.write_all(&head_buffer)?;
-        
-        // 解密并写入剩余数据
-        while let Ok(len) = self.read(&mut buf) {
+            let len = self.read(&mut buf)?;
             if len == 0 {
                 break;
             }
-            
-            // 检查是否应该取消
-            if should_cancel() {
-                return Err(anyhow::anyhow!("解密任务被取消"));
-            }
-            
-            output_file.write_all(&buf[..len])?;
-            
-            // 每处理一定量的数据后让出CPU，避免长时间阻塞
-            if self.pos % (1024 * 1024) == 0 { // 每1MB让出一次CPU
-                std::thread::yield_now();
-            }
+            writer.write_all(&buf[..len])?;
+        }
+        writer.flush()?;
+
+        Ok(ext)
+    }
+}
+
+/// 读取数据直到填满缓冲区或到达文件末尾，返回读取的字节数
+fn read_up_to(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(len) => filled += len,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
         }
-        
-        Ok(ext.to_string())
+    }
+    Ok(filled)
+}
+
+/// 文件类型识别器（使用静态实例避免重复创建）
+static INFER: LazyLock<Infer> = LazyLock::new(Infer::new);
+
+/// 根据解密后的文件头识别音频扩展名（无法识别时按 MP3 处理）
+fn detect_audio_extension(head: &[u8]) -> &'static str {
+    match INFER.get(head).map(|kind| kind.mime_type()) {
+        Some("audio/midi") => "midi",
+        Some("audio/opus") => "opus",
+        Some("audio/flac") | Some("audio/x-flac") => "flac",
+        Some("audio/webm") => "weba",
+        Some("audio/wav") | Some("audio/x-wav") => "wav",
+        Some("audio/ogg") => "ogg",
+        Some("audio/aac") => "aac",
+        _ => "mp3",
     }
 }
 
@@ -153,6 +170,12 @@ impl<'a> Read for KuGouDecoder<'a> {
         ];
 
         let len = self.origin.read(buf)?;
+        if self.pos + len as u64 >= KuGouDecoder::PUB_KEY_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "KGM 文件超出酷狗密钥支持的最大长度",
+            ));
+        }
         let audio = &mut buf[..len];
 
         let pub_key = KuGouDecoder::get_pub_key(self.pos..self.pos + len as u64);
@@ -196,6 +219,434 @@ impl Read for Bytes<'_> {
     }
 }
 
+/// QQ音乐 QMC 文件解密器（.qmc0/.qmc3/.qmcflac/.mflac/.mgg 等）
+///
+/// 旧格式使用静态密钥；新格式在文件末尾嵌入经 TEA 加密的密钥块，
+/// 按密钥长度选择映射密钥（≤300 字节）或改进的 RC4 密钥流。
+pub struct QqMusicDecoder {
+    file: std::fs::File,
+    cipher: QmcCipher,
+    /// 音频数据长度（不含末尾的密钥块）
+    audio_len: u64,
+    /// 按嵌入密钥解密失败时可回退到静态密钥的文件长度（扩展名不要求密钥块时才有）
+    static_fallback_len: Option<u64>,
+}
+
+/// QMC 密钥流
+enum QmcCipher {
+    Static,
+    Map(Vec<u8>),
+    Rc4(QmcRc4Cipher),
+}
+
+/// QMC 使用的改进 RC4（分段重置密钥流）
+struct QmcRc4Cipher {
+    key: Vec<u8>,
+    sbox: Vec<u8>,
+    hash: u32,
+}
+
+impl QmcRc4Cipher {
+    const FIRST_SEGMENT_SIZE: usize = 128;
+    const SEGMENT_SIZE: usize = 5120;
+
+    fn new(key: Vec<u8>) -> Self {
+        let n = key.len();
+        let mut sbox: Vec<u8> = (0..n).map(|i| i as u8).collect();
+        let mut j = 0usize;
+        for i in 0..n {
+            j = (j + sbox[i] as usize + key[i % n] as usize) % n;
+            sbox.swap(i, j);
+        }
+
+        let mut hash: u32 = 1;
+        for &value in &key {
+            if value == 0 {
+                continue;
+            }
+            let next = hash.wrapping_mul(value as u32);
+            if next == 0 || next <= hash {
+                break;
+            }
+            hash = next;
+        }
+        Self { key, sbox, hash }
+    }
+
+    fn segment_key(&self, id: usize) -> usize {
+        let n = self.key.len();
+        let seed = self.key[id % n] as f64;
+        let index = (self.hash as f64 / ((id + 1) as f64 * seed) * 100.0) as i64;
+        index.rem_euclid(n as i64) as usize
+    }
+
+    fn decrypt_first_segment(&self, buf: &mut [u8], offset: usize) {
+        for (i, byte) in buf.iter_mut().enumerate() {
+            *byte ^= self.key[self.segment_key(offset + i)];
+        }
+    }
+
+    fn decrypt_segment(&self, buf: &mut [u8], offset: usize) {
+        let n = self.key.len();
+        let mut sbox = self.sbox.clone();
+        let (mut j, mut k) = (0usize, 0usize);
+        let skip = offset % Self::SEGMENT_SIZE + self.segment_key(offset / Self::SEGMENT_SIZE);
+        for i in 0..skip + buf.len() {
+            j = (j + 1) % n;
+            k = (sbox[j] as usize + k) % n;
+            sbox.swap(j, k);
+            if i >= skip {
+                buf[i - skip] ^= sbox[(sbox[j] as usize + sbox[k] as usize) % n];
+            }
+        }
+    }
+
+    fn decrypt(&self, buf: &mut [u8], mut offset: usize) {
+        let mut processed = 0;
+        if offset < Self::FIRST_SEGMENT_SIZE {
+            let len = buf.len().min(Self::FIRST_SEGMENT_SIZE - offset);
+            self.decrypt_first_segment(&mut buf[..len], offset);
+            processed += len;
+            offset += len;
+        }
+        while processed < buf.len() {
+            // 每段不跨越 SEGMENT_SIZE 边界
+            let len = (buf.len() - processed).min(Self::SEGMENT_SIZE - offset % Self::SEGMENT_SIZE);
+            self.decrypt_segment(&mut buf[processed..processed + len], offset);
+            processed += len;
+            offset += len;
+        }
+    }
+}
+
+impl QmcCipher {
+    /// 旧版 QMC 静态密钥表
+    const STATIC_BOX: [u8; 256] = [
+        0x77, 0x48, 0x32, 0x73, 0xDE, 0xF2, 0xC0, 0xC8, 0x95, 0xEC, 0x30, 0xB2, 0x51, 0xC3, 0xE1, 0xA0,
+        0x9E, 0xE6, 0x9D, 0xCF, 0xFA, 0x7F, 0x14, 0xD1, 0xCE, 0xB8, 0xDC, 0xC3, 0x4A, 0x67, 0x93, 0xD6,
+        0x28, 0xC2, 0x91, 0x70, 0xCA, 0x8D, 0xA2, 0xA4, 0xF0, 0x08, 0x61, 0x90, 0x7E, 0x6F, 0xA2, 0xE0,
+        0xEB, 0xAE, 0x3E, 0xB6, 0x67, 0xC7, 0x92, 0xF4, 0x91, 0xB5, 0xF6, 0x6C, 0x5E, 0x84, 0x40, 0xF7,
+        0xF3, 0x1B, 0x02, 0x7F, 0xD5, 0xAB, 0x41, 0x89, 0x28, 0xF4, 0x25, 0xCC, 0x52, 0x11, 0xAD, 0x43,
+        0x68, 0xA6, 0x41, 0x8B, 0x84, 0xB5, 0xFF, 0x2C, 0x92, 0x4A, 0x26, 0xD8, 0x47, 0x6A, 0x7C, 0x95,
+        0x61, 0xCC, 0xE6, 0xCB, 0xBB, 0x3F, 0x47, 0x58, 0x89, 0x75, 0xC3, 0x75, 0xA1, 0xD9, 0xAF, 0xCC,
+        0x08, 0x73, 0x17, 0xDC, 0xAA, 0x9A, 0xA2, 0x16, 0x41, 0xD8, 0xA2, 0x06, 0xC6, 0x8B, 0xFC, 0x66,
+        0x34, 0x9F, 0xCF, 0x18, 0x23, 0xA0, 0x0A, 0x74, 0xE7, 0x2B, 0x27, 0x70, 0x92, 0xE9, 0xAF, 0x37,
+        0xE6, 0x8C, 0xA7, 0xBC, 0x62, 0x65, 0x9C, 0xC2, 0x08, 0xC9, 0x88, 0xB3, 0xF3, 0x43, 0xAC, 0x74,
+        0x2C, 0x0F, 0xD4, 0xAF, 0xA1, 0xC3, 0x01, 0x64, 0x95, 0x4E, 0x48, 0x9F, 0xF4, 0x35, 0x78, 0x95,
+        0x7A, 0x39, 0xD6, 0x6A, 0xA0, 0x6D, 0x40, 0xE8, 0x4F, 0xA8, 0xEF, 0x11, 0x1D, 0xF3, 0x1B, 0x3F,
+        0x3F, 0x07, 0xDD, 0x6F, 0x5B, 0x19, 0x30, 0x19, 0xFB, 0xEF, 0x0E, 0x37, 0xF0, 0x0E, 0xCD, 0x16,
+        0x49, 0xFE, 0x53, 0x47, 0x13, 0x1A, 0xBD, 0xA4, 0xF1, 0x40, 0x19, 0x60, 0x0E, 0xED, 0x68, 0x09,
+        0x06, 0x5F, 0x4D, 0xCF, 0x3D, 0x1A, 0xFE, 0x20, 0x77, 0xE4, 0xD9, 0xDA, 0xF9, 0xA4, 0x2B, 0x76,
+        0x1C, 0x71, 0xDB, 0x00, 0xBC, 0xFD, 0x0C, 0x6C, 0xA5, 0x47, 0xF7, 0xF6, 0x00, 0x79, 0x4A, 0x11,
+    ];
+
+    /// 按密钥长度选择密钥流（空密钥使用静态密钥）
+    fn from_key(key: Vec<u8>) -> Self {
+        match key.len() {
+            0 => QmcCipher::Static,
+            1..=300 => QmcCipher::Map(key),
+            _ => QmcCipher::Rc4(QmcRc4Cipher::new(key)),
+        }
+    }
+
+    /// 解密从 `offset` 开始的数据
+    fn decrypt(&self, buf: &mut [u8], offset: usize) {
+        match self {
+            QmcCipher::Static => {
+                for (i, byte) in buf.iter_mut().enumerate() {
+                    *byte ^= Self::STATIC_BOX[(Self::mask_offset(offset + i).pow(2) + 27) & 0xff];
+                }
+            }
+            QmcCipher::Map(key) => {
+                for (i, byte) in buf.iter_mut().enumerate() {
+                    let index = (Self::mask_offset(offset + i).pow(2) + 71214) % key.len();
+                    let shift = ((index & 0x7) + 4) % 8;
+                    // 与参考实现一致：左右移动相同位数后合并（不是循环移位）
+                    *byte ^= (key[index] << shift) | (key[index] >> shift);
+                }
+            }
+            QmcCipher::Rc4(cipher) => cipher.decrypt(buf, offset),
+        }
+    }
+
+    fn mask_offset(offset: usize) -> usize {
+        if offset > 0x7FFF {
+            offset % 0x7FFF
+        } else {
+            offset
+        }
+    }
+}
+
+impl QqMusicDecoder {
+    /// 新版密钥的前缀（需要额外两层 TEA 解密）
+    const KEY_V2_PREFIX: &'static [u8] = b"QQMusic EncV2,Key:";
+    const KEY_V2_TEA_KEY1: &'static [u8; 16] = b"386ZJY!@#*$%^&)(";
+    const KEY_V2_TEA_KEY2: &'static [u8; 16] = b"**#!(#$%&^a1cZ,T";
+
+    /// 打开文件并读取末尾的密钥块（缺少必需的密钥块时返回错误）
+    pub fn open(path: &Path) -> Result<Self> {
+        use std::io::{Seek, SeekFrom};
+
+        let mut file = std::fs::File::open(path)?;
+        let file_len = file.metadata()?.len();
+        if file_len < 8 {
+            return Err(anyhow!("文件过小，不是有效的QQ音乐加密文件"));
+        }
+
+        let read_at = |file: &mut std::fs::File, start: u64, len: u64| -> Result<Vec<u8>> {
+            let mut buf = vec![0; len as usize];
+            file.seek(SeekFrom::Start(start))?;
+            file.read_exact(&mut buf)?;
+            Ok(buf)
+        };
+
+        let tail = read_at(&mut file, file_len - 4, 4)?;
+        if tail == b"STag" {
+            return Err(anyhow!("该文件（STag 格式）不包含解密密钥，请在QQ音乐客户端中重新下载"));
+        }
+        let key_required = Self::requires_embedded_key(path);
+        let (cipher, audio_len) = match Self::read_key_block(&mut file, file_len, &tail, read_at) {
+            Ok(Some((key, audio_len))) => (QmcCipher::from_key(key), audio_len),
+            Ok(None) if key_required => {
+                return Err(anyhow!("未找到嵌入的密钥块，无法解密（文件可能不完整或使用了不支持的加密版本）"));
+            }
+            Err(e) if key_required => return Err(e),
+            // 旧格式：无密钥块，使用静态密钥
+            Ok(None) => (QmcCipher::Static, file_len),
+            Err(e) => {
+                log::warn!("无法解析嵌入的密钥块，回退到静态密钥 {:?}: {:#}", path, e);
+                (QmcCipher::Static, file_len)
+            }
+        };
+        let static_fallback_len = (!key_required && !matches!(cipher, QmcCipher::Static)).then_some(file_len);
+
+        Ok(Self {
+            file,
+            cipher,
+            audio_len,
+            static_fallback_len,
+        })
+    }
+
+    /// 读取文件末尾的密钥块，返回 (解码后的密钥, 音频数据长度)；没有密钥块时返回 None
+    fn read_key_block(
+        file: &mut std::fs::File,
+        file_len: u64,
+        tail: &[u8],
+        read_at: impl Fn(&mut std::fs::File, u64, u64) -> Result<Vec<u8>>,
+    ) -> Result<Option<(Vec<u8>, u64)>> {
+        if tail == b"QTag" {
+            let meta_len = u32::from_be_bytes(read_at(file, file_len - 8, 4)?.try_into().unwrap_or_default()) as u64;
+            if meta_len + 8 > file_len {
+                return Err(anyhow!("QTag 密钥块长度无效，文件可能不完整"));
+            }
+            let audio_len = file_len - 8 - meta_len;
+            let meta = read_at(file, audio_len, meta_len)?;
+            let meta = String::from_utf8_lossy(&meta);
+            let items: Vec<&str> = meta.split(',').collect();
+            if items.len() != 3 {
+                return Err(anyhow!("QTag 密钥块格式无效"));
+            }
+            return Ok(Some((Self::derive_key(items[0].as_bytes())?, audio_len)));
+        }
+
+        let key_len = u32::from_le_bytes(tail.try_into().unwrap_or_default()) as u64;
+        if key_len == 0 || key_len > 0xFFFF || key_len + 4 > file_len {
+            return Ok(None);
+        }
+        let audio_len = file_len - 4 - key_len;
+        let raw_key = read_at(file, audio_len, key_len)?;
+        let end = raw_key.iter().rposition(|&b| b != 0).map_or(0, |pos| pos + 1);
+        Ok(Some((Self::derive_key(&raw_key[..end])?, audio_len)))
+    }
+
+    /// 该扩展名是否必须带有嵌入密钥块
+    fn requires_embedded_key(path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| audio_decrypt::QQMUSIC_KEY_REQUIRED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+            .unwrap_or(false)
+    }
+
+    /// 解密文件开头并识别音频格式，返回扩展名（无法识别时说明密钥不正确）
+    ///
+    /// 文件末尾的音频数据恰好像密钥块时，按嵌入密钥解密会得到无法识别的数据，
+    /// 对不要求密钥块的扩展名再按静态密钥解密整个文件重试。
+    pub fn detect_format(&mut self) -> Result<&'static str> {
+        if let Some(format) = Self::sniff_format(&mut self.file, &self.cipher, self.audio_len)? {
+            return Ok(format);
+        }
+        if let Some(file_len) = self.static_fallback_len.take() {
+            if let Some(format) = Self::sniff_format(&mut self.file, &QmcCipher::Static, file_len)? {
+                self.cipher = QmcCipher::Static;
+                self.audio_len = file_len;
+                return Ok(format);
+            }
+        }
+        Err(anyhow!("解密结果不是可识别的音频格式，密钥可能不正确，未写入输出文件"))
+    }
+
+    /// 用指定密钥流解密文件开头并识别音频格式
+    fn sniff_format(file: &mut std::fs::File, cipher: &QmcCipher, len: u64) -> Result<Option<&'static str>> {
+        use std::io::{Seek, SeekFrom};
+
+        let mut head = vec![0; len.min(4096) as usize];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut head)?;
+        cipher.decrypt(&mut head, 0);
+
+        Ok(match INFER.get(&head).map(|kind| kind.mime_type()) {
+            Some("audio/mpeg") => Some("mp3"),
+            Some("audio/x-flac") | Some("audio/flac") => Some("flac"),
+            Some("audio/ogg") => Some("ogg"),
+            Some("audio/m4a") | Some("audio/mp4") | Some("video/mp4") => Some("m4a"),
+            Some("audio/x-wav") | Some("audio/wav") => Some("wav"),
+            _ => None,
+        })
+    }
+
+    /// 解密音频数据到指定路径（带取消检查）
+    pub fn decrypt_to_file_with_cancel<F>(&mut self, output_path: &Path, should_cancel: &F) -> Result<()>
+    where
+        F: Fn() -> bool
+    {
+        use std::io::{Seek, SeekFrom};
+
+        self.file.seek(SeekFrom::Start(0))?;
+        let output_file = std::fs::File::create(output_path)
+            .with_context(|| format!("无法创建输出文件: {:?}", output_path))?;
+        let mut writer = BufWriter::with_capacity(audio_decrypt::DECRYPT_CHUNK_SIZE, output_file);
+        let mut buf = vec![0; audio_decrypt::DECRYPT_CHUNK_SIZE];
+        let mut offset = 0u64;
+        while offset < self.audio_len {
+            if should_cancel() {
+                return Err(anyhow!("解密任务被取消"));
+            }
+            let len = (self.audio_len - offset).min(buf.len() as u64) as usize;
+            self.file.read_exact(&mut buf[..len])?;
+            self.cipher.decrypt(&mut buf[..len], offset as usize);
+            writer.write_all(&buf[..len])?;
+            offset += len as u64;
+        }
+        // 在重命名或整理输出前暴露写入错误
+        writer.flush().with_context(|| format!("无法写入输出文件: {:?}", output_path))?;
+        Ok(())
+    }
+
+    /// 解码嵌入的密钥（Base64 + TEA，支持 EncV2 前缀）
+    fn derive_key(raw: &[u8]) -> Result<Vec<u8>> {
+        let mut decoded = base64_decode(raw)?;
+        if let Some(rest) = decoded.strip_prefix(Self::KEY_V2_PREFIX) {
+            let layer = tc_tea_decrypt(rest, Self::KEY_V2_TEA_KEY1)?;
+            let layer = tc_tea_decrypt(&layer, Self::KEY_V2_TEA_KEY2)?;
+            decoded = base64_decode(&layer)?;
+        }
+        if decoded.len() < 16 {
+            return Err(anyhow!("嵌入的密钥长度过短"));
+        }
+
+        let simple_key = simple_make_key(106, 8);
+        let mut tea_key = [0u8; 16];
+        for i in 0..8 {
+            tea_key[i * 2] = simple_key[i];
+            tea_key[i * 2 + 1] = decoded[i];
+        }
+        let mut key = decoded[..8].to_vec();
+        key.extend(tc_tea_decrypt(&decoded[8..], &tea_key)?);
+        Ok(key)
+    }
+}
+
+/// 由盐值生成 TEA 密钥的前半部分
+fn simple_make_key(salt: u8, len: usize) -> Vec<u8> {
+    (0..len)
+        .map(|i| ((salt as f64 + i as f64 * 0.1).tan().abs() * 100.0) as u8)
+        .collect()
+}
+
+/// 解密单个 TEA 块（16 轮，大端序）
+fn tea_decrypt_block(block: [u8; 8], key: &[u32; 4]) -> [u8; 8] {
+    const DELTA: u32 = 0x9E37_79B9;
+    const ROUNDS: u32 = 16;
+    let mut v0 = u32::from_be_bytes([block[0], block[1], block[2], block[3]]);
+    let mut v1 = u32::from_be_bytes([block[4], block[5], block[6], block[7]]);
+    let mut sum = DELTA.wrapping_mul(ROUNDS);
+    for _ in 0..ROUNDS {
+        v1 = v1.wrapping_sub(
+            (v0 << 4).wrapping_add(key[2]) ^ v0.wrapping_add(sum) ^ (v0 >> 5).wrapping_add(key[3]),
+        );
+        v0 = v0.wrapping_sub(
+            (v1 << 4).wrapping_add(key[0]) ^ v1.wrapping_add(sum) ^ (v1 >> 5).wrapping_add(key[1]),
+        );
+        sum = sum.wrapping_sub(DELTA);
+    }
+    let mut out = [0; 8];
+    out[..4].copy_from_slice(&v0.to_be_bytes());
+    out[4..].copy_from_slice(&v1.to_be_bytes());
+    out
+}
+
+/// 腾讯 TEA-CBC 解密（带随机填充、2 字节盐和 7 字节零校验）
+fn tc_tea_decrypt(input: &[u8], key: &[u8; 16]) -> Result<Vec<u8>> {
+    const SALT_LEN: usize = 2;
+    const ZERO_LEN: usize = 7;
+    if input.len() % 8 != 0 || input.len() < 16 {
+        return Err(anyhow!("密钥数据长度无效"));
+    }
+
+    let key = [0, 4, 8, 12].map(|i| u32::from_be_bytes([key[i], key[i + 1], key[i + 2], key[i + 3]]));
+    let mut plain = Vec::with_capacity(input.len());
+    let mut dest = [0u8; 8];
+    let mut prev_cipher = [0u8; 8];
+    for chunk in input.chunks_exact(8) {
+        let mut block = [0u8; 8];
+        for i in 0..8 {
+            block[i] = chunk[i] ^ dest[i];
+        }
+        dest = tea_decrypt_block(block, &key);
+        plain.extend(dest.iter().zip(&prev_cipher).map(|(d, c)| d ^ c));
+        prev_cipher.copy_from_slice(chunk);
+    }
+
+    let pad_len = (plain[0] & 0x7) as usize;
+    let start = 1 + pad_len + SALT_LEN;
+    if start + ZERO_LEN > plain.len() {
+        return Err(anyhow!("密钥数据长度无效"));
+    }
+    let end = plain.len() - ZERO_LEN;
+    if plain[end..].iter().any(|&b| b != 0) {
+        return Err(anyhow!("密钥校验失败，文件可能已损坏"));
+    }
+    Ok(plain[start..end].to_vec())
+}
+
+/// 标准 Base64 解码（忽略空白、末尾的 `=` 和 NUL）
+fn base64_decode(input: &[u8]) -> Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &c in input {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' | 0 => break,
+            c if c.is_ascii_whitespace() => continue,
+            _ => return Err(anyhow!("密钥不是有效的 Base64 数据")),
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(output)
+}
+
 /// 音频解密管理器
 pub struct AudioDecryptManager;
 
@@ -219,9 +670,16 @@ impl AudioDecryptManager {
         // 确保路径长度在限制内
         output_path = crate::utils::string_utils::StringUtils::ensure_path_length(&output_path, 260)
             .unwrap_or_else(|_| output_path.clone());
-        // 确保文件名唯一
-        output_path = crate::utils::string_utils::StringUtils::ensure_unique_path(output_path);
-        let detected_format = decoder.decrypt_to_file_with_cancel(&output_path, should_cancel)?;
+        // 占用唯一的文件名（并行解密时其他线程不会选中同一个文件）
+        output_path = crate::utils::string_utils::StringUtils::reserve_unique_path(&output_path)?;
+        let detected_format = match decoder.decrypt_to_file_with_cancel(&output_path, should_cancel) {
+            Ok(format) => format,
+            Err(e) => {
+                // 取消或失败时删除不完整的输出文件
+                let _ = std::fs::remove_file(&output_path);
+                return Err(e);
+            }
+        };
         
         // 如果检测到的格式不是mp3，重命名文件
         if detected_format != "mp3" {
@@ -229,9 +687,19 @@ impl AudioDecryptManager {
             // 确保路径长度在限制内
             final_path = crate::utils::string_utils::StringUtils::ensure_path_length(&final_path, 260)
                 .unwrap_or_else(|_| final_path.clone());
-            // 确保文件名唯一
-            final_path = crate::utils::string_utils::StringUtils::ensure_unique_path(final_path);
-            std::fs::rename(&output_path, &final_path)?;
+            // 占用唯一的文件名，随后由重命名覆盖占位文件
+            final_path = match crate::utils::string_utils::StringUtils::reserve_unique_path(&final_path) {
+                Ok(path) => path,
+                Err(e) => {
+                    let _ = std::fs::remove_file(&output_path);
+                    return Err(e.into());
+                }
+            };
+            if let Err(e) = std::fs::rename(&output_path, &final_path) {
+                let _ = std::fs::remove_file(&output_path);
+                let _ = std::fs::remove_file(&final_path);
+                return Err(e.into());
+            }
             Ok(final_path.to_string_lossy().to_string())
         } else {
             Ok(output_path.to_string_lossy().to_string())
@@ -251,7 +719,12 @@ impl AudioDecryptManager {
             let safe_filename = crate::utils::string_utils::StringUtils::to_ascii_safe_pinyin(&file_stem);
             
             // 使用libncmdump DLL解密（DLL会自动输出到源文件位置）
-            Self::decrypt_ncm_with_dll(input_path)?;
+            // DLL 提取到同一临时路径，并行解密时需串行化加载与调用
+            static NCM_DLL_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+            {
+                let _guard = NCM_DLL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+                Self::decrypt_ncm_with_dll(input_path)?;
+            }
             
             // 检查源文件目录中是否生成了mp3文件
             let input_dir = input_path.parent()
@@ -292,10 +765,9 @@ impl AudioDecryptManager {
                 // 确保路径长度在限制内
                 final_output_path = crate::utils::string_utils::StringUtils::ensure_path_length(&final_output_path, 260)
                     .unwrap_or_else(|_| final_output_path.clone());
-                // 确保文件名唯一
-                final_output_path = crate::utils::string_utils::StringUtils::ensure_unique_path(final_output_path);
-                
                 std::fs::create_dir_all(output_dir)?;
+                // 占用唯一的文件名（并行解密时其他线程不会选中同一个文件）
+                final_output_path = crate::utils::string_utils::StringUtils::reserve_unique_path(&final_output_path)?;
                 
                 // 使用复制+删除的方式处理跨磁盘移动
                 std::fs::copy(&output_path, &final_output_path)?;
@@ -371,6 +843,40 @@ impl AudioDecryptManager {
     }
     
 
+    /// 解密QQ音乐QMC文件（带取消检查），自动识别MP3/FLAC等输出格式
+    pub fn decrypt_qqmusic_file<F>(input_path: &Path, output_dir: &Path, should_cancel: &F) -> Result<String>
+    where
+        F: Fn() -> bool
+    {
+        let mut decoder = QqMusicDecoder::open(input_path)?;
+        let detected_format = decoder.detect_format()?;
+
+        let file_stem = input_path.file_stem()
+            .ok_or_else(|| anyhow!("Invalid file name"))?
+            .to_string_lossy();
+        let safe_filename = crate::utils::string_utils::StringUtils::to_ascii_safe_pinyin(&file_stem);
+        let mut output_path = output_dir.join(format!("{}.{}", safe_filename, detected_format));
+        // 确保路径长度在限制内
+        output_path = crate::utils::string_utils::StringUtils::ensure_path_length(&output_path, 260)
+            .unwrap_or_else(|_| output_path.clone());
+        // 占用唯一的文件名（并行解密时其他线程不会选中同一个文件）
+        output_path = crate::utils::string_utils::StringUtils::reserve_unique_path(&output_path)?;
+
+        if let Err(e) = decoder.decrypt_to_file_with_cancel(&output_path, should_cancel) {
+            let _ = std::fs::remove_file(&output_path);
+            return Err(e);
+        }
+        Ok(output_path.to_string_lossy().to_string())
+    }
+
+    /// 检查文件是否为QQ音乐加密格式（QMC 没有统一的文件头，按扩展名判断）
+    pub fn is_qqmusic_file(path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| audio_decrypt::QQMUSIC_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+            .unwrap_or(false)
+    }
+
     /// 检查文件是否为酷狗KGM格式
     pub fn is_kugou_file(path: &Path) -> bool {
         if let Ok(mut file) = std::fs::File::open(path) {
@@ -401,3 +907,154 @@ impl AudioDecryptManager {
 }
 
 use std::ops::Range;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_make_key() {
+        assert_eq!(simple_make_key(106, 8), vec![0x69, 0x56, 0x46, 0x38, 0x2b, 0x20, 0x15, 0x0b]);
+    }
+
+    #[test]
+    fn test_base64_decode() {
+        assert_eq!(base64_decode(b"UVFNdXNpYw==").unwrap(), b"QQMusic");
+        assert_eq!(base64_decode(b"YWJj\0\0").unwrap(), b"abc");
+        assert!(base64_decode(b"a*b").is_err());
+    }
+
+    #[test]
+    fn test_is_qqmusic_file_by_extension() {
+        assert!(AudioDecryptManager::is_qqmusic_file(Path::new("song.qmcflac")));
+        assert!(AudioDecryptManager::is_qqmusic_file(Path::new("song.MFLAC")));
+        assert!(!AudioDecryptManager::is_qqmusic_file(Path::new("song.flac")));
+    }
+
+    #[test]
+    fn test_qqmusic_missing_key_block_is_an_error() {
+        let dir = std::env::temp_dir().join(format!("zmm_qmc_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // mflac 没有密钥块时不能回退到静态密钥
+        let mflac = dir.join("song.mflac");
+        std::fs::write(&mflac, [0xAAu8; 64]).unwrap();
+        assert!(QqMusicDecoder::open(&mflac).is_err());
+
+        let stag = dir.join("song.mgg");
+        let mut data = vec![0u8; 64];
+        data.extend_from_slice(b"STag");
+        std::fs::write(&stag, data).unwrap();
+        let err = QqMusicDecoder::open(&stag).err().unwrap();
+        assert!(err.to_string().contains("STag"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_qqmusic_falls_back_to_static_key() {
+        let dir = std::env::temp_dir().join(format!("zmm_qmc_static_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // 旧格式 qmc0：整个文件用静态密钥加密；末尾数据恰好像一个 16 字节密钥块的长度
+        let mut plain = b"ID3\x03\x00\x00\x00\x00\x00\x00".to_vec();
+        plain.resize(512, 0x55);
+        let mut data = plain.clone();
+        QmcCipher::Static.decrypt(&mut data, 0);
+        let len = data.len();
+        data[len - 4..].copy_from_slice(&16u32.to_le_bytes());
+        let qmc0 = dir.join("song.qmc0");
+        std::fs::write(&qmc0, &data).unwrap();
+
+        let mut decoder = QqMusicDecoder::open(&qmc0).unwrap();
+        assert_eq!(decoder.detect_format().unwrap(), "mp3");
+        let output = dir.join("song.mp3");
+        decoder.decrypt_to_file_with_cancel(&output, &|| false).unwrap();
+        let decrypted = std::fs::read(&output).unwrap();
+        assert_eq!(decrypted.len(), len);
+        assert_eq!(decrypted[..len - 4], plain[..len - 4]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_map_cipher_is_symmetric() {
+        let cipher = QmcCipher::from_key((1..=128).collect());
+        let original: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+        let mut data = original.clone();
+        cipher.decrypt(&mut data, 0);
+        assert_ne!(data, original);
+        cipher.decrypt(&mut data, 0);
+        assert_eq!(data, original);
+    }
+
+    /// 按需生成数据的 KGM 输入：文件头之后是全零数据，记录单次请求的最大长度
+    struct SyntheticKgm {
+        header: Vec<u8>,
+        remaining: u64,
+        max_request: std::rc::Rc<std::cell::Cell<usize>>,
+    }
+
+    impl SyntheticKgm {
+        fn new(audio_len: u64, max_request: &std::rc::Rc<std::cell::Cell<usize>>) -> Self {
+            let mut header = vec![0u8; KuGouDecoder::HEADER_LEN as usize];
+            header[..KuGouDecoder::MAGIC_HEADER.len()].copy_from_slice(&KuGouDecoder::MAGIC_HEADER);
+            Self { header, remaining: audio_len, max_request: max_request.clone() }
+        }
+    }
+
+    impl Read for SyntheticKgm {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.max_request.set(self.max_request.get().max(buf.len()));
+            if !self.header.is_empty() {
+                let len = buf.len().min(self.header.len());
+                buf[..len].copy_from_slice(&self.header[..len]);
+                self.header.drain(..len);
+                return Ok(len);
+            }
+            let len = (buf.len() as u64).min(self.remaining) as usize;
+            buf[..len].fill(0);
+            self.remaining -= len as u64;
+            Ok(len)
+        }
+    }
+
+    /// 只统计字节数的输出，记录单次写入的最大长度
+    #[derive(Default)]
+    struct CountingSink {
+        total: u64,
+        max_write: usize,
+    }
+
+    impl Write for CountingSink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.total += buf.len() as u64;
+            self.max_write = self.max_write.max(buf.len());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_kgm_decrypt_streams_large_file_in_bounded_chunks() {
+        const AUDIO_LEN: u64 = 64 * 1024 * 1024 + 123;
+        let max_request = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut decoder = KuGouDecoder::try_new(SyntheticKgm::new(AUDIO_LEN, &max_request)).unwrap();
+        let mut sink = CountingSink::default();
+        decoder.decrypt_to_writer_with_cancel(&mut sink, &|| false).unwrap();
+
+        // 输出完整，且每次读写都不超过固定块大小
+        assert_eq!(sink.total, AUDIO_LEN);
+        assert!(sink.max_write <= audio_decrypt::DECRYPT_CHUNK_SIZE);
+        assert!(max_request.get() <= audio_decrypt::DECRYPT_CHUNK_SIZE.max(KuGouDecoder::HEADER_LEN as usize));
+
+        // 取消在块之间生效
+        let mut decoder = KuGouDecoder::try_new(SyntheticKgm::new(AUDIO_LEN, &max_request)).unwrap();
+        let mut sink = CountingSink::default();
+        assert!(decoder.decrypt_to_writer_with_cancel(&mut sink, &|| true).is_err());
+        assert!(sink.total <= audio_decrypt::FORMAT_SNIFF_LEN as u64);
+    }
+}
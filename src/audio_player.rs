@@ -0,0 +1,429 @@
+/*!
+ * 播放列表播放器模块
+ * 按导出顺序连续播放轨道（应用每个轨道的分贝和裁剪），使用 symphonia 解码、rodio 输出
+ */
+
+use anyhow::{Context, Result};
+use crossbeam_channel::{Receiver, TryRecvError};
+use log::{debug, warn};
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::audio::AudioStream;
+use crate::models::{Track, TrackTrim};
+
+/// 播放列表条目（轨道的播放快照）
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistEntry {
+    /// 轨道 ID（复制的轨道与原轨道路径相同，按 ID 区分）
+    pub track_id: u64,
+    /// 文件路径
+    pub path: PathBuf,
+    /// 显示名称
+    pub name: String,
+    /// 分贝调整值
+    pub decibels: i32,
+    /// 裁剪范围
+    pub trim: Option<TrackTrim>,
+    /// 时长（秒）
+    pub duration: u32,
+}
+
+impl PlaylistEntry {
+    /// 从轨道创建条目
+    pub fn from_track(track: &Track) -> Self {
+        Self {
+            track_id: track.id,
+            path: track.path.clone(),
+            name: track.track_name.clone(),
+            decibels: track.decibels,
+            trim: track.trim,
+            duration: track.trim.map(|t| t.duration_secs()).unwrap_or(track.duration),
+        }
+    }
+
+    /// 分贝调整对应的音量倍数
+    pub fn gain(&self) -> f32 {
+        10f32.powf(self.decibels as f32 / 20.0)
+    }
+}
+
+/// 后台线程打开音源的结果
+struct OpenResult {
+    /// 成功打开的条目及其音源（剩余轨道都无法解码时为 None）
+    opened: Option<(PlaylistEntry, DecodedSource)>,
+    /// 打开前跳过的条目 (名称, 原因)
+    skipped: Vec<(String, String)>,
+}
+
+/// 播放列表播放器（独立于单轨道预览）
+#[derive(Default)]
+pub struct AudioPlayer {
+    playlist: Vec<PlaylistEntry>,
+    current: Option<usize>,
+    /// 音频输出流（首次播放时打开，必须与 Sink 一同保持存活）
+    output: Option<(OutputStream, OutputStreamHandle)>,
+    sink: Option<Sink>,
+    /// 正在后台解码和定位的音源（打开文件可能较慢，不在界面线程中进行）
+    loading: Option<Receiver<OpenResult>>,
+    /// 因无法解码而跳过的条目 (名称, 原因)
+    skipped: Vec<(String, String)>,
+    /// 音频输出错误
+    error: Option<String>,
+}
+
+impl AudioPlayer {
+    /// 创建播放器（不立即打开音频设备）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 同步播放列表（轨道顺序或分贝变化后调用），当前播放的轨道按轨道 ID 跟随
+    pub fn sync_playlist(&mut self, entries: Vec<PlaylistEntry>) {
+        if entries == self.playlist {
+            return;
+        }
+        let current_id = self.current_entry().map(|entry| entry.track_id);
+        self.playlist = entries;
+        match current_id {
+            Some(id) => match self.playlist.iter().position(|entry| entry.track_id == id) {
+                Some(index) => {
+                    self.current = Some(index);
+                    if let Some(sink) = &self.sink {
+                        sink.set_volume(self.playlist[index].gain());
+                    }
+                }
+                // 当前轨道已被移除
+                None => self.stop(),
+            },
+            None => self.current = None,
+        }
+    }
+
+    /// 播放列表
+    pub fn playlist(&self) -> &[PlaylistEntry] {
+        &self.playlist
+    }
+
+    /// 当前轨道索引
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// 当前轨道
+    pub fn current_entry(&self) -> Option<&PlaylistEntry> {
+        self.current.and_then(|index| self.playlist.get(index))
+    }
+
+    /// 是否正在播放、暂停或加载中
+    pub fn is_active(&self) -> bool {
+        self.sink.is_some() || self.loading.is_some()
+    }
+
+    /// 是否正在后台打开轨道
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
+    /// 是否暂停
+    pub fn is_paused(&self) -> bool {
+        self.sink.as_ref().is_some_and(|sink| sink.is_paused())
+    }
+
+    /// 当前轨道的播放位置
+    pub fn position(&self) -> Duration {
+        self.sink.as_ref().map(|sink| sink.get_pos()).unwrap_or_default()
+    }
+
+    /// 被跳过的条目 (名称, 原因)
+    pub fn skipped(&self) -> &[(String, String)] {
+        &self.skipped
+    }
+
+    /// 音频输出错误
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// 从指定索引开始播放，无法解码的轨道跳过并记录
+    ///
+    /// 解码和定位到裁剪起点在后台线程中进行，打开后由 `update` 开始播放。
+    pub fn play_from(&mut self, index: usize) {
+        self.stop();
+        self.error = None;
+        if index >= self.playlist.len() {
+            self.current = None;
+            return;
+        }
+        self.current = Some(index);
+
+        let entries = self.playlist[index..].to_vec();
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        std::thread::spawn(move || {
+            let mut skipped = Vec::new();
+            let mut opened = None;
+            for entry in entries {
+                match DecodedSource::open(&entry.path, entry.trim) {
+                    Ok(source) => {
+                        opened = Some((entry, source));
+                        break;
+                    }
+                    Err(e) => {
+                        warn!("跳过无法解码的轨道 {:?}: {:#}", entry.path, e);
+                        skipped.push((entry.name, format!("{:#}", e)));
+                    }
+                }
+            }
+            // 播放器已停止或切换轨道时接收端已丢弃，结果直接丢弃
+            let _ = sender.send(OpenResult { opened, skipped });
+        });
+        self.loading = Some(receiver);
+    }
+
+    /// 检查后台打开的音源，打开完成后开始播放
+    fn poll_loading(&mut self) {
+        let Some(receiver) = &self.loading else { return };
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.loading = None;
+                return;
+            }
+        };
+        self.loading = None;
+
+        for (name, reason) in result.skipped {
+            self.skipped.retain(|(skipped_name, _)| *skipped_name != name);
+            self.skipped.push((name, reason));
+        }
+        // 打开期间播放列表可能已变化，按轨道 ID 找到当前位置
+        let Some((index, entry, source)) = result.opened.and_then(|(entry, source)| {
+            self.playlist.iter().position(|e| e.track_id == entry.track_id).map(|index| (index, entry, source))
+        }) else {
+            // 剩余轨道都无法播放
+            self.current = None;
+            return;
+        };
+
+        if self.output.is_none() {
+            match OutputStream::try_default() {
+                Ok(output) => self.output = Some(output),
+                Err(e) => {
+                    self.error = Some(format!("无法打开音频输出设备: {}", e));
+                    return;
+                }
+            }
+        }
+        let Some((_, handle)) = &self.output else { return };
+        let sink = match Sink::try_new(handle) {
+            Ok(sink) => sink,
+            Err(e) => {
+                self.error = Some(format!("无法创建音频输出: {}", e));
+                return;
+            }
+        };
+        self.skipped.retain(|(name, _)| *name != entry.name);
+        sink.set_volume(self.playlist[index].gain());
+        sink.append(source);
+        self.sink = Some(sink);
+        self.current = Some(index);
+    }
+
+    /// 播放/暂停切换（未播放时从当前或第一个轨道开始）
+    pub fn toggle_pause(&mut self) {
+        match &self.sink {
+            Some(sink) if sink.is_paused() => sink.play(),
+            Some(sink) => sink.pause(),
+            None if self.loading.is_some() => {}
+            None => self.play_from(self.current.unwrap_or(0)),
+        }
+    }
+
+    /// 下一首
+    pub fn next(&mut self) {
+        let next = self.current.map(|index| index + 1).unwrap_or(0);
+        if next < self.playlist.len() {
+            self.play_from(next);
+        } else {
+            self.stop();
+        }
+    }
+
+    /// 上一首（播放超过 3 秒时回到当前轨道开头）
+    pub fn previous(&mut self) {
+        let Some(index) = self.current else {
+            return;
+        };
+        if self.position() > Duration::from_secs(3) || index == 0 {
+            self.play_from(index);
+        } else {
+            self.play_from(index - 1);
+        }
+    }
+
+    /// 停止播放（保留当前轨道位置，以便再次从该轨道开始）
+    pub fn stop(&mut self) {
+        self.loading = None;
+        if let Some(sink) = self.sink.take() {
+            sink.stop();
+        }
+    }
+
+    /// 每帧调用：后台打开完成时开始播放，当前轨道播放完毕时自动切换到下一首
+    pub fn update(&mut self) {
+        self.poll_loading();
+        if self.sink.as_ref().is_some_and(|sink| sink.empty()) {
+            self.next();
+        }
+    }
+}
+
+/// 基于 symphonia 的流式解码音源
+struct DecodedSource {
+    stream: AudioStream,
+    samples: Vec<f32>,
+    position: usize,
+    channels: u16,
+    sample_rate: u32,
+    /// 裁剪范围内剩余的采样数（None 表示播放到文件结束）
+    remaining: Option<u64>,
+}
+
+impl DecodedSource {
+    /// 打开文件并解码第一个数据包（确认文件可播放）
+    fn open(path: &Path, trim: Option<TrackTrim>) -> Result<Self> {
+        let mut stream = AudioStream::open(path)?;
+        if let Some(trim) = trim.filter(|t| t.start_secs > 0.0) {
+            stream
+                .seek(trim.start_secs)
+                .with_context(|| format!("无法定位到裁剪起点: {:?}", path))?;
+        }
+
+        let mut source = Self {
+            stream,
+            samples: Vec::new(),
+            position: 0,
+            channels: 2,
+            sample_rate: 44100,
+            remaining: None,
+        };
+        if !source.decode_next() {
+            return Err(anyhow::anyhow!("未解码到任何音频数据: {:?}", path));
+        }
+        if let Some(trim) = trim {
+            let secs = (trim.end_secs - trim.start_secs).max(0.0);
+            source.remaining = Some((secs * source.sample_rate as f64) as u64 * source.channels as u64);
+        }
+        Ok(source)
+    }
+
+    /// 解码下一个数据包到缓冲区，文件结束或出错时返回 false
+    fn decode_next(&mut self) -> bool {
+        match self.stream.next_chunk() {
+            Ok(Some((samples, channels, sample_rate))) => {
+                self.samples.clear();
+                self.samples.extend_from_slice(samples);
+                self.position = 0;
+                self.channels = channels as u16;
+                self.sample_rate = sample_rate;
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                debug!("停止解码: {:#}", e);
+                false
+            }
+        }
+    }
+}
+
+impl Iterator for DecodedSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        if self.position >= self.samples.len() && !self.decode_next() {
+            return None;
+        }
+        let sample = self.samples[self.position];
+        self.position += 1;
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= 1;
+        }
+        Some(sample)
+    }
+}
+
+impl Source for DecodedSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        // 以数据包为帧，声道数或采样率变化时 rodio 会在帧边界重新读取
+        Some(self.samples.len() - self.position)
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, decibels: i32) -> PlaylistEntry {
+        PlaylistEntry {
+            track_id: name.bytes().map(u64::from).sum(),
+            path: PathBuf::from(format!("definitely_missing_{}.ogg", name)),
+            name: name.to_string(),
+            decibels,
+            trim: None,
+            duration: 60,
+        }
+    }
+
+    #[test]
+    fn test_gain_from_decibels() {
+        assert!((entry("a", 0).gain() - 1.0).abs() < 1e-6);
+        assert!((entry("a", -20).gain() - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_play_skips_undecodable_tracks() {
+        let mut player = AudioPlayer::new();
+        player.sync_playlist(vec![entry("a", 0), entry("b", 0)]);
+        player.toggle_pause();
+        let started = std::time::Instant::now();
+        while player.is_loading() && started.elapsed() < Duration::from_secs(10) {
+            std::thread::sleep(Duration::from_millis(10));
+            player.update();
+        }
+        assert!(!player.is_active());
+        assert_eq!(player.current_index(), None);
+        let names: Vec<&str> = player.skipped().iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_sync_playlist_follows_current_duplicate() {
+        let original = entry("a", 0);
+        let copy = PlaylistEntry { track_id: 1000, name: "a (副本)".to_string(), ..original.clone() };
+        let mut player = AudioPlayer::new();
+        player.sync_playlist(vec![original.clone(), copy.clone()]);
+        player.current = Some(1);
+
+        // 在前面插入轨道后，仍指向同一路径的副本而不是原轨道
+        player.sync_playlist(vec![entry("b", 0), original, copy]);
+        assert_eq!(player.current_index(), Some(2));
+    }
+}
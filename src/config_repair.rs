@@ -0,0 +1,370 @@
+/*!
+ * 旧配置修复模块
+ * 解析已导出（或手动编辑过）的音乐模组配置，检测过时的写法并按当前模板格式重写
+ */
+
+use anyhow::{Context, Result};
+use log::info;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::file_ops::{parse_config, split_array, unquote, ConfigClass};
+use crate::models::TrackDefaults;
+use crate::templates::{ConfigTemplateData, TemplateEngine, TrackTemplateData};
+use crate::utils::constants::file_ops::{MAX_DECIBELS, MIN_DECIBELS};
+
+/// 轨道列表文件名（当前格式由 CfgMusic 通过 #include 引用）
+const TRACKS_FILE: &str = "FileListWithMusicTracks.hpp";
+
+/// CfgPatches 中当前模板会写出的字段
+const KNOWN_PATCH_FIELDS: [&str; 7] = ["name", "author", "requiredVersion", "requiredAddons[]", "units[]", "weapons[]", "worlds[]"];
+
+/// 轨道类中当前模板会写出的字段
+const KNOWN_TRACK_FIELDS: [&str; 4] = ["name", "sound[]", "duration", "musicClass"];
+
+/// 从旧配置中提取的轨道
+#[derive(Debug, Clone, PartialEq)]
+struct RepairedTrack {
+    class_name: String,
+    name: String,
+    path: String,
+    decibels: i32,
+    duration: u32,
+    /// 模板之外的自定义字段
+    extra_entries: Vec<(String, String)>,
+}
+
+/// 修复结果报告
+#[derive(Debug, Default)]
+pub struct RepairReport {
+    /// 发现并修复的问题
+    pub changes: Vec<String>,
+    /// 无法保留的自定义内容
+    pub dropped: Vec<String>,
+    /// 原文件的备份
+    pub backups: Vec<PathBuf>,
+    /// 识别出的轨道数
+    pub track_count: usize,
+}
+
+impl RepairReport {
+    /// 报告的显示文本
+    pub fn summary(&self) -> String {
+        if self.changes.is_empty() {
+            return format!("未发现过时的配置写法（{} 个轨道），无需修复", self.track_count);
+        }
+        let mut text = format!("已修复 {} 处过时写法（{} 个轨道）:\n", self.changes.len(), self.track_count);
+        for change in &self.changes {
+            text.push_str(&format!("  • {}\n", change));
+        }
+        if !self.dropped.is_empty() {
+            text.push_str("\n以下自定义内容无法保留:\n");
+            for dropped in &self.dropped {
+                text.push_str(&format!("  • {}\n", dropped));
+            }
+        }
+        text.push_str("\n原文件已备份:\n");
+        for backup in &self.backups {
+            text.push_str(&format!("  {}\n", backup.display()));
+        }
+        text
+    }
+}
+
+/// 修复模组目录中的 config.cpp（及 FileListWithMusicTracks.hpp）
+///
+/// 无法解析时返回错误且不修改任何文件。
+pub fn repair_mod_config(mod_dir: &Path, defaults: &TrackDefaults) -> Result<RepairReport> {
+    let config_path = mod_dir.join("config.cpp");
+    let config_text = fs::read_to_string(&config_path)
+        .with_context(|| format!("无法读取配置文件: {:?}", config_path))?;
+    let config = parse_config(&config_text).with_context(|| format!("无法解析配置文件: {:?}", config_path))?;
+
+    let tracks_path = mod_dir.join(TRACKS_FILE);
+    let tracks_text = fs::read_to_string(&tracks_path).ok();
+    let tracks_config = match &tracks_text {
+        Some(text) => Some(parse_config(text).with_context(|| format!("无法解析轨道列表: {:?}", tracks_path))?),
+        None => None,
+    };
+
+    let plan = plan_repair(&config_text, &config, tracks_text.as_deref(), tracks_config.as_ref(), defaults)?;
+    let mut report = RepairReport {
+        changes: plan.changes.clone(),
+        dropped: plan.dropped.clone(),
+        track_count: plan.tracks.len(),
+        ..RepairReport::default()
+    };
+    if plan.changes.is_empty() {
+        return Ok(report);
+    }
+
+    // 先备份原文件，再重写
+    report.backups.push(backup_file(&config_path)?);
+    if tracks_text.is_some() {
+        report.backups.push(backup_file(&tracks_path)?);
+    }
+
+    let engine = TemplateEngine::new()?;
+    let mut config_content = engine.render_config(&plan.config)?;
+    for class in &plan.extra_classes {
+        config_content.push_str(&class.to_text(0));
+    }
+    TemplateEngine::write_arma_config_file(&config_path, &config_content)?;
+
+    let mut tracks_content = String::new();
+    for track in &plan.tracks {
+        let data = TrackTemplateData {
+            track_class: track.class_name.clone(),
+            track_name: track.name.clone(),
+            track_path: track.path.clone(),
+            decibels: format_decibels(track.decibels),
+            duration: track.duration,
+            class_name: plan.config.class_name.clone(),
+        };
+        let mut rendered = engine.render_track(&data)?;
+        insert_extra_entries(&mut rendered, &track.extra_entries);
+        tracks_content.push_str(&rendered);
+        tracks_content.push('\n');
+    }
+    TemplateEngine::write_arma_config_file(&tracks_path, &tracks_content)?;
+
+    info!("旧配置修复完成: {:?}，{} 处修改", mod_dir, report.changes.len());
+    Ok(report)
+}
+
+/// 修复计划（纯数据，便于测试）
+#[derive(Debug)]
+struct RepairPlan {
+    config: ConfigTemplateData,
+    tracks: Vec<RepairedTrack>,
+    extra_classes: Vec<ConfigClass>,
+    changes: Vec<String>,
+    dropped: Vec<String>,
+}
+
+/// 检测过时写法并生成修复计划
+fn plan_repair(
+    config_text: &str,
+    config: &ConfigClass,
+    tracks_text: Option<&str>,
+    tracks_config: Option<&ConfigClass>,
+    defaults: &TrackDefaults,
+) -> Result<RepairPlan> {
+    let mut changes = Vec::new();
+    let mut dropped = Vec::new();
+
+    let patches = config.class("CfgPatches").ok_or_else(|| anyhow::anyhow!("未找到 CfgPatches，无法识别为模组配置"))?;
+    let patch = patches.classes.first().ok_or_else(|| anyhow::anyhow!("CfgPatches 中没有任何类"))?;
+    let cfg_music = config.class("CfgMusic").ok_or_else(|| anyhow::anyhow!("未找到 CfgMusic，不是音乐模组配置"))?;
+
+    for field in ["requiredVersion", "requiredAddons[]", "units[]", "weapons[]", "worlds[]"] {
+        if patch.entry(field).is_none() {
+            changes.push(format!("CfgPatches 缺少 {}", field));
+        }
+    }
+    for (key, value) in &patch.entries {
+        let is_default_list = value.replace(char::is_whitespace, "") == "{}";
+        if !KNOWN_PATCH_FIELDS.iter().any(|known| known.eq_ignore_ascii_case(key)) {
+            dropped.push(format!("CfgPatches 字段 {} = {}", key, value));
+        } else if key.ends_with("[]") && !is_default_list {
+            dropped.push(format!("CfgPatches 字段 {} = {}（当前模板为空列表）", key, value));
+        }
+    }
+    if has_lf_only_line_endings(config_text) || tracks_text.map(has_lf_only_line_endings).unwrap_or(false) {
+        changes.push("使用 LF 换行（当前格式为 CRLF）".to_string());
+    }
+
+    // 轨道：当前格式放在 FileListWithMusicTracks.hpp，旧写法直接写在 CfgMusic 中
+    let mut track_classes: Vec<&ConfigClass> = cfg_music.classes.iter().collect();
+    if !track_classes.is_empty() {
+        changes.push(format!("{} 个轨道直接写在 CfgMusic 中（当前格式使用 #include \"{}\"）", track_classes.len(), TRACKS_FILE));
+    }
+    let includes_tracks = cfg_music.includes.iter().any(|include| include.eq_ignore_ascii_case(TRACKS_FILE));
+    if includes_tracks {
+        let tracks_config = tracks_config.ok_or_else(|| anyhow::anyhow!("config.cpp 引用的 {} 不存在", TRACKS_FILE))?;
+        track_classes.extend(tracks_config.classes.iter());
+    }
+
+    // 音乐分类
+    let (class_name, music_class_name) = match config.class("CfgMusicClasses").and_then(|c| c.classes.first()) {
+        Some(music_class) => (
+            music_class.name.clone(),
+            music_class.entry("displayName").map(unquote).unwrap_or_else(|| music_class.name.clone()),
+        ),
+        None => {
+            let class_name = track_classes
+                .iter()
+                .find_map(|track| track.entry("musicClass").map(unquote))
+                .unwrap_or_else(|| patch.name.clone());
+            changes.push(format!("缺少 CfgMusicClasses，已补充音乐分类 {}", class_name));
+            (class_name.clone(), class_name)
+        }
+    };
+
+    let mut tracks = Vec::new();
+    for track_class in track_classes {
+        tracks.push(repair_track(track_class, &class_name, defaults, &mut changes)?);
+    }
+
+    // 模板之外的顶层类原样保留
+    let extra_classes = config
+        .classes
+        .iter()
+        .filter(|class| !["CfgPatches", "CfgMusic", "CfgMusicClasses"].iter().any(|known| known.eq_ignore_ascii_case(&class.name)))
+        .cloned()
+        .collect();
+
+    Ok(RepairPlan {
+        config: ConfigTemplateData {
+            mod_name_no_spaces: patch.name.clone(),
+            mod_name: patch.entry("name").map(unquote).unwrap_or_else(|| patch.name.clone()),
+            author_name: patch.entry("author").map(unquote).unwrap_or_default(),
+            class_name,
+            music_class_name,
+        },
+        tracks,
+        extra_classes,
+        changes,
+        dropped,
+    })
+}
+
+/// 检查并修复单个轨道类
+fn repair_track(track: &ConfigClass, class_name: &str, defaults: &TrackDefaults, changes: &mut Vec<String>) -> Result<RepairedTrack> {
+    let sound = track
+        .entry("sound[]")
+        .ok_or_else(|| anyhow::anyhow!("轨道 {} 缺少 sound[]", track.name))?;
+    let parts = split_array(sound);
+    let raw_path = parts.first().map(|p| unquote(p)).unwrap_or_default();
+    if raw_path.is_empty() {
+        return Err(anyhow::anyhow!("轨道 {} 的 sound[] 没有文件路径", track.name));
+    }
+
+    // 路径统一为反斜杠且不带前导反斜杠
+    let path = raw_path.replace('/', "\\").trim_start_matches('\\').to_string();
+    if path != raw_path {
+        changes.push(format!("轨道 {} 的路径 {} 已规范为 {}", track.name, raw_path, path));
+    }
+
+    let volume = parts.get(1).map(|v| v.replace(char::is_whitespace, "")).unwrap_or_default();
+    let decibels = match volume.strip_prefix("db") {
+        Some(db) => db.parse::<i32>().unwrap_or(defaults.decibels),
+        None => {
+            // 旧写法使用线性音量（1 = 0dB）
+            let db = match volume.parse::<f64>() {
+                Ok(linear) if linear > 0.0 => (20.0 * linear.log10()).round() as i32,
+                _ => defaults.decibels,
+            };
+            let db = db.clamp(MIN_DECIBELS, MAX_DECIBELS);
+            changes.push(format!("轨道 {} 的音量 {} 使用数值写法，已转换为 db{}", track.name, volume, format_decibels(db)));
+            db
+        }
+    };
+
+    let duration = match track.entry("duration").and_then(|d| d.trim().parse::<f64>().ok()) {
+        Some(duration) => duration.round().max(0.0) as u32,
+        None => {
+            changes.push(format!("轨道 {} 缺少 duration，使用默认 {} 秒", track.name, defaults.duration));
+            defaults.duration
+        }
+    };
+
+    match track.entry("musicClass").map(unquote) {
+        Some(music_class) if music_class == class_name => {}
+        Some(music_class) => changes.push(format!("轨道 {} 的 musicClass {} 与音乐分类不一致，已改为 {}", track.name, music_class, class_name)),
+        None => changes.push(format!("轨道 {} 缺少 musicClass", track.name)),
+    }
+
+    let name = match track.entry("name") {
+        Some(name) => unquote(name),
+        None => {
+            changes.push(format!("轨道 {} 缺少 name，使用类名", track.name));
+            track.name.clone()
+        }
+    };
+
+    let extra_entries = track
+        .entries
+        .iter()
+        .filter(|(key, _)| !KNOWN_TRACK_FIELDS.iter().any(|known| known.eq_ignore_ascii_case(key)))
+        .cloned()
+        .collect();
+
+    Ok(RepairedTrack {
+        class_name: track.name.clone(),
+        name,
+        path,
+        decibels,
+        duration,
+        extra_entries,
+    })
+}
+
+/// 备份文件为 `<文件名>.bak-<时间戳>`
+fn backup_file(path: &Path) -> Result<PathBuf> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let backup_path = path.with_file_name(format!("{}.bak-{}", file_name, timestamp));
+    fs::copy(path, &backup_path)
+        .with_context(|| format!("无法备份文件: {:?} -> {:?}", path, backup_path))?;
+    Ok(backup_path)
+}
+
+/// 在渲染好的轨道类结尾 `};` 之前插入自定义字段
+fn insert_extra_entries(rendered: &mut String, extra_entries: &[(String, String)]) {
+    if extra_entries.is_empty() {
+        return;
+    }
+    if let Some(end) = rendered.rfind("};") {
+        let extra: String = extra_entries
+            .iter()
+            .map(|(key, value)| format!("    {} = {};\n", key, value))
+            .collect();
+        rendered.insert_str(end, &extra);
+    }
+}
+
+fn format_decibels(decibels: i32) -> String {
+    if decibels >= 0 {
+        format!("+{}", decibels)
+    } else {
+        decibels.to_string()
+    }
+}
+
+fn has_lf_only_line_endings(text: &str) -> bool {
+    text.contains('\n') && !text.contains("\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD_CONFIG: &str = "// 旧版导出\nclass CfgPatches\n{\n    class MyMod\n    {\n        name = \"My Mod\";\n        author = \"Me\";\n        requiredAddons[] = {};\n    };\n};\nclass CfgMusic\n{\n    class MyModSong0\n    {\n        name = \"Song\";\n        sound[] = {\"/MyMod/folderwithtracks/song.ogg\", 0.5, 1};\n        titles[] = {};\n    };\n};\n";
+
+    #[test]
+    fn test_plan_repair_detects_outdated_patterns() {
+        let config = parse_config(OLD_CONFIG).unwrap();
+        let defaults = TrackDefaults::default();
+        let plan = plan_repair(OLD_CONFIG, &config, None, None, &defaults).unwrap();
+
+        let track = &plan.tracks[0];
+        assert_eq!(track.path, "MyMod\\folderwithtracks\\song.ogg");
+        assert_eq!(track.decibels, -6);
+        assert_eq!(track.duration, defaults.duration);
+        assert_eq!(track.extra_entries, vec![("titles[]".to_string(), "{}".to_string())]);
+        assert_eq!(plan.config.class_name, "MyMod");
+        assert!(plan.changes.iter().any(|c| c.contains("直接写在 CfgMusic")));
+        assert!(plan.changes.iter().any(|c| c.contains("LF")));
+    }
+
+    #[test]
+    fn test_insert_extra_entries_before_class_end() {
+        let mut rendered = "class A\n{\n    name = \"a\";\n};".to_string();
+        insert_extra_entries(&mut rendered, &[("titles[]".to_string(), "{}".to_string())]);
+        assert_eq!(rendered, "class A\n{\n    name = \"a\";\n    titles[] = {};\n};");
+    }
+}
@@ -2,109 +2,162 @@ use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
 use std::fs;
 use log::{info, warn};
-use reqwest;
-use indicatif::{ProgressBar, ProgressStyle};
+use crate::ffmpeg_plugin::MirrorSource;
+use crate::http_client::{DownloadProgress, HttpClient, HttpSettings, Mirror};
+
+/// FFmpeg 发布页地址
+const FFMPEG_RELEASE_BASE: &str = "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/";
+/// FFmpeg 压缩包文件名
+const FFMPEG_ARCHIVE_NAME: &str = "ffmpeg-master-latest-win64-gpl.zip";
+/// 校验和清单文件名
+const FFMPEG_CHECKSUMS_NAME: &str = "checksums.sha256";
 
 /// FFmpeg 下载器
 pub struct FFmpegDownloader {
-    download_url: String,
     output_path: PathBuf,
+    http_settings: HttpSettings,
+    /// 用户选择的镜像源（作为第一个尝试的镜像）
+    mirror_source: MirrorSource,
+    /// 首先尝试的镜像序号（重试时切换到下一个镜像）
+    start_mirror: usize,
 }
 
 impl FFmpegDownloader {
     /// 创建新的下载器实例
-    pub fn new(output_dir: &Path) -> Self {
-        // 使用最佳下载源（优先中国镜像）
-        let download_url = Self::get_best_download_url();
+    pub fn new(output_dir: &Path, http_settings: HttpSettings) -> Self {
         let output_path = output_dir.join("ffmpeg.exe");
         
         Self {
-            download_url,
             output_path,
+            http_settings,
+            mirror_source: MirrorSource::default(),
+            start_mirror: 0,
         }
     }
 
-    /// 获取最佳下载URL（支持多个镜像源）
-    fn get_best_download_url() -> String {
-        // 优先使用中国友好的镜像源
-        let urls = Self::get_all_download_urls();
-        
-        // 返回第一个URL（GitHub代理镜像2，最稳定）
-        // 虽然 get_all_download_urls() 总是返回至少4个URL，但为了健壮性添加检查
-        if let Some(url) = urls.first() {
-            info!("使用下载源: {}", url);
-            url.clone()
-        } else {
-            // 如果意外为空，返回默认URL（不应该发生）
-            warn!("下载URL列表为空，使用默认URL");
-            "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-win64-gpl.zip".to_string()
+    /// 设置首先尝试的镜像源，其余镜像作为备用
+    pub fn with_mirror_source(mut self, mirror_source: MirrorSource) -> Self {
+        self.mirror_source = mirror_source;
+        self
+    }
+
+    /// 设置首先尝试的镜像序号，其余镜像按原顺序依次作为备用
+    pub fn with_start_mirror(mut self, start_mirror: usize) -> Self {
+        self.start_mirror = start_mirror;
+        self
+    }
+
+    /// 下载镜像名称（按选择的镜像源排列的尝试顺序）
+    pub fn mirror_names(mirror_source: &MirrorSource) -> Vec<String> {
+        Self::get_mirrors(FFMPEG_ARCHIVE_NAME, mirror_source)
+            .into_iter()
+            .map(|mirror| mirror.name)
+            .collect()
+    }
+
+    /// 从指定序号开始轮换镜像顺序
+    fn rotated_mirrors(&self, file_name: &str) -> Vec<Mirror> {
+        let mut mirrors = Self::get_mirrors(file_name, &self.mirror_source);
+        if !mirrors.is_empty() {
+            let start = self.start_mirror % mirrors.len();
+            mirrors.rotate_left(start);
         }
+        mirrors
     }
 
-    /// 获取所有可用的下载URL
-    fn get_all_download_urls() -> Vec<String> {
-        vec![
+    /// 获取指定发布文件的所有镜像：选择的镜像源排在最前，其余按默认顺序（优先中国镜像）作为备用
+    ///
+    /// 自定义镜像为代理地址前缀（如 `https://mirror.example.com/`），地址中包含 `{url}` 时替换为 GitHub 原始地址。
+    fn get_mirrors(file_name: &str, mirror_source: &MirrorSource) -> Vec<Mirror> {
+        let url = format!("{}{}", FFMPEG_RELEASE_BASE, file_name);
+        let mut mirrors = vec![
             // GitHub代理镜像2（推荐，最稳定）
-            "https://ghproxy.net/https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-win64-gpl.zip".to_string(),
+            Mirror::new("GitHub代理镜像2 (推荐)", format!("https://ghproxy.net/{}", url)),
             // GitHub官方（备用）
-            "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-win64-gpl.zip".to_string(),
+            Mirror::new("GitHub官方", url.clone()),
             // GitHub代理镜像1（备用2）
-            "https://ghproxy.com/https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-win64-gpl.zip".to_string(),
+            Mirror::new("GitHub代理镜像1", format!("https://ghproxy.com/{}", url)),
             // GitHub代理镜像3（最后备用）
-            "https://mirror.ghproxy.com/https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-win64-gpl.zip".to_string(),
-        ]
+            Mirror::new("GitHub代理镜像3", format!("https://mirror.ghproxy.com/{}", url)),
+        ];
+
+        match mirror_source {
+            MirrorSource::GitHubProxy => {}
+            MirrorSource::GitHub => {
+                if let Some(index) = mirrors.iter().position(|mirror| mirror.url == url) {
+                    let official = mirrors.remove(index);
+                    mirrors.insert(0, official);
+                }
+            }
+            MirrorSource::Custom(prefix) => {
+                let prefix = prefix.trim();
+                if !prefix.is_empty() {
+                    let custom_url = if prefix.contains("{url}") {
+                        prefix.replace("{url}", &url)
+                    } else {
+                        format!("{}/{}", prefix.trim_end_matches('/'), url)
+                    };
+                    mirrors.insert(0, Mirror::new("自定义镜像", custom_url));
+                }
+            }
+        }
+        mirrors
     }
 
-    /// 智能下载 FFmpeg（支持多源自动切换）
-    pub async fn download_ffmpeg_with_fallback<F>(
+    /// 智能下载 FFmpeg（支持多源自动切换、断点续传和校验）
+    ///
+    /// `should_cancel` 在读取每个数据块之间检查，返回 true 时中止下载并删除未完成的文件。
+    pub async fn download_ffmpeg_with_fallback<F, C>(
         &self,
         progress_callback: F,
+        should_cancel: C,
     ) -> Result<PathBuf>
     where
-        F: Fn(f64, &str) -> Result<()>,
+        F: Fn(DownloadProgress, &str) -> Result<()>,
+        C: Fn() -> bool,
     {
-        let urls = Self::get_all_download_urls();
-        
-        for (index, url) in urls.iter().enumerate() {
-            let source_name = match index {
-                0 => "GitHub代理镜像2 (推荐)",
-                1 => "GitHub官方", 
-                2 => "GitHub代理镜像1",
-                3 => "GitHub代理镜像3",
-                _ => "未知源",
-            };
-            
-            info!("尝试从 {} 下载 FFmpeg: {}", source_name, url);
-            
-            // 发送初始进度
-            if let Err(e) = progress_callback(0.0, &format!("正在连接 {}...", source_name)) {
-                warn!("发送初始进度失败: {}", e);
-            }
-            
-            // 创建临时下载器尝试下载
-            let temp_downloader = FFmpegDownloader {
-                download_url: url.clone(),
-                output_path: self.output_path.clone(),
-            };
-            
-            match temp_downloader.download_ffmpeg(&progress_callback).await {
-                Ok(path) => {
-                    info!("从 {} 下载成功", source_name);
-                    return Ok(path);
-                }
-                Err(e) => {
-                    warn!("从 {} 下载失败: {}", source_name, e);
-                    if index < urls.len() - 1 {
-                        info!("尝试下一个下载源...");
-                        if let Err(e) = progress_callback(0.0, &format!("{} 失败，尝试下一个源...", source_name)) {
-                            warn!("发送进度更新失败: {}", e);
-                        }
-                    }
-                }
+        info!("开始下载 FFmpeg...");
+        let client = HttpClient::new(&self.http_settings)?;
+        
+        // 获取校验和（失败时跳过校验，不影响下载）
+        let expected_sha256 = match client.get_text_with_mirrors(&self.rotated_mirrors(FFMPEG_CHECKSUMS_NAME)).await {
+            Ok((_, checksums)) => HttpClient::find_checksum(&checksums, FFMPEG_ARCHIVE_NAME),
+            Err(e) => {
+                warn!("无法获取 FFmpeg 校验和，跳过校验: {}", e);
+                None
             }
+        };
+        
+        let zip_path = self.output_path.with_extension("zip");
+        client.download_with_mirrors(
+            &self.rotated_mirrors(FFMPEG_ARCHIVE_NAME),
+            &zip_path,
+            expected_sha256.as_deref(),
+            &progress_callback,
+            &should_cancel,
+        ).await?;
+        if should_cancel() {
+            let _ = fs::remove_file(&zip_path);
+            return Err(anyhow::Error::new(crate::http_client::DownloadCancelled));
         }
         
-        Err(anyhow::anyhow!("所有下载源都失败了，请检查网络连接或手动下载 FFmpeg"))
+        // 解压文件
+        info!("开始解压 FFmpeg...");
+        if let Err(e) = progress_callback(DownloadProgress::finished(0, 0), "正在解压 FFmpeg...") {
+            warn!("发送进度更新失败: {}", e);
+        }
+        self.extract_ffmpeg(&zip_path)?;
+        
+        // 删除压缩包
+        fs::remove_file(&zip_path)?;
+        
+        // 验证下载的文件
+        if Self::is_ffmpeg_available(&self.output_path) {
+            info!("FFmpeg 下载并验证成功: {:?}", self.output_path);
+            Ok(self.output_path.clone())
+        } else {
+            Err(anyhow::anyhow!("下载的 FFmpeg 文件无效"))
+        }
     }
     
     /// 获取用户工作空间目录
@@ -136,10 +189,10 @@ impl FFmpegDownloader {
     
 
     /// 创建用户工作空间下载器（支持多源）
-    pub fn new_user_workspace_with_fallback() -> Result<Self> {
+    pub fn new_user_workspace_with_fallback(http_settings: HttpSettings) -> Result<Self> {
         let ffmpeg_dir = Self::get_ffmpeg_directory()?;
         info!("FFmpeg 将下载到: {:?} (支持多源)", ffmpeg_dir);
-        Ok(Self::new(&ffmpeg_dir))
+        Ok(Self::new(&ffmpeg_dir, http_settings))
     }
     
     /// 检查 FFmpeg 是否已存在且可用
@@ -166,124 +219,6 @@ impl FFmpegDownloader {
         result.map(|status| status.success()).unwrap_or(false)
     }
     
-    /// 下载 FFmpeg
-    pub async fn download_ffmpeg<F>(
-        &self,
-        progress_callback: F,
-    ) -> Result<PathBuf>
-    where
-        F: Fn(f64, &str) -> Result<()>,
-    {
-        info!("开始下载 FFmpeg...");
-        
-        // 创建输出目录
-        if let Some(parent) = self.output_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        
-        // 创建 HTTP 客户端
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(300)) // 5分钟超时
-            .connect_timeout(std::time::Duration::from_secs(30)) // 30秒连接超时
-            .tcp_keepalive(std::time::Duration::from_secs(60)) // TCP保活
-            .pool_max_idle_per_host(10) // 连接池优化
-            .build()?;
-        
-        // 发送请求获取文件大小
-        let response = client.head(&self.download_url).send().await?;
-        let total_size = response.headers()
-            .get("content-length")
-            .and_then(|ct_len| ct_len.to_str().ok())
-            .and_then(|ct_len| ct_len.parse::<u64>().ok())
-            .unwrap_or(0);
-        
-        info!("FFmpeg 文件大小: {} bytes", total_size);
-        
-        // 下载文件
-        let mut response = client.get(&self.download_url).send().await?;
-        
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!("下载失败: HTTP {}", response.status()));
-        }
-        
-        // 创建临时文件
-        let temp_path = self.output_path.with_extension("tmp");
-        let file = fs::File::create(&temp_path)?;
-        let mut downloaded: u64 = 0;
-        
-        // 创建进度条
-        let progress_bar = if total_size > 0 {
-            ProgressBar::new(total_size)
-        } else {
-            ProgressBar::new_spinner()
-        };
-        
-        progress_bar.set_style(
-            ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
-                .unwrap()
-                .progress_chars("#>-"),
-        );
-        
-        // 发送初始进度
-        if let Err(e) = progress_callback(0.0, "开始下载...") {
-            warn!("发送初始进度失败: {}", e);
-        }
-        
-        // 下载数据块
-        let mut chunk_count = 0;
-        // 使用缓冲写入以提高I/O效率
-        use std::io::{BufWriter, Write};
-        let mut writer = BufWriter::with_capacity(64 * 1024, file); // 64KB 缓冲区
-        
-        while let Some(chunk) = response.chunk().await? {
-            writer.write_all(&chunk)?;
-            downloaded += chunk.len() as u64;
-            chunk_count += 1;
-            
-            // 更新进度
-            progress_bar.set_position(downloaded);
-            
-            // 每下载 100KB 或每 10 个块调用一次回调，提供更频繁的进度更新
-            if chunk_count % 10 == 0 || downloaded % (100 * 1024) == 0 {
-                let progress = if total_size > 0 {
-                    (downloaded as f64 / total_size as f64) * 100.0
-                } else {
-                    0.0
-                };
-                
-                let status = if total_size > 0 {
-                    format!("下载中... {:.1}% ({}/{} bytes)", progress, downloaded, total_size)
-                } else {
-                    format!("下载中... {} bytes", downloaded)
-                };
-                
-                if let Err(e) = progress_callback(progress, &status) {
-                    warn!("进度回调失败: {}", e);
-                }
-            }
-        }
-        
-        // 确保所有数据都写入文件
-        writer.flush()?;
-        progress_bar.finish_with_message("下载完成");
-        
-        // 解压文件
-        info!("开始解压 FFmpeg...");
-        self.extract_ffmpeg(&temp_path)?;
-        
-        // 删除临时文件
-        fs::remove_file(&temp_path)?;
-        
-        // 验证下载的文件
-        if Self::is_ffmpeg_available(&self.output_path) {
-            info!("FFmpeg 下载并验证成功: {:?}", self.output_path);
-            Ok(self.output_path.clone())
-        } else {
-            Err(anyhow::anyhow!("下载的 FFmpeg 文件无效"))
-        }
-    }
-    
     /// 解压 FFmpeg 文件
     fn extract_ffmpeg(&self, zip_path: &Path) -> Result<()> {
         use std::io::Read;
@@ -364,4 +299,23 @@ pub struct FFmpegInfo {
     pub features: Vec<String>,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selected_mirror_source_is_tried_first() {
+        let proxy = FFmpegDownloader::mirror_names(&MirrorSource::GitHubProxy);
+        assert_eq!(proxy[0], "GitHub代理镜像2 (推荐)");
+
+        let github = FFmpegDownloader::mirror_names(&MirrorSource::GitHub);
+        assert_eq!(github[0], "GitHub官方");
+        assert_eq!(github.len(), proxy.len());
 
+        let custom = FFmpegDownloader::get_mirrors(FFMPEG_ARCHIVE_NAME, &MirrorSource::Custom("https://mirror.example.com/".to_string()));
+        assert_eq!(custom[0].url, format!("https://mirror.example.com/{}{}", FFMPEG_RELEASE_BASE, FFMPEG_ARCHIVE_NAME));
+        assert_eq!(custom.len(), proxy.len() + 1);
+        // 未填写地址的自定义镜像源使用默认顺序
+        assert_eq!(FFmpegDownloader::mirror_names(&MirrorSource::Custom(" ".to_string())), proxy);
+    }
+}
@@ -33,7 +33,19 @@ pub enum MirrorSource {
 
 impl Default for MirrorSource {
     fn default() -> Self {
-        MirrorSource::GitHub
+        // 国内直连 GitHub 经常失败，默认先尝试代理镜像（应用配置和下载器都使用此默认值）
+        MirrorSource::GitHubProxy
+    }
+}
+
+impl MirrorSource {
+    /// 下载对话框中显示的名称
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            MirrorSource::GitHub => "GitHub官方",
+            MirrorSource::GitHubProxy => "GitHub代理镜像（推荐）",
+            MirrorSource::Custom(_) => "自定义镜像",
+        }
     }
 }
 
@@ -332,13 +344,13 @@ mod tests {
     fn test_ffmpeg_config_default() {
         let config = FFmpegConfig::default(PathBuf::from("test.json"));
         assert_eq!(config.auto_download, true);
-        assert_eq!(config.mirror_source, MirrorSource::GitHub);
+        assert_eq!(config.mirror_source, MirrorSource::GitHubProxy);
         assert!(config.ffmpeg_path.is_none());
     }
 
     #[test]
     fn test_mirror_source_enum() {
-        assert_eq!(MirrorSource::default(), MirrorSource::GitHub);
+        assert_eq!(MirrorSource::default(), MirrorSource::GitHubProxy);
         assert_eq!(MirrorSource::GitHub, MirrorSource::GitHub);
         assert_eq!(MirrorSource::GitHubProxy, MirrorSource::GitHubProxy);
         assert_eq!(MirrorSource::Custom("test".to_string()), MirrorSource::Custom("test".to_string()));
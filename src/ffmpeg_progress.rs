@@ -0,0 +1,126 @@
+/*!
+ * FFmpeg 进度解析模块
+ * 读取 `-progress pipe:1` 输出，结合总时长计算单个文件的转换进度
+ */
+
+use anyhow::{anyhow, Context, Result};
+use std::io::{BufRead, BufReader};
+use std::process::{Child, ExitStatus};
+use std::sync::Mutex;
+
+/// 启用机器可读进度输出的 FFmpeg 参数（需放在输出文件之前）
+pub const PROGRESS_ARGS: [&str; 3] = ["-progress", "pipe:1", "-nostats"];
+
+/// 等待 FFmpeg 进程结束，期间解析进度并检查取消
+///
+/// 总时长从 stderr 的 `Duration:` 行读取；未读到时不报告进度。
+/// 返回退出状态和 stderr 全文（用于错误信息）。取消时终止进程并返回错误。
+pub fn wait_with_progress<F>(
+    child: &mut Child,
+    should_cancel: &F,
+    on_progress: &(dyn Fn(f32) + Sync),
+) -> Result<(ExitStatus, String)>
+where
+    F: Fn() -> bool + ?Sized,
+{
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let total: Mutex<Option<f64>> = Mutex::new(None);
+
+    std::thread::scope(|scope| {
+        // stderr 必须持续读取，否则管道写满会使 FFmpeg 阻塞
+        let stderr_reader = scope.spawn(|| {
+            let mut text = String::new();
+            let Some(stderr) = stderr else { return text };
+            for line in BufReader::new(stderr).lines().map_while(|line| line.ok()) {
+                if let Some(duration) = parse_duration_line(&line) {
+                    let mut total = total.lock().unwrap_or_else(|e| e.into_inner());
+                    if total.is_none() && duration > 0.0 {
+                        *total = Some(duration);
+                    }
+                }
+                text.push_str(&line);
+                text.push('\n');
+            }
+            text
+        });
+        scope.spawn(|| {
+            let Some(stdout) = stdout else { return };
+            for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+                let Some(seconds) = parse_progress_line(&line) else { continue };
+                let total = *total.lock().unwrap_or_else(|e| e.into_inner());
+                if let Some(total) = total {
+                    on_progress((seconds / total).clamp(0.0, 1.0) as f32);
+                }
+            }
+        });
+
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) => {
+                    if should_cancel() {
+                        // 终止进程后管道关闭，读取线程随之结束
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        break Err(anyhow!("转换任务被取消"));
+                    }
+                    std::thread::sleep(std::time::Duration::from_millis(50));
+                }
+                Err(e) => break Err(e).context("FFmpeg 执行失败"),
+            }
+        };
+        let stderr_text = stderr_reader.join().unwrap_or_default();
+        status.map(|status| (status, stderr_text))
+    })
+}
+
+/// 解析进度行中已输出的时长（秒），如 `out_time_us=1234567` 或 `out_time=00:00:01.234567`
+pub fn parse_progress_line(line: &str) -> Option<f64> {
+    let (key, value) = line.trim().split_once('=')?;
+    match key {
+        // 旧版 FFmpeg 的 out_time_ms 实际单位也是微秒
+        "out_time_us" | "out_time_ms" => value.parse::<i64>().ok().filter(|us| *us >= 0).map(|us| us as f64 / 1_000_000.0),
+        "out_time" => parse_timestamp(value),
+        _ => None,
+    }
+}
+
+/// 解析 stderr 中输入文件的总时长，如 `  Duration: 00:03:21.05, start: 0.000000, bitrate: 320 kb/s`
+pub fn parse_duration_line(line: &str) -> Option<f64> {
+    let rest = line.trim_start().strip_prefix("Duration:")?;
+    parse_timestamp(rest.split(',').next()?.trim())
+}
+
+/// 解析 `HH:MM:SS.frac` 格式的时间戳
+fn parse_timestamp(value: &str) -> Option<f64> {
+    let mut parts = value.trim().split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || hours < 0.0 {
+        return None;
+    }
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_progress_line() {
+        assert_eq!(parse_progress_line("out_time_us=1500000"), Some(1.5));
+        assert_eq!(parse_progress_line("out_time_ms=2000000"), Some(2.0));
+        assert_eq!(parse_progress_line("out_time=00:01:02.500000"), Some(62.5));
+        assert_eq!(parse_progress_line("out_time=N/A"), None);
+        assert_eq!(parse_progress_line("progress=continue"), None);
+    }
+
+    #[test]
+    fn test_parse_duration_line() {
+        let line = "  Duration: 00:03:21.05, start: 0.000000, bitrate: 320 kb/s";
+        assert!((parse_duration_line(line).unwrap() - 201.05).abs() < 1e-9);
+        assert_eq!(parse_duration_line("  Duration: N/A, bitrate: N/A"), None);
+    }
+}
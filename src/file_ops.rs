@@ -4,36 +4,72 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::audio::AudioProcessor;
-use crate::models::{ProjectSettings, Track, VideoFile};
+use crate::audio_converter::AudioConverter;
+use crate::models::{ExportManifest, ExportManifestEntry, ExportSettings, ModExportJob, ModExportOutcome, ModType, NamingScheme, ProjectSettings, Track, TrackDefaults, VideoFile};
 use crate::video_converter::VideoConverter;
 use crate::utils::{FileUtils, StringUtils};
 use crate::utils::constants::file_ops;
 
+/// 导出目录与源文件目录的重叠关系
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportDirOverlap {
+    /// 源文件目录就是模组输出目录或位于其中，导出会覆盖源文件
+    SourceInsideOutput(PathBuf),
+    /// 选择的导出目录就是源文件所在目录，导出会写入正在读取的文件夹
+    ExportDirIsSource(PathBuf),
+    /// 模组输出目录位于源文件目录中，会在素材库内生成嵌套的模组文件夹
+    OutputInsideSource(PathBuf),
+}
+
+/// 导出时放置文件的方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CopyOptions {
+    /// 同盘时优先创建硬链接
+    pub use_hardlinks: bool,
+    /// 复制缓冲区大小（字节），None 表示自动（优先使用系统复制，失败时按文件大小选择缓冲区）
+    pub buffer_size: Option<usize>,
+}
+
 /// 文件操作工具
 pub struct FileOperations;
 
 impl FileOperations {
     /// 优化的文件复制方法
-    fn copy_file_optimized(source: &Path, destination: &Path) -> Result<()> {
-        use std::io::{BufReader, BufWriter, Read, Write};
+    ///
+    /// 自动模式下优先使用 `fs::copy`（系统可利用 copy_file_range/reflink 等加速），
+    /// 失败时回退为按文件大小选择缓冲区的分块复制；指定缓冲区大小时直接分块复制。
+    fn copy_file_optimized(source: &Path, destination: &Path, buffer_size: Option<usize>) -> Result<()> {
+        use std::io::{BufWriter, Read, Write};
         
         // 创建目标目录
         if let Some(parent) = destination.parent() {
             fs::create_dir_all(parent)?;
         }
+        Self::remove_existing_destination(source, destination)?;
+
+        let buffer_size = match buffer_size {
+            Some(size) => size.clamp(file_ops::MIN_COPY_BUFFER_SIZE, file_ops::MAX_COPY_BUFFER_SIZE),
+            None => match fs::copy(source, destination) {
+                Ok(_) => return Ok(()),
+                Err(e) => {
+                    debug!("系统复制失败，回退为分块复制: {:?} - {}", source, e);
+                    let file_size = fs::metadata(source).map(|m| m.len()).unwrap_or(0);
+                    Self::auto_copy_buffer_size(file_size)
+                }
+            },
+        };
         
-        // 打开源文件
-        let source_file = fs::File::open(source)?;
-        let mut reader = BufReader::with_capacity(64 * 1024, source_file); // 64KB 缓冲区
+        // 打开源文件（读取直接进入缓冲区，不再额外包一层 BufReader）
+        let mut source_file = fs::File::open(source)?;
         
         // 创建目标文件
         let dest_file = fs::File::create(destination)?;
-        let mut writer = BufWriter::with_capacity(64 * 1024, dest_file); // 64KB 缓冲区
+        let mut writer = BufWriter::with_capacity(buffer_size, dest_file);
         
         // 复制数据
-        let mut buffer = [0u8; 64 * 1024]; // 64KB 缓冲区
+        let mut buffer = vec![0u8; buffer_size];
         loop {
-            let bytes_read = reader.read(&mut buffer)?;
+            let bytes_read = source_file.read(&mut buffer)?;
             if bytes_read == 0 {
                 break;
             }
@@ -43,11 +79,115 @@ impl FileOperations {
         writer.flush()?;
         Ok(())
     }
+
+    /// 按文件大小选择复制缓冲区：约为文件的 1/16，取 2 的幂并限制在允许范围内
+    pub fn auto_copy_buffer_size(file_size: u64) -> usize {
+        let target = (file_size / 16).max(1) as usize;
+        target
+            .checked_next_power_of_two()
+            .unwrap_or(file_ops::MAX_COPY_BUFFER_SIZE)
+            .clamp(file_ops::MIN_COPY_BUFFER_SIZE, file_ops::MAX_COPY_BUFFER_SIZE)
+    }
+
+    /// 将源文件放置到目标位置：启用硬链接时优先创建硬链接，
+    /// 跨盘或硬链接失败时回退为复制。返回是否使用了硬链接
+    fn link_or_copy_file(source: &Path, destination: &Path, copy: CopyOptions) -> Result<bool> {
+        if copy.use_hardlinks {
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            // 硬链接无法覆盖已有文件
+            Self::remove_existing_destination(source, destination)?;
+            match fs::hard_link(source, destination) {
+                Ok(_) => {
+                    debug!("创建硬链接: {:?} -> {:?}", source, destination);
+                    return Ok(true);
+                }
+                Err(e) => {
+                    debug!("无法创建硬链接，回退为复制: {:?} - {}", source, e);
+                }
+            }
+        }
+
+        Self::copy_file_optimized(source, destination, copy.buffer_size)?;
+        Ok(false)
+    }
+
+    /// 删除目标位置的已有文件
+    ///
+    /// 上次导出可能把目标创建为源文件的硬链接，直接覆盖写入会经由共享的 inode 改写源文件；
+    /// 目标与源文件是同一路径时不删除。
+    fn remove_existing_destination(source: &Path, destination: &Path) -> Result<()> {
+        if !destination.exists() {
+            return Ok(());
+        }
+        let same_path = match (fs::canonicalize(source), fs::canonicalize(destination)) {
+            (Ok(source), Ok(destination)) => source == destination,
+            _ => source == destination,
+        };
+        if same_path {
+            return Ok(());
+        }
+        fs::remove_file(destination).with_context(|| format!("无法删除已有文件: {:?}", destination))
+    }
+
     /// 选择音频文件（仅支持OGG格式）
     pub fn select_audio_files() -> Option<Vec<PathBuf>> {
         FileUtils::select_audio_files()
     }
 
+    /// 选择要递归添加的音频文件夹
+    pub fn select_audio_folder() -> Option<PathBuf> {
+        FileUtils::select_audio_folder()
+    }
+
+    /// 递归收集文件夹中的 OGG 文件（按路径排序），并返回是否因达到文件数上限而停止扫描
+    ///
+    /// 最多深入 `MAX_FOLDER_SCAN_DEPTH` 层、收集 `MAX_FOLDER_SCAN_FILES` 个文件，
+    /// 避免误选磁盘根目录或符号链接循环时长时间卡住；无法读取的子文件夹跳过。
+    pub fn collect_ogg_files_recursive(dir: &Path) -> (Vec<PathBuf>, bool) {
+        Self::collect_ogg_files_limited(dir, file_ops::MAX_FOLDER_SCAN_FILES)
+    }
+
+    /// 递归收集最多 `max_files` 个 OGG 文件；只有找到更多文件时才算达到上限
+    fn collect_ogg_files_limited(dir: &Path, max_files: usize) -> (Vec<PathBuf>, bool) {
+        let mut files = Vec::new();
+        let mut pending = vec![(dir.to_path_buf(), 0)];
+        while let Some((dir, depth)) = pending.pop() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    warn!("无法读取文件夹 {:?}: {}", dir, e);
+                    continue;
+                }
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    if depth < file_ops::MAX_FOLDER_SCAN_DEPTH {
+                        pending.push((path, depth + 1));
+                    } else {
+                        warn!("超过最大扫描深度，跳过文件夹: {:?}", path);
+                    }
+                } else if FileUtils::is_supported_audio_file(&path) {
+                    if files.len() >= max_files {
+                        warn!("已收集 {} 个文件，停止扫描: {:?}", files.len(), dir);
+                        files.sort();
+                        return (files, true);
+                    }
+                    files.push(path);
+                }
+            }
+        }
+        files.sort();
+        (files, false)
+    }
+
+    /// 选择播放列表文件（M3U/M3U8/PLS）
+    pub fn select_playlist_file() -> Option<PathBuf> {
+        FileUtils::select_playlist_file()
+    }
+
     /// 选择Logo文件
     pub fn select_logo_file() -> Option<PathBuf> {
         FileUtils::select_paa_file()
@@ -58,6 +198,11 @@ impl FileOperations {
         FileUtils::select_pbo_file()
     }
 
+    /// 选择多个PBO文件（用于容器模组）
+    pub fn select_pbo_files() -> Option<Vec<PathBuf>> {
+        FileUtils::select_pbo_files()
+    }
+
 
     /// 选择加密音频文件
     pub fn select_encrypted_audio_files() -> Option<Vec<PathBuf>> {
@@ -65,6 +210,16 @@ impl FileOperations {
     }
 
 
+    /// 选择要打开的项目文件
+    pub fn select_project_file() -> Option<PathBuf> {
+        FileUtils::select_project_file()
+    }
+
+    /// 选择项目文件的保存位置
+    pub fn select_project_save_path(default_name: &str) -> Option<PathBuf> {
+        FileUtils::select_project_save_path(default_name)
+    }
+
     /// 选择导出目录
     pub fn select_export_directory() -> Option<PathBuf> {
         FileUtils::select_export_directory()
@@ -81,7 +236,8 @@ impl FileOperations {
     }
 
     /// 加载音频文件并创建轨道
-    pub fn load_audio_files(paths: Vec<PathBuf>, class_name: &str) -> Result<Vec<Track>> {
+    pub fn load_audio_files(paths: Vec<PathBuf>, class_name: &str, defaults: &TrackDefaults) -> Result<Vec<Track>> {
+        let defaults = defaults.validated();
         let mut tracks = Vec::new();
 
         for (index, path) in paths.iter().enumerate() {
@@ -104,27 +260,47 @@ impl FileOperations {
             }
 
             // 保留原始文件名作为曲目显示名（stringtable 写入中文；磁盘文件名仍用拼音）
-            let track_name = path
+            let file_stem_name = path
                 .file_stem()
                 .map(|s| s.to_string_lossy().to_string())
                 .unwrap_or_else(|| format!("track_{:03}", index));
 
-            let internally_renamed = StringUtils::is_internal_rename_result(&track_name);
+            let internally_renamed = StringUtils::is_internal_rename_result(&file_stem_name);
 
-            // 创建轨道
-            let mut track = Track::new(path.clone(), track_name, class_name.to_string());
+            // 创建轨道（开启“从标签读取名称”时，TITLE 标签在添加到列表后由后台读取）
+            let mut track = Track::new(path.clone(), file_stem_name, class_name.to_string());
             track.internally_renamed = internally_renamed;
+            track.refresh_file_size();
+
+            // 可选：扫描响度，用建议值作为默认分贝
+            let mut decibels = defaults.decibels;
+            if defaults.scan_loudness {
+                match AudioProcessor::measure_levels(path) {
+                    Ok(levels) => {
+                        track.rms_dbfs = levels.rms_dbfs();
+                        decibels = track.suggested_decibels().unwrap_or(decibels);
+                        debug!("扫描响度: {:?}, 峰值 {:.3}, RMS {:?} dBFS", path, levels.peak, track.rms_dbfs);
+                    }
+                    Err(e) => warn!("无法扫描响度 {:?}: {}", path, e),
+                }
+            }
 
-            // 获取音频信息
-            match AudioProcessor::get_audio_info(path) {
-                Ok(audio_info) => {
-                    track.set_original_values(audio_info.duration, file_ops::DEFAULT_DECIBELS);
-                    debug!("加载音频文件: {:?}, 时长: {}秒", path, audio_info.duration);
+            // 只读取文件头中的时长；没有时先用默认值并标记为估计值，添加到列表后在后台解码测量
+            match AudioProcessor::read_header_duration(path) {
+                Ok(Some(duration)) => {
+                    track.set_original_values(duration, decibels);
+                    debug!("加载音频文件: {:?}, 时长: {}秒", path, duration);
+                }
+                Ok(None) => {
+                    debug!("文件头没有时长信息，稍后在后台测量: {:?}", path);
+                    track.set_original_values(defaults.duration, decibels);
+                    track.duration_estimated = true;
                 }
                 Err(e) => {
                     warn!("无法读取音频信息 {:?}: {}", path, e);
-                    // 即使无法读取音频信息，也设置默认值
-                    track.set_original_values(file_ops::DEFAULT_TRACK_DURATION, file_ops::DEFAULT_DECIBELS);
+                    // 即使无法读取音频信息，也设置默认值，并标记时长为估计值
+                    track.set_original_values(defaults.duration, decibels);
+                    track.duration_estimated = true;
                 }
             }
 
@@ -191,6 +367,29 @@ impl FileOperations {
         Ok(video_files)
     }
 
+    /// 检查导出目录是否可写：目录不存在时先创建，再创建并删除一个临时文件
+    ///
+    /// 在复制任何文件前调用，避免复制了部分轨道后才在写入配置时失败。
+    pub fn check_export_dir_writable(export_dir: &Path) -> Result<()> {
+        fs::create_dir_all(export_dir)
+            .with_context(|| format!("无法创建导出目录: {:?}", export_dir))?;
+        let probe = export_dir.join(format!(".zmm_write_test_{}", std::process::id()));
+        fs::write(&probe, b"")
+            .with_context(|| format!("无法在导出目录中写入文件: {:?}", export_dir))?;
+        if let Err(e) = fs::remove_file(&probe) {
+            warn!("无法删除写入测试文件 {:?}: {}", probe, e);
+        }
+        Ok(())
+    }
+
+    /// 错误是否由权限不足或只读位置引起
+    pub fn is_permission_denied(error: &anyhow::Error) -> bool {
+        error
+            .chain()
+            .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+            .any(|e| matches!(e.kind(), std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::ReadOnlyFilesystem))
+    }
+
     /// 创建模组目录结构
     pub fn create_mod_structure(project: &ProjectSettings, export_dir: &Path) -> Result<PathBuf> {
         let mod_name_no_spaces = project.mod_name_no_spaces();
@@ -204,10 +403,11 @@ impl FileOperations {
         match project.mod_type {
             crate::models::ModType::Music => {
                 // 音乐模组：创建轨道目录
-                let tracks_dir = mod_dir.join("folderwithtracks");
+                let tracks_folder = project.tracks_folder();
+                let tracks_dir = mod_dir.join(&tracks_folder);
                 fs::create_dir_all(&tracks_dir)
                     .with_context(|| format!("无法创建轨道目录: {:?}", tracks_dir))?;
-                info!("创建音乐模组目录结构: {:?} (包含folderwithtracks)", mod_dir);
+                info!("创建音乐模组目录结构: {:?} (包含{})", mod_dir, tracks_folder);
             }
             crate::models::ModType::Video => {
                 // 视频模组：不需要创建额外文件夹
@@ -224,48 +424,93 @@ impl FileOperations {
     }
 
 
+    /// 在文件名被占用时追加数字后缀（`name_1.ogg`、`name_2.ogg`...）直到找到可用的名称
+    fn unique_filename(ascii_filename: &str, extension: &str, is_taken: impl Fn(&str) -> bool) -> String {
+        // 使用预分配的String避免多次分配
+        let mut filename = String::with_capacity(ascii_filename.len() + extension.len() + 1);
+        filename.push_str(ascii_filename);
+        filename.push_str(extension);
+
+        let mut counter = 1;
+        while is_taken(&filename) {
+            filename = format!("{}_{}{}", ascii_filename, counter, extension);
+            counter += 1;
+        }
+        filename
+    }
+
+    /// 预测 `copy_track_files_pinyin` 导出到空目录时生成的文件名（不访问目标目录）
+    ///
+    /// 与实际复制一致：源文件不存在的轨道会被跳过。
+    pub fn preview_track_filenames(project: &ProjectSettings, tracks: &[Track], naming: &NamingScheme, tag_class_names: bool) -> Vec<String> {
+        let mut used_filenames = std::collections::HashSet::new();
+        let mut filenames = Vec::with_capacity(tracks.len());
+
+        for (i, track) in tracks.iter().enumerate() {
+            if !track.path.exists() {
+                continue;
+            }
+            let ascii_filename = StringUtils::render_output_name(project, track, i, naming, tag_class_names);
+            let filename = Self::unique_filename(&ascii_filename, track.output_format.extension(), |candidate| {
+                used_filenames.contains(candidate)
+            });
+            used_filenames.insert(filename.clone());
+            filenames.push(filename);
+        }
+        filenames
+    }
+
     /// 通用的文件复制函数，支持音频和视频文件
-    /// 返回 (复制的文件名列表, 跳过的重复文件数量)
-    fn copy_files_pinyin_generic<T>(
+    /// 返回 (与条目一一对应的文件名列表（源文件不存在、未复制的条目为 None）, 跳过的重复文件数量)
+    /// `get_stem` 根据条目和序号生成文件名（不含扩展名），`transfer` 负责把单个文件写入目标位置，返回是否使用了硬链接；
+    /// 每个条目开始前检查取消并报告进度
+    #[allow(clippy::too_many_arguments)]
+    fn copy_files_pinyin_generic<T, G, F, C, P>(
         items: &[T],
-        mod_dir: &Path,
+        tracks_dir: &Path,
         get_path: fn(&T) -> &Path,
-        get_name: fn(&T) -> &str,
-        extension: &str,
+        get_stem: G,
+        get_extension: fn(&T) -> &'static str,
         item_type: &str,
-    ) -> Result<(Vec<String>, usize)>
+        transfer: F,
+        should_cancel: &C,
+        progress: &P,
+    ) -> Result<(Vec<Option<String>>, usize)>
     where
         T: std::fmt::Debug,
+        G: Fn(&T, usize) -> String,
+        F: Fn(&T, &Path) -> Result<bool>,
+        C: Fn() -> bool + ?Sized,
+        P: Fn(usize, &str) + ?Sized,
     {
-        let tracks_dir = mod_dir.join("folderwithtracks");
         // 预分配容量，避免多次重新分配
         let mut copied_files = Vec::with_capacity(items.len());
         // 用于跟踪已使用的文件名，避免重复
         let mut used_filenames = std::collections::HashSet::new();
         let mut skipped_count = 0;
+        let mut linked_count = 0;
 
         for (i, item) in items.iter().enumerate() {
             let source = get_path(item);
+            let extension = get_extension(item);
+            if should_cancel() {
+                return Err(anyhow::anyhow!(file_ops::EXPORT_CANCELLED));
+            }
+            progress(i, &source.file_name().unwrap_or_default().to_string_lossy());
             
-            // 生成ASCII安全的文件名（拼音风格）
-            let ascii_filename = Self::generate_ascii_filename_pinyin(get_name(item), i);
-            // 使用预分配的String避免多次分配
-            let mut new_filename = String::with_capacity(ascii_filename.len() + extension.len() + 1);
-            new_filename.push_str(&ascii_filename);
-            new_filename.push_str(extension);
+            // 生成ASCII安全的文件名
+            let ascii_filename = get_stem(item, i);
             
             // 检查文件名是否已存在，如果存在则添加数字后缀
-            let mut final_filename = new_filename.clone();
-            let mut counter = 1;
-            while used_filenames.contains(&final_filename) || tracks_dir.join(&final_filename).exists() {
-                final_filename = format!("{}_{}{}", ascii_filename, counter, extension);
-                counter += 1;
-            }
+            let final_filename = Self::unique_filename(&ascii_filename, extension, |candidate| {
+                used_filenames.contains(candidate) || tracks_dir.join(candidate).exists()
+            });
             
             let destination = tracks_dir.join(&final_filename);
 
             if !source.exists() {
                 warn!("源文件不存在: {:?}", source);
+                copied_files.push(None);
                 continue;
             }
 
@@ -274,7 +519,7 @@ impl FileOperations {
                 if let (Ok(source_metadata), Ok(dest_metadata)) = (source.metadata(), destination.metadata()) {
                     if source_metadata.len() == dest_metadata.len() {
                         debug!("跳过重复文件: {:?}", destination);
-                        copied_files.push(final_filename.clone());
+                        copied_files.push(Some(final_filename.clone()));
                         used_filenames.insert(final_filename);
                         skipped_count += 1;
                         continue;
@@ -282,42 +527,316 @@ impl FileOperations {
                 }
             }
 
-            // 使用更高效的文件复制方法
-            Self::copy_file_optimized(source, &destination)
-                .with_context(|| format!("无法复制文件: {:?} -> {:?}", source, destination))?;
+            // 使用更高效的文件复制方法（或同盘硬链接、格式转换）
+            if transfer(item, &destination)
+                .with_context(|| format!("无法复制文件: {:?} -> {:?}", source, destination))?
+            {
+                linked_count += 1;
+            }
 
-            copied_files.push(final_filename.clone());
+            copied_files.push(Some(final_filename.clone()));
             used_filenames.insert(final_filename);
             debug!("复制文件: {:?} -> {:?}", source, destination);
         }
 
-        info!("成功复制 {} 个{}，跳过 {} 个重复文件", copied_files.len(), item_type, skipped_count);
+        info!(
+            "成功复制 {} 个{}（其中硬链接 {} 个），跳过 {} 个重复文件",
+            copied_files.iter().flatten().count(), item_type, linked_count, skipped_count
+        );
         Ok((copied_files, skipped_count))
     }
 
     /// 复制轨道文件到模组目录并自动重命名（拼音风格）
-    /// 返回 (复制的文件名列表, 跳过的重复文件数量)
-    pub fn copy_track_files_pinyin(tracks: &[Track], mod_dir: &Path) -> Result<(Vec<String>, usize)> {
+    /// 返回 (与轨道一一对应的文件名列表（源文件不存在的轨道为 None）, 跳过的重复文件数量)
+    #[allow(clippy::too_many_arguments)]
+    pub fn copy_track_files_pinyin<C, P>(
+        project: &ProjectSettings,
+        tracks: &[Track],
+        mod_dir: &Path,
+        tracks_folder: &str,
+        settings: &ExportSettings,
+        copy: CopyOptions,
+        should_cancel: &C,
+        progress: &P,
+    ) -> Result<(Vec<Option<String>>, usize)>
+    where
+        C: Fn() -> bool + ?Sized,
+        P: Fn(usize, &str) + ?Sized,
+    {
+        let converter = Self::converter_for_tracks(tracks)?;
         Self::copy_files_pinyin_generic(
             tracks,
-            mod_dir,
+            &mod_dir.join(tracks_folder),
             |track| &track.path,
-            |track| &track.track_name,
-            ".ogg",
+            |track, i| StringUtils::render_output_name(project, track, i, &settings.output_naming, settings.tag_class_names),
+            |track| track.output_format.extension(),
             "轨道文件",
+            |track, destination| Self::export_track_file(track, destination, converter.as_ref(), copy, should_cancel),
+            should_cancel,
+            progress,
         )
     }
 
+    /// 存在设置了输出格式覆盖的轨道时创建音频转换器
+    fn converter_for_tracks(tracks: &[Track]) -> Result<Option<AudioConverter>> {
+        if tracks.iter().any(Track::needs_conversion) {
+            AudioConverter::new()
+                .map(Some)
+                .context("部分轨道设置了输出格式覆盖或裁剪，需要 FFmpeg 进行转换")
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 按轨道的输出格式写入导出文件：默认格式复制（或硬链接），覆盖格式或裁剪使用 FFmpeg 转换
+    fn export_track_file<C>(
+        track: &Track,
+        destination: &Path,
+        converter: Option<&AudioConverter>,
+        copy: CopyOptions,
+        should_cancel: &C,
+    ) -> Result<bool>
+    where
+        C: Fn() -> bool + ?Sized,
+    {
+        match converter {
+            Some(converter) if track.needs_conversion() => {
+                debug!("按输出格式覆盖/裁剪转换轨道: {:?} ({:?}, {:?})", track.path, track.output_format, track.trim);
+                converter.convert_track_output(&track.path, destination, track.output_format, track.trim, should_cancel)?;
+                Ok(false)
+            }
+            _ => Self::link_or_copy_file(&track.path, destination, copy),
+        }
+    }
+
+    /// 增量复制轨道文件：仅复制自上次导出以来已更改的轨道，未更改的轨道沿用已有文件
+    /// 返回 (与轨道一一对应的文件名列表（源文件不存在的轨道为 None）, 实际复制的已更改轨道数量)
+    #[allow(clippy::too_many_arguments)]
+    pub fn copy_changed_track_files_pinyin<C, P>(
+        project: &ProjectSettings,
+        tracks: &[Track],
+        mod_dir: &Path,
+        tracks_folder: &str,
+        settings: &ExportSettings,
+        manifest: &ExportManifest,
+        copy: CopyOptions,
+        should_cancel: &C,
+        progress: &P,
+    ) -> Result<(Vec<Option<String>>, usize)>
+    where
+        C: Fn() -> bool + ?Sized,
+        P: Fn(usize, &str) + ?Sized,
+    {
+        let tracks_dir = mod_dir.join(tracks_folder);
+        let mut files = Vec::with_capacity(tracks.len());
+        let mut used_filenames = std::collections::HashSet::new();
+        let mut changed_count = 0;
+        let converter = Self::converter_for_tracks(tracks)?;
+
+        // 先保留未更改轨道的文件名，避免被已更改轨道占用
+        for track in tracks {
+            if let Some(entry) = manifest.find_for_track(track) {
+                if !manifest.is_track_changed(track) && tracks_dir.join(&entry.filename).exists() {
+                    used_filenames.insert(entry.filename.clone());
+                }
+            }
+        }
+
+        for (i, track) in tracks.iter().enumerate() {
+            if should_cancel() {
+                return Err(anyhow::anyhow!(file_ops::EXPORT_CANCELLED));
+            }
+            progress(i, &track.path.file_name().unwrap_or_default().to_string_lossy());
+            let previous = manifest.find_for_track(track);
+
+            if let Some(entry) = previous {
+                if !manifest.is_track_changed(track) && tracks_dir.join(&entry.filename).exists() {
+                    debug!("轨道未更改，沿用已有文件: {}", entry.filename);
+                    files.push(Some(entry.filename.clone()));
+                    continue;
+                }
+            }
+
+            if !track.path.exists() {
+                warn!("源文件不存在: {:?}", track.path);
+                files.push(None);
+                continue;
+            }
+
+            // 路径和格式未变的轨道沿用上次的文件名，覆盖旧文件
+            let extension = track.output_format.extension();
+            let final_filename = match previous {
+                Some(entry) if !used_filenames.contains(&entry.filename) && entry.filename.ends_with(extension) => {
+                    entry.filename.clone()
+                }
+                _ => {
+                    let ascii_filename = StringUtils::render_output_name(project, track, i, &settings.output_naming, settings.tag_class_names);
+                    Self::unique_filename(&ascii_filename, extension, |candidate| {
+                        used_filenames.contains(candidate) || tracks_dir.join(candidate).exists()
+                    })
+                }
+            };
+
+            // 格式变化时移除上次导出的旧文件，避免残留
+            if let Some(entry) = previous {
+                if entry.filename != final_filename && !used_filenames.contains(&entry.filename) {
+                    let stale = tracks_dir.join(&entry.filename);
+                    if stale.exists() {
+                        if let Err(e) = fs::remove_file(&stale) {
+                            warn!("无法删除旧的导出文件 {:?}: {}", stale, e);
+                        }
+                    }
+                }
+            }
+
+            let destination = tracks_dir.join(&final_filename);
+            Self::export_track_file(track, &destination, converter.as_ref(), copy, should_cancel)
+                .with_context(|| format!("无法复制文件: {:?} -> {:?}", track.path, destination))?;
+
+            debug!("复制已更改轨道: {:?} -> {:?}", track.path, destination);
+            used_filenames.insert(final_filename.clone());
+            files.push(Some(final_filename));
+            changed_count += 1;
+        }
+
+        info!(
+            "增量导出：复制 {} 个已更改轨道，沿用 {} 个未更改轨道",
+            changed_count,
+            files.iter().flatten().count() - changed_count
+        );
+        Ok((files, changed_count))
+    }
+
+    /// 按轨道顺序取出实际导出的文件名（跳过未复制的轨道），供生成配置和一致性检查使用
+    pub fn exported_filenames(track_files: &[Option<String>]) -> Vec<String> {
+        track_files.iter().flatten().cloned().collect()
+    }
+
+    /// 读取项目文件（.zmm 或 .json，也接受配置文件），格式不正确时返回错误，不修改当前项目
+    pub fn load_project_file(path: &Path) -> Result<crate::models::ProjectFile> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("无法读取项目文件: {:?}", path))?;
+        crate::models::AppState::parse_project_file(&content)
+            .with_context(|| format!("不是有效的项目文件（需要包含 project 字段）: {:?}", path))
+    }
+
+    /// 读取模组目录中的上次导出记录
+    pub fn load_export_manifest(mod_dir: &Path) -> Option<ExportManifest> {
+        let manifest_path = mod_dir.join(file_ops::EXPORT_MANIFEST_FILE);
+        let content = fs::read_to_string(&manifest_path).ok()?;
+        match serde_json::from_str::<ExportManifest>(&content) {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                warn!("导出记录格式错误 {:?}: {}", manifest_path, e);
+                None
+            }
+        }
+    }
+
+    /// 保存本次导出记录到模组目录
+    ///
+    /// `track_files` 与轨道一一对应（复制函数的结果），未复制的轨道不写入记录。
+    pub fn save_export_manifest(mod_dir: &Path, tracks: &[Track], track_files: &[Option<String>]) -> Result<()> {
+        let manifest = ExportManifest {
+            entries: tracks
+                .iter()
+                .zip(track_files.iter())
+                .filter_map(|(track, filename)| Some(ExportManifestEntry::from_track(track, filename.as_deref()?)))
+                .collect(),
+        };
+        let manifest_path = mod_dir.join(file_ops::EXPORT_MANIFEST_FILE);
+        let content = serde_json::to_string_pretty(&manifest)?;
+        fs::write(&manifest_path, content)
+            .with_context(|| format!("无法写入导出记录: {:?}", manifest_path))?;
+        debug!("保存导出记录: {:?}", manifest_path);
+        Ok(())
+    }
+
+    /// 导出一致性检查：核对待导出轨道数、已复制文件数与生成的配置类数是否一致
+    /// 返回发现的不一致项（为空表示一致）
+    pub fn verify_music_export(
+        tracks: &[Track],
+        copied_files: &[String],
+        mod_dir: &Path,
+        tracks_folder: &str,
+    ) -> Result<Vec<String>> {
+        let tracks_dir = mod_dir.join(tracks_folder);
+        let hpp_path = mod_dir.join("FileListWithMusicTracks.hpp");
+        let content = fs::read_to_string(&hpp_path)
+            .with_context(|| format!("无法读取轨道配置文件: {:?}", hpp_path))?;
+
+        // 解析生成的配置类及其引用的文件
+        let root = parse_config(&content)
+            .with_context(|| format!("无法解析轨道配置文件: {:?}", hpp_path))?;
+        let classes: Vec<(String, String)> = root
+            .classes
+            .iter()
+            .filter_map(|class| {
+                let sound_path = split_array(class.entry("sound[]")?).first().map(|path| unquote(path))?;
+                let filename = sound_path.rsplit('\\').next().unwrap_or(&sound_path).to_string();
+                Some((class.name.clone(), filename))
+            })
+            .collect();
+
+        let mut issues = Vec::new();
+
+        if tracks.len() != copied_files.len() || tracks.len() != classes.len() {
+            issues.push(format!(
+                "数量不一致: 待导出轨道 {} 个，已复制文件 {} 个，配置类 {} 个",
+                tracks.len(),
+                copied_files.len(),
+                classes.len()
+            ));
+        }
+
+        for track in tracks.iter().filter(|track| !track.path.exists()) {
+            issues.push(format!("源文件缺失，未复制: {} ({})", track.track_name, track.path.display()));
+        }
+
+        for (class_name, filename) in &classes {
+            if !tracks_dir.join(filename).exists() {
+                issues.push(format!("配置类 {} 引用的文件不存在: {}", class_name, filename));
+            }
+        }
+
+        for filename in copied_files {
+            if !classes.iter().any(|(_, referenced)| referenced == filename) {
+                issues.push(format!("已复制文件没有对应的配置类: {}", filename));
+            }
+        }
+
+        if issues.is_empty() {
+            debug!("导出一致性检查通过: {} 个轨道", tracks.len());
+        } else {
+            warn!("导出一致性检查发现 {} 个问题", issues.len());
+        }
+        Ok(issues)
+    }
+
     /// 复制视频文件到模组目录并自动重命名（拼音风格）
     /// 返回 (复制的文件名列表, 跳过的重复文件数量)
-    pub fn copy_video_files_pinyin(video_files: &[VideoFile], mod_dir: &Path) -> Result<(Vec<String>, usize)> {
-        // 视频文件直接放在模组根目录，不需要folderwithtracks文件夹
+    pub fn copy_video_files_pinyin<C, P>(
+        video_files: &[VideoFile],
+        mod_dir: &Path,
+        copy: CopyOptions,
+        should_cancel: &C,
+        progress: &P,
+    ) -> Result<(Vec<String>, usize)>
+    where
+        C: Fn() -> bool + ?Sized,
+        P: Fn(usize, &str) + ?Sized,
+    {
+        // 视频文件直接放在模组根目录，不需要轨道文件夹
         let mut copied_files = Vec::with_capacity(video_files.len());
         let mut used_filenames = std::collections::HashSet::new();
         let mut skipped_count = 0;
 
         for (i, video_file) in video_files.iter().enumerate() {
             let source = &video_file.path;
+            if should_cancel() {
+                return Err(anyhow::anyhow!(file_ops::EXPORT_CANCELLED));
+            }
+            progress(i, &source.file_name().unwrap_or_default().to_string_lossy());
             
             // 生成ASCII安全的文件名（拼音风格）
             let ascii_filename = Self::generate_ascii_filename_pinyin(&video_file.video_name, i);
@@ -354,8 +873,8 @@ impl FileOperations {
                 }
             }
 
-            // 使用更高效的文件复制方法
-            Self::copy_file_optimized(source, &destination)
+            // 使用更高效的文件复制方法（或同盘硬链接）
+            Self::link_or_copy_file(source, &destination, copy)
                 .with_context(|| format!("无法复制文件: {:?} -> {:?}", source, destination))?;
 
             copied_files.push(final_filename.clone());
@@ -368,6 +887,108 @@ impl FileOperations {
     }
 
 
+    /// 将解密输出文件按格式移动到输出目录下的子文件夹（如 mp3/、flac/）
+    /// 返回 (移动后的路径, 子文件夹名称)
+    pub fn organize_by_format(file_path: &Path, output_dir: &Path) -> Result<(PathBuf, String)> {
+        let folder = file_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .filter(|ext| !ext.is_empty())
+            .unwrap_or_else(|| "other".to_string());
+
+        let format_dir = output_dir.join(&folder);
+        fs::create_dir_all(&format_dir)
+            .with_context(|| format!("无法创建格式目录: {:?}", format_dir))?;
+
+        let file_name = file_path
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("无效的文件名: {:?}", file_path))?;
+        // 并行解密的多个线程可能同时整理同名文件，先独占目标文件名再用重命名覆盖占位文件
+        let destination = StringUtils::reserve_unique_path(&format_dir.join(file_name))
+            .with_context(|| format!("无法在格式目录中创建文件: {:?}", format_dir))?;
+
+        if let Err(e) = fs::rename(file_path, &destination) {
+            let _ = fs::remove_file(&destination);
+            return Err(e).with_context(|| format!("无法移动文件: {:?} -> {:?}", file_path, destination));
+        }
+
+        debug!("按格式整理: {:?} -> {:?}", file_path, destination);
+        Ok((destination, folder))
+    }
+
+    /// 将轨道内嵌封面转换为 logo.paa（保存在用户工作空间），返回生成的文件路径
+    pub fn import_logo_from_cover_art(track_path: &Path) -> Result<PathBuf> {
+        let cover = AudioProcessor::extract_cover_art(track_path)?
+            .ok_or_else(|| anyhow::anyhow!("轨道没有内嵌封面: {:?}", track_path))?;
+
+        let workspace = crate::ffmpeg_downloader::FFmpegDownloader::get_user_workspace()?;
+        let logo_path = workspace.join(file_ops::COVER_LOGO_FILE);
+        let options = crate::paa_converter::PaaOptions {
+            crop_to_power_of_two: true,
+            target_size: Some(file_ops::COVER_LOGO_SIZE),
+            center_crop: true,
+            ..Default::default()
+        };
+        crate::paa_converter::PaaConverter::convert_image_bytes_to_paa(&cover, &logo_path, options)
+            .with_context(|| format!("无法将封面转换为PAA: {:?}", track_path))?;
+
+        info!("已从轨道封面生成Logo: {:?} -> {:?}", track_path, logo_path);
+        Ok(logo_path)
+    }
+
+    /// 将轨道内嵌封面转换为与音频文件同名的 .paa（放在音频文件旁），没有封面时返回 None
+    pub fn generate_track_cover_paa(track_path: &Path) -> Result<Option<PathBuf>> {
+        let Some(cover) = AudioProcessor::extract_cover_art(track_path)? else {
+            return Ok(None);
+        };
+
+        let paa_path = track_path.with_extension("paa");
+        let options = crate::paa_converter::PaaOptions {
+            crop_to_power_of_two: true,
+            target_size: Some(file_ops::COVER_LOGO_SIZE),
+            center_crop: true,
+            ..Default::default()
+        };
+        crate::paa_converter::PaaConverter::convert_image_bytes_to_paa(&cover, &paa_path, options)
+            .with_context(|| format!("无法将封面转换为PAA: {:?}", track_path))?;
+
+        debug!("已从轨道封面生成PAA: {:?}", paa_path);
+        Ok(Some(paa_path))
+    }
+
+    /// 检查导出目录是否与源文件所在目录重叠（相同、祖先或子孙关系）
+    pub fn check_export_dir_overlap(
+        project: &ProjectSettings,
+        export_dir: &Path,
+        source_paths: &[&Path],
+    ) -> Vec<ExportDirOverlap> {
+        let normalize = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let export_dir = normalize(export_dir);
+        let mod_dir = export_dir.join(project.mod_name_no_spaces());
+
+        let source_dirs: std::collections::BTreeSet<PathBuf> = source_paths
+            .iter()
+            .filter_map(|path| path.parent())
+            .map(normalize)
+            .collect();
+
+        let mut overlaps = Vec::new();
+        for source_dir in source_dirs {
+            if source_dir.starts_with(&mod_dir) {
+                overlaps.push(ExportDirOverlap::SourceInsideOutput(source_dir));
+            } else if source_dir == export_dir {
+                overlaps.push(ExportDirOverlap::ExportDirIsSource(source_dir));
+            } else if mod_dir.starts_with(&source_dir) {
+                overlaps.push(ExportDirOverlap::OutputInsideSource(source_dir));
+            }
+        }
+
+        if !overlaps.is_empty() {
+            warn!("导出目录与源文件目录重叠: {:?}", overlaps);
+        }
+        overlaps
+    }
+
     /// 复制Logo文件
     pub fn copy_logo_file(project: &ProjectSettings, mod_dir: &Path) -> Result<()> {
         let logo_dest = mod_dir.join("logo.paa");
@@ -375,7 +996,7 @@ impl FileOperations {
         if let Some(logo_path) = &project.logo_path {
             if logo_path.exists() {
                 // 使用更高效的文件复制方法
-                Self::copy_file_optimized(logo_path, &logo_dest)
+                Self::copy_file_optimized(logo_path, &logo_dest, None)
                     .with_context(|| format!("无法复制Logo文件: {:?} -> {:?}", logo_path, logo_dest))?;
                 info!("复制自定义Logo: {:?}", logo_path);
             } else {
@@ -433,6 +1054,333 @@ impl FileOperations {
 
     /// 创建PBO模组结构
     pub fn create_pbo_mod_structure(project: &ProjectSettings, pbo_path: &Path, export_dir: &Path) -> Result<PathBuf> {
+        let (mod_dir, addons_dir) = Self::create_mod_shell(project, export_dir)?;
+
+        // 复制PBO文件
+        let pbo_dest = addons_dir.join("MusicModPBO.pbo");
+        fs::copy(pbo_path, &pbo_dest)
+            .with_context(|| format!("无法复制PBO文件: {:?} -> {:?}", pbo_path, pbo_dest))?;
+
+        info!("创建PBO模组结构: {:?}", mod_dir);
+        Ok(mod_dir)
+    }
+
+    /// 创建容器模组结构：将多个已有PBO按原文件名复制到 `Addons/`（不需要任何轨道）
+    pub fn create_container_mod_structure(project: &ProjectSettings, pbo_paths: &[PathBuf], export_dir: &Path) -> Result<PathBuf> {
+        if pbo_paths.is_empty() {
+            return Err(anyhow::anyhow!("没有选择任何PBO文件"));
+        }
+        if let Some(name) = Self::duplicate_file_name(pbo_paths) {
+            return Err(anyhow::anyhow!("PBO文件名重复: {}", name));
+        }
+
+        let (mod_dir, addons_dir) = Self::create_mod_shell(project, export_dir)?;
+
+        for pbo_path in pbo_paths {
+            let file_name = pbo_path
+                .file_name()
+                .ok_or_else(|| anyhow::anyhow!("无效的PBO路径: {:?}", pbo_path))?;
+            let pbo_dest = addons_dir.join(file_name);
+            fs::copy(pbo_path, &pbo_dest)
+                .with_context(|| format!("无法复制PBO文件: {:?} -> {:?}", pbo_path, pbo_dest))?;
+        }
+
+        info!("创建容器模组结构: {:?}（{} 个PBO）", mod_dir, pbo_paths.len());
+        Ok(mod_dir)
+    }
+
+    /// 查找重复的文件名（不区分大小写，复制到同一 `Addons/` 目录时会互相覆盖）
+    pub fn duplicate_file_name(paths: &[PathBuf]) -> Option<String> {
+        let mut seen = std::collections::HashSet::new();
+        paths
+            .iter()
+            .filter_map(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .find(|name| !seen.insert(name.to_lowercase()))
+    }
+
+    /// 执行模组导出的文件部分：创建目录、复制（或转换）文件、生成配置、核对一致性并保存导出记录
+    ///
+    /// 在后台线程运行，每个文件开始前报告进度并检查取消。导出成功、失败或取消都会生成结果说明。
+    pub fn export_mod<C, P>(job: &ModExportJob, should_cancel: &C, progress: &P) -> ModExportOutcome
+    where
+        C: Fn() -> bool + ?Sized,
+        P: Fn(usize, &str) + ?Sized,
+    {
+        let project = &job.project;
+        let settings = &job.export_settings;
+        let mut success_steps = Vec::new();
+        let mut error_steps = job.warnings.clone();
+
+        let mod_dir = match Self::create_mod_structure(project, &job.export_dir) {
+            Ok(mod_dir) => mod_dir,
+            Err(e) => {
+                warn!("创建模组结构失败 {:?}: {:#}", job.export_dir, e);
+                let permission_denied = Self::is_permission_denied(&e);
+                let message = if permission_denied {
+                    Self::export_dir_error_message(&job.export_dir, &e)
+                } else {
+                    format!(
+                        "模组导出失败！\n\n输出目录: {}\n\n错误信息:\n  创建模组结构失败: {}",
+                        job.export_dir.display(),
+                        e
+                    )
+                };
+                return ModExportOutcome { message, permission_denied, ..Default::default() };
+            }
+        };
+        success_steps.push("创建模组目录结构".to_string());
+
+        // 增量导出需要模组目录中存在上次导出的记录
+        let previous_manifest = if project.mod_type == ModType::Music && settings.only_export_changed {
+            let manifest = Self::load_export_manifest(&mod_dir);
+            if manifest.is_none() {
+                error_steps.push("未找到上次导出记录，已执行完整导出".to_string());
+            }
+            manifest
+        } else {
+            None
+        };
+
+        let copy_options = settings.copy_options();
+        let tracks_folder = project.tracks_folder();
+        // 音乐模组的 track_files 与轨道一一对应（未复制的轨道为 None），files 为实际导出的文件名
+        let copy_result = match project.mod_type {
+            ModType::Music => match &previous_manifest {
+                Some(manifest) => Self::copy_changed_track_files_pinyin(project, &job.tracks, &mod_dir, &tracks_folder, settings, manifest, copy_options, should_cancel, progress)
+                    .map(|(track_files, changed_count)| {
+                        let unchanged_count = track_files.iter().flatten().count() - changed_count;
+                        (track_files, unchanged_count, "已更改轨道文件")
+                    }),
+                None => Self::copy_track_files_pinyin(project, &job.tracks, &mod_dir, &tracks_folder, settings, copy_options, should_cancel, progress)
+                    .map(|(track_files, skipped_count)| (track_files, skipped_count, "轨道文件")),
+            },
+            ModType::Video => Self::copy_video_files_pinyin(&job.video_files, &mod_dir, copy_options, should_cancel, progress)
+                .map(|(files, skipped_count)| (files.into_iter().map(Some).collect(), skipped_count, "视频文件")),
+        };
+        let (track_files, skipped_count, file_type) = match copy_result {
+            Ok(result) => result,
+            Err(_) if should_cancel() => {
+                info!("模组导出已取消: {:?}", mod_dir);
+                let mut message = format!(
+                    "模组导出已取消。\n\n输出目录: {}\n\n已复制的文件保留在输出目录中，但尚未生成配置文件，模组暂不可用。重新导出即可完成。\n",
+                    mod_dir.display()
+                );
+                Self::append_export_steps(&mut message, &success_steps, "警告信息", &error_steps);
+                return ModExportOutcome { message, mod_dir: Some(mod_dir), cancelled: true, ..Default::default() };
+            }
+            Err(e) => {
+                warn!("复制文件失败: {:#}", e);
+                error_steps.push(format!("复制{}失败: {:#}", if project.mod_type == ModType::Music { "轨道文件" } else { "视频文件" }, e));
+                let mut message = format!("模组导出失败！\n\n输出目录: {}\n\n", mod_dir.display());
+                Self::append_export_steps(&mut message, &success_steps, "错误信息", &error_steps);
+                return ModExportOutcome { message, mod_dir: Some(mod_dir), ..Default::default() };
+            }
+        };
+
+        let files = Self::exported_filenames(&track_files);
+        let copied_files = files.len();
+        if previous_manifest.is_some() {
+            success_steps.push(format!(
+                "复制{} ({} 个，保留 {} 个未更改)",
+                file_type,
+                copied_files - skipped_count,
+                skipped_count
+            ));
+        } else if skipped_count > 0 {
+            success_steps.push(format!("复制{} ({} 个，跳过 {} 个重复)", file_type, copied_files, skipped_count));
+        } else {
+            success_steps.push(format!("复制{} ({} 个)", file_type, copied_files));
+        }
+        if copy_options.use_hardlinks {
+            success_steps.push("同盘文件已使用硬链接（跨盘时自动回退为复制）".to_string());
+        }
+
+        // 复制Logo文件
+        match Self::copy_logo_file(project, &mod_dir) {
+            Ok(_) => success_steps.push("复制Logo文件".to_string()),
+            Err(e) => error_steps.push(format!("复制Logo文件失败: {}", e)),
+        }
+
+        // 复制Steam Logo
+        match Self::copy_steam_logo(&mod_dir) {
+            Ok(_) => success_steps.push("复制Steam Logo".to_string()),
+            Err(e) => error_steps.push(format!("复制Steam Logo失败: {}", e)),
+        }
+
+        // 生成配置文件（视频模组不需要音频轨道）
+        let config_tracks: &[Track] = match project.mod_type {
+            ModType::Music => &job.tracks,
+            ModType::Video => &[],
+        };
+        if let Err(e) = crate::templates::TemplateEngine::new().and_then(|template_engine| {
+            template_engine.generate_all_configs(
+                project,
+                config_tracks,
+                &files,
+                settings.append_tags,
+                settings.tag_class_names,
+                settings.use_stringtable,
+                settings.use_google_translate,
+                &mod_dir,
+            )
+        }) {
+            error_steps.push(format!("生成配置文件失败: {}", e));
+            let mut message = format!("模组导出失败！\n\n输出目录: {}\n\n", mod_dir.display());
+            Self::append_export_steps(&mut message, &success_steps, "错误信息", &error_steps);
+            return ModExportOutcome { message, mod_dir: Some(mod_dir), ..Default::default() };
+        }
+
+        if settings.use_stringtable {
+            if settings.use_google_translate {
+                success_steps.push("生成 stringtable.xml（含 Google 翻译英文回退）".to_string());
+            } else {
+                success_steps.push("生成 stringtable.xml（拼音英文回退）".to_string());
+            }
+        }
+        success_steps.push("生成配置文件".to_string());
+
+        // 核对轨道、已复制文件与配置类是否一一对应
+        let consistency_issues = if project.mod_type == ModType::Music {
+            Self::verify_music_export(&job.tracks, &files, &mod_dir, &tracks_folder)
+                .unwrap_or_else(|e| vec![format!("无法执行一致性检查: {}", e)])
+        } else {
+            Vec::new()
+        };
+
+        // 记录本次导出状态，供下次增量导出比较（不一致时记录不可信，跳过）
+        let mut manifest_saved = false;
+        if project.mod_type == ModType::Music {
+            if consistency_issues.is_empty() {
+                match Self::save_export_manifest(&mod_dir, &job.tracks, &track_files) {
+                    Ok(_) => manifest_saved = true,
+                    Err(e) => error_steps.push(format!("保存导出记录失败: {}", e)),
+                }
+            } else {
+                error_steps.push("导出不一致，未保存导出记录".to_string());
+            }
+        }
+
+        // 构建最终结果消息
+        let mut message = if consistency_issues.is_empty() {
+            format!("模组导出成功！\n\n输出目录: {}\n\n", mod_dir.display())
+        } else {
+            format!("⚠️ 模组已导出，但一致性检查失败！\n\n输出目录: {}\n\n", mod_dir.display())
+        };
+        if !consistency_issues.is_empty() {
+            message.push_str("一致性问题（模组可能无法正常播放这些轨道）:\n");
+            for issue in &consistency_issues {
+                message.push_str(&format!("  {}\n", issue));
+            }
+            message.push('\n');
+        }
+        Self::append_export_steps(&mut message, &success_steps, "警告信息", &error_steps);
+
+        let item_type = match project.mod_type {
+            ModType::Music => "轨道数量",
+            ModType::Video => "视频数量",
+        };
+        message.push_str(&format!("\n统计信息:\n  {}: {}\n  模组名称: {}", item_type, job.item_count(), project.mod_name));
+
+        if consistency_issues.is_empty() {
+            info!("模组导出成功: {:?}", mod_dir);
+        } else {
+            warn!("模组导出存在不一致: {:?}", mod_dir);
+        }
+        ModExportOutcome { message, mod_dir: Some(mod_dir), manifest_saved, ..Default::default() }
+    }
+
+    /// 在导出结果中列出成功步骤和警告/错误信息
+    fn append_export_steps(message: &mut String, success_steps: &[String], error_title: &str, error_steps: &[String]) {
+        if !success_steps.is_empty() {
+            message.push_str("成功步骤:\n");
+            for step in success_steps {
+                message.push_str(&format!("  {}\n", step));
+            }
+        }
+        if !error_steps.is_empty() {
+            message.push_str(&format!("\n{}:\n", error_title));
+            for step in error_steps {
+                message.push_str(&format!("  {}\n", step));
+            }
+        }
+    }
+
+    /// 导出目录无法写入时的结果说明；权限不足时建议改用“文档”等可写位置
+    pub fn export_dir_error_message(export_dir: &Path, error: &anyhow::Error) -> String {
+        if !Self::is_permission_denied(error) {
+            return format!("模组导出失败！\n\n输出目录: {}\n\n错误信息:\n  {:#}", export_dir.display(), error);
+        }
+        let suggestion = dirs::document_dir()
+            .map(|dir| format!("，例如“文档”文件夹: {}", dir.display()))
+            .unwrap_or_default();
+        format!(
+            "模组导出失败！\n\n没有写入权限，未复制任何文件。\n输出目录: {}\n\n\
+             该位置需要管理员权限或为只读（如 C:\\Program Files）。请重新选择一个可写的位置{}。\n\n错误信息:\n  {:#}",
+            export_dir.display(),
+            suggestion,
+            error
+        )
+    }
+
+    /// 创建未打包的插件源文件夹，可直接交给 Addon Builder 或 Mikero 工具打包为 PBO
+    ///
+    /// 输出结构：`@模组名/mod.cpp`、`meta.cpp`、Logo，以及 `@模组名/Addons/<类名>/` 下的 `$PBOPREFIX$`、
+    /// `config.cpp`、`FileListWithMusicTracks.hpp`、轨道文件夹（和可选的 `stringtable.xml`）。
+    /// 前缀与配置中的轨道路径一致，均为去空格的模组名。返回 (模组目录, 插件源目录, 轨道文件名列表)。
+    pub fn create_addon_source_structure(
+        project: &ProjectSettings,
+        tracks: &[Track],
+        export_dir: &Path,
+        settings: &ExportSettings,
+    ) -> Result<(PathBuf, PathBuf, Vec<String>)> {
+        if project.mod_type != crate::models::ModType::Music {
+            return Err(anyhow::anyhow!("插件源文件仅支持音乐模组"));
+        }
+
+        let (mod_dir, addons_dir) = Self::create_mod_shell(project, export_dir)?;
+        let addon_dir = addons_dir.join(&project.class_name);
+        let tracks_folder = project.tracks_folder();
+        let tracks_dir = addon_dir.join(&tracks_folder);
+        fs::create_dir_all(&tracks_dir)
+            .with_context(|| format!("无法创建插件源目录: {:?}", tracks_dir))?;
+
+        let prefix_path = addon_dir.join("$PBOPREFIX$");
+        fs::write(&prefix_path, project.mod_name_no_spaces())
+            .with_context(|| format!("无法写入PBO前缀文件: {:?}", prefix_path))?;
+
+        let (track_files, _) = Self::copy_track_files_pinyin(project, tracks, &addon_dir, &tracks_folder, settings, settings.copy_options(), &|| false, &|_, _| {})?;
+        let files = Self::exported_filenames(&track_files);
+
+        let template_engine = crate::templates::TemplateEngine::new()?;
+        if settings.use_stringtable {
+            template_engine.generate_stringtable_xml(
+                project,
+                tracks,
+                settings.append_tags,
+                settings.use_google_translate,
+                &addon_dir,
+            )?;
+        }
+        template_engine.generate_config_cpp(project, settings.use_stringtable, &addon_dir.join("config.cpp"))?;
+        template_engine.generate_tracks_hpp(
+            project,
+            tracks,
+            &files,
+            settings.append_tags,
+            settings.tag_class_names,
+            settings.use_stringtable,
+            &addon_dir.join("FileListWithMusicTracks.hpp"),
+        )?;
+        template_engine.generate_mod_cpp(project, settings.use_stringtable, &mod_dir.join("mod.cpp"))?;
+        template_engine.generate_meta_cpp(project, project.workshop_id, &mod_dir.join("meta.cpp"))?;
+
+        info!("创建插件源文件结构: {:?}（{} 个轨道）", addon_dir, files.len());
+        Ok((mod_dir, addon_dir, files))
+    }
+
+    /// 创建 `@模组名` 目录、Logo 和 `Addons/` 目录，返回 (模组目录, Addons目录)
+    fn create_mod_shell(project: &ProjectSettings, export_dir: &Path) -> Result<(PathBuf, PathBuf)> {
         let mod_name_no_spaces = format!("@{}", project.mod_name_no_spaces());
         let mod_dir = export_dir.join(&mod_name_no_spaces);
 
@@ -449,14 +1397,633 @@ impl FileOperations {
         fs::create_dir_all(&addons_dir)
             .with_context(|| format!("无法创建Addons目录: {:?}", addons_dir))?;
 
-        // 复制PBO文件
-        let pbo_dest = addons_dir.join("MusicModPBO.pbo");
-        fs::copy(pbo_path, &pbo_dest)
-            .with_context(|| format!("无法复制PBO文件: {:?} -> {:?}", pbo_path, pbo_dest))?;
+        Ok((mod_dir, addons_dir))
+    }
 
-        info!("创建PBO模组结构: {:?}", mod_dir);
-        Ok(mod_dir)
+}
+
+
+/// 解析后的配置类
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigClass {
+    pub name: String,
+    /// 继承的父类（`class A: B`）
+    pub parent: Option<String>,
+    /// 字段（名称, 原始值），按出现顺序
+    pub entries: Vec<(String, String)>,
+    pub classes: Vec<ConfigClass>,
+    /// `#include` 引用的文件
+    pub includes: Vec<String>,
+}
+
+impl ConfigClass {
+    /// 按名称查找子类（不区分大小写，与 Arma 一致）
+    pub fn class(&self, name: &str) -> Option<&ConfigClass> {
+        self.classes.iter().find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// 按名称查找字段原始值
+    pub fn entry(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 序列化为配置文本（用于保留模板之外的自定义类）
+    pub(crate) fn to_text(&self, indent: usize) -> String {
+        let pad = "    ".repeat(indent);
+        let mut text = match &self.parent {
+            Some(parent) => format!("{}class {}: {}\n{}{{\n", pad, self.name, parent, pad),
+            None => format!("{}class {}\n{}{{\n", pad, self.name, pad),
+        };
+        for include in &self.includes {
+            text.push_str(&format!("{}    #include \"{}\"\n", pad, include));
+        }
+        for (key, value) in &self.entries {
+            text.push_str(&format!("{}    {} = {};\n", pad, key, value));
+        }
+        for class in &self.classes {
+            text.push_str(&class.to_text(indent + 1));
+        }
+        text.push_str(&format!("{}}};\n", pad));
+        text
+    }
+}
+
+/// 去除字符串值的引号（Arma 中 `""` 表示一个引号）
+pub fn unquote(value: &str) -> String {
+    let value = value.trim();
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(inner) => inner.replace("\"\"", "\""),
+        None => value.to_string(),
+    }
+}
+
+/// 拆分数组值 `{a, b, c}` 的顶层元素
+pub fn split_array(value: &str) -> Vec<String> {
+    let inner = value.trim().trim_start_matches('{').trim_end_matches('}');
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_string = false;
+    let mut depth = 0;
+    for c in inner.chars() {
+        match c {
+            '"' => in_string = !in_string,
+            '{' if !in_string => depth += 1,
+            '}' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    parts
+}
+
+/// 解析配置文本，返回名称为空的根类（导出一致性检查和旧配置修复共用）
+pub fn parse_config(text: &str) -> Result<ConfigClass> {
+    let chars: Vec<char> = strip_comments(text).chars().collect();
+    let mut parser = Parser { chars, pos: 0 };
+    let mut root = ConfigClass::default();
+    parser.parse_body(&mut root, false)?;
+    Ok(root)
+}
+
+/// 去除 `//` 和 `/* */` 注释（字符串内的除外）
+fn strip_comments(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if c == '"' {
+            in_string = !in_string;
+        } else if !in_string && c == '/' {
+            match chars.peek() {
+                Some('/') => {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            result.push('\n');
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                Some('*') => {
+                    chars.next();
+                    let mut previous = ' ';
+                    for c in chars.by_ref() {
+                        if previous == '*' && c == '/' {
+                            break;
+                        }
+                        previous = c;
+                    }
+                    result.push(' ');
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        result.push(c);
+    }
+    result
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn line(&self) -> usize {
+        self.chars[..self.pos.min(self.chars.len())].iter().filter(|&&c| c == '\n').count() + 1
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn read_identifier(&mut self) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || c == '_' || c == '[' || c == ']' {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn read_line(&mut self) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// 读取字段值直到顶层的 `;`
+    fn read_value(&mut self) -> Result<String> {
+        let start = self.pos;
+        let mut in_string = false;
+        let mut depth = 0i32;
+        while let Some(c) = self.peek() {
+            match c {
+                '"' => in_string = !in_string,
+                '{' if !in_string => depth += 1,
+                '}' if !in_string => {
+                    depth -= 1;
+                    if depth < 0 {
+                        return Err(anyhow::anyhow!("第 {} 行: 字段值缺少 ;", self.line()));
+                    }
+                }
+                ';' if !in_string && depth == 0 => {
+                    let value: String = self.chars[start..self.pos].iter().collect();
+                    self.pos += 1;
+                    return Ok(value.trim().to_string());
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        Err(anyhow::anyhow!("第 {} 行: 字段值未结束", self.line()))
+    }
+
+    fn expect_semicolon(&mut self) {
+        self.skip_whitespace();
+        if self.peek() == Some(';') {
+            self.pos += 1;
+        }
     }
 
+    fn parse_body(&mut self, class: &mut ConfigClass, nested: bool) -> Result<()> {
+        loop {
+            self.skip_whitespace();
+            let Some(c) = self.peek() else {
+                if nested {
+                    return Err(anyhow::anyhow!("类 {} 缺少结尾的 }}", class.name));
+                }
+                return Ok(());
+            };
+
+            if c == '}' {
+                if !nested {
+                    return Err(anyhow::anyhow!("第 {} 行: 多余的 }}", self.line()));
+                }
+                self.pos += 1;
+                self.expect_semicolon();
+                return Ok(());
+            }
+
+            if c == '#' {
+                let line = self.read_line();
+                if let Some(rest) = line.trim().strip_prefix("#include") {
+                    class.includes.push(rest.trim().trim_matches(|c| c == '"' || c == '<' || c == '>').to_string());
+                }
+                continue;
+            }
+
+            let identifier = self.read_identifier();
+            if identifier.is_empty() {
+                return Err(anyhow::anyhow!("第 {} 行: 无法识别的字符 '{}'", self.line(), c));
+            }
+
+            if identifier == "class" {
+                self.skip_whitespace();
+                let mut child = ConfigClass {
+                    name: self.read_identifier(),
+                    ..ConfigClass::default()
+                };
+                if child.name.is_empty() {
+                    return Err(anyhow::anyhow!("第 {} 行: class 缺少名称", self.line()));
+                }
+                self.skip_whitespace();
+                if self.peek() == Some(':') {
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    child.parent = Some(self.read_identifier());
+                    self.skip_whitespace();
+                }
+                match self.peek() {
+                    Some('{') => {
+                        self.pos += 1;
+                        self.parse_body(&mut child, true)?;
+                    }
+                    // 前置声明 `class A;`
+                    Some(';') => self.pos += 1,
+                    _ => return Err(anyhow::anyhow!("第 {} 行: class {} 缺少 {{", self.line(), child.name)),
+                }
+                class.classes.push(child);
+                continue;
+            }
+
+            self.skip_whitespace();
+            if self.peek() == Some('+') {
+                self.pos += 1;
+            }
+            if self.peek() != Some('=') {
+                return Err(anyhow::anyhow!("第 {} 行: 字段 {} 缺少 =", self.line(), identifier));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let value = self.read_value()?;
+            class.entries.push((identifier, value));
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD_CONFIG: &str = "// 旧版导出\nclass CfgPatches\n{\n    class MyMod\n    {\n        name = \"My Mod\";\n        author = \"Me\";\n        requiredAddons[] = {};\n    };\n};\nclass CfgMusic\n{\n    class MyModSong0\n    {\n        name = \"Song\";\n        sound[] = {\"/MyMod/folderwithtracks/song.ogg\", 0.5, 1};\n        titles[] = {};\n    };\n};\n";
+
+    #[test]
+    fn test_parse_config_nested_classes() {
+        let root = parse_config(OLD_CONFIG).unwrap();
+        let patch = &root.class("cfgpatches").unwrap().classes[0];
+        assert_eq!(patch.name, "MyMod");
+        assert_eq!(patch.entry("name"), Some("\"My Mod\""));
+        let track = &root.class("CfgMusic").unwrap().classes[0];
+        assert_eq!(split_array(track.entry("sound[]").unwrap()), vec!["\"/MyMod/folderwithtracks/song.ogg\"", "0.5", "1"]);
+    }
+
+    #[test]
+    fn test_parse_config_reports_unbalanced_braces() {
+        assert!(parse_config("class CfgPatches\n{\n    class A\n    {\n").is_err());
+        assert!(parse_config("name = \"x\";\n};").is_err());
+    }
+
+
+    #[test]
+    fn test_collect_ogg_files_recursive() {
+        let dir = std::env::temp_dir().join(format!("zmm_collect_ogg_test_{}", std::process::id()));
+        let nested = dir.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        for path in [dir.join("2.ogg"), dir.join("notes.txt"), nested.join("1.OGG"), dir.join("a").join("3.ogg")] {
+            fs::write(path, b"x").unwrap();
+        }
+
+        let (files, limit_reached) = FileOperations::collect_ogg_files_recursive(&dir);
+        assert_eq!(files, vec![dir.join("2.ogg"), dir.join("a").join("3.ogg"), nested.join("1.OGG")]);
+        assert!(!limit_reached);
+        assert!(FileOperations::collect_ogg_files_recursive(&dir.join("missing")).0.is_empty());
+
+        // 恰好达到上限时不算超出，找到更多文件时才报告
+        assert!(!FileOperations::collect_ogg_files_limited(&dir, 3).1);
+        let (files, limit_reached) = FileOperations::collect_ogg_files_limited(&dir, 2);
+        assert_eq!(files.len(), 2);
+        assert!(limit_reached);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_auto_copy_buffer_size_scales_with_file_size() {
+        assert_eq!(FileOperations::auto_copy_buffer_size(0), file_ops::MIN_COPY_BUFFER_SIZE);
+        assert_eq!(FileOperations::auto_copy_buffer_size(16 * 1024 * 1024), 1024 * 1024);
+        assert_eq!(FileOperations::auto_copy_buffer_size(u64::MAX / 2), file_ops::MAX_COPY_BUFFER_SIZE);
+    }
+
+    /// 复制性能基准：`cargo test --release copy_benchmark -- --ignored --nocapture`
+    ///
+    /// 对比旧的固定 64KB 分块复制、按文件大小自动选择的缓冲区以及系统复制（自动模式）
+    #[test]
+    #[ignore]
+    fn copy_benchmark_large_file() {
+        let dir = std::env::temp_dir().join(format!("zmm_copy_bench_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("source.bin");
+        let size: usize = 256 * 1024 * 1024;
+        let chunk: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+        {
+            use std::io::Write;
+            let mut file = fs::File::create(&source).unwrap();
+            for _ in 0..size / chunk.len() {
+                file.write_all(&chunk).unwrap();
+            }
+        }
+
+        let modes = [
+            ("固定 64KB 缓冲区", Some(64 * 1024)),
+            ("自动缓冲区", Some(FileOperations::auto_copy_buffer_size(size as u64))),
+            ("系统复制（自动）", None),
+        ];
+        for (label, buffer_size) in modes {
+            let destination = dir.join("destination.bin");
+            let start = std::time::Instant::now();
+            FileOperations::copy_file_optimized(&source, &destination, buffer_size).unwrap();
+            let elapsed = start.elapsed();
+            assert_eq!(fs::metadata(&destination).unwrap().len(), size as u64);
+            println!(
+                "{}: {:.2?}（{:.0} MB/s）",
+                label,
+                elapsed,
+                size as f64 / 1024.0 / 1024.0 / elapsed.as_secs_f64()
+            );
+            fs::remove_file(&destination).unwrap();
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reexport_over_hardlink_keeps_source() {
+        let dir = std::env::temp_dir().join(format!("zmm_hardlink_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("source.ogg");
+        let destination = dir.join("out").join("track.ogg");
+        fs::write(&source, b"original").unwrap();
+
+        let linked = CopyOptions { use_hardlinks: true, buffer_size: None };
+        if FileOperations::link_or_copy_file(&source, &destination, linked).unwrap() {
+            // 上次导出留下的硬链接：改为复制后源文件不能被改写
+            for buffer_size in [None, Some(file_ops::MIN_COPY_BUFFER_SIZE)] {
+                let other = dir.join("other.ogg");
+                fs::write(&other, b"replacement").unwrap();
+                let copy = CopyOptions { use_hardlinks: false, buffer_size };
+                assert!(!FileOperations::link_or_copy_file(&other, &destination, copy).unwrap());
+                assert_eq!(fs::read(&destination).unwrap(), b"replacement");
+                assert_eq!(fs::read(&source).unwrap(), b"original");
+                FileOperations::link_or_copy_file(&source, &destination, linked).unwrap();
+            }
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_unique_filename_appends_counter_when_taken() {
+        let taken = ["song.ogg".to_string(), "song_1.ogg".to_string()];
+        let name = FileOperations::unique_filename("song", ".ogg", |candidate| taken.iter().any(|t| t == candidate));
+        assert_eq!(name, "song_2.ogg");
+        assert_eq!(FileOperations::unique_filename("other", ".ogg", |_| false), "other.ogg");
+    }
+
+    #[test]
+    fn test_preview_track_filenames_follows_naming_scheme() {
+        let dir = std::env::temp_dir().join(format!("zmm_naming_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut tracks = Vec::new();
+        for (file, name) in [("Nice Intro.ogg", "开场"), ("battle-01.ogg", "战斗")] {
+            let path = dir.join(file);
+            fs::write(&path, b"x").unwrap();
+            let mut track = Track::new(path, name.to_string(), "MyMod".to_string());
+            track.tag = "Combat".to_string();
+            tracks.push(track);
+        }
+
+        let project = ProjectSettings { class_name: "MyMod".to_string(), ..Default::default() };
+        let preview = |naming: &NamingScheme| FileOperations::preview_track_filenames(&project, &tracks, naming, false);
+
+        let pinyin = preview(&NamingScheme::Pinyin);
+        assert_eq!(pinyin[0], format!("{}.ogg", StringUtils::safe_filename_pinyin("开场", 0)));
+        let original = preview(&NamingScheme::Original);
+        assert_eq!(original, vec!["Nice Intro.ogg".to_string(), "battle-01.ogg".to_string()]);
+        // {class} 为导出时的配置类名
+        let classed = preview(&NamingScheme::Template("{tag}_{class}".to_string()));
+        assert_eq!(classed, vec!["Combat_MyModSong0.ogg".to_string(), "Combat_MyModSong1.ogg".to_string()]);
+        // 模板结果相同时追加数字后缀
+        let tagged = preview(&NamingScheme::Template("{tag}".to_string()));
+        assert_eq!(tagged, vec!["Combat.ogg".to_string(), "Combat_1.ogg".to_string()]);
+        let indexed = NamingScheme::Template("{index} {name}".to_string());
+        assert!(preview(&indexed)[1].starts_with("002 "));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_export_manifest_skips_missing_sources_without_shifting_entries() {
+        let dir = std::env::temp_dir().join(format!("zmm_manifest_missing_test_{}", std::process::id()));
+        let mod_dir = dir.join("@MyMod");
+        fs::create_dir_all(mod_dir.join("tracks")).unwrap();
+        let mut tracks = Vec::new();
+        for (file, name) in [("a.ogg", "A"), ("missing.ogg", "B"), ("c.ogg", "C")] {
+            let path = dir.join(file);
+            if file != "missing.ogg" {
+                fs::write(&path, name).unwrap();
+            }
+            tracks.push(Track::new(path, name.to_string(), "MyMod".to_string()));
+        }
+        let project = ProjectSettings::default();
+        let settings = ExportSettings { output_naming: NamingScheme::Original, ..Default::default() };
+
+        let (track_files, _) = FileOperations::copy_track_files_pinyin(
+            &project,
+            &tracks,
+            &mod_dir,
+            "tracks",
+            &settings,
+            CopyOptions::default(),
+            &|| false,
+            &|_, _| {},
+        )
+        .unwrap();
+        assert_eq!(track_files, vec![Some("a.ogg".to_string()), None, Some("c.ogg".to_string())]);
+        assert_eq!(FileOperations::exported_filenames(&track_files), vec!["a.ogg".to_string(), "c.ogg".to_string()]);
+
+        FileOperations::save_export_manifest(&mod_dir, &tracks, &track_files).unwrap();
+        let manifest = FileOperations::load_export_manifest(&mod_dir).unwrap();
+        assert_eq!(manifest.entries.len(), 2);
+        assert_eq!(manifest.entries[1].track_name, "C");
+        assert_eq!(manifest.entries[1].filename, "c.ogg");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_incremental_export_keeps_duplicated_tracks_apart() {
+        let dir = std::env::temp_dir().join(format!("zmm_manifest_duplicate_test_{}", std::process::id()));
+        let mod_dir = dir.join("@MyMod");
+        let tracks_dir = mod_dir.join("tracks");
+        fs::create_dir_all(&tracks_dir).unwrap();
+        let path = dir.join("song.ogg");
+        fs::write(&path, "song").unwrap();
+        let mut state = crate::models::AppState::default();
+        let project = state.project.clone();
+        assert!(state.add_track_with_duplicate_check(Track::new(path, "Song".to_string(), "MyMod".to_string())));
+        state.duplicate_track(0).unwrap();
+        let mut tracks = state.tracks.clone();
+        for track in &mut tracks {
+            track.dirty_since_export = false;
+        }
+        let settings = ExportSettings { output_naming: NamingScheme::Original, ..Default::default() };
+
+        let (first_files, _) = FileOperations::copy_track_files_pinyin(
+            &project,
+            &tracks,
+            &mod_dir,
+            "tracks",
+            &settings,
+            CopyOptions::default(),
+            &|| false,
+            &|_, _| {},
+        )
+        .unwrap();
+        assert_eq!(first_files, vec![Some("song.ogg".to_string()), Some("song_1.ogg".to_string())]);
+        FileOperations::save_export_manifest(&mod_dir, &tracks, &first_files).unwrap();
+
+        // 第二次导出：两个轨道都未更改，沿用各自的文件，不复制也不留下多余文件
+        let manifest = FileOperations::load_export_manifest(&mod_dir).unwrap();
+        let (second_files, changed_count) = FileOperations::copy_changed_track_files_pinyin(
+            &project,
+            &tracks,
+            &mod_dir,
+            "tracks",
+            &settings,
+            &manifest,
+            CopyOptions::default(),
+            &|| false,
+            &|_, _| {},
+        )
+        .unwrap();
+        assert_eq!(changed_count, 0);
+        assert_eq!(second_files, first_files);
+        let mut exported: Vec<String> = fs::read_dir(&tracks_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        exported.sort();
+        assert_eq!(exported, vec!["song.ogg".to_string(), "song_1.ogg".to_string()]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_export_mod_reports_success_and_cancellation() {
+        let dir = std::env::temp_dir().join(format!("zmm_export_job_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut tracks = Vec::new();
+        for name in ["a", "b"] {
+            let path = dir.join(format!("{}.ogg", name));
+            fs::write(&path, name).unwrap();
+            tracks.push(Track::new(path, name.to_string(), "MyMod".to_string()));
+        }
+        let job = ModExportJob {
+            project: ProjectSettings { mod_name: "MyMod".to_string(), ..Default::default() },
+            tracks,
+            video_files: Vec::new(),
+            export_settings: ExportSettings { use_stringtable: false, use_google_translate: false, ..Default::default() },
+            export_dir: dir.join("out"),
+            warnings: vec!["名称: 测试警告".to_string()],
+            result_prefix: String::new(),
+        };
+
+        // 第一个文件开始前取消：不生成配置也不保存导出记录
+        let outcome = FileOperations::export_mod(&job, &|| true, &|_, _| {});
+        assert!(outcome.cancelled);
+        assert!(!outcome.manifest_saved);
+        assert!(outcome.message.contains("已取消"));
+        let mod_dir = outcome.mod_dir.unwrap();
+        assert!(!mod_dir.join("config.cpp").exists());
+
+        let reported = std::sync::Mutex::new(Vec::new());
+        let outcome = FileOperations::export_mod(&job, &|| false, &|i: usize, _: &str| reported.lock().unwrap().push(i));
+        assert!(!outcome.cancelled);
+        assert!(outcome.manifest_saved, "{}", outcome.message);
+        assert!(outcome.message.starts_with("模组导出成功"));
+        assert!(outcome.message.contains("测试警告"));
+        assert_eq!(*reported.lock().unwrap(), vec![0, 1]);
+        assert!(mod_dir.join("config.cpp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_export_dir_overlap() {
+        let dir = std::env::temp_dir().join(format!("zmm_overlap_test_{}", std::process::id()));
+        let project = ProjectSettings { mod_name: "MyMod".to_string(), ..Default::default() };
+        let mod_dir_name = project.mod_name_no_spaces();
+        fs::create_dir_all(dir.join("library").join(&mod_dir_name)).unwrap();
+        let dir = fs::canonicalize(&dir).unwrap();
+        let library = dir.join("library");
+        let song = library.join("song.ogg");
+        let overlap = |export_dir: &Path, source: &Path| FileOperations::check_export_dir_overlap(&project, export_dir, &[source]);
+
+        // 导出目录就是源文件目录
+        assert_eq!(overlap(&library, &song), vec![ExportDirOverlap::ExportDirIsSource(library.clone())]);
+        // 导出目录位于源文件目录中
+        let nested = library.join("exports");
+        assert_eq!(overlap(&nested, &song), vec![ExportDirOverlap::OutputInsideSource(library.clone())]);
+        // 导出目录是源文件目录的上级，源文件位于模组输出目录中
+        let inside_mod = library.join(&mod_dir_name).join("song.ogg");
+        assert_eq!(
+            overlap(&library, &inside_mod),
+            vec![ExportDirOverlap::SourceInsideOutput(library.join(&mod_dir_name))]
+        );
+        // 导出目录是源文件目录的上级，但源文件不在模组输出目录中
+        assert!(overlap(&dir, &song).is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_export_dir_writable_leaves_no_probe_file() {
+        let dir = std::env::temp_dir().join(format!("zmm_writable_test_{}", std::process::id())).join("nested");
+        FileOperations::check_export_dir_writable(&dir).unwrap();
+        assert!(dir.is_dir());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+
+        let denied = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::PermissionDenied)).context("无法创建模组目录");
+        assert!(FileOperations::is_permission_denied(&denied));
+        let missing = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::NotFound));
+        assert!(!FileOperations::is_permission_denied(&missing));
+    }
+}
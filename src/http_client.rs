@@ -0,0 +1,400 @@
+/*!
+ * HTTP 客户端模块
+ * 统一处理镜像源切换、断点续传、校验和验证、代理设置与下载取消
+ */
+
+use anyhow::{anyhow, Context, Result};
+use indicatif::{ProgressBar, ProgressStyle};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 网络设置（跨会话持久化）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpSettings {
+    /// 代理地址（如 http://127.0.0.1:7890），为空表示不使用代理
+    pub proxy: String,
+    /// 连接超时（秒）
+    pub connect_timeout_secs: u64,
+    /// 单次请求总超时（秒）
+    pub timeout_secs: u64,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            proxy: String::new(),
+            connect_timeout_secs: 30,
+            timeout_secs: 300,
+        }
+    }
+}
+
+impl HttpSettings {
+    /// 获取有效的代理地址
+    pub fn proxy_url(&self) -> Option<&str> {
+        let proxy = self.proxy.trim();
+        if proxy.is_empty() {
+            None
+        } else {
+            Some(proxy)
+        }
+    }
+
+    /// 验证代理地址是否可用
+    pub fn validate(&self) -> Result<()> {
+        if let Some(proxy) = self.proxy_url() {
+            reqwest::Proxy::all(proxy)
+                .with_context(|| format!("无效的代理地址: {}", proxy))?;
+        }
+        Ok(())
+    }
+}
+
+/// 下载镜像源
+#[derive(Debug, Clone)]
+pub struct Mirror {
+    /// 显示名称
+    pub name: String,
+    /// 下载地址
+    pub url: String,
+}
+
+impl Mirror {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+        }
+    }
+}
+
+/// 下载被取消
+#[derive(Debug)]
+pub struct DownloadCancelled;
+
+impl std::fmt::Display for DownloadCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "下载被取消")
+    }
+}
+
+impl std::error::Error for DownloadCancelled {}
+
+/// 下载进度
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DownloadProgress {
+    /// 进度百分比（总大小未知时为 0）
+    pub percent: f64,
+    /// 已下载字节数（含断点续传前已下载的部分）
+    pub downloaded: u64,
+    /// 文件总大小，服务器未提供时为 None
+    pub total: Option<u64>,
+}
+
+impl DownloadProgress {
+    /// 根据已下载字节数和总大小（0 表示未知）构造进度
+    pub fn bytes(downloaded: u64, total_size: u64) -> Self {
+        Self {
+            percent: HttpClient::percent(downloaded, total_size),
+            downloaded,
+            total: (total_size > 0).then_some(total_size),
+        }
+    }
+
+    /// 已完成下载（进入校验/解压等后续阶段）
+    pub fn finished(downloaded: u64, total_size: u64) -> Self {
+        Self {
+            percent: 100.0,
+            ..Self::bytes(downloaded, total_size)
+        }
+    }
+}
+
+/// 判断错误是否由取消下载引起
+pub fn is_cancelled(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<DownloadCancelled>().is_some()
+}
+
+/// 网络停滞时检查取消标志的间隔
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// HTTP 客户端
+///
+/// 进度回调签名为 `Fn(下载进度, 状态文本) -> Result<()>`，回调返回错误表示取消下载；
+/// 下载过程中还会在每次读取数据块之间调用 `should_cancel`，取消时删除未完成的临时文件。
+pub struct HttpClient {
+    client: reqwest::Client,
+}
+
+impl HttpClient {
+    /// 根据网络设置创建客户端
+    pub fn new(settings: &HttpSettings) -> Result<Self> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_secs))
+            .connect_timeout(Duration::from_secs(settings.connect_timeout_secs))
+            .tcp_keepalive(Duration::from_secs(60)) // TCP保活
+            .pool_max_idle_per_host(10); // 连接池优化
+
+        if let Some(proxy) = settings.proxy_url() {
+            info!("使用代理: {}", proxy);
+            let proxy = reqwest::Proxy::all(proxy)
+                .with_context(|| format!("无效的代理地址: {}", proxy))?;
+            builder = builder.proxy(proxy);
+        }
+
+        let client = builder.build().context("无法创建 HTTP 客户端")?;
+        Ok(Self { client })
+    }
+
+    /// 获取文本内容
+    pub async fn get_text(&self, url: &str) -> Result<String> {
+        let response = self.client.get(url).send().await
+            .with_context(|| format!("请求失败: {}", url))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("请求失败: HTTP {} ({})", response.status(), url));
+        }
+        Ok(response.text().await?)
+    }
+
+    /// 依次尝试各镜像获取文本内容
+    pub async fn get_text_with_mirrors<'a>(&self, mirrors: &'a [Mirror]) -> Result<(&'a Mirror, String)> {
+        for mirror in mirrors {
+            match self.get_text(&mirror.url).await {
+                Ok(text) => return Ok((mirror, text)),
+                Err(e) => warn!("从 {} 获取失败: {}", mirror.name, e),
+            }
+        }
+        Err(anyhow!("所有镜像源都无法访问"))
+    }
+
+    /// 依次尝试各镜像下载文件，返回下载成功的镜像
+    pub async fn download_with_mirrors<'a, F, C>(
+        &self,
+        mirrors: &'a [Mirror],
+        dest: &Path,
+        expected_sha256: Option<&str>,
+        progress_callback: F,
+        should_cancel: C,
+    ) -> Result<&'a Mirror>
+    where
+        F: Fn(DownloadProgress, &str) -> Result<()>,
+        C: Fn() -> bool,
+    {
+        for (index, mirror) in mirrors.iter().enumerate() {
+            info!("尝试从 {} 下载: {}", mirror.name, mirror.url);
+            Self::report(&progress_callback, DownloadProgress::default(), &format!("正在连接 {}...", mirror.name))?;
+
+            match self.download_file(&mirror.url, dest, expected_sha256, &progress_callback, &should_cancel).await {
+                Ok(()) => {
+                    info!("从 {} 下载成功", mirror.name);
+                    return Ok(mirror);
+                }
+                Err(e) if is_cancelled(&e) => return Err(e),
+                Err(e) => {
+                    warn!("从 {} 下载失败: {}", mirror.name, e);
+                    if index < mirrors.len() - 1 {
+                        info!("尝试下一个下载源...");
+                        Self::report(&progress_callback, DownloadProgress::default(), &format!("{} 失败，尝试下一个源...", mirror.name))?;
+                    }
+                }
+            }
+        }
+
+        Err(anyhow!("所有下载源都失败了，请检查网络连接或代理设置"))
+    }
+
+    /// 下载单个文件（支持断点续传和 SHA-256 校验）
+    ///
+    /// 未完成的数据保存在 `<dest>.part` 中，下次下载时从断点继续。
+    pub async fn download_file<F, C>(
+        &self,
+        url: &str,
+        dest: &Path,
+        expected_sha256: Option<&str>,
+        progress_callback: F,
+        should_cancel: C,
+    ) -> Result<()>
+    where
+        F: Fn(DownloadProgress, &str) -> Result<()>,
+        C: Fn() -> bool,
+    {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("无法创建下载目录: {:?}", parent))?;
+        }
+
+        let part_path = Self::part_path(dest);
+        let existing = fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+
+        let mut request = self.client.get(url);
+        if existing > 0 {
+            info!("发现未完成的下载（{} bytes），尝试断点续传", existing);
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+        }
+
+        let mut response = request.send().await
+            .with_context(|| format!("请求失败: {}", url))?;
+        let status = response.status();
+
+        if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            // 临时文件与服务器文件不一致，清除后由下一次尝试重新下载
+            let _ = fs::remove_file(&part_path);
+            return Err(anyhow!("断点续传失败，已清除临时文件"));
+        }
+        if !status.is_success() {
+            return Err(anyhow!("下载失败: HTTP {}", status));
+        }
+
+        let resumed = existing > 0 && status == reqwest::StatusCode::PARTIAL_CONTENT;
+        let mut downloaded = if resumed { existing } else { 0 };
+        let total_size = response.content_length().map(|len| len + downloaded).unwrap_or(0);
+        info!("下载文件大小: {} bytes（已下载 {} bytes）", total_size, downloaded);
+
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(&part_path)
+            .with_context(|| format!("无法创建临时文件: {:?}", part_path))?;
+        // 使用缓冲写入以提高I/O效率
+        let mut writer = BufWriter::with_capacity(64 * 1024, file); // 64KB 缓冲区
+
+        // 创建进度条
+        let progress_bar = if total_size > 0 {
+            ProgressBar::new(total_size)
+        } else {
+            ProgressBar::new_spinner()
+        };
+        if let Ok(style) = ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+        {
+            progress_bar.set_style(style.progress_chars("#>-"));
+        }
+        progress_bar.set_position(downloaded);
+
+        let initial_status = if resumed { "继续下载..." } else { "开始下载..." };
+        Self::report(&progress_callback, DownloadProgress::bytes(downloaded, total_size), initial_status)?;
+
+        let mut chunk_count = 0;
+        loop {
+            if should_cancel() {
+                return Err(Self::abort_download(writer, &progress_bar, &part_path));
+            }
+            // 限时等待数据块，网络停滞时也能及时响应取消
+            let chunk = match tokio::time::timeout(CANCEL_POLL_INTERVAL, response.chunk()).await {
+                Ok(chunk) => chunk?,
+                Err(_) => continue,
+            };
+            let Some(chunk) = chunk else { break };
+            writer.write_all(&chunk)?;
+            downloaded += chunk.len() as u64;
+            chunk_count += 1;
+            progress_bar.set_position(downloaded);
+
+            // 每 10 个块回调一次，同时作为取消检查点
+            if chunk_count % 10 == 0 {
+                let progress = DownloadProgress::bytes(downloaded, total_size);
+                let status = if total_size > 0 {
+                    format!("下载中... {:.1}% ({}/{} bytes)", progress.percent, downloaded, total_size)
+                } else {
+                    format!("下载中... {} bytes", downloaded)
+                };
+                if Self::report(&progress_callback, progress, &status).is_err() {
+                    return Err(Self::abort_download(writer, &progress_bar, &part_path));
+                }
+            }
+        }
+
+        writer.flush()?;
+        drop(writer);
+        progress_bar.finish_with_message("下载完成");
+
+        if let Some(expected) = expected_sha256 {
+            Self::report(&progress_callback, DownloadProgress::finished(downloaded, total_size), "正在校验文件...")?;
+            let actual = Self::sha256_file(&part_path)?;
+            if !actual.eq_ignore_ascii_case(expected.trim()) {
+                let _ = fs::remove_file(&part_path);
+                return Err(anyhow!("文件校验失败: 期望 {}，实际 {}", expected.trim(), actual));
+            }
+            info!("SHA-256 校验通过: {}", actual);
+        }
+
+        if dest.exists() {
+            fs::remove_file(dest)
+                .with_context(|| format!("无法覆盖已有文件: {:?}", dest))?;
+        }
+        fs::rename(&part_path, dest)
+            .with_context(|| format!("无法保存下载文件: {:?}", dest))?;
+        Ok(())
+    }
+
+    /// 取消下载：关闭并删除未完成的临时文件，返回取消错误
+    fn abort_download(writer: BufWriter<fs::File>, progress_bar: &ProgressBar, part_path: &Path) -> anyhow::Error {
+        drop(writer);
+        progress_bar.abandon();
+        if let Err(e) = fs::remove_file(part_path) {
+            warn!("无法删除未完成的下载文件 {:?}: {}", part_path, e);
+        }
+        info!("下载已取消，已删除临时文件: {:?}", part_path);
+        anyhow::Error::new(DownloadCancelled)
+    }
+
+    /// 计算文件的 SHA-256（小写十六进制）
+    pub fn sha256_file(path: &Path) -> Result<String> {
+        let mut file = fs::File::open(path)
+            .with_context(|| format!("无法打开文件: {:?}", path))?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+        Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// 从 `sha256sum` 格式的清单中查找指定文件的校验和
+    pub fn find_checksum(checksums: &str, file_name: &str) -> Option<String> {
+        checksums.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let name = parts.next()?.trim_start_matches('*');
+            (name == file_name && hash.len() == 64).then(|| hash.to_lowercase())
+        })
+    }
+
+    /// 断点续传临时文件路径
+    fn part_path(dest: &Path) -> PathBuf {
+        let mut name = dest.file_name().unwrap_or_default().to_os_string();
+        name.push(".part");
+        dest.with_file_name(name)
+    }
+
+    fn percent(downloaded: u64, total_size: u64) -> f64 {
+        if total_size > 0 {
+            (downloaded as f64 / total_size as f64) * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    /// 调用进度回调，回调失败视为取消
+    fn report<F>(progress_callback: &F, progress: DownloadProgress, status: &str) -> Result<()>
+    where
+        F: Fn(DownloadProgress, &str) -> Result<()>,
+    {
+        progress_callback(progress, status).map_err(|e| {
+            info!("下载已取消: {}", e);
+            anyhow::Error::new(DownloadCancelled)
+        })
+    }
+}
@@ -0,0 +1,560 @@
+/*!
+ * 界面语言模块
+ * 简体中文/英文字符串表，`tr(key)` 按当前语言查找，缺失的条目回退到中文
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 界面语言（跨会话持久化）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Lang {
+    /// 简体中文
+    #[default]
+    Zh,
+    /// 英文
+    En,
+}
+
+impl Lang {
+    /// 所有可选语言（菜单顺序）
+    pub const ALL: [Lang; 2] = [Lang::Zh, Lang::En];
+
+    /// 显示名称（始终使用该语言本身的名称）
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Lang::Zh => "简体中文",
+            Lang::En => "English",
+        }
+    }
+
+    fn table(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::Zh => ZH,
+            Lang::En => EN,
+        }
+    }
+}
+
+/// 当前界面语言（每帧由 AppState 同步）
+static CURRENT_LANG: AtomicU8 = AtomicU8::new(0);
+
+/// 设置当前界面语言
+pub fn set_language(lang: Lang) {
+    CURRENT_LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+/// 当前界面语言
+pub fn language() -> Lang {
+    match CURRENT_LANG.load(Ordering::Relaxed) {
+        1 => Lang::En,
+        _ => Lang::Zh,
+    }
+}
+
+/// 按当前语言查找字符串
+pub fn tr(key: &str) -> &str {
+    tr_in(language(), key)
+}
+
+/// 按指定语言查找字符串；缺失时回退到中文，中文也缺失时返回键本身
+pub fn tr_in(lang: Lang, key: &str) -> &str {
+    lookup(lang.table(), key)
+        .or_else(|| lookup(ZH, key))
+        .unwrap_or(key)
+}
+
+/// 查找字符串并依次用参数替换其中的 `{}`
+pub fn trf(key: &str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut parts = tr(key).split("{}");
+    let mut result = parts.next().unwrap_or_default().to_string();
+    let mut args = args.iter();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            let _ = write!(result, "{}", arg);
+        }
+        result.push_str(part);
+    }
+    result
+}
+
+fn lookup(table: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, text)| *text)
+}
+
+/// 简体中文字符串表（完整，作为回退）
+const ZH: &[(&str, &str)] = &[
+    // 通用
+    ("common.ok", "确定"),
+    ("common.cancel", "取消"),
+    ("common.close", "关闭"),
+    ("common.not_set", "未设置"),
+    ("common.author", "作者:"),
+    ("common.use_default_logo", "使用默认Logo"),
+    ("common.copy", "复制"),
+    ("common.move_up", "上移"),
+    ("common.move_down", "下移"),
+    ("common.remove", "移除"),
+    // 菜单栏
+    ("menu.file", "文件"),
+    ("menu.project_settings", "项目设置"),
+    ("menu.general", "常规"),
+    ("menu.add_logo", "添加封面图片 (.paa)"),
+    ("menu.open_project", "打开项目..."),
+    ("menu.open_project_hint", "打开 .zmm 项目文件，替换当前项目和轨道列表"),
+    ("menu.save_project_as", "另存为项目..."),
+    ("menu.save_project_as_hint", "将项目设置、轨道列表和导出设置保存为 .zmm 文件"),
+    ("menu.import_playlist", "导入播放列表..."),
+    ("menu.import_playlist_hint", "从 M3U/M3U8/PLS 播放列表按顺序添加轨道"),
+    ("menu.paste_paths", "从剪贴板粘贴路径"),
+    ("menu.paste_paths_hint", "读取剪贴板中每行一个的文件路径列表来添加轨道"),
+    ("menu.copy_paths", "复制轨道路径到剪贴板"),
+    ("menu.copy_paths_hint", "将所有轨道的源文件路径（每行一个）复制到剪贴板"),
+    ("menu.export", "导出..."),
+    ("menu.prepare_zeus", "为 Zeus 一键准备并导出..."),
+    ("menu.recent_export_dirs", "最近导出目录"),
+    ("menu.export_here", "导出到此目录"),
+    ("menu.clear_list", "清空列表"),
+    ("menu.confirm_on_close", "关闭时确认未完成的工作"),
+    ("menu.confirm_on_close_hint", "有运行中的任务或未导出的轨道修改时，关闭窗口前先询问"),
+    ("menu.exit", "退出"),
+    ("menu.view", "视图"),
+    ("menu.theme", "主题:"),
+    ("menu.tools", "工具"),
+    ("menu.mod_type", "模组类型:"),
+    ("menu.music_mod", "🎵 音乐模组"),
+    ("menu.video_mod", "🎬 视频模组"),
+    ("menu.build_addon", "构建插件..."),
+    ("menu.addon_source", "生成插件源文件..."),
+    ("menu.addon_source_hint", "生成未打包的插件源文件夹（$PBOPREFIX$、config.cpp 和轨道），可用 Addon Builder 打包为 PBO"),
+    ("menu.container_mod", "创建容器模组..."),
+    ("menu.repair_config", "修复旧配置..."),
+    ("menu.repair_config_hint", "将旧版本导出或手动编辑的音乐模组配置重写为当前格式（会先备份原文件）"),
+    ("menu.paa_converter", "转换图片为PAA..."),
+    ("menu.audio_decrypt", "音频解密..."),
+    ("menu.audio_convert", "音频格式转换..."),
+    ("menu.video_convert", "视频格式转换..."),
+    ("menu.conversion_history", "转换历史 ({})..."),
+    ("menu.ffmpeg_plugin", "FFmpeg 插件管理..."),
+    ("menu.performance_settings", "性能设置..."),
+    ("menu.similar_tracks", "查找相似轨道..."),
+    ("menu.chapter_split", "按章节分割..."),
+    ("menu.sync_durations", "同步全部时长"),
+    ("menu.sync_durations_hint", "解码每个轨道，将时长设置为实际音频时长"),
+    ("menu.batch_tag", "批量设置标签..."),
+    ("menu.batch_tag_hint", "为选中的轨道设置同一标签（Ctrl/Shift+单击可多选）"),
+    ("menu.decibel_adjust", "统一调整音量..."),
+    ("menu.decibel_adjust_hint", "对所有轨道统一增减分贝或设为同一值"),
+    ("menu.decibel_ramp", "分贝渐变..."),
+    ("menu.decibel_ramp_hint", "按列表顺序从第一个到最后一个轨道逐步调整分贝"),
+    ("menu.compare_projects", "比较项目..."),
+    ("menu.ingame_order", "预览游戏内顺序..."),
+    ("menu.ingame_order_hint", "按导出顺序查看轨道在 Zeus 中显示的名称"),
+    ("menu.playlist_player", "试听播放列表..."),
+    ("menu.playlist_player_hint", "按导出顺序连续播放所有轨道（应用分贝和裁剪）"),
+    ("menu.track_count", "轨道计数"),
+    ("menu.undo", "撤销"),
+    ("menu.undo_hint", "恢复上一次删除、清空、批量设置标签或调整顺序前的轨道列表"),
+    ("menu.clear_tracks", "清空所有轨道"),
+    ("menu.clear_videos", "清空所有视频"),
+    ("menu.help", "帮助"),
+    ("menu.user_guide", "📖 新用户指导"),
+    ("menu.about", "ℹ️ 关于"),
+    ("menu.language", "语言:"),
+    // 项目设置
+    ("project.title", "项目设置"),
+    ("project.basic_info", "基本信息"),
+    ("project.mod_name", "模组名称:"),
+    ("project.mod_name_rule", "仅允许英文、数字、空格和 - _ . 符号"),
+    ("project.tracks_folder", "轨道文件夹:"),
+    ("project.tracks_folder_hint", "导出时存放音频文件的文件夹名称，配置中的 sound[] 路径会指向该文件夹"),
+    ("project.tracks_folder_sanitized", "将使用: {}（只允许英文字母、数字和下划线）"),
+    ("project.workshop", "Steam 创意工坊"),
+    ("project.workshop_id", "物品ID:"),
+    ("project.workshop_id_hint", "已发布物品的 publishedid，导出时写入 meta.cpp；首次上传前留空"),
+    ("project.workshop_tags", "标签:"),
+    ("performance.title", "性能设置"),
+    ("performance.limit_threads", "限制并行转换的最大线程数"),
+    ("performance.threads", "线程数"),
+    ("performance.auto_hint", "未限制时根据CPU核心数和文件大小自动选择线程数"),
+    ("performance.cores", "本机逻辑核心数: {}"),
+    ("performance.game_warning", "⚠ 线程数越高转换越快，但同时运行的游戏（如 Arma 3）可能出现卡顿"),
+    ("performance.next_task_hint", "设置对之后启动的音频/视频转换任务生效"),
+    ("project.logo_settings", "Logo设置"),
+    ("project.logo_path", "Logo路径:"),
+    ("project.use_cover_logo", "使用轨道封面作为Logo"),
+    ("project.use_cover_logo_hint", "使用 {} 的内嵌封面"),
+    ("project.no_cover", "已加载的轨道均无内嵌封面"),
+    ("project.checking_cover", "正在检查轨道封面..."),
+    ("project.track_defaults", "新轨道默认值"),
+    ("project.default_decibels", "默认分贝 (dB):"),
+    ("project.fallback_duration", "回退时长 (秒):"),
+    ("project.fallback_duration_hint", "无法读取音频时长时使用"),
+    ("project.cover_paa", "导入时为带封面的文件生成同名 .paa"),
+    ("project.cover_paa_hint", "提取内嵌封面并转换为 PAA，保存在音频文件旁（没有封面的文件跳过）"),
+    ("project.scan_loudness", "导入时扫描响度并建议分贝值"),
+    ("project.scan_loudness_hint", "解码整个文件计算均方根电平，用建议值代替默认分贝（大量文件时较慢）"),
+    ("project.read_tag_names", "从标签读取名称"),
+    ("project.read_tag_names_hint", "导入时优先使用 TITLE 标签作为曲目名称，没有标签时使用文件名；输出文件名仍使用拼音"),
+    ("project.invalid_name", "⚠ 模组名称必须只包含英文字符"),
+    ("project.invalid_name_hint", "请先输入有效的英文模组名称"),
+    // 导出设置
+    ("export.title", "导出设置"),
+    ("export.info", "导出信息"),
+    ("export.folder_note", "模组将在选择的导出目录下创建名为 {} 的文件夹。"),
+    ("export.options", "导出选项"),
+    ("export.append_tags", "在轨道名称前添加标签"),
+    ("export.tag_class_names", "将标签加入轨道类名（按类名排序时分组）"),
+    ("export.tag_class_names_warning", "⚠️ 类名会变为如 Combat_MyModSong0；引用旧类名的任务脚本或其他模组需要同步修改。"),
+    ("export.stringtable", "使用 Stringtable 本地化（支持中文曲目名，推荐）"),
+    ("export.stringtable_note", "将生成 stringtable.xml，配置中使用 $STR_ 键；游戏语言为简体中文/繁体中文时显示中文曲名。"),
+    ("export.google_translate", "使用 Google 翻译生成英文回退（需联网，失败则用拼音）"),
+    ("export.output_naming", "导出文件名:"),
+    ("export.naming_pinyin", "轨道名称拼音"),
+    ("export.naming_original", "保留源文件名"),
+    ("export.naming_template", "模板"),
+    ("export.naming_template_note", "可用变量：{index}（序号，从 001 开始）、{tag}、{name}（轨道名称）、{class}（轨道的 CfgMusic 类名，如 MyModSong0）；结果会转换为 ASCII 安全的文件名，重名时追加数字后缀。"),
+    ("export.naming_example", "示例: {}"),
+    ("export.only_changed", "仅导出已更改"),
+    ("export.only_changed_note", "仅复制自上次导出以来已更改的轨道（当前 {} 个），其余文件保留在已有模组文件夹中，配置文件会重新生成。"),
+    ("export.hardlinks", "同盘使用硬链接"),
+    ("export.hardlinks_warning", "⚠️ 硬链接与源文件共享数据，编辑源文件会同时改变已导出的文件。"),
+    ("export.hardlinks_note", "导出目录与源文件在同一磁盘时创建硬链接，节省空间并加快导出。"),
+    ("export.copy_buffer", "复制缓冲区 (KB，0 为自动):"),
+    ("export.copy_buffer_note", "自动模式使用系统优化的复制方式；大文件较多或网络磁盘上可尝试 1024 KB 以上的缓冲区。"),
+    ("export.export", "导出"),
+    ("export.preview_config", "预览配置"),
+    ("export.preview_title", "配置预览"),
+    ("export.preview_note", "按当前导出选项生成，文件名按导出到空目录时的命名方式推算。"),
+    // 关于
+    ("about.title", "关于"),
+    ("about.app_name", "宙斯音乐制作器"),
+    ("about.version", "版本:"),
+    ("about.email", "邮箱:"),
+    ("about.uptime", "运行时长:"),
+    ("about.uptime_secs", "{}秒"),
+    ("about.uptime_mins", "{}分{}秒"),
+    ("about.uptime_hours", "{}小时{}分"),
+    ("about.description", "软件描述"),
+    ("about.description_line1", "增强版Arma 3音乐模组生成工具"),
+    ("about.description_line2", "用于自动生成Arma 3音乐模组文件结构"),
+    // 轨道列表
+    ("tracks.search_hint", "搜索轨道..."),
+    ("tracks.clear_search", "清除搜索"),
+    ("tracks.shown_count", "显示 {} / {} 条"),
+    ("tracks.add_folder", "添加文件夹"),
+    ("tracks.add_folder_hover", "递归添加所选文件夹及其子文件夹中的所有 OGG 文件（应用当前的导入过滤条件）"),
+    ("tracks.import_folder", "从文件夹导入..."),
+    // 从文件夹导入
+    ("folder_import.title", "从文件夹导入"),
+    ("folder_import.folder", "文件夹:"),
+    ("folder_import.not_selected", "未选择"),
+    ("folder_import.browse", "选择..."),
+    ("folder_import.recursive_note", "将递归扫描所有子文件夹中的 OGG 文件"),
+    ("folder_import.filters", "过滤条件（作为默认设置保存）:"),
+    ("folder_import.include", "包含模式:"),
+    ("folder_import.include_hover", "分号分隔，支持 * 和 ?，为空表示全部包含"),
+    ("folder_import.exclude", "排除模式:"),
+    ("folder_import.exclude_hover", "分号分隔，例如 *sample*;*preview*"),
+    ("folder_import.min_size", "最小文件大小:"),
+    ("folder_import.min_duration", "最短时长:"),
+    ("folder_import.seconds_suffix", " 秒"),
+    ("folder_import.import", "导入"),
+    // PAA 转换器
+    ("paa.title", "图片转PAA转换器"),
+    ("paa.heading", "图片转PAA格式转换"),
+    ("paa.select_files", "选择图片文件 (支持多选)"),
+    ("paa.image_filter", "图片文件"),
+    ("paa.select_files_title", "选择要转换的图片文件"),
+    ("paa.select_output", "选择输出目录"),
+    ("paa.select_output_title", "选择PAA文件输出目录"),
+    ("paa.added_with_duplicates", "添加了 {} 个文件，跳过了 {} 个重复文件"),
+    ("paa.added", "成功添加了 {} 个文件"),
+    ("paa.no_files", "未选择任何文件"),
+    ("paa.selected_with_duplicates", "⚠️ 已选择 {} 个文件（其中 {} 个重复）:"),
+    ("paa.selected", "已选择 {} 个文件:"),
+    ("paa.output_dir", "输出目录: {}"),
+    ("paa.options", "转换选项"),
+    ("paa.crop_pow2", "裁剪到2的次方尺寸 (推荐)"),
+    ("paa.target_size", "目标尺寸:"),
+    ("paa.auto_size", "自动选择"),
+    ("paa.custom_size", "自定义尺寸:"),
+    ("paa.custom_size_hint", "如 2048"),
+    ("paa.apply", "应用"),
+    ("paa.invalid_size", "请输入有效的正整数尺寸"),
+    ("paa.current_size", "当前: {}x{}"),
+    ("paa.auto_rounding", "自动取整:"),
+    ("paa.max_size", "上限:"),
+    ("paa.auto_note", "自动选择按源图边长（居中裁剪时为短边）对齐到2的次方：向下取整不会放大图片，向上取整会放大；结果不超过上限"),
+    ("paa.size_rule", "尺寸必须为2的次方，范围 {}-{}，其他值会自动调整为最接近的有效尺寸"),
+    ("paa.crop_mode", "裁剪方式:"),
+    ("paa.center_crop", "居中裁剪 (推荐)"),
+    ("paa.keep_ratio", "保持原始比例"),
+    ("paa.no_upscale", "不放大：超出源图时限制为源图对应的2的次方"),
+    ("paa.no_upscale_hint", "避免小图被放大到较大的目标尺寸而变模糊；可在“预览效果”中查看每个文件的实际尺寸"),
+    ("paa.compression", "压缩格式:"),
+    ("paa.compression_hint", "自动：图片含透明像素时使用 DXT5，否则使用体积更小的 DXT1"),
+    ("paa.mipmaps", "生成 mipmap（推荐）"),
+    ("paa.mipmaps_hint", "写入逐级减半到 1x1 的缩小纹理，避免远处显示模糊或闪烁；关闭时只写入原尺寸一级"),
+    ("paa.formats", "支持的图片格式: PNG, JPG, JPEG, BMP, TGA, TIFF, WEBP"),
+    ("paa.restore_defaults", "恢复默认设置"),
+    ("paa.start", "开始转换"),
+    ("paa.task_running", "任务进行中"),
+    ("paa.need_files", "请先选择文件和输出目录"),
+    ("paa.preview", "预览效果"),
+];
+
+/// 英文字符串表（缺失的条目回退到中文）
+const EN: &[(&str, &str)] = &[
+    // 通用
+    ("common.ok", "OK"),
+    ("common.cancel", "Cancel"),
+    ("common.close", "Close"),
+    ("common.not_set", "Not set"),
+    ("common.author", "Author:"),
+    ("common.use_default_logo", "Use default logo"),
+    ("common.copy", "Copy"),
+    ("common.move_up", "Move up"),
+    ("common.move_down", "Move down"),
+    ("common.remove", "Remove"),
+    // 菜单栏
+    ("menu.file", "File"),
+    ("menu.project_settings", "Project Settings"),
+    ("menu.general", "General"),
+    ("menu.add_logo", "Add Cover Image (.paa)"),
+    ("menu.open_project", "Open Project..."),
+    ("menu.open_project_hint", "Open a .zmm project file, replacing the current project and track list"),
+    ("menu.save_project_as", "Save Project As..."),
+    ("menu.save_project_as_hint", "Save project settings, track list and export settings as a .zmm file"),
+    ("menu.import_playlist", "Import Playlist..."),
+    ("menu.import_playlist_hint", "Add tracks in order from an M3U/M3U8/PLS playlist"),
+    ("menu.paste_paths", "Paste Paths from Clipboard"),
+    ("menu.paste_paths_hint", "Read a list of file paths, one per line, from the clipboard to add tracks"),
+    ("menu.copy_paths", "Copy Track Paths to Clipboard"),
+    ("menu.copy_paths_hint", "Copy the source file paths of all tracks, one per line, to the clipboard"),
+    ("menu.export", "Export..."),
+    ("menu.prepare_zeus", "Prepare for Zeus and Export..."),
+    ("menu.recent_export_dirs", "Recent Export Folders"),
+    ("menu.export_here", "Export to this folder"),
+    ("menu.clear_list", "Clear List"),
+    ("menu.confirm_on_close", "Confirm unfinished work on close"),
+    ("menu.confirm_on_close_hint", "Ask before closing while a task is running or track changes have not been exported"),
+    ("menu.exit", "Exit"),
+    ("menu.view", "View"),
+    ("menu.theme", "Theme:"),
+    ("menu.tools", "Tools"),
+    ("menu.mod_type", "Mod type:"),
+    ("menu.music_mod", "🎵 Music Mod"),
+    ("menu.video_mod", "🎬 Video Mod"),
+    ("menu.build_addon", "Build Addon..."),
+    ("menu.addon_source", "Generate Addon Source..."),
+    ("menu.addon_source_hint", "Creates an unpacked addon source folder ($PBOPREFIX$, config.cpp and tracks) that Addon Builder can pack into a PBO"),
+    ("menu.container_mod", "Create Container Mod..."),
+    ("menu.repair_config", "Repair Old Config..."),
+    ("menu.repair_config_hint", "Rewrite music mod configs from older versions or manual edits in the current format (the original is backed up first)"),
+    ("menu.paa_converter", "Convert Images to PAA..."),
+    ("menu.audio_decrypt", "Decrypt Audio..."),
+    ("menu.audio_convert", "Convert Audio..."),
+    ("menu.video_convert", "Convert Video..."),
+    ("menu.conversion_history", "Conversion History ({})..."),
+    ("menu.ffmpeg_plugin", "FFmpeg Plugin Manager..."),
+    ("menu.performance_settings", "Performance Settings..."),
+    ("menu.similar_tracks", "Find Similar Tracks..."),
+    ("menu.chapter_split", "Split by Chapters..."),
+    ("menu.sync_durations", "Sync All Durations"),
+    ("menu.sync_durations_hint", "Decode every track and set its duration to the actual audio length"),
+    ("menu.batch_tag", "Batch Set Tag..."),
+    ("menu.batch_tag_hint", "Set the same tag on the selected tracks (Ctrl/Shift+click to multi-select)"),
+    ("menu.decibel_adjust", "Adjust All Volumes..."),
+    ("menu.decibel_adjust_hint", "Shift every track's decibels by the same amount or set them all to one value"),
+    ("menu.decibel_ramp", "Decibel Ramp..."),
+    ("menu.decibel_ramp_hint", "Gradually adjust decibels from the first to the last track in list order"),
+    ("menu.compare_projects", "Compare Projects..."),
+    ("menu.ingame_order", "Preview In-Game Order..."),
+    ("menu.ingame_order_hint", "See the names tracks will show in Zeus, in export order"),
+    ("menu.playlist_player", "Preview Playlist..."),
+    ("menu.playlist_player_hint", "Play all tracks in export order (with decibels and trims applied)"),
+    ("menu.track_count", "Track Count"),
+    ("menu.undo", "Undo"),
+    ("menu.undo_hint", "Restore the track list from before the last delete, clear, batch tag or reorder"),
+    ("menu.clear_tracks", "Clear All Tracks"),
+    ("menu.clear_videos", "Clear All Videos"),
+    ("menu.help", "Help"),
+    ("menu.user_guide", "📖 Getting Started"),
+    ("menu.about", "ℹ️ About"),
+    ("menu.language", "Language:"),
+    // 项目设置
+    ("project.title", "Project Settings"),
+    ("project.basic_info", "Basic Info"),
+    ("project.mod_name", "Mod name:"),
+    ("project.mod_name_rule", "Only English letters, digits, spaces and - _ . are allowed"),
+    ("project.tracks_folder", "Tracks folder:"),
+    ("project.tracks_folder_hint", "Folder the audio files are exported to; the sound[] paths in the config point at it"),
+    ("project.tracks_folder_sanitized", "Will use: {} (only English letters, digits and underscores)"),
+    ("project.workshop", "Steam Workshop"),
+    ("project.workshop_id", "Item ID:"),
+    ("project.workshop_id_hint", "publishedid of the published item, written to meta.cpp on export; leave empty before the first upload"),
+    ("project.workshop_tags", "Tags:"),
+    ("performance.title", "Performance Settings"),
+    ("performance.limit_threads", "Limit the maximum number of conversion threads"),
+    ("performance.threads", "Threads"),
+    ("performance.auto_hint", "When not limited, the thread count is chosen from the CPU core count and file sizes"),
+    ("performance.cores", "Logical cores on this machine: {}"),
+    ("performance.game_warning", "⚠ More threads convert faster, but games running at the same time (such as Arma 3) may stutter"),
+    ("performance.next_task_hint", "Applies to audio/video conversions started afterwards"),
+    ("project.logo_settings", "Logo"),
+    ("project.logo_path", "Logo path:"),
+    ("project.use_cover_logo", "Use Track Cover as Logo"),
+    ("project.use_cover_logo_hint", "Use the embedded cover of {}"),
+    ("project.no_cover", "None of the loaded tracks has an embedded cover"),
+    ("project.checking_cover", "Checking tracks for cover art..."),
+    ("project.track_defaults", "New Track Defaults"),
+    ("project.default_decibels", "Default decibels (dB):"),
+    ("project.fallback_duration", "Fallback duration (s):"),
+    ("project.fallback_duration_hint", "Used when the audio duration cannot be read"),
+    ("project.cover_paa", "Generate a matching .paa for files with cover art on import"),
+    ("project.cover_paa_hint", "Extract the embedded cover, convert it to PAA and save it next to the audio file (files without a cover are skipped)"),
+    ("project.scan_loudness", "Scan loudness on import and suggest decibels"),
+    ("project.scan_loudness_hint", "Decode the whole file to measure its RMS level and use the suggestion instead of the default decibels (slow for many files)"),
+    ("project.read_tag_names", "Read names from tags"),
+    ("project.read_tag_names_hint", "Prefer the TITLE tag as the track name on import, falling back to the file name; output file names still use pinyin"),
+    ("project.invalid_name", "⚠ The mod name must contain English characters only"),
+    ("project.invalid_name_hint", "Enter a valid English mod name first"),
+    // 导出设置
+    ("export.title", "Export Settings"),
+    ("export.info", "Export Info"),
+    ("export.folder_note", "The mod will be created in a folder named {} inside the chosen export directory."),
+    ("export.options", "Export Options"),
+    ("export.append_tags", "Prefix track names with their tags"),
+    ("export.tag_class_names", "Add tags to track class names (groups them when sorted by class name)"),
+    ("export.tag_class_names_warning", "⚠️ Class names become e.g. Combat_MyModSong0; mission scripts or other mods that reference the old class names must be updated."),
+    ("export.stringtable", "Use Stringtable localization (supports Chinese track names, recommended)"),
+    ("export.stringtable_note", "Generates stringtable.xml and uses $STR_ keys in the config; Chinese track names are shown when the game language is Simplified or Traditional Chinese."),
+    ("export.google_translate", "Generate English fallbacks with Google Translate (needs internet, falls back to pinyin)"),
+    ("export.output_naming", "Exported file names:"),
+    ("export.naming_pinyin", "Track name (pinyin)"),
+    ("export.naming_original", "Keep source file name"),
+    ("export.naming_template", "Template"),
+    ("export.naming_template_note", "Tokens: {index} (number starting at 001), {tag}, {name} (track name), {class} (the track's CfgMusic class, e.g. MyModSong0); the result is converted to an ASCII-safe file name and numbered if it is already taken."),
+    ("export.naming_example", "Example: {}"),
+    ("export.only_changed", "Export changed tracks only"),
+    ("export.only_changed_note", "Only copies tracks changed since the last export (currently {}); other files stay in the existing mod folder and the config files are regenerated."),
+    ("export.hardlinks", "Use hard links on the same drive"),
+    ("export.hardlinks_warning", "⚠️ Hard links share data with the source files; editing a source file also changes the exported file."),
+    ("export.hardlinks_note", "Creates hard links when the export directory is on the same drive as the source files, saving space and speeding up the export."),
+    ("export.copy_buffer", "Copy buffer (KB, 0 = auto):"),
+    ("export.copy_buffer_note", "Auto uses the system's optimized copy; try 1024 KB or more for many large files or network drives."),
+    ("export.export", "Export"),
+    ("export.preview_config", "Preview Config"),
+    ("export.preview_title", "Config Preview"),
+    ("export.preview_note", "Generated from the current export options; file names assume an export into an empty folder."),
+    // 关于
+    ("about.title", "About"),
+    ("about.app_name", "Zeus Music Maker"),
+    ("about.version", "Version:"),
+    ("about.email", "Email:"),
+    ("about.uptime", "Uptime:"),
+    ("about.uptime_secs", "{}s"),
+    ("about.uptime_mins", "{}m {}s"),
+    ("about.uptime_hours", "{}h {}m"),
+    ("about.description", "Description"),
+    ("about.description_line1", "Enhanced Arma 3 music mod generator"),
+    ("about.description_line2", "Automatically generates the Arma 3 music mod file structure"),
+    // 轨道列表
+    ("tracks.search_hint", "Search tracks..."),
+    ("tracks.clear_search", "Clear search"),
+    ("tracks.shown_count", "Showing {} / {}"),
+    ("tracks.add_folder", "Add Folder"),
+    ("tracks.add_folder_hover", "Recursively add every OGG file in the selected folder and its subfolders (current import filters apply)"),
+    ("tracks.import_folder", "Import from Folder..."),
+    // 从文件夹导入
+    ("folder_import.title", "Import from Folder"),
+    ("folder_import.folder", "Folder:"),
+    ("folder_import.not_selected", "Not selected"),
+    ("folder_import.browse", "Browse..."),
+    ("folder_import.recursive_note", "All subfolders are scanned recursively for OGG files"),
+    ("folder_import.filters", "Filters (saved as defaults):"),
+    ("folder_import.include", "Include patterns:"),
+    ("folder_import.include_hover", "Separated by semicolons, * and ? supported; empty includes everything"),
+    ("folder_import.exclude", "Exclude patterns:"),
+    ("folder_import.exclude_hover", "Separated by semicolons, e.g. *sample*;*preview*"),
+    ("folder_import.min_size", "Minimum file size:"),
+    ("folder_import.min_duration", "Minimum duration:"),
+    ("folder_import.seconds_suffix", " s"),
+    ("folder_import.import", "Import"),
+    // PAA 转换器
+    ("paa.title", "Image to PAA Converter"),
+    ("paa.heading", "Convert Images to PAA"),
+    ("paa.select_files", "Select Images (multi-select)"),
+    ("paa.image_filter", "Images"),
+    ("paa.select_files_title", "Select images to convert"),
+    ("paa.select_output", "Select Output Folder"),
+    ("paa.select_output_title", "Select the PAA output folder"),
+    ("paa.added_with_duplicates", "Added {} files, skipped {} duplicates"),
+    ("paa.added", "Added {} files"),
+    ("paa.no_files", "No files selected"),
+    ("paa.selected_with_duplicates", "⚠️ {} files selected ({} duplicates):"),
+    ("paa.selected", "{} files selected:"),
+    ("paa.output_dir", "Output folder: {}"),
+    ("paa.options", "Conversion Options"),
+    ("paa.crop_pow2", "Crop to a power-of-two size (recommended)"),
+    ("paa.target_size", "Target size:"),
+    ("paa.auto_size", "Auto"),
+    ("paa.custom_size", "Custom size:"),
+    ("paa.custom_size_hint", "e.g. 2048"),
+    ("paa.apply", "Apply"),
+    ("paa.invalid_size", "Enter a valid positive integer size"),
+    ("paa.current_size", "Current: {}x{}"),
+    ("paa.auto_rounding", "Auto rounding:"),
+    ("paa.max_size", "Max:"),
+    ("paa.auto_note", "Auto aligns the source edge (the short edge when center-cropping) to a power of two: rounding down never upscales, rounding up does; the result never exceeds the maximum"),
+    ("paa.size_rule", "Sizes must be powers of two between {} and {}; other values are adjusted to the nearest valid size"),
+    ("paa.crop_mode", "Crop mode:"),
+    ("paa.center_crop", "Center crop (recommended)"),
+    ("paa.keep_ratio", "Keep aspect ratio"),
+    ("paa.no_upscale", "Don't upscale: limit to the source's power-of-two size"),
+    ("paa.no_upscale_hint", "Keeps small images from being blurred by upscaling; check each file's actual size in Preview"),
+    ("paa.compression", "Compression:"),
+    ("paa.compression_hint", "Auto: DXT5 when the image has transparent pixels, otherwise the smaller DXT1"),
+    ("paa.mipmaps", "Generate mipmaps (recommended)"),
+    ("paa.mipmaps_hint", "Writes textures halved level by level down to 1x1 to avoid blur or shimmering at a distance; when off only the full-size level is written"),
+    ("paa.formats", "Supported formats: PNG, JPG, JPEG, BMP, TGA, TIFF, WEBP"),
+    ("paa.restore_defaults", "Restore Defaults"),
+    ("paa.start", "Start Conversion"),
+    ("paa.task_running", "A task is running"),
+    ("paa.need_files", "Select files and an output folder first"),
+    ("paa.preview", "Preview"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_key_falls_back_to_chinese() {
+        assert_eq!(tr_in(Lang::En, "menu.file"), "File");
+        assert_eq!(tr_in(Lang::Zh, "menu.file"), "文件");
+        assert_eq!(tr_in(Lang::En, "no.such.key"), "no.such.key");
+        // 英文表缺失的条目使用中文
+        let zh_only = ZH.iter().find(|(key, _)| lookup(EN, key).is_none());
+        if let Some((key, text)) = zh_only {
+            assert_eq!(tr_in(Lang::En, key), *text);
+        }
+    }
+
+    #[test]
+    fn test_tables_consistent() {
+        for (key, text) in EN {
+            assert!(lookup(ZH, key).is_some(), "中文表缺少 {}", key);
+            let zh = lookup(ZH, key).unwrap_or_default();
+            assert_eq!(zh.matches("{}").count(), text.matches("{}").count(), "{} 的参数个数不一致", key);
+        }
+    }
+
+    #[test]
+    fn test_trf_substitutes_arguments() {
+        set_language(Lang::Zh);
+        assert_eq!(trf("paa.added_with_duplicates", &[&3, &1]), "添加了 3 个文件，跳过了 1 个重复文件");
+    }
+}
@@ -0,0 +1,208 @@
+/*!
+ * 文件夹导入过滤模块
+ * 递归扫描文件夹中的音频文件，并按通配符、文件大小和时长过滤
+ */
+
+use anyhow::{Context, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::audio::AudioProcessor;
+use crate::file_ops::FileOperations;
+
+/// 文件夹导入过滤设置（跨会话持久化）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportFilter {
+    /// 包含的文件名模式（分号分隔，为空表示全部包含），如 `*.ogg`
+    pub include_patterns: String,
+    /// 排除的文件名模式（分号分隔），如 `*sample*;*preview*`
+    pub exclude_patterns: String,
+    /// 最小文件大小（KB），0 表示不限制
+    pub min_size_kb: u64,
+    /// 最短时长（秒），0 表示不限制
+    pub min_duration_secs: u32,
+}
+
+/// 文件被过滤的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterReason {
+    /// 匹配排除模式
+    Excluded(String),
+    /// 不匹配任何包含模式
+    NotIncluded,
+    /// 文件过小
+    TooSmall,
+    /// 时长过短
+    TooShort,
+}
+
+impl FilterReason {
+    /// 原因的显示文本
+    pub fn description(&self) -> String {
+        match self {
+            FilterReason::Excluded(pattern) => format!("匹配排除模式 {}", pattern),
+            FilterReason::NotIncluded => "不匹配包含模式".to_string(),
+            FilterReason::TooSmall => "文件过小".to_string(),
+            FilterReason::TooShort => "时长过短".to_string(),
+        }
+    }
+}
+
+/// 文件夹扫描结果
+#[derive(Debug, Clone, Default)]
+pub struct ImportScan {
+    /// 通过过滤的文件（按路径排序）
+    pub files: Vec<PathBuf>,
+    /// 被过滤的文件及原因
+    pub filtered: Vec<(PathBuf, FilterReason)>,
+    /// 是否达到单次扫描的文件数上限（其余文件未扫描）
+    pub limit_reached: bool,
+}
+
+impl ImportScan {
+    /// 按原因汇总被过滤的文件数
+    pub fn filtered_summary(&self) -> String {
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for (_, reason) in &self.filtered {
+            let description = reason.description();
+            match counts.iter_mut().find(|(d, _)| *d == description) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((description, 1)),
+            }
+        }
+        counts
+            .iter()
+            .map(|(description, count)| format!("{} 个{}", count, description))
+            .collect::<Vec<_>>()
+            .join("，")
+    }
+}
+
+impl ImportFilter {
+    /// 解析分号/逗号分隔的模式列表
+    fn patterns(text: &str) -> impl Iterator<Item = &str> {
+        text.split([';', ',', '；', '，'])
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+    }
+
+    /// 按文件名模式判断（不读取文件）
+    pub fn check_name(&self, file_name: &str) -> Option<FilterReason> {
+        if let Some(pattern) = Self::patterns(&self.exclude_patterns).find(|p| wildcard_match(p, file_name)) {
+            return Some(FilterReason::Excluded(pattern.to_string()));
+        }
+        let mut includes = Self::patterns(&self.include_patterns).peekable();
+        if includes.peek().is_some() && !includes.any(|p| wildcard_match(p, file_name)) {
+            return Some(FilterReason::NotIncluded);
+        }
+        None
+    }
+
+    /// 检查单个文件，返回被过滤的原因（None 表示通过）
+    pub fn check_file(&self, path: &Path) -> Option<FilterReason> {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        if let Some(reason) = self.check_name(&file_name) {
+            return Some(reason);
+        }
+        if self.min_size_kb > 0 {
+            let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            if size < self.min_size_kb * 1024 {
+                return Some(FilterReason::TooSmall);
+            }
+        }
+        if self.min_duration_secs > 0 {
+            match AudioProcessor::get_audio_info(path) {
+                Ok(info) if info.duration < self.min_duration_secs => return Some(FilterReason::TooShort),
+                Ok(_) => {}
+                // 无法读取时长时不过滤，交由后续加载流程处理
+                Err(e) => debug!("无法读取时长，跳过时长过滤 {:?}: {}", path, e),
+            }
+        }
+        None
+    }
+
+    /// 递归扫描文件夹，收集支持的音频文件并应用过滤
+    pub fn scan_folder(&self, dir: &Path) -> Result<ImportScan> {
+        fs::read_dir(dir).with_context(|| format!("无法读取文件夹: {:?}", dir))?;
+        let (candidates, limit_reached) = FileOperations::collect_ogg_files_recursive(dir);
+
+        let mut scan = ImportScan { limit_reached, ..ImportScan::default() };
+        for path in candidates {
+            match self.check_file(&path) {
+                Some(reason) => scan.filtered.push((path, reason)),
+                None => scan.files.push(path),
+            }
+        }
+        Ok(scan)
+    }
+}
+
+/// 简单通配符匹配（`*` 匹配任意字符，`?` 匹配单个字符，不区分大小写）
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // 回溯：让上一个 * 多匹配一个字符
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*sample*", "Drum Sample 01.ogg"));
+        assert!(wildcard_match("*.ogg", "song.OGG"));
+        assert!(wildcard_match("track??.ogg", "track01.ogg"));
+        assert!(!wildcard_match("track??.ogg", "track1.ogg"));
+        assert!(!wildcard_match("*preview*", "song.ogg"));
+        assert!(wildcard_match("*", ""));
+    }
+
+    #[test]
+    fn test_check_name_exclude_and_include() {
+        let filter = ImportFilter {
+            include_patterns: "*.ogg".to_string(),
+            exclude_patterns: "*sample*; *preview*".to_string(),
+            ..ImportFilter::default()
+        };
+        assert_eq!(filter.check_name("song.ogg"), None);
+        assert_eq!(filter.check_name("song_preview.ogg"), Some(FilterReason::Excluded("*preview*".to_string())));
+        assert_eq!(filter.check_name("song.mp3"), Some(FilterReason::NotIncluded));
+    }
+
+    #[test]
+    fn test_filtered_summary_groups_reasons() {
+        let scan = ImportScan {
+            files: Vec::new(),
+            filtered: vec![
+                (PathBuf::from("a.ogg"), FilterReason::TooSmall),
+                (PathBuf::from("b.ogg"), FilterReason::TooSmall),
+                (PathBuf::from("c.ogg"), FilterReason::TooShort),
+            ],
+            limit_reached: false,
+        };
+        assert_eq!(scan.filtered_summary(), "2 个文件过小，1 个时长过短");
+    }
+}
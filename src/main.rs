@@ -4,26 +4,36 @@
  */
 
 use eframe::egui;
-use log::info;
+use log::{info, warn};
 
 mod app;
 mod models;
 mod audio;
+mod audio_player;
 mod file_ops;
 mod paa_converter;
 mod audio_decrypt;
 mod audio_converter;
+mod config_repair;
 mod video_converter;
 mod video_chunk_converter;
 mod video_chunk_parallel_processor;
 mod ffmpeg_plugin;
+mod ffmpeg_progress;
 mod ffmpeg_downloader;
+mod http_client;
+mod i18n;
+mod import_filter;
 mod templates;
+mod track_similarity;
 mod stringtable;
 mod translation;
 mod ui;
 mod threading;
 mod parallel_converter;
+mod progress_channel;
+mod playlist;
+mod project_diff;
 mod resource_manager;
 mod embedded;
 mod utils;
@@ -71,15 +81,29 @@ fn main() -> Result<(), eframe::Error> {
         }
     }
     
-    info!("启动宙斯音乐制作器");
+    // 安全模式：跳过配置加载（配置损坏导致无法正常启动时使用）
+    let safe_mode = std::env::args().any(|arg| arg == "--safe-mode");
+    
+    info!("启动宙斯音乐制作器{}", if safe_mode { "（安全模式）" } else { "" });
+    
+    // 恢复上次关闭时的窗口位置和大小（安全模式下不读取配置）
+    let geometry = if safe_mode { None } else { models::AppState::load_window_geometry() }
+        .map(clamp_to_screen);
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size(geometry.map_or(app_constants::DEFAULT_WINDOW_SIZE, |geometry| geometry.size))
+        .with_min_inner_size(app_constants::MIN_WINDOW_SIZE)
+        .with_decorations(true)
+        .with_transparent(false)
+        .with_icon(load_icon());
+    if let Some(geometry) = geometry {
+        if let Some(position) = geometry.position {
+            viewport = viewport.with_position(position);
+        }
+        viewport = viewport.with_maximized(geometry.maximized);
+    }
     
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1000.0, 700.0])
-            .with_min_inner_size([800.0, 600.0])
-            .with_decorations(true)
-            .with_transparent(false)
-            .with_icon(load_icon()),
+        viewport,
         // 启用持续渲染，确保在有任务运行时UI持续更新
         renderer: eframe::Renderer::Glow,
         ..Default::default()
@@ -88,28 +112,63 @@ fn main() -> Result<(), eframe::Error> {
     eframe::run_native(
         app_constants::APP_NAME,
         options,
-        Box::new(|cc| {
+        Box::new(move |cc| {
             // 配置字体以支持中文字符
             setup_custom_fonts(&cc.egui_ctx);
-            Box::new(ZeusMusicApp::new())
+            Box::new(ZeusMusicApp::new(safe_mode))
         }),
     )
 }
 
+/// 将保存的窗口限制在当前所有显示器组成的虚拟屏幕内，避免窗口出现在已断开的显示器上
+#[cfg(target_os = "windows")]
+fn clamp_to_screen(geometry: models::WindowGeometry) -> models::WindowGeometry {
+    use winapi::um::winuser::{
+        GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+    };
+
+    let (x, y, width, height) = unsafe {
+        (
+            GetSystemMetrics(SM_XVIRTUALSCREEN),
+            GetSystemMetrics(SM_YVIRTUALSCREEN),
+            GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            GetSystemMetrics(SM_CYVIRTUALSCREEN),
+        )
+    };
+    if width <= 0 || height <= 0 {
+        return geometry;
+    }
+    geometry.clamped_to_screen(
+        [x as f32, y as f32],
+        [(x + width) as f32, (y + height) as f32],
+    )
+}
+
+/// 其他平台由窗口管理器保证窗口可见
+#[cfg(not(target_os = "windows"))]
+fn clamp_to_screen(geometry: models::WindowGeometry) -> models::WindowGeometry {
+    geometry
+}
+
 fn setup_custom_fonts(ctx: &egui::Context) {
     // 配置字体以支持中文字符
     let mut fonts = egui::FontDefinitions::default();
     
-    // 尝试加载中文字体
-    if let Ok(font_data) = load_chinese_font() {
-        fonts.font_data.insert("chinese_font".to_owned(), font_data);
-        
-        // 将中文字体添加到字体族
-        if let Some(proportional) = fonts.families.get_mut(&egui::FontFamily::Proportional) {
-            proportional.insert(0, "chinese_font".to_owned());
+    // 尝试加载中文字体；找不到可用字体时保留默认字体，避免空字体数据导致连英文也无法显示
+    match load_chinese_font() {
+        Some(font_data) => {
+            fonts.font_data.insert("chinese_font".to_owned(), font_data);
+            
+            // 将中文字体添加到字体族
+            if let Some(proportional) = fonts.families.get_mut(&egui::FontFamily::Proportional) {
+                proportional.insert(0, "chinese_font".to_owned());
+            }
+            if let Some(monospace) = fonts.families.get_mut(&egui::FontFamily::Monospace) {
+                monospace.push("chinese_font".to_owned());
+            }
         }
-        if let Some(monospace) = fonts.families.get_mut(&egui::FontFamily::Monospace) {
-            monospace.push("chinese_font".to_owned());
+        None => {
+            warn!("未找到可用的中文字体，中文文本将无法正常显示（No usable CJK font found, Chinese text will not render）");
         }
     }
     
@@ -139,31 +198,35 @@ fn setup_custom_fonts(ctx: &egui::Context) {
     ctx.set_style(style);
 }
 
-fn load_chinese_font() -> Result<egui::FontData, Box<dyn std::error::Error>> {
-    // 尝试从系统字体目录加载中文字体（按优先级排序，优先使用较小的字体文件）
+/// 从系统字体目录加载中文字体，找不到或读取失败时返回 None
+fn load_chinese_font() -> Option<egui::FontData> {
+    // 按优先级排序，优先使用较小的字体文件
     let font_paths = [
         "C:/Windows/Fonts/simhei.ttf", // 黑体 - 相对较小
         "C:/Windows/Fonts/msyh.ttc", // 微软雅黑
         "C:/Windows/Fonts/simsun.ttc", // 宋体
         "C:/Windows/Fonts/NotoSansCJK-Regular.ttc", // Noto Sans CJK
+        "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc", // Linux Noto Sans CJK
+        "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+        "/System/Library/Fonts/PingFang.ttc", // macOS 苹方
     ];
     
     for font_path in &font_paths {
-        if std::path::Path::new(font_path).exists() {
-            // 使用更高效的方式读取字体文件
-            let font_data = std::fs::read(font_path)?;
-            return Ok(egui::FontData::from_owned(font_data));
+        if !std::path::Path::new(font_path).exists() {
+            continue;
+        }
+        match std::fs::read(font_path) {
+            // 空文件不是有效字体，插入后会破坏所有文本的渲染
+            Ok(font_data) if !font_data.is_empty() => {
+                info!("加载中文字体: {}", font_path);
+                return Some(egui::FontData::from_owned(font_data));
+            }
+            Ok(_) => warn!("字体文件为空，跳过: {}", font_path),
+            Err(e) => warn!("读取字体文件失败 {}: {}", font_path, e),
         }
     }
     
-    // 如果系统字体不可用，创建一个简单的字体数据
-    Ok(create_basic_chinese_font())
-}
-
-fn create_basic_chinese_font() -> egui::FontData {
-    // 创建一个基本的字体数据，包含常用的中文字符
-    // 这里我们使用一个简单的字体数据
-    egui::FontData::from_static(&[])
+    None
 }
 
 fn load_icon() -> egui::IconData {
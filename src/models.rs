@@ -1,10 +1,14 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 /// 音乐轨道数据模型
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Track {
+    /// 轨道 ID（创建时生成并随项目保存；复制的轨道使用新 ID，用于区分引用同一文件的多个轨道）
+    #[serde(default = "Track::generate_id")]
+    pub id: u64,
     /// 轨道名称（在游戏中显示）
     pub track_name: String,
     /// 类名（用于Arma 3配置）
@@ -24,11 +28,91 @@ pub struct Track {
     /// 曲目名是否来自内部拼音/ASCII 重命名（启用翻译时跳过 API）
     #[serde(default)]
     pub internally_renamed: bool,
+    /// 自上次导出以来是否有更改（新添加或名称/标签/分贝/路径变化）
+    #[serde(default)]
+    pub dirty_since_export: bool,
+    /// 输出格式覆盖（默认使用项目设置）
+    #[serde(default)]
+    pub output_format: TrackOutputFormat,
+    /// 裁剪范围（导出时应用，None 表示不裁剪）
+    #[serde(default)]
+    pub trim: Option<TrackTrim>,
+    /// 从内嵌封面生成的轨道 Logo（.paa）
+    #[serde(default)]
+    pub logo_path: Option<PathBuf>,
+    /// 使用提示备注（如“伏击”“胜利”），仅供整理，不写入游戏配置
+    #[serde(default)]
+    pub notes: String,
+    /// 时长是否为无法读取音频时使用的默认值
+    #[serde(default)]
+    pub duration_estimated: bool,
+    /// 均方根电平（dBFS，导入时扫描响度才有）
+    #[serde(default)]
+    pub rms_dbfs: Option<f32>,
+    /// FFmpeg ebur128 分析得到的综合响度（LUFS，手动分析后才有）
+    #[serde(default)]
+    pub loudness_lufs: Option<f32>,
+    /// 源文件大小（字节，加载时缓存；None 表示文件不存在或无法读取）
+    #[serde(skip)]
+    pub file_size: Option<u64>,
+}
+
+/// 轨道裁剪范围（秒）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrackTrim {
+    /// 起始时间
+    pub start_secs: f64,
+    /// 结束时间
+    pub end_secs: f64,
+}
+
+impl TrackTrim {
+    /// 裁剪后的时长（秒，向上取整）
+    pub fn duration_secs(&self) -> u32 {
+        (self.end_secs - self.start_secs).max(0.0).ceil() as u32
+    }
+}
+
+/// 轨道导出格式（单轨道覆盖项目设置）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TrackOutputFormat {
+    /// 使用项目设置：直接复制 OGG 源文件
+    #[default]
+    ProjectDefault,
+    /// 以指定质量重新编码为 OGG（0-10）
+    Ogg { quality: u8 },
+    /// 转换为未压缩的 WAV（适用于必须保持无损的音效）
+    Wav,
+}
+
+impl TrackOutputFormat {
+    /// 导出文件扩展名（含点）
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TrackOutputFormat::ProjectDefault | TrackOutputFormat::Ogg { .. } => ".ogg",
+            TrackOutputFormat::Wav => ".wav",
+        }
+    }
+
+    /// 导出时是否需要 FFmpeg 转换
+    pub fn needs_conversion(&self) -> bool {
+        !matches!(self, TrackOutputFormat::ProjectDefault)
+    }
+
+    /// 显示名称
+    pub fn display_name(&self) -> String {
+        match self {
+            TrackOutputFormat::ProjectDefault => "项目默认（直接复制 OGG）".to_string(),
+            TrackOutputFormat::Ogg { quality } => format!("重新编码 OGG（质量 {}）", quality),
+            TrackOutputFormat::Wav => "未压缩 WAV".to_string(),
+        }
+    }
 }
 
 impl Track {
     pub fn new(path: PathBuf, track_name: String, class_name: String) -> Self {
         Self {
+            id: Self::generate_id(),
             track_name,
             class_name,
             tag: String::new(),
@@ -38,9 +122,39 @@ impl Track {
             original_duration: 0,
             original_decibels: 0,
             internally_renamed: false,
+            dirty_since_export: true,
+            output_format: TrackOutputFormat::default(),
+            trim: None,
+            logo_path: None,
+            notes: String::new(),
+            duration_estimated: false,
+            rms_dbfs: None,
+            loudness_lufs: None,
+            file_size: None,
         }
     }
 
+    /// 生成新的轨道 ID：进程内递增，起点取当前时间（微秒），不会与之前保存的 ID 重复
+    pub fn generate_id() -> u64 {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static LAST_ID: AtomicU64 = AtomicU64::new(0);
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_micros() as u64)
+            .unwrap_or_default();
+        let next = |last: u64| now.max(last + 1);
+        let last = LAST_ID
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| Some(next(last)))
+            .unwrap_or_default();
+        next(last)
+    }
+
+    /// 重新读取并缓存源文件大小
+    pub fn refresh_file_size(&mut self) {
+        self.file_size = std::fs::metadata(&self.path).ok().map(|metadata| metadata.len());
+    }
+
     /// 获取显示名称（包含标签）
     pub fn display_name(&self) -> String {
         if self.tag.is_empty() {
@@ -56,6 +170,15 @@ impl Track {
         }
     }
 
+    /// 导出到游戏中的名称（按导出设置决定是否添加标签前缀）
+    pub fn export_name(&self, use_tags: bool) -> String {
+        if use_tags {
+            self.display_name()
+        } else {
+            self.track_name.clone()
+        }
+    }
+
     /// 设置原始值（在加载音频信息时调用）
     pub fn set_original_values(&mut self, duration: u32, decibels: i32) {
         self.original_duration = duration;
@@ -64,6 +187,19 @@ impl Track {
         self.decibels = decibels;
     }
 
+    /// 建议的分贝调整值，未分析响度时为 None
+    ///
+    /// 优先使用 FFmpeg 分析的综合响度（接近 `TARGET_LOUDNESS_LUFS`），否则使用均方根电平（接近 `TARGET_RMS_DBFS`）。
+    pub fn suggested_decibels(&self) -> Option<i32> {
+        use crate::utils::constants::file_ops::{MAX_DECIBELS, MIN_DECIBELS, TARGET_LOUDNESS_LUFS, TARGET_RMS_DBFS};
+        let gain = match (self.loudness_lufs, self.rms_dbfs) {
+            (Some(lufs), _) => TARGET_LOUDNESS_LUFS - lufs,
+            (None, Some(rms_dbfs)) => TARGET_RMS_DBFS - rms_dbfs,
+            (None, None) => return None,
+        };
+        Some((gain.round() as i32).clamp(MIN_DECIBELS, MAX_DECIBELS))
+    }
+
     /// 恢复到默认值
     pub fn reset_to_default(&mut self) {
         self.duration = self.original_duration;
@@ -75,6 +211,102 @@ impl Track {
         self.duration != self.original_duration || self.decibels != self.original_decibels
     }
 
+    /// 标记为自上次导出以来已更改
+    pub fn mark_dirty(&mut self) {
+        self.dirty_since_export = true;
+    }
+
+    /// 导出时是否需要 FFmpeg 处理（格式覆盖或裁剪）
+    pub fn needs_conversion(&self) -> bool {
+        self.output_format.needs_conversion() || self.trim.is_some()
+    }
+
+}
+
+/// 上次导出的单个轨道记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportManifestEntry {
+    /// 轨道 ID（旧版本导出记录中没有，此时按源文件路径对应）
+    #[serde(default)]
+    pub track_id: Option<u64>,
+    /// 源文件路径
+    pub path: PathBuf,
+    /// 轨道名称
+    pub track_name: String,
+    /// 标签
+    pub tag: String,
+    /// 分贝调整值
+    pub decibels: i32,
+    /// 时长（秒）
+    pub duration: u32,
+    /// 导出到轨道文件夹中的文件名
+    pub filename: String,
+    /// 导出格式
+    #[serde(default)]
+    pub output_format: TrackOutputFormat,
+    /// 裁剪范围
+    #[serde(default)]
+    pub trim: Option<TrackTrim>,
+    /// 使用提示备注（不影响导出内容，不参与更改检测）
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl ExportManifestEntry {
+    /// 根据轨道和导出文件名创建记录
+    pub fn from_track(track: &Track, filename: &str) -> Self {
+        Self {
+            track_id: Some(track.id),
+            path: track.path.clone(),
+            track_name: track.track_name.clone(),
+            tag: track.tag.clone(),
+            decibels: track.decibels,
+            duration: track.duration,
+            filename: filename.to_string(),
+            output_format: track.output_format,
+            trim: track.trim,
+            notes: track.notes.clone(),
+        }
+    }
+
+    /// 检查记录是否与轨道当前状态一致
+    pub fn matches(&self, track: &Track) -> bool {
+        self.path == track.path
+            && self.track_name == track.track_name
+            && self.tag == track.tag
+            && self.decibels == track.decibels
+            && self.duration == track.duration
+            && self.output_format == track.output_format
+            && self.trim == track.trim
+    }
+}
+
+/// 上次导出状态记录（用于增量导出计算差异）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportManifest {
+    /// 导出的轨道记录
+    pub entries: Vec<ExportManifestEntry>,
+}
+
+impl ExportManifest {
+    /// 查找轨道的记录：按轨道 ID 对应（复制的轨道与原轨道路径相同，但各有记录），旧记录没有 ID 时按源文件路径
+    pub fn find_for_track(&self, track: &Track) -> Option<&ExportManifestEntry> {
+        self.entries.iter().find(|entry| match entry.track_id {
+            Some(id) => id == track.id,
+            None => entry.path == track.path,
+        })
+    }
+
+    /// 检查轨道自上次导出以来是否已更改
+    pub fn is_track_changed(&self, track: &Track) -> bool {
+        if track.dirty_since_export {
+            return true;
+        }
+        match self.find_for_track(track) {
+            Some(entry) => !entry.matches(track),
+            None => true,
+        }
+    }
 }
 
 /// 模组类型
@@ -154,6 +386,15 @@ pub struct ProjectSettings {
     pub class_name: String,
     /// 模组类型
     pub mod_type: ModType,
+    /// 轨道文件夹名称（位于模组目录，导出时按类名规则清理）
+    #[serde(default = "ProjectSettings::default_tracks_folder_name")]
+    pub tracks_folder_name: String,
+    /// Steam 创意工坊物品ID（未发布时为空，meta.cpp 中不写 publishedid）
+    #[serde(default)]
+    pub workshop_id: Option<u64>,
+    /// Steam 创意工坊标签
+    #[serde(default = "ProjectSettings::default_workshop_tags")]
+    pub workshop_tags: Vec<String>,
 }
 
 impl Default for ProjectSettings {
@@ -165,11 +406,57 @@ impl Default for ProjectSettings {
             use_default_logo: true,
             class_name: "MyMusicClass".to_string(),
             mod_type: ModType::Music,
+            tracks_folder_name: Self::default_tracks_folder_name(),
+            workshop_id: None,
+            workshop_tags: Self::default_workshop_tags(),
         }
     }
 }
 
 impl ProjectSettings {
+    /// 默认（占位）模组名称
+    const PLACEHOLDER_MOD_NAMES: [&'static str; 2] = ["New Music Mod", "New Video Mod"];
+
+    /// 项目设置中可勾选的创意工坊标签
+    pub const WORKSHOP_TAGS: [&'static str; 6] = ["Mod", "Sound", "Music", "Zeus", "Multiplayer", "Singleplayer"];
+
+    fn default_tracks_folder_name() -> String {
+        crate::utils::constants::file_ops::DEFAULT_TRACKS_FOLDER.to_string()
+    }
+
+    fn default_workshop_tags() -> Vec<String> {
+        vec!["Sound".to_string(), "Zeus".to_string()]
+    }
+
+    /// 勾选或取消一个创意工坊标签
+    pub fn set_workshop_tag(&mut self, tag: &str, enabled: bool) {
+        let present = self.workshop_tags.iter().any(|t| t == tag);
+        if enabled && !present {
+            self.workshop_tags.push(tag.to_string());
+        } else if !enabled && present {
+            self.workshop_tags.retain(|t| t != tag);
+        }
+    }
+
+    /// 导出使用的轨道文件夹名称
+    ///
+    /// 与类名相同，只保留英文字母、数字和下划线，且不以数字开头；为空时使用默认的 `folderwithtracks`。
+    pub fn tracks_folder(&self) -> String {
+        let mut folder: String = self
+            .tracks_folder_name
+            .trim()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if folder.chars().all(|c| c == '_') {
+            return Self::default_tracks_folder_name();
+        }
+        if folder.starts_with(|c: char| c.is_ascii_digit()) {
+            folder.insert(0, '_');
+        }
+        folder
+    }
+
     /// 更新类名（从模组名称生成）
     pub fn update_class_name(&mut self) {
         self.class_name = self
@@ -181,6 +468,39 @@ impl ProjectSettings {
                 '_'
             })
             .collect::<String>();
+        // Arma 类名不能以数字开头
+        if self.class_name.starts_with(|c: char| c.is_ascii_digit()) {
+            self.class_name.insert(0, '_');
+        }
+    }
+
+    /// 导出前检查项目设置，返回所有问题说明
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+
+        let mod_name = self.mod_name.trim();
+        if mod_name.is_empty() {
+            problems.push("模组名称不能为空".to_string());
+        } else if Self::PLACEHOLDER_MOD_NAMES.iter().any(|name| name.eq_ignore_ascii_case(mod_name)) {
+            problems.push(format!("模组名称仍是默认的 \"{}\"，请改为自己的模组名称", mod_name));
+        }
+
+        if self.class_name.is_empty() {
+            problems.push("类名不能为空".to_string());
+        } else {
+            if !self.class_name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+                problems.push(format!("类名 \"{}\" 必须以英文字母或下划线开头", self.class_name));
+            }
+            if !self.class_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                problems.push(format!("类名 \"{}\" 只能包含英文字母、数字和下划线", self.class_name));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
     }
 
     /// 根据模组类型设置默认名称
@@ -223,6 +543,181 @@ impl ProjectSettings {
     }
 }
 
+/// 按路径缓存由后台线程读取的文件信息
+///
+/// 界面每帧只读取缓存；缺失的路径加入请求队列，由主循环交给后台线程读取，结果通过任务消息写回。
+#[derive(Debug, Clone)]
+pub struct BackgroundPathCache<V> {
+    values: HashMap<PathBuf, V>,
+    /// 已请求、尚未返回结果的路径
+    pending: HashSet<PathBuf>,
+    /// 等待交给后台线程的路径
+    requests: Vec<PathBuf>,
+}
+
+impl<V> Default for BackgroundPathCache<V> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+            pending: HashSet::new(),
+            requests: Vec::new(),
+        }
+    }
+}
+
+impl<V> BackgroundPathCache<V> {
+    /// 只读取缓存，不发起请求
+    pub fn get(&self, path: &Path) -> Option<&V> {
+        self.values.get(path)
+    }
+
+    /// 读取缓存；没有缓存时加入请求队列并返回 None
+    pub fn get_or_request(&mut self, path: &Path) -> Option<&V> {
+        if !self.values.contains_key(path) && self.pending.insert(path.to_path_buf()) {
+            self.requests.push(path.to_path_buf());
+        }
+        self.values.get(path)
+    }
+
+    /// 是否还有尚未返回结果的路径
+    pub fn is_loading(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// 取出等待交给后台线程的路径
+    pub fn take_requests(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.requests)
+    }
+
+    /// 写入后台线程返回的结果
+    pub fn insert(&mut self, path: PathBuf, value: V) {
+        self.pending.remove(&path);
+        self.values.insert(path, value);
+    }
+
+    /// 清空缓存（文件内容可能已变化时调用），之后读取时重新请求
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.pending.clear();
+        self.requests.clear();
+    }
+}
+
+/// 新导入的轨道在后台补全的工作
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackImportJob {
+    pub path: PathBuf,
+    /// 文件头没有时长，需要解码测量
+    pub measure_duration: bool,
+    /// 将内嵌封面转换为同名 .paa 并设为轨道 Logo
+    pub cover_paa: bool,
+    /// 读取 TITLE 标签作为曲目名称
+    pub read_title: bool,
+}
+
+impl TrackImportJob {
+    /// 根据刚添加的轨道和导入默认设置生成后台工作，没有需要补全的内容时返回 None
+    pub fn for_track(track: &Track, defaults: &TrackDefaults) -> Option<Self> {
+        let job = Self {
+            path: track.path.clone(),
+            measure_duration: track.duration_estimated,
+            cover_paa: defaults.cover_paa && track.logo_path.is_none(),
+            read_title: defaults.read_tag_names,
+        };
+        (job.measure_duration || job.cover_paa || job.read_title).then_some(job)
+    }
+}
+
+/// 后台补全的轨道信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackImportExtras {
+    pub path: PathBuf,
+    /// 解码测量的时长（秒），未测量或测量失败时为 None
+    pub duration: Option<u32>,
+    /// 由内嵌封面生成的 .paa，未生成或文件没有封面时为 None
+    pub logo_path: Option<PathBuf>,
+    /// 标签中的标题，未读取或没有标题时为 None
+    pub title: Option<String>,
+}
+
+/// 按文件列表缓存的总大小（列表变化时才重新读取文件信息）
+#[derive(Debug, Clone, Default)]
+pub struct TotalSizeCache {
+    paths: Vec<PathBuf>,
+    total: u64,
+}
+
+impl TotalSizeCache {
+    /// 文件总大小（字节），无法读取的文件忽略
+    pub fn total_size(&mut self, paths: &[PathBuf]) -> u64 {
+        if self.paths != paths {
+            self.paths = paths.to_vec();
+            self.total = crate::utils::FileUtils::total_size(paths);
+        }
+        self.total
+    }
+}
+
+/// 为 Zeus 准备时在后台读取的单个轨道信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZeusTrackProbe {
+    /// 发起检查时的轨道索引
+    pub track_index: usize,
+    pub path: PathBuf,
+    /// 首个音频流是否为 Vorbis 编码，无法识别时为错误说明
+    pub is_vorbis: Result<bool, String>,
+    /// 轨道时长为 0 时读取的实际时长（秒）
+    pub duration: Option<u32>,
+}
+
+/// 为 Zeus 准备导出的检查结果
+#[derive(Debug, Clone, Default)]
+pub struct ZeusPreparationReport {
+    /// 已完成的检查与自动修复
+    pub steps: Vec<String>,
+    /// 需要用户处理后才能导出的问题
+    pub blocking_issues: Vec<String>,
+}
+
+/// 在后台执行的模组导出（界面线程完成检查后提交，工作线程负责复制、转换和生成配置）
+#[derive(Debug, Clone)]
+pub struct ModExportJob {
+    pub project: ProjectSettings,
+    pub tracks: Vec<Track>,
+    pub video_files: Vec<VideoFile>,
+    pub export_settings: ExportSettings,
+    pub export_dir: PathBuf,
+    /// 导出前检查发现的警告，显示在导出结果中
+    pub warnings: Vec<String>,
+    /// 导出结果前附加的说明（如 Zeus 准备步骤）
+    pub result_prefix: String,
+}
+
+impl ModExportJob {
+    /// 需要复制的条目数量（进度总数）
+    pub fn item_count(&self) -> usize {
+        match self.project.mod_type {
+            ModType::Music => self.tracks.len(),
+            ModType::Video => self.video_files.len(),
+        }
+    }
+}
+
+/// 后台模组导出的结果
+#[derive(Debug, Clone, Default)]
+pub struct ModExportOutcome {
+    /// 导出结果对话框中显示的说明
+    pub message: String,
+    /// 已创建的模组目录
+    pub mod_dir: Option<PathBuf>,
+    /// 导出目录没有写入权限
+    pub permission_denied: bool,
+    /// 已保存导出记录（可清除轨道的更改标记）
+    pub manifest_saved: bool,
+    /// 导出被用户取消
+    pub cancelled: bool,
+}
+
 /// 导出设置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportSettings {
@@ -236,6 +731,383 @@ pub struct ExportSettings {
     /// 是否使用 Google 免费翻译 API 生成英文等回退文本（需联网）
     #[serde(default = "ExportSettings::default_use_google_translate")]
     pub use_google_translate: bool,
+    /// 是否仅导出自上次导出以来已更改的轨道（增量导出）
+    #[serde(default)]
+    pub only_export_changed: bool,
+    /// 同盘导出时使用硬链接代替复制（跨盘或失败时回退为复制）
+    #[serde(default)]
+    pub use_hardlinks: bool,
+    /// 将标签加入轨道类名（会改变配置类名，默认关闭）
+    #[serde(default)]
+    pub tag_class_names: bool,
+    /// 复制缓冲区大小（KB），0 表示自动
+    #[serde(default)]
+    pub copy_buffer_kb: u32,
+    /// 导出音频文件的命名方式
+    #[serde(default)]
+    pub output_naming: NamingScheme,
+}
+
+/// 导出音频文件的命名方式（结果总会转换为 ASCII 安全的文件名，重名时追加数字后缀）
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NamingScheme {
+    /// 轨道名称转写为拼音
+    #[default]
+    Pinyin,
+    /// 保留源文件名
+    Original,
+    /// 按模板生成，支持 `{index}`（从 001 开始）、`{tag}`、`{name}`、`{class}`
+    Template(String),
+}
+
+impl NamingScheme {
+    /// 默认的命名模板
+    pub const DEFAULT_TEMPLATE: &'static str = "{index}_{name}";
+}
+
+/// 音频解密选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioDecryptOptions {
+    /// 最大并发数
+    pub max_concurrency: usize,
+    /// 是否按格式将解密输出整理到子文件夹（mp3/、flac/）
+    pub organize_by_format: bool,
+}
+
+impl Default for AudioDecryptOptions {
+    fn default() -> Self {
+        Self {
+            max_concurrency: crate::utils::constants::audio_decrypt::DEFAULT_MAX_CONCURRENT_DECRYPTS,
+            organize_by_format: false,
+        }
+    }
+}
+
+/// 音频转换选项（跨会话持久化）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConvertOptions {
+    /// OGG Vorbis 质量等级（FFmpeg `-q:a`，0-10，越高音质越好、文件越大）
+    pub quality: u8,
+    /// 响度归一化：转换时使用 FFmpeg `loudnorm` 滤镜将响度统一到目标值
+    pub normalize_loudness: bool,
+    /// 响度归一化的目标综合响度（LUFS）
+    pub target_lufs: i32,
+}
+
+impl AudioConvertOptions {
+    /// 默认质量等级（音质与体积的平衡点）
+    pub const DEFAULT_QUALITY: u8 = 5;
+    /// 默认目标响度（LUFS，常见的流媒体响度标准）
+    pub const DEFAULT_TARGET_LUFS: i32 = -16;
+    /// 目标响度可选范围（`loudnorm` 支持 -70 到 -5，过低的值没有实际用途）
+    pub const TARGET_LUFS_RANGE: std::ops::RangeInclusive<i32> = -30..=-5;
+
+    /// 相对编码开销（默认设置为 1.0），用于估算转换耗时
+    ///
+    /// 质量越高编码越慢；`loudnorm` 会上采样到 192kHz 处理，开销明显增加。
+    pub fn encode_cost(&self) -> f64 {
+        let quality_cost = 0.7 + 0.06 * self.quality.min(10) as f64;
+        if self.normalize_loudness {
+            quality_cost * 1.5
+        } else {
+            quality_cost
+        }
+    }
+}
+
+impl Default for AudioConvertOptions {
+    fn default() -> Self {
+        Self {
+            quality: Self::DEFAULT_QUALITY,
+            normalize_loudness: false,
+            target_lufs: Self::DEFAULT_TARGET_LUFS,
+        }
+    }
+}
+
+/// 视频转换选项（跨会话持久化）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VideoConvertOptions {
+    /// 输出分辨率上限（高度，像素），None 表示保持原始分辨率；低于上限的视频不会放大
+    pub max_height: Option<u32>,
+    /// 视频质量等级（0-10，越高画质越好、文件越大）
+    pub video_quality: u8,
+    /// 音频质量等级（0-10）
+    pub audio_quality: u8,
+}
+
+impl VideoConvertOptions {
+    /// 默认视频质量等级
+    pub const DEFAULT_VIDEO_QUALITY: u8 = 5;
+    /// 默认音频质量等级
+    pub const DEFAULT_AUDIO_QUALITY: u8 = 3;
+    /// 可选的分辨率上限及显示名称
+    pub const RESOLUTION_PRESETS: [(Option<u32>, &'static str); 4] = [
+        (None, "原始"),
+        (Some(1080), "1080p"),
+        (Some(720), "720p"),
+        (Some(480), "480p"),
+    ];
+
+    /// 当前分辨率上限的显示名称
+    pub fn resolution_name(&self) -> String {
+        Self::RESOLUTION_PRESETS
+            .iter()
+            .find(|(height, _)| *height == self.max_height)
+            .map(|(_, name)| name.to_string())
+            .unwrap_or_else(|| format!("{}p", self.max_height.unwrap_or_default()))
+    }
+
+    /// 相对编码开销（默认设置为 1.0），用于估算转换耗时
+    ///
+    /// 质量越高编码越慢；限制分辨率时需要编码的像素更少。
+    pub fn encode_cost(&self) -> f64 {
+        let quality_cost = 0.6 + 0.08 * self.video_quality.min(10) as f64;
+        let resolution_cost = match self.max_height {
+            Some(height) if height <= 480 => 0.4,
+            Some(height) if height <= 720 => 0.6,
+            Some(height) if height <= 1080 => 0.9,
+            _ => 1.0,
+        };
+        quality_cost * resolution_cost
+    }
+
+    /// FFmpeg 缩放参数：按高度等比缩小（宽度取偶数），原始高度更低时保持不变
+    pub fn scale_args(&self) -> Vec<String> {
+        match self.max_height {
+            Some(height) => vec!["-vf".to_string(), format!("scale=-2:'min({},ih)'", height)],
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Default for VideoConvertOptions {
+    fn default() -> Self {
+        Self {
+            max_height: None,
+            video_quality: Self::DEFAULT_VIDEO_QUALITY,
+            audio_quality: Self::DEFAULT_AUDIO_QUALITY,
+        }
+    }
+}
+
+/// 新轨道的默认值（无法读取音频信息时的回退值，跨会话持久化）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackDefaults {
+    /// 默认分贝值
+    pub decibels: i32,
+    /// 无法读取时长时使用的时长（秒）
+    pub duration: u32,
+    /// 导入时为带内嵌封面的文件生成同名 .paa（默认关闭）
+    pub cover_paa: bool,
+    /// 导入时解码扫描响度，并用建议值作为默认分贝（默认关闭，较慢）
+    pub scan_loudness: bool,
+    /// 导入时优先使用标签中的标题作为曲目名称（默认关闭，在后台读取）
+    pub read_tag_names: bool,
+}
+
+impl Default for TrackDefaults {
+    fn default() -> Self {
+        Self {
+            decibels: crate::utils::constants::file_ops::DEFAULT_DECIBELS,
+            duration: crate::utils::constants::file_ops::DEFAULT_TRACK_DURATION,
+            cover_paa: false,
+            scan_loudness: false,
+            read_tag_names: false,
+        }
+    }
+}
+
+impl TrackDefaults {
+    /// 限制到轨道编辑器允许的范围（手动修改的配置文件可能越界）
+    pub fn validated(self) -> Self {
+        use crate::utils::constants::file_ops::{MAX_DECIBELS, MAX_TRACK_DURATION, MIN_DECIBELS};
+        Self {
+            decibels: self.decibels.clamp(MIN_DECIBELS, MAX_DECIBELS),
+            duration: self.duration.clamp(1, MAX_TRACK_DURATION),
+            ..self
+        }
+    }
+}
+
+/// 界面主题（跨会话持久化）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AppTheme {
+    /// 跟随系统
+    #[default]
+    System,
+    /// 浅色
+    Light,
+    /// 深色
+    Dark,
+}
+
+impl AppTheme {
+    /// 所有可选主题（菜单顺序）
+    pub const ALL: [AppTheme; 3] = [AppTheme::System, AppTheme::Light, AppTheme::Dark];
+
+    /// 显示名称
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AppTheme::System => "跟随系统",
+            AppTheme::Light => "浅色",
+            AppTheme::Dark => "深色",
+        }
+    }
+
+    /// 是否使用深色配色；跟随系统但无法获取系统主题时返回 None（保持当前配色）
+    pub fn dark_mode(&self, system_dark: Option<bool>) -> Option<bool> {
+        match self {
+            AppTheme::System => system_dark,
+            AppTheme::Light => Some(false),
+            AppTheme::Dark => Some(true),
+        }
+    }
+}
+
+/// 上次关闭时的窗口位置和大小（逻辑像素，跨会话持久化）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    /// 窗口外框左上角位置（None 表示由系统决定）
+    pub position: Option<[f32; 2]>,
+    /// 窗口内容区大小
+    pub size: [f32; 2],
+    /// 是否最大化（最大化时 position/size 保留最大化前的值）
+    #[serde(default)]
+    pub maximized: bool,
+    /// 保存时的缩放比例（逻辑像素与物理像素之比）
+    #[serde(default = "WindowGeometry::default_scale")]
+    pub scale: f32,
+}
+
+impl WindowGeometry {
+    fn default_scale() -> f32 {
+        1.0
+    }
+
+    /// 将窗口限制在屏幕区域内（物理像素，左上角和右下角），保证窗口完整可见
+    ///
+    /// 用于保存时所在的显示器已断开等情况；大小不小于 `MIN_WINDOW_SIZE`。
+    pub fn clamped_to_screen(self, screen_min: [f32; 2], screen_max: [f32; 2]) -> Self {
+        use crate::utils::constants::app::MIN_WINDOW_SIZE;
+        let scale = if self.scale > 0.0 { self.scale } else { 1.0 };
+        let mut size = self.size;
+        let mut position = self.position;
+        for axis in 0..2 {
+            let min = screen_min[axis] / scale;
+            let max = screen_max[axis] / scale;
+            size[axis] = size[axis].clamp(MIN_WINDOW_SIZE[axis], (max - min).max(MIN_WINDOW_SIZE[axis]));
+            if let Some(position) = position.as_mut() {
+                position[axis] = position[axis].clamp(min, (max - size[axis]).max(min));
+            }
+        }
+        Self { position, size, ..self }
+    }
+}
+
+/// 分贝渐变曲线
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecibelRampCurve {
+    /// 匀速变化
+    #[default]
+    Linear,
+    /// 先慢后快
+    EaseIn,
+    /// 先快后慢
+    EaseOut,
+}
+
+impl DecibelRampCurve {
+    /// 显示名称
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            DecibelRampCurve::Linear => "线性",
+            DecibelRampCurve::EaseIn => "先慢后快",
+            DecibelRampCurve::EaseOut => "先快后慢",
+        }
+    }
+
+    /// 将 0..=1 的位置映射为变化进度
+    fn apply(&self, t: f64) -> f64 {
+        match self {
+            DecibelRampCurve::Linear => t,
+            DecibelRampCurve::EaseIn => t * t,
+            DecibelRampCurve::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+/// 按列表顺序从首轨到末轨的分贝渐变
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecibelRamp {
+    /// 第一个轨道的分贝值
+    pub start: i32,
+    /// 最后一个轨道的分贝值
+    pub end: i32,
+    /// 渐变曲线
+    pub curve: DecibelRampCurve,
+}
+
+impl DecibelRamp {
+    /// 计算 `count` 个轨道的分贝值（四舍五入并限制在编辑器允许的范围内）
+    pub fn values(&self, count: usize) -> Vec<i32> {
+        use crate::utils::constants::file_ops::{MAX_DECIBELS, MIN_DECIBELS};
+        let start = self.start.clamp(MIN_DECIBELS, MAX_DECIBELS) as f64;
+        let end = self.end.clamp(MIN_DECIBELS, MAX_DECIBELS) as f64;
+        (0..count)
+            .map(|i| {
+                let t = if count > 1 { i as f64 / (count - 1) as f64 } else { 0.0 };
+                let value = start + (end - start) * self.curve.apply(t);
+                (value.round() as i32).clamp(MIN_DECIBELS, MAX_DECIBELS)
+            })
+            .collect()
+    }
+}
+
+/// 统一调整分贝的方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecibelAdjustMode {
+    /// 在每个轨道当前值的基础上增减
+    #[default]
+    Offset,
+    /// 将所有轨道设为同一个值
+    Absolute,
+}
+
+impl DecibelAdjustMode {
+    /// 显示名称
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            DecibelAdjustMode::Offset => "统一调整",
+            DecibelAdjustMode::Absolute => "统一设置",
+        }
+    }
+}
+
+/// 对所有轨道统一调整分贝
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecibelAdjust {
+    /// 调整方式
+    pub mode: DecibelAdjustMode,
+    /// 增减量（统一调整）或目标值（统一设置）
+    pub value: i32,
+}
+
+impl DecibelAdjust {
+    /// 计算调整后的分贝值，返回 (新值, 是否被范围限制)
+    pub fn apply(&self, decibels: i32) -> (i32, bool) {
+        use crate::utils::constants::file_ops::{MAX_DECIBELS, MIN_DECIBELS};
+        let target = match self.mode {
+            DecibelAdjustMode::Offset => decibels.saturating_add(self.value),
+            DecibelAdjustMode::Absolute => self.value,
+        };
+        let clamped = target.clamp(MIN_DECIBELS, MAX_DECIBELS);
+        (clamped, clamped != target)
+    }
 }
 
 /// 任务类型
@@ -248,6 +1120,25 @@ pub enum TaskType {
     AudioConvert,
     VideoConvert,
     VideoModExport,
+    SimilarTrackScan,
+    DurationSync,
+    LoudnessAnalysis,
+    ChapterSplit,
+    DecryptPipeline,
+    ZeusPreparation,
+}
+
+/// 转换历史记录（源文件 → 输出文件）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionHistoryEntry {
+    /// 产生该输出的任务类型
+    pub task_type: TaskType,
+    /// 源文件
+    pub source: PathBuf,
+    /// 输出文件
+    pub output: PathBuf,
+    /// 输出文件是否存在（记录时和打开转换历史面板时检查，界面不在每帧访问磁盘）
+    pub output_exists: bool,
 }
 
 /// 任务状态
@@ -281,6 +1172,34 @@ pub struct ProgressInfo {
     pub estimated_remaining: Option<u64>,
     /// 处理速度（文件/秒）
     pub processing_speed: Option<f32>,
+    /// 结束时间
+    pub end_time: Option<std::time::SystemTime>,
+    /// 工作量（输入字节数 × 编码开销系数），用于根据历史吞吐量估算耗时
+    pub workload: f64,
+    /// 已完成的文件数
+    #[serde(skip)]
+    pub completed_files: usize,
+    /// 已确认成功的文件数
+    #[serde(skip)]
+    pub succeeded_files: usize,
+    /// 已确认失败的文件数
+    #[serde(skip)]
+    pub failed_files: usize,
+    /// 正在转换的文件的进度（任务ID → 0.0-1.0）
+    #[serde(skip)]
+    pub file_fractions: HashMap<usize, f32>,
+}
+
+impl ProgressInfo {
+    /// 根据已完成文件数和正在转换文件的进度重新计算整体进度（只增不减）
+    fn refresh_progress(&mut self) {
+        if self.total_files == 0 {
+            return;
+        }
+        let in_flight: f32 = self.file_fractions.values().sum();
+        let progress = (self.completed_files as f32 + in_flight) / self.total_files as f32;
+        self.progress = self.progress.max(progress.min(1.0));
+    }
 }
 
 impl Default for ProgressInfo {
@@ -295,10 +1214,95 @@ impl Default for ProgressInfo {
             start_time: None,
             estimated_remaining: None,
             processing_speed: None,
+            end_time: None,
+            workload: 0.0,
+            completed_files: 0,
+            succeeded_files: 0,
+            failed_files: 0,
+            file_fractions: HashMap::new(),
         }
     }
 }
 
+/// FFmpeg 下载统计（用于显示已用时间、速度和剩余时间）
+#[derive(Debug, Clone, Default)]
+pub struct DownloadStats {
+    /// 下载开始时间
+    started: Option<Instant>,
+    /// 当前连接首次报告字节数的时间和字节数（切换镜像后重新计算）
+    baseline: Option<(Instant, u64)>,
+    /// 最近一次收到新数据的时间
+    last_progress: Option<Instant>,
+    /// 已下载字节数
+    pub downloaded: u64,
+    /// 文件总大小（未知时为 None）
+    pub total: Option<u64>,
+    /// 是否已下载完成（进入校验/解压阶段）
+    finished: bool,
+}
+
+impl DownloadStats {
+    /// 开始新的下载
+    pub fn start(now: Instant) -> Self {
+        Self {
+            started: Some(now),
+            last_progress: Some(now),
+            ..Self::default()
+        }
+    }
+
+    /// 记录一次进度报告
+    ///
+    /// 只有状态没有字节数的报告（如“正在连接...”）表示开始新的连接，速度从下一次报告重新计算。
+    pub fn record(&mut self, downloaded: u64, total: Option<u64>, percent: f64, now: Instant) {
+        if percent >= 100.0 {
+            self.finished = true;
+            return;
+        }
+        if downloaded == 0 && total.is_none() {
+            self.baseline = None;
+            self.last_progress = Some(now);
+            return;
+        }
+        match self.baseline {
+            None => {
+                self.baseline = Some((now, downloaded));
+                self.last_progress = Some(now);
+            }
+            Some(_) if downloaded > self.downloaded => self.last_progress = Some(now),
+            Some(_) => {}
+        }
+        self.downloaded = downloaded;
+        self.total = total;
+    }
+
+    /// 已用时间
+    pub fn elapsed(&self, now: Instant) -> Option<Duration> {
+        self.started.map(|started| now.saturating_duration_since(started))
+    }
+
+    /// 当前连接的平均下载速度（字节/秒），数据不足一秒时返回 None
+    pub fn bytes_per_sec(&self, now: Instant) -> Option<f64> {
+        let (since, base) = self.baseline?;
+        let secs = now.saturating_duration_since(since).as_secs_f64();
+        (secs >= 1.0).then(|| self.downloaded.saturating_sub(base) as f64 / secs)
+    }
+
+    /// 预计剩余时间，总大小未知或速度为 0 时返回 None
+    pub fn eta(&self, now: Instant) -> Option<Duration> {
+        let total = self.total?;
+        let speed = self.bytes_per_sec(now).filter(|speed| *speed > 0.0)?;
+        Some(Duration::from_secs_f64(total.saturating_sub(self.downloaded) as f64 / speed))
+    }
+
+    /// 超过 `DOWNLOAD_STALL_TIMEOUT_SECS` 秒没有收到新数据
+    pub fn is_stalled(&self, now: Instant) -> bool {
+        let timeout = Duration::from_secs(crate::utils::constants::app::DOWNLOAD_STALL_TIMEOUT_SECS);
+        !self.finished
+            && self.last_progress.is_some_and(|last| now.saturating_duration_since(last) >= timeout)
+    }
+}
+
 /// 任务管理器
 #[derive(Debug, Clone)]
 pub struct TaskManager {
@@ -325,7 +1329,14 @@ impl Default for TaskManager {
 
 impl TaskManager {
     /// 开始新任务
-    pub fn start_task(&mut self, task_type: TaskType, total_files: usize) {
+    /// 同一时间只跟踪一个任务：已有任务运行时拒绝启动并返回 false，避免覆盖其进度
+    #[must_use]
+    pub fn start_task(&mut self, task_type: TaskType, total_files: usize) -> bool {
+        if self.is_running() {
+            log::warn!("已有任务正在运行，拒绝启动新任务: {:?}", task_type);
+            return false;
+        }
+
         self.current_task = Some(ProgressInfo {
             task_type,
             status: TaskStatus::Running,
@@ -336,15 +1347,24 @@ impl TaskManager {
             start_time: Some(std::time::SystemTime::now()),
             estimated_remaining: None,
             processing_speed: None,
+            end_time: None,
+            workload: 0.0,
+            completed_files: 0,
+            succeeded_files: 0,
+            failed_files: 0,
+            file_fractions: HashMap::new(),
         });
         self.show_progress = true;
         self.can_cancel = true;
+        true
     }
 
     /// 更新进度
     pub fn update_progress(&mut self, current_file: usize, filename: &str) {
         if let Some(ref mut task) = self.current_task {
             task.current_file = current_file;
+            task.completed_files = current_file;
+            task.file_fractions.clear();
             // 避免不必要的字符串克隆，只在文件名变化时更新
             if task.current_filename != filename {
                 task.current_filename = filename.to_string();
@@ -369,17 +1389,80 @@ impl TaskManager {
         }
     }
 
+    /// 更新单个文件的转换进度，整体进度计入正在转换文件的完成比例
+    pub fn update_file_progress(&mut self, task_id: usize, fraction: f32) {
+        if let Some(ref mut task) = self.current_task {
+            task.file_fractions.insert(task_id, fraction.clamp(0.0, 1.0));
+            task.refresh_progress();
+        }
+    }
+
+    /// 记录单个文件的处理结果（任务线程异常退出时据此报告部分结果）
+    pub fn record_file_result(&mut self, success: bool) {
+        if let Some(ref mut task) = self.current_task {
+            if success {
+                task.succeeded_files += 1;
+            } else {
+                task.failed_files += 1;
+            }
+        }
+    }
+
+    /// 标记单个文件转换结束（并行任务完成时调用）
+    pub fn finish_file(&mut self, task_id: usize, completed_files: usize) {
+        if let Some(ref mut task) = self.current_task {
+            task.file_fractions.remove(&task_id);
+            task.completed_files = completed_files;
+            task.refresh_progress();
+        }
+    }
+
     /// 完成任务
     pub fn complete_task(&mut self) {
         if let Some(mut task) = self.current_task.take() {
             task.status = TaskStatus::Completed;
             task.progress = 1.0;
+            task.end_time = Some(std::time::SystemTime::now());
             self.task_history.push(task);
         }
         self.show_progress = false;
         self.can_cancel = false;
     }
 
+    /// 记录当前任务的工作量（用于之后的耗时估算）
+    pub fn set_workload(&mut self, workload: f64) {
+        if let Some(ref mut task) = self.current_task {
+            task.workload = workload;
+        }
+    }
+
+    /// 估算指定工作量的耗时（秒），返回 (秒数, 是否基于历史数据)
+    ///
+    /// 优先使用本次会话中同类已完成任务的平均吞吐量，没有历史数据时使用保守的默认速度。
+    pub fn estimate_seconds(&self, task_type: &TaskType, workload: f64) -> Option<(u64, bool)> {
+        let (total_workload, total_seconds) = self
+            .task_history
+            .iter()
+            .filter(|task| &task.task_type == task_type && task.status == TaskStatus::Completed && task.workload > 0.0)
+            .filter_map(|task| {
+                let elapsed = task.end_time?.duration_since(task.start_time?).ok()?;
+                Some((task.workload, elapsed.as_secs_f64()))
+            })
+            .fold((0.0, 0.0), |(w, t), (workload, seconds)| (w + workload, t + seconds));
+
+        if total_seconds > 0.0 {
+            return Some(((workload / (total_workload / total_seconds)).ceil() as u64, true));
+        }
+
+        // 默认吞吐量（字节/秒），仅作为首次估算
+        let default_throughput = match task_type {
+            TaskType::AudioConvert => 5.0 * 1024.0 * 1024.0,
+            TaskType::VideoConvert => 1.5 * 1024.0 * 1024.0,
+            _ => return None,
+        };
+        Some(((workload / default_throughput).ceil() as u64, false))
+    }
+
     /// 任务失败
     pub fn fail_task(&mut self, error: String) {
         if let Some(mut task) = self.current_task.take() {
@@ -422,6 +1505,14 @@ impl ExportSettings {
     fn default_use_google_translate() -> bool {
         true
     }
+
+    /// 导出时放置文件的方式
+    pub fn copy_options(&self) -> crate::file_ops::CopyOptions {
+        crate::file_ops::CopyOptions {
+            use_hardlinks: self.use_hardlinks,
+            buffer_size: (self.copy_buffer_kb > 0).then(|| self.copy_buffer_kb as usize * 1024),
+        }
+    }
 }
 
 impl Default for ExportSettings {
@@ -431,10 +1522,31 @@ impl Default for ExportSettings {
             use_default_logo: true,
             use_stringtable: true,
             use_google_translate: true,
+            only_export_changed: false,
+            use_hardlinks: false,
+            tag_class_names: false,
+            copy_buffer_kb: 0,
+            output_naming: NamingScheme::default(),
         }
     }
 }
 
+/// 项目文件（项目设置和轨道/视频列表），兼容包含这些字段的配置文件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectFile {
+    /// 项目设置
+    pub project: ProjectSettings,
+    /// 轨道列表
+    #[serde(default)]
+    pub tracks: Vec<Track>,
+    /// 视频文件列表
+    #[serde(default)]
+    pub video_files: Vec<VideoFile>,
+    /// 导出设置（配置文件和旧项目文件中可能没有，此时保留当前设置）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_settings: Option<ExportSettings>,
+}
+
 /// 应用程序状态
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppState {
@@ -452,14 +1564,29 @@ pub struct AppState {
     pub video_paths: HashSet<PathBuf>,
     /// 选中的轨道索引
     pub selected_track: Option<usize>,
+    /// 多选的轨道索引（Ctrl/Shift+单击，为空时仅使用 selected_track）
+    #[serde(skip)]
+    pub selected_tracks: Vec<usize>,
+    /// 轨道列表搜索过滤文本（仅影响显示，不区分大小写）
+    #[serde(skip)]
+    pub track_filter: String,
     /// 选中的视频文件索引
     pub selected_video: Option<usize>,
     /// 导出设置
     pub export_settings: ExportSettings,
     /// 是否显示项目设置对话框
     pub show_project_settings: bool,
+    /// 后台检查的轨道是否带有内嵌封面（按文件路径缓存）
+    #[serde(skip)]
+    pub cover_art_cache: BackgroundPathCache<bool>,
     /// 是否显示导出对话框
     pub show_export_dialog: bool,
+    /// 是否显示配置预览窗口
+    #[serde(skip)]
+    pub show_cfg_preview: bool,
+    /// 配置预览文本（config.cpp 与 CfgMusic 轨道列表）
+    #[serde(skip)]
+    pub cfg_preview_text: Option<String>,
     /// 是否显示关于对话框
     pub show_about: bool,
     /// 是否显示新用户指导对话框
@@ -478,14 +1605,27 @@ pub struct AppState {
     pub paa_selected_files: Vec<std::path::PathBuf>,
     /// PAA转换输出目录
     pub paa_output_directory: Option<std::path::PathBuf>,
-    /// PAA转换选项
+    /// PAA转换选项（跨会话持久化）
+    #[serde(default)]
     pub paa_options: crate::paa_converter::PaaOptions,
+    /// PAA转换所选图片的源尺寸（按路径缓存，避免每帧读取图片头）
+    #[serde(skip)]
+    pub paa_source_dimensions: HashMap<PathBuf, Result<(u32, u32), String>>,
+    /// PAA自定义目标尺寸输入框内容
+    #[serde(skip)]
+    pub paa_custom_size_input: String,
+    /// PAA目标尺寸校验提示
+    #[serde(skip)]
+    pub paa_size_message: Option<String>,
     /// 是否显示预览对话框
     pub show_paa_preview: bool,
     /// 是否显示PAA转换结果对话框
     pub show_paa_result: bool,
     /// PAA转换结果消息
     pub paa_result: Option<String>,
+    /// PAA转换结果对应的输出目录（结果对话框中“打开输出目录”使用）
+    #[serde(skip)]
+    pub paa_result_dir: Option<std::path::PathBuf>,
     /// 是否显示轨道计数对话框
     pub show_track_count: bool,
     /// 图片纹理管理器
@@ -497,6 +1637,15 @@ pub struct AppState {
     pub show_export_result: bool,
     /// 导出结果信息
     pub export_result: Option<String>,
+    /// 导出结果对应的输出目录（结果对话框中“打开输出目录”使用）
+    #[serde(skip)]
+    pub export_result_dir: Option<std::path::PathBuf>,
+    /// 上次导出因目录不可写而失败（结果对话框提供“重新选择导出目录”）
+    #[serde(skip)]
+    pub export_dir_not_writable: bool,
+    /// 已通过检查、等待交给后台线程的模组导出（由 app.rs update 启动）
+    #[serde(skip)]
+    pub pending_mod_export: Option<ModExportJob>,
     /// 是否显示音频解密对话框
     pub show_audio_decrypt: bool,
     /// 音频解密选中的文件
@@ -505,10 +1654,34 @@ pub struct AppState {
     pub audio_decrypt_output_directory: Option<std::path::PathBuf>,
     /// 音频解密结果
     pub audio_decrypt_result: Option<String>,
+    /// 音频解密结果对应的输出目录（结果对话框中“打开输出目录”使用）
+    #[serde(skip)]
+    pub audio_decrypt_result_dir: Option<std::path::PathBuf>,
     /// 是否显示音频解密结果对话框
     pub show_audio_decrypt_result: bool,
     /// 是否执行音频解密
     pub should_decrypt_audio: bool,
+    /// 是否需要执行解密→转换→添加流水线（使用音频解密对话框中的文件和输出目录）
+    #[serde(skip)]
+    pub should_run_decrypt_pipeline: bool,
+    /// 音频解密选项（跨会话持久化）
+    #[serde(default)]
+    pub audio_decrypt_options: AudioDecryptOptions,
+    /// 音频转换选项
+    #[serde(default)]
+    pub audio_convert_options: AudioConvertOptions,
+    /// 新轨道的默认分贝和时长回退值（跨会话持久化）
+    #[serde(default)]
+    pub track_defaults: TrackDefaults,
+    /// 界面主题（跨会话持久化）
+    #[serde(default)]
+    pub theme: AppTheme,
+    /// 界面语言（跨会话持久化）
+    #[serde(default)]
+    pub language: crate::i18n::Lang,
+    /// 窗口位置和大小（跨会话持久化，None 表示使用默认大小）
+    #[serde(default)]
+    pub window_geometry: Option<WindowGeometry>,
     /// 是否显示音频转换对话框
     pub show_audio_converter: bool,
     /// 音频转换选中的文件
@@ -517,6 +1690,9 @@ pub struct AppState {
     pub audio_convert_output_directory: Option<std::path::PathBuf>,
     /// 音频转换结果
     pub audio_convert_result: Option<String>,
+    /// 音频转换结果对应的输出目录（结果对话框中“打开输出目录”使用）
+    #[serde(skip)]
+    pub audio_convert_result_dir: Option<std::path::PathBuf>,
     /// 是否显示音频转换结果对话框
     pub show_audio_convert_result: bool,
     /// 是否执行音频转换
@@ -531,6 +1707,15 @@ pub struct AppState {
     pub is_downloading_ffmpeg: bool,
     /// 是否已经启动了下载任务
     pub ffmpeg_download_started: bool,
+    /// FFmpeg下载首先尝试的镜像序号（失败重试时切换）
+    #[serde(skip)]
+    pub ffmpeg_download_mirror_index: usize,
+    /// FFmpeg下载统计
+    #[serde(skip)]
+    pub ffmpeg_download_stats: DownloadStats,
+    /// 是否在当前下载取消后切换镜像重新下载
+    #[serde(skip)]
+    pub ffmpeg_download_switch_pending: bool,
     /// 手动选择的FFmpeg路径
     pub manual_ffmpeg_path: Option<std::path::PathBuf>,
     /// 是否显示手动路径选择对话框
@@ -543,33 +1728,253 @@ pub struct AppState {
     pub video_convert_output_directory: Option<std::path::PathBuf>,
     /// 视频转换结果
     pub video_convert_result: Option<String>,
+    /// 视频转换结果对应的输出目录（结果对话框中“打开输出目录”使用）
+    #[serde(skip)]
+    pub video_convert_result_dir: Option<std::path::PathBuf>,
     /// 是否显示视频转换结果对话框
     pub show_video_convert_result: bool,
     /// 是否执行视频转换
     pub should_convert_video: bool,
+    /// 上一批音频/视频转换中失败的输入文件（用于重试失败项）
+    #[serde(skip)]
+    pub last_failed_conversions: Vec<std::path::PathBuf>,
+    /// 视频转换输出格式（仅 OGV 可用于 Arma 视频模组）
+    #[serde(default)]
+    pub video_output_format: crate::video_converter::VideoFormat,
+    /// 视频转换选项（分辨率上限、质量）
+    #[serde(default)]
+    pub video_convert_options: VideoConvertOptions,
+    /// 视频分片并行转换设置（高级分片设置）
+    #[serde(default)]
+    pub video_chunk_config: crate::video_chunk_converter::VideoChunkConfig,
+    /// 后台读取的视频时长（秒），用于预计分片数；None 表示无法读取
+    #[serde(skip)]
+    pub video_durations: BackgroundPathCache<Option<u32>>,
+    /// 音频转换所选文件的总大小（用于估算耗时）
+    #[serde(skip)]
+    pub audio_convert_size_cache: TotalSizeCache,
+    /// 视频转换所选文件的总大小（用于估算耗时）
+    #[serde(skip)]
+    pub video_convert_size_cache: TotalSizeCache,
+    /// 新导入轨道等待交给后台线程的补全工作（由 app.rs update 启动处理）
+    #[serde(skip)]
+    pub pending_import_jobs: Vec<TrackImportJob>,
     /// 是否显示FFmpeg插件管理对话框
     pub show_ffmpeg_plugin: bool,
-    /// FFmpeg镜像源
+    /// FFmpeg下载首先尝试的镜像源（记住上次的选择）
     pub ffmpeg_mirror_source: crate::ffmpeg_plugin::MirrorSource,
     /// FFmpeg自动下载选项
     pub ffmpeg_auto_download: bool,
+    /// 网络设置（代理、超时）
+    #[serde(default)]
+    pub http_settings: crate::http_client::HttpSettings,
+    /// 并行转换的最大线程数（None 表示自动选择）
+    #[serde(default)]
+    pub max_threads_override: Option<usize>,
+    /// 是否显示性能设置对话框
+    #[serde(skip)]
+    pub show_performance_settings: bool,
     /// 文件操作提示信息
     pub file_operation_message: Option<String>,
     /// 任务管理器
     #[serde(skip)]
     pub task_manager: TaskManager,
+    /// 本次会话的转换输出历史（不持久化，退出即清空）
+    #[serde(skip)]
+    pub conversion_history: Vec<ConversionHistoryEntry>,
+    /// 是否显示转换历史面板
+    #[serde(skip)]
+    pub show_conversion_history: bool,
+    /// 是否开始相似轨道分析
+    #[serde(skip)]
+    pub should_scan_similar_tracks: bool,
+    /// 相似轨道分析结果（None 表示尚未分析）
+    #[serde(skip)]
+    pub similar_track_groups: Option<Vec<crate::track_similarity::SimilarTrackGroup>>,
+    /// 无法解码、仅按名称比较的轨道数
+    #[serde(skip)]
+    pub similar_track_undecodable: usize,
+    /// 是否显示相似轨道对话框
+    #[serde(skip)]
+    pub show_similar_tracks: bool,
+    /// 是否显示游戏内顺序预览
+    #[serde(skip)]
+    pub show_ingame_order: bool,
+    /// 是否开始同步全部轨道时长
+    #[serde(skip)]
+    pub should_sync_durations: bool,
+    /// 是否开始为 Zeus 检查轨道并导出
+    #[serde(skip)]
+    pub should_prepare_for_zeus: bool,
+    /// 轨道列表撤销栈（删除、清空、批量标签、调整顺序前的快照，最新的在末尾）
+    #[serde(skip)]
+    pub track_undo_stack: Vec<Vec<Track>>,
+    /// 导出时名称检查发现问题、可自动修正名称的轨道索引
+    #[serde(skip)]
+    pub name_fix_indices: Vec<usize>,
+    /// 是否开始分析轨道编辑器中当前轨道的音量
+    #[serde(skip)]
+    pub should_analyze_loudness: bool,
+    /// 轨道编辑器打开时检测的 FFmpeg 可用性（None 表示尚未检测；编辑器关闭或安装 FFmpeg 后重新检测）
+    #[serde(skip)]
+    pub track_editor_ffmpeg_available: Option<bool>,
+    /// 是否显示分贝渐变对话框
+    #[serde(skip)]
+    pub show_decibel_ramp: bool,
+    /// 分贝渐变设置
+    #[serde(skip)]
+    pub decibel_ramp: DecibelRamp,
+    /// 是否显示统一调整音量对话框
+    #[serde(skip)]
+    pub show_decibel_adjust: bool,
+    /// 统一调整音量设置
+    #[serde(skip)]
+    pub decibel_adjust: DecibelAdjust,
+    /// 是否显示播放列表播放器
+    #[serde(skip)]
+    pub show_audio_player: bool,
+    /// 是否显示批量设置标签对话框
+    #[serde(skip)]
+    pub show_batch_tag: bool,
+    /// 批量设置的标签
+    #[serde(skip)]
+    pub batch_tag_text: String,
+    /// 时长同步结果说明
+    #[serde(skip)]
+    pub duration_sync_result: Option<String>,
+    /// 是否显示时长同步结果对话框
+    #[serde(skip)]
+    pub show_duration_sync_result: bool,
+    /// 文件夹导入过滤设置（作为默认值持久化）
+    #[serde(default)]
+    pub import_filter: crate::import_filter::ImportFilter,
+    /// 最近使用的导出目录（最新的在前，跨会话保留）
+    #[serde(default)]
+    pub recent_export_dirs: Vec<PathBuf>,
+    /// 是否显示从文件夹导入对话框
+    #[serde(skip)]
+    pub show_folder_import: bool,
+    /// 从文件夹导入：选择的文件夹
+    #[serde(skip)]
+    pub folder_import_dir: Option<PathBuf>,
+    /// 是否开始从文件夹导入
+    #[serde(skip)]
+    pub should_import_folder: bool,
+    /// 是否显示创建容器模组对话框
+    #[serde(skip)]
+    pub show_container_mod: bool,
+    /// 容器模组：要打包的已有PBO文件
+    #[serde(skip)]
+    pub container_mod_pbos: Vec<PathBuf>,
+    /// 后台分析的波形（按文件路径缓存，避免重复解码）
+    #[serde(skip)]
+    pub waveform_cache: BackgroundPathCache<Result<crate::audio::Waveform, String>>,
+    /// 等待波形分析完成后应用建议裁剪点的文件路径
+    #[serde(skip)]
+    pub trim_suggestion_pending: Option<PathBuf>,
+    /// 轨道编辑器中正在调整的裁剪点（文件路径, 裁剪范围）
+    #[serde(skip)]
+    pub trim_edit: Option<(PathBuf, TrackTrim)>,
+    /// 是否显示按章节分割对话框
+    #[serde(skip)]
+    pub show_chapter_split: bool,
+    /// 按章节分割：源文件
+    #[serde(skip)]
+    pub chapter_split_source: Option<PathBuf>,
+    /// 按章节分割：后台读取的章节（按文件路径缓存，错误时为错误信息）
+    #[serde(skip)]
+    pub chapter_cache: BackgroundPathCache<Result<Vec<crate::audio::AudioChapter>, String>>,
+    /// 按章节分割：输出目录
+    #[serde(skip)]
+    pub chapter_split_output_dir: Option<PathBuf>,
+    /// 是否开始按章节分割
+    #[serde(skip)]
+    pub should_split_chapters: bool,
+    /// 配置文件版本（用于迁移旧版本配置）
+    #[serde(default)]
+    pub config_version: u32,
+    /// 是否以安全模式启动（跳过配置加载，且不写回配置文件）
+    #[serde(skip)]
+    pub safe_mode: bool,
+    /// 配置加载提示（迁移、字段丢失、备份等）
+    #[serde(skip)]
+    pub config_load_notice: Option<String>,
+    /// 是否已关闭安全模式提示
+    #[serde(skip)]
+    pub safe_mode_dialog_dismissed: bool,
+    /// 是否显示项目比较对话框
+    #[serde(skip)]
+    pub show_project_compare: bool,
+    /// 项目比较：旧版本文件
+    #[serde(skip)]
+    pub compare_before_path: Option<PathBuf>,
+    /// 项目比较：新版本文件
+    #[serde(skip)]
+    pub compare_after_path: Option<PathBuf>,
+    /// 项目比较结果（错误时为错误信息）
+    #[serde(skip)]
+    pub compare_result: Option<Result<Vec<crate::project_diff::TrackChange>, String>>,
+    /// 关闭窗口时若有运行中的任务或未导出的修改，是否先确认（"不再询问"后为 false）
+    #[serde(default = "AppState::default_confirm_on_close")]
+    pub confirm_on_close: bool,
+    /// 是否显示关闭确认对话框
+    #[serde(skip)]
+    pub show_close_confirm: bool,
+    /// 用户已确认关闭，不再拦截关闭请求
+    #[serde(skip)]
+    pub close_confirmed: bool,
+    /// 等待确认的项目文件（有未导出的修改时先询问再替换当前项目）
+    #[serde(skip)]
+    pub pending_project_load: Option<PathBuf>,
 }
 
+/// 当前配置文件版本
+pub const CONFIG_VERSION: u32 = 2;
+
 
 impl AppState {
+    fn default_confirm_on_close() -> bool {
+        true
+    }
+
+    /// 获取关闭窗口前需要用户确认的原因（为空表示可以直接关闭）
+    pub fn close_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.task_manager.is_running() {
+            if let Some(task) = self.task_manager.get_current_progress() {
+                warnings.push(format!(
+                    "任务正在进行中（{}/{}），关闭将中断任务",
+                    task.current_file, task.total_files
+                ));
+            }
+        }
+        let dirty = self.dirty_track_count();
+        if dirty > 0 {
+            warnings.push(format!("有 {} 个轨道自上次导出以来已修改但尚未导出", dirty));
+        }
+        warnings
+    }
+
     /// 防重复添加轨道（基于文件路径）
     pub fn add_track_with_duplicate_check(&mut self, track: Track) -> bool {
+        // 缓存与列表中的唯一路径不一致说明有代码绕过了缓存直接修改列表，先自动修复
+        // （复制的轨道与原轨道共用路径，因此大小不同时再按唯一路径数确认）
+        if self.track_paths.len() != self.tracks.len()
+            && self.track_paths.len() != self.tracks.iter().map(|t| &t.path).collect::<HashSet<_>>().len()
+        {
+            log::warn!("轨道路径缓存与轨道列表不一致，正在重建缓存");
+            self.rebuild_path_caches();
+        }
         // 使用HashSet进行O(1)重复检测
         if self.track_paths.contains(&track.path) {
             return false; // 重复，未添加
         }
         self.track_paths.insert(track.path.clone());
+        if let Some(job) = TrackImportJob::for_track(&track, &self.track_defaults) {
+            self.pending_import_jobs.push(job);
+        }
         self.tracks.push(track);
+        self.debug_assert_path_caches();
         true // 成功添加
     }
 
@@ -589,6 +1994,24 @@ impl AppState {
         (added_count, duplicate_count)
     }
 
+    /// 复制轨道并插入到原轨道之后，返回副本的索引
+    ///
+    /// 副本与原轨道指向同一文件（有意绕过路径重复检测），名称追加“ (副本)”，
+    /// 以便为同一 OGG 设置不同的标签或分贝。副本成为当前选中的轨道。
+    pub fn duplicate_track(&mut self, index: usize) -> Option<usize> {
+        let mut copy = self.tracks.get(index)?.clone();
+        copy.id = Track::generate_id();
+        copy.track_name.push_str(" (副本)");
+        copy.mark_dirty();
+
+        let new_index = index + 1;
+        self.tracks.insert(new_index, copy);
+        self.selected_track = Some(new_index);
+        self.selected_tracks.clear();
+        self.debug_assert_path_caches();
+        Some(new_index)
+    }
+
     /// 获取轨道重复统计信息
     pub fn get_track_duplicate_info(&self) -> String {
         let total_tracks = self.tracks.len();
@@ -603,13 +2026,49 @@ impl AppState {
         }
     }
 
+    /// 在破坏性修改轨道列表前保存快照（超过 `MAX_UNDO_SNAPSHOTS` 时丢弃最旧的）
+    fn push_track_undo_snapshot(&mut self) {
+        self.track_undo_stack.push(self.tracks.clone());
+        let excess = self
+            .track_undo_stack
+            .len()
+            .saturating_sub(crate::utils::constants::app::MAX_UNDO_SNAPSHOTS);
+        self.track_undo_stack.drain(..excess);
+    }
+
+    /// 是否有可撤销的轨道列表修改
+    pub fn can_undo_tracks(&self) -> bool {
+        !self.track_undo_stack.is_empty()
+    }
+
+    /// 恢复上一次破坏性修改前的轨道列表，没有可撤销的修改时返回 false
+    pub fn undo_track_change(&mut self) -> bool {
+        let Some(tracks) = self.track_undo_stack.pop() else {
+            return false;
+        };
+        self.tracks = tracks;
+        self.rebuild_path_caches();
+        let len = self.tracks.len();
+        self.selected_track = match self.selected_track {
+            Some(_) if len == 0 => None,
+            Some(index) => Some(index.min(len - 1)),
+            None => None,
+        };
+        self.selected_tracks.retain(|&index| index < len);
+        true
+    }
+
     /// 移除选中的轨道
     pub fn remove_selected_track(&mut self) {
+        self.selected_tracks.clear();
         if let Some(index) = self.selected_track {
             if index < self.tracks.len() {
+                self.push_track_undo_snapshot();
                 let removed_track = self.tracks.remove(index);
-                // 从路径缓存中移除
-                self.track_paths.remove(&removed_track.path);
+                // 从路径缓存中移除（仍有复制的轨道使用该路径时保留）
+                if !self.tracks.iter().any(|t| t.path == removed_track.path) {
+                    self.track_paths.remove(&removed_track.path);
+                }
                 // 调整选中索引，如果删除的是最后一个，则选择前一个
                 if index >= self.tracks.len() && !self.tracks.is_empty() {
                     self.selected_track = Some(index - 1);
@@ -623,11 +2082,438 @@ impl AppState {
         }
     }
 
-    /// 清空所有轨道
+    /// 单击轨道：普通单击单选，Ctrl 切换多选，Shift 从上次选中的轨道起范围选择
+    pub fn click_track(&mut self, index: usize, ctrl: bool, shift: bool) {
+        if shift {
+            let anchor = self.selected_track.unwrap_or(index);
+            self.selected_tracks = (anchor.min(index)..=anchor.max(index)).collect();
+            // 保留范围起点，以便继续调整范围
+            self.selected_track.get_or_insert(index);
+            return;
+        }
+        if ctrl {
+            if self.selected_tracks.is_empty() {
+                self.selected_tracks.extend(self.selected_track);
+            }
+            match self.selected_tracks.iter().position(|&i| i == index) {
+                Some(pos) => {
+                    self.selected_tracks.remove(pos);
+                }
+                None => {
+                    self.selected_tracks.push(index);
+                    self.selected_tracks.sort_unstable();
+                }
+            }
+        } else {
+            self.selected_tracks.clear();
+        }
+        self.selected_track = Some(index);
+    }
+
+    /// 轨道是否处于选中状态（单选或多选）
+    pub fn is_track_selected(&self, index: usize) -> bool {
+        if self.selected_tracks.is_empty() {
+            self.selected_track == Some(index)
+        } else {
+            self.selected_tracks.contains(&index)
+        }
+    }
+
+    /// 当前选中的所有轨道索引（无多选时为单选的轨道）
+    pub fn selected_track_indices(&self) -> Vec<usize> {
+        let indices = if self.selected_tracks.is_empty() {
+            self.selected_track.into_iter().collect()
+        } else {
+            self.selected_tracks.clone()
+        };
+        indices.into_iter().filter(|&i| i < self.tracks.len()).collect()
+    }
+
+    /// 为指定轨道设置同一标签，返回标签发生变化的轨道数
+    pub fn apply_tag_to_tracks(&mut self, indices: &[usize], tag: &str) -> usize {
+        let tag = tag.trim();
+        if indices.iter().any(|&index| self.tracks.get(index).is_some_and(|track| track.tag != tag)) {
+            self.push_track_undo_snapshot();
+        }
+        let mut changed = 0;
+        for &index in indices {
+            if let Some(track) = self.tracks.get_mut(index) {
+                if track.tag != tag {
+                    track.tag = tag.to_string();
+                    track.mark_dirty();
+                    changed += 1;
+                }
+            }
+        }
+        changed
+    }
+
+    /// 记录导出目录（去重，最新的在前，最多保留 MAX_RECENT_EXPORT_DIRS 个）
+    pub fn remember_export_dir(&mut self, dir: &Path) {
+        self.recent_export_dirs.retain(|existing| existing != dir);
+        self.recent_export_dirs.insert(0, dir.to_path_buf());
+        self.recent_export_dirs
+            .truncate(crate::utils::constants::app::MAX_RECENT_EXPORT_DIRS);
+    }
+
+    /// 选中的轨道能否上移/下移
+    pub fn can_move_selected_track(&self, up: bool) -> bool {
+        match self.selected_track {
+            Some(index) if index < self.tracks.len() => {
+                if up { index > 0 } else { index + 1 < self.tracks.len() }
+            }
+            _ => false,
+        }
+    }
+
+    /// 将选中的轨道与相邻轨道交换（决定导出顺序），选中项跟随移动；无法移动时返回 false
+    pub fn move_selected_track(&mut self, up: bool) -> bool {
+        if !self.can_move_selected_track(up) {
+            return false;
+        }
+        let Some(index) = self.selected_track else {
+            return false;
+        };
+        let target = if up { index - 1 } else { index + 1 };
+        self.push_track_undo_snapshot();
+        self.tracks.swap(index, target);
+        self.selected_track = Some(target);
+        self.selected_tracks.clear();
+        true
+    }
+
+    /// 指定轨道按当前导出设置生成的配置类名
+    pub fn track_config_class_name(&self, index: usize) -> Option<String> {
+        let track = self.tracks.get(index)?;
+        Some(crate::templates::TemplateEngine::config_class_name(
+            &self.project,
+            track,
+            index,
+            self.export_settings.tag_class_names,
+        ))
+    }
+
+    /// 将指定轨道的名称重新生成为 ASCII 拼音名称，返回名称发生变化的轨道数（可撤销）
+    ///
+    /// 与其他轨道转写后同名时追加数字后缀（如 `zhandou 2`），保证游戏内名称互不相同。
+    pub fn fix_track_names(&mut self, indices: &[usize]) -> usize {
+        use crate::utils::string_utils::StringUtils;
+
+        let indices: Vec<usize> = indices.iter().copied().filter(|&i| i < self.tracks.len()).collect();
+        if indices.is_empty() {
+            return 0;
+        }
+        self.push_track_undo_snapshot();
+
+        // 不修改的轨道保留原名称，新名称需避开它们的转写结果
+        let mut used: HashSet<String> = self.tracks.iter()
+            .enumerate()
+            .filter(|(i, _)| !indices.contains(i))
+            .map(|(_, track)| StringUtils::to_ascii_safe_pinyin(&track.track_name))
+            .collect();
+        let mut changed = 0;
+        for index in indices {
+            let base = StringUtils::to_ascii_safe_pinyin(&self.tracks[index].track_name);
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while used.contains(&name) {
+                name = format!("{} {}", base, suffix);
+                suffix += 1;
+            }
+            used.insert(name.clone());
+
+            let track = &mut self.tracks[index];
+            if track.track_name != name {
+                track.track_name = name;
+                track.internally_renamed = StringUtils::is_internal_rename_result(&track.track_name);
+                track.mark_dirty();
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// 按文件路径移除轨道，返回移除数量
+    pub fn remove_tracks_by_path(&mut self, paths: &[PathBuf]) -> usize {
+        if self.tracks.iter().any(|track| paths.contains(&track.path)) {
+            self.push_track_undo_snapshot();
+        }
+        let before = self.tracks.len();
+        self.tracks.retain(|track| !paths.contains(&track.path));
+        self.rebuild_path_caches();
+        let removed = before - self.tracks.len();
+        if removed > 0 {
+            self.selected_track = None;
+            self.selected_tracks.clear();
+        }
+        removed
+    }
+
+    /// 应用实际测量的时长，返回时长发生变化的轨道数（可撤销）
+    ///
+    /// 结果按发起测量时的轨道索引应用，轨道已被移动或删除时跳过。
+    pub fn apply_measured_durations(&mut self, measured: &[(usize, PathBuf, u32)]) -> usize {
+        let changes_duration = measured.iter().any(|(index, path, duration)| {
+            self.tracks.get(*index).is_some_and(|t| &t.path == path && t.duration != *duration)
+        });
+        if changes_duration {
+            self.push_track_undo_snapshot();
+        }
+        let mut changed = 0;
+        for (index, path, duration) in measured {
+            if let Some(track) = self.tracks.get_mut(*index).filter(|t| &t.path == path) {
+                track.duration_estimated = false;
+                if track.duration != *duration {
+                    track.duration = *duration;
+                    track.mark_dirty();
+                    changed += 1;
+                }
+            }
+        }
+        changed
+    }
+
+    /// 应用后台补全的轨道信息，返回更新的轨道数
+    ///
+    /// 按路径应用到所有指向该文件的轨道（包括副本）；用户已手动修改的时长、名称或已选择的 Logo 不会被覆盖。
+    pub fn apply_import_extras(&mut self, extras: &TrackImportExtras) -> usize {
+        let mut updated = 0;
+        for track in self.tracks.iter_mut().filter(|t| t.path == extras.path) {
+            let mut changed = false;
+            if let Some(duration) = extras.duration.filter(|_| track.duration_estimated) {
+                track.original_duration = duration;
+                track.duration = duration;
+                track.duration_estimated = false;
+                changed = true;
+            }
+            if let Some(logo_path) = extras.logo_path.as_ref().filter(|_| track.logo_path.is_none()) {
+                track.logo_path = Some(logo_path.clone());
+                changed = true;
+            }
+            // 名称仍为导入时的文件名才替换为标签标题
+            let default_name = track.path.file_stem().map(|s| s.to_string_lossy());
+            if let Some(title) = extras.title.as_ref().filter(|_| default_name.as_deref() == Some(track.track_name.as_str())) {
+                track.track_name = title.clone();
+                track.mark_dirty();
+                changed = true;
+            }
+            if changed {
+                updated += 1;
+            }
+        }
+        updated
+    }
+
+    /// 记录音量分析结果并将分贝设为建议值；轨道已被移动或删除时返回 false
+    pub fn apply_measured_loudness(&mut self, track_index: usize, path: &Path, loudness_lufs: f32) -> bool {
+        let Some(track) = self.tracks.get_mut(track_index).filter(|t| t.path == path) else {
+            return false;
+        };
+        track.loudness_lufs = Some(loudness_lufs);
+        if let Some(suggested) = track.suggested_decibels() {
+            if track.decibels != suggested {
+                track.decibels = suggested;
+                track.mark_dirty();
+            }
+        }
+        true
+    }
+
+    /// 按列表顺序应用分贝渐变，返回分贝发生变化的轨道数（可撤销）
+    pub fn apply_decibel_ramp(&mut self, ramp: &DecibelRamp) -> usize {
+        let values = ramp.values(self.tracks.len());
+        if !self.tracks.iter().zip(&values).any(|(track, decibels)| track.decibels != *decibels) {
+            return 0;
+        }
+        self.push_track_undo_snapshot();
+        let mut changed = 0;
+        for (track, decibels) in self.tracks.iter_mut().zip(values) {
+            if track.decibels != decibels {
+                track.decibels = decibels;
+                track.mark_dirty();
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// 对所有轨道统一调整分贝，返回分贝发生变化的轨道数（可撤销）
+    pub fn apply_decibel_adjust(&mut self, adjust: &DecibelAdjust) -> usize {
+        if !self.tracks.iter().any(|track| adjust.apply(track.decibels).0 != track.decibels) {
+            return 0;
+        }
+        self.push_track_undo_snapshot();
+        let mut changed = 0;
+        for track in &mut self.tracks {
+            let (decibels, _) = adjust.apply(track.decibels);
+            if track.decibels != decibels {
+                track.decibels = decibels;
+                track.mark_dirty();
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// 轨道列表中的最小和最大分贝值，没有轨道时返回 None
+    pub fn decibel_range(&self) -> Option<(i32, i32)> {
+        let min = self.tracks.iter().map(|t| t.decibels).min()?;
+        let max = self.tracks.iter().map(|t| t.decibels).max()?;
+        Some((min, max))
+    }
+
+    /// 根据轨道和视频列表重建路径缓存
+    ///
+    /// 批量修改列表（加载项目、导入、撤销等）后调用，保证重复检测结果正确。
+    pub fn rebuild_path_caches(&mut self) {
+        self.track_paths = self.tracks.iter().map(|t| t.path.clone()).collect();
+        self.video_paths = self.video_files.iter().map(|v| v.path.clone()).collect();
+        self.debug_assert_path_caches();
+    }
+
+    /// 调试构建下检查路径缓存与列表中的唯一路径一致
+    pub fn debug_assert_path_caches(&self) {
+        debug_assert_eq!(
+            self.track_paths.len(),
+            self.tracks.iter().map(|t| &t.path).collect::<HashSet<_>>().len(),
+            "轨道路径缓存与轨道列表不一致"
+        );
+        debug_assert_eq!(
+            self.video_paths.len(),
+            self.video_files.iter().map(|v| &v.path).collect::<HashSet<_>>().len(),
+            "视频路径缓存与视频列表不一致"
+        );
+    }
+
+    /// 用项目文件中的项目设置、轨道/视频列表和导出设置替换当前项目，返回加载说明
+    ///
+    /// 重复路径的视频只保留第一个；源文件缺失的条目保留，并在说明中提示。
+    pub fn apply_project_file(&mut self, file: ProjectFile) -> String {
+        // 轨道按保存的顺序全部保留（用户可能有意重复添加同一文件），视频按路径去重
+        let tracks = file.tracks;
+        let mut seen = HashSet::new();
+        let video_count = file.video_files.len();
+        let video_files: Vec<VideoFile> = file.video_files.into_iter().filter(|v| seen.insert(v.path.clone())).collect();
+        let duplicates = video_count - video_files.len();
+        let mut seen = HashSet::new();
+        let missing: Vec<&PathBuf> = tracks.iter().map(|t| &t.path)
+            .chain(video_files.iter().map(|v| &v.path))
+            .filter(|path| seen.insert(*path) && !path.exists())
+            .collect();
+        for path in &missing {
+            log::warn!("项目引用的源文件不存在: {:?}", path);
+        }
+        let missing_names: Vec<String> = missing.iter()
+            .take(5)
+            .map(|path| path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().to_string())
+            .collect();
+        let missing = missing.len();
+
+        self.project = file.project;
+        if let Some(export_settings) = file.export_settings {
+            self.export_settings = export_settings;
+        }
+        self.tracks = tracks;
+        self.tracks.iter_mut().for_each(Track::refresh_file_size);
+        self.video_files = video_files;
+        self.selected_track = None;
+        self.selected_tracks.clear();
+        self.selected_video = None;
+        self.show_track_editor = false;
+        self.track_undo_stack.clear();
+        self.cover_art_cache.clear();
+        self.rebuild_path_caches();
+
+        let mut summary = format!(
+            "已加载项目 {}: {} 个轨道，{} 个视频",
+            self.project.mod_name,
+            self.tracks.len(),
+            self.video_files.len()
+        );
+        if duplicates > 0 {
+            summary.push_str(&format!("，忽略 {} 个重复视频", duplicates));
+        }
+        if missing > 0 {
+            summary.push_str(&format!("；⚠️ {} 个源文件不存在: {}", missing, missing_names.join(", ")));
+            if missing > missing_names.len() {
+                summary.push_str(" 等");
+            }
+        }
+        summary
+    }
+
+    /// 将项目设置、轨道列表和导出设置保存为项目文件（.zmm）
+    pub fn save_project(&self, path: &Path) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        let file = ProjectFile {
+            project: self.project.clone(),
+            tracks: self.tracks.clone(),
+            video_files: self.video_files.clone(),
+            export_settings: Some(self.export_settings.clone()),
+        };
+        let json = serde_json::to_string_pretty(&file).context("无法序列化项目")?;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("无法创建项目目录: {:?}", parent))?;
+        }
+        std::fs::write(path, json).with_context(|| format!("无法写入项目文件: {:?}", path))?;
+        log::info!("项目已保存: {:?}", path);
+        Ok(())
+    }
+
+    /// 设置项目 Logo；路径变化时清空轨道封面检查缓存，封面被替换或删除后重新检查
+    pub fn set_project_logo(&mut self, logo_path: PathBuf) {
+        if self.project.logo_path.as_ref() != Some(&logo_path) {
+            self.cover_art_cache.clear();
+        }
+        self.project.logo_path = Some(logo_path);
+        self.project.use_default_logo = false;
+    }
+
+    /// 打开项目文件替换当前项目，返回加载说明
+    ///
+    /// 文件格式不正确时返回错误且不修改当前项目；引用的源文件缺失时仍然加载，并在说明中列出。
+    pub fn load_project(&mut self, path: &Path) -> anyhow::Result<String> {
+        let file = crate::file_ops::FileOperations::load_project_file(path)?;
+        Ok(self.apply_project_file(file))
+    }
+
+    /// 清空所有轨道（可撤销）
     pub fn clear_tracks(&mut self) {
+        if !self.tracks.is_empty() {
+            self.push_track_undo_snapshot();
+        }
         self.tracks.clear();
         self.track_paths.clear();
         self.selected_track = None;
+        self.selected_tracks.clear();
+        self.track_filter.clear();
+    }
+
+    /// 按搜索过滤文本筛选轨道，返回匹配轨道在 `tracks` 中的原始索引
+    ///
+    /// 过滤文本为空时返回全部索引；匹配不区分大小写，比较对象为 `display_name()`。
+    pub fn filtered_track_indices(&self) -> Vec<usize> {
+        let filter = self.track_filter.trim().to_lowercase();
+        self.tracks
+            .iter()
+            .enumerate()
+            .filter(|(_, track)| filter.is_empty() || track.display_name().to_lowercase().contains(&filter))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// 获取自上次导出以来已更改的轨道数量
+    pub fn dirty_track_count(&self) -> usize {
+        self.tracks.iter().filter(|t| t.dirty_since_export).count()
+    }
+
+    /// 导出成功后清除所有轨道的更改标记
+    pub fn clear_dirty_flags(&mut self) {
+        for track in &mut self.tracks {
+            track.dirty_since_export = false;
+        }
     }
 
     /// 获取轨道数量
@@ -642,12 +2528,18 @@ impl AppState {
 
     /// 防重复添加视频文件（基于文件路径）
     pub fn add_video_with_duplicate_check(&mut self, video: VideoFile) -> bool {
+        // 缓存大小与列表不一致说明有代码绕过了缓存直接修改列表，先自动修复
+        if self.video_paths.len() != self.video_files.len() {
+            log::warn!("视频路径缓存与视频列表不一致，正在重建缓存");
+            self.rebuild_path_caches();
+        }
         // 使用HashSet进行O(1)重复检测
         if self.video_paths.contains(&video.path) {
             return false; // 重复，未添加
         }
         self.video_paths.insert(video.path.clone());
         self.video_files.push(video);
+        self.debug_assert_path_caches();
         true // 成功添加
     }
 
@@ -687,6 +2579,111 @@ impl AppState {
         }
     }
 
+    /// 记录一次转换输出，同一输出文件只保留最新记录
+    pub fn record_conversion_output(&mut self, task_type: TaskType, source: PathBuf, output: PathBuf) {
+        self.conversion_history.retain(|entry| entry.output != output);
+        self.conversion_history.push(ConversionHistoryEntry { task_type, source, output, output_exists: true });
+    }
+
+    /// 打开转换历史面板时重新检查各输出文件是否仍然存在
+    pub fn refresh_conversion_history(&mut self) {
+        for entry in &mut self.conversion_history {
+            entry.output_exists = entry.output.exists();
+        }
+    }
+
+    /// 为 Zeus 音乐模组准备项目：规范音乐分类类名、按后台读取的轨道信息检查并修复轨道兼容性
+    ///
+    /// 轨道按实际编码判断是否为 Vorbis；读取结果按索引应用，检查期间轨道被移动时要求重新执行。
+    pub fn prepare_for_zeus(&mut self, probes: &[ZeusTrackProbe]) -> ZeusPreparationReport {
+        let mut report = ZeusPreparationReport::default();
+
+        // 音乐分类类名必须是合法的 Arma 类名（字母或下划线开头，仅含字母、数字、下划线）
+        let class_name_valid = self.project.class_name
+            .chars()
+            .next()
+            .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+            && self.project.class_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !class_name_valid {
+            self.project.update_class_name();
+            if !self.project.class_name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+                self.project.class_name = format!("Zeus_{}", self.project.class_name);
+            }
+            report.steps.push(format!("修正音乐分类类名为: {}", self.project.class_name));
+        } else {
+            report.steps.push(format!("音乐分类类名: {}（CfgMusicClasses）", self.project.class_name));
+        }
+
+        if self.project.author_name.trim().is_empty() || self.project.author_name == ProjectSettings::default().author_name {
+            report.steps.push("提示: 作者名称仍为默认值，可在项目设置中修改".to_string());
+        }
+
+        // 检查并修复轨道
+        let mut fixed_durations = 0;
+        let mut fixed_names = 0;
+        for (index, track) in self.tracks.iter_mut().enumerate() {
+            let Some(probe) = probes.iter().find(|p| p.track_index == index && p.path == track.path) else {
+                report.blocking_issues.push(format!("检查期间轨道已变化，请重新执行: {}", track.path.display()));
+                continue;
+            };
+            if !track.path.exists() {
+                report.blocking_issues.push(format!("源文件不存在: {}", track.path.display()));
+                continue;
+            }
+            match &probe.is_vorbis {
+                Ok(true) => {}
+                Ok(false) => {
+                    report.blocking_issues.push(format!(
+                        "不是OGG Vorbis编码（请先使用音频格式转换）: {}",
+                        track.path.display()
+                    ));
+                    continue;
+                }
+                Err(e) => {
+                    report.blocking_issues.push(format!("无法识别音频编码（{}）: {}", e, track.path.display()));
+                    continue;
+                }
+            }
+
+            if track.track_name.trim().is_empty() {
+                track.track_name = crate::utils::string_utils::StringUtils::generate_track_name_from_path(&track.path, index);
+                track.mark_dirty();
+                fixed_names += 1;
+            }
+
+            if track.duration == 0 {
+                if let Some(duration) = probe.duration {
+                    track.duration = duration;
+                    track.duration_estimated = false;
+                    track.mark_dirty();
+                    fixed_durations += 1;
+                }
+            }
+        }
+
+        if report.blocking_issues.is_empty() {
+            report.steps.push(format!("检查 {} 个轨道: 均为 Arma 兼容的OGG Vorbis文件", self.tracks.len()));
+        }
+        if fixed_names > 0 {
+            report.steps.push(format!("为 {} 个轨道补全了曲目名称", fixed_names));
+        }
+        if fixed_durations > 0 {
+            report.steps.push(format!("为 {} 个轨道读取了实际时长", fixed_durations));
+        }
+        report.steps.push("本次导出启用 Stringtable 本地化并执行完整导出（不修改导出设置）".to_string());
+
+        report
+    }
+
+    /// 为 Zeus 导出使用的推荐设置：本地化曲目名、完整导出，其余沿用当前导出设置
+    pub fn zeus_export_settings(&self) -> ExportSettings {
+        ExportSettings {
+            use_stringtable: true,
+            only_export_changed: false,
+            ..self.export_settings.clone()
+        }
+    }
+
     /// 清空所有视频文件
     pub fn clear_videos(&mut self) {
         self.video_files.clear();
@@ -696,30 +2693,185 @@ impl AppState {
 }
 
 impl AppState {
+    /// 只读取配置文件中保存的窗口位置和大小（在创建窗口之前调用）
+    pub fn load_window_geometry() -> Option<WindowGeometry> {
+        let content = std::fs::read_to_string(Self::get_config_path()).ok()?;
+        let mut value = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+        serde_json::from_value(value.get_mut("window_geometry")?.take()).ok()
+    }
+
     /// 从配置文件加载状态
+    ///
+    /// 旧版本配置会先迁移到当前版本；与当前结构不兼容时逐字段加载，
+    /// 尽量保留可用设置，并在丢失设置前备份原配置文件。
     pub fn load_config() -> Self {
         let config_path = Self::get_config_path();
         
         // 尝试从配置文件加载
-        if let Ok(config_content) = std::fs::read_to_string(&config_path) {
-            if let Ok(mut state) = serde_json::from_str::<AppState>(&config_content) {
-                // 恢复运行时状态
-                state.restore_runtime_state();
-                log::info!("从配置文件加载状态: {:?}", config_path);
+        let config_content = match std::fs::read_to_string(&config_path) {
+            Ok(content) => content,
+            Err(_) => {
+                log::info!("配置文件不存在，使用默认状态");
+                return Self::default();
+            }
+        };
+        
+        let mut value = match serde_json::from_str::<serde_json::Value>(&config_content) {
+            Ok(value) => value,
+            Err(e) => {
+                log::warn!("配置文件格式错误，使用默认状态: {}", e);
+                let mut state = Self::default();
+                state.config_load_notice = Some(format!(
+                    "配置文件已损坏，无法解析（{}），已使用默认设置。{}",
+                    e,
+                    Self::backup_notice(Self::backup_config_file())
+                ));
                 return state;
-            } else {
-                log::warn!("配置文件格式错误，使用默认状态");
             }
-        } else {
-            log::info!("配置文件不存在，使用默认状态");
+        };
+        
+        let migrated_from = Self::migrate_config(&mut value);
+        let mut dropped_fields = Vec::new();
+        let mut state = match serde_json::from_value::<AppState>(value.clone()) {
+            Ok(state) => state,
+            Err(e) => {
+                log::warn!("配置文件与当前版本不兼容，尝试逐字段加载: {}", e);
+                Self::load_partial_config(&value, &mut dropped_fields)
+            }
+        };
+        
+        let mut notices = Vec::new();
+        if let Some(version) = migrated_from {
+            log::info!("配置文件已从版本 {} 迁移到版本 {}", version, CONFIG_VERSION);
+        }
+        if !dropped_fields.is_empty() {
+            notices.push(format!(
+                "以下设置与当前版本不兼容，已恢复为默认值: {}。{}",
+                dropped_fields.join(", "),
+                Self::backup_notice(Self::backup_config_file())
+            ));
+        }
+        
+        // 恢复运行时状态
+        state.config_version = CONFIG_VERSION;
+        state.restore_runtime_state();
+        state.recent_export_dirs.retain(|dir| dir.is_dir());
+        state.config_load_notice = (!notices.is_empty()).then(|| notices.join("\n"));
+        log::info!("从配置文件加载状态: {:?}", config_path);
+        state
+    }
+    
+    /// 安全模式启动：跳过配置加载，使用默认设置
+    pub fn safe_mode_state() -> Self {
+        log::warn!("以安全模式启动，跳过配置加载");
+        Self {
+            safe_mode: true,
+            is_first_launch: false,
+            ..Self::default()
+        }
+    }
+    
+    /// 将旧版本配置迁移到当前版本，返回迁移前的版本号（无需迁移时返回 None）
+    fn migrate_config(value: &mut serde_json::Value) -> Option<u32> {
+        let version = value
+            .get("config_version")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as u32;
+        if version >= CONFIG_VERSION {
+            return None;
+        }
+        let object = value.as_object_mut()?;
+        
+        if version < 2 {
+            // v1 → v2：镜像源此前不可选择，保存的 GitHub 只是默认值；改为下载对话框的默认（代理镜像优先）
+            if object.get("ffmpeg_mirror_source").and_then(|v| v.as_str()) == Some("GitHub") {
+                object.insert("ffmpeg_mirror_source".to_string(), "GitHubProxy".into());
+            }
         }
         
-        // 使用默认状态
-        Self::default()
+        object.insert("config_version".to_string(), CONFIG_VERSION.into());
+        Some(version)
+    }
+    
+    /// 解析项目文件内容，也接受配置文件（只读取其中的项目相关字段，其余字段忽略）
+    ///
+    /// 必须包含 project 字段；缺少的列表为空、缺少导出设置时保留当前设置，
+    /// 与配置加载不同，项目相关字段格式不正确时返回错误而不是跳过。
+    pub fn parse_project_file(content: &str) -> anyhow::Result<ProjectFile> {
+        use anyhow::Context;
+
+        let value = serde_json::from_str::<serde_json::Value>(content.trim_start_matches('\u{feff}'))
+            .context("不是有效的 JSON")?;
+        if value.get("project").is_none() {
+            anyhow::bail!("缺少 project 字段");
+        }
+        serde_json::from_value(value).context("项目文件中存在格式不正确的字段")
+    }
+    
+    /// 逐字段加载配置：在默认配置上依次套用各字段，跳过无法反序列化的字段
+    fn load_partial_config(value: &serde_json::Value, dropped_fields: &mut Vec<String>) -> Self {
+        let mut merged = match serde_json::to_value(Self::default()) {
+            Ok(serde_json::Value::Object(map)) => map,
+            _ => return Self::default(),
+        };
+        
+        if let Some(source) = value.as_object() {
+            for (key, field) in source {
+                let previous = merged.insert(key.clone(), field.clone());
+                if serde_json::from_value::<AppState>(serde_json::Value::Object(merged.clone())).is_err() {
+                    match previous {
+                        Some(previous) => merged.insert(key.clone(), previous),
+                        None => merged.remove(key),
+                    };
+                    log::warn!("跳过不兼容的配置字段: {}", key);
+                    dropped_fields.push(key.clone());
+                }
+            }
+        }
+        
+        serde_json::from_value(serde_json::Value::Object(merged)).unwrap_or_default()
+    }
+    
+    /// 备份当前配置文件，返回备份路径
+    pub fn backup_config_file() -> std::io::Result<std::path::PathBuf> {
+        let config_path = Self::get_config_path();
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let backup_path = config_path.with_extension(format!("json.bak-{}", timestamp));
+        std::fs::copy(&config_path, &backup_path)?;
+        log::info!("配置文件已备份: {:?}", backup_path);
+        Ok(backup_path)
+    }
+    
+    /// 删除配置文件（先备份），下次启动使用默认设置
+    pub fn reset_config_file() -> std::io::Result<Option<std::path::PathBuf>> {
+        let config_path = Self::get_config_path();
+        if !config_path.exists() {
+            return Ok(None);
+        }
+        let backup_path = Self::backup_config_file()?;
+        std::fs::remove_file(&config_path)?;
+        log::info!("配置文件已重置: {:?}", config_path);
+        Ok(Some(backup_path))
+    }
+    
+    fn backup_notice(backup: std::io::Result<std::path::PathBuf>) -> String {
+        match backup {
+            Ok(path) => format!("原配置已备份到: {}", path.display()),
+            Err(e) => format!("备份原配置失败: {}", e),
+        }
     }
     
     /// 保存状态到配置文件
     pub fn save_config(&self) -> Result<(), Box<dyn std::error::Error>> {
+        // 安全模式下不覆盖原有配置，留给用户决定备份或重置
+        if self.safe_mode {
+            log::info!("安全模式下不保存配置");
+            return Ok(());
+        }
+        
         let config_path = Self::get_config_path();
         
         // 确保配置目录存在
@@ -761,11 +2913,11 @@ impl AppState {
         self.selected_track = None;
         self.selected_video = None;
         
-        // 清空路径缓存
-        self.track_paths.clear();
-        self.video_paths.clear();
+        // 重建路径缓存
+        self.rebuild_path_caches();
         
-        // 清空PAA相关状态
+        // 清空PAA相关状态（并修正配置文件中可能存在的非法目标尺寸）
+        self.paa_options.normalize();
         self.paa_selected_files.clear();
         self.paa_output_directory = None;
         self.paa_result = None;
@@ -784,6 +2936,7 @@ impl AppState {
         self.video_convert_selected_files.clear();
         self.video_convert_output_directory = None;
         self.video_convert_result = None;
+        self.last_failed_conversions.clear();
         
         // 重置运行时状态
         self.runtime_texture_manager = None;
@@ -791,6 +2944,8 @@ impl AppState {
         // 重置UI状态（这些不应该被持久化）
         self.show_project_settings = false;
         self.show_export_dialog = false;
+        self.show_cfg_preview = false;
+        self.cfg_preview_text = None;
         self.show_about = false;
         self.show_track_editor = false;
         self.show_paa_preview = false;
@@ -829,10 +2984,15 @@ impl Default for AppState {
             track_paths: HashSet::new(),
             video_paths: HashSet::new(),
             selected_track: None,
+            selected_tracks: Vec::new(),
+            track_filter: String::new(),
             selected_video: None,
             export_settings: ExportSettings::default(),
             show_project_settings: false,
+            cover_art_cache: BackgroundPathCache::default(),
             show_export_dialog: false,
+            show_cfg_preview: false,
+            cfg_preview_text: None,
             show_about: false,
             show_user_guide: false,
             is_first_launch: true,
@@ -842,25 +3002,41 @@ impl Default for AppState {
             paa_selected_files: Vec::new(),
             paa_output_directory: None,
             paa_options: crate::paa_converter::PaaOptions::default(),
+            paa_source_dimensions: HashMap::new(),
+            paa_custom_size_input: String::new(),
+            paa_size_message: None,
             show_paa_preview: false,
             show_paa_result: false,
             paa_result: None,
+            paa_result_dir: None,
             show_track_count: false,
             image_texture_manager: crate::paa_converter::ImageTextureManager::default(),
             runtime_texture_manager: None,
             show_export_result: false,
             export_result: None,
+            export_result_dir: None,
+            export_dir_not_writable: false,
+            pending_mod_export: None,
             show_paa_converter: false,
             show_audio_decrypt: false,
             audio_decrypt_selected_files: Vec::new(),
             audio_decrypt_output_directory: None,
             audio_decrypt_result: None,
+            audio_decrypt_result_dir: None,
             show_audio_decrypt_result: false,
             should_decrypt_audio: false,
+            should_run_decrypt_pipeline: false,
+            audio_decrypt_options: AudioDecryptOptions::default(),
+            audio_convert_options: AudioConvertOptions::default(),
+            track_defaults: TrackDefaults::default(),
+            theme: AppTheme::default(),
+            language: crate::i18n::Lang::default(),
+            window_geometry: None,
             show_audio_converter: false,
             audio_convert_selected_files: Vec::new(),
             audio_convert_output_directory: None,
             audio_convert_result: None,
+            audio_convert_result_dir: None,
             show_audio_convert_result: false,
             should_convert_audio: false,
             show_ffmpeg_download: false,
@@ -868,19 +3044,835 @@ impl Default for AppState {
             ffmpeg_download_status: String::new(),
             is_downloading_ffmpeg: false,
             ffmpeg_download_started: false,
+            ffmpeg_download_mirror_index: 0,
+            ffmpeg_download_stats: DownloadStats::default(),
+            ffmpeg_download_switch_pending: false,
             manual_ffmpeg_path: None,
             show_manual_path_selection: false,
             show_video_converter: false,
             video_convert_selected_files: Vec::new(),
             video_convert_output_directory: None,
             video_convert_result: None,
+            video_convert_result_dir: None,
             show_video_convert_result: false,
             should_convert_video: false,
+            last_failed_conversions: Vec::new(),
+            video_output_format: crate::video_converter::VideoFormat::default(),
+            video_convert_options: VideoConvertOptions::default(),
+            video_chunk_config: crate::video_chunk_converter::VideoChunkConfig::default(),
+            video_durations: BackgroundPathCache::default(),
+            audio_convert_size_cache: TotalSizeCache::default(),
+            video_convert_size_cache: TotalSizeCache::default(),
+            pending_import_jobs: Vec::new(),
             show_ffmpeg_plugin: false,
             ffmpeg_mirror_source: crate::ffmpeg_plugin::MirrorSource::default(),
             ffmpeg_auto_download: true,
+            http_settings: crate::http_client::HttpSettings::default(),
+            max_threads_override: None,
+            show_performance_settings: false,
             file_operation_message: None,
             task_manager: TaskManager::default(),
+            conversion_history: Vec::new(),
+            show_conversion_history: false,
+            should_scan_similar_tracks: false,
+            similar_track_groups: None,
+            similar_track_undecodable: 0,
+            show_similar_tracks: false,
+            show_ingame_order: false,
+            should_sync_durations: false,
+            should_prepare_for_zeus: false,
+            should_analyze_loudness: false,
+            track_editor_ffmpeg_available: None,
+            track_undo_stack: Vec::new(),
+            name_fix_indices: Vec::new(),
+            show_decibel_ramp: false,
+            decibel_ramp: DecibelRamp::default(),
+            show_decibel_adjust: false,
+            decibel_adjust: DecibelAdjust::default(),
+            show_audio_player: false,
+            show_batch_tag: false,
+            batch_tag_text: String::new(),
+            duration_sync_result: None,
+            show_duration_sync_result: false,
+            import_filter: crate::import_filter::ImportFilter::default(),
+            recent_export_dirs: Vec::new(),
+            show_folder_import: false,
+            folder_import_dir: None,
+            should_import_folder: false,
+            show_container_mod: false,
+            container_mod_pbos: Vec::new(),
+            waveform_cache: BackgroundPathCache::default(),
+            trim_suggestion_pending: None,
+            trim_edit: None,
+            show_chapter_split: false,
+            chapter_split_source: None,
+            chapter_cache: BackgroundPathCache::default(),
+            chapter_split_output_dir: None,
+            should_split_chapters: false,
+            config_version: CONFIG_VERSION,
+            safe_mode: false,
+            config_load_notice: None,
+            safe_mode_dialog_dismissed: false,
+            show_project_compare: false,
+            compare_before_path: None,
+            compare_after_path: None,
+            compare_result: None,
+            confirm_on_close: true,
+            show_close_confirm: false,
+            close_confirmed: false,
+            pending_project_load: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_task_rejected_while_another_is_running() {
+        let mut manager = TaskManager::default();
+        assert!(manager.start_task(TaskType::PaaConvert, 10));
+        manager.update_progress(4, "cover.png");
+
+        assert!(!manager.start_task(TaskType::AudioConvert, 3));
+
+        let task = manager.get_current_progress().expect("任务应仍在运行");
+        assert_eq!(task.task_type, TaskType::PaaConvert);
+        assert_eq!(task.total_files, 10);
+        assert_eq!(task.current_file, 4);
+        assert_eq!(task.current_filename, "cover.png");
+    }
+
+    #[test]
+    fn test_start_task_allowed_after_completion() {
+        let mut manager = TaskManager::default();
+        assert!(manager.start_task(TaskType::PaaConvert, 1));
+        manager.complete_task();
+
+        assert!(manager.start_task(TaskType::AudioConvert, 2));
+        let task = manager.get_current_progress().expect("新任务应已启动");
+        assert_eq!(task.task_type, TaskType::AudioConvert);
+        assert_eq!(manager.task_history.len(), 1);
+    }
+
+    #[test]
+    fn test_file_results_counted_separately_from_progress() {
+        let mut manager = TaskManager::default();
+        manager.record_file_result(true);
+        assert!(manager.start_task(TaskType::AudioDecrypt, 5));
+        manager.record_file_result(true);
+        manager.record_file_result(false);
+        manager.record_file_result(true);
+        manager.update_progress(3, "c.kgm");
+
+        let task = manager.get_current_progress().unwrap();
+        assert_eq!((task.succeeded_files, task.failed_files), (2, 1));
+        assert_eq!(task.current_file, 3);
+    }
+
+    #[test]
+    fn test_rebuild_path_caches_repairs_direct_mutation() {
+        let mut state = AppState::default();
+        let path = PathBuf::from("music/song.ogg");
+        state.tracks.push(Track::new(path.clone(), "Song".to_string(), "Song".to_string()));
+
+        // 绕过缓存直接修改列表后，添加同一路径仍应被判定为重复
+        assert!(!state.add_track_with_duplicate_check(Track::new(path.clone(), "Song".to_string(), "Song".to_string())));
+        assert_eq!(state.tracks.len(), 1);
+        assert!(state.track_paths.contains(&path));
+
+        state.tracks.clear();
+        state.rebuild_path_caches();
+        assert!(state.track_paths.is_empty());
+    }
+
+    #[test]
+    fn test_file_progress_contributes_to_overall_progress() {
+        let mut manager = TaskManager::default();
+        assert!(manager.start_task(TaskType::AudioConvert, 4));
+        manager.update_progress(1, "b.mp3");
+        manager.update_file_progress(1, 0.5);
+        let progress = manager.current_task.as_ref().unwrap().progress;
+        assert!((progress - 0.375).abs() < 1e-6);
+
+        // 并行任务：两个文件同时转换，其中一个完成
+        manager.update_file_progress(2, 0.5);
+        manager.finish_file(1, 2);
+        let progress = manager.current_task.as_ref().unwrap().progress;
+        assert!((progress - 0.625).abs() < 1e-6);
+
+        // 进度不会倒退
+        manager.update_file_progress(2, 0.1);
+        let progress = manager.current_task.as_ref().unwrap().progress;
+        assert!((progress - 0.625).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_estimate_seconds_uses_history_throughput() {
+        let mut manager = TaskManager::default();
+        assert_eq!(manager.estimate_seconds(&TaskType::VideoConvert, 0.0), Some((0, false)));
+        assert_eq!(manager.estimate_seconds(&TaskType::PaaConvert, 100.0), None);
+
+        let start = std::time::SystemTime::now();
+        manager.task_history.push(ProgressInfo {
+            task_type: TaskType::VideoConvert,
+            status: TaskStatus::Completed,
+            start_time: Some(start),
+            end_time: Some(start + std::time::Duration::from_secs(100)),
+            workload: 1000.0,
+            ..ProgressInfo::default()
+        });
+        assert_eq!(manager.estimate_seconds(&TaskType::VideoConvert, 500.0), Some((50, true)));
+    }
+
+    #[test]
+    fn test_encode_cost_follows_quality_settings() {
+        assert!((AudioConvertOptions::default().encode_cost() - 1.0).abs() < 1e-9);
+        assert!((VideoConvertOptions::default().encode_cost() - 1.0).abs() < 1e-9);
+
+        let high_quality = AudioConvertOptions { quality: 10, ..AudioConvertOptions::default() };
+        let normalized = AudioConvertOptions { normalize_loudness: true, ..AudioConvertOptions::default() };
+        assert!(high_quality.encode_cost() > 1.0);
+        assert!(normalized.encode_cost() > 1.0);
+
+        let downscaled = VideoConvertOptions { max_height: Some(720), ..VideoConvertOptions::default() };
+        let low_quality = VideoConvertOptions { video_quality: 0, ..VideoConvertOptions::default() };
+        assert!(downscaled.encode_cost() < 1.0);
+        assert!(low_quality.encode_cost() < 1.0);
+    }
+
+    #[test]
+    fn test_duration_sync_apply_and_undo() {
+        let mut state = AppState::default();
+        for name in ["a", "b"] {
+            let mut track = Track::new(PathBuf::from(format!("{}.ogg", name)), name.to_string(), name.to_string());
+            track.duration = 180;
+            track.dirty_since_export = false;
+            state.add_track_with_duplicate_check(track);
+        }
+
+        // 同一文件重复添加的轨道按索引各自更新
+        let mut copy = state.tracks[0].clone();
+        copy.duration = 170;
+        state.tracks.push(copy);
+
+        let measured = vec![(0, PathBuf::from("a.ogg"), 95), (1, PathBuf::from("b.ogg"), 180), (2, PathBuf::from("a.ogg"), 95)];
+        assert_eq!(state.apply_measured_durations(&measured), 2);
+        assert_eq!(state.tracks[0].duration, 95);
+        assert_eq!(state.tracks[2].duration, 95);
+        assert!(state.tracks[0].dirty_since_export);
+        assert!(!state.tracks[1].dirty_since_export);
+
+        assert!(state.undo_track_change());
+        assert_eq!(state.tracks[0].duration, 180);
+        assert_eq!(state.tracks[2].duration, 170);
+        assert!(!state.can_undo_tracks());
+
+        // 测量期间轨道被移动时不应用
+        assert_eq!(state.apply_measured_durations(&[(1, PathBuf::from("a.ogg"), 60)]), 0);
+    }
+
+    #[test]
+    fn test_import_jobs_queue_and_apply_extras() {
+        let mut state = AppState::default();
+        let mut estimated = Track::new(PathBuf::from("a.ogg"), "a".to_string(), "a".to_string());
+        estimated.set_original_values(180, 0);
+        estimated.duration_estimated = true;
+        state.add_track_with_duplicate_check(estimated);
+        state.add_track_with_duplicate_check(Track::new(PathBuf::from("b.ogg"), "b".to_string(), "b".to_string()));
+
+        // 只有文件头没有时长的轨道需要在后台测量
+        let jobs = std::mem::take(&mut state.pending_import_jobs);
+        assert_eq!(jobs, vec![TrackImportJob { path: PathBuf::from("a.ogg"), measure_duration: true, cover_paa: false, read_title: false }]);
+
+        // 开启封面转换时所有新轨道都会生成封面
+        state.track_defaults.cover_paa = true;
+        state.add_track_with_duplicate_check(Track::new(PathBuf::from("c.ogg"), "c".to_string(), "c".to_string()));
+        let jobs = std::mem::take(&mut state.pending_import_jobs);
+        assert_eq!(jobs, vec![TrackImportJob { path: PathBuf::from("c.ogg"), measure_duration: false, cover_paa: true, read_title: false }]);
+
+        // 结果应用到同一文件的所有轨道，已手动修改时长的副本保持不变
+        let mut edited = state.tracks[0].clone();
+        edited.duration = 60;
+        edited.duration_estimated = false;
+        state.tracks.push(edited);
+        let extras = TrackImportExtras { path: PathBuf::from("a.ogg"), duration: Some(95), logo_path: None, title: None };
+        assert_eq!(state.apply_import_extras(&extras), 1);
+        assert_eq!(state.tracks[0].duration, 95);
+        assert!(!state.tracks[0].duration_estimated);
+        assert!(!state.tracks[0].is_modified());
+        assert_eq!(state.tracks[3].duration, 60);
+
+        // 生成的封面不覆盖用户已选择的 Logo
+        state.tracks[2].logo_path = Some(PathBuf::from("custom.paa"));
+        let cover = TrackImportExtras { path: PathBuf::from("c.ogg"), duration: None, logo_path: Some(PathBuf::from("c.paa")), title: None };
+        assert_eq!(state.apply_import_extras(&cover), 0);
+        assert_eq!(state.tracks[2].logo_path, Some(PathBuf::from("custom.paa")));
+
+        // 标签标题只替换仍为文件名的曲目名称
+        state.tracks[1].track_name = "Renamed".to_string();
+        let title = TrackImportExtras { path: PathBuf::from("a.ogg"), duration: None, logo_path: None, title: Some("Title".to_string()) };
+        assert_eq!(state.apply_import_extras(&title), 2);
+        assert_eq!(state.tracks[0].track_name, "Title");
+        let title = TrackImportExtras { path: PathBuf::from("b.ogg"), duration: None, logo_path: None, title: Some("Title".to_string()) };
+        assert_eq!(state.apply_import_extras(&title), 0);
+        assert_eq!(state.tracks[1].track_name, "Renamed");
+
+        // 轨道在后台处理期间被删除时不做任何修改
+        let removed = TrackImportExtras { path: PathBuf::from("d.ogg"), duration: Some(95), logo_path: None, title: None };
+        assert_eq!(state.apply_import_extras(&removed), 0);
+    }
+
+    #[test]
+    fn test_migrate_config_from_v1_resets_unselected_mirror_source() {
+        let mut value = serde_json::json!({ "config_version": 1, "ffmpeg_mirror_source": "GitHub" });
+        assert_eq!(AppState::migrate_config(&mut value), Some(1));
+        assert_eq!(value["ffmpeg_mirror_source"], "GitHubProxy");
+
+        // 新版本中用户选择的 GitHub 不再被修改
+        let mut value = serde_json::json!({ "config_version": CONFIG_VERSION, "ffmpeg_mirror_source": "GitHub" });
+        assert_eq!(AppState::migrate_config(&mut value), None);
+        assert_eq!(value["ffmpeg_mirror_source"], "GitHub");
+    }
+
+    #[test]
+    fn test_load_partial_config_keeps_compatible_fields() {
+        let value = serde_json::json!({
+            "auto_show_guide": false,
+            "paa_options": "不兼容的旧格式",
+        });
+        let mut dropped = Vec::new();
+        let state = AppState::load_partial_config(&value, &mut dropped);
+        assert!(!state.auto_show_guide);
+        assert_eq!(dropped, vec!["paa_options".to_string()]);
+    }
+
+    #[test]
+    fn test_track_defaults_validated_clamps_to_editor_range() {
+        let defaults = TrackDefaults { decibels: -40, duration: 0, cover_paa: true, scan_loudness: false, read_tag_names: true }.validated();
+        assert_eq!(defaults, TrackDefaults { decibels: -10, duration: 1, cover_paa: true, scan_loudness: false, read_tag_names: true });
+        let defaults = TrackDefaults { decibels: -3, duration: 240, cover_paa: false, scan_loudness: false, read_tag_names: false };
+        assert_eq!(defaults.validated(), defaults);
+    }
+
+    #[test]
+    fn test_update_class_name_prefixes_leading_digit() {
+        let mut project = ProjectSettings { mod_name: "1st Squad Music".to_string(), ..Default::default() };
+        project.update_class_name();
+        assert_eq!(project.class_name, "_1st_Squad_Music");
+    }
+
+    #[test]
+    fn test_project_validate() {
+        let mut project = ProjectSettings { mod_name: "Squad Music".to_string(), ..Default::default() };
+        project.update_class_name();
+        assert_eq!(project.validate(), Ok(()));
+
+        assert_eq!(ProjectSettings::default().validate().unwrap_err().len(), 1);
+
+        let project = ProjectSettings { mod_name: " ".to_string(), class_name: String::new(), ..Default::default() };
+        assert_eq!(project.validate().unwrap_err().len(), 2);
+
+        let project = ProjectSettings { mod_name: "Squad".to_string(), class_name: "9Squad".to_string(), ..Default::default() };
+        let problems = project.validate().unwrap_err();
+        assert!(problems[0].contains("开头"), "{:?}", problems);
+    }
+
+    #[test]
+    fn test_app_theme_dark_mode() {
+        assert_eq!(AppTheme::Light.dark_mode(Some(true)), Some(false));
+        assert_eq!(AppTheme::Dark.dark_mode(None), Some(true));
+        assert_eq!(AppTheme::System.dark_mode(Some(true)), Some(true));
+        assert_eq!(AppTheme::System.dark_mode(None), None);
+    }
+
+    #[test]
+    fn test_suggested_decibels_from_rms() {
+        let mut track = Track::new(PathBuf::from("a.ogg"), "a".to_string(), "Cls".to_string());
+        assert_eq!(track.suggested_decibels(), None);
+        track.rms_dbfs = Some(-14.4);
+        assert_eq!(track.suggested_decibels(), Some(-4));
+        track.rms_dbfs = Some(-40.0);
+        assert_eq!(track.suggested_decibels(), Some(5));
+
+        // FFmpeg 分析的综合响度优先于均方根电平
+        track.loudness_lufs = Some(-12.6);
+        assert_eq!(track.suggested_decibels(), Some(-3));
+    }
+
+    #[test]
+    fn test_apply_measured_loudness() {
+        let mut state = AppState::default();
+        state.tracks.push(Track::new(PathBuf::from("a.ogg"), "a".to_string(), "Cls".to_string()));
+        state.tracks[0].dirty_since_export = false;
+
+        assert!(!state.apply_measured_loudness(0, Path::new("b.ogg"), -20.0));
+        assert!(!state.apply_measured_loudness(1, Path::new("a.ogg"), -20.0));
+        assert_eq!(state.tracks[0].loudness_lufs, None);
+
+        assert!(state.apply_measured_loudness(0, Path::new("a.ogg"), -20.0));
+        assert_eq!(state.tracks[0].loudness_lufs, Some(-20.0));
+        assert_eq!(state.tracks[0].decibels, 4);
+        assert!(state.tracks[0].dirty_since_export);
+    }
+
+    #[test]
+    fn test_decibel_ramp_values() {
+        let ramp = DecibelRamp { start: -6, end: 2, curve: DecibelRampCurve::Linear };
+        assert_eq!(ramp.values(5), vec![-6, -4, -2, 0, 2]);
+        assert_eq!(ramp.values(1), vec![-6]);
+        assert!(ramp.values(0).is_empty());
+
+        let ease_in = DecibelRamp { curve: DecibelRampCurve::EaseIn, ..ramp }.values(5);
+        let ease_out = DecibelRamp { curve: DecibelRampCurve::EaseOut, ..ramp }.values(5);
+        assert_eq!((ease_in[0], ease_in[4]), (-6, 2));
+        assert!(ease_in[2] < -2 && ease_out[2] > -2);
+
+        // 超出范围的端点被限制
+        let clamped = DecibelRamp { start: -40, end: 40, curve: DecibelRampCurve::Linear };
+        assert_eq!(clamped.values(2), vec![-10, 5]);
+    }
+
+    #[test]
+    fn test_decibel_ramp_apply_and_undo() {
+        let mut state = AppState::default();
+        for name in ["a.ogg", "b.ogg", "c.ogg"] {
+            state.tracks.push(Track::new(PathBuf::from(name), name.to_string(), "Cls".to_string()));
+        }
+        let ramp = DecibelRamp { start: 0, end: 4, curve: DecibelRampCurve::Linear };
+        assert_eq!(state.apply_decibel_ramp(&ramp), 2);
+        assert_eq!(state.tracks.iter().map(|t| t.decibels).collect::<Vec<_>>(), vec![0, 2, 4]);
+
+        assert!(state.undo_track_change());
+        assert!(state.tracks.iter().all(|t| t.decibels == 0));
+        assert!(!state.can_undo_tracks());
+
+        // 不改变任何分贝时不记录撤销快照
+        assert_eq!(state.apply_decibel_ramp(&DecibelRamp { start: 0, end: 0, curve: DecibelRampCurve::Linear }), 0);
+        assert!(!state.can_undo_tracks());
+    }
+
+    #[test]
+    fn test_decibel_adjust_clamps_and_is_undoable() {
+        let mut state = AppState::default();
+        for (name, decibels) in [("a.ogg", -8), ("b.ogg", 0), ("c.ogg", 4)] {
+            let mut track = Track::new(PathBuf::from(name), name.to_string(), "Cls".to_string());
+            track.set_original_values(60, decibels);
+            state.tracks.push(track);
+        }
+
+        let adjust = DecibelAdjust { mode: DecibelAdjustMode::Offset, value: -3 };
+        assert_eq!(adjust.apply(-8), (-10, true));
+        assert_eq!(state.apply_decibel_adjust(&adjust), 3);
+        assert_eq!(state.decibel_range(), Some((-10, 1)));
+        assert!(state.tracks.iter().all(|t| t.is_modified()));
+
+        assert!(state.undo_track_change());
+        assert_eq!(state.decibel_range(), Some((-8, 4)));
+
+        let set = DecibelAdjust { mode: DecibelAdjustMode::Absolute, value: 0 };
+        assert_eq!(state.apply_decibel_adjust(&set), 2);
+        assert_eq!(state.apply_decibel_adjust(&set), 0);
+        assert!(!state.tracks[1].is_modified());
+    }
+
+    #[test]
+    fn test_apply_project_file_keeps_duplicate_tracks() {
+        let track = |path: &str| Track::new(PathBuf::from(path), path.to_string(), "Cls".to_string());
+        let file = ProjectFile {
+            project: ProjectSettings { mod_name: "Loaded".to_string(), ..ProjectSettings::default() },
+            tracks: vec![track("missing_a.ogg"), track("missing_b.ogg"), track("missing_a.ogg")],
+            ..ProjectFile::default()
+        };
+        let mut state = AppState::default();
+        state.selected_track = Some(0);
+
+        let summary = state.apply_project_file(file);
+        assert_eq!(state.project.mod_name, "Loaded");
+        assert_eq!(state.tracks.len(), 3);
+        assert_eq!(state.tracks[2].path, PathBuf::from("missing_a.ogg"));
+        assert_eq!(state.selected_track, None);
+        assert!(!summary.contains("忽略"), "{}", summary);
+        assert!(summary.contains("2 个源文件不存在: missing_a.ogg, missing_b.ogg"), "{}", summary);
+    }
+
+    #[test]
+    fn test_background_path_cache_requests_each_path_once() {
+        let mut cache = BackgroundPathCache::<Option<u32>>::default();
+        let path = PathBuf::from("clip.ogv");
+        assert_eq!(cache.get_or_request(&path), None);
+        assert_eq!(cache.get_or_request(&path), None);
+        assert_eq!(cache.take_requests(), vec![path.clone()]);
+        assert!(cache.take_requests().is_empty());
+        assert!(cache.is_loading());
+
+        cache.insert(path.clone(), Some(90));
+        assert_eq!(cache.get_or_request(&path), Some(&Some(90)));
+        assert!(!cache.is_loading());
+        assert!(cache.take_requests().is_empty());
+
+        // 清空后重新请求
+        cache.clear();
+        assert_eq!(cache.get_or_request(&path), None);
+        assert_eq!(cache.take_requests(), vec![path]);
+    }
+
+    #[test]
+    fn test_cover_art_cache_cleared_when_logo_changes_or_project_loads() {
+        let mut state = AppState::default();
+        let track = PathBuf::from("song.ogg");
+        state.cover_art_cache.insert(track.clone(), true);
+
+        state.set_project_logo(PathBuf::from("logo.paa"));
+        assert_eq!(state.cover_art_cache.get(&track), None);
+        assert_eq!(state.project.logo_path, Some(PathBuf::from("logo.paa")));
+
+        state.cover_art_cache.insert(track.clone(), true);
+        state.set_project_logo(PathBuf::from("logo.paa"));
+        assert_eq!(state.cover_art_cache.get(&track), Some(&true));
+
+        state.apply_project_file(ProjectFile::default());
+        assert_eq!(state.cover_art_cache.get(&track), None);
+    }
+
+    #[test]
+    fn test_prepare_for_zeus_uses_probed_codec_and_keeps_export_settings() {
+        let dir = std::env::temp_dir().join(format!("zmm_zeus_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut state = AppState::default();
+        for name in ["a.ogg", "b.ogg"] {
+            let path = dir.join(name);
+            std::fs::write(&path, b"x").unwrap();
+            let mut track = Track::new(path, name.to_string(), "Cls".to_string());
+            track.duration = 0;
+            state.tracks.push(track);
+        }
+        state.export_settings.only_export_changed = true;
+        let paths: Vec<PathBuf> = state.tracks.iter().map(|t| t.path.clone()).collect();
+        let probe = |index: usize, is_vorbis: Result<bool, String>| ZeusTrackProbe {
+            track_index: index,
+            path: paths[index].clone(),
+            is_vorbis,
+            duration: Some(42),
+        };
+
+        // 扩展名为 .ogg 但实际不是 Vorbis 编码时阻止导出
+        let probes = vec![probe(0, Ok(true)), probe(1, Ok(false))];
+        let report = state.prepare_for_zeus(&probes);
+        assert_eq!(report.blocking_issues.len(), 1, "{:?}", report.blocking_issues);
+        assert_eq!(state.tracks[0].duration, 42);
+
+        let probes = vec![probe(0, Ok(true)), probe(1, Ok(true))];
+        assert!(state.prepare_for_zeus(&probes).blocking_issues.is_empty());
+        assert!(state.export_settings.only_export_changed);
+        assert!(!state.zeus_export_settings().only_export_changed);
+        assert!(state.zeus_export_settings().use_stringtable);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_and_load_project_round_trip() {
+        let dir = std::env::temp_dir().join(format!("zmm_project_test_{}", std::process::id()));
+        let path = dir.join("test.zmm");
+        let mut state = AppState::default();
+        state.project.mod_name = "Saved".to_string();
+        state.export_settings.append_tags = true;
+        let mut track = Track::new(PathBuf::from("missing_track.ogg"), "Song".to_string(), "Cls".to_string());
+        track.tag = "Tag".to_string();
+        track.duration = 123;
+        state.add_track_with_duplicate_check(track);
+        state.video_files.push(VideoFile::new(PathBuf::from("missing_video.ogv"), "Clip".to_string(), "Cls".to_string()));
+        state.save_project(&path).unwrap();
+
+        // 项目文件只包含项目相关字段，不包含窗口、对话框等应用状态
+        let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let mut keys: Vec<&String> = saved.as_object().unwrap().keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["export_settings", "project", "tracks", "video_files"]);
+
+        let mut loaded = AppState::default();
+        loaded.export_settings.append_tags = false;
+        let summary = loaded.load_project(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.project.mod_name, "Saved");
+        assert!(loaded.export_settings.append_tags);
+        assert_eq!(loaded.tracks.len(), 1);
+        assert_eq!(loaded.tracks[0].tag, "Tag");
+        assert_eq!(loaded.tracks[0].duration, 123);
+        assert!(loaded.track_paths.contains(&PathBuf::from("missing_track.ogg")));
+        assert_eq!(loaded.video_files.len(), 1);
+        assert_eq!(loaded.video_files[0].video_name, "Clip");
+        assert!(summary.contains("2 个源文件不存在"), "{}", summary);
+    }
+
+    #[test]
+    fn test_track_undo_restores_list_and_caches() {
+        let mut state = AppState::default();
+        for name in ["a", "b", "c"] {
+            state.add_track_with_duplicate_check(Track::new(PathBuf::from(name), name.to_string(), "Cls".to_string()));
+        }
+        assert!(!state.can_undo_tracks());
+
+        state.selected_track = Some(2);
+        state.remove_selected_track();
+        state.apply_tag_to_tracks(&[0], "Combat");
+        // 标签未变化时不保存快照
+        state.apply_tag_to_tracks(&[0], "Combat");
+        state.clear_tracks();
+        assert_eq!(state.track_undo_stack.len(), 3);
+
+        assert!(state.undo_track_change());
+        assert_eq!(state.tracks.len(), 2);
+        assert_eq!(state.tracks[0].tag, "Combat");
+        assert!(state.undo_track_change());
+        assert_eq!(state.tracks[0].tag, "");
+        assert!(state.undo_track_change());
+        assert_eq!(state.tracks.len(), 3);
+        assert!(state.track_paths.contains(&PathBuf::from("c")));
+        assert!(!state.undo_track_change());
+
+        // 快照数量有上限
+        for _ in 0..crate::utils::constants::app::MAX_UNDO_SNAPSHOTS + 5 {
+            state.selected_track = Some(0);
+            assert!(state.move_selected_track(false));
+        }
+        assert_eq!(state.track_undo_stack.len(), crate::utils::constants::app::MAX_UNDO_SNAPSHOTS);
+
+        state.apply_project_file(ProjectFile::default());
+        assert!(!state.can_undo_tracks());
+    }
+
+    #[test]
+    fn test_window_geometry_clamped_to_screen() {
+        // 保存在右侧第二显示器上的窗口，恢复时回到主显示器内
+        let geometry = WindowGeometry { position: Some([2500.0, 100.0]), size: [1200.0, 800.0], maximized: false, scale: 1.0 };
+        let clamped = geometry.clamped_to_screen([0.0, 0.0], [1920.0, 1080.0]);
+        assert_eq!(clamped.position, Some([720.0, 100.0]));
+        assert_eq!(clamped.size, [1200.0, 800.0]);
+
+        // 比屏幕大的窗口缩小到屏幕大小；缩放比例 2 时屏幕为 960x540 逻辑像素，但不小于最小大小
+        let geometry = WindowGeometry { position: Some([-50.0, -50.0]), size: [3000.0, 2000.0], maximized: true, scale: 2.0 };
+        let clamped = geometry.clamped_to_screen([0.0, 0.0], [1920.0, 1080.0]);
+        assert_eq!(clamped.size, [960.0, 600.0]);
+        assert_eq!(clamped.position, Some([0.0, 0.0]));
+        assert!(clamped.maximized);
+    }
+
+    #[test]
+    fn test_fix_track_names_adds_numeric_suffix() {
+        let mut state = AppState::default();
+        for name in ["zhandou", "战斗", "站斗"] {
+            state.tracks.push(Track::new(PathBuf::from(name), name.to_string(), "Cls".to_string()));
+        }
+
+        assert_eq!(state.fix_track_names(&[1, 2]), 2);
+        let names: Vec<&str> = state.tracks.iter().map(|t| t.track_name.as_str()).collect();
+        assert_eq!(names, ["zhandou", "zhandou 2", "zhandou 3"]);
+        assert!(state.tracks[1].dirty_since_export);
+
+        assert!(state.undo_track_change());
+        assert_eq!(state.tracks[1].track_name, "战斗");
+    }
+
+    #[test]
+    fn test_click_track_multi_selection_and_batch_tag() {
+        let mut state = AppState::default();
+        for name in ["a", "b", "c", "d"] {
+            state.tracks.push(Track::new(PathBuf::from(format!("{}.ogg", name)), name.to_string(), "Cls".to_string()));
+        }
+        state.click_track(1, false, false);
+        assert_eq!(state.selected_track_indices(), vec![1]);
+        state.click_track(3, true, false);
+        assert_eq!(state.selected_track_indices(), vec![1, 3]);
+        assert_eq!(state.selected_track, Some(3));
+        state.click_track(1, true, false);
+        assert_eq!(state.selected_track_indices(), vec![3]);
+
+        state.click_track(0, false, false);
+        state.click_track(2, false, true);
+        assert_eq!(state.selected_track_indices(), vec![0, 1, 2]);
+        assert!(state.is_track_selected(1) && !state.is_track_selected(3));
+
+        let changed = state.apply_tag_to_tracks(&state.selected_track_indices(), " Album ");
+        assert_eq!(changed, 3);
+        assert_eq!(state.tracks[2].tag, "Album");
+        assert!(state.tracks[3].tag.is_empty());
+    }
+
+    #[test]
+    fn test_remember_export_dir_dedupes_and_caps() {
+        let mut state = AppState::default();
+        for i in 0..12 {
+            state.remember_export_dir(Path::new(&format!("dir{}", i)));
+        }
+        state.remember_export_dir(Path::new("dir5"));
+        assert_eq!(state.recent_export_dirs.len(), 10);
+        assert_eq!(state.recent_export_dirs[0], PathBuf::from("dir5"));
+        assert_eq!(state.recent_export_dirs[1], PathBuf::from("dir11"));
+        assert_eq!(state.recent_export_dirs.iter().filter(|d| d.as_path() == Path::new("dir5")).count(), 1);
+    }
+
+    #[test]
+    fn test_move_selected_track_swaps_and_follows_selection() {
+        let mut state = AppState::default();
+        for name in ["a", "b", "c"] {
+            state.tracks.push(Track::new(PathBuf::from(format!("{}.ogg", name)), name.to_string(), "Cls".to_string()));
+        }
+        assert!(!state.move_selected_track(true));
+
+        state.selected_track = Some(0);
+        assert!(!state.move_selected_track(true));
+        assert!(state.move_selected_track(false));
+        assert_eq!(state.selected_track, Some(1));
+        let names: Vec<&str> = state.tracks.iter().map(|t| t.track_name.as_str()).collect();
+        assert_eq!(names, vec!["b", "a", "c"]);
+
+        state.selected_track = Some(2);
+        assert!(!state.move_selected_track(false));
+    }
+
+    #[test]
+    fn test_project_file_requires_project_field() {
+        assert!(AppState::parse_project_file(r#"{"tracks": []}"#).is_err());
+        assert!(AppState::parse_project_file(r#"{"project": 1}"#).is_err());
+
+        // 只有部分字段的项目文件可以打开，缺少导出设置时保留当前设置
+        let content = serde_json::json!({ "project": ProjectSettings { mod_name: "Partial".to_string(), ..ProjectSettings::default() } });
+        let file = AppState::parse_project_file(&content.to_string()).unwrap();
+        assert_eq!(file.project.mod_name, "Partial");
+        assert!(file.tracks.is_empty());
+        assert!(file.export_settings.is_none());
+
+        // 完整的配置文件（拖入窗口加载）只读取项目相关字段
+        let mut config = AppState::default();
+        config.project.mod_name = "FromConfig".to_string();
+        config.tracks.push(Track::new(PathBuf::from("a.ogg"), "A".to_string(), "Cls".to_string()));
+        let content = format!("\u{feff}{}", serde_json::to_string(&config).unwrap());
+        let file = AppState::parse_project_file(&content).unwrap();
+        assert_eq!(file.project.mod_name, "FromConfig");
+        assert_eq!(file.tracks.len(), 1);
+        assert!(file.export_settings.is_some());
+    }
+
+    #[test]
+    fn test_filtered_track_indices_map_to_original_positions() {
+        let mut state = AppState::default();
+        for name in ["Main Theme", "Battle", "Ending Theme"] {
+            state.tracks.push(Track::new(PathBuf::from(name), name.to_string(), "Cls".to_string()));
+        }
+
+        assert_eq!(state.filtered_track_indices(), vec![0, 1, 2]);
+
+        state.track_filter = "THEME".to_string();
+        assert_eq!(state.filtered_track_indices(), vec![0, 2]);
+
+        state.clear_tracks();
+        assert!(state.track_filter.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_track_inserts_copy_after_original() {
+        let mut state = AppState::default();
+        for name in ["a", "b"] {
+            assert!(state.add_track_with_duplicate_check(Track::new(PathBuf::from(name), name.to_string(), "Cls".to_string())));
+        }
+
+        assert_eq!(state.duplicate_track(0), Some(1));
+        assert_eq!(state.tracks.len(), 3);
+        assert_eq!(state.tracks[1].track_name, "a (副本)");
+        assert_eq!(state.tracks[1].path, state.tracks[0].path);
+        assert_eq!(state.tracks[2].track_name, "b");
+        assert_eq!(state.selected_track, Some(1));
+        assert_eq!(state.duplicate_track(5), None);
+
+        // 删除副本后原轨道的路径仍在缓存中，重复添加仍被拒绝
+        state.remove_selected_track();
+        assert!(state.track_paths.contains(&PathBuf::from("a")));
+        assert!(!state.add_track_with_duplicate_check(Track::new(PathBuf::from("a"), "a".to_string(), "Cls".to_string())));
+    }
+
+    #[test]
+    fn test_video_convert_options_scale_args() {
+        let mut options = VideoConvertOptions::default();
+        assert!(options.scale_args().is_empty());
+        assert_eq!(options.resolution_name(), "原始");
+
+        options.max_height = Some(720);
+        assert_eq!(options.scale_args(), vec!["-vf".to_string(), "scale=-2:'min(720,ih)'".to_string()]);
+        assert_eq!(options.resolution_name(), "720p");
+    }
+
+    #[test]
+    fn download_stats_reports_speed_eta_and_stall() {
+        let t0 = Instant::now();
+        let mut stats = DownloadStats::start(t0);
+        assert!(stats.bytes_per_sec(t0).is_none());
+
+        stats.record(0, Some(10_000_000), 0.0, t0);
+        let t2 = t0 + Duration::from_secs(2);
+        stats.record(2_000_000, Some(10_000_000), 20.0, t2);
+        assert_eq!(stats.bytes_per_sec(t2), Some(1_000_000.0));
+        assert_eq!(stats.eta(t2), Some(Duration::from_secs(8)));
+        assert_eq!(stats.elapsed(t2), Some(Duration::from_secs(2)));
+
+        // 30 秒没有新数据视为停滞，收到新数据后恢复
+        let stalled = t2 + Duration::from_secs(30);
+        stats.record(2_000_000, Some(10_000_000), 20.0, stalled);
+        assert!(stats.is_stalled(stalled));
+        stats.record(2_100_000, Some(10_000_000), 21.0, stalled);
+        assert!(!stats.is_stalled(stalled));
+
+        // 切换镜像后速度重新计算
+        stats.record(0, None, 0.0, stalled);
+        assert!(stats.bytes_per_sec(stalled + Duration::from_secs(5)).is_none());
+
+        stats.record(10_000_000, Some(10_000_000), 100.0, stalled);
+        assert!(!stats.is_stalled(stalled + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn tracks_folder_is_sanitized_like_class_names() {
+        let mut project = ProjectSettings::default();
+        assert_eq!(project.tracks_folder(), "folderwithtracks");
+
+        project.tracks_folder_name = " my tracks-01 ".to_string();
+        assert_eq!(project.tracks_folder(), "my_tracks_01");
+        project.tracks_folder_name = "2024 music".to_string();
+        assert_eq!(project.tracks_folder(), "_2024_music");
+        project.tracks_folder_name = "音乐".to_string();
+        assert_eq!(project.tracks_folder(), "folderwithtracks");
+
+        // 旧项目文件没有该字段时使用默认值
+        let json = serde_json::to_value(ProjectSettings::default()).unwrap();
+        let mut object = json.as_object().unwrap().clone();
+        object.remove("tracks_folder_name");
+        let loaded: ProjectSettings = serde_json::from_value(serde_json::Value::Object(object)).unwrap();
+        assert_eq!(loaded.tracks_folder(), "folderwithtracks");
+    }
+
+    #[test]
+    fn config_class_names_follow_tags_and_index() {
+        let mut state = AppState::default();
+        for name in ["Battle", "Battle", "battle"] {
+            state.tracks.push(Track::new(format!("{}.ogg", name).into(), name.to_string(), state.project.class_name.clone()));
         }
+        state.tracks[1].tag = "Combat".to_string();
+        state.export_settings.tag_class_names = true;
+
+        let class_id = crate::utils::string_utils::StringUtils::to_ascii_safe_pinyin(&state.project.class_name);
+        assert_eq!(state.track_config_class_name(0), Some(format!("{}Song0", class_id)));
+        assert_eq!(state.track_config_class_name(1), Some(format!("Combat_{}Song1", class_id)));
+        assert_eq!(state.track_config_class_name(3), None);
+        // 相同的标题按序号生成不同的类名
+        assert_ne!(state.track_config_class_name(0), state.track_config_class_name(2));
+
+        // 不同标签转写为相同（仅大小写不同）的前缀时，类名仍按序号区分
+        state.tracks[0].tag = "战斗".to_string();
+        state.tracks[2].tag = "Zhandou".to_string();
+        assert_eq!(state.track_config_class_name(0), Some(format!("zhandou_{}Song0", class_id)));
+        assert_eq!(state.track_config_class_name(2), Some(format!("Zhandou_{}Song2", class_id)));
     }
 }
@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
 use image::{DynamicImage, RgbaImage, GenericImageView, imageops};
 use log::{debug, info};
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use egui::TextureHandle;
 
 /// 裁剪区域选择（相对于原始图片的比例，0.0-1.0）
@@ -50,15 +52,111 @@ impl CropSelection {
     
 }
 
+/// 自动选择尺寸时的取整方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PaaAutoRounding {
+    /// 向上取整到不小于源边长的2的次方（会放大图片）
+    Up,
+    /// 向下取整到不大于源边长的2的次方（从不放大，默认）
+    #[default]
+    Down,
+}
+
+impl PaaAutoRounding {
+    /// 显示名称
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PaaAutoRounding::Up => "向上取整",
+            PaaAutoRounding::Down => "向下取整",
+        }
+    }
+}
+
+/// PAA 纹理压缩格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PaaCompression {
+    /// 源图有有效透明通道时使用 DXT5，否则使用 DXT1（默认）
+    #[default]
+    Auto,
+    /// DXT1：不透明纹理，体积为 DXT5 的一半（忽略透明通道）
+    Dxt1,
+    /// DXT5：带平滑透明通道
+    Dxt5,
+}
+
+impl PaaCompression {
+    /// 低于此值的 Alpha 视为有效透明（忽略接近不透明的噪点）
+    const ALPHA_THRESHOLD: u8 = 250;
+
+    /// 显示名称
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PaaCompression::Auto => "自动",
+            PaaCompression::Dxt1 => "DXT1（不透明）",
+            PaaCompression::Dxt5 => "DXT5（透明）",
+        }
+    }
+
+    /// 格式名称（用于结果说明）
+    pub fn format_name(&self) -> &'static str {
+        match self {
+            PaaCompression::Auto => "自动",
+            PaaCompression::Dxt1 => "DXT1",
+            PaaCompression::Dxt5 => "DXT5",
+        }
+    }
+
+    /// 按图片内容确定实际格式（`Auto` 时检查透明通道）
+    pub fn resolve(self, img: &RgbaImage) -> Self {
+        match self {
+            PaaCompression::Auto if Self::has_meaningful_alpha(img) => PaaCompression::Dxt5,
+            PaaCompression::Auto => PaaCompression::Dxt1,
+            other => other,
+        }
+    }
+
+    /// 图片是否有有效的透明像素
+    fn has_meaningful_alpha(img: &RgbaImage) -> bool {
+        img.pixels().any(|pixel| pixel[3] < Self::ALPHA_THRESHOLD)
+    }
+
+    /// PAA 文件头中的类型标识
+    fn type_tag(&self) -> u16 {
+        match self {
+            PaaCompression::Dxt1 => 0xFF01,
+            PaaCompression::Auto | PaaCompression::Dxt5 => 0xFF05,
+        }
+    }
+
+    /// 每个 4x4 块的字节数
+    fn block_size(&self) -> usize {
+        match self {
+            PaaCompression::Dxt1 => 8,
+            PaaCompression::Auto | PaaCompression::Dxt5 => 16,
+        }
+    }
+}
+
 /// PAA转换选项
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct PaaOptions {
     /// 是否裁剪到2的次方尺寸
     pub crop_to_power_of_two: bool,
-    /// 目标尺寸（如果为None，则自动选择最接近的2的次方）
+    /// 目标尺寸（如果为None，则按 `auto_rounding` 和 `auto_max_size` 自动选择）
     pub target_size: Option<u32>,
     /// 是否居中裁剪
     pub center_crop: bool,
+    /// 自动选择尺寸时的取整方式
+    pub auto_rounding: PaaAutoRounding,
+    /// 自动选择尺寸的上限
+    pub auto_max_size: u32,
+    /// 目标尺寸超过源图时限制为源边长对应的2的次方（避免放大导致模糊）
+    pub limit_to_source: bool,
+    /// 纹理压缩格式
+    pub compression: PaaCompression,
+    /// 是否生成完整的 mipmap 链（关闭时只写入原尺寸一级）
+    pub generate_mipmaps: bool,
 }
 
 impl Default for PaaOptions {
@@ -67,8 +165,126 @@ impl Default for PaaOptions {
             crop_to_power_of_two: true,
             target_size: None,
             center_crop: true,
+            auto_rounding: PaaAutoRounding::Down,
+            auto_max_size: Self::MAX_TEXTURE_SIZE,
+            limit_to_source: false,
+            compression: PaaCompression::Auto,
+            generate_mipmaps: true,
+        }
+    }
+}
+
+impl PaaOptions {
+    /// Arma 3 纹理的最小边长
+    pub const MIN_TEXTURE_SIZE: u32 = 4;
+    /// Arma 3 纹理的最大边长
+    pub const MAX_TEXTURE_SIZE: u32 = 4096;
+
+    /// 将任意尺寸吸附到最接近的2的次方（距离相同时取较大值），并限制在 Arma 支持的范围内
+    pub fn snap_texture_size(size: u32) -> u32 {
+        let size = size.clamp(Self::MIN_TEXTURE_SIZE, Self::MAX_TEXTURE_SIZE);
+        let lower = 1u32 << (31 - size.leading_zeros());
+        if lower == size {
+            return size;
+        }
+        let upper = lower << 1;
+        let snapped = if size - lower < upper - size { lower } else { upper };
+        snapped.min(Self::MAX_TEXTURE_SIZE)
+    }
+
+    /// 校验自定义目标尺寸，返回 (有效尺寸, 调整说明)；尺寸已合法时无说明
+    pub fn validate_target_size(size: u32) -> (u32, Option<String>) {
+        let snapped = Self::snap_texture_size(size);
+        if snapped == size {
+            return (size, None);
+        }
+
+        let message = if size > Self::MAX_TEXTURE_SIZE {
+            format!("{} 超过 Arma 最大纹理尺寸，已限制为 {}", size, snapped)
+        } else if size < Self::MIN_TEXTURE_SIZE {
+            format!("{} 小于最小纹理尺寸，已调整为 {}", size, snapped)
+        } else {
+            format!("{} 不是2的次方，已调整为最接近的 {}", size, snapped)
+        };
+        (snapped, Some(message))
+    }
+
+    /// 输出使用的源边长：居中裁剪时为短边，否则为长边
+    pub fn source_edge(&self, width: u32, height: u32) -> u32 {
+        if self.center_crop {
+            width.min(height).max(1)
+        } else {
+            width.max(height).max(1)
+        }
+    }
+
+    /// 不超过源边长的最大2的次方（不小于最小纹理尺寸）
+    pub fn source_power_of_two(&self, width: u32, height: u32) -> u32 {
+        let edge = self.source_edge(width, height);
+        (1u32 << (31 - edge.leading_zeros())).clamp(Self::MIN_TEXTURE_SIZE, Self::MAX_TEXTURE_SIZE)
+    }
+
+    /// 自动选择目标尺寸：取源边长，按取整方式对齐到2的次方，再限制在最小尺寸与上限之间
+    ///
+    /// 例如源边长 600 向上取整为 1024、向下取整为 512；上限为 512 时两者都是 512。
+    /// 默认向下取整，因此自动模式不会放大图片。
+    pub fn auto_target_size(&self, width: u32, height: u32) -> u32 {
+        let size = match self.auto_rounding {
+            PaaAutoRounding::Up => PaaConverter::next_power_of_two(self.source_edge(width, height)),
+            PaaAutoRounding::Down => self.source_power_of_two(width, height),
+        };
+        let max_size = Self::snap_texture_size(self.auto_max_size);
+        size.clamp(Self::MIN_TEXTURE_SIZE, max_size)
+    }
+
+    /// 给定源尺寸时实际使用的目标边长（自定义尺寸优先，开启限制时不超过源图）
+    pub fn target_size_for(&self, width: u32, height: u32) -> u32 {
+        let size = match self.target_size {
+            Some(size) => Self::snap_texture_size(size),
+            None => self.auto_target_size(width, height),
+        };
+        if self.limit_to_source {
+            size.min(self.source_power_of_two(width, height))
+        } else {
+            size
         }
     }
+
+    /// 给定源尺寸时转换后的边长（不裁剪到2的次方时返回原尺寸）
+    pub fn output_size_for(&self, width: u32, height: u32) -> (u32, u32) {
+        if self.crop_to_power_of_two {
+            let size = self.target_size_for(width, height);
+            (size, size)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// 目标尺寸超过源图时返回放大提示
+    pub fn upscale_warning(&self, width: u32, height: u32) -> Option<String> {
+        if !self.crop_to_power_of_two {
+            return None;
+        }
+        let target = self.target_size_for(width, height);
+        let edge = self.source_edge(width, height);
+        (target > edge).then(|| {
+            format!(
+                "源图 {}x{} 将被放大到 {}x{}，可能模糊；建议限制为 {}x{}",
+                width,
+                height,
+                target,
+                target,
+                self.source_power_of_two(width, height),
+                self.source_power_of_two(width, height)
+            )
+        })
+    }
+
+    /// 将目标尺寸规范化为合法的2的次方
+    pub fn normalize(&mut self) {
+        self.target_size = self.target_size.map(Self::snap_texture_size);
+        self.auto_max_size = Self::snap_texture_size(self.auto_max_size);
+    }
 }
 
 /// 图片纹理管理器
@@ -88,13 +304,25 @@ pub struct RuntimeImageTextureManager {
     pub current_texture: Option<TextureHandle>,
     /// 基础管理器
     pub base: ImageTextureManager,
+    /// 批量预览缩略图缓存（按路径，解码失败时保存错误信息，避免每帧重复解码）
+    thumbnails: HashMap<PathBuf, std::result::Result<TextureHandle, String>>,
+    /// 已交给后台线程解码、尚未返回的路径
+    thumbnails_loading: HashSet<PathBuf>,
+    /// 后台线程解码完成的缩略图（路径, 图片或错误信息）
+    thumbnail_sender: crossbeam_channel::Sender<DecodedThumbnail>,
+    thumbnail_receiver: crossbeam_channel::Receiver<DecodedThumbnail>,
 }
 
+/// 后台解码完成的缩略图（路径, 图片或错误信息）
+type DecodedThumbnail = (PathBuf, std::result::Result<egui::ColorImage, String>);
+
 impl std::fmt::Debug for RuntimeImageTextureManager {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RuntimeImageTextureManager")
             .field("current_texture", &self.current_texture.is_some())
             .field("base", &self.base)
+            .field("thumbnails", &self.thumbnails.len())
+            .field("thumbnails_loading", &self.thumbnails_loading.len())
             .finish()
     }
 }
@@ -104,6 +332,7 @@ impl Clone for RuntimeImageTextureManager {
         Self {
             current_texture: None, // TextureHandle不能克隆，重新创建
             base: self.base.clone(),
+            ..Self::default()
         }
     }
 }
@@ -120,14 +349,110 @@ impl Default for ImageTextureManager {
 
 impl Default for RuntimeImageTextureManager {
     fn default() -> Self {
+        let (thumbnail_sender, thumbnail_receiver) = crossbeam_channel::unbounded();
         Self {
             current_texture: None,
             base: ImageTextureManager::default(),
+            thumbnails: HashMap::new(),
+            thumbnails_loading: HashSet::new(),
+            thumbnail_sender,
+            thumbnail_receiver,
         }
     }
 }
 
 impl RuntimeImageTextureManager {
+    /// 缩略图最大边长（像素），限制大批量文件时的显存占用
+    pub const THUMBNAIL_SIZE: u32 = 128;
+
+    /// 解码图片并缩小到不超过 `max_size` 的缩略图（保持宽高比，不放大）
+    pub fn load_thumbnail_image(path: &Path, max_size: u32) -> Result<egui::ColorImage> {
+        let image = PaaConverter::load_image(path)?;
+        let image = if image.width() > max_size || image.height() > max_size {
+            image.thumbnail(max_size, max_size)
+        } else {
+            image
+        };
+        let rgba = image.to_rgba8();
+        let size = [rgba.width() as usize, rgba.height() as usize];
+        Ok(egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw()))
+    }
+
+    /// 上传后台已解码的缩略图，并将尚未缓存的文件交给后台线程解码
+    ///
+    /// 界面线程只创建纹理，不解码图片。返回是否还有未加载的文件（调用方应继续请求重绘）。
+    pub fn load_pending_thumbnails(&mut self, ctx: &egui::Context, paths: &[PathBuf]) -> bool {
+        for (path, result) in self.thumbnail_receiver.try_iter() {
+            self.thumbnails_loading.remove(&path);
+            let entry = result.map(|image| {
+                ctx.load_texture(
+                    format!("paa_thumbnail_{}", path.display()),
+                    image,
+                    egui::TextureOptions::LINEAR,
+                )
+            });
+            self.thumbnails.insert(path, entry);
+        }
+
+        let pending: Vec<PathBuf> = paths
+            .iter()
+            .filter(|path| !self.thumbnails.contains_key(*path) && !self.thumbnails_loading.contains(*path))
+            .cloned()
+            .collect();
+        if !pending.is_empty() {
+            self.thumbnails_loading.extend(pending.iter().cloned());
+            let sender = self.thumbnail_sender.clone();
+            std::thread::spawn(move || {
+                for path in pending {
+                    let result = Self::load_thumbnail_image(&path, Self::THUMBNAIL_SIZE).map_err(|e| format!("{:#}", e));
+                    if let Err(ref e) = result {
+                        debug!("缩略图加载失败: {}", e);
+                    }
+                    if sender.send((path, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        !self.thumbnails_loading.is_empty()
+    }
+
+    /// 获取已缓存的缩略图；`None` 表示尚未加载
+    pub fn thumbnail(&self, path: &Path) -> Option<&std::result::Result<TextureHandle, String>> {
+        self.thumbnails.get(path)
+    }
+
+    /// 移除不在列表中的文件的缩略图，释放对应纹理
+    pub fn retain_thumbnails(&mut self, paths: &[PathBuf]) {
+        let keep: HashSet<&PathBuf> = paths.iter().collect();
+        self.thumbnails.retain(|path, _| keep.contains(path));
+    }
+}
+
+/// 单个图片的转换结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaaConversion {
+    /// 参与转换的源尺寸（有裁剪选区时为选区尺寸）
+    pub source_size: (u32, u32),
+    /// 输出尺寸
+    pub output_size: (u32, u32),
+    /// 是否放大了源图
+    pub upscaled: bool,
+    /// 实际使用的压缩格式
+    pub compression: PaaCompression,
+}
+
+impl PaaConversion {
+    /// 结果说明，如 `512x512 DXT1` 或 `1024x1024 DXT5（由 256x256 放大）`
+    pub fn describe(&self) -> String {
+        let (width, height) = self.output_size;
+        let format = self.compression.format_name();
+        if self.upscaled {
+            format!("{}x{} {}（由 {}x{} 放大，可能模糊）", width, height, format, self.source_size.0, self.source_size.1)
+        } else {
+            format!("{}x{} {}", width, height, format)
+        }
+    }
 }
 
 /// PAA转换器
@@ -135,13 +460,42 @@ pub struct PaaConverter;
 
 impl PaaConverter {
 
+    /// 转换前检查输入图片（只读取图片头）：格式能否识别、色深是否受支持
+    ///
+    /// 16 位、浮点色深、CMYK 图片和不支持的 DDS 格式（如 BC7）返回提示信息，批量转换时记为单个文件失败。
+    pub fn validate_input(path: &Path) -> std::result::Result<(), String> {
+        use image::{ExtendedColorType, ImageDecoder};
+
+        const HINT: &str = "请使用8位RGB/RGBA图片";
+        if Self::is_dds(path) {
+            Self::check_dds_file(path)?;
+        }
+
+        let decoder = image::ImageReader::open(path)
+            .map_err(|e| format!("无法打开图片: {}", e))?
+            .with_guessed_format()
+            .map_err(|e| format!("无法读取图片: {}", e))?
+            .into_decoder()
+            .map_err(|e| format!("无法识别的图片格式（{}），{}", e, HINT))?;
+
+        match decoder.original_color_type() {
+            ExtendedColorType::L16
+            | ExtendedColorType::La16
+            | ExtendedColorType::Rgb16
+            | ExtendedColorType::Rgba16 => Err(format!("不支持每通道 16 位的图片，{}", HINT)),
+            ExtendedColorType::Rgb32F | ExtendedColorType::Rgba32F => Err(format!("不支持浮点色深的图片，{}", HINT)),
+            ExtendedColorType::Cmyk8 => Err(format!("不支持 CMYK 图片，{}", HINT)),
+            _ => Ok(()),
+        }
+    }
+
     /// 将图片文件转换为PAA格式（带选项和裁剪）
     pub fn convert_image_to_paa_with_crop<P: AsRef<Path>>(
         input_path: P, 
         output_path: P, 
         options: PaaOptions,
         crop_selection: Option<&CropSelection>
-    ) -> Result<()> {
+    ) -> Result<PaaConversion> {
         let input_path = input_path.as_ref();
         let output_path = output_path.as_ref();
 
@@ -153,8 +507,19 @@ impl PaaConverter {
         }
 
         // 加载图片
-        let img = image::open(input_path)
-            .with_context(|| format!("无法加载图片: {:?}", input_path))?;
+        let img = Self::load_image(input_path)?;
+
+        // 参与转换的源尺寸
+        let source_size = match crop_selection {
+            Some(crop) => {
+                let (_, _, width, height) = crop.get_pixel_coords(img.width(), img.height());
+                (width, height)
+            }
+            None => img.dimensions(),
+        };
+        if let Some(warning) = options.upscale_warning(source_size.0, source_size.1) {
+            log::warn!("{:?}: {}", input_path, warning);
+        }
 
         // 处理图片（裁剪、调整尺寸等）
         let processed_img = if let Some(crop) = crop_selection {
@@ -164,13 +529,47 @@ impl PaaConverter {
         };
 
         // 转换为PAA格式
-        let paa_data = Self::image_to_paa(&processed_img)?;
+        let compression = options.compression.resolve(&processed_img);
+        let paa_data = Self::image_to_paa(&processed_img, compression, options.generate_mipmaps)?;
 
         // 写入PAA文件
         std::fs::write(output_path, &paa_data)
             .with_context(|| format!("无法写入PAA文件: {:?}", output_path))?;
 
         info!("PAA转换完成: {:?}", output_path);
+        let output_size = processed_img.dimensions();
+        Ok(PaaConversion {
+            source_size,
+            output_size,
+            upscaled: output_size.0 > options.source_edge(source_size.0, source_size.1),
+            compression,
+        })
+    }
+
+    /// 加载源图片：DDS（DXT1/DXT3/DXT5）、RLE 压缩和带透明通道的 TGA 等均交给 image 库解码，保留透明通道；
+    /// 不支持的 DDS 格式（如 BC7）先读取文件头给出明确的错误
+    pub fn load_image(path: &Path) -> Result<DynamicImage> {
+        if Self::is_dds(path) {
+            Self::check_dds_file(path).map_err(|e| anyhow::anyhow!("{}: {:?}", e, path))?;
+        }
+        image::open(path).with_context(|| format!("无法加载图片: {:?}", path))
+    }
+
+    /// 将内存中的图片数据（如音频内嵌封面）转换为PAA文件
+    pub fn convert_image_bytes_to_paa(data: &[u8], output_path: &Path, options: PaaOptions) -> Result<()> {
+        let img = image::load_from_memory(data).context("无法解析图片数据")?;
+        let processed_img = Self::process_image(img, &options)?;
+        let compression = options.compression.resolve(&processed_img);
+        let paa_data = Self::image_to_paa(&processed_img, compression, options.generate_mipmaps)?;
+
+        if let Some(parent) = output_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("无法创建输出目录: {:?}", parent))?;
+        }
+        std::fs::write(output_path, &paa_data)
+            .with_context(|| format!("无法写入PAA文件: {:?}", output_path))?;
+
+        info!("封面图片已转换为PAA: {:?}", output_path);
         Ok(())
     }
 
@@ -187,13 +586,7 @@ impl PaaConverter {
         let cropped_img = imageops::crop_imm(&img, crop_x, crop_y, crop_width, crop_height).to_image();
         
         // 确定目标尺寸
-        let target_size = if let Some(size) = options.target_size {
-            size
-        } else {
-            // 自动选择最接近的2的次方
-            let max_dim = crop_width.max(crop_height);
-            Self::next_power_of_two(max_dim)
-        };
+        let target_size = options.target_size_for(crop_width, crop_height);
         
         info!("目标尺寸: {}x{}", target_size, target_size);
         
@@ -228,11 +621,7 @@ impl PaaConverter {
         let (width, height) = rgba_img.dimensions();
 
         if options.crop_to_power_of_two {
-            let target_size = options.target_size.unwrap_or_else(|| {
-                // 自动选择最接近的2的次方尺寸
-                let max_dim = width.max(height);
-                Self::next_power_of_two(max_dim)
-            });
+            let target_size = options.target_size_for(width, height);
 
             // 如果当前尺寸不是目标尺寸，进行裁剪或缩放
             if width != target_size || height != target_size {
@@ -275,6 +664,67 @@ impl PaaConverter {
         }
     }
 
+    /// DDS 文件头的最大长度（魔数 + 124 字节头 + 20 字节 DX10 扩展头）
+    const DDS_MAX_HEADER_LEN: usize = 4 + 124 + 20;
+
+    fn is_dds(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("dds"))
+    }
+
+    /// 读取 DDS 文件头并检查格式是否受支持
+    fn check_dds_file(path: &Path) -> std::result::Result<(), String> {
+        let mut header = Vec::with_capacity(Self::DDS_MAX_HEADER_LEN);
+        std::fs::File::open(path)
+            .and_then(|file| file.take(Self::DDS_MAX_HEADER_LEN as u64).read_to_end(&mut header))
+            .map_err(|e| format!("无法打开图片: {}", e))?;
+        Self::check_dds_header(&header)
+    }
+
+    /// 检查 DDS 文件头：image 库只能解码 DXT1/DXT3/DXT5（含 DX10 扩展头中的 BC1-BC3），
+    /// 其他格式（如 BC7、BC4/BC5、浮点和未压缩格式）返回说明
+    fn check_dds_header(data: &[u8]) -> std::result::Result<(), String> {
+        const DDPF_FOURCC: u32 = 0x4;
+
+        let u32_at = |offset: usize| -> Option<u32> {
+            data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+        if !data.starts_with(b"DDS ") || u32_at(4) != Some(124) {
+            return Err("不是有效的 DDS 文件".to_string());
+        }
+        let (Some(pf_flags), Some(four_cc), Some(bit_count)) = (u32_at(80), data.get(84..88), u32_at(88)) else {
+            return Err("DDS 文件头不完整".to_string());
+        };
+        if pf_flags & DDPF_FOURCC == 0 {
+            return Err(format!("不支持未压缩的 DDS（{} 位），{}", bit_count, Self::DDS_HINT));
+        }
+        match four_cc {
+            b"DXT1" | b"DXT3" | b"DXT5" => Ok(()),
+            b"DX10" => match u32_at(4 + 124).ok_or("DDS DX10 扩展头不完整")? {
+                70..=78 => Ok(()),
+                other => Err(format!("不支持的 DDS 格式: {}，{}", Self::dxgi_format_name(other), Self::DDS_HINT)),
+            },
+            other => {
+                let name = String::from_utf8_lossy(other).trim_end_matches('\0').to_string();
+                Err(format!("不支持的 DDS 格式: {}，{}", name, Self::DDS_HINT))
+            }
+        }
+    }
+
+    const DDS_HINT: &'static str = "请使用 DXT1/DXT3/DXT5 格式的 DDS";
+
+    /// 常见 DXGI 格式名称（用于错误提示）
+    fn dxgi_format_name(format: u32) -> String {
+        match format {
+            80..=82 => "BC4".to_string(),
+            83..=85 => "BC5".to_string(),
+            94..=96 => "BC6H".to_string(),
+            97..=99 => "BC7".to_string(),
+            2 | 10 => "浮点 RGBA".to_string(),
+            28 | 29 | 87 | 91 => "未压缩 RGBA".to_string(),
+            other => format!("DXGI 格式 {}", other),
+        }
+    }
+
     /// 计算下一个2的次方
     pub fn next_power_of_two(n: u32) -> u32 {
         if n <= 1 {
@@ -288,32 +738,584 @@ impl PaaConverter {
         power
     }
 
-    /// 将ImageData转换为PAA字节数据
-    fn image_to_paa(img: &RgbaImage) -> Result<Vec<u8>> {
+    /// 读取图片源尺寸（只读取图片头，DDS 会先检查格式是否受支持）
+    pub fn source_dimensions(input_path: &Path) -> Result<(u32, u32)> {
+        if Self::is_dds(input_path) {
+            Self::check_dds_file(input_path).map_err(|e| anyhow::anyhow!("{}: {:?}", e, input_path))?;
+        }
+        image::image_dimensions(input_path).with_context(|| format!("无法读取图片尺寸: {:?}", input_path))
+    }
+
+    /// 将图片编码为 PAA 字节数据（DXT1/DXT5，`generate_mipmaps` 时含完整 mipmap 链）
+    ///
+    /// 文件结构：类型标识、TAGG 区（AVGC 平均颜色、MAXC 最大颜色、DXT5 时的 FLAG 透明标志、
+    /// OFFS 各级 mipmap 偏移）、空调色板、各级 mipmap（未压缩的 DXT 块数据）和结束标记。
+    fn image_to_paa(img: &RgbaImage, compression: PaaCompression, generate_mipmaps: bool) -> Result<Vec<u8>> {
+        /// OFFS 标签可记录的 mipmap 数量
+        const MAX_MIPMAPS: usize = 16;
+        /// mipmap 数据长度字段为 3 字节
+        const MAX_MIPMAP_DATA_LEN: usize = 0xFF_FFFF;
+
         let (width, height) = img.dimensions();
-        
-        // PAA文件头结构
+        if width == 0 || height == 0 || width > 0x7FFF || height > 0x7FFF {
+            return Err(anyhow::anyhow!("不支持的PAA纹理尺寸: {}x{}", width, height));
+        }
+
+        // 生成 mipmap 链：每级宽高减半（不小于 1），直到 1x1
+        let mut mipmaps = vec![img.clone()];
+        while generate_mipmaps && mipmaps.len() < MAX_MIPMAPS {
+            let last = &mipmaps[mipmaps.len() - 1];
+            if last.width() == 1 && last.height() == 1 {
+                break;
+            }
+            let next = Self::downsample_box(last);
+            mipmaps.push(next);
+        }
+
         let mut paa_data = Vec::new();
-        
-        // PAA文件头 (基于Arma 3 PAA格式规范)
-        // 文件头大小: 16字节
-        paa_data.extend_from_slice(&(16u32).to_le_bytes()); // 头大小
-        paa_data.extend_from_slice(&(width as u32).to_le_bytes()); // 宽度
-        paa_data.extend_from_slice(&(height as u32).to_le_bytes()); // 高度
-        paa_data.extend_from_slice(&(1u32).to_le_bytes()); // 格式标识
-        
-        // 添加像素数据
+        paa_data.extend_from_slice(&compression.type_tag().to_le_bytes());
+
+        // 平均颜色（BGRA）
+        let pixel_count = (width as u64 * height as u64).max(1);
+        let mut sums = [0u64; 4];
         for pixel in img.pixels() {
-            // PAA使用BGRA格式
-            paa_data.push(pixel[2]); // B
-            paa_data.push(pixel[1]); // G
-            paa_data.push(pixel[0]); // R
-            paa_data.push(pixel[3]); // A
+            for (sum, value) in sums.iter_mut().zip(pixel.0) {
+                *sum += value as u64;
+            }
         }
+        let average = sums.map(|sum| (sum / pixel_count) as u8);
+        Self::write_tagg(&mut paa_data, b"CGVA", &[average[2], average[1], average[0], average[3]]);
+        Self::write_tagg(&mut paa_data, b"CXAM", &[0xFF; 4]);
+        if compression == PaaCompression::Dxt5 {
+            // 1 表示透明通道需要插值（平滑透明）
+            Self::write_tagg(&mut paa_data, b"GALF", &1u32.to_le_bytes());
+        }
+        Self::write_tagg(&mut paa_data, b"SFFO", &[0u8; MAX_MIPMAPS * 4]);
+        let offsets_pos = paa_data.len() - MAX_MIPMAPS * 4;
+
+        // 空调色板
+        paa_data.extend_from_slice(&0u16.to_le_bytes());
 
-        debug!("生成PAA数据: {}x{}, {}字节", width, height, paa_data.len());
+        for (level, mipmap) in mipmaps.iter().enumerate() {
+            let offset = paa_data.len() as u32;
+            paa_data[offsets_pos + level * 4..offsets_pos + level * 4 + 4].copy_from_slice(&offset.to_le_bytes());
+
+            let data = Self::encode_dxt(mipmap, compression);
+            if data.len() > MAX_MIPMAP_DATA_LEN {
+                return Err(anyhow::anyhow!(
+                    "{}x{} 的 {} 纹理数据超过PAA单级上限，请使用较小尺寸或 DXT1",
+                    mipmap.width(),
+                    mipmap.height(),
+                    compression.format_name()
+                ));
+            }
+            paa_data.extend_from_slice(&(mipmap.width() as u16).to_le_bytes());
+            paa_data.extend_from_slice(&(mipmap.height() as u16).to_le_bytes());
+            paa_data.extend_from_slice(&(data.len() as u32).to_le_bytes()[..3]);
+            paa_data.extend_from_slice(&data);
+        }
+
+        // 结束标记（宽高为 0 的空 mipmap）
+        paa_data.extend_from_slice(&[0u8; 6]);
+
+        debug!(
+            "生成PAA数据: {}x{} {}, {} 级 mipmap, {}字节",
+            width,
+            height,
+            compression.format_name(),
+            mipmaps.len(),
+            paa_data.len()
+        );
         Ok(paa_data)
     }
 
+    /// 2x2 盒式滤波缩小一半（宽或高为 1 时该方向保持为 1）
+    fn downsample_box(img: &RgbaImage) -> RgbaImage {
+        let (width, height) = img.dimensions();
+        let (new_width, new_height) = ((width / 2).max(1), (height / 2).max(1));
+        RgbaImage::from_fn(new_width, new_height, |x, y| {
+            let xs = [(x * 2).min(width - 1), (x * 2 + 1).min(width - 1)];
+            let ys = [(y * 2).min(height - 1), (y * 2 + 1).min(height - 1)];
+            let mut sums = [0u32; 4];
+            for &sy in &ys {
+                for &sx in &xs {
+                    for (sum, value) in sums.iter_mut().zip(img.get_pixel(sx, sy).0) {
+                        *sum += value as u32;
+                    }
+                }
+            }
+            image::Rgba(sums.map(|sum| ((sum + 2) / 4) as u8))
+        })
+    }
+
+    /// 写入 TAGG 标签（名称按 PAA 约定倒序存储，如 AVGC 写作 "CGVA"）
+    fn write_tagg(out: &mut Vec<u8>, name: &[u8; 4], data: &[u8]) {
+        out.extend_from_slice(b"GGAT");
+        out.extend_from_slice(name);
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(data);
+    }
+
+    /// 按 4x4 块编码为 DXT1/DXT5 数据（边缘不足 4 像素时重复边缘像素）
+    fn encode_dxt(img: &RgbaImage, compression: PaaCompression) -> Vec<u8> {
+        let (width, height) = img.dimensions();
+        let blocks_x = width.div_ceil(4);
+        let blocks_y = height.div_ceil(4);
+        let mut out = Vec::with_capacity((blocks_x * blocks_y) as usize * compression.block_size());
+
+        for block_y in 0..blocks_y {
+            for block_x in 0..blocks_x {
+                let mut block = [[0u8; 4]; 16];
+                for (i, pixel) in block.iter_mut().enumerate() {
+                    let x = (block_x * 4 + (i % 4) as u32).min(width - 1);
+                    let y = (block_y * 4 + (i / 4) as u32).min(height - 1);
+                    *pixel = img.get_pixel(x, y).0;
+                }
+                if compression != PaaCompression::Dxt1 {
+                    out.extend_from_slice(&Self::encode_alpha_block(&block));
+                }
+                out.extend_from_slice(&Self::encode_color_block(&block));
+            }
+        }
+        out
+    }
+
+    /// 编码 DXT 颜色块（4 色模式：两个 RGB565 端点加 2 位索引）
+    fn encode_color_block(block: &[[u8; 4]; 16]) -> [u8; 8] {
+        // 取包围盒的两个角作为端点，并按颜色分布选择包围盒的对角线方向
+        let mut min = [0, 1, 2].map(|c| block.iter().map(|p| p[c]).min().unwrap_or(0));
+        let mut max = [0, 1, 2].map(|c| block.iter().map(|p| p[c]).max().unwrap_or(0));
+        let center = [0, 1, 2].map(|c| (min[c] as i32 + max[c] as i32) / 2);
+        let (mut cov_rg, mut cov_rb) = (0i32, 0i32);
+        for pixel in block {
+            let r = pixel[0] as i32 - center[0];
+            cov_rg += r * (pixel[1] as i32 - center[1]);
+            cov_rb += r * (pixel[2] as i32 - center[2]);
+        }
+        if cov_rg < 0 {
+            std::mem::swap(&mut min[1], &mut max[1]);
+        }
+        if cov_rb < 0 {
+            std::mem::swap(&mut min[2], &mut max[2]);
+        }
+
+        let mut color0 = Self::to_rgb565(max);
+        let mut color1 = Self::to_rgb565(min);
+        // 4 色模式要求 color0 > color1；相等时所有像素使用 color0
+        if color0 < color1 {
+            std::mem::swap(&mut color0, &mut color1);
+        }
+        let mut indices = 0u32;
+        if color0 != color1 {
+            let p0 = Self::from_rgb565(color0);
+            let p1 = Self::from_rgb565(color1);
+            let palette = [
+                p0,
+                p1,
+                [0, 1, 2].map(|c| ((2 * p0[c] as u32 + p1[c] as u32) / 3) as u8),
+                [0, 1, 2].map(|c| ((p0[c] as u32 + 2 * p1[c] as u32) / 3) as u8),
+            ];
+            for (i, pixel) in block.iter().enumerate() {
+                let distance = |color: &[u8; 3]| -> i32 {
+                    (0..3).map(|c| (pixel[c] as i32 - color[c] as i32).pow(2)).sum()
+                };
+                let index = (0..4).min_by_key(|&k| distance(&palette[k])).unwrap_or(0) as u32;
+                indices |= index << (i * 2);
+            }
+        }
+
+        let mut out = [0u8; 8];
+        out[0..2].copy_from_slice(&color0.to_le_bytes());
+        out[2..4].copy_from_slice(&color1.to_le_bytes());
+        out[4..8].copy_from_slice(&indices.to_le_bytes());
+        out
+    }
+
+    /// 编码 DXT5 透明块（两个 8 位端点加 3 位索引，8 级插值）
+    fn encode_alpha_block(block: &[[u8; 4]; 16]) -> [u8; 8] {
+        let alpha0 = block.iter().map(|p| p[3]).max().unwrap_or(255);
+        let alpha1 = block.iter().map(|p| p[3]).min().unwrap_or(255);
+
+        let mut indices = 0u64;
+        if alpha0 != alpha1 {
+            let mut palette = [0u8; 8];
+            palette[0] = alpha0;
+            palette[1] = alpha1;
+            for i in 1..7u32 {
+                palette[i as usize + 1] = (((7 - i) * alpha0 as u32 + i * alpha1 as u32) / 7) as u8;
+            }
+            for (i, pixel) in block.iter().enumerate() {
+                let index = (0..8)
+                    .min_by_key(|&k| (pixel[3] as i32 - palette[k] as i32).abs())
+                    .unwrap_or(0) as u64;
+                indices |= index << (i * 3);
+            }
+        }
+
+        let mut out = [0u8; 8];
+        out[0] = alpha0;
+        out[1] = alpha1;
+        out[2..8].copy_from_slice(&indices.to_le_bytes()[..6]);
+        out
+    }
+
+    /// RGB888 转 RGB565
+    fn to_rgb565(color: [u8; 3]) -> u16 {
+        ((color[0] as u16 >> 3) << 11) | ((color[1] as u16 >> 2) << 5) | (color[2] as u16 >> 3)
+    }
+
+    /// RGB565 转 RGB888（高位复制到低位）
+    fn from_rgb565(color: u16) -> [u8; 3] {
+        let r = ((color >> 11) & 0x1F) as u8;
+        let g = ((color >> 5) & 0x3F) as u8;
+        let b = (color & 0x1F) as u8;
+        [(r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2)]
+    }
+
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap_texture_size_keeps_powers_of_two() {
+        for size in [4, 8, 16, 256, 512, 1024, 2048, 4096] {
+            assert_eq!(PaaOptions::snap_texture_size(size), size);
+        }
+    }
+
+    #[test]
+    fn test_snap_texture_size_rounds_to_nearest() {
+        assert_eq!(PaaOptions::snap_texture_size(300), 256);
+        assert_eq!(PaaOptions::snap_texture_size(384), 512);
+        assert_eq!(PaaOptions::snap_texture_size(700), 512);
+        assert_eq!(PaaOptions::snap_texture_size(800), 1024);
+        assert_eq!(PaaOptions::snap_texture_size(1500), 1024);
+        assert_eq!(PaaOptions::snap_texture_size(3000), 2048);
+        assert_eq!(PaaOptions::snap_texture_size(3100), 4096);
+    }
+
+    #[test]
+    fn test_snap_texture_size_clamps_to_arma_limits() {
+        assert_eq!(PaaOptions::snap_texture_size(0), PaaOptions::MIN_TEXTURE_SIZE);
+        assert_eq!(PaaOptions::snap_texture_size(1), PaaOptions::MIN_TEXTURE_SIZE);
+        assert_eq!(PaaOptions::snap_texture_size(5000), PaaOptions::MAX_TEXTURE_SIZE);
+        assert_eq!(PaaOptions::snap_texture_size(u32::MAX), PaaOptions::MAX_TEXTURE_SIZE);
+    }
+
+    #[test]
+    fn test_snapped_sizes_are_always_valid() {
+        for size in 0..=10_000 {
+            let snapped = PaaOptions::snap_texture_size(size);
+            assert!(snapped.is_power_of_two(), "{} -> {}", size, snapped);
+            assert!((PaaOptions::MIN_TEXTURE_SIZE..=PaaOptions::MAX_TEXTURE_SIZE).contains(&snapped));
+        }
+    }
+
+    #[test]
+    fn test_auto_target_size_rounding_for_odd_dimensions() {
+        let up = PaaOptions { auto_rounding: PaaAutoRounding::Up, center_crop: false, ..PaaOptions::default() };
+        let down = PaaOptions { auto_rounding: PaaAutoRounding::Down, center_crop: false, ..PaaOptions::default() };
+        // (宽, 高, 向上取整, 向下取整)
+        let cases = [
+            (1, 1, 4, 4),
+            (3, 7, 8, 4),
+            (600, 400, 1024, 512),
+            (333, 1000, 1024, 512),
+            (512, 300, 512, 512),
+            (1025, 1025, 2048, 1024),
+            (5000, 3000, 4096, 4096),
+        ];
+        for (width, height, expected_up, expected_down) in cases {
+            assert_eq!(up.auto_target_size(width, height), expected_up, "{}x{} 向上", width, height);
+            assert_eq!(down.auto_target_size(width, height), expected_down, "{}x{} 向下", width, height);
+        }
+    }
+
+    #[test]
+    fn test_auto_target_size_respects_max_cap() {
+        let options = PaaOptions { auto_max_size: 512, auto_rounding: PaaAutoRounding::Up, ..PaaOptions::default() };
+        assert_eq!(options.auto_target_size(600, 600), 512);
+        assert_eq!(options.auto_target_size(100, 100), 128);
+        // 自定义尺寸不受自动上限影响
+        let options = PaaOptions { target_size: Some(1024), ..options };
+        assert_eq!(options.target_size_for(600, 400), 1024);
+    }
+
+    #[test]
+    fn test_default_auto_mode_never_upscales() {
+        let options = PaaOptions::default();
+        for (width, height) in [(256, 256), (600, 300), (300, 601), (1000, 1000), (4, 9)] {
+            assert!(options.upscale_warning(width, height).is_none(), "{}x{}", width, height);
+        }
+        // 居中裁剪按短边计算
+        assert_eq!(options.auto_target_size(600, 300), 256);
+    }
+
+    #[test]
+    fn test_upscale_warning_and_limit_to_source() {
+        let options = PaaOptions { target_size: Some(1024), ..PaaOptions::default() };
+        assert!(options.upscale_warning(256, 256).is_some());
+        assert!(options.upscale_warning(2048, 2048).is_none());
+
+        let limited = PaaOptions { limit_to_source: true, ..options };
+        assert_eq!(limited.target_size_for(256, 256), 256);
+        assert_eq!(limited.target_size_for(300, 300), 256);
+        assert!(limited.upscale_warning(300, 300).is_none());
+    }
+
+    #[test]
+    fn test_validate_target_size_messages() {
+        assert_eq!(PaaOptions::validate_target_size(512), (512, None));
+
+        let (size, message) = PaaOptions::validate_target_size(600);
+        assert_eq!(size, 512);
+        assert!(message.is_some());
+
+        let (size, message) = PaaOptions::validate_target_size(8192);
+        assert_eq!(size, PaaOptions::MAX_TEXTURE_SIZE);
+        assert!(message.is_some());
+    }
+
+    fn paa_type_and_tags(data: &[u8]) -> (u16, Vec<String>) {
+        let tag = u16::from_le_bytes([data[0], data[1]]);
+        let mut names = Vec::new();
+        let mut pos = 2;
+        while data[pos..].starts_with(b"GGAT") {
+            names.push(String::from_utf8_lossy(&data[pos + 4..pos + 8]).chars().rev().collect());
+            let len = u32::from_le_bytes(data[pos + 8..pos + 12].try_into().unwrap()) as usize;
+            pos += 12 + len;
+        }
+        (tag, names)
+    }
+
+    #[test]
+    fn test_auto_compression_follows_alpha_channel() {
+        let opaque = RgbaImage::from_pixel(8, 8, image::Rgba([200, 10, 10, 255]));
+        let mut transparent = opaque.clone();
+        transparent.put_pixel(3, 3, image::Rgba([0, 0, 0, 0]));
+
+        assert_eq!(PaaCompression::Auto.resolve(&opaque), PaaCompression::Dxt1);
+        assert_eq!(PaaCompression::Auto.resolve(&transparent), PaaCompression::Dxt5);
+        assert_eq!(PaaCompression::Dxt1.resolve(&transparent), PaaCompression::Dxt1);
+    }
+
+    #[test]
+    fn test_paa_header_reflects_compression() {
+        let img = RgbaImage::from_pixel(8, 8, image::Rgba([255, 0, 0, 255]));
+
+        let dxt1 = PaaConverter::image_to_paa(&img, PaaCompression::Dxt1, false).unwrap();
+        let (tag, names) = paa_type_and_tags(&dxt1);
+        assert_eq!(tag, 0xFF01);
+        assert_eq!(names, vec!["AVGC", "MAXC", "OFFS"]);
+
+        let dxt5 = PaaConverter::image_to_paa(&img, PaaCompression::Dxt5, false).unwrap();
+        let (tag, names) = paa_type_and_tags(&dxt5);
+        assert_eq!(tag, 0xFF05);
+        assert_eq!(names, vec!["AVGC", "MAXC", "FLAG", "OFFS"]);
+
+        // 单级 8x8（4 个块）：DXT1 每块 8 字节，DXT5 每块 16 字节
+        let header = 2 + 3 * 12 + 4 + 4 + 64 + 2;
+        assert_eq!(dxt1.len(), header + (7 + 4 * 8) + 6);
+        assert_eq!(dxt5.len(), header + 12 + 4 + (7 + 4 * 16) + 6);
+    }
+
+    /// 读取各级 mipmap 的尺寸
+    fn paa_mipmap_sizes(data: &[u8]) -> Vec<(u16, u16)> {
+        let mut pos = 2;
+        while data[pos..].starts_with(b"GGAT") {
+            pos += 12 + u32::from_le_bytes(data[pos + 8..pos + 12].try_into().unwrap()) as usize;
+        }
+        pos += 2; // 空调色板
+        let mut sizes = Vec::new();
+        loop {
+            let width = u16::from_le_bytes([data[pos], data[pos + 1]]);
+            let height = u16::from_le_bytes([data[pos + 2], data[pos + 3]]);
+            if width == 0 {
+                break;
+            }
+            let len = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], 0]) as usize;
+            sizes.push((width, height));
+            pos += 7 + len;
+        }
+        assert_eq!(pos + 6, data.len());
+        sizes
+    }
+
+    #[test]
+    fn test_mipmaps_down_to_one_pixel() {
+        let img = RgbaImage::from_pixel(8, 2, image::Rgba([0, 128, 255, 255]));
+        let data = PaaConverter::image_to_paa(&img, PaaCompression::Dxt1, true).unwrap();
+        assert_eq!(paa_mipmap_sizes(&data), vec![(8, 2), (4, 1), (2, 1), (1, 1)]);
+
+        let single = PaaConverter::image_to_paa(&img, PaaCompression::Dxt1, false).unwrap();
+        assert_eq!(paa_mipmap_sizes(&single), vec![(8, 2)]);
+    }
+
+    #[test]
+    fn test_downsample_box_averages_pixels() {
+        let mut img = RgbaImage::from_pixel(2, 2, image::Rgba([0, 0, 0, 255]));
+        img.put_pixel(0, 0, image::Rgba([200, 100, 40, 255]));
+        let half = PaaConverter::downsample_box(&img);
+        assert_eq!(half.dimensions(), (1, 1));
+        assert_eq!(half.get_pixel(0, 0).0, [50, 25, 10, 255]);
+    }
+
+    #[test]
+    fn test_color_block_solid_red() {
+        let block = [[255, 0, 0, 255]; 16];
+        let encoded = PaaConverter::encode_color_block(&block);
+        assert_eq!(u16::from_le_bytes([encoded[0], encoded[1]]), 0xF800);
+        assert_eq!(&encoded[4..], &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_validate_input_rejects_16_bit_png() {
+        let dir = std::env::temp_dir().join(format!("zmm_paa_validate_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let deep = dir.join("deep.png");
+        image::ImageBuffer::from_pixel(4, 4, image::Rgba([1000u16, 2000, 3000, 65535])).save(&deep).unwrap();
+        let message = PaaConverter::validate_input(&deep).unwrap_err();
+        assert!(message.contains("16 位"));
+        assert!(message.contains("8位RGB/RGBA"));
+
+        let normal = dir.join("normal.png");
+        image::RgbaImage::from_pixel(4, 4, image::Rgba([10, 20, 30, 255])).save(&normal).unwrap();
+        assert!(PaaConverter::validate_input(&normal).is_ok());
+
+        let garbage = dir.join("garbage.png");
+        std::fs::write(&garbage, b"not an image").unwrap();
+        assert!(PaaConverter::validate_input(&garbage).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    /// 4x4 测试图：左半透明、右半不透明
+    fn half_transparent_pixel(x: u32) -> [u8; 4] {
+        if x < 2 { [255, 0, 0, 0] } else { [0, 0, 255, 255] }
+    }
+
+    /// 构造 32 位 TGA（左上角原点，8 位透明通道）；`rle` 时每行为一个重复包加一个原始包
+    fn tga_fixture(rle: bool) -> Vec<u8> {
+        let mut data = vec![0, 0, if rle { 10 } else { 2 }, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 4, 0, 32, 0x28];
+        let bgra = |x: u32| {
+            let [r, g, b, a] = half_transparent_pixel(x);
+            [b, g, r, a]
+        };
+        for _ in 0..4 {
+            if rle {
+                data.push(0x81);
+                data.extend_from_slice(&bgra(0));
+                data.push(0x01);
+                data.extend_from_slice(&bgra(2));
+                data.extend_from_slice(&bgra(3));
+            } else {
+                for x in 0..4 {
+                    data.extend_from_slice(&bgra(x));
+                }
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_tga_fixtures_keep_alpha_through_dxt5() {
+        let dir = std::env::temp_dir().join(format!("zmm_paa_tga_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for (name, rle) in [("rle.tga", true), ("raw32.tga", false)] {
+            let input = dir.join(name);
+            std::fs::write(&input, tga_fixture(rle)).unwrap();
+            assert!(PaaConverter::validate_input(&input).is_ok(), "{}", name);
+
+            let img = PaaConverter::load_image(&input).unwrap().to_rgba8();
+            for (x, _, pixel) in img.enumerate_pixels() {
+                assert_eq!(pixel.0, half_transparent_pixel(x), "{}", name);
+            }
+
+            let output = dir.join(name).with_extension("paa");
+            let conversion = PaaConverter::convert_image_to_paa_with_crop(input.as_path(), output.as_path(), PaaOptions::default(), None).unwrap();
+            assert_eq!(conversion.compression, PaaCompression::Dxt5, "{}", name);
+            assert_eq!(paa_type_and_tags(&std::fs::read(&output).unwrap()).0, 0xFF05);
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    /// 构造 DDS 文件（FourCC 为 DX10 时附加扩展头）
+    fn dds_fixture(width: u32, height: u32, four_cc: &[u8; 4], dxgi_format: Option<u32>, payload: &[u8]) -> Vec<u8> {
+        let mut header = [0u32; 31];
+        header[0] = 124;
+        header[1] = 0x1007;
+        header[2] = height;
+        header[3] = width;
+        header[18] = 32;
+        header[19] = 0x4;
+        header[20] = u32::from_le_bytes(*four_cc);
+        header[26] = 0x1000;
+        let mut data = b"DDS ".to_vec();
+        data.extend(header.iter().flat_map(|value| value.to_le_bytes()));
+        if let Some(format) = dxgi_format {
+            data.extend([format, 3, 0, 1, 0].iter().flat_map(|value| value.to_le_bytes()));
+        }
+        data.extend_from_slice(payload);
+        data
+    }
+
+    #[test]
+    fn test_dxt5_dds_decodes_before_reencoding() {
+        let source = RgbaImage::from_fn(6, 4, |x, _| image::Rgba(half_transparent_pixel(x % 4)));
+        let payload = PaaConverter::encode_dxt(&source, PaaCompression::Dxt5);
+        let dir = std::env::temp_dir().join(format!("zmm_paa_dds_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for (four_cc, dxgi_format) in [(b"DXT5", None), (b"DX10", Some(77))] {
+            let input = dir.join("texture.dds");
+            std::fs::write(&input, dds_fixture(6, 4, four_cc, dxgi_format, &payload)).unwrap();
+            assert!(PaaConverter::validate_input(&input).is_ok());
+            assert_eq!(PaaConverter::load_image(&input).unwrap().to_rgba8(), source);
+        }
+
+        let input = dir.join("texture.dds");
+        let output = dir.join("texture.paa");
+        let conversion = PaaConverter::convert_image_to_paa_with_crop(input.as_path(), output.as_path(), PaaOptions::default(), None).unwrap();
+        assert_eq!(conversion.compression, PaaCompression::Dxt5);
+
+        // 不支持的格式按文件报告明确的错误
+        let bc7 = dir.join("bc7.dds");
+        std::fs::write(&bc7, dds_fixture(4, 4, b"DX10", Some(98), &[0; 16])).unwrap();
+        let message = PaaConverter::validate_input(&bc7).unwrap_err();
+        assert!(message.contains("BC7"), "{}", message);
+        assert!(PaaConverter::convert_image_to_paa_with_crop(bc7.as_path(), dir.join("bc7.paa").as_path(), PaaOptions::default(), None).is_err());
+
+        // 截断或声明超大尺寸的文件只导致该文件失败
+        for (width, height) in [(8, 8), (65535, 65535)] {
+            let truncated = dir.join("truncated.dds");
+            std::fs::write(&truncated, dds_fixture(width, height, b"DXT1", None, &[0; 8])).unwrap();
+            assert!(PaaConverter::load_image(&truncated).is_err());
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_load_thumbnail_image_caps_dimensions() {
+        let dir = std::env::temp_dir().join(format!("zmm_paa_thumbnail_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let wide = dir.join("wide.png");
+        RgbaImage::from_pixel(512, 256, image::Rgba([10, 20, 30, 255])).save(&wide).unwrap();
+        let thumbnail = RuntimeImageTextureManager::load_thumbnail_image(&wide, RuntimeImageTextureManager::THUMBNAIL_SIZE).unwrap();
+        assert_eq!(thumbnail.size, [128, 64]);
+
+        // 小图不放大
+        let small = dir.join("small.png");
+        RgbaImage::from_pixel(32, 16, image::Rgba([10, 20, 30, 255])).save(&small).unwrap();
+        let thumbnail = RuntimeImageTextureManager::load_thumbnail_image(&small, RuntimeImageTextureManager::THUMBNAIL_SIZE).unwrap();
+        assert_eq!(thumbnail.size, [32, 16]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
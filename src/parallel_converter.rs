@@ -4,48 +4,91 @@
  */
 
 use anyhow::{Context, Result};
-use crossbeam_channel::{bounded, Receiver, Sender};
+use crossbeam_channel::{bounded, Receiver};
 use log::{info, warn, debug};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 use std::fmt;
 use crate::audio_converter::AudioConverter;
-use crate::video_converter::VideoConverter;
+use crate::models::{AudioConvertOptions, VideoConvertOptions};
+use crate::video_converter::{VideoConverter, VideoFormat};
 use crate::resource_manager::{GlobalResourceManager, SmartThreadPool};
+use crate::progress_channel::{coalescing_channel, Coalesce, CoalescingReceiver, CoalescingSender};
+
+/// 暂停时检查暂停/取消状态的间隔
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 暂停期间阻塞工作线程，直到继续或取消
+///
+/// 只在领取下一个文件前调用，正在运行的 FFmpeg 进程不受暂停影响。
+pub fn wait_while_paused(pause_flag: &AtomicBool, is_cancelled: impl Fn() -> bool) {
+    while pause_flag.load(Ordering::Relaxed) && !is_cancelled() {
+        thread::sleep(PAUSE_POLL_INTERVAL);
+    }
+}
+
+/// 读取取消标志
+///
+/// 其他线程持有标志时崩溃会使 Mutex 中毒，此时按已取消处理，让工作线程正常退出并发送完成消息，而不是继续 panic。
+pub fn is_cancelled(cancel_flag: &Mutex<bool>) -> bool {
+    match cancel_flag.lock() {
+        Ok(flag) => *flag,
+        Err(_) => true,
+    }
+}
+
+/// 清除取消标志（同时清除上一个工作线程崩溃造成的中毒状态）
+pub fn reset_cancelled(cancel_flag: &Mutex<bool>) {
+    if cancel_flag.is_poisoned() {
+        warn!("上一个任务的工作线程异常退出，已重置取消标志");
+        cancel_flag.clear_poison();
+    }
+    *cancel_flag.lock().unwrap_or_else(|e| e.into_inner()) = false;
+}
+
+/// 任务中止时的结果说明：区分用户取消和工作线程异常退出
+pub fn cancellation_note(cancel_flag: &Mutex<bool>) -> String {
+    if cancel_flag.is_poisoned() {
+        "任务已中止：工作线程异常退出（取消标志已损坏）".to_string()
+    } else {
+        "任务被用户取消".to_string()
+    }
+}
 
 /// 音频转换器trait
 pub trait AudioConverterTrait {
-    fn convert_to_ogg_with_cancel<F>(&self, input_path: &std::path::Path, output_path: &std::path::Path, should_cancel: &F) -> Result<String, anyhow::Error>
+    fn convert_to_ogg_with_progress<F>(&self, input_path: &std::path::Path, output_path: &std::path::Path, options: AudioConvertOptions, should_cancel: &F, on_progress: &(dyn Fn(f32) + Sync)) -> Result<String, anyhow::Error>
     where
         F: Fn() -> bool + ?Sized;
 }
 
 /// 视频转换器trait
 pub trait VideoConverterTrait {
-    fn convert_to_ogv(&self, input_path: &std::path::Path, output_path: &std::path::Path) -> Result<(), anyhow::Error>;
+    fn convert_video_with_progress(&self, input_path: &std::path::Path, output_path: &std::path::Path, format: VideoFormat, options: VideoConvertOptions, on_progress: &(dyn Fn(f32) + Sync)) -> Result<(), anyhow::Error>;
 }
 
 // 为AudioConverter实现trait
 impl AudioConverterTrait for AudioConverter {
-    fn convert_to_ogg_with_cancel<F>(&self, input_path: &std::path::Path, output_path: &std::path::Path, should_cancel: &F) -> Result<String, anyhow::Error>
+    fn convert_to_ogg_with_progress<F>(&self, input_path: &std::path::Path, output_path: &std::path::Path, options: AudioConvertOptions, should_cancel: &F, on_progress: &(dyn Fn(f32) + Sync)) -> Result<String, anyhow::Error>
     where
         F: Fn() -> bool + ?Sized,
     {
-        self.convert_to_ogg_with_cancel(input_path, output_path, should_cancel)
+        AudioConverter::convert_to_ogg_with_progress(self, input_path, output_path, options, should_cancel, on_progress)
     }
 }
 
 impl VideoConverterTrait for AudioConverter {
-    fn convert_to_ogv(&self, _input_path: &std::path::Path, _output_path: &std::path::Path) -> Result<(), anyhow::Error> {
+    fn convert_video_with_progress(&self, _input_path: &std::path::Path, _output_path: &std::path::Path, _format: VideoFormat, _options: VideoConvertOptions, _on_progress: &(dyn Fn(f32) + Sync)) -> Result<(), anyhow::Error> {
         Err(anyhow::anyhow!("AudioConverter不支持视频转换"))
     }
 }
 
 // 为VideoConverter实现trait
 impl AudioConverterTrait for VideoConverter {
-    fn convert_to_ogg_with_cancel<F>(&self, _input_path: &std::path::Path, _output_path: &std::path::Path, _should_cancel: &F) -> Result<String, anyhow::Error>
+    fn convert_to_ogg_with_progress<F>(&self, _input_path: &std::path::Path, _output_path: &std::path::Path, _options: AudioConvertOptions, _should_cancel: &F, _on_progress: &(dyn Fn(f32) + Sync)) -> Result<String, anyhow::Error>
     where
         F: Fn() -> bool + ?Sized,
     {
@@ -54,8 +97,8 @@ impl AudioConverterTrait for VideoConverter {
 }
 
 impl VideoConverterTrait for VideoConverter {
-    fn convert_to_ogv(&self, input_path: &std::path::Path, output_path: &std::path::Path) -> Result<(), anyhow::Error> {
-        self.convert_to_ogv(input_path, output_path)
+    fn convert_video_with_progress(&self, input_path: &std::path::Path, output_path: &std::path::Path, format: VideoFormat, options: VideoConvertOptions, on_progress: &(dyn Fn(f32) + Sync)) -> Result<(), anyhow::Error> {
+        VideoConverter::convert_video_with_progress(self, input_path, output_path, format, options, &|| false, on_progress)
     }
 }
 
@@ -68,6 +111,8 @@ pub struct ParallelConfig {
     pub queue_size: usize,
     /// 是否启用智能线程调度
     pub smart_scheduling: bool,
+    /// 用户设置的最大线程数（设置后不再自动选择线程数，也是按文件大小调整时的上限）
+    pub max_threads_override: Option<usize>,
 }
 
 impl Default for ParallelConfig {
@@ -76,6 +121,7 @@ impl Default for ParallelConfig {
             max_threads: Self::get_optimal_thread_count(),
             queue_size: 1000,
             smart_scheduling: true,
+            max_threads_override: None,
         }
     }
 }
@@ -89,6 +135,20 @@ impl ParallelConfig {
         (cpu_count * 2).min(8).max(2)
     }
     
+    /// 将用户设置的最大线程数限制在 1..=逻辑核心数
+    pub fn clamp_thread_override(threads: usize) -> usize {
+        threads.clamp(1, num_cpus::get().max(1))
+    }
+
+    /// 应用用户设置的最大线程数：设置后直接使用该值，不再自动选择
+    pub fn with_thread_override(mut self, max_threads_override: Option<usize>) -> Self {
+        self.max_threads_override = max_threads_override.map(Self::clamp_thread_override);
+        if let Some(threads) = self.max_threads_override {
+            self.max_threads = threads;
+        }
+        self
+    }
+
     /// 根据文件大小动态调整线程数
     pub fn adjust_for_file_size(&mut self, total_files: usize, avg_file_size_mb: f64) {
         if self.smart_scheduling {
@@ -100,6 +160,10 @@ impl ParallelConfig {
                 self.max_threads = (self.max_threads * 3 / 2).min(12);
             }
         }
+        // 用户设置的最大线程数始终是上限
+        if let Some(limit) = self.max_threads_override {
+            self.max_threads = self.max_threads.min(limit);
+        }
         
         info!("调整后并发线程数: {}", self.max_threads);
     }
@@ -111,11 +175,16 @@ pub enum ConversionTask {
     Audio {
         input_path: PathBuf,
         output_path: PathBuf,
+        /// OGG 编码选项（质量等级、响度归一化）
+        options: AudioConvertOptions,
         task_id: usize,
     },
     Video {
         input_path: PathBuf,
         output_path: PathBuf,
+        format: VideoFormat,
+        /// 分辨率上限与质量
+        options: VideoConvertOptions,
         task_id: usize,
     },
 }
@@ -124,17 +193,14 @@ pub enum ConversionTask {
 #[derive(Debug, Clone)]
 pub enum ConversionResult {
     Success {
-        #[allow(dead_code)]
         task_id: usize,
         input_path: PathBuf,
-        #[allow(dead_code)]
         output_path: PathBuf,
         #[allow(dead_code)]
         duration: Duration,
         message: String,
     },
     Error {
-        #[allow(dead_code)]
         task_id: usize,
         input_path: PathBuf,
         error: String,
@@ -149,6 +215,11 @@ pub enum ProgressUpdate {
         filename: String,
         total_tasks: usize,
     },
+    /// 单个任务的转换进度（0.0-1.0）
+    FileProgress {
+        task_id: usize,
+        fraction: f32,
+    },
     TaskCompleted {
         task_id: usize,
         result: ConversionResult,
@@ -159,20 +230,34 @@ pub enum ProgressUpdate {
         success_count: usize,
         error_count: usize,
         total_duration: Duration,
-        results: Vec<ConversionResult>,
+        /// 每个文件的结果说明，按任务序号排列
+        results: Vec<String>,
     },
 }
 
+impl Coalesce for ProgressUpdate {
+    /// 任务开始和单文件进度按任务合并（通道已满时不阻塞工作线程），完成消息必须送达
+    fn coalesce_key(&self) -> Option<(u8, usize)> {
+        match self {
+            ProgressUpdate::TaskStarted { task_id, .. } => Some((0, *task_id)),
+            ProgressUpdate::FileProgress { task_id, .. } => Some((1, *task_id)),
+            ProgressUpdate::TaskCompleted { .. } | ProgressUpdate::AllTasksCompleted { .. } => None,
+        }
+    }
+}
+
 /// 并行转换器
 pub struct ParallelConverter {
     /// 配置
     config: ParallelConfig,
     /// 进度更新发送器
-    progress_sender: Sender<ProgressUpdate>,
+    progress_sender: CoalescingSender<ProgressUpdate>,
     /// 进度更新接收器
-    progress_receiver: Receiver<ProgressUpdate>,
+    progress_receiver: CoalescingReceiver<ProgressUpdate>,
     /// 取消标志
     cancel_flag: Arc<Mutex<bool>>,
+    /// 暂停标志（暂停时工作线程不再领取新文件）
+    pause_flag: Arc<AtomicBool>,
     /// 统计信息
     stats: Arc<Mutex<ConversionStats>>,
     /// 资源管理器
@@ -196,25 +281,33 @@ struct ConversionStats {
 impl ParallelConverter {
     /// 创建新的并行转换器
     pub fn new(config: ParallelConfig) -> Self {
-        let (progress_sender, progress_receiver) = bounded(config.queue_size);
+        let (progress_sender, progress_receiver) = coalescing_channel(config.queue_size);
         
         Self {
             config,
             progress_sender,
             progress_receiver,
             cancel_flag: Arc::new(Mutex::new(false)),
+            pause_flag: Arc::new(AtomicBool::new(false)),
             stats: Arc::new(Mutex::new(ConversionStats::default())),
             resource_manager: Arc::new(GlobalResourceManager::new()),
         }
     }
+
+    /// 使用外部暂停标志（如任务处理器的暂停标志），使界面可以暂停/继续转换
+    pub fn with_pause_flag(mut self, pause_flag: Arc<AtomicBool>) -> Self {
+        self.pause_flag = pause_flag;
+        self
+    }
     
     /// 并行转换音频文件
     pub fn convert_audio_files_parallel(
         &self,
         files: Vec<PathBuf>,
         output_dir: PathBuf,
+        options: AudioConvertOptions,
     ) -> Result<()> {
-        info!("开始并行音频转换，文件数: {}, 线程数: {}", files.len(), self.config.max_threads);
+        info!("开始并行音频转换，文件数: {}, 线程数: {}, 选项: {:?}", files.len(), self.config.max_threads, options);
         
         // 重置统计信息
         self.reset_stats();
@@ -224,7 +317,7 @@ impl ParallelConverter {
             .context("无法创建音频转换器，请确保FFmpeg已安装")?;
         
         // 准备转换任务
-        let tasks = self.prepare_audio_tasks(files, output_dir)?;
+        let tasks = self.prepare_audio_tasks(files, output_dir, options)?;
         
         // 启动并行转换
         self.start_parallel_conversion(tasks, converter)
@@ -235,8 +328,10 @@ impl ParallelConverter {
         &self,
         files: Vec<PathBuf>,
         output_dir: PathBuf,
+        format: VideoFormat,
+        options: VideoConvertOptions,
     ) -> Result<()> {
-        info!("开始并行视频转换，文件数: {}, 线程数: {}", files.len(), self.config.max_threads);
+        info!("开始并行视频转换，文件数: {}, 线程数: {}, 格式: {:?}, 选项: {:?}", files.len(), self.config.max_threads, format, options);
         
         // 重置统计信息
         self.reset_stats();
@@ -246,14 +341,14 @@ impl ParallelConverter {
             .context("无法创建视频转换器，请确保FFmpeg已安装")?;
         
         // 准备转换任务
-        let tasks = self.prepare_video_tasks(files, output_dir)?;
+        let tasks = self.prepare_video_tasks(files, output_dir, format, options)?;
         
         // 启动并行转换
         self.start_parallel_conversion(tasks, converter)
     }
     
     /// 准备音频转换任务
-    fn prepare_audio_tasks(&self, files: Vec<PathBuf>, output_dir: PathBuf) -> Result<Vec<ConversionTask>> {
+    fn prepare_audio_tasks(&self, files: Vec<PathBuf>, output_dir: PathBuf, options: AudioConvertOptions) -> Result<Vec<ConversionTask>> {
         let mut tasks = Vec::new();
         
         for (i, input_path) in files.iter().enumerate() {
@@ -278,6 +373,7 @@ impl ParallelConverter {
             tasks.push(ConversionTask::Audio {
                 input_path: input_path.clone(),
                 output_path,
+                options,
                 task_id: i,
             });
         }
@@ -292,7 +388,7 @@ impl ParallelConverter {
     }
     
     /// 准备视频转换任务
-    fn prepare_video_tasks(&self, files: Vec<PathBuf>, output_dir: PathBuf) -> Result<Vec<ConversionTask>> {
+    fn prepare_video_tasks(&self, files: Vec<PathBuf>, output_dir: PathBuf, format: VideoFormat, options: VideoConvertOptions) -> Result<Vec<ConversionTask>> {
         let mut tasks = Vec::new();
         
         for (i, input_path) in files.iter().enumerate() {
@@ -302,9 +398,9 @@ impl ParallelConverter {
                     &file_stem.to_string_lossy(), 
                     i
                 );
-                format!("{}.ogv", pinyin_filename)
+                format!("{}.{}", pinyin_filename, format.extension())
             } else {
-                format!("video{:03}.ogv", i)
+                format!("video{:03}.{}", i, format.extension())
             };
             
             let mut output_path = output_dir.join(output_filename);
@@ -317,6 +413,8 @@ impl ParallelConverter {
             tasks.push(ConversionTask::Video {
                 input_path: input_path.clone(),
                 output_path,
+                format,
+                options,
                 task_id: i,
             });
         }
@@ -337,6 +435,7 @@ impl ParallelConverter {
     {
         let progress_sender = self.progress_sender.clone();
         let cancel_flag = self.cancel_flag.clone();
+        let pause_flag = self.pause_flag.clone();
         let stats = self.stats.clone();
         
         // 创建任务队列
@@ -355,7 +454,11 @@ impl ParallelConverter {
         
         // 动态调整线程数
         thread_pool.adjust_thread_count();
-        let actual_thread_count = thread_pool.get_max_threads().min(self.config.max_threads);
+        let actual_thread_count = match self.config.max_threads_override {
+            // 用户设置了最大线程数时直接使用，不受线程池自动上限影响
+            Some(_) => self.config.max_threads,
+            None => thread_pool.get_max_threads().min(self.config.max_threads),
+        };
         
         info!("使用智能线程池，实际线程数: {}", actual_thread_count);
         
@@ -365,6 +468,7 @@ impl ParallelConverter {
             let task_receiver = task_receiver.clone();
             let progress_sender = progress_sender.clone();
             let cancel_flag = cancel_flag.clone();
+            let pause_flag = pause_flag.clone();
             let stats = stats.clone();
             let converter = converter.clone();
             let thread_pool = thread_pool.clone();
@@ -375,6 +479,7 @@ impl ParallelConverter {
                     task_receiver,
                     progress_sender,
                     cancel_flag,
+                    pause_flag,
                     stats,
                     converter,
                     thread_pool,
@@ -401,8 +506,11 @@ impl ParallelConverter {
                 .map(|start| start.elapsed())
                 .unwrap_or_default();
             
-            // 收集所有转换结果
-            let results = final_stats.task_results.clone();
+            // 工作线程按完成顺序收集结果，这里按任务序号整理成说明文本
+            let mut results = ordered_result_lines(&final_stats.task_results);
+            if cancel_flag.is_poisoned() {
+                results.push(cancellation_note(&cancel_flag));
+            }
             let _ = progress_sender.send(ProgressUpdate::AllTasksCompleted {
                 success_count: final_stats.successful_tasks,
                 error_count: final_stats.failed_tasks,
@@ -419,12 +527,14 @@ impl ParallelConverter {
         converter: &C,
         input_path: &std::path::Path,
         output_path: &std::path::Path,
+        options: AudioConvertOptions,
         cancel_check: &dyn Fn() -> bool,
+        on_progress: &(dyn Fn(f32) + Sync),
     ) -> Result<String, anyhow::Error>
     where
         C: AudioConverterTrait,
     {
-        converter.convert_to_ogg_with_cancel(input_path, output_path, cancel_check)
+        converter.convert_to_ogg_with_progress(input_path, output_path, options, cancel_check, on_progress)
     }
     
     /// 执行视频转换任务的辅助方法
@@ -432,19 +542,24 @@ impl ParallelConverter {
         converter: &C,
         input_path: &std::path::Path,
         output_path: &std::path::Path,
+        format: VideoFormat,
+        options: VideoConvertOptions,
+        on_progress: &(dyn Fn(f32) + Sync),
     ) -> Result<(), anyhow::Error>
     where
         C: VideoConverterTrait,
     {
-        converter.convert_to_ogv(input_path, output_path)
+        converter.convert_video_with_progress(input_path, output_path, format, options, on_progress)
     }
     
     /// 工作线程函数
+    #[allow(clippy::too_many_arguments)]
     fn worker_thread<C>(
         worker_id: usize,
         task_receiver: Receiver<ConversionTask>,
-        progress_sender: Sender<ProgressUpdate>,
+        progress_sender: CoalescingSender<ProgressUpdate>,
         cancel_flag: Arc<Mutex<bool>>,
+        pause_flag: Arc<AtomicBool>,
         stats: Arc<Mutex<ConversionStats>>,
         converter: C,
         thread_pool: Arc<SmartThreadPool>,
@@ -457,11 +572,11 @@ impl ParallelConverter {
         thread_pool.thread_start(worker_id);
         
         while let Ok(task) = task_receiver.recv() {
+            // 暂停时等待继续后再开始下一个文件
+            wait_while_paused(&pause_flag, || is_cancelled(&cancel_flag));
+
             // 检查取消标志
-            if *cancel_flag.lock().unwrap_or_else(|_| {
-                warn!("获取取消标志失败，假设任务被取消");
-                panic!("Mutex poisoned, cannot continue")
-            }) {
+            if is_cancelled(&cancel_flag) {
                 info!("工作线程 {} 收到取消信号", worker_id);
                 break;
             }
@@ -483,24 +598,31 @@ impl ParallelConverter {
                 total_tasks,
             });
             
-            // 执行转换任务
+            // 执行转换任务（单文件进度在通道已满时按任务合并，不阻塞转换）
             let start_time = Instant::now();
+            let task_id = task.task_id();
+            let report_progress = |fraction: f32| {
+                let _ = progress_sender.send(ProgressUpdate::FileProgress { task_id, fraction });
+            };
             let result = match &task {
-                ConversionTask::Audio { input_path, output_path, task_id } => {
-                    let cancel_check = || *cancel_flag.lock().unwrap_or_else(|_| {
-                        warn!("获取取消标志失败，假设任务被取消");
-                        panic!("Mutex poisoned, cannot continue")
-                    });
+                ConversionTask::Audio { input_path, output_path, options, task_id } => {
+                    let cancel_check = || is_cancelled(&cancel_flag);
                     
                     // 使用trait方法进行音频转换
-                    match Self::convert_audio_task(&converter, input_path, output_path, &cancel_check) {
-                        Ok(_) => {
+                    match Self::convert_audio_task(&converter, input_path, output_path, *options, &cancel_check, &report_progress) {
+                        Ok(message) => {
+                            // 直接复制音频流时保留说明，便于用户区分
+                            let message = if message == AudioConverter::STREAM_COPY_MESSAGE {
+                                message
+                            } else {
+                                "音频转换成功".to_string()
+                            };
                             ConversionResult::Success {
                                 task_id: *task_id,
                                 input_path: input_path.clone(),
                                 output_path: output_path.clone(),
                                 duration: start_time.elapsed(),
-                                message: "音频转换成功".to_string(),
+                                message,
                             }
                         }
                         Err(e) => {
@@ -512,9 +634,9 @@ impl ParallelConverter {
                         }
                     }
                 }
-                ConversionTask::Video { input_path, output_path, task_id } => {
+                ConversionTask::Video { input_path, output_path, format, options, task_id } => {
                     // 使用trait方法进行视频转换
-                    match Self::convert_video_task(&converter, input_path, output_path) {
+                    match Self::convert_video_task(&converter, input_path, output_path, *format, *options, &report_progress) {
                         Ok(_) => {
                             ConversionResult::Success {
                                 task_id: *task_id,
@@ -576,7 +698,7 @@ impl ParallelConverter {
     }
     
     /// 获取进度接收器
-    pub fn get_progress_receiver(&self) -> &Receiver<ProgressUpdate> {
+    pub fn get_progress_receiver(&self) -> &CoalescingReceiver<ProgressUpdate> {
         &self.progress_receiver
     }
     
@@ -616,6 +738,13 @@ impl ConversionTask {
 
 // 为ConversionResult实现辅助方法
 impl ConversionResult {
+    pub fn task_id(&self) -> usize {
+        match self {
+            ConversionResult::Success { task_id, .. } => *task_id,
+            ConversionResult::Error { task_id, .. } => *task_id,
+        }
+    }
+
     pub fn input_path(&self) -> &PathBuf {
         match self {
             ConversionResult::Success { input_path, .. } => input_path,
@@ -624,16 +753,28 @@ impl ConversionResult {
     }
 }
 
-// 为ConversionResult实现Display trait
+// 为ConversionResult实现Display trait（与串行转换的结果行格式一致）
 impl fmt::Display for ConversionResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file_name = |path: &PathBuf| path.file_name().unwrap_or_default().to_string_lossy().to_string();
         match self {
-            ConversionResult::Success { message, .. } => write!(f, "{}", message),
-            ConversionResult::Error { error, .. } => write!(f, "{}", error),
+            ConversionResult::Success { message, input_path, output_path, .. } => {
+                write!(f, "✓ {}: {} -> {}", message, file_name(input_path), file_name(output_path))
+            }
+            ConversionResult::Error { error, input_path, .. } => {
+                write!(f, "✗ {} - {}", file_name(input_path), error)
+            }
         }
     }
 }
 
+/// 将按完成顺序收集的结果按任务序号排序并转换为说明文本
+fn ordered_result_lines(results: &[ConversionResult]) -> Vec<String> {
+    let mut ordered: Vec<&ConversionResult> = results.iter().collect();
+    ordered.sort_by_key(|result| result.task_id());
+    ordered.into_iter().map(ToString::to_string).collect()
+}
+
 // 为AudioConverter和VideoConverter实现Clone trait
 impl Clone for AudioConverter {
     fn clone(&self) -> Self {
@@ -651,3 +792,172 @@ impl Clone for VideoConverter {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// 模拟转换器：序号越小耗时越长，使完成顺序与任务顺序相反；文件名含 "bad" 时失败
+    #[derive(Clone)]
+    struct MockConverter;
+
+    impl MockConverter {
+        fn run(input_path: &Path) -> Result<()> {
+            let name = input_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let index: u64 = name[..2].parse().unwrap_or(0);
+            thread::sleep(Duration::from_millis((8 - index.min(8)) * 10));
+            if name.contains("bad") {
+                Err(anyhow::anyhow!("模拟失败"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl AudioConverterTrait for MockConverter {
+        fn convert_to_ogg_with_progress<F>(&self, input_path: &std::path::Path, _output_path: &std::path::Path, _options: AudioConvertOptions, _should_cancel: &F, _on_progress: &(dyn Fn(f32) + Sync)) -> Result<String, anyhow::Error>
+        where
+            F: Fn() -> bool + ?Sized,
+        {
+            Self::run(input_path).map(|_| String::new())
+        }
+    }
+
+    impl VideoConverterTrait for MockConverter {
+        fn convert_video_with_progress(&self, input_path: &std::path::Path, _output_path: &std::path::Path, _format: VideoFormat, _options: VideoConvertOptions, _on_progress: &(dyn Fn(f32) + Sync)) -> Result<(), anyhow::Error> {
+            Self::run(input_path)
+        }
+    }
+
+    #[test]
+    fn test_result_lines_ordered_by_task_id() {
+        let results = vec![
+            ConversionResult::Error { task_id: 2, input_path: PathBuf::from("c.wav"), error: "音频转换失败: x".to_string() },
+            ConversionResult::Success {
+                task_id: 0,
+                input_path: PathBuf::from("a.wav"),
+                output_path: PathBuf::from("out/a.ogg"),
+                duration: Duration::ZERO,
+                message: "音频转换成功".to_string(),
+            },
+            ConversionResult::Error { task_id: 1, input_path: PathBuf::from("b.wav"), error: "音频转换失败: y".to_string() },
+        ];
+        assert_eq!(
+            ordered_result_lines(&results),
+            vec![
+                "✓ 音频转换成功: a.wav -> a.ogg".to_string(),
+                "✗ b.wav - 音频转换失败: y".to_string(),
+                "✗ c.wav - 音频转换失败: x".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parallel_results_collected_from_all_workers() {
+        let converter = ParallelConverter::new(ParallelConfig {
+            max_threads: 4,
+            queue_size: 100,
+            smart_scheduling: false,
+            max_threads_override: None,
+        });
+        let files: Vec<PathBuf> = (0..8)
+            .map(|i| PathBuf::from(format!("{:02}_{}.wav", i, if i % 3 == 0 { "bad" } else { "ok" })))
+            .collect();
+        let output_dir = std::env::temp_dir().join("zeus_parallel_results_test");
+        let tasks = converter.prepare_audio_tasks(files, output_dir, AudioConvertOptions::default()).unwrap();
+        converter.start_parallel_conversion(tasks, MockConverter).unwrap();
+
+        let receiver = converter.get_progress_receiver();
+        let (success_count, error_count, results) = loop {
+            match receiver.recv_timeout(Duration::from_secs(10)).expect("并行转换未完成") {
+                ProgressUpdate::AllTasksCompleted { success_count, error_count, results, .. } => {
+                    break (success_count, error_count, results);
+                }
+                _ => continue,
+            }
+        };
+
+        assert_eq!((success_count, error_count), (5, 3));
+        assert_eq!(results.len(), 8);
+        for (i, line) in results.iter().enumerate() {
+            assert!(line.contains(&format!("{:02}_", i)), "第 {} 行顺序错误: {}", i, line);
+            assert_eq!(line.starts_with('✗'), i % 3 == 0, "{}", line);
+        }
+    }
+
+    #[test]
+    fn wait_while_paused_returns_on_resume_or_cancel() {
+        // 未暂停时立即返回
+        let pause = Arc::new(AtomicBool::new(false));
+        wait_while_paused(&pause, || false);
+
+        // 暂停中被取消时返回
+        pause.store(true, Ordering::Relaxed);
+        wait_while_paused(&pause, || true);
+
+        // 暂停后由其他线程继续
+        let resumer = {
+            let pause = pause.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                pause.store(false, Ordering::Relaxed);
+            })
+        };
+        wait_while_paused(&pause, || false);
+        assert!(!pause.load(Ordering::Relaxed));
+        resumer.join().unwrap();
+    }
+
+    #[test]
+    fn thread_override_replaces_auto_count_and_caps_adjustment() {
+        let cores = num_cpus::get().max(1);
+        assert_eq!(ParallelConfig::clamp_thread_override(0), 1);
+        assert_eq!(ParallelConfig::clamp_thread_override(cores + 16), cores);
+
+        let config = ParallelConfig::default().with_thread_override(Some(1));
+        assert_eq!(config.max_threads, 1);
+
+        // 小文件大批量时自动调整会增加线程数，但不能超过用户设置
+        let mut config = config;
+        config.adjust_for_file_size(100, 1.0);
+        assert_eq!(config.max_threads, 1);
+
+        let config = ParallelConfig::default().with_thread_override(None);
+        assert_eq!(config.max_threads, ParallelConfig::default().max_threads);
+    }
+
+    #[test]
+    fn poisoned_cancel_flag_still_reports_completion() {
+        let converter = ParallelConverter::new(ParallelConfig {
+            max_threads: 2,
+            queue_size: 100,
+            smart_scheduling: false,
+            max_threads_override: None,
+        });
+        // 模拟持有取消标志的线程崩溃
+        let flag = converter.cancel_flag.clone();
+        let _ = thread::spawn(move || {
+            let _guard = flag.lock().unwrap();
+            panic!("模拟工作线程崩溃");
+        })
+        .join();
+        assert!(converter.cancel_flag.is_poisoned());
+        assert!(is_cancelled(&converter.cancel_flag));
+
+        let files: Vec<PathBuf> = (0..4).map(|i| PathBuf::from(format!("{:02}_ok.wav", i))).collect();
+        let output_dir = std::env::temp_dir().join("zeus_parallel_poison_test");
+        let tasks = converter.prepare_audio_tasks(files, output_dir, AudioConvertOptions::default()).unwrap();
+        converter.start_parallel_conversion(tasks, MockConverter).unwrap();
+
+        let receiver = converter.get_progress_receiver();
+        let (success_count, results) = loop {
+            match receiver.recv_timeout(Duration::from_secs(10)).expect("取消标志中毒后未发送完成消息") {
+                ProgressUpdate::AllTasksCompleted { success_count, results, .. } => break (success_count, results),
+                _ => continue,
+            }
+        };
+        assert_eq!(success_count, 0);
+        assert!(results.last().is_some_and(|line| line.contains("工作线程异常退出")), "{:?}", results);
+    }
+}
@@ -0,0 +1,181 @@
+/*!
+ * 播放列表导入模块
+ * 解析 M3U/M3U8/PLS 播放列表或粘贴的路径列表，将条目解析为本地文件路径（保持原有顺序）
+ */
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 播放列表条目的解析结果
+#[derive(Debug, Default)]
+pub struct PlaylistImport {
+    /// 可用的本地文件（按播放列表顺序）
+    pub files: Vec<PathBuf>,
+    /// 无法解析的条目及原因
+    pub unresolved: Vec<(String, String)>,
+}
+
+/// 读取播放列表文件并解析条目
+pub fn load_playlist(path: &Path) -> Result<PlaylistImport> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("无法读取播放列表: {:?}", path))?;
+    let content = String::from_utf8_lossy(&bytes);
+    let content = content.trim_start_matches('\u{feff}'); // 去除 UTF-8 BOM
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+    let is_pls = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("pls"))
+        .unwrap_or(false);
+    let entries = if is_pls { parse_pls(content) } else { parse_m3u(content) };
+
+    let mut import = PlaylistImport::default();
+    for entry in entries {
+        match resolve_entry(&entry, base_dir) {
+            Ok(file) => import.files.push(file),
+            Err(reason) => import.unresolved.push((entry, reason)),
+        }
+    }
+    Ok(import)
+}
+
+/// 解析 M3U/M3U8：非空且不以 `#` 开头的行为条目
+pub fn parse_m3u(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 解析 PLS：按 `FileN=` 的编号排序
+pub fn parse_pls(content: &str) -> Vec<String> {
+    let mut entries: Vec<(u32, String)> = content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once('=')?;
+            let number = key.trim().strip_prefix("File").or_else(|| key.trim().strip_prefix("file"))?;
+            Some((number.parse().ok()?, value.trim().to_string()))
+        })
+        .filter(|(_, value)| !value.is_empty())
+        .collect();
+    entries.sort_by_key(|(number, _)| *number);
+    entries.into_iter().map(|(_, value)| value).collect()
+}
+
+/// 解析粘贴的路径列表（每行一个，支持带引号的路径）
+pub fn parse_path_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .map(|line| {
+            let unquoted = ['"', '\'']
+                .iter()
+                .find_map(|&quote| line.strip_prefix(quote)?.strip_suffix(quote))
+                .unwrap_or(line)
+                .trim();
+            // POSIX shell 风格的转义空格（Windows 路径中的 `\` 是分隔符，不做处理）
+            if cfg!(windows) {
+                unquoted.to_string()
+            } else {
+                unquoted.replace("\\ ", " ")
+            }
+        })
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// 解析粘贴的路径列表，相对路径基于当前工作目录
+pub fn resolve_path_list(text: &str) -> PlaylistImport {
+    let mut import = PlaylistImport::default();
+    for entry in parse_path_list(text) {
+        match resolve_entry(&entry, Path::new(".")) {
+            Ok(file) => import.files.push(file),
+            Err(reason) => import.unresolved.push((entry, reason)),
+        }
+    }
+    import
+}
+
+/// 将条目解析为存在的本地文件路径（相对路径基于播放列表所在目录）
+fn resolve_entry(entry: &str, base_dir: &Path) -> std::result::Result<PathBuf, String> {
+    let lower = entry.to_ascii_lowercase();
+    let local = if let Some(rest) = lower.strip_prefix("file://") {
+        // file:///C:/Music/a.ogg 或 file:///home/user/a.ogg
+        let rest = &entry[entry.len() - rest.len()..];
+        let rest = if rest.len() > 2 && rest.as_bytes()[2] == b':' { &rest[1..] } else { rest };
+        percent_decode(rest)
+    } else if lower.contains("://") {
+        return Err("网络地址不支持".to_string());
+    } else {
+        entry.to_string()
+    };
+
+    let path = PathBuf::from(&local);
+    let path = if path.is_absolute() { path } else { base_dir.join(path) };
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err("文件不存在".to_string())
+    }
+}
+
+/// 解码 URL 中的 %XX 转义
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_m3u_skips_comments() {
+        let content = "#EXTM3U\n#EXTINF:123,Artist - Song\nmusic/song.ogg\n\nother.ogg\n";
+        assert_eq!(parse_m3u(content), vec!["music/song.ogg", "other.ogg"]);
+    }
+
+    #[test]
+    fn test_parse_pls_orders_by_number() {
+        let content = "[playlist]\nFile2=b.ogg\nTitle2=B\nFile1=a.ogg\nNumberOfEntries=2\n";
+        assert_eq!(parse_pls(content), vec!["a.ogg", "b.ogg"]);
+    }
+
+    #[test]
+    fn test_resolve_entry_rejects_urls_and_missing_files() {
+        let base = Path::new(".");
+        assert_eq!(resolve_entry("http://example.com/a.ogg", base), Err("网络地址不支持".to_string()));
+        assert_eq!(resolve_entry("definitely_missing_file.ogg", base), Err("文件不存在".to_string()));
+    }
+
+    #[test]
+    fn test_parse_path_list_strips_quotes() {
+        let text = "\"C:\\Music\\My Song.ogg\"\r\n'/home/user/a b.ogg'\n\n  /home/user/plain.ogg  \n";
+        assert_eq!(
+            parse_path_list(text),
+            vec!["C:\\Music\\My Song.ogg", "/home/user/a b.ogg", "/home/user/plain.ogg"]
+        );
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("My%20Song%E6%AD%8C.ogg"), "My Song歌.ogg");
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+}
@@ -0,0 +1,224 @@
+/*!
+ * 进度消息通道
+ * 有界通道已满时合并高频进度消息而不阻塞工作线程，完成类消息保证按顺序送达
+ */
+
+use crossbeam_channel::{bounded, Receiver, RecvError, RecvTimeoutError, SendError, Sender, TryRecvError, TrySendError};
+use log::warn;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 阻塞接收时检查暂存消息的间隔
+const PENDING_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 可合并的进度消息
+pub trait Coalesce {
+    /// 合并键：键相同的消息在通道已满时只保留最新一条；None 表示必须送达、不可合并的消息
+    fn coalesce_key(&self) -> Option<(u8, usize)>;
+}
+
+/// 创建进度消息通道
+pub fn coalescing_channel<T: Coalesce>(capacity: usize) -> (CoalescingSender<T>, CoalescingReceiver<T>) {
+    let (sender, receiver) = bounded(capacity);
+    let pending = Arc::new(Mutex::new(Vec::new()));
+    let coalesced = Arc::new(AtomicUsize::new(0));
+    (
+        CoalescingSender { sender, pending: pending.clone(), coalesced },
+        CoalescingReceiver { receiver, pending, capacity },
+    )
+}
+
+/// 进度消息发送器
+///
+/// 进度消息在通道已满时不阻塞，而是按合并键只保留最新一条，等通道空闲后再交给接收端；
+/// 不可合并的消息始终阻塞发送，且会先送出暂存的进度，保证顺序且不会丢失。
+pub struct CoalescingSender<T> {
+    sender: Sender<T>,
+    /// 通道已满时暂存的最新进度消息（每个合并键一条，按首次暂存顺序）
+    pending: Arc<Mutex<Vec<T>>>,
+    coalesced: Arc<AtomicUsize>,
+}
+
+impl<T> Clone for CoalescingSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            pending: self.pending.clone(),
+            coalesced: self.coalesced.clone(),
+        }
+    }
+}
+
+impl<T: Coalesce> CoalescingSender<T> {
+    /// 发送消息：进度消息不会阻塞，其他消息在通道满时等待接收端处理
+    pub fn send(&self, message: T) -> Result<(), SendError<T>> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+
+        let Some(key) = message.coalesce_key() else {
+            // 先送出暂存的进度，避免接收端在完成消息之后收到过时的进度
+            let flushed: Vec<T> = pending.drain(..).collect();
+            drop(pending);
+            for progress in flushed {
+                self.sender.send(progress)?;
+            }
+            return self.sender.send(message);
+        };
+
+        // 同键进度已在暂存中时直接替换，保证之后送达的不会比暂存的更旧
+        if let Some(slot) = pending.iter_mut().find(|m| m.coalesce_key() == Some(key)) {
+            *slot = message;
+            self.coalesced.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+        match self.sender.try_send(message) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(message)) => {
+                if self.coalesced.fetch_add(1, Ordering::Relaxed) == 0 {
+                    warn!("进度消息通道已满，接收端处理滞后，开始合并进度消息");
+                }
+                pending.push(message);
+                Ok(())
+            }
+            Err(TrySendError::Disconnected(message)) => Err(SendError(message)),
+        }
+    }
+
+    /// 尝试立即发送（通道满时返回错误，不暂存）
+    pub fn try_send(&self, message: T) -> Result<(), TrySendError<T>> {
+        self.sender.try_send(message)
+    }
+
+    /// 因接收端滞后而被合并的进度消息数
+    pub fn coalesced_count(&self) -> usize {
+        self.coalesced.load(Ordering::Relaxed)
+    }
+
+    /// 清零合并计数（新任务开始时调用）
+    pub fn reset_coalesced_count(&self) {
+        self.coalesced.store(0, Ordering::Relaxed);
+    }
+}
+
+/// 进度消息接收器（通道为空时再取出被合并暂存的进度消息）
+pub struct CoalescingReceiver<T> {
+    receiver: Receiver<T>,
+    pending: Arc<Mutex<Vec<T>>>,
+    capacity: usize,
+}
+
+impl<T> CoalescingReceiver<T> {
+    fn pop_pending(&self) -> Option<T> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        if pending.is_empty() {
+            None
+        } else {
+            Some(pending.remove(0))
+        }
+    }
+
+    /// 取出下一条消息，没有消息时立即返回 None
+    pub fn try_recv(&self) -> Option<T> {
+        self.receiver.try_recv().ok().or_else(|| self.pop_pending())
+    }
+
+    /// 阻塞等待下一条消息，所有发送端关闭且没有暂存消息时返回错误
+    pub fn recv(&self) -> Result<T, RecvError> {
+        loop {
+            match self.recv_timeout(PENDING_POLL_INTERVAL) {
+                Ok(message) => return Ok(message),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return Err(RecvError),
+            }
+        }
+    }
+
+    /// 在超时前等待下一条消息
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.receiver.try_recv() {
+                Ok(message) => return Ok(message),
+                Err(TryRecvError::Disconnected) => {
+                    return self.pop_pending().ok_or(RecvTimeoutError::Disconnected);
+                }
+                Err(TryRecvError::Empty) => {}
+            }
+            if let Some(message) = self.pop_pending() {
+                return Ok(message);
+            }
+
+            // 暂存消息只会在通道已满时产生，短间隔等待后重新检查
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            match self.receiver.recv_timeout((deadline - now).min(PENDING_POLL_INTERVAL)) {
+                Ok(message) => return Ok(message),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return self.pop_pending().ok_or(RecvTimeoutError::Disconnected);
+                }
+            }
+        }
+    }
+
+    /// 通道中等待处理的消息数（不含暂存的合并消息）
+    pub fn queued(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// 通道容量
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Message {
+        Progress { task: usize, value: u32 },
+        Done,
+    }
+
+    impl Coalesce for Message {
+        fn coalesce_key(&self) -> Option<(u8, usize)> {
+            match self {
+                Message::Progress { task, .. } => Some((0, *task)),
+                Message::Done => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_coalesces_per_key_and_keeps_terminal_messages() {
+        let (sender, receiver) = coalescing_channel(1);
+
+        sender.send(Message::Progress { task: 0, value: 1 }).unwrap();
+        // 通道已满：每个任务只保留最新一条进度，不阻塞
+        sender.send(Message::Progress { task: 1, value: 1 }).unwrap();
+        sender.send(Message::Progress { task: 0, value: 2 }).unwrap();
+        sender.send(Message::Progress { task: 1, value: 3 }).unwrap();
+        assert_eq!(sender.coalesced_count(), 3);
+
+        assert_eq!(receiver.try_recv(), Some(Message::Progress { task: 0, value: 1 }));
+        assert_eq!(receiver.try_recv(), Some(Message::Progress { task: 1, value: 3 }));
+        assert_eq!(receiver.try_recv(), Some(Message::Progress { task: 0, value: 2 }));
+        assert_eq!(receiver.try_recv(), None);
+
+        // 完成消息先送出暂存的最新进度，再送出自身
+        sender.send(Message::Progress { task: 2, value: 1 }).unwrap();
+        sender.send(Message::Progress { task: 2, value: 2 }).unwrap();
+        let worker = std::thread::spawn(move || sender.send(Message::Done).unwrap());
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(Message::Progress { task: 2, value: 1 }));
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(Message::Progress { task: 2, value: 2 }));
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(Message::Done));
+        worker.join().unwrap();
+
+        // 发送端全部关闭后返回错误
+        assert_eq!(receiver.recv(), Err(RecvError));
+    }
+}
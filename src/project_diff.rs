@@ -0,0 +1,194 @@
+/*!
+ * 项目比较模块
+ * 读取两个项目/预设文件中的轨道列表并计算差异（只读，不修改当前项目）
+ */
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 用于比较的轨道信息
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PresetTrack {
+    /// 源文件路径
+    pub path: PathBuf,
+    /// 轨道名称
+    pub track_name: String,
+    /// 标签
+    #[serde(default)]
+    pub tag: String,
+    /// 分贝调整值
+    #[serde(default)]
+    pub decibels: i32,
+    /// 时长（秒）
+    #[serde(default)]
+    pub duration: u32,
+}
+
+/// 支持的文件结构：包含 `tracks` 的项目/配置文件，或包含 `entries` 的导出记录
+#[derive(Deserialize)]
+struct PresetFile {
+    #[serde(default)]
+    tracks: Option<Vec<PresetTrack>>,
+    #[serde(default)]
+    entries: Option<Vec<PresetTrack>>,
+}
+
+/// 单个轨道的变化
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrackChange {
+    /// 新增的轨道
+    Added(PresetTrack),
+    /// 移除的轨道
+    Removed(PresetTrack),
+    /// 属性有变化的轨道，附带变化说明
+    Modified {
+        before: PresetTrack,
+        after: PresetTrack,
+        changes: Vec<String>,
+    },
+}
+
+/// 从文件读取轨道列表
+pub fn load_preset_tracks(path: &Path) -> Result<Vec<PresetTrack>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("无法读取文件: {:?}", path))?;
+    let preset: PresetFile = serde_json::from_str(&content)
+        .with_context(|| format!("无法解析项目文件: {:?}", path))?;
+    preset
+        .tracks
+        .or(preset.entries)
+        .ok_or_else(|| anyhow!("文件中没有轨道列表: {:?}", path))
+}
+
+/// 比较两个轨道列表：优先按源文件路径匹配，路径不同时按轨道名称匹配
+pub fn diff_presets(before: &[PresetTrack], after: &[PresetTrack]) -> Vec<TrackChange> {
+    let mut matched_after = vec![false; after.len()];
+    let mut pairs: Vec<(usize, Option<usize>)> = Vec::with_capacity(before.len());
+
+    // 第一轮：按路径匹配
+    for (i, old) in before.iter().enumerate() {
+        let found = after
+            .iter()
+            .enumerate()
+            .position(|(j, new)| !matched_after[j] && new.path == old.path);
+        if let Some(j) = found {
+            matched_after[j] = true;
+        }
+        pairs.push((i, found));
+    }
+
+    // 第二轮：未匹配的按名称匹配
+    for pair in pairs.iter_mut().filter(|(_, found)| found.is_none()) {
+        let old = &before[pair.0];
+        let found = after
+            .iter()
+            .enumerate()
+            .position(|(j, new)| !matched_after[j] && new.track_name == old.track_name);
+        if let Some(j) = found {
+            matched_after[j] = true;
+            pair.1 = Some(j);
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (i, found) in pairs {
+        let old = &before[i];
+        match found {
+            Some(j) => {
+                let field_changes = describe_changes(old, &after[j]);
+                if !field_changes.is_empty() {
+                    changes.push(TrackChange::Modified {
+                        before: old.clone(),
+                        after: after[j].clone(),
+                        changes: field_changes,
+                    });
+                }
+            }
+            None => changes.push(TrackChange::Removed(old.clone())),
+        }
+    }
+    for (j, new) in after.iter().enumerate() {
+        if !matched_after[j] {
+            changes.push(TrackChange::Added(new.clone()));
+        }
+    }
+
+    changes
+}
+
+/// 描述两个匹配轨道之间的字段变化
+fn describe_changes(before: &PresetTrack, after: &PresetTrack) -> Vec<String> {
+    let mut changes = Vec::new();
+    if before.track_name != after.track_name {
+        changes.push(format!("名称: {} → {}", before.track_name, after.track_name));
+    }
+    if before.tag != after.tag {
+        changes.push(format!("标签: {} → {}", display_or_empty(&before.tag), display_or_empty(&after.tag)));
+    }
+    if before.decibels != after.decibels {
+        changes.push(format!("分贝: {} → {}", before.decibels, after.decibels));
+    }
+    if before.duration != after.duration {
+        changes.push(format!("时长: {}s → {}s", before.duration, after.duration));
+    }
+    if before.path != after.path {
+        changes.push(format!("路径: {} → {}", before.path.display(), after.path.display()));
+    }
+    changes
+}
+
+fn display_or_empty(value: &str) -> &str {
+    if value.is_empty() {
+        "(无)"
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(path: &str, name: &str, decibels: i32) -> PresetTrack {
+        PresetTrack {
+            path: PathBuf::from(path),
+            track_name: name.to_string(),
+            tag: String::new(),
+            decibels,
+            duration: 120,
+        }
+    }
+
+    #[test]
+    fn test_diff_presets_detects_added_removed_and_modified() {
+        let before = vec![track("a.ogg", "A", 0), track("b.ogg", "B", 0)];
+        let after = vec![track("a.ogg", "A", 3), track("c.ogg", "C", 0)];
+
+        let changes = diff_presets(&before, &after);
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(&changes[0], TrackChange::Modified { changes, .. } if changes == &vec!["分贝: 0 → 3".to_string()]));
+        assert!(matches!(&changes[1], TrackChange::Removed(t) if t.track_name == "B"));
+        assert!(matches!(&changes[2], TrackChange::Added(t) if t.track_name == "C"));
+    }
+
+    #[test]
+    fn test_diff_presets_falls_back_to_name_match() {
+        let before = vec![track("old/song.ogg", "Song", 0)];
+        let after = vec![track("new/song.ogg", "Song", 0)];
+
+        let changes = diff_presets(&before, &after);
+        assert_eq!(changes.len(), 1);
+        match &changes[0] {
+            TrackChange::Modified { changes, .. } => assert!(changes[0].starts_with("路径:")),
+            other => panic!("应为修改: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diff_presets_identical_is_empty() {
+        let tracks = vec![track("a.ogg", "A", 0)];
+        assert!(diff_presets(&tracks, &tracks).is_empty());
+    }
+}
@@ -384,11 +384,7 @@ pub fn generate_music_stringtable(
     ));
 
     for (i, track) in tracks.iter().enumerate() {
-        let display = if use_tags && !track.tag.is_empty() {
-            format!("[{}] {}", track.tag, track.track_name)
-        } else {
-            track.track_name.clone()
-        };
+        let display = track.export_name(use_tags);
 
         entries.push(StringtableEntry::from_text(
             key_track(&prefix, i),
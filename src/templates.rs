@@ -2,6 +2,7 @@ use anyhow::{Context, Result};
 use handlebars::Handlebars;
 use log::{debug, info, warn};
 use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
@@ -39,6 +40,17 @@ pub struct TrackTemplateData {
     pub class_name: String,
 }
 
+/// 导出前名称检查发现的问题
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackNameIssue {
+    /// 问题轨道的索引
+    pub track_index: usize,
+    /// 问题说明
+    pub message: String,
+    /// 是否可以通过重新生成轨道名称修正
+    pub fixable: bool,
+}
+
 /// 模板引擎
 pub struct TemplateEngine {
     handlebars: Handlebars<'static>,
@@ -80,7 +92,7 @@ impl TemplateEngine {
         Ok(())
     }
 
-    fn write_arma_config_file(path: &Path, content: &str) -> Result<()> {
+    pub(crate) fn write_arma_config_file(path: &Path, content: &str) -> Result<()> {
         let mut content_with_crlf = content.replace('\n', "\r\n");
         if !content_with_crlf.ends_with("\r\n") {
             content_with_crlf.push_str("\r\n");
@@ -109,13 +121,138 @@ impl TemplateEngine {
         crate::utils::string_utils::StringUtils::to_ascii_safe_pinyin(&project.class_name)
     }
 
-    /// 生成config.cpp文件
-    pub fn generate_config_cpp(
-        &self,
+    /// 轨道的配置类名：默认为 `<类名>Song<序号>`；启用标签类名时在前面加上标签，如 `Combat_MyModSong0`
+    ///
+    /// 序号保证唯一；没有标签的轨道保持默认类名。
+    fn track_class_name(class_id: &str, track: &Track, index: usize, tag_class_names: bool) -> String {
+        let tag = if tag_class_names {
+            crate::utils::string_utils::StringUtils::class_identifier(&track.tag)
+        } else {
+            String::new()
+        };
+        if tag.is_empty() {
+            format!("{}Song{}", class_id, index)
+        } else {
+            format!("{}_{}Song{}", tag, class_id, index)
+        }
+    }
+
+    /// 生成任务触发器中播放各轨道的 SQF 代码，每个轨道一行 `playMusic "<类名>";`
+    ///
+    /// 类名与 `FileListWithMusicTracks.hpp` 中生成的完全一致，轨道名称作为行尾注释。
+    pub fn generate_sqf_snippets(project: &ProjectSettings, tracks: &[Track], tag_class_names: bool) -> String {
+        let class_id = Self::music_class_identifier(project);
+        let mut content = format!("// {} - {} 个轨道\n", project.mod_name, tracks.len());
+        for (i, track) in tracks.iter().enumerate() {
+            let track_class = Self::track_class_name(&class_id, track, i, tag_class_names);
+            content.push_str(&format!("playMusic \"{}\"; // {}\n", track_class, track.track_name));
+        }
+        content
+    }
+
+    /// 轨道导出到 `FileListWithMusicTracks.hpp` 时的配置类名
+    pub fn config_class_name(project: &ProjectSettings, track: &Track, index: usize, tag_class_names: bool) -> String {
+        Self::track_class_name(&Self::music_class_identifier(project), track, index, tag_class_names)
+    }
+
+    /// 配置类名是否合法（仅字母、数字和下划线，不以数字开头）
+    fn is_valid_class_name(name: &str) -> bool {
+        !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    /// 导出前检查轨道名称：生成的类名是否非法或重复、名称中是否有无法转写的字符、
+    /// 不同名称转写为拼音后是否相同（游戏内显示为同名轨道）
+    pub fn check_track_names(
         project: &ProjectSettings,
-        use_stringtable: bool,
-        output_path: &Path,
-    ) -> Result<()> {
+        tracks: &[Track],
+        use_tags: bool,
+        tag_class_names: bool,
+    ) -> Vec<TrackNameIssue> {
+        use crate::utils::string_utils::StringUtils;
+
+        let class_id = Self::music_class_identifier(project);
+        let mut issues = Vec::new();
+        let mut class_names: HashMap<String, usize> = HashMap::new();
+        let mut display_names: HashMap<String, usize> = HashMap::new();
+
+        for (i, track) in tracks.iter().enumerate() {
+            let track_class = Self::track_class_name(&class_id, track, i, tag_class_names);
+            if !Self::is_valid_class_name(&track_class) {
+                issues.push(TrackNameIssue {
+                    track_index: i,
+                    message: format!("轨道 {}「{}」的类名 \"{}\" 包含 Arma 不支持的字符", i + 1, track.track_name, track_class),
+                    fixable: false,
+                });
+            }
+            if let Some(&first) = class_names.get(&track_class) {
+                issues.push(TrackNameIssue {
+                    track_index: i,
+                    message: format!("轨道 {} 与轨道 {} 的类名 \"{}\" 重复", i + 1, first + 1, track_class),
+                    fixable: false,
+                });
+            } else {
+                class_names.insert(track_class, i);
+            }
+
+            let export_name = track.export_name(use_tags);
+            let unsupported = StringUtils::untransliterable_chars(&export_name);
+            if !unsupported.is_empty() {
+                issues.push(TrackNameIssue {
+                    track_index: i,
+                    message: format!(
+                        "轨道 {}「{}」包含无法转写的字符 {}，拼音名称中将替换为下划线",
+                        i + 1,
+                        track.track_name,
+                        unsupported.iter().collect::<String>()
+                    ),
+                    fixable: true,
+                });
+            }
+
+            let display_name = StringUtils::to_ascii_safe_pinyin(&export_name);
+            match display_names.get(&display_name) {
+                Some(&first) if tracks[first].export_name(use_tags) != export_name => {
+                    issues.push(TrackNameIssue {
+                        track_index: i,
+                        message: format!(
+                            "轨道 {}「{}」与轨道 {}「{}」转写后同名 \"{}\"",
+                            i + 1,
+                            track.track_name,
+                            first + 1,
+                            tracks[first].track_name,
+                            display_name
+                        ),
+                        fixable: true,
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    display_names.insert(display_name, i);
+                }
+            }
+        }
+
+        issues
+    }
+
+    /// 渲染config.cpp模板（不写入文件）
+    pub fn render_config(&self, data: &ConfigTemplateData) -> Result<String> {
+        self.handlebars
+            .render("config", data)
+            .context("渲染config模板失败")
+    }
+
+    /// 渲染单个轨道模板（不写入文件）
+    pub fn render_track(&self, data: &TrackTemplateData) -> Result<String> {
+        self.handlebars
+            .render("track", data)
+            .context("渲染track模板失败")
+    }
+
+    /// 渲染音乐模组的config.cpp内容（不写入文件）
+    fn render_config_cpp(&self, project: &ProjectSettings, use_stringtable: bool) -> Result<String> {
         let prefix = localization_prefix(&project.class_name);
         let music_class_name = if use_stringtable {
             str_reference(&key_music_class(&prefix))
@@ -131,10 +268,17 @@ impl TemplateEngine {
             music_class_name,
         };
 
-        let content = self
-            .handlebars
-            .render("config", &data)
-            .context("渲染config模板失败")?;
+        self.render_config(&data)
+    }
+
+    /// 生成config.cpp文件
+    pub fn generate_config_cpp(
+        &self,
+        project: &ProjectSettings,
+        use_stringtable: bool,
+        output_path: &Path,
+    ) -> Result<()> {
+        let content = self.render_config_cpp(project, use_stringtable)?;
 
         Self::write_arma_config_file(output_path, &content)?;
         debug!("生成config.cpp: {:?}", output_path);
@@ -163,39 +307,62 @@ impl TemplateEngine {
         Ok(())
     }
 
-    /// 生成FileListWithMusicTracks.hpp文件
-    pub fn generate_tracks_hpp(
+    /// 渲染meta.cpp内容（Steam 创意工坊上传使用）
+    ///
+    /// 没有物品ID时省略 `publishedid` 行，上传工具不接受 `publishedid = 0`。
+    pub fn render_meta_cpp(project: &ProjectSettings, published_id: Option<u64>) -> String {
+        let quote = |value: &str| format!("\"{}\"", value.replace('"', "\"\""));
+        let mut content = String::from("protocol = 1;\n");
+        if let Some(id) = published_id.filter(|id| *id != 0) {
+            content.push_str(&format!("publishedid = {};\n", id));
+        }
+        content.push_str(&format!("name = {};\n", quote(&Self::mod_display_name(project, false))));
+        let tags: Vec<String> = project
+            .workshop_tags
+            .iter()
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+            .map(quote)
+            .collect();
+        content.push_str(&format!("tags[] = {{{}}};\n", tags.join(", ")));
+        content
+    }
+
+    /// 生成meta.cpp文件
+    pub fn generate_meta_cpp(&self, project: &ProjectSettings, published_id: Option<u64>, output_path: &Path) -> Result<()> {
+        let content = Self::render_meta_cpp(project, published_id);
+
+        Self::write_arma_config_file(output_path, &content)?;
+        debug!("生成meta.cpp: {:?}", output_path);
+        Ok(())
+    }
+
+    /// 渲染FileListWithMusicTracks.hpp内容（不写入文件）
+    fn render_tracks_hpp(
         &self,
         project: &ProjectSettings,
         tracks: &[Track],
         copied_files: &[String],
         use_tags: bool,
+        tag_class_names: bool,
         use_stringtable: bool,
-        output_path: &Path,
-    ) -> Result<()> {
+    ) -> Result<String> {
         let prefix = localization_prefix(&project.class_name);
         let class_id = Self::music_class_identifier(project);
+        let mod_folder = project.mod_name_no_spaces();
+        let tracks_folder = project.tracks_folder();
         let mut content = String::new();
 
         for (i, track) in tracks.iter().enumerate() {
             let track_name = if use_stringtable {
                 str_reference(&key_track(&prefix, i))
             } else {
-                let display = if use_tags && !track.tag.is_empty() {
-                    format!("[{}] {}", track.tag, track.track_name)
-                } else {
-                    track.track_name.clone()
-                };
-                crate::utils::string_utils::StringUtils::to_ascii_safe_pinyin(&display)
+                crate::utils::string_utils::StringUtils::to_ascii_safe_pinyin(&track.export_name(use_tags))
             };
 
-            let track_class = format!("{}Song{}", class_id, i);
+            let track_class = Self::track_class_name(&class_id, track, i, tag_class_names);
             let filename = copied_files.get(i).map(|s| s.as_str()).unwrap_or("track.ogg");
-            let track_path = format!(
-                "{}\\folderwithtracks\\{}",
-                project.mod_name_no_spaces(),
-                filename
-            );
+            let track_path = format!("{}\\{}\\{}", mod_folder, tracks_folder, filename);
             let decibels = if track.decibels >= 0 {
                 format!("+{}", track.decibels)
             } else {
@@ -211,15 +378,35 @@ impl TemplateEngine {
                 class_name: class_id.clone(),
             };
 
-            let track_content = self
-                .handlebars
-                .render("track", &data)
-                .context("渲染track模板失败")?;
+            let track_content = self.render_track(&data)?;
 
             content.push_str(&track_content);
             content.push('\n');
         }
 
+        Ok(content)
+    }
+
+    /// 生成FileListWithMusicTracks.hpp文件
+    pub fn generate_tracks_hpp(
+        &self,
+        project: &ProjectSettings,
+        tracks: &[Track],
+        copied_files: &[String],
+        use_tags: bool,
+        tag_class_names: bool,
+        use_stringtable: bool,
+        output_path: &Path,
+    ) -> Result<()> {
+        let content = self.render_tracks_hpp(
+            project,
+            tracks,
+            copied_files,
+            use_tags,
+            tag_class_names,
+            use_stringtable,
+        )?;
+
         Self::write_arma_config_file(output_path, &content)?;
         debug!("生成FileListWithMusicTracks.hpp: {:?}", output_path);
         Ok(())
@@ -280,6 +467,40 @@ impl TemplateEngine {
         Ok(())
     }
 
+    /// 预览导出时生成的 config.cpp 与 CfgMusic 轨道列表（FileListWithMusicTracks.hpp）
+    ///
+    /// 文件名按 `copy_track_files_pinyin` 导出到空目录时的命名规则推算，
+    /// 渲染失败时返回错误描述而不是空文本。
+    pub fn render_cfg_music_preview(
+        &self,
+        project: &ProjectSettings,
+        tracks: &[Track],
+        naming: &crate::models::NamingScheme,
+        append_tags: bool,
+        tag_class_names: bool,
+        use_stringtable: bool,
+    ) -> String {
+        let render = || -> Result<String> {
+            let filenames = crate::file_ops::FileOperations::preview_track_filenames(project, tracks, naming, tag_class_names);
+            let config = self.render_config_cpp(project, use_stringtable)?;
+            let track_list = self.render_tracks_hpp(
+                project,
+                tracks,
+                &filenames,
+                append_tags,
+                tag_class_names,
+                use_stringtable,
+            )?;
+            Ok(format!(
+                "// ===== config.cpp =====\n{}\n\n// ===== FileListWithMusicTracks.hpp =====\n{}",
+                config.trim_end(),
+                track_list
+            ))
+        };
+
+        render().unwrap_or_else(|e| format!("生成配置预览失败: {:#}", e))
+    }
+
     /// 生成所有配置文件
     pub fn generate_all_configs(
         &self,
@@ -287,6 +508,7 @@ impl TemplateEngine {
         tracks: &[Track],
         copied_files: &[String],
         use_tags: bool,
+        tag_class_names: bool,
         use_stringtable: bool,
         use_google_translate: bool,
         mod_dir: &Path,
@@ -315,6 +537,7 @@ impl TemplateEngine {
                     tracks,
                     copied_files,
                     use_tags,
+                    tag_class_names,
                     use_stringtable,
                     &tracks_path,
                 )?;
@@ -328,27 +551,36 @@ impl TemplateEngine {
             }
         }
 
+        self.generate_meta_cpp(project, project.workshop_id, &mod_dir.join("meta.cpp"))?;
+
         info!("生成所有配置文件完成");
         Ok(())
     }
 }
 
-impl Default for TemplateEngine {
-    fn default() -> Self {
-        Self::new().unwrap_or_else(|e| {
-            eprintln!("警告: 无法创建模板引擎: {}", e);
-            Self {
-                handlebars: Handlebars::new(),
-            }
-        })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::ProjectSettings;
 
+    #[test]
+    fn test_render_meta_cpp_omits_missing_published_id() {
+        let mut project = ProjectSettings {
+            mod_name: "Squad Music".to_string(),
+            ..Default::default()
+        };
+        project.workshop_tags = vec!["Sound".to_string(), "Zeus".to_string(), " ".to_string()];
+
+        let meta = TemplateEngine::render_meta_cpp(&project, None);
+        assert!(!meta.contains("publishedid"));
+        assert!(meta.contains("name = \"Squad Music\";"));
+        assert!(meta.contains("tags[] = {\"Sound\", \"Zeus\"};"));
+        assert!(!TemplateEngine::render_meta_cpp(&project, Some(0)).contains("publishedid"));
+
+        let meta = TemplateEngine::render_meta_cpp(&project, Some(1234567890));
+        assert!(meta.contains("publishedid = 1234567890;"));
+    }
+
     #[test]
     fn test_template_engine_creation() {
         let engine = TemplateEngine::new();
@@ -367,4 +599,115 @@ mod tests {
         };
         assert!(!data.mod_name.is_empty());
     }
+
+    #[test]
+    fn test_track_class_name_with_tags() {
+        let mut track = Track::new("a.ogg".into(), "Main Theme".to_string(), "MyMod".to_string());
+        assert_eq!(TemplateEngine::track_class_name("MyMod", &track, 0, true), "MyModSong0");
+
+        track.tag = "Combat".to_string();
+        assert_eq!(TemplateEngine::track_class_name("MyMod", &track, 3, false), "MyModSong3");
+        assert_eq!(TemplateEngine::track_class_name("MyMod", &track, 3, true), "Combat_MyModSong3");
+
+        track.tag = "1st wave!".to_string();
+        assert_eq!(TemplateEngine::track_class_name("MyMod", &track, 1, true), "T1st_wave_MyModSong1");
+    }
+
+    #[test]
+    fn test_check_track_names_flags_transliteration_collisions() {
+        let project = ProjectSettings::default();
+        let tracks: Vec<Track> = ["战斗", "站斗", "Main Theme", "Main Theme", "☃"]
+            .iter()
+            .map(|name| Track::new("a.ogg".into(), name.to_string(), project.class_name.clone()))
+            .collect();
+
+        let issues = TemplateEngine::check_track_names(&project, &tracks, false, false);
+        let flagged: Vec<usize> = issues.iter().map(|issue| issue.track_index).collect();
+        // 不同中文标题转写为同一拼音时提示；完全相同的名称不算冲突
+        assert_eq!(flagged, vec![1, 4]);
+        assert!(issues.iter().all(|issue| issue.fixable));
+        assert!(issues[1].message.contains('☃'));
+    }
+
+    #[test]
+    fn test_cfg_music_preview_reflects_append_tags_and_filenames() {
+        let dir = std::env::temp_dir().join(format!("zmm_cfg_preview_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("source.ogg");
+        std::fs::write(&source, b"ogg").unwrap();
+
+        let project = ProjectSettings::default();
+        let mut track = Track::new(source, "Main Theme".to_string(), project.class_name.clone());
+        track.tag = "Combat".to_string();
+        let tracks = vec![track];
+        let engine = TemplateEngine::new().unwrap();
+
+        let naming = crate::models::NamingScheme::default();
+        let filename = crate::file_ops::FileOperations::preview_track_filenames(&project, &tracks, &naming, false)[0].clone();
+        let plain = engine.render_cfg_music_preview(&project, &tracks, &naming, false, false, false);
+        let tagged = engine.render_cfg_music_preview(&project, &tracks, &naming, true, false, false);
+
+        assert!(plain.contains("config.cpp"));
+        assert!(plain.contains(&filename));
+        assert_ne!(plain, tagged);
+        assert!(tagged.contains("Combat"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_duplicated_tracks_get_unique_class_names() {
+        let project = ProjectSettings::default();
+        let original = Track::new("a.ogg".into(), "Intro".to_string(), project.class_name.clone());
+        let mut copy = original.clone();
+        copy.track_name.push_str(" (副本)");
+        let tracks = vec![original, copy];
+        let files = vec!["Intro.ogg".to_string(), "Intro_fu_ben.ogg".to_string()];
+
+        let engine = TemplateEngine::new().unwrap();
+        let content = engine
+            .render_tracks_hpp(&project, &tracks, &files, false, false, false)
+            .unwrap();
+        let class_id = TemplateEngine::music_class_identifier(&project);
+        let folder = format!("{}\\{}", project.mod_name_no_spaces(), project.tracks_folder());
+        let lines_starting = |prefix: &str| -> Vec<String> {
+            content.lines().map(str::trim).filter(|line| line.starts_with(prefix)).map(str::to_string).collect()
+        };
+
+        // 每个轨道各有一个类，分别指向自己的文件
+        assert_eq!(
+            lines_starting("class "),
+            vec![format!("class {}Song0", class_id), format!("class {}Song1", class_id)]
+        );
+        assert_eq!(
+            lines_starting("sound[]"),
+            vec![
+                format!("sound[] = {{\"{}\\Intro.ogg\", db+0, 1}};", folder),
+                format!("sound[] = {{\"{}\\Intro_fu_ben.ogg\", db+0, 1}};", folder),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sqf_snippets_match_generated_class_names() {
+        let project = ProjectSettings::default();
+        let mut tagged = Track::new("b.ogg".into(), "Battle".to_string(), project.class_name.clone());
+        tagged.tag = "Combat".to_string();
+        let tracks = vec![
+            Track::new("a.ogg".into(), "Intro".to_string(), project.class_name.clone()),
+            tagged,
+        ];
+        let files = vec!["Intro.ogg".to_string(), "Battle.ogg".to_string()];
+
+        let engine = TemplateEngine::new().unwrap();
+        let hpp = engine.render_tracks_hpp(&project, &tracks, &files, false, true, false).unwrap();
+        let snippet = TemplateEngine::generate_sqf_snippets(&project, &tracks, true);
+        let class_id = TemplateEngine::music_class_identifier(&project);
+
+        let lines: Vec<&str> = snippet.lines().filter(|line| line.starts_with("playMusic")).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(&format!("playMusic \"{}Song0\";", class_id)));
+        assert!(lines[1].starts_with(&format!("playMusic \"Combat_{}Song1\";", class_id)));
+        assert!(hpp.contains(&format!("Combat_{}Song1", class_id)));
+    }
 }
@@ -1,17 +1,25 @@
 use anyhow::Result;
-use crossbeam_channel::{bounded, Receiver, Sender};
+use crossbeam_channel::bounded;
 use log::{info, warn};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use crate::audio_decrypt::AudioDecryptManager;
+use crate::file_ops::FileOperations;
 use crate::paa_converter::{PaaConverter, PaaOptions};
 use crate::audio_converter::AudioConverter;
-use crate::video_converter::VideoConverter;
+use crate::models::{AudioConvertOptions, VideoConvertOptions};
+use crate::video_converter::{VideoConverter, VideoFormat};
 use crate::ffmpeg_downloader::FFmpegDownloader;
-use crate::parallel_converter::{ParallelConverter, ParallelConfig, ProgressUpdate};
+use crate::http_client::HttpSettings;
+use crate::audio::{AudioChapter, AudioProcessor};
+use crate::track_similarity::{self, SimilarTrackGroup, TrackSample};
+use crate::parallel_converter::{cancellation_note, is_cancelled, reset_cancelled, wait_while_paused, ParallelConverter, ParallelConfig, ProgressUpdate};
+use crate::progress_channel::{coalescing_channel, Coalesce, CoalescingReceiver, CoalescingSender};
 use crate::video_chunk_parallel_processor::{VideoChunkParallelProcessor, ChunkProgressUpdate};
 use crate::video_chunk_converter::VideoChunkConfig;
+use crate::resource_manager::SmartThreadPool;
 
 /// 任务消息
 #[derive(Debug, Clone)]
@@ -27,10 +35,19 @@ pub enum TaskMessage {
         error_count: usize,
         results: Vec<String>,
     },
+    /// 单个文件的转换进度（0.0-1.0，来自 FFmpeg 进度输出）
+    FileProgress {
+        task_id: usize,
+        fraction: f32,
+    },
     /// FFmpeg下载进度更新
     FFmpegDownloadProgress {
         progress: f64,
         status: String,
+        /// 已下载字节数
+        downloaded_bytes: u64,
+        /// 文件总大小（未知时为 None）
+        total_bytes: Option<u64>,
     },
     /// FFmpeg下载完成
     FFmpegDownloadCompleted {
@@ -41,69 +58,623 @@ pub enum TaskMessage {
     ParallelProgressUpdate(ProgressUpdate),
     /// 分片转换进度更新
     ChunkProgressUpdate(ChunkProgressUpdate),
+    /// 单个文件转换输出（用于会话转换历史）
+    OutputProduced {
+        input_path: PathBuf,
+        output_path: PathBuf,
+    },
+    /// 单个文件转换失败（用于重试失败项）
+    ConversionFailed {
+        input_path: PathBuf,
+    },
+    /// 单个文件处理失败（解密等不提供重试的任务，仅用于统计失败数量）
+    FileFailed,
+    /// 相似轨道分析完成
+    SimilarTracksFound {
+        groups: Vec<SimilarTrackGroup>,
+        /// 无法解码（仅按名称比较）的文件数
+        undecodable_count: usize,
+    },
+    /// 按章节分割完成
+    ChaptersSplit {
+        /// (输出文件路径, 章节标题)
+        outputs: Vec<(PathBuf, String)>,
+        /// 分割失败的章节说明
+        failed: Vec<String>,
+    },
+    /// 解密→转换流水线完成（添加轨道由主线程完成）
+    DecryptPipelineFinished {
+        files: Vec<PipelineFileResult>,
+        cancelled: bool,
+    },
+    /// 轨道时长测量完成
+    DurationsMeasured {
+        /// (发起测量时的轨道索引, 文件路径, 实际时长秒数)
+        durations: Vec<(usize, PathBuf, u32)>,
+        /// 无法测量的文件说明
+        failed: Vec<String>,
+    },
+    /// 后台读取了一个视频的时长（None 表示无法读取）
+    VideoDurationProbed {
+        path: PathBuf,
+        duration: Option<u32>,
+    },
+    /// 从文件夹导入完成（扫描、过滤和加载由后台完成，添加轨道由主线程完成）
+    FolderImported {
+        result: Result<FolderImportResult, String>,
+    },
+    /// 单个新导入轨道的后台补全完成
+    TrackImportExtrasLoaded {
+        extras: crate::models::TrackImportExtras,
+    },
+    /// 单个轨道的内嵌封面检查完成
+    CoverArtProbed {
+        path: PathBuf,
+        has_cover: bool,
+    },
+    /// 单个文件的章节读取完成
+    ChaptersRead {
+        path: PathBuf,
+        chapters: Result<Vec<AudioChapter>, String>,
+    },
+    /// 单个文件的波形分析完成
+    WaveformComputed {
+        path: PathBuf,
+        waveform: Result<crate::audio::Waveform, String>,
+    },
+    /// 为 Zeus 准备的轨道检查完成（修复和导出由主线程完成）
+    ZeusTracksProbed {
+        probes: Vec<crate::models::ZeusTrackProbe>,
+    },
+    /// 模组导出结束（成功、失败或取消）
+    ModExportFinished {
+        outcome: crate::models::ModExportOutcome,
+    },
+    /// 单个轨道的音量分析完成
+    LoudnessMeasured {
+        /// 发起分析时的轨道索引
+        track_index: usize,
+        path: PathBuf,
+        /// 综合响度（LUFS）或错误说明
+        result: Result<f32, String>,
+    },
 }
 
+/// 从文件夹导入的结果
+#[derive(Debug, Clone)]
+pub struct FolderImportResult {
+    /// 通过过滤并加载成功的轨道
+    pub tracks: Vec<crate::models::Track>,
+    /// 扫描和过滤结果
+    pub scan: crate::import_filter::ImportScan,
+}
+
+/// 解密→转换→添加流水线中单个文件的结果
+#[derive(Debug, Clone)]
+pub struct PipelineFileResult {
+    /// 源文件名
+    pub filename: String,
+    /// 各阶段结果描述（按执行顺序）
+    pub stages: Vec<String>,
+    /// 转换得到的 OGG 文件（任一阶段失败时为 None）
+    pub output: Option<PathBuf>,
+}
+
+impl Coalesce for TaskMessage {
+    /// 高频进度消息可合并：整体进度、下载进度各保留一条，单文件进度和并行转换进度按任务保留一条
+    fn coalesce_key(&self) -> Option<(u8, usize)> {
+        match self {
+            TaskMessage::UpdateProgress { .. } => Some((0, 0)),
+            TaskMessage::FFmpegDownloadProgress { .. } => Some((1, 0)),
+            TaskMessage::FileProgress { task_id, .. } => Some((2, *task_id)),
+            TaskMessage::ParallelProgressUpdate(update) => update.coalesce_key().map(|(kind, task_id)| (3 + kind, task_id)),
+            _ => None,
+        }
+    }
+}
+
+/// 任务消息通道的健康状况
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChannelHealth {
+    /// 通道中等待界面处理的消息数
+    pub queued: usize,
+    /// 通道容量
+    pub capacity: usize,
+    /// 本次任务中因界面处理滞后而被合并的进度消息数
+    pub coalesced: usize,
+}
+
+impl ChannelHealth {
+    /// 界面处理是否明显滞后于工作线程
+    pub fn is_lagging(&self) -> bool {
+        self.coalesced > 0 || self.queued * 2 > self.capacity
+    }
+}
+
+/// 任务消息发送器（进度消息在通道已满时合并，完成类消息不会丢失）
+pub type TaskSender = CoalescingSender<TaskMessage>;
+
+/// 并行解密统计信息
+#[derive(Debug, Default)]
+struct DecryptStats {
+    completed: usize,
+    success_count: usize,
+    error_count: usize,
+    /// (原始文件索引, 结果描述)
+    results: Vec<(usize, String)>,
+    /// 按格式整理时各子文件夹的文件数
+    format_counts: std::collections::BTreeMap<String, usize>,
+}
+
+/// 任务消息通道容量
+const TASK_CHANNEL_CAPACITY: usize = 5000;
+
 /// 多线程任务处理器
 pub struct ThreadedTaskProcessor {
     /// 进度更新发送器
-    progress_sender: Sender<TaskMessage>,
+    progress_sender: TaskSender,
     /// 进度更新接收器
-    progress_receiver: Receiver<TaskMessage>,
+    progress_receiver: CoalescingReceiver<TaskMessage>,
     /// 取消标志
     cancel_flag: Arc<Mutex<bool>>,
+    /// FFmpeg 下载的取消标志（下载与其他任务互不影响，取消结果由 FFmpegDownloadCompleted 报告）
+    download_cancel_flag: Arc<Mutex<bool>>,
+    /// 暂停标志（并行转换的工作线程在领取下一个文件前检查）
+    pause_flag: Arc<AtomicBool>,
     /// 并行转换器
     parallel_converter: Option<ParallelConverter>,
+    /// 当前任务主线程句柄（用于检测线程意外退出）
+    worker_handle: Mutex<Option<thread::JoinHandle<()>>>,
+    /// 用户设置的并行转换最大线程数（None 表示自动）
+    max_threads_override: Option<usize>,
 }
 
 impl ThreadedTaskProcessor {
     pub fn new() -> Self {
         // 增大通道缓冲区以提高并发性能
-        let (progress_sender, progress_receiver) = bounded(5000);
+        let (progress_sender, progress_receiver) = coalescing_channel(TASK_CHANNEL_CAPACITY);
         Self {
             progress_sender,
             progress_receiver,
             cancel_flag: Arc::new(Mutex::new(false)),
+            download_cancel_flag: Arc::new(Mutex::new(false)),
+            pause_flag: Arc::new(AtomicBool::new(false)),
             parallel_converter: None,
+            worker_handle: Mutex::new(None),
+            max_threads_override: None,
+        }
+    }
+
+    /// 启动任务主线程并记录句柄
+    fn spawn_task<F>(&self, task: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let handle = thread::spawn(task);
+        match self.worker_handle.lock() {
+            Ok(mut worker) => *worker = Some(handle),
+            Err(e) => warn!("记录任务线程句柄失败: {}", e),
+        }
+    }
+
+    /// 任务主线程是否已结束（没有记录的线程时返回 false）
+    pub fn worker_finished(&self) -> bool {
+        self.worker_handle
+            .lock()
+            .map(|worker| worker.as_ref().is_some_and(|handle| handle.is_finished()))
+            .unwrap_or(false)
+    }
+
+    /// 回收已结束的任务主线程，返回退出结果（线程 panic 时为 panic 信息）
+    pub fn take_finished_worker(&self) -> Option<std::result::Result<(), String>> {
+        let mut worker = self.worker_handle.lock().ok()?;
+        if !worker.as_ref()?.is_finished() {
+            return None;
         }
+        let handle = worker.take()?;
+        Some(handle.join().map_err(|panic| {
+            panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "未知错误".to_string())
+        }))
     }
 
-    /// 处理音频解密任务
+    /// 处理音频解密任务（多线程并行，限制最大并发数）
     pub fn process_audio_decrypt(
         &self,
         files: Vec<PathBuf>,
         output_dir: PathBuf,
+        max_concurrency: usize,
+        organize_by_format: bool,
     ) -> Result<()> {
         let progress_sender = self.progress_sender.clone();
         let cancel_flag = self.cancel_flag.clone();
 
-        thread::spawn(move || {
-            let mut success_count = 0;
-            let mut error_count = 0;
-            let mut results = Vec::new();
+        self.spawn_task(move || {
+            let total_files = files.len();
+            let worker_count = max_concurrency.max(1).min(total_files.max(1));
+            info!("开始并行音频解密，文件数: {}, 并发数: {}", total_files, worker_count);
+
+            // 任务队列
+            let (task_sender, task_receiver) = bounded::<(usize, PathBuf)>(total_files.max(1));
+            for task in files.into_iter().enumerate() {
+                if let Err(e) = task_sender.send(task) {
+                    warn!("发送解密任务到队列失败: {}", e);
+                }
+            }
+            drop(task_sender);
+
+            let thread_pool = Arc::new(SmartThreadPool::new(worker_count));
+            let stats = Arc::new(Mutex::new(DecryptStats::default()));
+            let output_dir = Arc::new(output_dir);
+
+            let mut handles = Vec::with_capacity(worker_count);
+            for worker_id in 0..worker_count {
+                let task_receiver = task_receiver.clone();
+                let progress_sender = progress_sender.clone();
+                let cancel_flag = cancel_flag.clone();
+                let thread_pool = thread_pool.clone();
+                let stats = stats.clone();
+                let output_dir = output_dir.clone();
+
+                handles.push(thread::spawn(move || {
+                    thread_pool.thread_start(worker_id);
+                    let worker_start = std::time::Instant::now();
 
+                    while let Ok((index, input_path)) = task_receiver.recv() {
+                        // 检查取消标志
+                        if is_cancelled(&cancel_flag) {
+                            info!("解密工作线程 {} 收到取消信号", worker_id);
+                            break;
+                        }
+
+                        let filename = input_path
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                            .to_string();
+
+                        let cancel_check = || is_cancelled(&cancel_flag);
+                        let mut format_folder = None;
+                        let result = match Self::decrypt_single_file(&input_path, &output_dir, &filename, &cancel_check) {
+                            Ok((source, output_path)) => {
+                                // 按格式整理到子文件夹
+                                let output_path = if organize_by_format {
+                                    match FileOperations::organize_by_format(&output_path, &output_dir) {
+                                        Ok((organized_path, folder)) => {
+                                            format_folder = Some(folder);
+                                            organized_path
+                                        }
+                                        Err(e) => {
+                                            warn!("按格式整理输出失败: {:?} - {}", output_path, e);
+                                            output_path
+                                        }
+                                    }
+                                } else {
+                                    output_path
+                                };
+                                let _ = progress_sender.send(TaskMessage::OutputProduced {
+                                    input_path: input_path.clone(),
+                                    output_path: output_path.clone(),
+                                });
+                                let relative = output_path
+                                    .strip_prefix(output_dir.as_path())
+                                    .unwrap_or(&output_path)
+                                    .to_string_lossy()
+                                    .replace('\\', "/");
+                                Ok(format!("{}: {} -> {}", source, filename, relative))
+                            }
+                            Err(msg) => {
+                                let _ = progress_sender.send(TaskMessage::FileFailed);
+                                Err(msg)
+                            }
+                        };
+
+                        // 汇总各工作线程的进度
+                        let completed = {
+                            let mut stats_guard = stats.lock().unwrap_or_else(|e| {
+                                warn!("解密统计Mutex poisoned: {:?}，使用默认值", e);
+                                e.into_inner()
+                            });
+                            match &result {
+                                Ok(_) => stats_guard.success_count += 1,
+                                Err(_) => stats_guard.error_count += 1,
+                            }
+                            stats_guard.completed += 1;
+                            if let Some(folder) = format_folder {
+                                *stats_guard.format_counts.entry(folder).or_insert(0) += 1;
+                            }
+                            stats_guard.results.push((index, result.unwrap_or_else(|msg| msg)));
+                            stats_guard.completed
+                        };
+
+                        if let Err(e) = progress_sender.send(TaskMessage::UpdateProgress {
+                            current_file: completed,
+                            filename,
+                        }) {
+                            warn!("发送进度更新失败: {}", e);
+                        }
+                    }
+
+                    thread_pool.thread_finish(worker_id, worker_start.elapsed());
+                }));
+            }
+
+            for handle in handles {
+                if let Err(e) = handle.join() {
+                    warn!("解密工作线程异常退出: {:?}", e);
+                }
+            }
+
+            let cancelled = is_cancelled(&cancel_flag);
+            let mut stats = match Arc::try_unwrap(stats) {
+                Ok(stats) => stats.into_inner().unwrap_or_else(|e| e.into_inner()),
+                Err(stats) => std::mem::take(&mut *stats.lock().unwrap_or_else(|e| e.into_inner())),
+            };
+
+            // 按原始文件顺序输出结果
+            stats.results.sort_by_key(|(index, _)| *index);
+            let mut results: Vec<String> = stats.results.into_iter().map(|(_, msg)| msg).collect();
+
+            // 取消时保留已完成文件的结果，便于确认哪些输出已生成
+            if cancelled {
+                info!("音频解密任务被取消");
+                results.push(cancellation_note(&cancel_flag));
+                let _ = progress_sender.send(TaskMessage::TaskCompleted {
+                    success_count: stats.success_count,
+                    error_count: stats.error_count,
+                    results,
+                });
+                return;
+            }
+
+            // 报告按格式整理后的目录结构
+            if organize_by_format && !stats.format_counts.is_empty() {
+                let layout = stats.format_counts
+                    .iter()
+                    .map(|(folder, count)| format!("{}/ ({} 个)", folder, count))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                results.insert(0, format!("输出目录结构: {}\n", layout));
+            }
+
+            // 发送完成消息
+            if let Err(e) = progress_sender.send(TaskMessage::TaskCompleted {
+                success_count: stats.success_count,
+                error_count: stats.error_count,
+                results,
+            }) {
+                warn!("发送任务完成消息失败: {}", e);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// 解密单个文件，成功时返回 (来源名称, 输出文件路径)，失败时返回错误描述
+    fn decrypt_single_file<F>(
+        input_path: &std::path::Path,
+        output_dir: &std::path::Path,
+        filename: &str,
+        cancel_check: &F,
+    ) -> std::result::Result<(&'static str, PathBuf), String>
+    where
+        F: Fn() -> bool,
+    {
+        if AudioDecryptManager::is_kugou_file(input_path) {
+            AudioDecryptManager::decrypt_kugou_file_with_cancel(input_path, output_dir, cancel_check)
+                .map(|output_path| ("酷狗", PathBuf::from(output_path)))
+                .map_err(|e| format!("酷狗: {} - {}", filename, e))
+        } else if AudioDecryptManager::is_qqmusic_file(input_path) {
+            AudioDecryptManager::decrypt_qqmusic_file(input_path, output_dir, cancel_check)
+                .map(|output_path| ("QQ音乐", PathBuf::from(output_path)))
+                .map_err(|e| format!("QQ音乐: {} - {}", filename, e))
+        } else if AudioDecryptManager::is_netease_file(input_path) {
+            AudioDecryptManager::decrypt_netease_file(input_path, output_dir)
+                .map(|output_path| ("网易云", PathBuf::from(output_path)))
+                .map_err(|e| format!("网易云: {} - {}", filename, e))
+        } else {
+            Err(format!("不支持: {} - 不支持的音频格式", filename))
+        }
+    }
+
+    /// 解密加密音频并重新编码为 Arma 兼容的 OGG（逐个文件依次执行两个阶段）
+    pub fn process_decrypt_pipeline(&self, files: Vec<PathBuf>, output_dir: PathBuf, options: AudioConvertOptions) -> Result<()> {
+        let progress_sender = self.progress_sender.clone();
+        let cancel_flag = self.cancel_flag.clone();
+
+        self.spawn_task(move || {
+            let converter = match AudioConverter::new() {
+                Ok(conv) => Some(conv),
+                Err(e) => {
+                    warn!("FFmpeg 未找到: {}", e);
+                    None
+                }
+            };
+            let should_cancel = || is_cancelled(&cancel_flag);
+
+            let mut results = Vec::with_capacity(files.len());
+            let mut cancelled = false;
             for (i, input_path) in files.iter().enumerate() {
+                if should_cancel() {
+                    info!("解密流水线任务被取消");
+                    cancelled = true;
+                    break;
+                }
+
+                let filename = input_path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let mut result = PipelineFileResult {
+                    filename: filename.clone(),
+                    stages: Vec::new(),
+                    output: None,
+                };
+
+                // 阶段一：解密
+                let _ = progress_sender.send(TaskMessage::UpdateProgress {
+                    current_file: i,
+                    filename: format!("{}（解密中）", filename),
+                });
+                let decrypted = match Self::decrypt_single_file(input_path, &output_dir, &filename, &should_cancel) {
+                    Ok((source, decrypted)) => {
+                        result.stages.push(format!("解密 ✓ ({})", source));
+                        decrypted
+                    }
+                    Err(msg) => {
+                        result.stages.push(format!("解密 ✗ {}", msg));
+                        results.push(result);
+                        let _ = progress_sender.send(TaskMessage::FileFailed);
+                        continue;
+                    }
+                };
+
+                // 阶段二：重新编码为 OGG
+                let Some(converter) = converter.as_ref() else {
+                    result.stages.push("转换 ✗ FFmpeg 未找到，请使用软件的自动下载功能或手动安装 FFmpeg".to_string());
+                    results.push(result);
+                    let _ = progress_sender.send(TaskMessage::FileFailed);
+                    continue;
+                };
+                if should_cancel() {
+                    result.stages.push("转换 ✗ 任务被用户取消".to_string());
+                    results.push(result);
+                    cancelled = true;
+                    break;
+                }
+                let _ = progress_sender.send(TaskMessage::UpdateProgress {
+                    current_file: i,
+                    filename: format!("{}（转换中）", filename),
+                });
+                let pinyin_filename = crate::utils::string_utils::StringUtils::safe_filename_pinyin(
+                    &decrypted.file_stem().unwrap_or_default().to_string_lossy(),
+                    i,
+                );
+                let output_path = crate::utils::string_utils::StringUtils::ensure_unique_path(
+                    output_dir.join(format!("{}.ogg", pinyin_filename)),
+                );
+                match converter.convert_to_ogg_with_cancel(&decrypted, &output_path, options, &should_cancel) {
+                    Ok(_) => {
+                        result.stages.push("转换 ✓".to_string());
+                        let _ = progress_sender.send(TaskMessage::OutputProduced {
+                            input_path: input_path.clone(),
+                            output_path: output_path.clone(),
+                        });
+                        result.output = Some(output_path);
+                    }
+                    Err(e) => {
+                        warn!("流水线转换失败: {:?} - {}", decrypted, e);
+                        result.stages.push(format!("转换 ✗ {}", e));
+                        let _ = progress_sender.send(TaskMessage::FileFailed);
+                    }
+                }
+                results.push(result);
+
+                let _ = progress_sender.send(TaskMessage::UpdateProgress {
+                    current_file: i + 1,
+                    filename,
+                });
+            }
+
+            info!(
+                "解密流水线完成: {} 个文件，{} 个已转换",
+                results.len(),
+                results.iter().filter(|r| r.output.is_some()).count()
+            );
+            if let Err(e) = progress_sender.send(TaskMessage::DecryptPipelineFinished { files: results, cancelled }) {
+                warn!("发送解密流水线结果失败: {}", e);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// 分析疑似重复的轨道（名称相似度 + 音频指纹）
+    pub fn process_similar_track_scan(&self, tracks: Vec<(PathBuf, String, u32)>) -> Result<()> {
+        let progress_sender = self.progress_sender.clone();
+        let cancel_flag = self.cancel_flag.clone();
+
+        self.spawn_task(move || {
+            let mut samples = Vec::with_capacity(tracks.len());
+            let mut undecodable_count = 0;
+
+            for (i, (path, track_name, duration)) in tracks.into_iter().enumerate() {
                 // 检查取消标志
-                if *cancel_flag.lock().unwrap_or_else(|_| {
-                    warn!("获取取消标志失败，假设任务被取消");
-                    panic!("Mutex poisoned, cannot continue")
+                if is_cancelled(&cancel_flag) {
+                    info!("相似轨道分析任务被取消");
+                    let _ = progress_sender.send(TaskMessage::TaskCompleted {
+                        success_count: i,
+                        error_count: 0,
+                        results: vec![cancellation_note(&cancel_flag)],
+                    });
+                    return;
+                }
+
+                // 发送进度更新
+                let filename = path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                if let Err(e) = progress_sender.send(TaskMessage::UpdateProgress {
+                    current_file: i,
+                    filename,
                 }) {
-                    info!("音频解密任务被取消");
-                    // 立即发送取消完成消息
+                    warn!("发送进度更新失败: {}", e);
+                }
+
+                let fingerprint = match AudioProcessor::compute_fingerprint(&path) {
+                    Ok(fingerprint) => Some(fingerprint),
+                    Err(e) => {
+                        warn!("无法计算音频指纹，仅按名称比较: {:?} - {}", path, e);
+                        undecodable_count += 1;
+                        None
+                    }
+                };
+                samples.push(TrackSample { path, track_name, duration, fingerprint });
+            }
+
+            let groups = track_similarity::find_similar_groups(&samples);
+            info!("相似轨道分析完成: {} 组疑似重复", groups.len());
+            if let Err(e) = progress_sender.send(TaskMessage::SimilarTracksFound { groups, undecodable_count }) {
+                warn!("发送分析结果失败: {}", e);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// 解码每个轨道并测量实际时长
+    pub fn process_duration_sync(&self, tracks: Vec<(usize, PathBuf)>) -> Result<()> {
+        let progress_sender = self.progress_sender.clone();
+        let cancel_flag = self.cancel_flag.clone();
+
+        self.spawn_task(move || {
+            let mut durations = Vec::with_capacity(tracks.len());
+            let mut failed = Vec::new();
+
+            for (i, (track_index, path)) in tracks.into_iter().enumerate() {
+                // 检查取消标志
+                if is_cancelled(&cancel_flag) {
+                    info!("时长同步任务被取消");
                     let _ = progress_sender.send(TaskMessage::TaskCompleted {
-                        success_count,
-                        error_count,
-                        results: vec!["任务被用户取消".to_string()],
+                        success_count: i,
+                        error_count: 0,
+                        results: vec![cancellation_note(&cancel_flag)],
                     });
                     return;
                 }
 
                 // 发送进度更新
-                let filename = input_path
+                let filename = path
                     .file_name()
                     .unwrap_or_default()
                     .to_string_lossy()
                     .to_string();
-                
                 if let Err(e) = progress_sender.send(TaskMessage::UpdateProgress {
                     current_file: i,
                     filename: filename.clone(),
@@ -111,57 +682,347 @@ impl ThreadedTaskProcessor {
                     warn!("发送进度更新失败: {}", e);
                 }
 
-                // 处理文件
-                let cancel_check = || *cancel_flag.lock().unwrap_or_else(|_| {
-                    warn!("获取取消标志失败，假设任务被取消");
-                    panic!("Mutex poisoned, cannot continue")
-                });
-                let result = if AudioDecryptManager::is_kugou_file(input_path) {
-                    match AudioDecryptManager::decrypt_kugou_file_with_cancel(input_path, &output_dir, &cancel_check) {
-                        Ok(output_path) => {
-                            success_count += 1;
-                            Ok(format!("酷狗: {} -> {}", 
-                                filename,
-                                std::path::Path::new(&output_path).file_name().unwrap_or_default().to_string_lossy()
-                            ))
-                        }
-                        Err(e) => {
-                            error_count += 1;
-                            Err(format!("酷狗: {} - {}", filename, e))
-                        }
-                    }
-                } else if AudioDecryptManager::is_netease_file(input_path) {
-                    match AudioDecryptManager::decrypt_netease_file(input_path, &output_dir) {
-                        Ok(output_path) => {
-                            success_count += 1;
-                            Ok(format!("网易云: {} -> {}", 
-                                filename,
-                                std::path::Path::new(&output_path).file_name().unwrap_or_default().to_string_lossy()
-                            ))
-                        }
-                        Err(e) => {
-                            error_count += 1;
-                            Err(format!("网易云: {} - {}", filename, e))
-                        }
+                match AudioProcessor::measure_duration(&path) {
+                    Ok(duration) => durations.push((track_index, path, duration)),
+                    Err(e) => {
+                        warn!("无法测量音频时长: {:?} - {}", path, e);
+                        failed.push(format!("{}: {}", filename, e));
                     }
+                }
+            }
+
+            info!("时长测量完成: 成功 {} 个，失败 {} 个", durations.len(), failed.len());
+            if let Err(e) = progress_sender.send(TaskMessage::DurationsMeasured { durations, failed }) {
+                warn!("发送时长测量结果失败: {}", e);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// 在后台线程读取视频时长，每读取一个文件发回一条结果（用于预计分片数）
+    ///
+    /// 只读取文件信息，不占用任务，也不记录为任务主线程，可与其他任务同时进行。
+    pub fn probe_video_durations(&self, paths: Vec<PathBuf>) {
+        let progress_sender = self.progress_sender.clone();
+
+        thread::spawn(move || {
+            let converter = VideoConverter::new();
+            for path in paths {
+                let duration = match &converter {
+                    Ok(converter) => converter.probe_duration(&path).map_err(|e| {
+                        warn!("无法读取视频时长 {:?}: {}", path, e);
+                    }).ok(),
+                    Err(_) => None,
+                };
+                if progress_sender.send(TaskMessage::VideoDurationProbed { path, duration }).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// 在后台检查项目设置对话框请求的轨道是否带有内嵌封面
+    pub fn probe_cover_art(&self, paths: Vec<PathBuf>) {
+        let progress_sender = self.progress_sender.clone();
+
+        thread::spawn(move || {
+            for path in paths {
+                let has_cover = matches!(AudioProcessor::extract_cover_art(&path), Ok(Some(_)));
+                if progress_sender.send(TaskMessage::CoverArtProbed { path, has_cover }).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// 在后台读取按章节分割对话框请求的章节
+    pub fn read_chapters(&self, paths: Vec<PathBuf>) {
+        let progress_sender = self.progress_sender.clone();
+
+        thread::spawn(move || {
+            for path in paths {
+                let chapters = AudioProcessor::read_chapters(&path).map_err(|e| e.to_string());
+                if progress_sender.send(TaskMessage::ChaptersRead { path, chapters }).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// 在后台分析轨道编辑器请求的波形
+    pub fn compute_waveforms(&self, paths: Vec<PathBuf>) {
+        let progress_sender = self.progress_sender.clone();
+
+        thread::spawn(move || {
+            for path in paths {
+                let waveform = AudioProcessor::compute_waveform(&path).map_err(|e| e.to_string());
+                if progress_sender.send(TaskMessage::WaveformComputed { path, waveform }).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// 递归扫描文件夹、应用导入过滤并加载通过过滤的音频文件
+    pub fn process_folder_import(
+        &self,
+        dir: PathBuf,
+        filter: crate::import_filter::ImportFilter,
+        class_name: String,
+        defaults: crate::models::TrackDefaults,
+    ) -> Result<()> {
+        let progress_sender = self.progress_sender.clone();
+        let cancel_flag = self.cancel_flag.clone();
+
+        self.spawn_task(move || {
+            let _ = progress_sender.send(TaskMessage::UpdateProgress {
+                current_file: 0,
+                filename: dir.display().to_string(),
+            });
+
+            let scan = match filter.scan_folder(&dir) {
+                Ok(scan) => scan,
+                Err(e) => {
+                    let _ = progress_sender.send(TaskMessage::FolderImported { result: Err(format!("{:#}", e)) });
+                    return;
+                }
+            };
+            if is_cancelled(&cancel_flag) {
+                info!("从文件夹导入任务被取消");
+                let _ = progress_sender.send(TaskMessage::TaskCompleted {
+                    success_count: 0,
+                    error_count: 0,
+                    results: vec![cancellation_note(&cancel_flag)],
+                });
+                return;
+            }
+
+            let result = FileOperations::load_audio_files(scan.files.clone(), &class_name, &defaults)
+                .map(|tracks| FolderImportResult { tracks, scan })
+                .map_err(|e| format!("{:#}", e));
+            let _ = progress_sender.send(TaskMessage::FolderImported { result });
+        });
+
+        Ok(())
+    }
+
+    /// 在后台补全新导入的轨道（解码测量时长、将内嵌封面转换为 PAA、读取标签标题）
+    ///
+    /// 与视频时长读取一样使用独立线程，不占用单任务的工作线程，也不显示进度。
+    pub fn process_track_import_jobs(&self, jobs: Vec<crate::models::TrackImportJob>) {
+        let progress_sender = self.progress_sender.clone();
+
+        thread::spawn(move || {
+            for job in jobs {
+                let duration = if job.measure_duration {
+                    AudioProcessor::measure_duration(&job.path).map_err(|e| {
+                        warn!("无法测量时长 {:?}: {}", job.path, e);
+                    }).ok()
                 } else {
-                    error_count += 1;
-                    Err(format!("不支持: {} - 不支持的音频格式", filename))
+                    None
                 };
+                let logo_path = if job.cover_paa {
+                    FileOperations::generate_track_cover_paa(&job.path).unwrap_or_else(|e| {
+                        warn!("无法为轨道生成封面PAA {:?}: {}", job.path, e);
+                        None
+                    })
+                } else {
+                    None
+                };
+                let title = if job.read_title {
+                    AudioProcessor::read_tags(&job.path).map_err(|e| {
+                        warn!("无法读取标签 {:?}: {}", job.path, e);
+                    }).ok().and_then(|tags| tags.title)
+                } else {
+                    None
+                };
+                let extras = crate::models::TrackImportExtras { path: job.path, duration, logo_path, title };
+                if progress_sender.send(TaskMessage::TrackImportExtrasLoaded { extras }).is_err() {
+                    break;
+                }
+            }
+        });
+    }
 
-                match result {
-                    Ok(msg) => results.push(msg),
-                    Err(msg) => results.push(msg),
+    /// 为 Zeus 准备检查轨道：读取实际音频编码，并为时长为 0 的轨道读取时长
+    ///
+    /// `tracks` 为 (轨道索引, 文件路径, 是否需要读取时长)。
+    pub fn process_zeus_probe(&self, tracks: Vec<(usize, PathBuf, bool)>) -> Result<()> {
+        let progress_sender = self.progress_sender.clone();
+        let cancel_flag = self.cancel_flag.clone();
+
+        self.spawn_task(move || {
+            let mut probes = Vec::with_capacity(tracks.len());
+
+            for (i, (track_index, path, needs_duration)) in tracks.into_iter().enumerate() {
+                if is_cancelled(&cancel_flag) {
+                    info!("Zeus 准备任务被取消");
+                    let _ = progress_sender.send(TaskMessage::TaskCompleted {
+                        success_count: i,
+                        error_count: 0,
+                        results: vec![cancellation_note(&cancel_flag)],
+                    });
+                    return;
+                }
+
+                let filename = path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                if let Err(e) = progress_sender.send(TaskMessage::UpdateProgress {
+                    current_file: i,
+                    filename,
+                }) {
+                    warn!("发送进度更新失败: {}", e);
                 }
+
+                let is_vorbis = AudioProcessor::is_vorbis(&path).map_err(|e| {
+                    warn!("无法识别音频编码: {:?} - {}", path, e);
+                    e.to_string()
+                });
+                let duration = if needs_duration && is_vorbis == Ok(true) {
+                    AudioProcessor::get_audio_info(&path).ok().map(|info| info.duration)
+                } else {
+                    None
+                };
+                probes.push(crate::models::ZeusTrackProbe { track_index, path, is_vorbis, duration });
             }
 
-            // 发送完成消息
-            if let Err(e) = progress_sender.send(TaskMessage::TaskCompleted {
-                success_count,
-                error_count,
-                results,
-            }) {
-                warn!("发送任务完成消息失败: {}", e);
+            info!("Zeus 准备的轨道检查完成: {} 个轨道", probes.len());
+            if let Err(e) = progress_sender.send(TaskMessage::ZeusTracksProbed { probes }) {
+                warn!("发送轨道检查结果失败: {}", e);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// 导出模组：复制（或转换）文件并生成配置，每个文件开始前报告进度，取消后停止复制
+    pub fn process_mod_export(&self, job: crate::models::ModExportJob) -> Result<()> {
+        let progress_sender = self.progress_sender.clone();
+        let cancel_flag = self.cancel_flag.clone();
+
+        self.spawn_task(move || {
+            let should_cancel = || is_cancelled(&cancel_flag);
+            let progress = |current_file: usize, filename: &str| {
+                if let Err(e) = progress_sender.send(TaskMessage::UpdateProgress {
+                    current_file,
+                    filename: filename.to_string(),
+                }) {
+                    warn!("发送进度更新失败: {}", e);
+                }
+            };
+
+            let mut outcome = FileOperations::export_mod(&job, &should_cancel, &progress);
+            outcome.message = format!("{}{}", job.result_prefix, outcome.message);
+            if let Err(e) = progress_sender.send(TaskMessage::ModExportFinished { outcome }) {
+                warn!("发送模组导出结果失败: {}", e);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// 使用 FFmpeg 分析单个轨道的综合响度
+    pub fn process_loudness_analysis(&self, track_index: usize, path: PathBuf) -> Result<()> {
+        let progress_sender = self.progress_sender.clone();
+        let cancel_flag = self.cancel_flag.clone();
+
+        self.spawn_task(move || {
+            let should_cancel = || is_cancelled(&cancel_flag);
+            let result = AudioConverter::new()
+                .and_then(|converter| converter.measure_loudness(&path, &should_cancel))
+                .map_err(|e| {
+                    warn!("音量分析失败: {:?} - {}", path, e);
+                    e.to_string()
+                });
+            if let Ok(lufs) = result {
+                info!("音量分析完成: {:?} - {:.1} LUFS", path, lufs);
+            }
+            if let Err(e) = progress_sender.send(TaskMessage::LoudnessMeasured { track_index, path, result }) {
+                warn!("发送音量分析结果失败: {}", e);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// 按章节将音频文件分割为多个 OGG 文件
+    pub fn process_chapter_split(
+        &self,
+        source: PathBuf,
+        chapters: Vec<AudioChapter>,
+        output_dir: PathBuf,
+    ) -> Result<()> {
+        let progress_sender = self.progress_sender.clone();
+        let cancel_flag = self.cancel_flag.clone();
+
+        self.spawn_task(move || {
+            let converter = match AudioConverter::new() {
+                Ok(conv) => conv,
+                Err(e) => {
+                    warn!("FFmpeg 未找到: {}", e);
+                    let _ = progress_sender.send(TaskMessage::ChaptersSplit {
+                        outputs: Vec::new(),
+                        failed: vec![format!("FFmpeg 未找到: {}\n\n请使用软件的自动下载功能或手动安装 FFmpeg", e)],
+                    });
+                    return;
+                }
+            };
+
+            let source_stem = crate::utils::string_utils::StringUtils::safe_filename_pinyin(
+                &source.file_stem().unwrap_or_default().to_string_lossy(),
+                0,
+            );
+            let should_cancel = || is_cancelled(&cancel_flag);
+            let mut outputs = Vec::new();
+            let mut failed = Vec::new();
+
+            for (i, chapter) in chapters.into_iter().enumerate() {
+                if should_cancel() {
+                    info!("章节分割任务被取消");
+                    failed.push(cancellation_note(&cancel_flag));
+                    break;
+                }
+
+                if let Err(e) = progress_sender.send(TaskMessage::UpdateProgress {
+                    current_file: i,
+                    filename: chapter.title.clone(),
+                }) {
+                    warn!("发送进度更新失败: {}", e);
+                }
+
+                let chapter_name = crate::utils::string_utils::StringUtils::safe_filename_pinyin(&chapter.title, i + 1);
+                let output_path = crate::utils::string_utils::StringUtils::ensure_unique_path(
+                    output_dir.join(format!("{}_{:02}_{}.ogg", source_stem, i + 1, chapter_name)),
+                );
+                match converter.extract_segment_to_ogg(
+                    &source,
+                    &output_path,
+                    chapter.start_seconds,
+                    chapter.end_seconds,
+                    &should_cancel,
+                ) {
+                    Ok(_) => {
+                        let _ = progress_sender.send(TaskMessage::OutputProduced {
+                            input_path: source.clone(),
+                            output_path: output_path.clone(),
+                        });
+                        outputs.push((output_path, chapter.title));
+                    }
+                    Err(e) => {
+                        warn!("章节分割失败: {} - {}", chapter.title, e);
+                        failed.push(format!("{}: {}", chapter.title, e));
+                        let _ = progress_sender.send(TaskMessage::FileFailed);
+                    }
+                }
+            }
+
+            info!("章节分割完成: 成功 {} 个，失败 {} 个", outputs.len(), failed.len());
+            if let Err(e) = progress_sender.send(TaskMessage::ChaptersSplit { outputs, failed }) {
+                warn!("发送章节分割结果失败: {}", e);
             }
         });
 
@@ -178,23 +1039,20 @@ impl ThreadedTaskProcessor {
         let progress_sender = self.progress_sender.clone();
         let cancel_flag = self.cancel_flag.clone();
 
-        thread::spawn(move || {
+        self.spawn_task(move || {
             let mut success_count = 0;
             let mut error_count = 0;
             let mut results = Vec::new();
 
             for (i, input_path) in files.iter().enumerate() {
                 // 检查取消标志
-                if *cancel_flag.lock().unwrap_or_else(|_| {
-                    warn!("获取取消标志失败，假设任务被取消");
-                    panic!("Mutex poisoned, cannot continue")
-                }) {
+                if is_cancelled(&cancel_flag) {
                     info!("PAA转换任务被取消");
                     // 立即发送取消完成消息
                     let _ = progress_sender.send(TaskMessage::TaskCompleted {
                         success_count,
                         error_count,
-                        results: vec!["任务被用户取消".to_string()],
+                        results: vec![cancellation_note(&cancel_flag)],
                     });
                     return;
                 }
@@ -213,6 +1071,14 @@ impl ThreadedTaskProcessor {
                     warn!("发送进度更新失败: {}", e);
                 }
 
+                // 转换前检查图片格式和色深，不支持的文件记为失败并继续处理下一个
+                if let Err(message) = PaaConverter::validate_input(input_path) {
+                    error_count += 1;
+                    results.push(format!("转换失败: {} - {}", filename, message));
+                    warn!("PAA转换跳过不支持的图片: {:?} - {}", input_path, message);
+                    continue;
+                }
+
                 // 处理文件
                 if let Some(file_stem) = input_path.file_stem() {
                     // 使用安全文件名
@@ -233,9 +1099,9 @@ impl ThreadedTaskProcessor {
                         options.clone(),
                         None
                     ) {
-                        Ok(_) => {
+                        Ok(conversion) => {
                             success_count += 1;
-                            results.push(format!("转换成功: {}", filename));
+                            results.push(format!("转换成功: {} ({})", filename, conversion.describe()));
                             info!("PAA转换成功: {:?}", output_path);
                         }
                         Err(e) => {
@@ -265,21 +1131,22 @@ impl ThreadedTaskProcessor {
         &self,
         files: Vec<PathBuf>,
         output_dir: PathBuf,
+        options: AudioConvertOptions,
     ) -> Result<()> {
         info!("使用并行转换处理音频文件: {} 个文件", files.len());
         
         // 创建并行转换器
-        let mut config = ParallelConfig::default();
+        let mut config = ParallelConfig::default().with_thread_override(self.max_threads_override);
         
         // 根据文件数量和大小调整配置
         if files.len() > 10 {
             config.adjust_for_file_size(files.len(), 50.0); // 假设平均50MB
         }
         
-        let parallel_converter = ParallelConverter::new(config);
+        let parallel_converter = ParallelConverter::new(config).with_pause_flag(self.pause_flag.clone());
         
         // 启动并行转换
-        parallel_converter.convert_audio_files_parallel(files, output_dir)?;
+        parallel_converter.convert_audio_files_parallel(files, output_dir, options)?;
         
         // 启动进度转发线程
         self.start_progress_forwarding(parallel_converter);
@@ -292,11 +1159,13 @@ impl ThreadedTaskProcessor {
         &self,
         files: Vec<PathBuf>,
         output_dir: PathBuf,
+        options: AudioConvertOptions,
     ) -> Result<()> {
         let progress_sender = self.progress_sender.clone();
         let cancel_flag = self.cancel_flag.clone();
+        let pause_flag = self.pause_flag.clone();
 
-        thread::spawn(move || {
+        self.spawn_task(move || {
             // 注意：音频转换不使用 Tokio，所以不需要创建运行时
             // 如果将来需要使用异步功能，再添加运行时
             let mut success_count = 0;
@@ -318,16 +1187,16 @@ impl ThreadedTaskProcessor {
             };
 
             for (i, input_path) in files.iter().enumerate() {
+                // 暂停时在开始下一个文件前等待
+                wait_while_paused(&pause_flag, || is_cancelled(&cancel_flag));
+
                 // 检查取消标志
-                if *cancel_flag.lock().unwrap_or_else(|_| {
-                    warn!("获取取消标志失败，假设任务被取消");
-                    panic!("Mutex poisoned, cannot continue")
-                }) {
+                if is_cancelled(&cancel_flag) {
                     info!("音频转换任务被取消");
                     let _ = progress_sender.send(TaskMessage::TaskCompleted {
                         success_count,
                         error_count,
-                        results: vec!["任务被用户取消".to_string()],
+                        results: vec![cancellation_note(&cancel_flag)],
                     });
                     return;
                 }
@@ -361,25 +1230,36 @@ impl ThreadedTaskProcessor {
                     output_path = crate::utils::string_utils::StringUtils::ensure_unique_path(output_path);
                     
                     // 执行转换
-                    let cancel_check = || *cancel_flag.lock().unwrap_or_else(|_| {
-                    warn!("获取取消标志失败，假设任务被取消");
-                    panic!("Mutex poisoned, cannot continue")
-                });
-                    match converter.convert_to_ogg_with_cancel(input_path, &output_path, &cancel_check) {
-                        Ok(_) => {
+                    let cancel_check = || is_cancelled(&cancel_flag);
+                    let report_progress = |fraction: f32| {
+                        // 单文件进度可丢弃，通道满时不等待
+                        let _ = progress_sender.send(TaskMessage::FileProgress { task_id: i, fraction });
+                    };
+                    match converter.convert_to_ogg_with_progress(input_path, &output_path, options, &cancel_check, &report_progress) {
+                        Ok(message) => {
                             success_count += 1;
-                            results.push(format!("转换成功: {} -> {}.ogg", filename, pinyin_filename));
+                            if message == AudioConverter::STREAM_COPY_MESSAGE {
+                                results.push(format!("{}: {} -> {}.ogg", message, filename, pinyin_filename));
+                            } else {
+                                results.push(format!("转换成功: {} -> {}.ogg", filename, pinyin_filename));
+                            }
                             info!("音频转换成功: {:?}", output_path);
+                            let _ = progress_sender.send(TaskMessage::OutputProduced {
+                                input_path: input_path.clone(),
+                                output_path: output_path.clone(),
+                            });
                         }
                         Err(e) => {
                             error_count += 1;
                             results.push(format!("转换失败: {} - {}", filename, e));
                             warn!("音频转换失败: {:?} - {}", input_path, e);
+                            let _ = progress_sender.send(TaskMessage::ConversionFailed { input_path: input_path.clone() });
                         }
                     }
                 } else {
                     error_count += 1;
                     results.push(format!("转换失败: {} - 无法获取文件名", filename));
+                    let _ = progress_sender.send(TaskMessage::ConversionFailed { input_path: input_path.clone() });
                 }
             }
 
@@ -401,24 +1281,28 @@ impl ThreadedTaskProcessor {
         &self,
         files: Vec<PathBuf>,
         output_dir: PathBuf,
+        format: VideoFormat,
+        options: VideoConvertOptions,
     ) -> Result<()> {
         info!("使用并行转换处理视频文件: {} 个文件", files.len());
         
         // 创建并行转换器
-        let mut config = ParallelConfig::default();
+        let mut config = ParallelConfig::default().with_thread_override(self.max_threads_override);
         
-        // 视频转换通常更消耗资源，减少并发数
-        config.max_threads = (config.max_threads / 2).max(2);
+        // 视频转换通常更消耗资源，减少并发数（用户设置了最大线程数时按设置执行）
+        if config.max_threads_override.is_none() {
+            config.max_threads = (config.max_threads / 2).max(2);
+        }
         
         // 根据文件数量和大小调整配置
         if files.len() > 5 {
             config.adjust_for_file_size(files.len(), 200.0); // 假设平均200MB
         }
         
-        let parallel_converter = ParallelConverter::new(config);
+        let parallel_converter = ParallelConverter::new(config).with_pause_flag(self.pause_flag.clone());
         
         // 启动并行转换
-        parallel_converter.convert_video_files_parallel(files, output_dir)?;
+        parallel_converter.convert_video_files_parallel(files, output_dir, format, options)?;
         
         // 启动进度转发线程
         self.start_progress_forwarding(parallel_converter);
@@ -431,19 +1315,28 @@ impl ThreadedTaskProcessor {
         &self,
         files: Vec<PathBuf>,
         output_dir: PathBuf,
+        format: VideoFormat,
+        options: VideoConvertOptions,
+        chunk_config: VideoChunkConfig,
     ) -> Result<()> {
         let progress_sender = self.progress_sender.clone();
-        let _cancel_flag = self.cancel_flag.clone();
+        let cancel_flag = self.cancel_flag.clone();
 
-        // 创建分片配置
-        let chunk_config = VideoChunkConfig::default();
-        let chunk_processor = VideoChunkParallelProcessor::new(chunk_config);
+        // 使用用户的分片设置，输出格式以转换对话框的选择为准
+        let chunk_config = VideoChunkConfig {
+            output_format: format,
+            ..chunk_config.validated()
+        };
+        let chunk_processor = VideoChunkParallelProcessor::new(chunk_config)
+            .with_max_threads_override(self.max_threads_override)
+            .with_cancel_flag(cancel_flag)
+            .with_pause_flag(self.pause_flag.clone());
 
-        thread::spawn(move || {
+        self.spawn_task(move || {
             info!("开始分片并行视频转换: {} 个文件", files.len());
 
             // 启动分片并行转换
-            if let Err(e) = chunk_processor.process_videos_parallel(files.clone(), output_dir, 5, 3) {
+            if let Err(e) = chunk_processor.process_videos_parallel(files.clone(), output_dir, options) {
                 warn!("分片并行视频转换失败: {}", e);
                 let _ = progress_sender.send(TaskMessage::TaskCompleted {
                     success_count: 0,
@@ -463,7 +1356,7 @@ impl ThreadedTaskProcessor {
     /// 监听分片转换进度
     fn monitor_chunk_progress(
         chunk_processor: VideoChunkParallelProcessor,
-        progress_sender: Sender<TaskMessage>,
+        progress_sender: TaskSender,
     ) {
         let receiver = chunk_processor.get_progress_receiver();
         
@@ -477,11 +1370,14 @@ impl ThreadedTaskProcessor {
         &self,
         files: Vec<PathBuf>,
         output_dir: PathBuf,
+        format: VideoFormat,
+        options: VideoConvertOptions,
     ) -> Result<()> {
         let progress_sender = self.progress_sender.clone();
         let cancel_flag = self.cancel_flag.clone();
+        let pause_flag = self.pause_flag.clone();
 
-        thread::spawn(move || {
+        self.spawn_task(move || {
             // 注意：视频转换不使用 Tokio，所以不需要创建运行时
             // 如果将来需要使用异步功能，再添加运行时
             let mut success_count = 0;
@@ -503,16 +1399,16 @@ impl ThreadedTaskProcessor {
             };
 
             for (i, input_path) in files.iter().enumerate() {
+                // 暂停时在开始下一个文件前等待
+                wait_while_paused(&pause_flag, || is_cancelled(&cancel_flag));
+
                 // 检查取消标志
-                if *cancel_flag.lock().unwrap_or_else(|_| {
-                    warn!("获取取消标志失败，假设任务被取消");
-                    panic!("Mutex poisoned, cannot continue")
-                }) {
+                if is_cancelled(&cancel_flag) {
                     info!("视频转换任务被取消");
                     let _ = progress_sender.send(TaskMessage::TaskCompleted {
                         success_count,
                         error_count,
-                        results: vec!["任务被用户取消".to_string()],
+                        results: vec![cancellation_note(&cancel_flag)],
                     });
                     return;
                 }
@@ -540,7 +1436,7 @@ impl ThreadedTaskProcessor {
                 } else {
                     format!("video{:03}", i)
                 };
-                let output_filename = pinyin_filename + ".ogv";
+                let output_filename = format!("{}.{}", pinyin_filename, format.extension());
                 let mut output_path = output_dir.join(output_filename);
                 // 确保路径长度在限制内
                 output_path = crate::utils::string_utils::StringUtils::ensure_path_length(&output_path, 260)
@@ -549,16 +1445,26 @@ impl ThreadedTaskProcessor {
                 output_path = crate::utils::string_utils::StringUtils::ensure_unique_path(output_path);
 
                 // 执行视频转换
-                match converter.convert_to_ogv(input_path, &output_path) {
+                let cancel_check = || is_cancelled(&cancel_flag);
+                let report_progress = |fraction: f32| {
+                    // 单文件进度可丢弃，通道满时不等待
+                    let _ = progress_sender.send(TaskMessage::FileProgress { task_id: i, fraction });
+                };
+                match converter.convert_video_with_progress(input_path, &output_path, format, options, &cancel_check, &report_progress) {
                     Ok(_) => {
                         success_count += 1;
                         results.push(format!("✓ 成功转换: {} -> {}", filename, output_path.display()));
                         info!("视频转换成功: {} -> {}", input_path.display(), output_path.display());
+                        let _ = progress_sender.send(TaskMessage::OutputProduced {
+                            input_path: input_path.clone(),
+                            output_path: output_path.clone(),
+                        });
                     }
                     Err(e) => {
                         error_count += 1;
                         results.push(format!("✗ 转换失败: {} - {}", filename, e));
                         warn!("视频转换失败: {} - {}", input_path.display(), e);
+                        let _ = progress_sender.send(TaskMessage::ConversionFailed { input_path: input_path.clone() });
                     }
                 }
             }
@@ -587,9 +1493,15 @@ impl ThreadedTaskProcessor {
     }
 
     /// 处理 FFmpeg 下载任务
-    pub fn process_ffmpeg_download(&self) -> Result<()> {
+    pub fn process_ffmpeg_download(
+        &self,
+        http_settings: HttpSettings,
+        mirror_source: crate::ffmpeg_plugin::MirrorSource,
+        start_mirror: usize,
+    ) -> Result<()> {
         let progress_sender = self.progress_sender.clone();
-        let cancel_flag = self.cancel_flag.clone();
+        reset_cancelled(&self.download_cancel_flag);
+        let cancel_flag = self.download_cancel_flag.clone();
 
         thread::spawn(move || {
             let rt = match tokio::runtime::Runtime::new() {
@@ -607,11 +1519,13 @@ impl ThreadedTaskProcessor {
             let _ = progress_sender.send(TaskMessage::FFmpegDownloadProgress {
                 progress: 0.0,
                 status: "准备下载 FFmpeg...".to_string(),
+                downloaded_bytes: 0,
+                total_bytes: None,
             });
 
             // 创建下载器
-                    let downloader = match FFmpegDownloader::new_user_workspace_with_fallback() {
-                Ok(downloader) => downloader,
+            let downloader = match FFmpegDownloader::new_user_workspace_with_fallback(http_settings) {
+                Ok(downloader) => downloader.with_mirror_source(mirror_source).with_start_mirror(start_mirror),
                 Err(e) => {
                     let _ = progress_sender.send(TaskMessage::FFmpegDownloadCompleted {
                         success: false,
@@ -625,23 +1539,22 @@ impl ThreadedTaskProcessor {
             let result = rt.block_on(async {
                 downloader.download_ffmpeg_with_fallback(|progress, status| {
                     // 检查取消标志
-                    if *cancel_flag.lock().unwrap_or_else(|_| {
-                    warn!("获取取消标志失败，假设任务被取消");
-                    panic!("Mutex poisoned, cannot continue")
-                }) {
+                    if is_cancelled(&cancel_flag) {
                         return Err(anyhow::anyhow!("下载被取消"));
                     }
 
                     // 发送进度更新
                     if let Err(e) = progress_sender.send(TaskMessage::FFmpegDownloadProgress {
-                        progress,
+                        progress: progress.percent,
                         status: status.to_string(),
+                        downloaded_bytes: progress.downloaded,
+                        total_bytes: progress.total,
                     }) {
                         warn!("发送下载进度失败: {}", e);
                     }
 
                     Ok(())
-                }).await
+                }, || is_cancelled(&cancel_flag)).await
             });
 
             // 发送完成消息
@@ -668,6 +1581,13 @@ impl ThreadedTaskProcessor {
                         message: format!("FFmpeg 下载成功！\n路径: {}\n配置已自动更新", ffmpeg_path.display()),
                     });
                 }
+                Err(e) if crate::http_client::is_cancelled(&e) => {
+                    info!("FFmpeg 下载已取消");
+                    let _ = progress_sender.send(TaskMessage::FFmpegDownloadCompleted {
+                        success: false,
+                        message: crate::utils::constants::app::FFMPEG_DOWNLOAD_CANCELLED.to_string(),
+                    });
+                }
                 Err(e) => {
                     let _ = progress_sender.send(TaskMessage::FFmpegDownloadCompleted {
                         success: false,
@@ -683,9 +1603,18 @@ impl ThreadedTaskProcessor {
         Ok(())
     }
 
-    /// 获取进度接收器
-    pub fn get_progress_receiver(&self) -> &Receiver<TaskMessage> {
-        &self.progress_receiver
+    /// 取出下一条任务消息（通道为空时再取出被合并暂存的进度消息）
+    pub fn try_recv_message(&self) -> Option<TaskMessage> {
+        self.progress_receiver.try_recv()
+    }
+
+    /// 任务消息通道的健康状况
+    pub fn channel_health(&self) -> ChannelHealth {
+        ChannelHealth {
+            queued: self.progress_receiver.queued(),
+            capacity: self.progress_receiver.capacity(),
+            coalesced: self.progress_sender.coalesced_count(),
+        }
     }
 
     /// 启动进度转发线程
@@ -693,15 +1622,13 @@ impl ThreadedTaskProcessor {
         let progress_sender = self.progress_sender.clone();
         let cancel_flag = self.cancel_flag.clone();
         
-        thread::spawn(move || {
+        // 并行转换没有任务主线程，由转发线程承担看门狗检查的任务线程
+        self.spawn_task(move || {
             let receiver = parallel_converter.get_progress_receiver();
             
             while let Ok(update) = receiver.recv() {
                 // 检查取消标志
-                if *cancel_flag.lock().unwrap_or_else(|_| {
-                    warn!("获取取消标志失败，假设任务被取消");
-                    panic!("Mutex poisoned, cannot continue")
-                }) {
+                if is_cancelled(&cancel_flag) {
                     info!("进度转发线程收到取消信号");
                     break;
                 }
@@ -720,9 +1647,9 @@ impl ThreadedTaskProcessor {
     /// 取消当前任务（快速取消）
     pub fn cancel_task(&self) {
         // 立即设置取消标志
-        if let Ok(mut flag) = self.cancel_flag.lock() {
-            *flag = true;
-        }
+        *self.cancel_flag.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        // 解除暂停，让等待中的工作线程看到取消并退出
+        self.pause_flag.store(false, Ordering::Relaxed);
         
         // 如果存在并行转换器，也取消它
         if let Some(ref converter) = self.parallel_converter {
@@ -739,6 +1666,12 @@ impl ThreadedTaskProcessor {
         info!("任务取消信号已发送");
     }
 
+    /// 取消 FFmpeg 下载（下载线程在数据块之间检查标志，中止后发送 FFmpegDownloadCompleted）
+    pub fn cancel_ffmpeg_download(&self) {
+        *self.download_cancel_flag.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        info!("FFmpeg 下载取消信号已发送");
+    }
+
     /// 等待所有任务完成（用于快速关闭）
     pub fn wait_for_completion(&self, timeout_ms: u64) -> bool {
         let start_time = std::time::Instant::now();
@@ -753,7 +1686,7 @@ impl ThreadedTaskProcessor {
         while start_time.elapsed() < timeout {
             // 尝试接收所有待处理的消息
             let mut has_message = false;
-            while let Ok(_) = self.progress_receiver.try_recv() {
+            while self.try_recv_message().is_some() {
                 has_message = true;
                 consecutive_empty_checks = 0;
             }
@@ -785,13 +1718,34 @@ impl ThreadedTaskProcessor {
         }
     }
 
-    /// 重置取消标志
+    /// 暂停并行转换：正在转换的文件继续完成，之后不再领取新文件
+    pub fn pause_task(&self) {
+        self.pause_flag.store(true, Ordering::Relaxed);
+        info!("转换任务已暂停");
+    }
+
+    /// 继续已暂停的转换
+    pub fn resume_task(&self) {
+        self.pause_flag.store(false, Ordering::Relaxed);
+        info!("转换任务已继续");
+    }
+
+    /// 转换是否处于暂停状态
+    pub fn is_paused(&self) -> bool {
+        self.pause_flag.load(Ordering::Relaxed)
+    }
+
+    /// 设置并行转换的最大线程数（None 表示自动选择），对之后启动的转换任务生效
+    pub fn set_max_threads_override(&mut self, max_threads_override: Option<usize>) {
+        self.max_threads_override = max_threads_override;
+    }
+
+    /// 重置取消标志（新任务开始时调用，同时重置通道合并计数和暂停状态）
     pub fn reset_cancel_flag(&self) {
-        if let Ok(mut flag) = self.cancel_flag.lock() {
-            *flag = false;
-        } else {
-            warn!("重置取消标志失败");
-        }
+        // 上一个任务的工作线程崩溃会使取消标志中毒，新任务开始时清除，否则之后的任务都会被当作已取消
+        reset_cancelled(&self.cancel_flag);
+        self.pause_flag.store(false, Ordering::Relaxed);
+        self.progress_sender.reset_coalesced_count();
     }
 }
 
@@ -800,3 +1754,50 @@ impl Default for ThreadedTaskProcessor {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_message_coalesce_keys() {
+        // 高频进度消息可合并：整体进度和下载进度各一个键，单文件进度按任务区分
+        let progress = TaskMessage::UpdateProgress { current_file: 1, filename: String::new() };
+        let download = TaskMessage::FFmpegDownloadProgress {
+            progress: 0.5,
+            status: String::new(),
+            downloaded_bytes: 0,
+            total_bytes: None,
+        };
+        assert_eq!(progress.coalesce_key(), Some((0, 0)));
+        assert_eq!(download.coalesce_key(), Some((1, 0)));
+        assert_eq!(TaskMessage::FileProgress { task_id: 3, fraction: 0.5 }.coalesce_key(), Some((2, 3)));
+
+        // 并行转换进度沿用自身的键，并与上面的键错开
+        let parallel = TaskMessage::ParallelProgressUpdate(ProgressUpdate::FileProgress { task_id: 3, fraction: 0.5 });
+        assert_eq!(parallel.coalesce_key(), Some((4, 3)));
+
+        // 完成消息是终止消息，必须送达
+        let completed = TaskMessage::TaskCompleted { success_count: 1, error_count: 0, results: Vec::new() };
+        let download_completed = TaskMessage::FFmpegDownloadCompleted { success: true, message: String::new() };
+        assert_eq!(completed.coalesce_key(), None);
+        assert_eq!(download_completed.coalesce_key(), None);
+    }
+
+    #[test]
+    fn reset_cancel_flag_clears_poisoned_flag() {
+        let processor = ThreadedTaskProcessor::new();
+        let flag = processor.cancel_flag.clone();
+        let _ = thread::spawn(move || {
+            let _guard = flag.lock().unwrap();
+            panic!("模拟工作线程崩溃");
+        })
+        .join();
+        assert!(is_cancelled(&processor.cancel_flag));
+        assert!(cancellation_note(&processor.cancel_flag).contains("异常退出"));
+
+        processor.reset_cancel_flag();
+        assert!(!processor.cancel_flag.is_poisoned());
+        assert!(!is_cancelled(&processor.cancel_flag));
+    }
+}
@@ -0,0 +1,310 @@
+/*!
+ * 相似轨道检测模块
+ * 通过规范化名称与简易音频指纹查找疑似重复添加的轨道
+ */
+
+use std::path::PathBuf;
+
+use crate::audio::AudioFingerprint;
+
+/// 名称相似度阈值
+const NAME_SIMILARITY_THRESHOLD: f32 = 0.85;
+/// 音频指纹相似度阈值
+const FINGERPRINT_SIMILARITY_THRESHOLD: f32 = 0.9;
+/// 指纹比较时允许的时长差（秒），超过则认为不是同一首歌
+const MAX_DURATION_DIFF_SECONDS: u32 = 5;
+
+/// 待分析的轨道
+#[derive(Debug, Clone)]
+pub struct TrackSample {
+    pub path: PathBuf,
+    pub track_name: String,
+    pub duration: u32,
+    /// 音频指纹（解码失败时为 None，仅按名称比较）
+    pub fingerprint: Option<AudioFingerprint>,
+}
+
+/// 一组疑似重复的轨道
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarTrackGroup {
+    /// 组内轨道的文件路径（按项目中的顺序）
+    pub paths: Vec<PathBuf>,
+    /// 判定依据
+    pub name_match: bool,
+    pub audio_match: bool,
+}
+
+impl SimilarTrackGroup {
+    /// 判定依据的显示文本
+    pub fn reason(&self) -> &'static str {
+        match (self.name_match, self.audio_match) {
+            (true, true) => "名称和音频均相似",
+            (true, false) => "名称相似",
+            (false, true) => "音频指纹相似",
+            (false, false) => "",
+        }
+    }
+}
+
+/// 规范化轨道名称：去除扩展名、副本编号、码率标记和标点
+pub fn normalize_name(name: &str) -> String {
+    let mut name = name.trim().to_lowercase();
+
+    // 去除音频扩展名
+    for ext in [".ogg", ".mp3", ".flac", ".wav", ".m4a", ".aac", ".wma"] {
+        if let Some(stripped) = name.strip_suffix(ext) {
+            name = stripped.to_string();
+            break;
+        }
+    }
+
+    // 去除 "- 副本"、"- copy" 等复制后缀
+    for suffix in ["- 副本", "副本", "- copy", " copy"] {
+        if let Some(stripped) = name.trim_end().strip_suffix(suffix) {
+            name = stripped.to_string();
+        }
+    }
+
+    let mut result = String::new();
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        // 去除括号内的纯数字/码率（如 "(1)"、"[320k]"、"（2）"）
+        if matches!(c, '(' | '[' | '（' | '【') {
+            let mut inner = String::new();
+            let mut closed = false;
+            for next in chars.by_ref() {
+                if matches!(next, ')' | ']' | '）' | '】') {
+                    closed = true;
+                    break;
+                }
+                inner.push(next);
+            }
+            if closed && is_noise_token(inner.trim()) {
+                continue;
+            }
+            result.push(' ');
+            result.push_str(&inner);
+            result.push(' ');
+            continue;
+        }
+        if c.is_alphanumeric() {
+            result.push(c);
+        } else {
+            result.push(' ');
+        }
+    }
+
+    result
+        .split_whitespace()
+        .filter(|token| !is_bitrate_token(token))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 括号内可忽略的内容（副本编号、码率）
+fn is_noise_token(token: &str) -> bool {
+    token.is_empty() || token.chars().all(|c| c.is_ascii_digit()) || is_bitrate_token(token)
+}
+
+/// 码率标记，如 "320k"、"128kbps"
+fn is_bitrate_token(token: &str) -> bool {
+    let digits = token.trim_end_matches("kbps").trim_end_matches('k');
+    digits.len() != token.len() && !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// 计算两个规范化名称的相似度（0.0-1.0，基于编辑距离）
+pub fn name_similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let max_len = a.len().max(b.len());
+    if max_len == 0 {
+        return 0.0;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    1.0 - prev[b.len()] as f32 / max_len as f32
+}
+
+/// 名称中的数字（如曲目编号），去除前导零后按出现顺序返回
+fn numeric_tokens(name: &str) -> Vec<&str> {
+    name.split(|c: char| !c.is_ascii_digit())
+        .filter(|run| !run.is_empty())
+        .map(|run| match run.trim_start_matches('0') {
+            "" => "0",
+            trimmed => trimmed,
+        })
+        .collect()
+}
+
+/// 两个规范化名称是否相似：数字必须完全相同（"Track 01" 与 "Track 02" 是不同曲目），其余部分按编辑距离比较
+pub fn names_match(a: &str, b: &str) -> bool {
+    !a.is_empty() && numeric_tokens(a) == numeric_tokens(b) && name_similarity(a, b) >= NAME_SIMILARITY_THRESHOLD
+}
+
+/// 按名称与音频指纹将疑似重复的轨道分组（仅返回包含两个及以上轨道的组）
+pub fn find_similar_groups(samples: &[TrackSample]) -> Vec<SimilarTrackGroup> {
+    let names: Vec<String> = samples.iter().map(|s| normalize_name(&s.track_name)).collect();
+    let file_names: Vec<String> = samples
+        .iter()
+        .map(|s| normalize_name(&s.path.file_stem().unwrap_or_default().to_string_lossy()))
+        .collect();
+
+    // 并查集
+    let mut parent: Vec<usize> = (0..samples.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+
+    let mut name_pairs = Vec::new();
+    let mut audio_pairs = Vec::new();
+    for i in 0..samples.len() {
+        for j in (i + 1)..samples.len() {
+            let name_match = [(&names[i], &names[j]), (&file_names[i], &file_names[j])]
+                .iter()
+                .any(|(a, b)| names_match(a, b));
+
+            let audio_match = match (&samples[i].fingerprint, &samples[j].fingerprint) {
+                (Some(a), Some(b)) => {
+                    samples[i].duration.abs_diff(samples[j].duration) <= MAX_DURATION_DIFF_SECONDS
+                        && a.similarity(b) >= FINGERPRINT_SIMILARITY_THRESHOLD
+                }
+                _ => false,
+            };
+
+            if name_match || audio_match {
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                parent[rj] = ri;
+            }
+            if name_match {
+                name_pairs.push(i);
+            }
+            if audio_match {
+                audio_pairs.push(i);
+            }
+        }
+    }
+
+    let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+    for i in 0..samples.len() {
+        let root = find(&mut parent, i);
+        match groups.iter_mut().find(|(r, _)| *r == root) {
+            Some((_, members)) => members.push(i),
+            None => groups.push((root, vec![i])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(root, members)| SimilarTrackGroup {
+            name_match: name_pairs.iter().any(|&i| find(&mut parent, i) == root),
+            audio_match: audio_pairs.iter().any(|&i| find(&mut parent, i) == root),
+            paths: members.iter().map(|&i| samples[i].path.clone()).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(path: &str, name: &str) -> TrackSample {
+        TrackSample {
+            path: PathBuf::from(path),
+            track_name: name.to_string(),
+            duration: 180,
+            fingerprint: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_name_strips_copy_markers() {
+        assert_eq!(normalize_name("Song (1).ogg"), "song");
+        assert_eq!(normalize_name("Song - 副本"), "song");
+        assert_eq!(normalize_name("Song [320k]"), "song");
+        assert_eq!(normalize_name("Song 128kbps"), "song");
+        assert_eq!(normalize_name("Song (Live)"), "song live");
+    }
+
+    #[test]
+    fn test_name_similarity() {
+        assert_eq!(name_similarity("song", "song"), 1.0);
+        assert!(name_similarity("my song", "my songs") > 0.85);
+        assert!(name_similarity("alpha", "omega") < 0.5);
+    }
+
+    #[test]
+    fn test_find_similar_groups_by_name() {
+        let samples = vec![
+            sample("a/song.ogg", "Song"),
+            sample("b/song (1).ogg", "Song (1)"),
+            sample("c/other.ogg", "Completely Different"),
+        ];
+        let groups = find_similar_groups(&samples);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].paths, vec![PathBuf::from("a/song.ogg"), PathBuf::from("b/song (1).ogg")]);
+        assert!(groups[0].name_match);
+        assert!(!groups[0].audio_match);
+    }
+
+    #[test]
+    fn test_numbered_titles_are_not_duplicates() {
+        assert!(!names_match(&normalize_name("Track 01"), &normalize_name("Track 02")));
+        assert!(!names_match(&normalize_name("Chapter 9"), &normalize_name("Chapter 10")));
+        assert!(names_match(&normalize_name("Track 01"), &normalize_name("Track 1")));
+
+        let samples: Vec<TrackSample> = (1..=12)
+            .map(|i| sample(&format!("album/{:02} Track.ogg", i), &format!("Track {:02}", i)))
+            .collect();
+        assert!(find_similar_groups(&samples).is_empty());
+    }
+
+    #[test]
+    fn test_near_duplicates_with_numbers_are_grouped() {
+        assert!(names_match(&normalize_name("Track 01"), &normalize_name("Track 01 (1)")));
+        assert!(names_match(&normalize_name("Symphony No 5"), &normalize_name("Symphony No. 5 - 副本")));
+
+        let samples = vec![
+            sample("a/03 Battle Theme.ogg", "Battle Theme 3"),
+            sample("b/03 Battle Theme (1).ogg", "Battle Theme 3 (1)"),
+            sample("c/04 Battle Theme.ogg", "Battle Theme 4"),
+        ];
+        let groups = find_similar_groups(&samples);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            groups[0].paths,
+            vec![PathBuf::from("a/03 Battle Theme.ogg"), PathBuf::from("b/03 Battle Theme (1).ogg")]
+        );
+        assert!(groups[0].name_match);
+    }
+
+    #[test]
+    fn test_find_similar_groups_by_fingerprint() {
+        let bits: Vec<bool> = (0..200).map(|i| i % 3 == 0).collect();
+        let fingerprint = AudioFingerprint { bits, analyzed_seconds: 20.0 };
+        let mut a = sample("a/first.ogg", "Alpha");
+        let mut b = sample("b/second.ogg", "Omega");
+        a.fingerprint = Some(fingerprint.clone());
+        b.fingerprint = Some(fingerprint);
+
+        let groups = find_similar_groups(&[a, b]);
+        assert_eq!(groups.len(), 1);
+        assert!(groups[0].audio_match);
+        assert!(!groups[0].name_match);
+    }
+}
@@ -2,6 +2,7 @@ use eframe::egui;
 use log::{info, warn};
 
 use crate::file_ops::FileOperations;
+use crate::i18n::{tr, trf};
 use crate::models::{AppState, TaskType, TaskStatus};
 use crate::templates::TemplateEngine;
 use crate::threading::ThreadedTaskProcessor;
@@ -118,36 +119,107 @@ impl UIComponents {
     /// 渲染主菜单栏
     pub fn render_menu_bar(ui: &mut egui::Ui, state: &mut AppState) {
         egui::menu::bar(ui, |ui| {
-            ui.menu_button("文件", |ui| {
-                ui.menu_button("项目设置", |ui| {
-                    if ui.button("常规").clicked() {
+            ui.menu_button(tr("menu.file"), |ui| {
+                ui.menu_button(tr("menu.project_settings"), |ui| {
+                    if ui.button(tr("menu.general")).clicked() {
                         state.show_project_settings = true;
                         ui.close_menu();
                     }
-                    if ui.button("添加封面图片 (.paa)").clicked() {
+                    if ui.button(tr("menu.add_logo")).clicked() {
                         if let Some(path) = FileOperations::select_logo_file() {
-                            state.project.logo_path = Some(path);
-                            state.project.use_default_logo = false;
+                            state.set_project_logo(path);
                             info!("选择Logo文件: {:?}", state.project.logo_path);
                         }
                         ui.close_menu();
                     }
                 });
                 ui.separator();
-                if ui.button("导出...").clicked() {
+                if ui.button(tr("menu.open_project")).on_hover_text(tr("menu.open_project_hint")).clicked() {
+                    Self::open_project(state);
+                    ui.close_menu();
+                }
+                let save_shortcut = ui.ctx().format_shortcut(&crate::utils::constants::shortcuts::SAVE_PROJECT);
+                if ui.add(egui::Button::new(tr("menu.save_project_as")).shortcut_text(save_shortcut))
+                    .on_hover_text(tr("menu.save_project_as_hint"))
+                    .clicked()
+                {
+                    Self::save_project_as(state);
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.add_enabled(state.project.mod_type == crate::models::ModType::Music, egui::Button::new(tr("menu.import_playlist")))
+                    .on_hover_text(tr("menu.import_playlist_hint"))
+                    .clicked()
+                {
+                    Self::import_playlist(state);
+                    ui.close_menu();
+                }
+                if ui.add_enabled(state.project.mod_type == crate::models::ModType::Music, egui::Button::new(tr("menu.paste_paths")))
+                    .on_hover_text(tr("menu.paste_paths_hint"))
+                    .clicked()
+                {
+                    Self::paste_paths_from_clipboard(state);
+                    ui.close_menu();
+                }
+                if ui.add_enabled(!state.tracks.is_empty(), egui::Button::new(tr("menu.copy_paths")))
+                    .on_hover_text(tr("menu.copy_paths_hint"))
+                    .clicked()
+                {
+                    Self::copy_track_paths_to_clipboard(ui.ctx(), state);
+                    ui.close_menu();
+                }
+                let export_shortcut = ui.ctx().format_shortcut(&crate::utils::constants::shortcuts::EXPORT);
+                if ui.add(egui::Button::new(tr("menu.export")).shortcut_text(export_shortcut)).clicked() {
                     state.show_export_dialog = true;
                     ui.close_menu();
                 }
+                if ui.button(tr("menu.prepare_zeus")).clicked() {
+                    state.should_prepare_for_zeus = true;
+                    ui.close_menu();
+                }
+                ui.add_enabled_ui(!state.recent_export_dirs.is_empty(), |ui| {
+                    ui.menu_button(tr("menu.recent_export_dirs"), |ui| {
+                        let mut chosen = None;
+                        for dir in &state.recent_export_dirs {
+                            if ui.button(dir.display().to_string()).on_hover_text(tr("menu.export_here")).clicked() {
+                                chosen = Some(dir.clone());
+                            }
+                        }
+                        ui.separator();
+                        if ui.button(tr("menu.clear_list")).clicked() {
+                            state.recent_export_dirs.clear();
+                            ui.close_menu();
+                        }
+                        if let Some(dir) = chosen {
+                            Self::export_mod(state, &dir);
+                            ui.close_menu();
+                        }
+                    });
+                });
                 ui.separator();
-                if ui.button("退出").clicked() {
+                ui.checkbox(&mut state.confirm_on_close, tr("menu.confirm_on_close"))
+                    .on_hover_text(tr("menu.confirm_on_close_hint"));
+                if ui.button(tr("menu.exit")).clicked() {
                     ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
                 }
             });
 
-            ui.menu_button("工具", |ui| {
+            ui.menu_button(tr("menu.view"), |ui| {
+                ui.label(tr("menu.theme"));
+                for theme in crate::models::AppTheme::ALL {
+                    if ui.radio_value(&mut state.theme, theme, theme.display_name()).clicked() {
+                        if let Err(e) = state.save_config() {
+                            warn!("保存主题设置失败: {}", e);
+                        }
+                        ui.close_menu();
+                    }
+                }
+            });
+
+            ui.menu_button(tr("menu.tools"), |ui| {
                 // 模组类型选择 - 使用垂直布局，更清晰
                 ui.vertical(|ui| {
-                    ui.label("模组类型:");
+                    ui.label(tr("menu.mod_type"));
                     ui.add_space(5.0);
                     
                     // 保存当前模组类型
@@ -155,7 +227,7 @@ impl UIComponents {
                     
                     // 使用selectable_label创建单选按钮组
                     ui.horizontal(|ui| {
-                        if ui.selectable_label(state.project.mod_type == crate::models::ModType::Music, "🎵 音乐模组").clicked() {
+                        if ui.selectable_label(state.project.mod_type == crate::models::ModType::Music, tr("menu.music_mod")).clicked() {
                             if state.project.mod_type != crate::models::ModType::Music {
                                 state.project.mod_type = crate::models::ModType::Music;
                                 if old_type == crate::models::ModType::Video {
@@ -167,7 +239,7 @@ impl UIComponents {
                         
                         ui.add_space(10.0);
                         
-                        if ui.selectable_label(state.project.mod_type == crate::models::ModType::Video, "🎬 视频模组").clicked() {
+                        if ui.selectable_label(state.project.mod_type == crate::models::ModType::Video, tr("menu.video_mod")).clicked() {
                             if state.project.mod_type != crate::models::ModType::Video {
                                 state.project.mod_type = crate::models::ModType::Video;
                                 if old_type == crate::models::ModType::Music {
@@ -179,65 +251,174 @@ impl UIComponents {
                     });
                 });
                 ui.separator();
-                if ui.button("构建插件...").clicked() {
+                if ui.button(tr("menu.build_addon")).clicked() {
                     if let Some(pbo_path) = FileOperations::select_pbo_file() {
                         Self::build_addon(state, &pbo_path);
                     }
                     ui.close_menu();
                 }
+                if ui.add_enabled(
+                    state.project.mod_type == crate::models::ModType::Music && !state.tracks.is_empty(),
+                    egui::Button::new(tr("menu.addon_source")),
+                )
+                    .on_hover_text(tr("menu.addon_source_hint"))
+                    .clicked()
+                {
+                    Self::build_addon_source(state);
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.container_mod")).clicked() {
+                    state.show_container_mod = true;
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.repair_config"))
+                    .on_hover_text(tr("menu.repair_config_hint"))
+                    .clicked()
+                {
+                    Self::repair_old_config(state);
+                    ui.close_menu();
+                }
                 ui.separator();
-                if ui.button("转换图片为PAA...").clicked() {
+                if ui.button(tr("menu.paa_converter")).clicked() {
                     state.show_paa_converter = true;
                     ui.close_menu();
                 }
-                if ui.button("音频解密...").clicked() {
+                if ui.button(tr("menu.audio_decrypt")).clicked() {
                     state.show_audio_decrypt = true;
                     ui.close_menu();
                 }
-                if ui.button("音频格式转换...").clicked() {
+                if ui.button(tr("menu.audio_convert")).clicked() {
                     state.show_audio_converter = true;
                     ui.close_menu();
                 }
-                if ui.button("视频格式转换...").clicked() {
+                if ui.button(tr("menu.video_convert")).clicked() {
                     state.show_video_converter = true;
                     ui.close_menu();
                 }
+                if ui.button(trf("menu.conversion_history", &[&state.conversion_history.len()])).clicked() {
+                    state.refresh_conversion_history();
+                    state.show_conversion_history = true;
+                    ui.close_menu();
+                }
                 ui.separator();
-                if ui.button("FFmpeg 插件管理...").clicked() {
+                if ui.button(tr("menu.ffmpeg_plugin")).clicked() {
                     state.show_ffmpeg_plugin = true;
                     ui.close_menu();
                 }
-                if ui.button("轨道计数").clicked() {
+                if ui.button(tr("menu.performance_settings")).clicked() {
+                    state.show_performance_settings = true;
+                    ui.close_menu();
+                }
+                if ui.add_enabled(state.tracks.len() > 1, egui::Button::new(tr("menu.similar_tracks"))).clicked() {
+                    state.should_scan_similar_tracks = true;
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.chapter_split")).clicked() {
+                    state.show_chapter_split = true;
+                    ui.close_menu();
+                }
+                if ui.add_enabled(!state.tracks.is_empty(), egui::Button::new(tr("menu.sync_durations")))
+                    .on_hover_text(tr("menu.sync_durations_hint"))
+                    .clicked()
+                {
+                    state.should_sync_durations = true;
+                    ui.close_menu();
+                }
+                let selected_count = state.selected_track_indices().len();
+                if ui.add_enabled(selected_count > 0, egui::Button::new(tr("menu.batch_tag")))
+                    .on_hover_text(tr("menu.batch_tag_hint"))
+                    .clicked()
+                {
+                    state.show_batch_tag = true;
+                    ui.close_menu();
+                }
+                if ui.add_enabled(!state.tracks.is_empty(), egui::Button::new(tr("menu.decibel_adjust")))
+                    .on_hover_text(tr("menu.decibel_adjust_hint"))
+                    .clicked()
+                {
+                    state.show_decibel_adjust = true;
+                    ui.close_menu();
+                }
+                if ui.add_enabled(state.tracks.len() > 1, egui::Button::new(tr("menu.decibel_ramp")))
+                    .on_hover_text(tr("menu.decibel_ramp_hint"))
+                    .clicked()
+                {
+                    state.show_decibel_ramp = true;
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.compare_projects")).clicked() {
+                    state.show_project_compare = true;
+                    ui.close_menu();
+                }
+                if ui.add_enabled(!state.tracks.is_empty(), egui::Button::new(tr("menu.ingame_order")))
+                    .on_hover_text(tr("menu.ingame_order_hint"))
+                    .clicked()
+                {
+                    state.show_ingame_order = true;
+                    ui.close_menu();
+                }
+                if ui.add_enabled(!state.tracks.is_empty(), egui::Button::new(tr("menu.playlist_player")))
+                    .on_hover_text(tr("menu.playlist_player_hint"))
+                    .clicked()
+                {
+                    state.show_audio_player = true;
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.track_count")).clicked() {
                     state.show_track_count = true;
                     ui.close_menu();
                 }
-                if ui.button("清空所有轨道").clicked() {
+                let undo_shortcut = ui.ctx().format_shortcut(&crate::utils::constants::shortcuts::UNDO);
+                if ui.add_enabled(state.can_undo_tracks(), egui::Button::new(tr("menu.undo")).shortcut_text(undo_shortcut))
+                    .on_hover_text(tr("menu.undo_hint"))
+                    .clicked()
+                {
+                    Self::undo_track_change(state);
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.clear_tracks")).clicked() {
                     state.clear_tracks();
                     state.file_operation_message = None; // 清除提示信息
                     ui.close_menu();
                 }
-                if ui.button("清空所有视频").clicked() {
+                if ui.button(tr("menu.clear_videos")).clicked() {
                     state.clear_videos();
                     state.file_operation_message = None; // 清除提示信息
                     ui.close_menu();
                 }
             });
 
-            ui.menu_button("帮助", |ui| {
-                if ui.button("📖 新用户指导").clicked() {
+            ui.menu_button(tr("menu.help"), |ui| {
+                if ui.button(tr("menu.user_guide")).clicked() {
                     state.show_user_guide = true;
                     ui.close_menu();
                 }
-                if ui.button("ℹ️ 关于").clicked() {
+                if ui.button(tr("menu.about")).clicked() {
                     state.show_about = true;
                     ui.close_menu();
                 }
+                ui.separator();
+                ui.label(tr("menu.language"));
+                for lang in crate::i18n::Lang::ALL {
+                    if ui.radio_value(&mut state.language, lang, lang.display_name()).clicked() {
+                        crate::i18n::set_language(lang);
+                        if let Err(e) = state.save_config() {
+                            warn!("保存语言设置失败: {}", e);
+                        }
+                        ui.close_menu();
+                    }
+                }
             });
         });
     }
 
     /// 渲染轨道列表
     pub fn render_track_list(ui: &mut egui::Ui, state: &mut AppState) {
+        // 搜索框位于滚动区域之外，轨道较多时始终可见
+        if state.project.mod_type == crate::models::ModType::Music && !state.tracks.is_empty() {
+            Self::render_track_filter(ui, state);
+        }
+
         egui::ScrollArea::vertical()
             .max_height(ui.available_height() - 50.0)
             .show(ui, |ui| {
@@ -259,6 +440,25 @@ impl UIComponents {
             });
     }
 
+    /// 渲染轨道搜索框和过滤计数
+    fn render_track_filter(ui: &mut egui::Ui, state: &mut AppState) {
+        let shown = state.filtered_track_indices().len();
+        let total = state.track_count();
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.add(
+                egui::TextEdit::singleline(&mut state.track_filter)
+                    .hint_text(tr("tracks.search_hint"))
+                    .desired_width(200.0),
+            );
+            if !state.track_filter.is_empty() && ui.small_button("✖").on_hover_text(tr("tracks.clear_search")).clicked() {
+                state.track_filter.clear();
+            }
+            ui.label(egui::RichText::new(trf("tracks.shown_count", &[&shown, &total])).weak());
+        });
+        ui.add_space(5.0);
+    }
+
     /// 渲染音乐轨道
     fn render_music_tracks(ui: &mut egui::Ui, state: &mut AppState, selected_track: &mut Option<usize>) {
         if state.tracks.is_empty() {
@@ -282,22 +482,50 @@ impl UIComponents {
             ui.add_space(5.0);
             // 使用预分配的字符串避免重复分配
             let mut track_display = String::with_capacity(100);
-            for (i, track) in state.tracks.iter().enumerate() {
-                let is_selected = *selected_track == Some(i);
+            let mut clicked = None;
+            let modifiers = ui.input(|i| i.modifiers);
+            // 过滤只影响显示，行号映射回 state.tracks 中的原始索引
+            let visible_indices = state.filtered_track_indices();
+            if visible_indices.is_empty() {
+                ui.label(egui::RichText::new("没有匹配搜索条件的轨道").weak());
+            }
+            for i in visible_indices {
+                let track = &state.tracks[i];
+                let is_selected = if state.selected_tracks.is_empty() {
+                    *selected_track == Some(i)
+                } else {
+                    state.selected_tracks.contains(&i)
+                };
                 
                 // 重用字符串缓冲区
                 track_display.clear();
                 track_display.push_str("🎵 ");
                 track_display.push_str(&track.display_name());
-                track_display.push_str(" (");
-                track_display.push_str(&track.duration.to_string());
-                track_display.push_str("秒)");
+                if track.dirty_since_export {
+                    track_display.push_str(" *");
+                }
+                if !track.notes.is_empty() {
+                    track_display.push_str(" 📝");
+                }
                 
-                let response = ui.selectable_label(is_selected, &track_display);
+                let mut response = ui.horizontal(|ui| {
+                    let response = ui.selectable_label(is_selected, &track_display);
+                    // 时长使用默认值时显示为橙色
+                    let duration_text = egui::RichText::new(format!("{}秒", track.duration)).small();
+                    if track.duration_estimated {
+                        ui.label(duration_text.color(egui::Color32::from_rgb(255, 165, 0)))
+                            .on_hover_text("无法读取实际时长，使用默认值；可使用“工具 → 同步全部时长”或手动修改");
+                    } else {
+                        ui.label(duration_text.weak());
+                    }
+                    response
+                }).inner;
+                if !track.notes.is_empty() {
+                    response = response.on_hover_text(&track.notes);
+                }
 
                 if response.clicked() {
-                    *selected_track = Some(i);
-                    state.selected_video = None; // 清除视频选择
+                    clicked = Some(i);
                 }
 
                 // 双击编辑轨道
@@ -306,6 +534,14 @@ impl UIComponents {
                     state.show_track_editor = true;
                 }
             }
+
+            // Ctrl/Shift+单击多选（用于批量设置标签）
+            if let Some(i) = clicked {
+                state.selected_track = *selected_track;
+                state.click_track(i, modifiers.command, modifiers.shift);
+                *selected_track = state.selected_track;
+                state.selected_video = None; // 清除视频选择
+            }
         }
     }
 
@@ -369,7 +605,16 @@ impl UIComponents {
             match state.project.mod_type {
                 crate::models::ModType::Music => {
                     if ui.button("添加OGG歌曲").clicked() {
-                        Self::add_audio_files(ui, state);
+                        Self::add_audio_files(ui.ctx(), state);
+                    }
+                    if ui.button(tr("tracks.add_folder"))
+                        .on_hover_text(tr("tracks.add_folder_hover"))
+                        .clicked()
+                    {
+                        Self::add_audio_folder(state);
+                    }
+                    if ui.button(tr("tracks.import_folder")).clicked() {
+                        state.show_folder_import = true;
                     }
                     
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -377,11 +622,18 @@ impl UIComponents {
                             state.remove_selected_track();
                             state.file_operation_message = None; // 清除提示信息
                         }
+                        // 轨道顺序即 CfgMusic 中的导出顺序
+                        if ui.add_enabled(state.can_move_selected_track(false), egui::Button::new(tr("common.move_down"))).clicked() {
+                            state.move_selected_track(false);
+                        }
+                        if ui.add_enabled(state.can_move_selected_track(true), egui::Button::new(tr("common.move_up"))).clicked() {
+                            state.move_selected_track(true);
+                        }
                     });
                 }
                 crate::models::ModType::Video => {
                     if ui.button("添加视频文件").clicked() {
-                        Self::add_video_files(ui, state);
+                        Self::add_video_files(ui.ctx(), state);
                     }
                     
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -396,12 +648,12 @@ impl UIComponents {
     }
 
     /// 添加音频文件
-    fn add_audio_files(ui: &mut egui::Ui, state: &mut AppState) {
+    pub(crate) fn add_audio_files(ctx: &egui::Context, state: &mut AppState) {
         if let Some(paths) = FileOperations::select_audio_files() {
-            // 使用多线程处理音频加载
-            state.task_manager.start_task(crate::models::TaskType::AudioLoad, paths.len());
+            // 使用多线程处理音频加载（后台任务运行时不跟踪进度，避免完成时误结束该任务）
+            let tracked = state.task_manager.start_task(crate::models::TaskType::AudioLoad, paths.len());
             // 这里需要从外部传入 task_processor，暂时使用简单版本
-            match FileOperations::load_audio_files(paths, &state.project.class_name) {
+            match FileOperations::load_audio_files(paths, &state.project.class_name, &state.track_defaults) {
                 Ok(tracks) => {
                     let track_count = tracks.len();
                     info!("开始添加 {} 个轨道", track_count);
@@ -417,20 +669,285 @@ impl UIComponents {
                     }
                     
                     info!("添加了 {} 个轨道，跳过了 {} 个重复，当前总轨道数: {}", added_count, duplicate_count, state.track_count());
-                    state.task_manager.complete_task();
+                    if tracked {
+                        state.task_manager.complete_task();
+                    }
                     // 强制重绘UI
-                    ui.ctx().request_repaint();
+                    ctx.request_repaint();
                 }
                 Err(e) => {
                     warn!("加载音频文件失败: {}", e);
-                    state.task_manager.fail_task(format!("加载音频文件失败: {}", e));
+                    if tracked {
+                        state.task_manager.fail_task(format!("加载音频文件失败: {}", e));
+                    } else {
+                        state.file_operation_message = Some(format!("加载音频文件失败: {}", e));
+                    }
+                }
+            }
+        }
+    }
+
+    /// 选择文件夹并直接开始后台导入（与“从文件夹导入”使用同一流程和过滤条件）
+    fn add_audio_folder(state: &mut AppState) {
+        if state.task_manager.is_running() {
+            state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        if let Some(dir) = FileOperations::select_audio_folder() {
+            state.folder_import_dir = Some(dir);
+            state.should_import_folder = true;
+        }
+    }
+
+    /// 显示从文件夹导入对话框（递归扫描并按模式、大小、时长过滤）
+    pub fn show_folder_import_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_folder_import {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [500.0, 320.0].into(), [150.0, 120.0].into());
+        let mut should_import = false;
+
+        egui::Window::new(tr("folder_import.title"))
+            .open(&mut state.show_folder_import)
+            .default_pos(safe_pos)
+            .resizable(false)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr("folder_import.folder"));
+                    match state.folder_import_dir {
+                        Some(ref dir) => ui.label(dir.display().to_string()),
+                        None => ui.label(tr("folder_import.not_selected")),
+                    };
+                    if ui.button(tr("folder_import.browse")).clicked() {
+                        if let Some(dir) = FileOperations::select_audio_folder() {
+                            state.folder_import_dir = Some(dir);
+                        }
+                    }
+                });
+                ui.label(egui::RichText::new(tr("folder_import.recursive_note")).small().weak());
+
+                ui.add_space(5.0);
+                ui.separator();
+                ui.label(tr("folder_import.filters"));
+                egui::Grid::new("folder_import_filter").num_columns(2).show(ui, |ui| {
+                    ui.label(tr("folder_import.include"));
+                    ui.text_edit_singleline(&mut state.import_filter.include_patterns)
+                        .on_hover_text(tr("folder_import.include_hover"));
+                    ui.end_row();
+                    ui.label(tr("folder_import.exclude"));
+                    ui.text_edit_singleline(&mut state.import_filter.exclude_patterns)
+                        .on_hover_text(tr("folder_import.exclude_hover"));
+                    ui.end_row();
+                    ui.label(tr("folder_import.min_size"));
+                    ui.add(egui::DragValue::new(&mut state.import_filter.min_size_kb).suffix(" KB").clamp_range(0..=1_000_000));
+                    ui.end_row();
+                    ui.label(tr("folder_import.min_duration"));
+                    ui.add(egui::DragValue::new(&mut state.import_filter.min_duration_secs).suffix(tr("folder_import.seconds_suffix")).clamp_range(0..=3600));
+                    ui.end_row();
+                });
+
+                ui.add_space(10.0);
+                let can_import = state.folder_import_dir.is_some() && !state.task_manager.is_running();
+                if ui.add_enabled(can_import, egui::Button::new(tr("folder_import.import"))).clicked() {
+                    should_import = true;
+                }
+            });
+
+        if should_import {
+            state.should_import_folder = true;
+        }
+    }
+
+    /// 添加后台从文件夹导入的轨道并报告过滤结果
+    pub(crate) fn finish_folder_import(state: &mut AppState, result: Result<crate::threading::FolderImportResult, String>) {
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("从文件夹导入失败: {}", e);
+                state.file_operation_message = Some(format!("从文件夹导入失败: {}", e));
+                return;
+            }
+        };
+        let scan = &result.scan;
+        for (path, reason) in &scan.filtered {
+            info!("导入时过滤: {:?} ({})", path, reason.description());
+        }
+
+        let filtered_note = if scan.filtered.is_empty() {
+            String::new()
+        } else {
+            format!("；过滤了 {} 个文件（{}）", scan.filtered.len(), scan.filtered_summary())
+        };
+        let limit_note = if scan.limit_reached {
+            format!(
+                "；⚠️ 已达到单次扫描上限 {} 个文件，其余文件未导入",
+                crate::utils::constants::file_ops::MAX_FOLDER_SCAN_FILES
+            )
+        } else {
+            String::new()
+        };
+        let (added_count, duplicate_count) = state.add_tracks_with_duplicate_check(result.tracks);
+        state.file_operation_message = Some(format!(
+            "从文件夹导入了 {} 个轨道，跳过了 {} 个重复文件{}{}",
+            added_count, duplicate_count, filtered_note, limit_note
+        ));
+        state.show_folder_import = false;
+    }
+
+    /// 选择并打开项目文件
+    fn open_project(state: &mut AppState) {
+        if state.task_manager.is_running() {
+            state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        let Some(path) = FileOperations::select_project_file() else {
+            return;
+        };
+        Self::request_project_load(state, path);
+    }
+
+    /// 请求打开项目文件：有未导出的修改时先显示确认对话框
+    pub(crate) fn request_project_load(state: &mut AppState, path: std::path::PathBuf) {
+        if state.task_manager.is_running() {
+            state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        if state.dirty_track_count() > 0 {
+            state.pending_project_load = Some(path);
+        } else {
+            Self::load_project(state, &path);
+        }
+    }
+
+    /// 打开项目文件替换当前项目（失败时保留当前项目）
+    fn load_project(state: &mut AppState, path: &std::path::Path) {
+        match state.load_project(path) {
+            Ok(summary) => {
+                info!("{}: {:?}", summary, path);
+                state.file_operation_message = Some(summary);
+            }
+            Err(e) => {
+                warn!("打开项目失败: {:#}", e);
+                state.export_result = Some(format!(
+                    "无法打开项目文件，当前项目未被修改。\n\n文件: {}\n错误: {:#}",
+                    path.display(),
+                    e
+                ));
+                state.show_export_result = true;
+            }
+        }
+    }
+
+    /// 将当前项目另存为项目文件
+    pub(crate) fn save_project_as(state: &mut AppState) {
+        let default_name: String = state.project.mod_name.trim()
+            .chars()
+            .map(|c| if c.is_control() || "\\/:*?\"<>|".contains(c) { '_' } else { c })
+            .collect();
+        let default_name = if default_name.is_empty() { "project".to_string() } else { default_name };
+        let Some(path) = FileOperations::select_project_save_path(&default_name) else {
+            return;
+        };
+        match state.save_project(&path) {
+            Ok(()) => {
+                state.file_operation_message = Some(format!("项目已保存到 {}", path.display()));
+            }
+            Err(e) => {
+                warn!("保存项目失败: {:#}", e);
+                state.file_operation_message = Some(format!("保存项目失败: {:#}", e));
+            }
+        }
+    }
+
+    /// 导入播放列表（保持播放列表顺序）并报告无法解析的条目
+    fn import_playlist(state: &mut AppState) {
+        let Some(path) = FileOperations::select_playlist_file() else {
+            return;
+        };
+        match crate::playlist::load_playlist(&path) {
+            Ok(import) => Self::add_resolved_files(state, import, "播放列表"),
+            Err(e) => {
+                warn!("读取播放列表失败: {}", e);
+                state.file_operation_message = Some(format!("读取播放列表失败: {}", e));
+            }
+        }
+    }
+
+    /// 读取剪贴板中的路径列表（每行一个）并添加轨道
+    fn paste_paths_from_clipboard(state: &mut AppState) {
+        let text = match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("读取剪贴板失败: {}", e);
+                state.file_operation_message = Some(format!("读取剪贴板失败（剪贴板中需要是文本）: {}", e));
+                return;
+            }
+        };
+        if crate::playlist::parse_path_list(&text).is_empty() {
+            state.file_operation_message = Some("剪贴板中没有文件路径".to_string());
+            return;
+        }
+        let import = crate::playlist::resolve_path_list(&text);
+        Self::add_resolved_files(state, import, "剪贴板中的路径");
+    }
+
+    /// 将轨道源文件路径（每行一个）复制到剪贴板
+    fn copy_track_paths_to_clipboard(ctx: &egui::Context, state: &mut AppState) {
+        let text = state
+            .tracks
+            .iter()
+            .map(|track| track.path.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        ctx.output_mut(|o| o.copied_text = text);
+        state.file_operation_message = Some(format!("已复制 {} 个轨道路径到剪贴板", state.tracks.len()));
+    }
+
+    /// 添加已解析的文件（非 OGG 文件记为无法导入）并报告无法解析的条目
+    fn add_resolved_files(state: &mut AppState, mut import: crate::playlist::PlaylistImport, source: &str) {
+        // 仅支持 OGG，其它格式记为无法导入
+        let (files, unsupported): (Vec<_>, Vec<_>) = import
+            .files
+            .drain(..)
+            .partition(|file| crate::utils::FileUtils::is_supported_audio_file(file));
+        import.unresolved.extend(
+            unsupported
+                .into_iter()
+                .map(|file| (file.display().to_string(), "不是 OGG 文件，请先转换".to_string())),
+        );
+        for (entry, reason) in &import.unresolved {
+            warn!("{}条目无法导入: {} ({})", source, entry, reason);
+        }
+
+        match FileOperations::load_audio_files(files, &state.project.class_name, &state.track_defaults) {
+            Ok(tracks) => {
+                let (added_count, duplicate_count) = state.add_tracks_with_duplicate_check(tracks);
+                let mut message = format!("从{}添加了 {} 个轨道，跳过了 {} 个重复文件", source, added_count, duplicate_count);
+                if !import.unresolved.is_empty() {
+                    let examples: Vec<String> = import
+                        .unresolved
+                        .iter()
+                        .take(5)
+                        .map(|(entry, reason)| format!("{}（{}）", entry, reason))
+                        .collect();
+                    message.push_str(&format!("；{} 个条目无法导入: {}", import.unresolved.len(), examples.join("，")));
+                    if import.unresolved.len() > examples.len() {
+                        message.push_str(" 等");
+                    }
                 }
+                state.file_operation_message = Some(message);
+            }
+            Err(e) => {
+                warn!("加载音频文件失败: {}", e);
+                state.file_operation_message = Some(format!("加载音频文件失败: {}", e));
             }
         }
     }
 
     /// 添加视频文件
-    fn add_video_files(ui: &mut egui::Ui, state: &mut AppState) {
+    pub(crate) fn add_video_files(ctx: &egui::Context, state: &mut AppState) {
         // 根据模组类型选择不同的文件选择器
         let paths = match state.project.mod_type {
             crate::models::ModType::Video => FileOperations::select_ogv_video_files(),
@@ -439,7 +956,7 @@ impl UIComponents {
         
         if let Some(paths) = paths {
             // 使用多线程处理视频加载
-            state.task_manager.start_task(crate::models::TaskType::AudioLoad, paths.len()); // 复用AudioLoad任务类型
+            let tracked = state.task_manager.start_task(crate::models::TaskType::AudioLoad, paths.len()); // 复用AudioLoad任务类型
             match FileOperations::load_video_files(paths, &state.project.class_name) {
                 Ok(videos) => {
                     let video_count = videos.len();
@@ -456,13 +973,19 @@ impl UIComponents {
                     }
                     
                     info!("添加了 {} 个视频文件，跳过了 {} 个重复，当前总视频数: {}", added_count, duplicate_count, state.video_count());
-                    state.task_manager.complete_task();
+                    if tracked {
+                        state.task_manager.complete_task();
+                    }
                     // 强制重绘UI
-                    ui.ctx().request_repaint();
+                    ctx.request_repaint();
                 }
                 Err(e) => {
                     warn!("加载视频文件失败: {}", e);
-                    state.task_manager.fail_task(format!("加载视频文件失败: {}", e));
+                    if tracked {
+                        state.task_manager.fail_task(format!("加载视频文件失败: {}", e));
+                    } else {
+                        state.file_operation_message = Some(format!("加载视频文件失败: {}", e));
+                    }
                 }
             }
         }
@@ -474,13 +997,28 @@ impl UIComponents {
             return;
         }
 
+        // 查找第一个带封面的轨道（封面在后台按路径检查并缓存）
+        let mut cover_track = None;
+        for track in &state.tracks {
+            if state.cover_art_cache.get_or_request(&track.path) == Some(&true) {
+                cover_track = Some(track.path.clone());
+                break;
+            }
+        }
+        let checking_cover = cover_track.is_none() && state.cover_art_cache.is_loading();
+        if checking_cover {
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+        }
+        let mut should_import_cover = false;
+
         let mut should_close = false;
         let mut should_save = false;
 
         let window_size = egui::Vec2::new(500.0, 450.0);
         let safe_pos = Self::calculate_safe_position(ctx, window_size, egui::Pos2::new(100.0, 100.0));
         
-        egui::Window::new("项目设置")
+        egui::Window::new(tr("project.title"))
+            .id(egui::Id::new("project_settings_dialog"))
             .open(&mut state.show_project_settings)
             .resizable(true)
             .default_size(window_size)
@@ -492,12 +1030,12 @@ impl UIComponents {
                     // 基本信息区域
                     ui.group(|ui| {
                         ui.vertical(|ui| {
-                            ui.heading("基本信息");
+                            ui.heading(tr("project.basic_info"));
                             ui.add_space(5.0);
                             
                             // 模组名称输入
                             ui.horizontal(|ui| {
-                                ui.label("模组名称:");
+                                ui.label(tr("project.mod_name"));
                                 let response = ui.text_edit_singleline(&mut state.project.mod_name);
                                 
                                 // 检测输入变化并自动过滤非英文字符
@@ -514,7 +1052,7 @@ impl UIComponents {
                             ui.horizontal(|ui| {
                                 ui.add_space(80.0); // 对齐到"模组名称:"之后
                                 ui.label(
-                                    egui::RichText::new("仅允许英文、数字、空格和 - _ . 符号")
+                                    egui::RichText::new(tr("project.mod_name_rule"))
                                         .small()
                                         .color(egui::Color32::from_rgb(100, 100, 100))
                                 );
@@ -523,9 +1061,29 @@ impl UIComponents {
                             ui.add_space(8.0);
                             
                             ui.horizontal(|ui| {
-                                ui.label("作者:");
+                                ui.label(tr("common.author"));
                                 ui.text_edit_singleline(&mut state.project.author_name);
                             });
+
+                            if state.project.mod_type == crate::models::ModType::Music {
+                                ui.add_space(8.0);
+                                ui.horizontal(|ui| {
+                                    ui.label(tr("project.tracks_folder"));
+                                    ui.text_edit_singleline(&mut state.project.tracks_folder_name)
+                                        .on_hover_text(tr("project.tracks_folder_hint"));
+                                });
+                                let tracks_folder = state.project.tracks_folder();
+                                if tracks_folder != state.project.tracks_folder_name {
+                                    ui.horizontal(|ui| {
+                                        ui.add_space(80.0);
+                                        ui.label(
+                                            egui::RichText::new(trf("project.tracks_folder_sanitized", &[&tracks_folder]))
+                                                .small()
+                                                .color(egui::Color32::from_rgb(100, 100, 100))
+                                        );
+                                    });
+                                }
+                            }
                         });
                     });
                     
@@ -534,24 +1092,104 @@ impl UIComponents {
                     // Logo设置区域
                     ui.group(|ui| {
                         ui.vertical(|ui| {
-                            ui.heading("Logo设置");
+                            ui.heading(tr("project.logo_settings"));
                             ui.add_space(5.0);
                             
-                            ui.checkbox(&mut state.project.use_default_logo, "使用默认Logo");
+                            ui.checkbox(&mut state.project.use_default_logo, tr("common.use_default_logo"));
                             
                             ui.add_space(5.0);
                             
                             ui.horizontal(|ui| {
-                                ui.label("Logo路径:");
+                                ui.label(tr("project.logo_path"));
                                 if let Some(ref logo_path) = state.project.logo_path {
                                     ui.label(logo_path.to_string_lossy());
                                 } else {
-                                    ui.label("未设置");
+                                    ui.label(tr("common.not_set"));
                                 }
                             });
+
+                            ui.add_space(5.0);
+
+                            let button = ui.add_enabled(
+                                cover_track.is_some(),
+                                egui::Button::new(tr("project.use_cover_logo")),
+                            );
+                            let button = match &cover_track {
+                                Some(path) => button.on_hover_text(trf(
+                                    "project.use_cover_logo_hint",
+                                    &[&path.file_name().unwrap_or_default().to_string_lossy()],
+                                )),
+                                None if checking_cover => button.on_disabled_hover_text(tr("project.checking_cover")),
+                                None => button.on_disabled_hover_text(tr("project.no_cover")),
+                            };
+                            if button.clicked() {
+                                should_import_cover = true;
+                            }
                         });
                     });
                     
+                    ui.add_space(10.0);
+
+                    // Steam 创意工坊（写入 meta.cpp）
+                    ui.group(|ui| {
+                        ui.vertical(|ui| {
+                            ui.heading(tr("project.workshop"));
+                            ui.add_space(5.0);
+
+                            ui.horizontal(|ui| {
+                                ui.label(tr("project.workshop_id"));
+                                let mut id_text = state.project.workshop_id.map(|id| id.to_string()).unwrap_or_default();
+                                let response = ui.text_edit_singleline(&mut id_text)
+                                    .on_hover_text(tr("project.workshop_id_hint"));
+                                if response.changed() {
+                                    let id_text = id_text.trim();
+                                    if id_text.is_empty() {
+                                        state.project.workshop_id = None;
+                                    } else if let Ok(id) = id_text.parse::<u64>() {
+                                        state.project.workshop_id = Some(id).filter(|id| *id != 0);
+                                    }
+                                }
+                            });
+
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(tr("project.workshop_tags"));
+                                for tag in crate::models::ProjectSettings::WORKSHOP_TAGS {
+                                    let mut enabled = state.project.workshop_tags.iter().any(|t| t == tag);
+                                    if ui.checkbox(&mut enabled, tag).changed() {
+                                        state.project.set_workshop_tag(tag, enabled);
+                                    }
+                                }
+                            });
+                        });
+                    });
+
+                    ui.add_space(10.0);
+
+                    // 新轨道默认值
+                    ui.group(|ui| {
+                        ui.vertical(|ui| {
+                            use crate::utils::constants::file_ops::{MAX_DECIBELS, MAX_TRACK_DURATION, MIN_DECIBELS};
+                            ui.heading(tr("project.track_defaults"));
+                            ui.add_space(5.0);
+
+                            ui.horizontal(|ui| {
+                                ui.label(tr("project.default_decibels"));
+                                ui.add(egui::Slider::new(&mut state.track_defaults.decibels, MIN_DECIBELS..=MAX_DECIBELS));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label(tr("project.fallback_duration"));
+                                ui.add(egui::DragValue::new(&mut state.track_defaults.duration).clamp_range(1..=MAX_TRACK_DURATION))
+                                    .on_hover_text(tr("project.fallback_duration_hint"));
+                            });
+                            ui.checkbox(&mut state.track_defaults.cover_paa, tr("project.cover_paa"))
+                                .on_hover_text(tr("project.cover_paa_hint"));
+                            ui.checkbox(&mut state.track_defaults.scan_loudness, tr("project.scan_loudness"))
+                                .on_hover_text(tr("project.scan_loudness_hint"));
+                            ui.checkbox(&mut state.track_defaults.read_tag_names, tr("project.read_tag_names"))
+                                .on_hover_text(tr("project.read_tag_names_hint"));
+                        });
+                    });
+
                     ui.add_space(15.0);
                     
                     // 验证提示信息
@@ -560,7 +1198,7 @@ impl UIComponents {
                     if !is_valid {
                         ui.horizontal(|ui| {
                             ui.label(
-                                egui::RichText::new("⚠ 模组名称必须只包含英文字符")
+                                egui::RichText::new(tr("project.invalid_name"))
                                     .color(egui::Color32::from_rgb(220, 50, 50))
                             );
                         });
@@ -570,27 +1208,50 @@ impl UIComponents {
                     // 按钮区域
                     ui.horizontal(|ui| {
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("取消").clicked() {
+                            if ui.button(tr("common.cancel")).clicked() {
                                 should_close = true;
                             }
                             
                             // 只有当模组名称有效时才允许确定
-                            let confirm_button = ui.add_enabled(is_valid, egui::Button::new("确定"));
+                            let confirm_button = ui.add_enabled(is_valid, egui::Button::new(tr("common.ok")));
                             if confirm_button.clicked() {
                                 state.project.update_class_name();
+                                state.project.tracks_folder_name = state.project.tracks_folder();
                                 should_save = true;
                                 should_close = true;
                             }
                             
                             // 鼠标悬停提示
                             if !is_valid {
-                                confirm_button.on_hover_text("请先输入有效的英文模组名称");
+                                confirm_button.on_hover_text(tr("project.invalid_name_hint"));
                             }
                         });
                     });
                 });
             });
 
+        if should_import_cover {
+            if let Some(track_path) = cover_track {
+                match FileOperations::import_logo_from_cover_art(&track_path) {
+                    Ok(logo_path) => {
+                        state.file_operation_message = Some(format!("已使用轨道封面生成Logo: {}", logo_path.display()));
+                        state.set_project_logo(logo_path);
+                    }
+                    Err(e) => {
+                        warn!("从轨道封面生成Logo失败: {}", e);
+                        state.file_operation_message = Some(format!("从轨道封面生成Logo失败: {}", e));
+                    }
+                }
+            }
+        }
+
+        if should_save {
+            state.track_defaults = state.track_defaults.validated();
+            if let Err(e) = state.save_config() {
+                warn!("保存设置失败: {}", e);
+            }
+        }
+
         if should_close {
             state.show_project_settings = false;
         }
@@ -603,17 +1264,25 @@ impl UIComponents {
         }
 
         let mut append_tags = state.export_settings.append_tags;
+        let mut tag_class_names = state.export_settings.tag_class_names;
         let mut use_default_logo = state.export_settings.use_default_logo;
         let mut use_stringtable = state.export_settings.use_stringtable;
         let mut use_google_translate = state.export_settings.use_google_translate;
+        let mut only_export_changed = state.export_settings.only_export_changed;
+        let mut use_hardlinks = state.export_settings.use_hardlinks;
+        let mut copy_buffer_kb = state.export_settings.copy_buffer_kb;
+        let mut output_naming = state.export_settings.output_naming.clone();
+        let dirty_track_count = state.dirty_track_count();
         let mut should_close = false;
         let mut should_export = false;
+        let mut should_preview = false;
         let mut export_dir = None;
 
         let window_size = egui::Vec2::new(600.0, 500.0);
         let safe_pos = Self::calculate_safe_position(ctx, window_size, egui::Pos2::new(150.0, 150.0));
         
-        egui::Window::new("导出设置")
+        egui::Window::new(tr("export.title"))
+            .id(egui::Id::new("export_dialog"))
             .open(&mut state.show_export_dialog)
             .resizable(true)
             .default_size(window_size)
@@ -625,12 +1294,9 @@ impl UIComponents {
                     // 导出信息区域
                     ui.group(|ui| {
                         ui.vertical(|ui| {
-                            ui.heading("导出信息");
+                            ui.heading(tr("export.info"));
                             ui.add_space(5.0);
-                            ui.label(format!(
-                                "模组将在选择的导出目录下创建名为 {} 的文件夹。",
-                                state.project.mod_name_no_spaces()
-                            ));
+                            ui.label(trf("export.folder_note", &[&state.project.mod_name_no_spaces()]));
                         });
                     });
                     
@@ -639,27 +1305,34 @@ impl UIComponents {
                     // 导出选项区域
                     ui.group(|ui| {
                         ui.vertical(|ui| {
-                            ui.heading("导出选项");
+                            ui.heading(tr("export.options"));
                             ui.add_space(5.0);
                             
-                            ui.checkbox(&mut append_tags, "在轨道名称前添加标签");
+                            ui.checkbox(&mut append_tags, tr("export.append_tags"));
+
+                            ui.add_space(8.0);
+
+                            ui.checkbox(&mut tag_class_names, tr("export.tag_class_names"));
+                            if tag_class_names {
+                                ui.colored_label(
+                                    egui::Color32::from_rgb(200, 120, 0),
+                                    tr("export.tag_class_names_warning"),
+                                );
+                            }
                             
                             ui.add_space(8.0);
                             
-                            ui.checkbox(&mut use_default_logo, "使用默认Logo");
+                            ui.checkbox(&mut use_default_logo, tr("common.use_default_logo"));
 
                             ui.add_space(8.0);
 
                             ui.checkbox(
                                 &mut use_stringtable,
-                                "使用 Stringtable 本地化（支持中文曲目名，推荐）",
+                                tr("export.stringtable"),
                             );
                             if use_stringtable {
                                 ui.label(
-                                    egui::RichText::new(
-                                        "将生成 stringtable.xml，配置中使用 $STR_ 键；\
-                                         游戏语言为简体中文/繁体中文时显示中文曲名。",
-                                    )
+                                    egui::RichText::new(tr("export.stringtable_note"))
                                     .small()
                                     .weak(),
                                 );
@@ -668,42 +1341,141 @@ impl UIComponents {
 
                                 ui.checkbox(
                                     &mut use_google_translate,
-                                    "使用 Google 翻译生成英文回退（需联网，失败则用拼音）",
+                                    tr("export.google_translate"),
                                 );
                             } else {
                                 use_google_translate = false;
                             }
-                        });
-                    });
-                    
-                    ui.add_space(15.0);
-                    
-                    // 按钮区域
-                    ui.horizontal(|ui| {
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("取消").clicked() {
-                                should_close = true;
-                            }
-                            if ui.button("导出").clicked() {
+
+                            if state.project.mod_type == crate::models::ModType::Music {
+                                use crate::models::NamingScheme;
+                                ui.add_space(8.0);
+
+                                ui.horizontal(|ui| {
+                                    ui.label(tr("export.output_naming"));
+                                    ui.radio_value(&mut output_naming, NamingScheme::Pinyin, tr("export.naming_pinyin"));
+                                    ui.radio_value(&mut output_naming, NamingScheme::Original, tr("export.naming_original"));
+                                    let is_template = matches!(output_naming, NamingScheme::Template(_));
+                                    if ui.radio(is_template, tr("export.naming_template")).clicked() && !is_template {
+                                        output_naming = NamingScheme::Template(NamingScheme::DEFAULT_TEMPLATE.to_string());
+                                    }
+                                });
+                                if let NamingScheme::Template(template) = &mut output_naming {
+                                    ui.horizontal(|ui| {
+                                        ui.add_space(20.0);
+                                        ui.text_edit_singleline(template);
+                                    });
+                                    ui.label(
+                                        egui::RichText::new(tr("export.naming_template_note"))
+                                        .small()
+                                        .weak(),
+                                    );
+                                }
+                                if let Some(track) = state.tracks.first() {
+                                    let example = crate::utils::StringUtils::render_output_name(&state.project, track, 0, &output_naming, tag_class_names);
+                                    ui.label(
+                                        egui::RichText::new(trf("export.naming_example", &[&format!("{}{}", example, track.output_format.extension())]))
+                                        .small()
+                                        .weak(),
+                                    );
+                                }
+
+                                ui.add_space(8.0);
+
+                                ui.checkbox(&mut only_export_changed, tr("export.only_changed"));
+                                ui.label(
+                                    egui::RichText::new(trf("export.only_changed_note", &[&dirty_track_count]))
+                                    .small()
+                                    .weak(),
+                                );
+                            }
+
+                            ui.add_space(8.0);
+
+                            ui.checkbox(&mut use_hardlinks, tr("export.hardlinks"));
+                            if use_hardlinks {
+                                ui.colored_label(
+                                    egui::Color32::from_rgb(200, 120, 0),
+                                    tr("export.hardlinks_warning"),
+                                );
+                            } else {
+                                ui.label(
+                                    egui::RichText::new(tr("export.hardlinks_note"))
+                                    .small()
+                                    .weak(),
+                                );
+                            }
+
+                            ui.add_space(8.0);
+
+                            ui.horizontal(|ui| {
+                                ui.label(tr("export.copy_buffer"));
+                                ui.add(
+                                    egui::DragValue::new(&mut copy_buffer_kb)
+                                        .clamp_range(
+                                            0..=(crate::utils::constants::file_ops::MAX_COPY_BUFFER_SIZE / 1024) as u32,
+                                        )
+                                        .speed(16.0),
+                                );
+                            });
+                            ui.label(
+                                egui::RichText::new(tr("export.copy_buffer_note"))
+                                .small()
+                                .weak(),
+                            );
+                        });
+                    });
+                    
+                    ui.add_space(15.0);
+                    
+                    // 按钮区域
+                    ui.horizontal(|ui| {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button(tr("common.cancel")).clicked() {
+                                should_close = true;
+                            }
+                            if ui.button(tr("export.export")).clicked() {
                                 if let Some(dir) = FileOperations::select_export_directory() {
                                     export_dir = Some(dir);
                                     should_export = true;
                                     should_close = true;
                                 }
                             }
+                            if state.project.mod_type == crate::models::ModType::Music
+                                && ui.button(tr("export.preview_config")).clicked()
+                            {
+                                should_preview = true;
+                            }
                         });
                     });
                 });
             });
 
+        // 预览窗口打开时，影响配置文本的选项变化后立即刷新预览
+        let preview_options_changed = append_tags != state.export_settings.append_tags
+            || tag_class_names != state.export_settings.tag_class_names
+            || use_stringtable != state.export_settings.use_stringtable
+            || output_naming != state.export_settings.output_naming;
+
+        // 每帧写回，使勾选立即生效（游戏内顺序预览等会同步更新）
+        state.export_settings.append_tags = append_tags;
+        state.export_settings.tag_class_names = tag_class_names;
+        state.export_settings.use_default_logo = use_default_logo;
+        state.export_settings.use_stringtable = use_stringtable;
+        state.export_settings.use_google_translate = use_google_translate;
+        state.export_settings.only_export_changed = only_export_changed;
+        state.export_settings.use_hardlinks = use_hardlinks;
+        state.export_settings.copy_buffer_kb = copy_buffer_kb;
+        state.export_settings.output_naming = output_naming;
         if should_close {
-            state.export_settings.append_tags = append_tags;
-            state.export_settings.use_default_logo = use_default_logo;
-            state.export_settings.use_stringtable = use_stringtable;
-            state.export_settings.use_google_translate = use_google_translate;
             state.show_export_dialog = false;
         }
 
+        if should_preview || (state.show_cfg_preview && preview_options_changed) {
+            Self::refresh_cfg_preview(state);
+            state.show_cfg_preview = true;
+        }
+
         if should_export {
             if let Some(dir) = export_dir {
                 Self::export_mod(state, &dir);
@@ -711,6 +1483,79 @@ impl UIComponents {
         }
     }
 
+    /// 按当前项目和导出选项重新生成配置预览文本
+    fn refresh_cfg_preview(state: &mut AppState) {
+        let preview = match TemplateEngine::new() {
+            Ok(template_engine) => template_engine.render_cfg_music_preview(
+                &state.project,
+                &state.tracks,
+                &state.export_settings.output_naming,
+                state.export_settings.append_tags,
+                state.export_settings.tag_class_names,
+                state.export_settings.use_stringtable,
+            ),
+            Err(e) => format!("生成配置预览失败: {:#}", e),
+        };
+        state.cfg_preview_text = Some(preview);
+    }
+
+    /// 显示配置预览窗口（只读）
+    pub fn show_cfg_preview_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_cfg_preview {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [700.0, 500.0].into(), [120.0, 120.0].into());
+        let mut should_close = false;
+
+        egui::Window::new(tr("export.preview_title"))
+            .id(egui::Id::new("cfg_preview_dialog"))
+            .open(&mut state.show_cfg_preview)
+            .default_pos(safe_pos)
+            .resizable(true)
+            .default_size([700.0, 500.0])
+            .min_size([400.0, 250.0])
+            .max_size([1000.0, 800.0])
+            .show(ctx, |ui| {
+                ui.set_min_height(ui.available_height());
+
+                ui.label(egui::RichText::new(tr("export.preview_note")).small().weak());
+                ui.add_space(5.0);
+
+                if let Some(ref text) = state.cfg_preview_text {
+                    Self::show_scrollable_result_content(
+                        ui,
+                        text,
+                        tr("export.preview_title"),
+                        &[],
+                        &["生成配置预览失败"],
+                        &["// ====="],
+                    );
+                }
+
+                ui.add_space(10.0);
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button(tr("common.close")).clicked() {
+                        should_close = true;
+                    }
+
+                    if ui.button(tr("common.copy")).clicked() {
+                        if let Some(ref text) = state.cfg_preview_text {
+                            ui.output_mut(|o| o.copied_text = text.clone());
+                        }
+                    }
+                });
+            });
+
+        if should_close {
+            state.show_cfg_preview = false;
+        }
+        if !state.show_cfg_preview {
+            state.cfg_preview_text = None;
+        }
+    }
+
     /// 显示关于对话框
     pub fn show_about_dialog(ctx: &egui::Context, state: &mut AppState, uptime: std::time::Duration) {
         if !state.show_about {
@@ -722,7 +1567,8 @@ impl UIComponents {
         let window_size = egui::Vec2::new(400.0, 300.0);
         let safe_pos = Self::calculate_safe_position(ctx, window_size, egui::Pos2::new(200.0, 200.0));
         
-        egui::Window::new("关于")
+        egui::Window::new(tr("about.title"))
+            .id(egui::Id::new("about_dialog"))
             .open(&mut state.show_about)
             .resizable(true)
             .default_size(window_size)
@@ -734,21 +1580,21 @@ impl UIComponents {
                     // 软件信息区域
                     ui.group(|ui| {
                         ui.vertical(|ui| {
-                            ui.heading("宙斯音乐制作器");
+                            ui.heading(tr("about.app_name"));
                             ui.add_space(5.0);
                             
                             ui.horizontal(|ui| {
-                                ui.label("版本:");
+                                ui.label(tr("about.version"));
                                 ui.label("2.0.0");
                             });
                             
                             ui.horizontal(|ui| {
-                                ui.label("作者:");
+                                ui.label(tr("common.author"));
                                 ui.label("ViVi141");
                             });
                             
                             ui.horizontal(|ui| {
-                                ui.label("邮箱:");
+                                ui.label(tr("about.email"));
                                 ui.label("747384120@qq.com");
                             });
                             
@@ -756,16 +1602,16 @@ impl UIComponents {
                             
                             // 显示运行时长
                             let uptime_text = if uptime.as_secs() < 60 {
-                                format!("运行时长: {}秒", uptime.as_secs())
+                                trf("about.uptime_secs", &[&uptime.as_secs()])
                             } else if uptime.as_secs() < 3600 {
-                                format!("运行时长: {}分{}秒", uptime.as_secs() / 60, uptime.as_secs() % 60)
+                                trf("about.uptime_mins", &[&(uptime.as_secs() / 60), &(uptime.as_secs() % 60)])
                             } else {
                                 let hours = uptime.as_secs() / 3600;
                                 let minutes = (uptime.as_secs() % 3600) / 60;
-                                format!("运行时长: {}小时{}分", hours, minutes)
+                                trf("about.uptime_hours", &[&hours, &minutes])
                             };
                             ui.horizontal(|ui| {
-                                ui.label("运行时长:");
+                                ui.label(tr("about.uptime"));
                                 ui.label(uptime_text);
                             });
                         });
@@ -776,10 +1622,10 @@ impl UIComponents {
                     // 描述区域
                     ui.group(|ui| {
                         ui.vertical(|ui| {
-                            ui.heading("软件描述");
+                            ui.heading(tr("about.description"));
                             ui.add_space(5.0);
-                            ui.label("增强版Arma 3音乐模组生成工具");
-                            ui.label("用于自动生成Arma 3音乐模组文件结构");
+                            ui.label(tr("about.description_line1"));
+                            ui.label(tr("about.description_line2"));
                         });
                     });
                     
@@ -788,7 +1634,7 @@ impl UIComponents {
                     // 按钮区域
                     ui.horizontal(|ui| {
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("关闭").clicked() {
+                            if ui.button(tr("common.close")).clicked() {
                                 should_close = true;
                             }
                         });
@@ -804,6 +1650,7 @@ impl UIComponents {
     /// 显示轨道编辑器
     pub fn show_track_editor_dialog(ctx: &egui::Context, state: &mut AppState) {
         if !state.show_track_editor {
+            state.track_editor_ffmpeg_available = None;
             return;
         }
 
@@ -815,8 +1662,19 @@ impl UIComponents {
             }
         };
 
+        // 类名随标签实时变化，在借用轨道之前计算
+        let config_class_name = state.track_config_class_name(track_index).unwrap_or_default();
+        // 只在编辑器打开时检测一次 FFmpeg，避免每帧查找可执行文件
+        let ffmpeg_available = *state.track_editor_ffmpeg_available.get_or_insert_with(|| {
+            crate::ffmpeg_plugin::FFmpegPlugin::new()
+                .map(|plugin| plugin.check_ffmpeg_available())
+                .unwrap_or(false)
+        });
+
         let track = &mut state.tracks[track_index];
         let mut should_close = false;
+        let mut should_duplicate = false;
+        let mut should_analyze_loudness = false;
         
         let window_size = egui::Vec2::new(500.0, 600.0);
         let safe_pos = Self::calculate_safe_position(ctx, window_size, egui::Pos2::new(100.0, 100.0));
@@ -838,15 +1696,39 @@ impl UIComponents {
                             
                             ui.horizontal(|ui| {
                                 ui.label("轨道名称:");
-                                ui.text_edit_singleline(&mut track.track_name);
+                                if ui.text_edit_singleline(&mut track.track_name).changed() {
+                                    track.mark_dirty();
+                                }
                             });
                             
                             ui.add_space(8.0);
                             
                             ui.horizontal(|ui| {
                                 ui.label("标签:");
-                                ui.text_edit_singleline(&mut track.tag);
+                                if ui.text_edit_singleline(&mut track.tag).changed() {
+                                    track.mark_dirty();
+                                }
+                            });
+
+                            ui.add_space(8.0);
+
+                            ui.horizontal(|ui| {
+                                ui.label("类名:");
+                                let mut class_name_text = config_class_name.as_str();
+                                ui.add(egui::TextEdit::singleline(&mut class_name_text).text_color(ui.visuals().weak_text_color()))
+                                    .on_hover_text("导出时生成的 CfgMusic 类名，由项目类名、序号和标签（启用标签类名时）决定，可用于 playMusic");
                             });
+
+                            ui.add_space(8.0);
+
+                            // 备注只用于整理，不写入配置，因此不标记为已更改
+                            ui.label("备注:");
+                            ui.add(
+                                egui::TextEdit::multiline(&mut track.notes)
+                                    .desired_rows(2)
+                                    .desired_width(f32::INFINITY)
+                                    .hint_text("使用提示，例如：伏击、胜利、撤离"),
+                            );
                         });
                     });
                     
@@ -860,20 +1742,115 @@ impl UIComponents {
                             
                             ui.horizontal(|ui| {
                                 ui.label("时长 (秒):");
-                                ui.add(egui::Slider::new(&mut track.duration, 0..=3600));
+                                if ui.add(egui::Slider::new(&mut track.duration, 0..=crate::utils::constants::file_ops::MAX_TRACK_DURATION)).changed() {
+                                    track.duration_estimated = false;
+                                    track.mark_dirty();
+                                }
                             });
+                            if track.duration_estimated {
+                                ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "⚠️ 无法读取实际时长，当前为默认值");
+                            }
                             
                             ui.add_space(8.0);
                             
                             ui.horizontal(|ui| {
                                 ui.label("分贝 (dB):");
-                                ui.add(egui::Slider::new(&mut track.decibels, -10..=5));
+                                if ui.add(egui::Slider::new(
+                                    &mut track.decibels,
+                                    crate::utils::constants::file_ops::MIN_DECIBELS..=crate::utils::constants::file_ops::MAX_DECIBELS,
+                                )).changed() {
+                                    track.mark_dirty();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                let analyzing = state.task_manager.get_current_progress().is_some_and(|task| {
+                                    task.task_type == TaskType::LoudnessAnalysis && task.status == TaskStatus::Running
+                                });
+                                if analyzing {
+                                    ui.spinner();
+                                    ui.label("正在分析音量...");
+                                } else {
+                                    let task_running = state.task_manager.is_running();
+                                    if ui.add_enabled(ffmpeg_available && !task_running, egui::Button::new("自动分析音量"))
+                                        .on_hover_text("使用 FFmpeg 测量综合响度（EBU R128），并将分贝设为使其接近参考响度的值")
+                                        .on_disabled_hover_text(if task_running { "任务进行中" } else { crate::utils::constants::app::FFMPEG_NOT_READY_HINT })
+                                        .clicked()
+                                    {
+                                        should_analyze_loudness = true;
+                                    }
+                                    if !ffmpeg_available {
+                                        ui.colored_label(egui::Color32::from_rgb(200, 50, 50), crate::utils::constants::app::FFMPEG_NOT_READY_HINT);
+                                    }
+                                }
                             });
+                            if let (Some(lufs), Some(suggested)) = (track.loudness_lufs, track.suggested_decibels()) {
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("响度: {:.1} LUFS，建议 {} dB", lufs, suggested));
+                                    if ui.add_enabled(track.decibels != suggested, egui::Button::new("应用建议")).clicked() {
+                                        track.decibels = suggested;
+                                        track.mark_dirty();
+                                    }
+                                });
+                            } else if let (Some(rms_dbfs), Some(suggested)) = (track.rms_dbfs, track.suggested_decibels()) {
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("响度: {:.1} dBFS (RMS)，建议 {} dB", rms_dbfs, suggested));
+                                    if ui.add_enabled(track.decibels != suggested, egui::Button::new("应用建议")).clicked() {
+                                        track.decibels = suggested;
+                                        track.mark_dirty();
+                                    }
+                                });
+                            }
+
+                            if let Some(ref logo_path) = track.logo_path {
+                                ui.add_space(8.0);
+                                ui.horizontal(|ui| {
+                                    ui.label("轨道Logo:");
+                                    ui.label(logo_path.file_name().unwrap_or_default().to_string_lossy())
+                                        .on_hover_text(logo_path.display().to_string());
+                                });
+                            }
                         });
                     });
                     
                     ui.add_space(10.0);
                     
+                    // 导出格式覆盖（默认使用项目设置）
+                    ui.collapsing("导出格式（高级）", |ui| {
+                        use crate::models::TrackOutputFormat;
+                        let previous_format = track.output_format;
+                        let is_ogg = matches!(track.output_format, TrackOutputFormat::Ogg { .. });
+                        ui.radio_value(&mut track.output_format, TrackOutputFormat::ProjectDefault,
+                            TrackOutputFormat::ProjectDefault.display_name());
+                        if ui.radio(is_ogg, "重新编码 OGG").clicked() && !is_ogg {
+                            track.output_format = TrackOutputFormat::Ogg { quality: 5 };
+                        }
+                        if let TrackOutputFormat::Ogg { quality } = &mut track.output_format {
+                            ui.horizontal(|ui| {
+                                ui.add_space(20.0);
+                                ui.label("质量:");
+                                ui.add(egui::Slider::new(quality, 0..=10));
+                            });
+                        }
+                        ui.radio_value(&mut track.output_format, TrackOutputFormat::Wav,
+                            TrackOutputFormat::Wav.display_name());
+                        
+                        if track.output_format.needs_conversion() {
+                            ui.label(egui::RichText::new("导出时将使用 FFmpeg 转换此轨道；WAV 体积较大，仅建议用于短音效")
+                                .small()
+                                .weak());
+                        }
+                        if track.output_format != previous_format {
+                            track.mark_dirty();
+                        }
+                    });
+                    
+                    // 波形与裁剪点
+                    ui.collapsing("裁剪（波形）", |ui| {
+                        Self::render_trim_editor(ui, track, &mut state.waveform_cache, &mut state.trim_suggestion_pending, &mut state.trim_edit);
+                    });
+                    
+                    ui.add_space(10.0);
+                    
                     // 状态显示区域
                     if track.is_modified() {
                         ui.group(|ui| {
@@ -888,6 +1865,13 @@ impl UIComponents {
                     ui.horizontal(|ui| {
                         if ui.button("恢复默认").clicked() {
                             track.reset_to_default();
+                            track.mark_dirty();
+                        }
+                        if ui.button("复制轨道")
+                            .on_hover_text("在当前轨道之后插入一个指向同一文件的副本，可设置不同的标签和分贝")
+                            .clicked()
+                        {
+                            should_duplicate = true;
                         }
                         
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -908,10 +1892,210 @@ impl UIComponents {
                 StringUtils::is_internal_rename_result(&track.track_name);
             state.show_track_editor = false;
         }
+
+        if should_analyze_loudness {
+            state.should_analyze_loudness = true;
+        }
+
+        // 复制后编辑器切换到新副本
+        if should_duplicate {
+            if let Some(new_index) = state.duplicate_track(track_index) {
+                state.file_operation_message = Some(format!("已复制轨道: {}", state.tracks[new_index].track_name));
+            }
+        }
+    }
+
+    /// 渲染轨道的波形与可拖动的裁剪标记
+    fn render_trim_editor(
+        ui: &mut egui::Ui,
+        track: &mut crate::models::Track,
+        waveform_cache: &mut crate::models::BackgroundPathCache<Result<crate::audio::Waveform, String>>,
+        trim_suggestion_pending: &mut Option<std::path::PathBuf>,
+        trim_edit: &mut Option<(std::path::PathBuf, crate::models::TrackTrim)>,
+    ) {
+        use crate::models::TrackTrim;
+
+        // 波形在后台分析完成后应用建议的裁剪点
+        if trim_suggestion_pending.as_ref() == Some(&track.path) {
+            if let Some(result) = waveform_cache.get(&track.path) {
+                *trim_suggestion_pending = None;
+                if let Some((start_secs, end_secs)) = result.as_ref().ok().and_then(|waveform| waveform.suggest_trim()) {
+                    *trim_edit = Some((track.path.clone(), TrackTrim { start_secs, end_secs }));
+                }
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if trim_suggestion_pending.as_ref() == Some(&track.path) {
+                ui.spinner();
+                ui.label("正在分析波形...");
+            } else if ui.button("自动建议裁剪点").on_hover_text("分析波形，跳过开头和结尾的静音").clicked() {
+                waveform_cache.get_or_request(&track.path);
+                *trim_suggestion_pending = Some(track.path.clone());
+            }
+            if track.trim.is_some() && ui.button("清除裁剪").clicked() {
+                track.trim = None;
+                track.duration = track.original_duration;
+                track.mark_dirty();
+                *trim_edit = None;
+            }
+        });
+
+        if waveform_cache.is_loading() {
+            ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+        }
+        let waveform = match waveform_cache.get(&track.path) {
+            Some(Ok(waveform)) => waveform,
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, format!("无法分析波形: {}", e));
+                return;
+            }
+            None => {
+                if let Some(trim) = track.trim {
+                    ui.label(format!("当前裁剪: {:.2}s - {:.2}s", trim.start_secs, trim.end_secs));
+                }
+                return;
+            }
+        };
+        if waveform.duration_secs <= 0.0 {
+            return;
+        }
+
+        // 切换轨道后从已保存的裁剪（或完整范围）开始编辑
+        if trim_edit.as_ref().map_or(true, |(path, _)| path != &track.path) {
+            let trim = track.trim.unwrap_or(TrackTrim { start_secs: 0.0, end_secs: waveform.duration_secs });
+            *trim_edit = Some((track.path.clone(), trim));
+        }
+        let Some((_, edit)) = trim_edit.as_mut() else {
+            return;
+        };
+
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), 80.0),
+            egui::Sense::click_and_drag(),
+        );
+        let to_x = |secs: f64| rect.left() + (secs / waveform.duration_secs) as f32 * rect.width();
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, egui::Color32::from_gray(30));
+
+        // 每列取对应时间段的最大峰值
+        let columns = rect.width().max(1.0) as usize;
+        let peaks = &waveform.peaks;
+        for column in 0..columns {
+            let from = column * peaks.len() / columns;
+            let to = ((column + 1) * peaks.len() / columns).max(from + 1).min(peaks.len());
+            let peak = peaks.get(from..to).map_or(0.0, |range| range.iter().fold(0.0f32, |m, &p| m.max(p)));
+            let x = rect.left() + column as f32 + 0.5;
+            let half = peak * rect.height() / 2.0;
+            painter.line_segment(
+                [egui::pos2(x, rect.center().y - half), egui::pos2(x, rect.center().y + half)],
+                egui::Stroke::new(1.0, egui::Color32::from_rgb(90, 160, 230)),
+            );
+        }
+
+        // 裁掉的部分加暗显示
+        let dim = egui::Color32::from_black_alpha(160);
+        painter.rect_filled(egui::Rect::from_x_y_ranges(rect.left()..=to_x(edit.start_secs), rect.y_range()), 0.0, dim);
+        painter.rect_filled(egui::Rect::from_x_y_ranges(to_x(edit.end_secs)..=rect.right(), rect.y_range()), 0.0, dim);
+        for secs in [edit.start_secs, edit.end_secs] {
+            let x = to_x(secs);
+            painter.line_segment([egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())], egui::Stroke::new(2.0, egui::Color32::YELLOW));
+        }
+
+        // 拖动时移动离指针最近的标记
+        if response.dragged() || response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let secs = (((pos.x - rect.left()) / rect.width()) as f64 * waveform.duration_secs)
+                    .clamp(0.0, waveform.duration_secs);
+                if (secs - edit.start_secs).abs() <= (secs - edit.end_secs).abs() {
+                    edit.start_secs = secs.min(edit.end_secs);
+                } else {
+                    edit.end_secs = secs.max(edit.start_secs);
+                }
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "开始: {:.2}s  结束: {:.2}s  （裁剪后 {} 秒）",
+                edit.start_secs,
+                edit.end_secs,
+                edit.duration_secs()
+            ));
+            let unchanged = track.trim == Some(*edit);
+            if ui.add_enabled(!unchanged, egui::Button::new("接受裁剪"))
+                .on_hover_text("导出时按此范围裁剪音频，并将时长设为裁剪后的长度")
+                .clicked()
+            {
+                track.trim = Some(*edit);
+                track.duration = edit.duration_secs();
+                track.mark_dirty();
+            }
+        });
+    }
+
+    /// 根据后台轨道检查结果完成 Zeus 准备并导出，在结果中说明每一步
+    ///
+    /// 推荐的导出设置只用于本次导出，不修改用户保存的导出设置。
+    pub(crate) fn finish_zeus_preparation(state: &mut AppState, probes: &[crate::models::ZeusTrackProbe]) {
+        let report = state.prepare_for_zeus(probes);
+        let mut preparation = String::from("Zeus 准备步骤:\n");
+        for step in &report.steps {
+            preparation.push_str(&format!("  {}\n", step));
+        }
+
+        if !report.blocking_issues.is_empty() {
+            let mut result_message = format!("为 Zeus 准备未完成，未执行导出！\n\n{}\n需要处理的问题:\n", preparation);
+            for issue in &report.blocking_issues {
+                result_message.push_str(&format!("  {}\n", issue));
+            }
+            state.export_result = Some(result_message);
+            state.show_export_result = true;
+            return;
+        }
+
+        let Some(export_dir) = FileOperations::select_export_directory() else {
+            state.file_operation_message = Some("已完成 Zeus 准备，未选择导出目录".to_string());
+            return;
+        };
+
+        let zeus_settings = state.zeus_export_settings();
+        Self::export_mod_with(state, &export_dir, zeus_settings, format!("{}\n", preparation));
+        info!("已为 Zeus 准备，开始导出模组");
     }
 
-    /// 导出模组
+    /// 导出模组：在界面线程完成检查，复制和生成配置交给后台任务（由 app.rs update 启动）
     fn export_mod(state: &mut AppState, export_dir: &std::path::Path) {
+        let export_settings = state.export_settings.clone();
+        Self::export_mod_with(state, export_dir, export_settings, String::new());
+    }
+
+    /// 按指定的导出设置导出模组，`result_prefix` 附加在导出结果之前
+    fn export_mod_with(
+        state: &mut AppState,
+        export_dir: &std::path::Path,
+        export_settings: crate::models::ExportSettings,
+        result_prefix: String,
+    ) {
+        state.export_dir_not_writable = false;
+        if state.task_manager.is_running() || state.pending_mod_export.is_some() {
+            state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+            return;
+        }
+        let report = |state: &mut AppState, message: String| {
+            state.export_result = Some(format!("{}{}", result_prefix, message));
+            state.show_export_result = true;
+        };
+
+        // 无效的模组名称或类名会生成 Arma 无法加载的配置
+        if let Err(problems) = state.project.validate() {
+            report(state, format!(
+                "导出已阻止！\n\n项目设置存在以下问题，请在“文件 > 项目设置 > 常规”中修改：\n{}",
+                problems.iter().map(|problem| format!("• {}", problem)).collect::<Vec<_>>().join("\n")
+            ));
+            return;
+        }
+
         // 根据模组类型检查不同的数据
         let has_content = match state.project.mod_type {
             crate::models::ModType::Music => !state.tracks.is_empty(),
@@ -923,166 +2107,99 @@ impl UIComponents {
                 crate::models::ModType::Music => "导出失败：没有音频轨道可以导出",
                 crate::models::ModType::Video => "导出失败：没有视频文件可以导出",
             };
-            state.export_result = Some(error_msg.to_string());
-            state.show_export_result = true;
+            report(state, error_msg.to_string());
+            return;
+        }
+        // 复制任何文件前确认导出目录可写，避免导出到一半才失败
+        if let Err(e) = FileOperations::check_export_dir_writable(export_dir) {
+            warn!("导出目录不可写 {:?}: {:#}", export_dir, e);
+            state.export_dir_not_writable = FileOperations::is_permission_denied(&e);
+            report(state, FileOperations::export_dir_error_message(export_dir, &e));
             return;
         }
+        state.remember_export_dir(export_dir);
 
-        let mut success_steps = Vec::new();
-        let mut error_steps = Vec::new();
+        let mut warnings = Vec::new();
 
-        match FileOperations::create_mod_structure(&state.project, export_dir) {
-            Ok(mod_dir) => {
-                success_steps.push("创建模组目录结构".to_string());
-                
-                // 根据模组类型复制不同的文件
-                let (files, skipped_count, file_type) = match state.project.mod_type {
-                    crate::models::ModType::Music => {
-                        match FileOperations::copy_track_files_pinyin(&state.tracks, &mod_dir) {
-                            Ok((files, skipped_count)) => (files, skipped_count, "轨道文件"),
-                            Err(e) => {
-                                error_steps.push(format!("复制轨道文件失败: {}", e));
-                                return;
-                            }
-                        }
-                    }
-                    crate::models::ModType::Video => {
-                        match FileOperations::copy_video_files_pinyin(&state.video_files, &mod_dir) {
-                            Ok((files, skipped_count)) => (files, skipped_count, "视频文件"),
-                            Err(e) => {
-                                error_steps.push(format!("复制视频文件失败: {}", e));
-                                return;
-                            }
-                        }
-                    }
-                };
-                
-                let copied_files = files.len();
-                if skipped_count > 0 {
-                    success_steps.push(format!("复制{} ({} 个，跳过 {} 个重复)", file_type, copied_files, skipped_count));
-                } else {
-                    success_steps.push(format!("复制{} ({} 个)", file_type, copied_files));
-                }
-                
-                // 复制Logo文件
-                match FileOperations::copy_logo_file(&state.project, &mod_dir) {
-                    Ok(_) => success_steps.push("复制Logo文件".to_string()),
-                    Err(e) => error_steps.push(format!("复制Logo文件失败: {}", e)),
-                }
+        // 防止导出到源文件所在目录
+        let source_paths: Vec<&std::path::Path> = match state.project.mod_type {
+            crate::models::ModType::Music => state.tracks.iter().map(|track| track.path.as_path()).collect(),
+            crate::models::ModType::Video => state.video_files.iter().map(|video| video.path.as_path()).collect(),
+        };
+        let overlaps = FileOperations::check_export_dir_overlap(&state.project, export_dir, &source_paths);
+        let blocking: Vec<_> = overlaps
+            .iter()
+            .filter_map(|overlap| match overlap {
+                crate::file_ops::ExportDirOverlap::SourceInsideOutput(dir) => Some(dir),
+                crate::file_ops::ExportDirOverlap::ExportDirIsSource(dir) => Some(dir),
+                crate::file_ops::ExportDirOverlap::OutputInsideSource(_) => None,
+            })
+            .collect();
+        if !blocking.is_empty() {
+            let exports_into_source = overlaps
+                .iter()
+                .any(|overlap| matches!(overlap, crate::file_ops::ExportDirOverlap::ExportDirIsSource(_)));
+            let mut result_message = if exports_into_source {
+                format!(
+                    "导出已阻止！\n\n导出目录 {} 就是源文件所在目录，导出会写入正在读取的文件夹。\n\n冲突的源文件目录:\n",
+                    export_dir.display()
+                )
+            } else {
+                format!(
+                    "导出已阻止！\n\n模组输出目录 {} 包含源文件所在目录，导出会覆盖或重复复制源文件。\n\n冲突的源文件目录:\n",
+                    export_dir.join(state.project.mod_name_no_spaces()).display()
+                )
+            };
+            for dir in &blocking {
+                result_message.push_str(&format!("  {}\n", dir.display()));
+            }
+            result_message.push_str("\n请选择其他导出目录。");
+            report(state, result_message);
+            return;
+        }
+        for overlap in &overlaps {
+            if let crate::file_ops::ExportDirOverlap::OutputInsideSource(dir) = overlap {
+                warnings.push(format!("模组输出目录位于源文件目录中，建议导出到素材库之外: {}", dir.display()));
+            }
+        }
 
-                // 复制Steam Logo
-                match FileOperations::copy_steam_logo(&mod_dir) {
-                    Ok(_) => success_steps.push("复制Steam Logo".to_string()),
-                    Err(e) => error_steps.push(format!("复制Steam Logo失败: {}", e)),
+        // 检查生成的类名和游戏内名称，问题作为警告显示在导出结果中
+        state.name_fix_indices.clear();
+        if state.project.mod_type == crate::models::ModType::Music {
+            let name_issues = TemplateEngine::check_track_names(
+                &state.project,
+                &state.tracks,
+                export_settings.append_tags,
+                export_settings.tag_class_names,
+            );
+            for issue in &name_issues {
+                warnings.push(format!("名称: {}", issue.message));
+                if issue.fixable && !state.name_fix_indices.contains(&issue.track_index) {
+                    state.name_fix_indices.push(issue.track_index);
                 }
+            }
+        }
 
-                // 生成配置文件
-                let template_engine = TemplateEngine::default();
-                let config_result = match state.project.mod_type {
-                    crate::models::ModType::Music => {
-                        template_engine.generate_all_configs(
-                            &state.project,
-                            &state.tracks,
-                            &files,
-                            state.export_settings.append_tags,
-                            state.export_settings.use_stringtable,
-                            state.export_settings.use_google_translate,
-                            &mod_dir,
-                        )
-                    }
-                    crate::models::ModType::Video => {
-                        // 为视频模组生成配置文件
-                        template_engine.generate_all_configs(
-                            &state.project,
-                            &[], // 视频模组不需要音频轨道
-                            &files,
-                            state.export_settings.append_tags,
-                            state.export_settings.use_stringtable,
-                            state.export_settings.use_google_translate,
-                            &mod_dir,
-                        )
-                    }
-                };
-                
-                match config_result {
-                    Ok(_) => {
-                        if state.export_settings.use_stringtable {
-                            if state.export_settings.use_google_translate {
-                                success_steps.push(
-                                    "生成 stringtable.xml（含 Google 翻译英文回退）".to_string(),
-                                );
-                            } else {
-                                success_steps.push("生成 stringtable.xml（拼音英文回退）".to_string());
-                            }
-                        }
-                        success_steps.push("生成配置文件".to_string());
-                        
-                        // 构建最终结果消息
-                        let mut result_message = format!("模组导出成功！\n\n输出目录: {}\n\n", mod_dir.display());
-                        
-                        if !success_steps.is_empty() {
-                            result_message.push_str("成功步骤:\n");
-                            for step in &success_steps {
-                                result_message.push_str(&format!("  {}\n", step));
-                            }
-                        }
-                        
-                        if !error_steps.is_empty() {
-                            result_message.push_str("\n警告信息:\n");
-                            for step in &error_steps {
-                                result_message.push_str(&format!("  {}\n", step));
-                            }
-                        }
-                        
-                        let item_count = match state.project.mod_type {
-                            crate::models::ModType::Music => state.tracks.len(),
-                            crate::models::ModType::Video => state.video_files.len(),
-                        };
-                        let item_type = match state.project.mod_type {
-                            crate::models::ModType::Music => "轨道数量",
-                            crate::models::ModType::Video => "视频数量",
-                        };
-                        result_message.push_str(&format!("\n统计信息:\n  {}: {}\n  模组名称: {}", 
-                            item_type, item_count, state.project.mod_name
-                        ));
-                        
-                        state.export_result = Some(result_message);
-                        state.show_export_result = true;
-                        info!("模组导出成功: {:?}", mod_dir);
-                    },
-                    Err(e) => {
-                        error_steps.push(format!("生成配置文件失败: {}", e));
-                        let mut result_message = format!("模组导出失败！\n\n输出目录: {}\n\n", mod_dir.display());
-                        
-                        if !success_steps.is_empty() {
-                            result_message.push_str("成功步骤:\n");
-                            for step in &success_steps {
-                                result_message.push_str(&format!("  {}\n", step));
-                            }
-                        }
-                        
-                        if !error_steps.is_empty() {
-                            result_message.push_str("\n错误信息:\n");
-                            for step in &error_steps {
-                                result_message.push_str(&format!("  {}\n", step));
-                            }
-                        }
-                        
-                        state.export_result = Some(result_message);
-                        state.show_export_result = true;
-                    }
-                }
-            }
-            Err(e) => {
-                error_steps.push(format!("创建模组结构失败: {}", e));
-                let result_message = format!("模组导出失败！\n\n输出目录: {}\n\n错误信息:\n  {}", 
-                    export_dir.display(), 
-                    error_steps.join("\n  ")
-                );
-                state.export_result = Some(result_message);
-                state.show_export_result = true;
-            }
+        state.pending_mod_export = Some(crate::models::ModExportJob {
+            project: state.project.clone(),
+            tracks: state.tracks.clone(),
+            video_files: state.video_files.clone(),
+            export_settings,
+            export_dir: export_dir.to_path_buf(),
+            warnings,
+            result_prefix,
+        });
+    }
+
+    /// 后台导出结束：在导出结果对话框中显示成功、失败或取消
+    pub(crate) fn finish_mod_export(state: &mut AppState, outcome: crate::models::ModExportOutcome) {
+        if outcome.manifest_saved {
+            state.clear_dirty_flags();
         }
+        state.export_dir_not_writable = outcome.permission_denied;
+        state.export_result = Some(outcome.message);
+        state.export_result_dir = outcome.mod_dir;
+        state.show_export_result = true;
     }
 
     /// 构建插件
@@ -1091,13 +2208,11 @@ impl UIComponents {
             match FileOperations::create_pbo_mod_structure(&state.project, pbo_path, &export_dir) {
                 Ok(mod_dir) => {
                     // 生成mod.cpp
-                    let template_engine = TemplateEngine::default();
                     let mod_path = mod_dir.join("mod.cpp");
-                    if let Err(e) = template_engine.generate_mod_cpp(
-                        &state.project,
-                        state.export_settings.use_stringtable,
-                        &mod_path,
-                    ) {
+                    if let Err(e) = TemplateEngine::new().and_then(|template_engine| {
+                        template_engine.generate_mod_cpp(&state.project, state.export_settings.use_stringtable, &mod_path)?;
+                        template_engine.generate_meta_cpp(&state.project, state.project.workshop_id, &mod_dir.join("meta.cpp"))
+                    }) {
                         warn!("生成mod.cpp失败: {}", e);
                         // 显示错误提示
                         state.export_result = Some(format!("插件构建失败！\n\n错误: {}", e));
@@ -1109,11 +2224,12 @@ impl UIComponents {
                     
                     // 显示成功提示
                     let success_message = format!(
-                        "🎉 插件构建成功！\n\n📁 输出目录: {}\n📄 PBO文件: {}\n📝 模组文件: mod.cpp\n\n插件已准备就绪，可以安装到Arma 3中！",
+                        "🎉 插件构建成功！\n\n📁 输出目录: {}\n📄 PBO文件: {}\n📝 模组文件: mod.cpp、meta.cpp\n\n插件已准备就绪，可以安装到Arma 3中！",
                         mod_dir.display(),
                         pbo_path.display()
                     );
                     state.export_result = Some(success_message);
+                    state.export_result_dir = Some(mod_dir.clone());
                     state.show_export_result = true;
                 }
                 Err(e) => {
@@ -1126,6 +2242,191 @@ impl UIComponents {
         }
     }
 
+    /// 生成未打包的插件源文件夹（供 Addon Builder 等工具打包）
+    fn build_addon_source(state: &mut AppState) {
+        if let Err(errors) = state.project.validate() {
+            state.export_result = Some(format!("插件源文件生成失败！\n\n错误信息:\n{}", errors.join("\n")));
+            state.show_export_result = true;
+            return;
+        }
+        let Some(export_dir) = FileOperations::select_export_directory() else {
+            return;
+        };
+
+        match FileOperations::create_addon_source_structure(&state.project, &state.tracks, &export_dir, &state.export_settings) {
+            Ok((mod_dir, addon_dir, files)) => {
+                info!("插件源文件生成成功: {:?}", addon_dir);
+                let prefix = state.project.mod_name_no_spaces();
+                state.export_result = Some(format!(
+                    "🎉 插件源文件生成成功！\n\n📁 输出目录: {}\n\n目录结构:\n  {}/\n    mod.cpp、meta.cpp、Logo\n    Addons/{}/\n      $PBOPREFIX$ ({})\n      config.cpp\n      FileListWithMusicTracks.hpp{}\n      {}/ ({} 个轨道)\n\n📝 使用 Arma 3 Tools 的 Addon Builder 或 Mikero 的 pboProject 将 Addons/{} 打包为 PBO，\n   放到 Addons 目录并删除源文件夹后即可作为模组加载。",
+                    mod_dir.display(),
+                    mod_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                    state.project.class_name,
+                    prefix,
+                    if state.export_settings.use_stringtable { "\n      stringtable.xml" } else { "" },
+                    state.project.tracks_folder(),
+                    files.len(),
+                    state.project.class_name,
+                ));
+                state.export_result_dir = Some(mod_dir);
+                state.show_export_result = true;
+            }
+            Err(e) => {
+                warn!("生成插件源文件失败: {:#}", e);
+                state.export_result = Some(format!("插件源文件生成失败！\n\n错误: {:#}", e));
+                state.show_export_result = true;
+            }
+        }
+    }
+
+    /// 显示创建容器模组对话框（mod.cpp + Logo + 多个已有PBO，不需要轨道）
+    pub fn show_container_mod_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_container_mod {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [520.0, 380.0].into(), [150.0, 120.0].into());
+        let mut should_create = false;
+        let mut remove_index = None;
+
+        egui::Window::new("创建容器模组")
+            .open(&mut state.show_container_mod)
+            .default_pos(safe_pos)
+            .resizable(true)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new("将已构建好的PBO打包为 @模组 目录，并生成 mod.cpp 和 Logo").small().weak());
+                ui.add_space(5.0);
+
+                egui::Grid::new("container_mod_metadata").num_columns(2).show(ui, |ui| {
+                    ui.label("模组名称:");
+                    ui.text_edit_singleline(&mut state.project.mod_name);
+                    ui.end_row();
+                    ui.label("作者:");
+                    ui.text_edit_singleline(&mut state.project.author_name);
+                    ui.end_row();
+                    ui.label("Logo:");
+                    match state.project.logo_path {
+                        Some(ref logo) => ui.label(logo.display().to_string()),
+                        None => ui.label("默认Logo"),
+                    };
+                    ui.end_row();
+                });
+
+                ui.add_space(5.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(format!("PBO文件 ({}):", state.container_mod_pbos.len()));
+                    if ui.button("添加PBO...").clicked() {
+                        if let Some(paths) = FileOperations::select_pbo_files() {
+                            for path in paths {
+                                if !state.container_mod_pbos.contains(&path) {
+                                    state.container_mod_pbos.push(path);
+                                }
+                            }
+                        }
+                    }
+                    if ui.add_enabled(!state.container_mod_pbos.is_empty(), egui::Button::new("清空")).clicked() {
+                        state.container_mod_pbos.clear();
+                    }
+                });
+
+                egui::ScrollArea::vertical().max_height(180.0).show(ui, |ui| {
+                    if state.container_mod_pbos.is_empty() {
+                        ui.label(egui::RichText::new("尚未添加PBO文件").weak());
+                    }
+                    for (index, path) in state.container_mod_pbos.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("✖").on_hover_text("移除").clicked() {
+                                remove_index = Some(index);
+                            }
+                            ui.label(path.file_name().unwrap_or_default().to_string_lossy())
+                                .on_hover_text(path.display().to_string());
+                        });
+                    }
+                });
+
+                let duplicate = FileOperations::duplicate_file_name(&state.container_mod_pbos);
+                if let Some(ref name) = duplicate {
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), format!("⚠ 文件名重复: {}", name));
+                }
+
+                ui.add_space(10.0);
+                let can_create = !state.container_mod_pbos.is_empty() && duplicate.is_none();
+                if ui.add_enabled(can_create, egui::Button::new("创建容器模组...")).clicked() {
+                    should_create = true;
+                }
+            });
+
+        if let Some(index) = remove_index {
+            state.container_mod_pbos.remove(index);
+        }
+        if should_create {
+            Self::build_container_mod(state);
+        }
+    }
+
+    /// 构建容器模组
+    fn build_container_mod(state: &mut AppState) {
+        let Some(export_dir) = FileOperations::select_export_directory() else {
+            return;
+        };
+
+        let result = FileOperations::create_container_mod_structure(&state.project, &state.container_mod_pbos, &export_dir)
+            .and_then(|mod_dir| {
+                let template_engine = TemplateEngine::new()?;
+                template_engine.generate_mod_cpp(
+                    &state.project,
+                    state.export_settings.use_stringtable,
+                    &mod_dir.join("mod.cpp"),
+                )?;
+                template_engine.generate_meta_cpp(&state.project, state.project.workshop_id, &mod_dir.join("meta.cpp"))?;
+                Ok(mod_dir)
+            });
+
+        match result {
+            Ok(mod_dir) => {
+                info!("容器模组创建成功: {:?}", mod_dir);
+                let pbo_list = state
+                    .container_mod_pbos
+                    .iter()
+                    .map(|path| format!("  {}", path.file_name().unwrap_or_default().to_string_lossy()))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                state.export_result = Some(format!(
+                    "🎉 容器模组创建成功！\n\n📁 输出目录: {}\n📝 模组文件: mod.cpp、meta.cpp\n📦 PBO文件 ({}):\n{}",
+                    mod_dir.display(),
+                    state.container_mod_pbos.len(),
+                    pbo_list
+                ));
+                state.export_result_dir = Some(mod_dir);
+                state.show_container_mod = false;
+            }
+            Err(e) => {
+                warn!("创建容器模组失败: {}", e);
+                state.export_result = Some(format!("容器模组创建失败！\n\n错误: {}", e));
+            }
+        }
+        state.show_export_result = true;
+    }
+
+    /// 修复旧版本导出的模组配置
+    fn repair_old_config(state: &mut AppState) {
+        let Some(mod_dir) = rfd::FileDialog::new().set_title("选择包含 config.cpp 的模组文件夹").pick_folder() else {
+            return;
+        };
+
+        state.export_result = Some(match crate::config_repair::repair_mod_config(&mod_dir, &state.track_defaults) {
+            Ok(report) => format!("修复旧配置: {}\n\n{}", mod_dir.display(), report.summary()),
+            Err(e) => {
+                warn!("修复旧配置失败: {:#}", e);
+                format!("无法修复旧配置（解析失败时不会修改任何文件）。\n\n目录: {}\n错误: {:#}", mod_dir.display(), e)
+            }
+        });
+        state.export_result_dir = Some(mod_dir);
+        state.show_export_result = true;
+    }
+
     /// 显示PAA转换对话框
     pub fn show_paa_converter_dialog(ctx: &egui::Context, state: &mut AppState, task_processor: Option<&mut ThreadedTaskProcessor>) {
         if !state.show_paa_converter {
@@ -1138,7 +2439,8 @@ impl UIComponents {
         let window_size = egui::Vec2::new(800.0, 600.0);
         let safe_pos = Self::calculate_safe_position(ctx, window_size, egui::Pos2::new(50.0, 50.0));
         
-        egui::Window::new("图片转PAA转换器")
+        egui::Window::new(tr("paa.title"))
+            .id(egui::Id::new("paa_converter_dialog"))
             .open(&mut state.show_paa_converter)
             .resizable(true)
             .default_size(window_size)
@@ -1148,7 +2450,7 @@ impl UIComponents {
             .constrain(true)
             .show(ctx, |ui| {
                 ui.vertical(|ui| {
-                    ui.heading("图片转PAA格式转换");
+                    ui.heading(tr("paa.heading"));
                     ui.separator();
 
                     // 文件选择区域
@@ -1159,10 +2461,10 @@ impl UIComponents {
                         ui.set_min_height(min_height);
                         ui.vertical(|ui| {
                             ui.horizontal(|ui| {
-                                if ui.button("选择图片文件 (支持多选)").clicked() {
+                                if ui.button(tr("paa.select_files")).clicked() {
                                     if let Some(paths) = rfd::FileDialog::new()
-                                        .add_filter("图片文件", &["png", "jpg", "jpeg", "bmp", "tga", "tiff", "webp"])
-                                        .set_title("选择要转换的图片文件")
+                                        .add_filter(tr("paa.image_filter"), &["png", "jpg", "jpeg", "bmp", "tga", "dds", "tiff", "webp"])
+                                        .set_title(tr("paa.select_files_title"))
                                         .pick_files()
                                     {
                                         // 防重复添加文件，并提供反馈
@@ -1180,9 +2482,9 @@ impl UIComponents {
                                         
                                         // 显示添加结果
                                         if duplicate_count > 0 {
-                                            state.file_operation_message = Some(format!("添加了 {} 个文件，跳过了 {} 个重复文件", added_count, duplicate_count));
+                                            state.file_operation_message = Some(trf("paa.added_with_duplicates", &[&added_count, &duplicate_count]));
                                         } else if added_count > 0 {
-                                            state.file_operation_message = Some(format!("成功添加了 {} 个文件", added_count));
+                                            state.file_operation_message = Some(trf("paa.added", &[&added_count]));
                                         }
                                         
                                         if state.paa_output_directory.is_none() && !state.paa_selected_files.is_empty() {
@@ -1192,17 +2494,18 @@ impl UIComponents {
                                 }
                                 
 
-                                if ui.button("选择输出目录").clicked() {
+                                if ui.button(tr("paa.select_output")).clicked() {
                                     if let Some(output_dir) = rfd::FileDialog::new()
-                                        .set_title("选择PAA文件输出目录")
+                                        .set_title(tr("paa.select_output_title"))
                                         .pick_folder()
                                     {
                                         state.paa_output_directory = Some(output_dir);
                                     }
                                 }
 
-                                if ui.button("清空列表").clicked() {
+                                if ui.button(tr("menu.clear_list")).clicked() {
                                     state.paa_selected_files.clear();
+                                    state.paa_source_dimensions.clear();
                                     state.file_operation_message = None; // 清除提示信息
                                 }
                             });
@@ -1216,7 +2519,7 @@ impl UIComponents {
                             }
 
                             if state.paa_selected_files.is_empty() {
-                                ui.label("未选择任何文件");
+                                ui.label(tr("paa.no_files"));
                             } else {
                                 // 计算唯一文件数量
                                 let total_files = state.paa_selected_files.len();
@@ -1226,35 +2529,21 @@ impl UIComponents {
                                 
                                 if duplicate_count > 0 {
                                     ui.colored_label(egui::Color32::from_rgb(255, 165, 0), 
-                                        format!("⚠️ 已选择 {} 个文件（其中 {} 个重复）:", total_files, duplicate_count));
+                                        trf("paa.selected_with_duplicates", &[&total_files, &duplicate_count]));
                                 } else {
-                                    ui.label(format!("已选择 {} 个文件:", total_files));
+                                    ui.label(trf("paa.selected", &[&total_files]));
                                 }
                                 ui.add_space(5.0);
                                 
                                 egui::ScrollArea::vertical()
                                     .max_height(100.0)
                                     .show(ui, |ui| {
-                                        let mut indices_to_remove = Vec::new();
-                                        
-                                        for (i, file) in state.paa_selected_files.iter().enumerate() {
-                                            ui.horizontal(|ui| {
-                                                ui.label(format!("{}. {}", i + 1, file.file_name().unwrap_or_default().to_string_lossy()));
-                                                if ui.small_button("移除").clicked() {
-                                                    indices_to_remove.push(i);
-                                                }
-                                            });
-                                        }
-                                        
-                                        // 从后往前移除，避免索引问题
-                                        for &index in indices_to_remove.iter().rev() {
-                                            state.paa_selected_files.remove(index);
-                                        }
+                                        Self::render_reorderable_file_list(ui, &mut state.paa_selected_files);
                                     });
                             }
 
                             if let Some(ref output_dir) = state.paa_output_directory {
-                                ui.label(format!("输出目录: {}", output_dir.display()));
+                                ui.label(trf("paa.output_dir", &[&output_dir.display()]));
                             }
                         });
                     });
@@ -1268,29 +2557,113 @@ impl UIComponents {
                         let min_height = (available_height * 0.2).max(100.0).min(200.0);
                         ui.set_min_height(min_height);
                         ui.vertical(|ui| {
-                            ui.heading("转换选项");
+                            ui.heading(tr("paa.options"));
                             ui.separator();
 
-                            ui.checkbox(&mut state.paa_options.crop_to_power_of_two, "裁剪到2的次方尺寸 (推荐)");
+                            ui.checkbox(&mut state.paa_options.crop_to_power_of_two, tr("paa.crop_pow2"));
                             
                             if state.paa_options.crop_to_power_of_two {
                                 ui.horizontal(|ui| {
-                                    ui.label("目标尺寸:");
-                                    ui.radio_value(&mut state.paa_options.target_size, None, "自动选择");
+                                    ui.label(tr("paa.target_size"));
+                                    ui.radio_value(&mut state.paa_options.target_size, None, tr("paa.auto_size"));
                                     ui.radio_value(&mut state.paa_options.target_size, Some(256), "256x256");
                                     ui.radio_value(&mut state.paa_options.target_size, Some(512), "512x512");
                                     ui.radio_value(&mut state.paa_options.target_size, Some(1024), "1024x1024");
                                 });
 
                                 ui.horizontal(|ui| {
-                                    ui.label("裁剪方式:");
-                                    ui.radio_value(&mut state.paa_options.center_crop, true, "居中裁剪 (推荐)");
-                                    ui.radio_value(&mut state.paa_options.center_crop, false, "保持原始比例");
+                                    ui.label(tr("paa.custom_size"));
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut state.paa_custom_size_input)
+                                            .desired_width(60.0)
+                                            .hint_text(tr("paa.custom_size_hint")),
+                                    );
+                                    if ui.button(tr("paa.apply")).clicked() {
+                                        match state.paa_custom_size_input.trim().parse::<u32>() {
+                                            Ok(size) => {
+                                                let (size, message) = crate::paa_converter::PaaOptions::validate_target_size(size);
+                                                state.paa_options.target_size = Some(size);
+                                                state.paa_custom_size_input = size.to_string();
+                                                state.paa_size_message = message;
+                                            }
+                                            Err(_) => {
+                                                state.paa_size_message = Some(tr("paa.invalid_size").to_string());
+                                            }
+                                        }
+                                    }
+                                    if let Some(size) = state.paa_options.target_size {
+                                        if ![256, 512, 1024].contains(&size) {
+                                            ui.label(trf("paa.current_size", &[&size, &size]));
+                                        }
+                                    }
+                                });
+
+                                if state.paa_options.target_size.is_none() {
+                                    use crate::paa_converter::{PaaAutoRounding, PaaOptions};
+                                    ui.horizontal(|ui| {
+                                        ui.label(tr("paa.auto_rounding"));
+                                        for rounding in [PaaAutoRounding::Down, PaaAutoRounding::Up] {
+                                            ui.radio_value(&mut state.paa_options.auto_rounding, rounding, rounding.display_name());
+                                        }
+                                        ui.label(tr("paa.max_size"));
+                                        egui::ComboBox::from_id_source("paa_auto_max_size")
+                                            .selected_text(format!("{}", state.paa_options.auto_max_size))
+                                            .show_ui(ui, |ui| {
+                                                for size in [256, 512, 1024, 2048, PaaOptions::MAX_TEXTURE_SIZE] {
+                                                    ui.selectable_value(&mut state.paa_options.auto_max_size, size, size.to_string());
+                                                }
+                                            });
+                                    });
+                                    ui.label(
+                                        egui::RichText::new(tr("paa.auto_note"))
+                                            .small()
+                                            .weak(),
+                                    );
+                                }
+                                ui.label(
+                                    egui::RichText::new(trf(
+                                        "paa.size_rule",
+                                        &[
+                                            &crate::paa_converter::PaaOptions::MIN_TEXTURE_SIZE,
+                                            &crate::paa_converter::PaaOptions::MAX_TEXTURE_SIZE,
+                                        ],
+                                    ))
+                                    .small()
+                                    .weak(),
+                                );
+                                if let Some(ref message) = state.paa_size_message {
+                                    ui.colored_label(egui::Color32::from_rgb(200, 120, 0), message);
+                                }
+
+                                ui.horizontal(|ui| {
+                                    ui.label(tr("paa.crop_mode"));
+                                    ui.radio_value(&mut state.paa_options.center_crop, true, tr("paa.center_crop"));
+                                    ui.radio_value(&mut state.paa_options.center_crop, false, tr("paa.keep_ratio"));
                                 });
+
+                                ui.checkbox(&mut state.paa_options.limit_to_source, tr("paa.no_upscale"))
+                                    .on_hover_text(tr("paa.no_upscale_hint"));
                             }
 
+                            ui.horizontal(|ui| {
+                                use crate::paa_converter::PaaCompression;
+                                ui.label(tr("paa.compression"));
+                                for compression in [PaaCompression::Auto, PaaCompression::Dxt1, PaaCompression::Dxt5] {
+                                    ui.radio_value(&mut state.paa_options.compression, compression, compression.display_name());
+                                }
+                            })
+                            .response
+                            .on_hover_text(tr("paa.compression_hint"));
+                            ui.checkbox(&mut state.paa_options.generate_mipmaps, tr("paa.mipmaps"))
+                                .on_hover_text(tr("paa.mipmaps_hint"));
+
                             ui.add_space(5.0);
-                            ui.label("支持的图片格式: PNG, JPG, JPEG, BMP, TGA, TIFF, WEBP");
+                            ui.label(tr("paa.formats"));
+
+                            let is_default = state.paa_options == crate::paa_converter::PaaOptions::default();
+                            if ui.add_enabled(!is_default, egui::Button::new(tr("paa.restore_defaults"))).clicked() {
+                                state.paa_options = crate::paa_converter::PaaOptions::default();
+                            }
                         });
                     });
 
@@ -1298,18 +2671,24 @@ impl UIComponents {
 
                     // 操作按钮区域
                     ui.horizontal(|ui| {
-                        let can_convert = !state.paa_selected_files.is_empty() && state.paa_output_directory.is_some();
+                        let task_running = state.task_manager.is_running();
+                        let can_convert = !state.paa_selected_files.is_empty()
+                            && state.paa_output_directory.is_some()
+                            && !task_running;
                         
-                        if ui.add_enabled(can_convert, egui::Button::new("开始转换")).clicked() {
+                        if ui.add_enabled(can_convert, egui::Button::new(tr("paa.start")))
+                            .on_disabled_hover_text(if task_running { tr("paa.task_running") } else { tr("paa.need_files") })
+                            .clicked()
+                        {
                             should_convert = true;
                         }
 
-                        if ui.button("预览效果").clicked() {
+                        if ui.button(tr("paa.preview")).clicked() {
                             state.show_paa_preview = true;
                         }
 
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("关闭").clicked() {
+                            if ui.button(tr("common.close")).clicked() {
                                 should_close = true;
                             }
                         });
@@ -1320,13 +2699,22 @@ impl UIComponents {
         if should_close {
             state.show_paa_converter = false;
         }
+
+        // 文件移出列表后释放对应的缩略图纹理
+        if let Some(rtm) = state.runtime_texture_manager.as_mut() {
+            rtm.retain_thumbnails(&state.paa_selected_files);
+        }
         
         // 在闭包外面执行转换，避免借用冲突
         if should_convert {
             if let Some(ref output_dir) = state.paa_output_directory {
+                state.paa_result_dir = Some(output_dir.clone());
                 if let Some(processor) = task_processor {
                     // 使用多线程处理
-                    state.task_manager.start_task(crate::models::TaskType::PaaConvert, state.paa_selected_files.len());
+                    if !state.task_manager.start_task(crate::models::TaskType::PaaConvert, state.paa_selected_files.len()) {
+                        state.file_operation_message = Some(crate::utils::constants::app::TASK_BUSY_MESSAGE.to_string());
+                        return;
+                    }
                     processor.reset_cancel_flag();
                     
                     if let Err(e) = processor.process_paa_convert(
@@ -1354,6 +2742,16 @@ impl UIComponents {
 
         let mut should_close = false;
 
+        // 缩略图在后台解码，解码完成后在下一帧创建纹理
+        let thumbnails_pending = {
+            let rtm = state.runtime_texture_manager.get_or_insert_with(Default::default);
+            rtm.retain_thumbnails(&state.paa_selected_files);
+            rtm.load_pending_thumbnails(ctx, &state.paa_selected_files)
+        };
+        if thumbnails_pending {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
         let window_size = egui::Vec2::new(900.0, 700.0);
         let safe_pos = Self::calculate_safe_position(ctx, window_size, egui::Pos2::new(100.0, 100.0));
         
@@ -1378,12 +2776,100 @@ impl UIComponents {
                                 ui.label(format!("目标尺寸: {}x{}", size, size));
                             },
                             None => {
-                                ui.label("目标尺寸: 自动选择");
+                                ui.label(format!(
+                                    "目标尺寸: 自动选择（{}，上限 {}）",
+                                    state.paa_options.auto_rounding.display_name(),
+                                    state.paa_options.auto_max_size
+                                ));
                             },
                         }
                         ui.label(format!("裁剪方式: {}", if state.paa_options.center_crop { "居中裁剪" } else { "保持原始比例" }));
                     }
 
+                    // 每个文件实际使用的尺寸
+                    if !state.paa_selected_files.is_empty() {
+                        let mut upscale_count = 0;
+                        ui.add_space(10.0);
+                        ui.group(|ui| {
+                            ui.heading("各文件输出尺寸");
+                            egui::ScrollArea::vertical()
+                                .id_source("paa_preview_sizes")
+                                .max_height(150.0)
+                                .show(ui, |ui| {
+                                    for path in &state.paa_selected_files {
+                                        let name = path.file_name().unwrap_or_default().to_string_lossy();
+                                        let dimensions = state.paa_source_dimensions.entry(path.clone()).or_insert_with(|| {
+                                            crate::paa_converter::PaaConverter::source_dimensions(path).map_err(|e| e.to_string())
+                                        });
+                                        match dimensions {
+                                            Ok((width, height)) => {
+                                                let (width, height) = (*width, *height);
+                                                let (out_width, out_height) = state.paa_options.output_size_for(width, height);
+                                                ui.label(format!("{}: {}x{} → {}x{}", name, width, height, out_width, out_height));
+                                                if let Some(warning) = state.paa_options.upscale_warning(width, height) {
+                                                    upscale_count += 1;
+                                                    ui.colored_label(egui::Color32::from_rgb(200, 120, 0), format!("  ⚠️ {}", warning));
+                                                }
+                                            }
+                                            Err(e) => {
+                                                ui.colored_label(egui::Color32::RED, format!("{}: {}", name, e));
+                                            }
+                                        }
+                                    }
+                                });
+                            if upscale_count > 0 {
+                                ui.colored_label(
+                                    egui::Color32::from_rgb(200, 120, 0),
+                                    format!("{} 个文件的目标尺寸超过源图，放大后的 Logo 可能模糊", upscale_count),
+                                );
+                            }
+                            if upscale_count > 0 || state.paa_options.limit_to_source {
+                                ui.checkbox(&mut state.paa_options.limit_to_source, "不放大：超出源图时限制为源图对应的2的次方");
+                            }
+                        });
+                    }
+
+                    // 所有待转换文件的缩略图
+                    if !state.paa_selected_files.is_empty() {
+                        if let Some(ref rtm) = state.runtime_texture_manager {
+                            ui.add_space(10.0);
+                            ui.group(|ui| {
+                                ui.heading(format!("缩略图（{} 个文件）", state.paa_selected_files.len()));
+                                let thumbnail_size = crate::paa_converter::RuntimeImageTextureManager::THUMBNAIL_SIZE as f32;
+                                egui::ScrollArea::vertical()
+                                    .id_source("paa_preview_thumbnails")
+                                    .max_height(300.0)
+                                    .show(ui, |ui| {
+                                        ui.horizontal_wrapped(|ui| {
+                                            for path in &state.paa_selected_files {
+                                                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                                                ui.allocate_ui(egui::vec2(thumbnail_size, thumbnail_size + 24.0), |ui| {
+                                                    ui.vertical_centered(|ui| {
+                                                        match rtm.thumbnail(path) {
+                                                            Some(Ok(texture)) => {
+                                                                ui.add(
+                                                                    egui::Image::new(texture)
+                                                                        .max_size(egui::vec2(thumbnail_size, thumbnail_size)),
+                                                                )
+                                                                .on_hover_text(path.display().to_string());
+                                                            }
+                                                            Some(Err(e)) => {
+                                                                ui.colored_label(egui::Color32::RED, "❌").on_hover_text(e.as_str());
+                                                            }
+                                                            None => {
+                                                                ui.spinner();
+                                                            }
+                                                        }
+                                                        ui.add(egui::Label::new(egui::RichText::new(name).small()).truncate(true));
+                                                    });
+                                                });
+                                            }
+                                        });
+                                    });
+                            });
+                        }
+                    }
+
                     ui.add_space(10.0);
 
                     // 显示图片预览
@@ -1438,11 +2924,19 @@ impl UIComponents {
     /// 显示导出结果对话框
     pub fn show_export_result_dialog(ctx: &egui::Context, state: &mut AppState) {
         if !state.show_export_result {
+            // 通过标题栏关闭时也丢弃名称修正目标，避免轨道变化后修正错误的轨道
+            state.name_fix_indices.clear();
+            // 导出结果与输出目录同时设置，对话框关闭后丢弃，避免其他提示沿用旧目录
+            state.export_result_dir = None;
+            state.export_dir_not_writable = false;
             return;
         }
 
         let safe_pos = Self::calculate_safe_position(ctx, [600.0, 400.0].into(), [100.0, 100.0].into());
         let mut should_close = false;
+        let mut open_dir_error = None;
+        let mut should_fix_names = false;
+        let mut should_reselect_dir = false;
         
         egui::Window::new("导出结果")
             .open(&mut state.show_export_result)
@@ -1465,7 +2959,7 @@ impl UIComponents {
                             .show(ui, |ui| {
                                 // 按行分割结果文本并显示
                                 for line in result.lines() {
-                                    if line.contains("导出成功！") || line.contains("导出失败！") || line.contains("插件构建成功！") || line.contains("插件构建失败！") {
+                                    if line.contains("导出成功！") || line.contains("导出失败！") || line.contains("插件构建成功！") || line.contains("插件构建失败！") || line.contains("插件源文件生成成功！") || line.contains("插件源文件生成失败！") {
                                         ui.heading(line);
                                     } else if line.starts_with("  成功步骤:") || line.starts_with("  警告信息:") {
                                         ui.colored_label(egui::Color32::from_rgb(0, 150, 0), line);
@@ -1496,12 +2990,69 @@ impl UIComponents {
                             ui.output_mut(|o| o.copied_text = result.clone());
                         }
                     }
+
+                    if let Some(e) = Self::render_open_output_dir_button(ui, state.export_result_dir.as_deref()) {
+                        open_dir_error = Some(e);
+                    }
+
+                    if state.export_dir_not_writable
+                        && ui.button("重新选择导出目录")
+                            .on_hover_text("选择一个可写的位置后重新导出")
+                            .clicked()
+                    {
+                        should_reselect_dir = true;
+                    }
+
+                    if state.project.mod_type == crate::models::ModType::Music
+                        && !state.tracks.is_empty()
+                        && ui.button("复制SQF代码")
+                            .on_hover_text("复制每个轨道的 playMusic 语句，可直接粘贴到任务触发器或 Zeus 执行代码中")
+                            .clicked()
+                    {
+                        let snippet = TemplateEngine::generate_sqf_snippets(
+                            &state.project,
+                            &state.tracks,
+                            state.export_settings.tag_class_names,
+                        );
+                        ui.output_mut(|o| o.copied_text = snippet);
+                        state.file_operation_message = Some(format!("已复制 {} 个轨道的 SQF 代码", state.tracks.len()));
+                    }
+
+                    if !state.name_fix_indices.is_empty()
+                        && ui.button("自动修正名称")
+                            .on_hover_text("将有问题的轨道名称重新生成为拼音名称，转写后同名时追加数字后缀（可撤销）")
+                            .clicked()
+                    {
+                        should_fix_names = true;
+                    }
                 });
             });
         
+        if should_fix_names {
+            let indices = std::mem::take(&mut state.name_fix_indices);
+            let changed = state.fix_track_names(&indices);
+            state.file_operation_message = Some(format!("已修正 {} 个轨道名称，请重新导出", changed));
+            should_close = true;
+        }
+
+        if open_dir_error.is_some() {
+            state.file_operation_message = open_dir_error;
+        }
+
+        if should_reselect_dir {
+            if let Some(dir) = FileOperations::select_export_directory() {
+                state.export_result = None;
+                state.export_result_dir = None;
+                Self::export_mod(state, &dir);
+                return;
+            }
+        }
+
         if should_close {
             state.show_export_result = false;
             state.export_result = None;
+            state.export_dir_not_writable = false;
+            state.name_fix_indices.clear();
         }
     }
 
@@ -1585,20 +3136,7 @@ impl UIComponents {
                         egui::ScrollArea::vertical()
                             .max_height(150.0)
                             .show(ui, |ui| {
-                                let mut indices_to_remove = Vec::new();
-                                for (i, file) in state.audio_decrypt_selected_files.iter().enumerate() {
-                                    ui.horizontal(|ui| {
-                                        ui.label(format!("• {}", file.file_name().unwrap_or_default().to_string_lossy()));
-                                        if ui.small_button("移除").clicked() {
-                                            indices_to_remove.push(i);
-                                        }
-                                    });
-                                }
-                                
-                                // 从后往前删除，避免索引问题
-                                for &i in indices_to_remove.iter().rev() {
-                                    state.audio_decrypt_selected_files.remove(i);
-                                }
+                                Self::render_reorderable_file_list(ui, &mut state.audio_decrypt_selected_files);
                             });
                     } else {
                         ui.label("未选择任何文件");
@@ -1625,6 +3163,26 @@ impl UIComponents {
                             }
                         }
                     });
+
+                    ui.add_space(5.0);
+
+                    ui.horizontal(|ui| {
+                        ui.label("最大并发数:");
+                        ui.add(egui::Slider::new(
+                            &mut state.audio_decrypt_options.max_concurrency,
+                            1..=crate::utils::constants::audio_decrypt::MAX_CONCURRENT_DECRYPTS_LIMIT,
+                        ));
+                    });
+
+                    ui.checkbox(
+                        &mut state.audio_decrypt_options.organize_by_format,
+                        "按格式整理输出（mp3/、flac/ 等子文件夹）",
+                    );
+
+                    let is_default = state.audio_decrypt_options == crate::models::AudioDecryptOptions::default();
+                    if ui.add_enabled(!is_default, egui::Button::new("恢复默认设置")).clicked() {
+                        state.audio_decrypt_options = crate::models::AudioDecryptOptions::default();
+                    }
                 });
                 
                 ui.add_space(10.0);
@@ -1634,7 +3192,7 @@ impl UIComponents {
                     ui.add_space(5.0);
                     ui.label("• 酷狗音乐 (.kgm) - 自动检测输出格式");
                     ui.label("• 网易云音乐 (.ncm) - 支持MP3/FLAC输出");
-                    ui.label("• 其他加密格式 - 开发中");
+                    ui.label("• QQ音乐 (.qmc0/.qmc3/.qmcflac/.qmcogg/.mflac/.mgg 等) - 自动检测输出格式");
                 });
                 
                 ui.add_space(15.0);
@@ -1645,12 +3203,26 @@ impl UIComponents {
                         should_close = true;
                     }
                     
+                    let task_running = state.task_manager.is_running();
                     let can_decrypt = !state.audio_decrypt_selected_files.is_empty() 
-                        && state.audio_decrypt_output_directory.is_some();
+                        && state.audio_decrypt_output_directory.is_some()
+                        && !task_running;
                     
-                    if ui.add_enabled(can_decrypt, egui::Button::new("开始解密")).clicked() {
+                    if ui.add_enabled(can_decrypt, egui::Button::new("开始解密"))
+                        .on_disabled_hover_text(if task_running { "任务进行中" } else { "请先选择文件和输出目录" })
+                        .clicked()
+                    {
                         state.should_decrypt_audio = true;
                     }
+
+                    let is_music_mod = state.project.mod_type == crate::models::ModType::Music;
+                    if ui.add_enabled(can_decrypt && is_music_mod, egui::Button::new("解密、转换并添加为轨道"))
+                        .on_hover_text("解密后重新编码为 Arma 兼容的 OGG，并直接添加到轨道列表（需要 FFmpeg）")
+                        .on_disabled_hover_text(if !is_music_mod { "仅音乐模组可用" } else if task_running { "任务进行中" } else { "请先选择文件和输出目录" })
+                        .clicked()
+                    {
+                        state.should_run_decrypt_pipeline = true;
+                    }
                 });
             });
         
@@ -1677,8 +3249,16 @@ impl UIComponents {
         let mut success_count = 0;
         let mut error_count = 0;
         let mut converted_files = Vec::new();
+        let mut failed_files = Vec::new();
         
         for input_path in &paths {
+            let display_name = input_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if let Err(message) = crate::paa_converter::PaaConverter::validate_input(input_path) {
+                error_count += 1;
+                warn!("跳过不支持的图片: {:?} - {}", input_path, message);
+                failed_files.push(format!("{}: {}", display_name, message));
+                continue;
+            }
             if let Some(file_name) = input_path.file_stem() {
                 let output_path = output_dir.join(format!("{}.paa", file_name.to_string_lossy()));
                 
@@ -1688,14 +3268,15 @@ impl UIComponents {
                     options.clone(),
                     None
                 ) {
-                    Ok(_) => {
+                    Ok(conversion) => {
                         success_count += 1;
-                        converted_files.push(output_path.clone());
+                        converted_files.push((output_path.clone(), conversion));
                         info!("转换成功: {:?}", output_path);
                     },
                     Err(e) => {
                         error_count += 1;
                         warn!("转换失败: {:?} - {}", input_path, e);
+                        failed_files.push(format!("{}: {:#}", display_name, e));
                     }
                 }
             }
@@ -1716,12 +3297,34 @@ impl UIComponents {
         if let Some(size) = options.target_size {
             result_message.push_str(&format!("  目标尺寸: {}x{}\n", size, size));
         } else {
-            result_message.push_str("  目标尺寸: 自动选择\n");
+            result_message.push_str(&format!(
+                "  目标尺寸: 自动选择（{}，上限 {}）\n",
+                options.auto_rounding.display_name(),
+                options.auto_max_size
+            ));
         }
         result_message.push_str(&format!("  裁剪到2的次方: {}\n", if options.crop_to_power_of_two { "是" } else { "否" }));
         
         result_message.push_str(&format!("\n统计信息:\n  总文件数: {}\n  成功: {}\n  失败: {}\n", 
             paths.len(), success_count, error_count));
+
+        if !converted_files.is_empty() {
+            result_message.push_str("\n输出尺寸:\n");
+            for (path, conversion) in &converted_files {
+                result_message.push_str(&format!(
+                    "  {}: {}\n",
+                    path.file_name().unwrap_or_default().to_string_lossy(),
+                    conversion.describe()
+                ));
+            }
+        }
+
+        if !failed_files.is_empty() {
+            result_message.push_str("\n失败文件:\n");
+            for line in &failed_files {
+                result_message.push_str(&format!("  {}\n", line));
+            }
+        }
         
         // 设置转换结果并显示对话框
         state.paa_result = Some(result_message);
@@ -1747,6 +3350,16 @@ impl UIComponents {
         } else {
             0.0
         };
+        // 使用加载时缓存的文件大小，避免每帧读取文件信息；缺失的文件不计入总大小
+        let total_size: u64 = state.tracks.iter().filter_map(|t| t.file_size).sum();
+        let mut largest: Vec<&crate::models::Track> = state.tracks.iter().filter(|t| t.file_size.is_some()).collect();
+        largest.sort_by(|a, b| b.file_size.cmp(&a.file_size));
+        largest.truncate(3);
+        let largest: Vec<(String, String)> = largest
+            .into_iter()
+            .map(|t| (t.display_name(), crate::utils::FileUtils::format_size(t.file_size.unwrap_or(0))))
+            .collect();
+        let unknown: Vec<String> = state.tracks.iter().filter(|t| t.file_size.is_none()).map(|t| t.display_name()).collect();
 
         let safe_pos = Self::calculate_safe_position(ctx, [300.0, 150.0].into(), [100.0, 100.0].into());
         let mut should_close = false;
@@ -1755,9 +3368,9 @@ impl UIComponents {
             .open(&mut state.show_track_count)
             .default_pos(safe_pos)
             .resizable(false)
-            .default_size([300.0, 150.0])
+            .default_size([360.0, 220.0])
             .min_size([250.0, 120.0])
-            .max_size([400.0, 200.0])
+            .max_size([500.0, 400.0])
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
                     ui.add_space(20.0);
@@ -1770,6 +3383,25 @@ impl UIComponents {
                     if track_count > 0 {
                         ui.add_space(5.0);
                         ui.label(format!("总时长: {:.1} 秒", total_duration));
+                        ui.label(format!("总大小: {}", crate::utils::FileUtils::format_size(total_size)));
+
+                        if !largest.is_empty() {
+                            ui.add_space(8.0);
+                            ui.label("最大的轨道:");
+                            for (name, size) in &largest {
+                                ui.label(egui::RichText::new(format!("{} - {}", name, size)).small());
+                            }
+                        }
+                        if !unknown.is_empty() {
+                            ui.add_space(8.0);
+                            ui.colored_label(
+                                egui::Color32::from_rgb(255, 165, 0),
+                                format!("{} 个轨道的文件缺失，未计入总大小:", unknown.len()),
+                            );
+                            for name in &unknown {
+                                ui.label(egui::RichText::new(format!("{} - 未知", name)).small());
+                            }
+                        }
                     }
                     
                     ui.add_space(20.0);
@@ -1793,6 +3425,8 @@ impl UIComponents {
 
         let safe_pos = Self::calculate_safe_position(ctx, [600.0, 400.0].into(), [100.0, 100.0].into());
         let mut should_close = false;
+        let mut open_dir_error = None;
+        let mut log_message = None;
         
         egui::Window::new("PAA转换结果")
             .open(&mut state.show_paa_result)
@@ -1828,9 +3462,24 @@ impl UIComponents {
                             ui.output_mut(|o| o.copied_text = result.clone());
                         }
                     }
+
+                    if let Some(e) = Self::render_open_output_dir_button(ui, state.paa_result_dir.as_deref()) {
+                        open_dir_error = Some(e);
+                    }
+
+                    if let Some(message) = Self::render_save_log_button(ui, "PAA转换", state.paa_result.as_deref()) {
+                        log_message = Some(message);
+                    }
                 });
             });
         
+        if open_dir_error.is_some() {
+            state.file_operation_message = open_dir_error;
+        }
+        if log_message.is_some() {
+            state.file_operation_message = log_message;
+        }
+
         if should_close {
             state.show_paa_result = false;
             state.paa_result = None;
@@ -1845,6 +3494,8 @@ impl UIComponents {
 
         let safe_pos = Self::calculate_safe_position(ctx, [600.0, 400.0].into(), [100.0, 100.0].into());
         let mut should_close = false;
+        let mut open_dir_error = None;
+        let mut log_message = None;
         
         egui::Window::new("音频解密结果")
             .open(&mut state.show_audio_decrypt_result)
@@ -1896,9 +3547,24 @@ impl UIComponents {
                             ui.output_mut(|o| o.copied_text = result.clone());
                         }
                     }
+
+                    if let Some(e) = Self::render_open_output_dir_button(ui, state.audio_decrypt_result_dir.as_deref()) {
+                        open_dir_error = Some(e);
+                    }
+
+                    if let Some(message) = Self::render_save_log_button(ui, "音频解密", state.audio_decrypt_result.as_deref()) {
+                        log_message = Some(message);
+                    }
                 });
             });
         
+        if open_dir_error.is_some() {
+            state.file_operation_message = open_dir_error;
+        }
+        if log_message.is_some() {
+            state.file_operation_message = log_message;
+        }
+
         if should_close {
             state.show_audio_decrypt_result = false;
             state.audio_decrypt_result = None;
@@ -1914,6 +3580,8 @@ impl UIComponents {
         let safe_pos = Self::calculate_safe_position(ctx, [500.0, 300.0].into(), [200.0, 200.0].into());
         let mut should_close = false;
         let mut should_cancel = false;
+        let mut toggle_pause = false;
+        let is_paused = task_processor.is_paused();
         
         let current_progress = state.task_manager.get_current_progress().cloned();
         
@@ -1939,6 +3607,12 @@ impl UIComponents {
                                 TaskType::AudioConvert => "音频格式转换",
                                 TaskType::VideoConvert => "视频格式转换",
                                 TaskType::VideoModExport => "视频模组导出",
+                                TaskType::SimilarTrackScan => "相似轨道分析",
+                                TaskType::DurationSync => "同步轨道时长",
+                                TaskType::LoudnessAnalysis => "分析轨道音量",
+                                TaskType::ChapterSplit => "按章节分割",
+                                TaskType::DecryptPipeline => "解密、转换并添加",
+                                TaskType::ZeusPreparation => "为 Zeus 检查轨道",
                             });
                             
                             ui.add_space(5.0);
@@ -1947,6 +3621,9 @@ impl UIComponents {
                                 ui.label("状态:");
                                 match &progress.status {
                                     TaskStatus::Pending => ui.colored_label(egui::Color32::GRAY, "等待中"),
+                                    TaskStatus::Running if is_paused => {
+                                        ui.colored_label(egui::Color32::YELLOW, "已暂停（当前文件完成后不再开始新文件）")
+                                    }
                                     TaskStatus::Running => ui.colored_label(egui::Color32::GREEN, "处理中"),
                                     TaskStatus::Completed => ui.colored_label(egui::Color32::BLUE, "已完成"),
                                     TaskStatus::Failed(e) => ui.colored_label(egui::Color32::RED, &format!("失败: {}", e)),
@@ -1993,6 +3670,18 @@ impl UIComponents {
                                     }
                                 });
                             }
+
+                            // 消息通道健康状况
+                            let health = task_processor.channel_health();
+                            if health.is_lagging() {
+                                ui.colored_label(
+                                    egui::Color32::from_rgb(200, 120, 0),
+                                    format!(
+                                        "⚠️ 界面更新滞后（待处理消息 {}/{}，已合并 {} 条进度），转换不受影响",
+                                        health.queued, health.capacity, health.coalesced
+                                    ),
+                                );
+                            }
                         });
                     });
                     
@@ -2005,6 +3694,12 @@ impl UIComponents {
                                 should_cancel = true;
                             }
                         }
+                        // 音频/视频转换（串行、并行和分片）在开始下一个文件或分片前检查暂停
+                        let can_pause = matches!(progress.task_type, TaskType::AudioConvert | TaskType::VideoConvert)
+                            && matches!(progress.status, TaskStatus::Running);
+                        if can_pause && ui.button(if is_paused { "继续" } else { "暂停" }).clicked() {
+                            toggle_pause = true;
+                        }
                         
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                             if matches!(progress.status, TaskStatus::Completed | TaskStatus::Failed(_) | TaskStatus::Cancelled) {
@@ -2031,8 +3726,16 @@ impl UIComponents {
             state.task_manager.show_progress = false;
         }
         
-        if should_cancel {
-            task_processor.cancel_task();
+        if toggle_pause {
+            if is_paused {
+                task_processor.resume_task();
+            } else {
+                task_processor.pause_task();
+            }
+        }
+
+        if should_cancel {
+            task_processor.cancel_task();
             state.task_manager.cancel_task();
         }
     }
@@ -2135,21 +3838,7 @@ impl UIComponents {
                                 egui::ScrollArea::vertical()
                                     .max_height(150.0)
                                     .show(ui, |ui| {
-                                        let mut indices_to_remove = Vec::new();
-                                        
-                                        for (i, file) in state.audio_convert_selected_files.iter().enumerate() {
-                                            ui.horizontal(|ui| {
-                                                ui.label(format!("{}. {}", i + 1, file.file_name().unwrap_or_default().to_string_lossy()));
-                                                if ui.small_button("移除").clicked() {
-                                                    indices_to_remove.push(i);
-                                                }
-                                            });
-                                        }
-                                        
-                                        // 从后往前移除，避免索引问题
-                                        for &index in indices_to_remove.iter().rev() {
-                                            state.audio_convert_selected_files.remove(index);
-                                        }
+                                        Self::render_reorderable_file_list(ui, &mut state.audio_convert_selected_files);
                                     });
                             }
                         });
@@ -2191,7 +3880,48 @@ impl UIComponents {
                             ui.heading("支持格式");
                             ui.add_space(5.0);
                             ui.label("输入格式: MP3, WAV, FLAC, AAC, M4A, WMA, OGG, OPUS");
-                            ui.label("输出格式: OGG (Vorbis 编码，质量等级 5)");
+                            ui.label("输出格式: OGG (Vorbis 编码)");
+                            ui.horizontal(|ui| {
+                                ui.label("质量等级:");
+                                ui.add(egui::Slider::new(&mut state.audio_convert_options.quality, 0..=10))
+                                    .on_hover_text("0 体积最小，10 音质最好；默认 5 为音质与体积的平衡点");
+                                if state.audio_convert_options.quality != crate::models::AudioConvertOptions::DEFAULT_QUALITY
+                                    && ui.small_button("恢复默认").clicked()
+                                {
+                                    state.audio_convert_options.quality = crate::models::AudioConvertOptions::DEFAULT_QUALITY;
+                                }
+                            });
+
+                            ui.checkbox(&mut state.audio_convert_options.normalize_loudness, "响度归一化")
+                                .on_hover_text("使用 FFmpeg loudnorm 滤镜（单遍）将所有文件的响度统一到目标值");
+                            if state.audio_convert_options.normalize_loudness {
+                                ui.horizontal(|ui| {
+                                    ui.label("目标响度:");
+                                    ui.add(
+                                        egui::Slider::new(
+                                            &mut state.audio_convert_options.target_lufs,
+                                            crate::models::AudioConvertOptions::TARGET_LUFS_RANGE,
+                                        )
+                                        .suffix(" LUFS"),
+                                    );
+                                    if state.audio_convert_options.target_lufs != crate::models::AudioConvertOptions::DEFAULT_TARGET_LUFS
+                                        && ui.small_button("恢复默认").clicked()
+                                    {
+                                        state.audio_convert_options.target_lufs = crate::models::AudioConvertOptions::DEFAULT_TARGET_LUFS;
+                                    }
+                                });
+                                ui.label(
+                                    egui::RichText::new("响度已统一，添加为轨道后一般无需再调整每个轨道的分贝值")
+                                        .small()
+                                        .weak(),
+                                );
+                            }
+                            
+                            if !state.audio_convert_selected_files.is_empty() {
+                                let workload = state.audio_convert_size_cache.total_size(&state.audio_convert_selected_files) as f64
+                                    * state.audio_convert_options.encode_cost();
+                                Self::show_time_estimate(ui, &state.task_manager, crate::models::TaskType::AudioConvert, workload);
+                            }
                             
                             // FFmpeg状态显示
                             ui.add_space(5.0);
@@ -2207,7 +3937,7 @@ impl UIComponents {
                                             (true, "✓ FFmpeg 已就绪".to_string())
                                         }
                                     } else {
-                                        (false, "✗ FFmpeg 未就绪 - 请通过插件管理下载或配置".to_string())
+                                        (false, crate::utils::constants::app::FFMPEG_NOT_READY_HINT.to_string())
                                     }
                                 }
                                 Err(_) => (false, "✗ 无法初始化 FFmpeg 插件".to_string())
@@ -2230,11 +3960,16 @@ impl UIComponents {
                                 Err(_) => false,
                             };
                             
+                            let task_running = state.task_manager.is_running();
                             let can_convert = !state.audio_convert_selected_files.is_empty() 
                                 && state.audio_convert_output_directory.is_some()
-                                && ffmpeg_available;
+                                && ffmpeg_available
+                                && !task_running;
                             
-                            if ui.add_enabled(can_convert, egui::Button::new("开始转换")).clicked() {
+                            if ui.add_enabled(can_convert, egui::Button::new("开始转换"))
+                                .on_disabled_hover_text(if task_running { "任务进行中" } else { "请先选择文件和输出目录" })
+                                .clicked()
+                            {
                                 should_convert = true;
                             }
                             
@@ -2271,6 +4006,10 @@ impl UIComponents {
 
         let safe_pos = Self::calculate_safe_position(ctx, [600.0, 400.0].into(), [100.0, 100.0].into());
         let mut should_close = false;
+        let mut open_dir_error = None;
+        let mut log_message = None;
+        let mut should_retry = false;
+        let retry_count = Self::retryable_failure_count(state, crate::models::TaskType::AudioConvert);
         
         egui::Window::new("音频转换结果")
             .open(&mut state.show_audio_convert_result)
@@ -2306,15 +4045,122 @@ impl UIComponents {
                             ui.output_mut(|o| o.copied_text = result.clone());
                         }
                     }
+
+                    if let Some(e) = Self::render_open_output_dir_button(ui, state.audio_convert_result_dir.as_deref()) {
+                        open_dir_error = Some(e);
+                    }
+
+                    if let Some(message) = Self::render_save_log_button(ui, "音频转换", state.audio_convert_result.as_deref()) {
+                        log_message = Some(message);
+                    }
+
+                    if retry_count > 0 && ui.button(format!("重试失败项 ({})", retry_count)).clicked() {
+                        should_retry = true;
+                    }
                 });
             });
         
+        if should_retry {
+            state.audio_convert_selected_files = std::mem::take(&mut state.last_failed_conversions);
+            state.should_convert_audio = true;
+            should_close = true;
+        }
+
+        if open_dir_error.is_some() {
+            state.file_operation_message = open_dir_error;
+        }
+        if log_message.is_some() {
+            state.file_operation_message = log_message;
+        }
+
         if should_close {
             state.show_audio_convert_result = false;
             state.audio_convert_result = None;
         }
     }
 
+    /// 高级分片设置：分片参数和所选文件的预计分片数
+    fn render_video_chunk_settings(
+        ui: &mut egui::Ui,
+        config: &mut crate::video_chunk_converter::VideoChunkConfig,
+        files: &[std::path::PathBuf],
+        durations: &mut crate::models::BackgroundPathCache<Option<u32>>,
+    ) {
+        use crate::video_chunk_converter::VideoChunkConfig;
+
+        ui.label(egui::RichText::new("文件较多（超过 3 个）或平均大小超过 100MB 时使用分片并行转换").small().weak());
+        ui.checkbox(&mut config.smart_chunking, "智能分片")
+            .on_hover_text("按视频时长自动调整分片时长和数量（推荐）");
+        if config.smart_chunking {
+            ui.label(egui::RichText::new("智能分片开启时，以下分片时长和快速模式会按视频时长自动调整").small().weak());
+        }
+        ui.horizontal(|ui| {
+            ui.label("分片时长 (秒):");
+            ui.add(egui::Slider::new(&mut config.chunk_duration, VideoChunkConfig::CHUNK_DURATION_RANGE));
+        });
+        ui.horizontal(|ui| {
+            ui.label("重叠时长 (秒):");
+            ui.add(egui::Slider::new(&mut config.overlap_duration, VideoChunkConfig::OVERLAP_DURATION_RANGE))
+                .on_hover_text("相邻分片重叠的时长，避免拼接处音视频不同步");
+        });
+        ui.horizontal(|ui| {
+            ui.label("最大分片数:");
+            ui.add(egui::Slider::new(&mut config.max_chunks, VideoChunkConfig::MAX_CHUNKS_RANGE));
+        });
+        ui.checkbox(&mut config.fast_mode, "快速模式")
+            .on_hover_text("使用更快的编码预设，画质略有下降（适合短视频）");
+        let default_config = VideoChunkConfig { output_format: config.output_format, ..VideoChunkConfig::default() };
+        if *config != default_config && ui.small_button("恢复默认").clicked() {
+            *config = default_config;
+        }
+
+        if files.is_empty() {
+            return;
+        }
+        ui.separator();
+
+        let mut total_chunks = 0;
+        let mut unknown = 0;
+        egui::ScrollArea::vertical()
+            .id_source("video_chunk_predictions")
+            .max_height(120.0)
+            .show(ui, |ui| {
+                egui::Grid::new("video_chunk_prediction_grid").striped(true).show(ui, |ui| {
+                    for path in files {
+                        ui.label(path.file_name().unwrap_or_default().to_string_lossy().to_string());
+                        // 时长由后台线程读取，读取完成前显示“读取中”
+                        match durations.get_or_request(path) {
+                            Some(Some(duration)) => {
+                                let chunks = config.predicted_chunk_count(*duration);
+                                total_chunks += chunks;
+                                ui.label(format!("{}:{:02}", duration / 60, duration % 60));
+                                ui.label(format!("{} 个分片", chunks));
+                            }
+                            Some(None) => {
+                                unknown += 1;
+                                ui.label("-");
+                                ui.label(egui::RichText::new("无法读取时长").weak());
+                            }
+                            None => {
+                                unknown += 1;
+                                ui.label("-");
+                                ui.label(egui::RichText::new("读取中...").weak());
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        if durations.is_loading() {
+            ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+        }
+        if unknown > 0 {
+            ui.label(format!("预计共 {} 个分片（{} 个文件时长未知）", total_chunks, unknown));
+        } else {
+            ui.label(format!("预计共 {} 个分片", total_chunks));
+        }
+    }
+
     /// 显示视频转换对话框
     pub fn show_video_converter_dialog(ctx: &egui::Context, state: &mut AppState) {
         if !state.show_video_converter {
@@ -2463,14 +4309,83 @@ impl UIComponents {
                     
                     ui.add_space(10.0);
                     
+                    // 输出格式选择区域
+                    ui.group(|ui| {
+                        ui.vertical(|ui| {
+                            ui.heading("输出格式");
+                            ui.add_space(5.0);
+                            
+                            ui.horizontal(|ui| {
+                                for format in crate::video_converter::VideoFormat::ALL {
+                                    ui.radio_value(&mut state.video_output_format, format, format.display_name());
+                                }
+                            });
+                            
+                            if state.video_output_format.is_arma_compatible() {
+                                ui.label("OGV 是 Arma 3 视频模组唯一支持的格式（推荐）");
+                            } else {
+                                ui.colored_label(egui::Color32::from_rgb(255, 165, 0),
+                                    "⚠️ WebM 无法用于 Arma 视频模组，仅适合预览/文档；导出视频模组请使用 OGV");
+                            }
+
+                            ui.add_space(5.0);
+                            let options = &mut state.video_convert_options;
+                            ui.horizontal(|ui| {
+                                ui.label("分辨率上限:");
+                                egui::ComboBox::from_id_source("video_max_height")
+                                    .selected_text(options.resolution_name())
+                                    .show_ui(ui, |ui| {
+                                        for (height, name) in crate::models::VideoConvertOptions::RESOLUTION_PRESETS {
+                                            ui.selectable_value(&mut options.max_height, height, name);
+                                        }
+                                    });
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("视频质量:");
+                                ui.add(egui::Slider::new(&mut options.video_quality, 0..=10));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("音频质量:");
+                                ui.add(egui::Slider::new(&mut options.audio_quality, 0..=10));
+                            });
+                            if *options != crate::models::VideoConvertOptions::default()
+                                && ui.small_button("恢复默认").clicked()
+                            {
+                                *options = crate::models::VideoConvertOptions::default();
+                            }
+                        });
+                    });
+
+                    ui.add_space(5.0);
+                    egui::CollapsingHeader::new("高级分片设置")
+                        .id_source("video_chunk_settings")
+                        .show(ui, |ui| {
+                            Self::render_video_chunk_settings(
+                                ui,
+                                &mut state.video_chunk_config,
+                                &state.video_convert_selected_files,
+                                &mut state.video_durations,
+                            );
+                        });
+                    
+                    if !state.video_convert_selected_files.is_empty() {
+                        ui.add_space(5.0);
+                        let workload = state.video_convert_size_cache.total_size(&state.video_convert_selected_files) as f64
+                            * state.video_output_format.encode_cost()
+                            * state.video_convert_options.encode_cost();
+                        Self::show_time_estimate(ui, &state.task_manager, crate::models::TaskType::VideoConvert, workload);
+                    }
+                    
+                    ui.add_space(10.0);
+                    
                     // 转换说明
                     ui.group(|ui| {
                         ui.vertical(|ui| {
                             ui.heading("转换说明");
                             ui.add_space(5.0);
-                            ui.label("• 将视频文件转换为OGV格式，适用于武装突袭三");
+                            ui.label(format!("• 将视频文件转换为 {} 格式", state.video_output_format.extension().to_uppercase()));
                             ui.label("• 转换后的文件将保存到指定的输出目录");
-                            ui.label("• 转换过程中会保持原始视频的质量和分辨率");
+                            ui.label("• 超过分辨率上限的视频会按比例缩小，较小的视频保持原始分辨率");
                             ui.label("• 支持批量转换多个文件");
                             
                             // FFmpeg状态显示
@@ -2487,7 +4402,7 @@ impl UIComponents {
                                             (true, "✓ FFmpeg 已就绪".to_string())
                                         }
                                     } else {
-                                        (false, "✗ FFmpeg 未就绪 - 请通过插件管理下载或配置".to_string())
+                                        (false, crate::utils::constants::app::FFMPEG_NOT_READY_HINT.to_string())
                                     }
                                 }
                                 Err(_) => (false, "✗ 无法初始化 FFmpeg 插件".to_string())
@@ -2510,11 +4425,16 @@ impl UIComponents {
                             Err(_) => false,
                         };
                         
+                        let task_running = state.task_manager.is_running();
                         let can_convert = !state.video_convert_selected_files.is_empty() 
                             && state.video_convert_output_directory.is_some()
-                            && ffmpeg_available;
+                            && ffmpeg_available
+                            && !task_running;
                         
-                        if ui.add_enabled(can_convert, egui::Button::new("开始转换")).clicked() {
+                        if ui.add_enabled(can_convert, egui::Button::new("开始转换"))
+                            .on_disabled_hover_text(if task_running { "任务进行中" } else { "请先选择文件和输出目录" })
+                            .clicked()
+                        {
                             should_convert = true;
                             should_close = true;
                         }
@@ -2523,89 +4443,1356 @@ impl UIComponents {
                             should_convert = true;
                             should_close = true;
                         }
-                        
-                        if ui.button("FFmpeg插件管理").clicked() {
-                            state.show_ffmpeg_plugin = true;
-                        }
-                        
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("取消").clicked() {
-                                should_close = true;
-                            }
-                        });
+                        
+                        if ui.button("FFmpeg插件管理").clicked() {
+                            state.show_ffmpeg_plugin = true;
+                        }
+                        
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button("取消").clicked() {
+                                should_close = true;
+                            }
+                        });
+                    });
+                });
+            });
+        
+        if should_convert {
+            state.should_convert_video = true;
+        }
+        
+        if should_close {
+            state.show_video_converter = false;
+        }
+    }
+
+    /// 显示视频转换结果对话框
+    pub fn show_video_convert_result_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_video_convert_result {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [600.0, 400.0].into(), [100.0, 100.0].into());
+        let mut should_close = false;
+        let mut open_dir_error = None;
+        let mut log_message = None;
+        let mut should_retry = false;
+        let retry_count = Self::retryable_failure_count(state, crate::models::TaskType::VideoConvert);
+        
+        egui::Window::new("视频转换结果")
+            .open(&mut state.show_video_convert_result)
+            .default_pos(safe_pos)
+            .resizable(true)
+            .default_size([600.0, 400.0])
+            .min_size([400.0, 200.0])
+            .max_size([800.0, 600.0])
+            .show(ctx, |ui| {
+                ui.set_min_height(ui.available_height());
+                
+                if let Some(ref result) = state.video_convert_result {
+                    Self::show_scrollable_result_content(
+                        ui,
+                        result,
+                        "转换结果",
+                        &["转换完成！", "转换失败！"],
+                        &[],
+                        &["输出目录:", "统计信息:", "路径:"],
+                    );
+                }
+                
+                ui.add_space(10.0);
+                
+                // 按钮区域
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("确定").clicked() {
+                        should_close = true;
+                    }
+                    
+                    if ui.button("复制结果").clicked() {
+                        if let Some(ref result) = state.video_convert_result {
+                            ui.output_mut(|o| o.copied_text = result.clone());
+                        }
+                    }
+
+                    if let Some(e) = Self::render_open_output_dir_button(ui, state.video_convert_result_dir.as_deref()) {
+                        open_dir_error = Some(e);
+                    }
+
+                    if let Some(message) = Self::render_save_log_button(ui, "视频转换", state.video_convert_result.as_deref()) {
+                        log_message = Some(message);
+                    }
+
+                    if retry_count > 0 && ui.button(format!("重试失败项 ({})", retry_count)).clicked() {
+                        should_retry = true;
+                    }
+                });
+            });
+        
+        if should_retry {
+            state.video_convert_selected_files = std::mem::take(&mut state.last_failed_conversions);
+            state.should_convert_video = true;
+            should_close = true;
+        }
+
+        if open_dir_error.is_some() {
+            state.file_operation_message = open_dir_error;
+        }
+        if log_message.is_some() {
+            state.file_operation_message = log_message;
+        }
+
+        if should_close {
+            state.show_video_convert_result = false;
+        }
+    }
+
+    /// 上一批指定类型转换中可重试的失败文件数（任务进行中或上一个任务不是该类型时为 0）
+    fn retryable_failure_count(state: &AppState, task_type: crate::models::TaskType) -> usize {
+        let last_matches = state
+            .task_manager
+            .task_history
+            .last()
+            .is_some_and(|task| task.task_type == task_type);
+        if last_matches && !state.task_manager.is_running() {
+            state.last_failed_conversions.len()
+        } else {
+            0
+        }
+    }
+
+    /// 显示转换历史面板（本次会话）
+    pub fn show_conversion_history_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_conversion_history {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [650.0, 400.0].into(), [120.0, 120.0].into());
+        let mut should_close = false;
+        let mut should_clear = false;
+        let mut add_output: Option<usize> = None;
+        let mut reconvert: Option<usize> = None;
+
+        egui::Window::new("转换历史（本次会话）")
+            .open(&mut state.show_conversion_history)
+            .default_pos(safe_pos)
+            .resizable(true)
+            .default_size([650.0, 400.0])
+            .min_size([450.0, 200.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new("记录本次会话中解密与转换产生的文件，退出程序后自动清空。")
+                        .small()
+                        .weak(),
+                );
+                ui.add_space(5.0);
+
+                if state.conversion_history.is_empty() {
+                    ui.label("暂无转换记录");
+                } else {
+                    egui::ScrollArea::vertical()
+                        .max_height(ui.available_height() - 40.0)
+                        .show(ui, |ui| {
+                            for (index, entry) in state.conversion_history.iter().enumerate().rev() {
+                                let type_label = match entry.task_type {
+                                    crate::models::TaskType::AudioDecrypt => "解密",
+                                    crate::models::TaskType::AudioConvert => "音频",
+                                    crate::models::TaskType::VideoConvert => "视频",
+                                    crate::models::TaskType::ChapterSplit => "章节",
+                                    crate::models::TaskType::DecryptPipeline => "解密转换",
+                                    _ => "其他",
+                                };
+                                let source_name = entry.source.file_name().unwrap_or_default().to_string_lossy();
+                                let output_name = entry.output.file_name().unwrap_or_default().to_string_lossy();
+                                let output_ext = entry.output
+                                    .extension()
+                                    .map(|ext| ext.to_string_lossy().to_lowercase())
+                                    .unwrap_or_default();
+
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("[{}]", type_label));
+                                    ui.label(format!("{} → {}", source_name, output_name))
+                                        .on_hover_text(entry.output.display().to_string());
+
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                        if ui.button("重新转换").clicked() {
+                                            reconvert = Some(index);
+                                        }
+                                        let can_add = entry.output_exists && match state.project.mod_type {
+                                            crate::models::ModType::Music => output_ext == "ogg",
+                                            crate::models::ModType::Video => output_ext == "ogv",
+                                        };
+                                        if ui.add_enabled(can_add, egui::Button::new("添加到列表")).clicked() {
+                                            add_output = Some(index);
+                                        }
+                                    });
+                                });
+                            }
+                        });
+                }
+
+                ui.add_space(5.0);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("关闭").clicked() {
+                        should_close = true;
+                    }
+                    if ui.add_enabled(!state.conversion_history.is_empty(), egui::Button::new("清空历史")).clicked() {
+                        should_clear = true;
+                    }
+                });
+            });
+
+        if let Some(entry) = add_output.and_then(|index| state.conversion_history.get(index).cloned()) {
+            let added = match state.project.mod_type {
+                crate::models::ModType::Music => {
+                    FileOperations::load_audio_files(vec![entry.output.clone()], &state.project.class_name, &state.track_defaults)
+                        .map(|tracks| state.add_tracks_with_duplicate_check(tracks).0)
+                }
+                crate::models::ModType::Video => {
+                    FileOperations::load_video_files(vec![entry.output.clone()], &state.project.class_name)
+                        .map(|videos| state.add_videos_with_duplicate_check(videos).0)
+                }
+            };
+            state.file_operation_message = Some(match added {
+                Ok(0) => format!("已在列表中: {}", entry.output.display()),
+                Ok(_) => format!("已从转换历史添加: {}", entry.output.display()),
+                Err(e) => format!("添加失败: {}", e),
+            });
+        }
+
+        if let Some(entry) = reconvert.and_then(|index| state.conversion_history.get(index).cloned()) {
+            // 解密输出需转换为OGG；转换输出则从源文件重新转换
+            let output_dir = entry.output.parent().map(|dir| dir.to_path_buf());
+            match entry.task_type {
+                crate::models::TaskType::VideoConvert => {
+                    state.video_convert_selected_files = vec![entry.source];
+                    state.video_convert_output_directory = output_dir;
+                    state.show_video_converter = true;
+                }
+                crate::models::TaskType::ChapterSplit => {
+                    state.chapter_split_source = Some(entry.source);
+                    state.chapter_split_output_dir = output_dir;
+                    state.show_chapter_split = true;
+                }
+                crate::models::TaskType::AudioDecrypt => {
+                    state.audio_convert_selected_files = vec![entry.output];
+                    state.audio_convert_output_directory = output_dir;
+                    state.show_audio_converter = true;
+                }
+                crate::models::TaskType::DecryptPipeline => {
+                    state.audio_decrypt_selected_files = vec![entry.source];
+                    state.audio_decrypt_output_directory = output_dir;
+                    state.show_audio_decrypt = true;
+                }
+                _ => {
+                    state.audio_convert_selected_files = vec![entry.source];
+                    state.audio_convert_output_directory = output_dir;
+                    state.show_audio_converter = true;
+                }
+            }
+        }
+
+        if should_clear {
+            state.conversion_history.clear();
+        }
+        if should_close {
+            state.show_conversion_history = false;
+        }
+    }
+
+    /// 显示相似轨道对话框（审阅后再移除或合并）
+    pub fn show_similar_tracks_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_similar_tracks {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [650.0, 450.0].into(), [120.0, 120.0].into());
+        let mut should_close = false;
+        let mut should_rescan = false;
+        let mut to_remove: Vec<std::path::PathBuf> = Vec::new();
+
+        egui::Window::new("相似轨道")
+            .open(&mut state.show_similar_tracks)
+            .default_pos(safe_pos)
+            .resizable(true)
+            .default_size([650.0, 450.0])
+            .min_size([450.0, 250.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new("按名称相似度和音频指纹找出疑似重复添加的轨道。请逐组确认后再移除，分析本身不会修改项目。")
+                        .small()
+                        .weak(),
+                );
+                if state.similar_track_undecodable > 0 {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0),
+                        format!("⚠️ {} 个文件无法解码，仅按名称比较", state.similar_track_undecodable));
+                }
+                ui.add_space(5.0);
+
+                let groups = state.similar_track_groups.as_deref().unwrap_or_default();
+                if groups.is_empty() {
+                    ui.label("未发现疑似重复的轨道");
+                } else {
+                    ui.label(format!("发现 {} 组疑似重复:", groups.len()));
+                    ui.add_space(5.0);
+
+                    egui::ScrollArea::vertical()
+                        .max_height(ui.available_height() - 40.0)
+                        .show(ui, |ui| {
+                            for (group_index, group) in groups.iter().enumerate() {
+                                ui.group(|ui| {
+                                    ui.label(egui::RichText::new(format!("第 {} 组（{}）", group_index + 1, group.reason())).strong());
+
+                                    for path in &group.paths {
+                                        let track = state.tracks.iter().find(|t| &t.path == path);
+                                        ui.horizontal(|ui| {
+                                            match track {
+                                                Some(track) => {
+                                                    // 使用导入或重新定位时缓存的文件大小
+                                                    let size = track
+                                                        .file_size
+                                                        .map(|size| format!("{:.1} MB", size as f64 / 1_048_576.0))
+                                                        .unwrap_or_else(|| "文件缺失".to_string());
+                                                    ui.label(format!("{}  ({}s, {})", track.track_name, track.duration, size))
+                                                        .on_hover_text(path.display().to_string());
+                                                }
+                                                None => {
+                                                    ui.label(egui::RichText::new(format!("{}（已移除）", path.display())).weak());
+                                                }
+                                            }
+
+                                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                                if track.is_some() {
+                                                    if ui.small_button("移除").clicked() {
+                                                        to_remove.push(path.clone());
+                                                    }
+                                                    if ui.small_button("仅保留此项")
+                                                        .on_hover_text("移除本组中的其他轨道")
+                                                        .clicked()
+                                                    {
+                                                        to_remove.extend(group.paths.iter().filter(|p| *p != path).cloned());
+                                                    }
+                                                }
+                                            });
+                                        });
+                                    }
+                                });
+                                ui.add_space(5.0);
+                            }
+                        });
+                }
+
+                ui.add_space(5.0);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("关闭").clicked() {
+                        should_close = true;
+                    }
+                    if ui.button("重新分析").clicked() {
+                        should_rescan = true;
+                    }
+                });
+            });
+
+        if !to_remove.is_empty() {
+            let removed = state.remove_tracks_by_path(&to_remove);
+            state.file_operation_message = Some(format!("已移除 {} 个相似轨道", removed));
+        }
+        if should_rescan {
+            state.should_scan_similar_tracks = true;
+        }
+        if should_close || should_rescan {
+            state.show_similar_tracks = false;
+        }
+    }
+
+    /// 显示游戏内顺序预览（只读，随轨道顺序和导出设置实时更新）
+    pub fn show_ingame_order_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_ingame_order {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [500.0, 500.0].into(), [140.0, 100.0].into());
+        let mut append_tags = state.export_settings.append_tags;
+        let mut use_stringtable = state.export_settings.use_stringtable;
+
+        egui::Window::new("游戏内顺序预览")
+            .open(&mut state.show_ingame_order)
+            .default_pos(safe_pos)
+            .resizable(true)
+            .default_size([500.0, 500.0])
+            .min_size([350.0, 250.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new("按导出顺序列出轨道在 Zeus 音乐列表中显示的名称。调整轨道顺序或下方设置后会立即更新。")
+                        .small()
+                        .weak(),
+                );
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut append_tags, "在轨道名称前添加标签");
+                    ui.checkbox(&mut use_stringtable, "使用 stringtable 本地化");
+                });
+                if use_stringtable {
+                    ui.label(
+                        egui::RichText::new("中文客户端显示下列原文，其他语言客户端显示对应的翻译。")
+                            .small()
+                            .weak(),
+                    );
+                } else {
+                    ui.label(
+                        egui::RichText::new("未使用 stringtable 时，名称会转换为 ASCII 拼音写入配置。")
+                            .small()
+                            .weak(),
+                    );
+                }
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        let number_width = state.tracks.len().to_string().len();
+                        for (i, track) in state.tracks.iter().enumerate() {
+                            let name = track.export_name(append_tags);
+                            let name = if use_stringtable {
+                                name
+                            } else {
+                                crate::utils::string_utils::StringUtils::to_ascii_safe_pinyin(&name)
+                            };
+                            ui.label(egui::RichText::new(format!("{:>width$}. {}", i + 1, name, width = number_width)).monospace())
+                                .on_hover_text(track.path.display().to_string());
+                        }
+                    });
+            });
+
+        state.export_settings.append_tags = append_tags;
+        state.export_settings.use_stringtable = use_stringtable;
+    }
+
+    /// 显示配置恢复对话框（安全模式或配置加载提示）
+    pub fn show_config_recovery_dialog(ctx: &egui::Context, state: &mut AppState) {
+        let show_safe_mode = state.safe_mode && !state.safe_mode_dialog_dismissed;
+        if !show_safe_mode && state.config_load_notice.is_none() {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [500.0, 260.0].into(), [150.0, 150.0].into());
+        let title = if state.safe_mode { "安全模式" } else { "配置加载提示" };
+        let mut should_close = false;
+        let mut should_backup = false;
+        let mut should_reset = false;
+
+        egui::Window::new(title)
+            .default_pos(safe_pos)
+            .resizable(false)
+            .collapsible(false)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                if state.safe_mode {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "⚠️ 当前以安全模式运行");
+                    ui.label("已跳过配置文件加载，所有设置均为默认值；安全模式下不会写入配置文件。");
+                    ui.label("如果配置文件导致程序无法正常启动，可以先备份再重置配置。");
+                }
+                if let Some(ref notice) = state.config_load_notice {
+                    ui.add_space(5.0);
+                    ui.label(notice);
+                }
+
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if state.safe_mode {
+                        if ui.button("备份现有配置").clicked() {
+                            should_backup = true;
+                        }
+                        if ui.button("重置配置并退出安全模式")
+                            .on_hover_text("先备份现有配置，再删除配置文件；之后的设置会正常保存")
+                            .clicked()
+                        {
+                            should_reset = true;
+                        }
+                    }
+                    if ui.button(if state.safe_mode { "继续使用安全模式" } else { "确定" }).clicked() {
+                        should_close = true;
+                    }
+                });
+            });
+
+        if should_backup {
+            state.config_load_notice = Some(match AppState::backup_config_file() {
+                Ok(path) => format!("配置已备份到: {}", path.display()),
+                Err(e) => format!("备份配置失败: {}", e),
+            });
+        }
+        if should_reset {
+            match AppState::reset_config_file() {
+                Ok(backup) => {
+                    state.safe_mode = false;
+                    state.config_load_notice = None;
+                    state.file_operation_message = Some(match backup {
+                        Some(path) => format!("配置已重置，原配置备份于: {}", path.display()),
+                        None => "配置已重置".to_string(),
+                    });
+                    if let Err(e) = state.save_config() {
+                        warn!("保存默认配置失败: {}", e);
+                    }
+                }
+                Err(e) => {
+                    state.config_load_notice = Some(format!("重置配置失败: {}", e));
+                }
+            }
+        }
+        if should_close {
+            // 安全模式本身保持开启，仅关闭提示
+            state.config_load_notice = None;
+            state.safe_mode_dialog_dismissed = state.safe_mode;
+        }
+    }
+
+    /// 显示按章节分割对话框
+    pub fn show_chapter_split_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_chapter_split {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [550.0, 450.0].into(), [130.0, 130.0].into());
+        let mut should_pick_source = false;
+        let mut should_split = false;
+
+        egui::Window::new("按章节分割")
+            .open(&mut state.show_chapter_split)
+            .default_pos(safe_pos)
+            .resizable(true)
+            .default_size([550.0, 450.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new("读取音频文件内的章节标记（FLAC cuesheet、Vorbis CHAPTER 注释），每个章节生成一个 OGG 文件并添加为轨道。")
+                        .small()
+                        .weak(),
+                );
+                ui.add_space(5.0);
+
+                ui.horizontal(|ui| {
+                    ui.label("源文件:");
+                    match state.chapter_split_source {
+                        Some(ref path) => ui.label(path.display().to_string()),
+                        None => ui.label("未选择"),
+                    };
+                    if ui.button("选择...").clicked() {
+                        should_pick_source = true;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("输出目录:");
+                    match state.chapter_split_output_dir {
+                        Some(ref path) => ui.label(path.display().to_string()),
+                        None => ui.label("未选择"),
+                    };
+                    if ui.button("选择...").clicked() {
+                        if let Some(dir) = FileOperations::select_export_directory() {
+                            state.chapter_split_output_dir = Some(dir);
+                        }
+                    }
+                });
+
+                ui.separator();
+                if let Some(path) = &state.chapter_split_source {
+                    state.chapter_cache.get_or_request(path);
+                }
+                let loading = state.chapter_cache.is_loading();
+                let loaded = state.chapter_split_source.as_ref().and_then(|path| state.chapter_cache.get(path));
+                let chapters = match loaded {
+                    Some(Ok(chapters)) if !chapters.is_empty() => {
+                        ui.label(format!("共 {} 个章节:", chapters.len()));
+                        egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+                            for (i, chapter) in chapters.iter().enumerate() {
+                                let end = chapter
+                                    .end_seconds
+                                    .map(Self::format_seconds)
+                                    .unwrap_or_else(|| "结束".to_string());
+                                ui.label(format!(
+                                    "{:02}. {}  ({} - {})",
+                                    i + 1,
+                                    chapter.title,
+                                    Self::format_seconds(chapter.start_seconds),
+                                    end
+                                ));
+                            }
+                        });
+                        chapters.len()
+                    }
+                    Some(Ok(_)) => {
+                        ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "该文件没有章节信息，无法按章节分割");
+                        0
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(egui::Color32::RED, format!("读取章节失败: {}", e));
+                        0
+                    }
+                    None if loading => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("正在读取章节...");
+                        });
+                        ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+                        0
+                    }
+                    None => 0,
+                };
+
+                ui.add_space(10.0);
+                let can_split = chapters > 0 && state.chapter_split_output_dir.is_some();
+                if ui.add_enabled(can_split, egui::Button::new("按章节分割")).clicked() {
+                    should_split = true;
+                }
+            });
+
+        if should_pick_source {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("音频文件", &["ogg", "flac", "mp3", "m4a", "wav"])
+                .set_title("选择包含章节的音频文件")
+                .pick_file()
+            {
+                if state.chapter_split_output_dir.is_none() {
+                    state.chapter_split_output_dir = path.parent().map(|dir| dir.to_path_buf());
+                }
+                state.chapter_split_source = Some(path);
+            }
+        }
+        if should_split {
+            state.should_split_chapters = true;
+        }
+    }
+
+    /// 显示转换耗时估算（粗略估算，取决于硬件性能）
+    fn show_time_estimate(ui: &mut egui::Ui, task_manager: &crate::models::TaskManager, task_type: crate::models::TaskType, workload: f64) {
+        let Some((seconds, from_history)) = task_manager.estimate_seconds(&task_type, workload) else {
+            return;
+        };
+        let text = if seconds < 60 {
+            "预计耗时不到 1 分钟".to_string()
+        } else if seconds < 3600 {
+            format!("预计耗时约 {} 分钟", seconds.div_ceil(60))
+        } else {
+            format!("预计耗时约 {} 小时 {} 分钟", seconds / 3600, seconds % 3600 / 60)
+        };
+        let hint = if from_history {
+            "根据本次会话中已完成转换的速度估算，实际耗时取决于硬件性能和文件内容"
+        } else {
+            "使用默认速度粗略估算，完成一次转换后会更准确；实际耗时取决于硬件性能和文件内容"
+        };
+        ui.label(egui::RichText::new(format!("⏱ {}（估算）", text)).weak())
+            .on_hover_text(hint);
+    }
+
+    /// “打开输出目录”按钮：没有记录输出目录时不显示，目录已不存在时禁用；打开失败时返回错误提示
+    fn render_open_output_dir_button(ui: &mut egui::Ui, dir: Option<&std::path::Path>) -> Option<String> {
+        let dir = dir?;
+        let exists = dir.is_dir();
+        let response = ui.add_enabled(exists, egui::Button::new("打开输出目录"));
+        let response = if exists {
+            response.on_hover_text(dir.display().to_string())
+        } else {
+            response.on_disabled_hover_text(format!("输出目录已不存在: {}", dir.display()))
+        };
+        if response.clicked() {
+            if let Err(e) = crate::utils::FileUtils::open_in_file_manager(dir) {
+                warn!("打开输出目录失败: {}", e);
+                return Some(format!("打开输出目录失败: {}", e));
+            }
+        }
+        None
+    }
+
+    /// “保存日志”按钮：将完整结果文本写入用户选择的 .txt 文件，返回保存成功或失败的提示
+    fn render_save_log_button(ui: &mut egui::Ui, title: &str, result: Option<&str>) -> Option<String> {
+        let result = result?;
+        if !ui.button("保存日志").on_hover_text("将完整结果保存为文本文件，便于分享").clicked() {
+            return None;
+        }
+        let path = crate::utils::FileUtils::select_log_save_path(&format!("{}日志.txt", title))?;
+        match crate::utils::FileUtils::write_task_log(&path, title, result) {
+            Ok(()) => {
+                info!("日志已保存: {:?}", path);
+                Some(format!("日志已保存: {}", path.display()))
+            }
+            Err(e) => {
+                warn!("保存日志失败: {}", e);
+                Some(format!("保存日志失败: {}", e))
+            }
+        }
+    }
+
+    /// 显示可调整顺序的文件列表（输出文件按列表顺序编号），每行带上移/下移和移除按钮
+    ///
+    /// 每帧最多执行一个操作，移除和交换都使用当前帧的序号。
+    fn render_reorderable_file_list(ui: &mut egui::Ui, files: &mut Vec<std::path::PathBuf>) {
+        let last = files.len().saturating_sub(1);
+        let mut remove = None;
+        let mut swap = None;
+
+        for (i, file) in files.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(i > 0, egui::Button::new("⬆").small())
+                    .on_hover_text(tr("common.move_up"))
+                    .clicked()
+                {
+                    swap = Some((i - 1, i));
+                }
+                if ui.add_enabled(i < last, egui::Button::new("⬇").small())
+                    .on_hover_text(tr("common.move_down"))
+                    .clicked()
+                {
+                    swap = Some((i, i + 1));
+                }
+                ui.label(format!("{}. {}", i + 1, file.file_name().unwrap_or_default().to_string_lossy()))
+                    .on_hover_text(file.display().to_string());
+                if ui.small_button(tr("common.remove")).clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+
+        if let Some(index) = remove {
+            files.remove(index);
+        } else if let Some((a, b)) = swap {
+            files.swap(a, b);
+        }
+    }
+
+    /// 将秒数格式化为 分:秒
+    fn format_seconds(seconds: f64) -> String {
+        let total = seconds.max(0.0) as u64;
+        if total >= 3600 {
+            format!("{}:{:02}:{:02}", total / 3600, total % 3600 / 60, total % 60)
+        } else {
+            format!("{}:{:02}", total / 60, total % 60)
+        }
+    }
+
+    /// 显示时长同步结果对话框
+    pub fn show_duration_sync_result_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_duration_sync_result {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [450.0, 250.0].into(), [150.0, 150.0].into());
+        let mut should_close = false;
+
+        egui::Window::new("同步全部时长")
+            .open(&mut state.show_duration_sync_result)
+            .default_pos(safe_pos)
+            .resizable(true)
+            .default_width(450.0)
+            .show(ctx, |ui| {
+                if let Some(ref result) = state.duration_sync_result {
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        ui.label(result);
+                    });
+                }
+
+                ui.add_space(10.0);
+                if ui.button("确定").clicked() {
+                    should_close = true;
+                }
+            });
+
+        if should_close {
+            state.show_duration_sync_result = false;
+        }
+    }
+
+    /// 显示批量设置标签对话框
+    pub fn show_batch_tag_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_batch_tag {
+            return;
+        }
+
+        let indices = state.selected_track_indices();
+        let safe_pos = Self::calculate_safe_position(ctx, [380.0, 300.0].into(), [200.0, 160.0].into());
+        let mut should_close = false;
+        let mut should_apply = false;
+        let mut tag = std::mem::take(&mut state.batch_tag_text);
+
+        egui::Window::new("批量设置标签")
+            .open(&mut state.show_batch_tag)
+            .default_pos(safe_pos)
+            .resizable(true)
+            .default_size([380.0, 300.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("标签:");
+                    ui.text_edit_singleline(&mut tag);
+                });
+                ui.label(egui::RichText::new("留空将清除选中轨道的标签").small().weak());
+                ui.add_space(5.0);
+
+                ui.label(format!("将修改 {} 个轨道:", indices.len()));
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() - 40.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for &index in &indices {
+                            let track = &state.tracks[index];
+                            let mut preview = track.clone();
+                            preview.tag = tag.trim().to_string();
+                            ui.label(format!("{} → {}", track.display_name(), preview.display_name()));
+                        }
+                    });
+
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!indices.is_empty(), egui::Button::new(format!("应用到 {} 个轨道", indices.len()))).clicked() {
+                        should_apply = true;
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("取消").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+            });
+
+        if should_apply {
+            let changed = state.apply_tag_to_tracks(&indices, &tag);
+            state.file_operation_message = Some(format!("已为 {} 个轨道设置标签", changed));
+            should_close = true;
+        }
+        state.batch_tag_text = tag;
+        if should_close {
+            state.show_batch_tag = false;
+        }
+    }
+
+    /// 撤销上一次对轨道列表的破坏性修改，并显示结果提示
+    pub(crate) fn undo_track_change(state: &mut AppState) {
+        state.file_operation_message = Some(if state.undo_track_change() {
+            format!("已撤销，当前共 {} 个轨道", state.tracks.len())
+        } else {
+            "没有可撤销的操作".to_string()
+        });
+    }
+
+    /// 显示分贝渐变对话框
+    pub fn show_decibel_ramp_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_decibel_ramp {
+            return;
+        }
+
+        use crate::models::DecibelRampCurve;
+        use crate::utils::constants::file_ops::{MAX_DECIBELS, MIN_DECIBELS};
+
+        let safe_pos = Self::calculate_safe_position(ctx, [480.0, 450.0].into(), [150.0, 120.0].into());
+        let mut should_close = false;
+        let mut should_apply = false;
+        let mut ramp = state.decibel_ramp;
+        let preview = ramp.values(state.tracks.len());
+
+        egui::Window::new("分贝渐变")
+            .open(&mut state.show_decibel_ramp)
+            .default_pos(safe_pos)
+            .resizable(true)
+            .default_size([480.0, 450.0])
+            .min_size([380.0, 300.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new("按轨道列表顺序，从第一个轨道的起始值逐步过渡到最后一个轨道的结束值。")
+                        .small()
+                        .weak(),
+                );
+                ui.add_space(5.0);
+
+                ui.horizontal(|ui| {
+                    ui.label("起始 (dB):");
+                    ui.add(egui::Slider::new(&mut ramp.start, MIN_DECIBELS..=MAX_DECIBELS));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("结束 (dB):");
+                    ui.add(egui::Slider::new(&mut ramp.end, MIN_DECIBELS..=MAX_DECIBELS));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("曲线:");
+                    for curve in [DecibelRampCurve::Linear, DecibelRampCurve::EaseIn, DecibelRampCurve::EaseOut] {
+                        ui.radio_value(&mut ramp.curve, curve, curve.display_name());
+                    }
+                });
+
+                ui.separator();
+                ui.label("预览:");
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() - 40.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("decibel_ramp_preview").striped(true).show(ui, |ui| {
+                            for (i, (track, decibels)) in state.tracks.iter().zip(&preview).enumerate() {
+                                ui.label(format!("{}.", i + 1));
+                                ui.label(&track.track_name);
+                                let text = format!("{:+} → {:+} dB", track.decibels, decibels);
+                                if track.decibels == *decibels {
+                                    ui.label(egui::RichText::new(text).weak());
+                                } else {
+                                    ui.label(text);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if ui.button("应用").clicked() {
+                        should_apply = true;
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("关闭").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+            });
+
+        state.decibel_ramp = ramp;
+        if should_apply {
+            let changed = state.apply_decibel_ramp(&ramp);
+            state.file_operation_message = Some(format!("已应用分贝渐变，修改了 {} 个轨道", changed));
+        }
+        if should_close {
+            state.show_decibel_ramp = false;
+        }
+    }
+
+    /// 显示统一调整音量对话框
+    pub fn show_decibel_adjust_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_decibel_adjust {
+            return;
+        }
+
+        use crate::models::DecibelAdjustMode;
+        use crate::utils::constants::file_ops::{MAX_DECIBELS, MIN_DECIBELS};
+
+        let safe_pos = Self::calculate_safe_position(ctx, [380.0, 260.0].into(), [150.0, 120.0].into());
+        let mut should_close = false;
+        let mut should_apply = false;
+        let mut adjust = state.decibel_adjust;
+        let offset_range = (MIN_DECIBELS - MAX_DECIBELS)..=(MAX_DECIBELS - MIN_DECIBELS);
+        let clamped_count = state.tracks.iter().filter(|t| adjust.apply(t.decibels).1).count();
+        let current_range = state.decibel_range();
+
+        egui::Window::new("统一调整音量")
+            .open(&mut state.show_decibel_adjust)
+            .default_pos(safe_pos)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let previous_mode = adjust.mode;
+                    for mode in [DecibelAdjustMode::Offset, DecibelAdjustMode::Absolute] {
+                        ui.radio_value(&mut adjust.mode, mode, mode.display_name());
+                    }
+                    if adjust.mode != previous_mode {
+                        adjust.value = 0;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    match adjust.mode {
+                        DecibelAdjustMode::Offset => {
+                            ui.label("增减 (dB):");
+                            ui.add(egui::Slider::new(&mut adjust.value, offset_range.clone()));
+                        }
+                        DecibelAdjustMode::Absolute => {
+                            ui.label("目标 (dB):");
+                            ui.add(egui::Slider::new(&mut adjust.value, MIN_DECIBELS..=MAX_DECIBELS));
+                        }
+                    }
+                });
+
+                ui.separator();
+                match current_range {
+                    Some((min, max)) => ui.label(format!("当前范围: {:+} ~ {:+} dB（共 {} 个轨道）", min, max, state.tracks.len())),
+                    None => ui.label("没有轨道"),
+                };
+                if clamped_count > 0 {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 165, 0),
+                        format!("⚠️ {} 个轨道将被限制在 {} ~ {} dB 范围内", clamped_count, MIN_DECIBELS, MAX_DECIBELS),
+                    );
+                }
+
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!state.tracks.is_empty(), egui::Button::new("应用")).clicked() {
+                        should_apply = true;
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("关闭").clicked() {
+                            should_close = true;
+                        }
                     });
                 });
             });
-        
-        if should_convert {
-            state.should_convert_video = true;
+
+        state.decibel_adjust = adjust;
+        if should_apply {
+            let changed = state.apply_decibel_adjust(&adjust);
+            let range = state
+                .decibel_range()
+                .map(|(min, max)| format!("，当前范围 {:+} ~ {:+} dB", min, max))
+                .unwrap_or_default();
+            state.file_operation_message = Some(match adjust.mode {
+                DecibelAdjustMode::Offset => format!("已统一调整 {:+} dB，修改了 {} 个轨道{}", adjust.value, changed, range),
+                DecibelAdjustMode::Absolute => format!("已统一设置为 {:+} dB，修改了 {} 个轨道{}", adjust.value, changed, range),
+            });
         }
-        
         if should_close {
-            state.show_video_converter = false;
+            state.show_decibel_adjust = false;
         }
     }
 
-    /// 显示视频转换结果对话框
-    pub fn show_video_convert_result_dialog(ctx: &egui::Context, state: &mut AppState) {
-        if !state.show_video_convert_result {
+    /// 显示播放列表播放器对话框
+    pub fn show_audio_player_dialog(
+        ctx: &egui::Context,
+        state: &mut AppState,
+        player: &mut crate::audio_player::AudioPlayer,
+    ) {
+        use crate::audio_player::PlaylistEntry;
+
+        if !state.show_audio_player {
+            // 对话框关闭后停止播放
+            if player.is_active() {
+                player.stop();
+            }
             return;
         }
 
-        let safe_pos = Self::calculate_safe_position(ctx, [600.0, 400.0].into(), [100.0, 100.0].into());
+        player.sync_playlist(state.tracks.iter().map(PlaylistEntry::from_track).collect());
+        player.update();
+
+        let safe_pos = Self::calculate_safe_position(ctx, [420.0, 420.0].into(), [180.0, 140.0].into());
         let mut should_close = false;
-        
-        egui::Window::new("视频转换结果")
-            .open(&mut state.show_video_convert_result)
+        let mut play_index = None;
+
+        egui::Window::new("试听播放列表")
+            .open(&mut state.show_audio_player)
             .default_pos(safe_pos)
             .resizable(true)
-            .default_size([600.0, 400.0])
-            .min_size([400.0, 200.0])
-            .max_size([800.0, 600.0])
+            .default_size([420.0, 420.0])
+            .min_size([320.0, 260.0])
             .show(ctx, |ui| {
-                ui.set_min_height(ui.available_height());
-                
-                if let Some(ref result) = state.video_convert_result {
-                    Self::show_scrollable_result_content(
-                        ui,
-                        result,
-                        "转换结果",
-                        &["转换完成！", "转换失败！"],
-                        &[],
-                        &["输出目录:", "统计信息:", "路径:"],
-                    );
+                match player.current_entry() {
+                    Some(entry) => {
+                        ui.label(egui::RichText::new(&entry.name).strong());
+                        let status = if !player.is_active() {
+                            "已停止"
+                        } else if player.is_loading() {
+                            "加载中"
+                        } else if player.is_paused() {
+                            "已暂停"
+                        } else {
+                            "播放中"
+                        };
+                        ui.label(format!(
+                            "{}  {} / {}  ({:+} dB)",
+                            status,
+                            Self::format_seconds(player.position().as_secs_f64()),
+                            Self::format_seconds(entry.duration as f64),
+                            entry.decibels
+                        ));
+                    }
+                    None => {
+                        ui.label(egui::RichText::new("未在播放").weak());
+                    }
                 }
-                
-                ui.add_space(10.0);
-                
-                // 按钮区域
+
+                ui.horizontal(|ui| {
+                    let has_current = player.current_index().is_some();
+                    if ui.add_enabled(has_current, egui::Button::new("⏮")).on_hover_text("上一首").clicked() {
+                        player.previous();
+                    }
+                    let play_label = if player.is_active() && !player.is_paused() { "⏸" } else { "▶" };
+                    if ui.button(play_label).on_hover_text("播放/暂停").clicked() {
+                        player.toggle_pause();
+                    }
+                    if ui.add_enabled(player.is_active(), egui::Button::new("⏹")).on_hover_text("停止").clicked() {
+                        player.stop();
+                    }
+                    if ui.add_enabled(has_current, egui::Button::new("⏭")).on_hover_text("下一首").clicked() {
+                        player.next();
+                    }
+                });
+
+                if let Some(error) = player.error() {
+                    ui.colored_label(egui::Color32::from_rgb(200, 80, 80), error);
+                }
+
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() - 80.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for (i, entry) in player.playlist().iter().enumerate() {
+                            let selected = player.current_index() == Some(i);
+                            let text = format!("{}. {}", i + 1, entry.name);
+                            if ui.selectable_label(selected, text).on_hover_text("双击从此轨道开始播放").double_clicked() {
+                                play_index = Some(i);
+                            }
+                        }
+                    });
+
+                if !player.skipped().is_empty() {
+                    ui.separator();
+                    ui.label(egui::RichText::new(format!("已跳过 {} 个无法解码的轨道:", player.skipped().len())).weak());
+                    for (name, reason) in player.skipped() {
+                        ui.label(egui::RichText::new(format!("• {}", name)).small().weak()).on_hover_text(reason);
+                    }
+                }
+
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui.button("确定").clicked() {
+                    if ui.button("关闭").clicked() {
                         should_close = true;
                     }
-                    
-                    if ui.button("复制结果").clicked() {
-                        if let Some(ref result) = state.video_convert_result {
-                            ui.output_mut(|o| o.copied_text = result.clone());
+                });
+            });
+
+        if let Some(index) = play_index {
+            player.play_from(index);
+        }
+        if should_close {
+            state.show_audio_player = false;
+        }
+        if !state.show_audio_player {
+            player.stop();
+        } else if player.is_active() {
+            // 刷新播放位置并及时切换到下一首
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+    }
+
+    /// 显示关闭确认对话框（有运行中的任务或未导出的修改时）
+    pub fn show_close_confirm_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_close_confirm {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [420.0, 200.0].into(), [200.0, 150.0].into());
+        let warnings = state.close_warnings();
+        let mut should_close_window = false;
+        let mut should_cancel = false;
+        let mut dont_ask_again = !state.confirm_on_close;
+
+        egui::Window::new("确认关闭")
+            .default_pos(safe_pos)
+            .resizable(false)
+            .collapsible(false)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "⚠️ 还有未完成的工作：");
+                for warning in &warnings {
+                    ui.label(format!("• {}", warning));
+                }
+                ui.add_space(10.0);
+                ui.checkbox(&mut dont_ask_again, "不再询问");
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if ui.button("仍然关闭").clicked() {
+                        should_close_window = true;
+                    }
+                    if ui.button("取消").clicked() {
+                        should_cancel = true;
+                    }
+                });
+            });
+
+        state.confirm_on_close = !dont_ask_again;
+        // 等待确认期间任务已完成且修改已导出：不再需要确认，完成用户请求的关闭
+        if should_close_window || (!should_cancel && warnings.is_empty()) {
+            state.show_close_confirm = false;
+            state.close_confirmed = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        } else if should_cancel {
+            state.show_close_confirm = false;
+        }
+    }
+
+    /// 显示打开项目确认对话框（有未导出的修改时）
+    pub fn show_project_load_confirm_dialog(ctx: &egui::Context, state: &mut AppState) {
+        let Some(path) = state.pending_project_load.clone() else {
+            return;
+        };
+
+        let safe_pos = Self::calculate_safe_position(ctx, [420.0, 200.0].into(), [200.0, 150.0].into());
+        let dirty = state.dirty_track_count();
+        let mut should_load = false;
+        let mut should_cancel = false;
+
+        egui::Window::new("确认打开项目")
+            .default_pos(safe_pos)
+            .resizable(false)
+            .collapsible(false)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    format!("⚠️ 有 {} 个轨道自上次导出以来已修改但尚未导出", dirty),
+                );
+                ui.label(format!("打开 {} 将替换当前项目，这些修改会丢失。", path.display()));
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button("仍然打开").clicked() {
+                        should_load = true;
+                    }
+                    if ui.button("取消").clicked() {
+                        should_cancel = true;
+                    }
+                });
+            });
+
+        if should_load {
+            state.pending_project_load = None;
+            Self::load_project(state, &path);
+        } else if should_cancel {
+            state.pending_project_load = None;
+        }
+    }
+
+    /// 显示项目比较对话框（只读，不修改当前项目）
+    pub fn show_project_compare_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_project_compare {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [650.0, 500.0].into(), [120.0, 120.0].into());
+        let mut should_close = false;
+        let mut should_compare = false;
+
+        egui::Window::new("比较项目")
+            .open(&mut state.show_project_compare)
+            .default_pos(safe_pos)
+            .resizable(true)
+            .default_size([650.0, 500.0])
+            .min_size([450.0, 300.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new("选择两个项目/配置文件或导出记录（.json），列出轨道的增删和属性变化。")
+                        .small()
+                        .weak(),
+                );
+                ui.add_space(5.0);
+
+                for (label, path) in [
+                    ("旧版本:", &mut state.compare_before_path),
+                    ("新版本:", &mut state.compare_after_path),
+                ] {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        match path {
+                            Some(p) => ui.label(p.display().to_string()),
+                            None => ui.label("未选择"),
+                        };
+                        if ui.button("选择...").clicked() {
+                            if let Some(file) = rfd::FileDialog::new()
+                                .add_filter("项目文件", &["json", "zmm"])
+                                .set_title("选择要比较的项目文件")
+                                .pick_file()
+                            {
+                                *path = Some(file);
+                            }
                         }
+                    });
+                }
+
+                ui.add_space(5.0);
+                let can_compare = state.compare_before_path.is_some() && state.compare_after_path.is_some();
+                if ui.add_enabled(can_compare, egui::Button::new("比较")).clicked() {
+                    should_compare = true;
+                }
+                ui.separator();
+
+                match &state.compare_result {
+                    None => {
+                        ui.label("尚未比较");
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(egui::Color32::from_rgb(200, 50, 50), e);
+                    }
+                    Some(Ok(changes)) if changes.is_empty() => {
+                        ui.colored_label(egui::Color32::from_rgb(0, 150, 0), "两个项目的轨道完全相同");
+                    }
+                    Some(Ok(changes)) => {
+                        use crate::project_diff::TrackChange;
+                        let added = changes.iter().filter(|c| matches!(c, TrackChange::Added(_))).count();
+                        let removed = changes.iter().filter(|c| matches!(c, TrackChange::Removed(_))).count();
+                        let modified = changes.len() - added - removed;
+                        ui.label(format!("新增 {} 个，移除 {} 个，修改 {} 个", added, removed, modified));
+                        ui.add_space(5.0);
+
+                        egui::ScrollArea::vertical()
+                            .max_height(ui.available_height() - 40.0)
+                            .show(ui, |ui| {
+                                for change in changes {
+                                    match change {
+                                        TrackChange::Added(track) => {
+                                            ui.colored_label(egui::Color32::from_rgb(0, 150, 0), format!("+ {}", track.track_name))
+                                                .on_hover_text(track.path.display().to_string());
+                                        }
+                                        TrackChange::Removed(track) => {
+                                            ui.colored_label(egui::Color32::from_rgb(200, 50, 50), format!("- {}", track.track_name))
+                                                .on_hover_text(track.path.display().to_string());
+                                        }
+                                        TrackChange::Modified { after, changes, .. } => {
+                                            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), format!("~ {}", after.track_name))
+                                                .on_hover_text(after.path.display().to_string());
+                                            for field_change in changes {
+                                                ui.horizontal(|ui| {
+                                                    ui.add_space(20.0);
+                                                    ui.label(field_change);
+                                                });
+                                            }
+                                        }
+                                    }
+                                }
+                            });
+                    }
+                }
+
+                ui.add_space(5.0);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("关闭").clicked() {
+                        should_close = true;
                     }
                 });
             });
-        
+
+        if should_compare {
+            if let (Some(before), Some(after)) = (&state.compare_before_path, &state.compare_after_path) {
+                state.compare_result = Some(
+                    crate::project_diff::load_preset_tracks(before)
+                        .and_then(|before| {
+                            crate::project_diff::load_preset_tracks(after)
+                                .map(|after| crate::project_diff::diff_presets(&before, &after))
+                        })
+                        .map_err(|e| format!("比较失败: {}", e)),
+                );
+            }
+        }
         if should_close {
-            state.show_video_convert_result = false;
+            state.show_project_compare = false;
         }
     }
 
     /// 显示 FFmpeg 下载对话框
-    pub fn show_ffmpeg_download_dialog(ctx: &egui::Context, state: &mut AppState) {
+    pub fn show_ffmpeg_download_dialog(ctx: &egui::Context, state: &mut AppState, task_processor: &ThreadedTaskProcessor) {
         if !state.show_ffmpeg_download {
             return;
         }
 
         let safe_pos = Self::calculate_safe_position(ctx, [700.0, 600.0].into(), [100.0, 100.0].into());
         let mut should_close = false;
+        let mut should_cancel = false;
         let mut should_download = false;
+        let mut should_retry = false;
+        let mut should_switch_mirror = false;
+        let download_failed = !state.is_downloading_ffmpeg && state.ffmpeg_download_status.contains("失败");
+
+        // 重试时从下一个镜像开始尝试
+        let mirror_names = crate::ffmpeg_downloader::FFmpegDownloader::mirror_names(&state.ffmpeg_mirror_source);
+        let next_mirror_index = (state.ffmpeg_download_mirror_index + 1) % mirror_names.len().max(1);
+        let next_mirror_name = mirror_names.get(next_mirror_index).cloned();
         
         egui::Window::new("FFmpeg 下载")
             .open(&mut state.show_ffmpeg_download)
@@ -2621,10 +5808,11 @@ impl UIComponents {
                     ui.heading("FFmpeg 自动下载");
                     ui.separator();
                     
-                    if state.is_downloading_ffmpeg || state.ffmpeg_download_progress > 0.0 {
+                    if state.is_downloading_ffmpeg || state.ffmpeg_download_progress > 0.0 || download_failed {
                         // 下载进行中或已完成
                         let is_completed = state.ffmpeg_download_progress >= 100.0;
-                        let is_failed = state.ffmpeg_download_status.contains("失败");
+                        // 下载过程中的“某镜像失败，尝试下一个源”不算最终失败
+                        let is_failed = download_failed;
                         
                         ui.group(|ui| {
                             ui.vertical(|ui| {
@@ -2645,6 +5833,34 @@ impl UIComponents {
                                 ui.label(&state.ffmpeg_download_status);
                                 
                                 if !is_completed && !is_failed {
+                                    let now = std::time::Instant::now();
+                                    let stats = &state.ffmpeg_download_stats;
+                                    ui.add_space(5.0);
+                                    ui.horizontal(|ui| {
+                                        if let Some(elapsed) = stats.elapsed(now) {
+                                            ui.label(format!("已用时间: {}", Self::format_seconds(elapsed.as_secs_f64())));
+                                        }
+                                        if stats.downloaded > 0 {
+                                            let downloaded = crate::utils::FileUtils::format_size(stats.downloaded);
+                                            match stats.total {
+                                                Some(total) => ui.label(format!("已下载: {} / {}", downloaded, crate::utils::FileUtils::format_size(total))),
+                                                None => ui.label(format!("已下载: {}", downloaded)),
+                                            };
+                                        }
+                                        if let Some(speed) = stats.bytes_per_sec(now) {
+                                            ui.label(format!("速度: {:.2} MB/s", speed / (1024.0 * 1024.0)));
+                                        }
+                                        if let Some(eta) = stats.eta(now) {
+                                            ui.label(format!("预计剩余: {}", Self::format_seconds(eta.as_secs_f64())));
+                                        }
+                                    });
+                                    if stats.is_stalled(now) {
+                                        ui.add_space(5.0);
+                                        ui.colored_label(
+                                            egui::Color32::from_rgb(230, 160, 0),
+                                            format!("⚠ 连接可能已停滞（{} 秒未收到数据），可尝试切换下载源", crate::utils::constants::app::DOWNLOAD_STALL_TIMEOUT_SECS),
+                                        );
+                                    }
                                     ui.add_space(10.0);
                                     ui.label("请稍候，下载完成后将自动配置...");
                                 } else if is_completed {
@@ -2653,6 +5869,9 @@ impl UIComponents {
                                 } else if is_failed {
                                     ui.add_space(10.0);
                                     ui.colored_label(egui::Color32::from_rgb(255, 100, 100), "✗ 下载失败，请检查网络连接或重试");
+                                    if let Some(next_mirror) = next_mirror_name.as_ref() {
+                                        ui.label(format!("重试时将首先尝试: {}（已下载的部分会继续使用）", next_mirror));
+                                    }
                                 }
                             });
                         });
@@ -2661,13 +5880,23 @@ impl UIComponents {
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                             if !is_completed && !is_failed {
                                 if ui.button("取消下载").clicked() {
-                                    // 这里可以添加取消下载的逻辑
-                                    should_close = true;
+                                    should_cancel = true;
+                                }
+                                let stalled = state.ffmpeg_download_stats.is_stalled(std::time::Instant::now());
+                                if stalled && !state.ffmpeg_download_switch_pending {
+                                    if let Some(next_mirror) = next_mirror_name.as_ref() {
+                                        if ui.button("切换下载源").on_hover_text(format!("中止当前连接并从 {} 重新下载", next_mirror)).clicked() {
+                                            should_switch_mirror = true;
+                                        }
+                                    }
                                 }
                             } else {
                                 if ui.button("关闭").clicked() {
                                     should_close = true;
                                 }
+                                if is_failed && next_mirror_name.is_some() && ui.button("重试(切换镜像)").clicked() {
+                                    should_retry = true;
+                                }
                             }
                         });
                     } else {
@@ -2736,6 +5965,35 @@ impl UIComponents {
                             });
                         });
                         
+                        ui.add_space(10.0);
+
+                        // 下载源选择（首先尝试，失败时依次使用其他镜像）
+                        ui.horizontal(|ui| {
+                            use crate::ffmpeg_plugin::MirrorSource;
+                            ui.label("下载源:");
+                            let previous_source = state.ffmpeg_mirror_source.clone();
+                            let is_custom = matches!(state.ffmpeg_mirror_source, MirrorSource::Custom(_));
+                            egui::ComboBox::from_id_source("ffmpeg_mirror_source")
+                                .selected_text(state.ffmpeg_mirror_source.display_name())
+                                .show_ui(ui, |ui| {
+                                    for source in [MirrorSource::GitHubProxy, MirrorSource::GitHub] {
+                                        let name = source.display_name();
+                                        ui.selectable_value(&mut state.ffmpeg_mirror_source, source, name);
+                                    }
+                                    if ui.selectable_label(is_custom, MirrorSource::Custom(String::new()).display_name()).clicked() && !is_custom {
+                                        state.ffmpeg_mirror_source = MirrorSource::Custom(String::new());
+                                    }
+                                });
+                            if let MirrorSource::Custom(prefix) = &mut state.ffmpeg_mirror_source {
+                                ui.add(egui::TextEdit::singleline(prefix).hint_text("https://mirror.example.com/"))
+                                    .on_hover_text("代理地址前缀，将拼接 GitHub 下载地址；地址中包含 {url} 时替换为 GitHub 下载地址");
+                            }
+                            if state.ffmpeg_mirror_source != previous_source {
+                                state.ffmpeg_download_mirror_index = 0;
+                            }
+                        });
+                        ui.label(egui::RichText::new("所选下载源失败时会自动尝试其他镜像").small().weak());
+
                         ui.add_space(20.0);
                         
                         ui.horizontal(|ui| {
@@ -2750,6 +6008,7 @@ impl UIComponents {
                                 
                                 if ui.button("手动安装").clicked() {
                                     // 显示手动安装说明
+                                    state.audio_convert_result_dir = None;
                                     state.audio_convert_result = Some(
                                         "手动安装 FFmpeg 说明:\n\n\
                                         1. 访问 https://ffmpeg.org/download.html\n\
@@ -2770,17 +6029,42 @@ impl UIComponents {
                 });
             });
         
+        if should_cancel {
+            // 下载线程在数据块之间检查取消标志，中止后发送完成消息并重置状态
+            task_processor.cancel_ffmpeg_download();
+            state.ffmpeg_download_status = "正在取消下载...".to_string();
+        }
+
+        if should_switch_mirror {
+            // 先取消当前下载，收到取消完成消息后从下一个镜像重新开始
+            task_processor.cancel_ffmpeg_download();
+            state.ffmpeg_download_mirror_index = next_mirror_index;
+            state.ffmpeg_download_switch_pending = true;
+            state.ffmpeg_download_status = "正在切换下载源...".to_string();
+        }
+
         if should_close {
             state.show_ffmpeg_download = false;
             // 重置下载状态
             state.is_downloading_ffmpeg = false;
             state.ffmpeg_download_started = false;
+            state.ffmpeg_download_switch_pending = false;
             state.ffmpeg_download_progress = 0.0;
             state.ffmpeg_download_status = String::new();
         }
         
+        if should_retry {
+            state.ffmpeg_download_mirror_index = next_mirror_index;
+            should_download = true;
+        }
+
         if should_download {
+            // 记住本次使用的下载源
+            if let Err(e) = state.save_config() {
+                warn!("保存下载源设置失败: {}", e);
+            }
             state.is_downloading_ffmpeg = true;
+            state.ffmpeg_download_started = false;
             state.ffmpeg_download_progress = 0.0;
             state.ffmpeg_download_status = "准备下载...".to_string();
             
@@ -2879,6 +6163,7 @@ impl UIComponents {
                                     } else {
                                         info!("FFmpeg 路径已保存: {:?}", path);
                                         state.audio_convert_result = Some(format!("FFmpeg 路径设置成功！\n\n路径: {}", path.display()));
+                                        state.audio_convert_result_dir = None;
                                         state.show_audio_convert_result = true;
                                         should_close = true;
                                     }
@@ -2905,6 +6190,51 @@ impl UIComponents {
         }
     }
 
+    /// 显示性能设置对话框（并行转换最大线程数）
+    pub fn show_performance_settings_dialog(ctx: &egui::Context, state: &mut AppState) {
+        if !state.show_performance_settings {
+            return;
+        }
+
+        let safe_pos = Self::calculate_safe_position(ctx, [420.0, 240.0].into(), [150.0, 150.0].into());
+        let mut should_close = false;
+        let logical_cores = num_cpus::get().max(1);
+
+        egui::Window::new(tr("performance.title"))
+            .open(&mut state.show_performance_settings)
+            .default_pos(safe_pos)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let mut manual = state.max_threads_override.is_some();
+                if ui.checkbox(&mut manual, tr("performance.limit_threads")).changed() {
+                    state.max_threads_override = manual.then_some((logical_cores / 2).max(1));
+                }
+
+                if let Some(threads) = state.max_threads_override.as_mut() {
+                    *threads = crate::parallel_converter::ParallelConfig::clamp_thread_override(*threads);
+                    ui.add(egui::Slider::new(threads, 1..=logical_cores).text(tr("performance.threads")));
+                } else {
+                    ui.label(egui::RichText::new(tr("performance.auto_hint")).small().weak());
+                }
+                ui.label(egui::RichText::new(trf("performance.cores", &[&logical_cores])).small().weak());
+
+                ui.add_space(5.0);
+                ui.colored_label(egui::Color32::from_rgb(200, 120, 0), tr("performance.game_warning"));
+                ui.label(egui::RichText::new(tr("performance.next_task_hint")).small().weak());
+
+                ui.add_space(10.0);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button(tr("common.ok")).clicked() {
+                        should_close = true;
+                    }
+                });
+            });
+
+        if should_close {
+            state.show_performance_settings = false;
+        }
+    }
+
     /// 显示 FFmpeg 插件管理对话框
     pub fn show_ffmpeg_plugin_dialog(ctx: &egui::Context, state: &mut AppState) {
         if !state.show_ffmpeg_plugin {
@@ -2994,6 +6324,7 @@ impl UIComponents {
                                     if let Ok(mut plugin) = crate::ffmpeg_plugin::FFmpegPlugin::new() {
                                         match plugin.set_ffmpeg_path(path.clone()) {
                                             Ok(_) => {
+                                                state.track_editor_ffmpeg_available = None;
                                                 state.file_operation_message = Some(format!("FFmpeg路径设置成功: {}", path.display()));
                                             }
                                             Err(e) => {
@@ -3016,6 +6347,35 @@ impl UIComponents {
                         });
                     });
                     
+                    ui.add_space(10.0);
+                    
+                    // 网络设置
+                    ui.group(|ui| {
+                        ui.vertical(|ui| {
+                            ui.heading("网络设置");
+                            ui.add_space(5.0);
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("代理地址:");
+                                ui.add(egui::TextEdit::singleline(&mut state.http_settings.proxy)
+                                    .hint_text("http://127.0.0.1:7890")
+                                    .desired_width(250.0));
+                            });
+                            ui.label("留空则直接连接；适用于下载 FFmpeg 等联网功能");
+                            
+                            if let Err(e) = state.http_settings.validate() {
+                                ui.colored_label(egui::Color32::from_rgb(200, 50, 50), format!("✗ {}", e));
+                            }
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("连接超时(秒):");
+                                ui.add(egui::DragValue::new(&mut state.http_settings.connect_timeout_secs).clamp_range(5..=120));
+                                ui.label("下载超时(秒):");
+                                ui.add(egui::DragValue::new(&mut state.http_settings.timeout_secs).clamp_range(60..=3600));
+                            });
+                        });
+                    });
+                    
                     ui.add_space(20.0);
                     
                     // 显示文件操作提示信息
@@ -3137,7 +6497,7 @@ impl UIComponents {
                                     
                                     ui.horizontal(|ui| {
                                         ui.label("🔓");
-                                        ui.label("音频解密：支持酷狗KGM和网易云NCM格式解密");
+                                        ui.label("音频解密：支持酷狗KGM、网易云NCM和QQ音乐QMC格式解密");
                                     });
                                     
                                     ui.horizontal(|ui| {
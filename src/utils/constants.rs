@@ -14,7 +14,24 @@ pub mod audio_decrypt {
         0x7c, 0xd5, 0x32, 0xeb, 0x86, 0x02, 0x7f, 0x4b, 0xa8, 0xaf, 0xa6, 0x8e, 0x0f, 0xff, 0x99,
         0x14, 0x00, 0x04, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
     ];
-    
+
+    /// QQ音乐加密文件扩展名
+    pub const QQMUSIC_EXTENSIONS: &[&str] = &[
+        "qmc0", "qmc2", "qmc3", "qmc4", "qmc6", "qmc8", "qmcflac", "qmcogg", "tkm",
+        "mflac", "mflac0", "mgg", "mgg0", "mgg1", "mggl",
+    ];
+    /// 必须带有嵌入密钥块的QQ音乐扩展名（不能回退到静态密钥）
+    pub const QQMUSIC_KEY_REQUIRED_EXTENSIONS: &[&str] = &["mflac", "mflac0", "mgg", "mgg0", "mgg1", "mggl"];
+
+    /// 流式解密每次读写的块大小（字节）
+    pub const DECRYPT_CHUNK_SIZE: usize = 64 * 1024;
+    /// 用于识别输出格式而缓存的解密数据长度（字节）
+    pub const FORMAT_SNIFF_LEN: usize = 4 * 1024;
+
+    /// 默认最大并发解密数
+    pub const DEFAULT_MAX_CONCURRENT_DECRYPTS: usize = 4;
+    /// 最大并发解密数上限
+    pub const MAX_CONCURRENT_DECRYPTS_LIMIT: usize = 16;
 }
 
 
@@ -24,8 +41,36 @@ pub mod file_ops {
     pub const DEFAULT_TRACK_DURATION: u32 = 180;
     /// 默认分贝值
     pub const DEFAULT_DECIBELS: i32 = 0;
+    /// 轨道编辑器允许的分贝范围
+    pub const MIN_DECIBELS: i32 = -10;
+    pub const MAX_DECIBELS: i32 = 5;
+    /// 根据响度建议分贝值时的目标均方根电平（dBFS）
+    pub const TARGET_RMS_DBFS: f32 = -18.0;
+    /// 根据 FFmpeg 分析的综合响度建议分贝值时的参考响度（LUFS）
+    pub const TARGET_LOUDNESS_LUFS: f32 = -16.0;
+    /// 轨道编辑器允许的最大时长（秒）
+    pub const MAX_TRACK_DURATION: u32 = 3600;
+    /// 复制缓冲区大小范围（字节）
+    pub const MIN_COPY_BUFFER_SIZE: usize = 16 * 1024;
+    pub const MAX_COPY_BUFFER_SIZE: usize = 16 * 1024 * 1024;
     /// 最大文件大小（MB）
     pub const MAX_FILE_SIZE_MB: u64 = 500; // 500 MB
+    /// 默认的轨道文件夹名称（位于模组目录）
+    pub const DEFAULT_TRACKS_FOLDER: &str = "folderwithtracks";
+    /// 导出记录文件名（位于模组目录，用于增量导出）
+    pub const EXPORT_MANIFEST_FILE: &str = ".zmm_export_manifest.json";
+    /// 导出被用户取消时复制步骤返回的错误信息
+    pub const EXPORT_CANCELLED: &str = "导出已被用户取消";
+    /// 从轨道封面生成的Logo文件名（位于用户工作空间）
+    pub const COVER_LOGO_FILE: &str = "logo.paa";
+    /// 从轨道封面生成Logo时的目标尺寸
+    pub const COVER_LOGO_SIZE: u32 = 512;
+    /// 项目文件扩展名
+    pub const PROJECT_FILE_EXTENSION: &str = "zmm";
+    /// 递归扫描文件夹的最大深度
+    pub const MAX_FOLDER_SCAN_DEPTH: usize = 16;
+    /// 递归扫描文件夹最多收集的文件数
+    pub const MAX_FOLDER_SCAN_FILES: usize = 5000;
 }
 
 
@@ -37,4 +82,36 @@ pub mod app {
     pub const MIN_STACK_SIZE: u64 = 8388608; // 8MB
     /// 最大栈大小
     pub const MAX_STACK_SIZE: u64 = 8388608; // 8MB
+    /// 首次启动时的窗口大小
+    pub const DEFAULT_WINDOW_SIZE: [f32; 2] = [1000.0, 700.0];
+    /// 窗口最小大小
+    pub const MIN_WINDOW_SIZE: [f32; 2] = [800.0, 600.0];
+    /// 已有任务运行时拒绝启动新任务的提示
+    pub const TASK_BUSY_MESSAGE: &str = "任务进行中，请等待当前任务完成后再开始新任务";
+    /// FFmpeg 下载被用户取消时的完成消息
+    pub const FFMPEG_DOWNLOAD_CANCELLED: &str = "已取消";
+    /// FFmpeg 下载多长时间（秒）没有收到数据时提示连接可能已停滞
+    pub const DOWNLOAD_STALL_TIMEOUT_SECS: u64 = 30;
+    /// FFmpeg 不可用时的提示
+    pub const FFMPEG_NOT_READY_HINT: &str = "✗ FFmpeg 未就绪 - 请通过插件管理下载或配置";
+    /// 最多记住的最近导出目录数
+    pub const MAX_RECENT_EXPORT_DIRS: usize = 10;
+    /// 轨道列表撤销栈最多保留的快照数
+    pub const MAX_UNDO_SNAPSHOTS: usize = 10;
+}
+
+/// 键盘快捷键（Ctrl 在 macOS 上对应 Cmd）
+pub mod shortcuts {
+    use egui::{Key, KeyboardShortcut, Modifiers};
+
+    /// 添加歌曲/视频文件
+    pub const ADD_FILES: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::O);
+    /// 打开导出对话框
+    pub const EXPORT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::E);
+    /// 保存项目
+    pub const SAVE_PROJECT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::S);
+    /// 删除选中的轨道或视频
+    pub const DELETE: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::Delete);
+    /// 撤销上一次删除、清空、批量设置标签或调整顺序
+    pub const UNDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
 }
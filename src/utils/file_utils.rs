@@ -5,7 +5,7 @@
 
 use rfd::FileDialog;
 use std::path::{Path, PathBuf};
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
 use crate::utils::constants::file_ops;
 
 /// 文件工具
@@ -20,6 +20,21 @@ impl FileUtils {
             .pick_files()
     }
 
+    /// 选择包含音频文件的文件夹
+    pub fn select_audio_folder() -> Option<PathBuf> {
+        FileDialog::new()
+            .set_title("选择包含OGG音频文件的文件夹")
+            .pick_folder()
+    }
+
+    /// 选择播放列表文件
+    pub fn select_playlist_file() -> Option<PathBuf> {
+        FileDialog::new()
+            .add_filter("播放列表", &["m3u", "m3u8", "pls"])
+            .set_title("选择播放列表文件")
+            .pick_file()
+    }
+
     /// 选择PAA文件
     pub fn select_paa_file() -> Option<PathBuf> {
         FileDialog::new()
@@ -36,14 +51,47 @@ impl FileUtils {
             .pick_file()
     }
 
+    /// 选择多个PBO文件
+    pub fn select_pbo_files() -> Option<Vec<PathBuf>> {
+        FileDialog::new()
+            .add_filter("PBO文件", &["pbo"])
+            .set_title("选择PBO文件")
+            .pick_files()
+    }
+
     /// 选择加密音频文件
     pub fn select_encrypted_audio_files() -> Option<Vec<PathBuf>> {
         FileDialog::new()
-            .add_filter("加密音频文件", &["kgm", "ncm"])
+            .add_filter(
+                "加密音频文件",
+                &[&["kgm", "ncm"], crate::utils::constants::audio_decrypt::QQMUSIC_EXTENSIONS].concat(),
+            )
             .set_title("选择加密音频文件")
             .pick_files()
     }
 
+    /// 选择要打开的项目文件
+    pub fn select_project_file() -> Option<PathBuf> {
+        FileDialog::new()
+            .add_filter("项目文件", &[file_ops::PROJECT_FILE_EXTENSION, "json"])
+            .set_title("打开项目")
+            .pick_file()
+    }
+
+    /// 选择项目文件的保存位置（未填写扩展名时补上 .zmm）
+    pub fn select_project_save_path(default_name: &str) -> Option<PathBuf> {
+        let path = FileDialog::new()
+            .add_filter("项目文件", &[file_ops::PROJECT_FILE_EXTENSION])
+            .set_title("另存为项目")
+            .set_file_name(format!("{}.{}", default_name, file_ops::PROJECT_FILE_EXTENSION))
+            .save_file()?;
+        if path.extension().is_some() {
+            Some(path)
+        } else {
+            Some(path.with_extension(file_ops::PROJECT_FILE_EXTENSION))
+        }
+    }
+
     /// 选择导出目录
     pub fn select_export_directory() -> Option<PathBuf> {
         FileDialog::new()
@@ -86,10 +134,131 @@ impl FileUtils {
             .unwrap_or(false)
     }
 
+    /// 计算文件总大小（字节），无法读取的文件忽略
+    pub fn total_size(paths: &[PathBuf]) -> u64 {
+        paths
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    /// 将字节数格式化为 MB 或 GB（1 GB 以上使用 GB）
+    pub fn format_size(bytes: u64) -> String {
+        const MB: f64 = 1024.0 * 1024.0;
+        const GB: f64 = MB * 1024.0;
+        let bytes = bytes as f64;
+        if bytes >= GB {
+            format!("{:.2} GB", bytes / GB)
+        } else {
+            format!("{:.1} MB", bytes / MB)
+        }
+    }
+
+    /// 在系统文件管理器中打开目录（Windows 使用 explorer，macOS 使用 open，其他系统使用 xdg-open）
+    pub fn open_in_file_manager(dir: &Path) -> Result<()> {
+        if !dir.is_dir() {
+            return Err(anyhow!("目录不存在: {:?}", dir));
+        }
+        let program = if cfg!(target_os = "windows") {
+            "explorer"
+        } else if cfg!(target_os = "macos") {
+            "open"
+        } else {
+            "xdg-open"
+        };
+        std::process::Command::new(program)
+            .arg(dir)
+            .spawn()
+            .map_err(|e| anyhow!("无法打开文件管理器 {}: {}", program, e))?;
+        Ok(())
+    }
+
+    /// 选择任务日志的保存位置
+    pub fn select_log_save_path(default_name: &str) -> Option<PathBuf> {
+        FileDialog::new()
+            .add_filter("文本文件", &["txt"])
+            .set_title("保存日志")
+            .set_file_name(default_name)
+            .save_file()
+    }
+
+    /// 将任务结果原样写入日志文件，开头附带任务名称、保存时间和程序版本
+    pub fn write_task_log(path: &Path, title: &str, result: &str) -> Result<()> {
+        let unix_secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        std::fs::write(path, Self::task_log_content(title, result, unix_secs))
+            .with_context(|| format!("无法写入日志文件: {:?}", path))
+    }
+
+    fn task_log_content(title: &str, result: &str, unix_secs: u64) -> String {
+        format!(
+            "{} v{}\n任务: {}\n时间: {}\n\n{}\n",
+            crate::utils::constants::app::APP_NAME,
+            env!("CARGO_PKG_VERSION"),
+            title,
+            Self::format_utc_timestamp(unix_secs),
+            result.trim_end(),
+        )
+    }
+
+    /// 将 Unix 时间戳格式化为 `YYYY-MM-DD HH:MM:SS UTC`
+    fn format_utc_timestamp(unix_secs: u64) -> String {
+        let days = (unix_secs / 86_400) as i64;
+        let secs = unix_secs % 86_400;
+        // 由 1970-01-01 起的天数换算公历日期（Howard Hinnant 的 civil_from_days 算法）
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+            year, month, day, secs / 3600, secs % 3600 / 60, secs % 60
+        )
+    }
+
     /// 检查文件是否过大
     pub fn is_file_too_large(path: &Path) -> Result<bool> {
         let metadata = std::fs::metadata(path)?;
         let file_size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
         Ok(file_size_mb > file_ops::MAX_FILE_SIZE_MB as f64)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_size_switches_to_gb() {
+        assert_eq!(FileUtils::format_size(0), "0.0 MB");
+        assert_eq!(FileUtils::format_size(5 * 1024 * 1024 + 512 * 1024), "5.5 MB");
+        assert_eq!(FileUtils::format_size(3 * 1024 * 1024 * 1024 / 2), "1.50 GB");
+    }
+
+    #[test]
+    fn test_open_in_file_manager_rejects_missing_directory() {
+        let missing = std::env::temp_dir().join("zmm_missing_output_dir_for_test");
+        let error = FileUtils::open_in_file_manager(&missing).unwrap_err();
+        assert!(error.to_string().contains("目录不存在"));
+    }
+
+    #[test]
+    fn test_task_log_content_keeps_result_verbatim() {
+        assert_eq!(FileUtils::format_utc_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(FileUtils::format_utc_timestamp(1_709_210_096), "2024-02-29 12:34:56 UTC");
+
+        let result = "转换失败: a.mp3 - FFmpeg 转换失败: Invalid data\n输出目录: C:\\out\n";
+        let content = FileUtils::task_log_content("音频转换", result, 0);
+        assert!(content.contains(env!("CARGO_PKG_VERSION")));
+        assert!(content.contains("任务: 音频转换\n时间: 1970-01-01 00:00:00 UTC"));
+        assert!(content.ends_with(result));
+    }
+}
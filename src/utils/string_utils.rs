@@ -66,6 +66,23 @@ impl StringUtils {
         result
     }
 
+    /// 找出 `chinese_to_pinyin` 无法转写、只能替换为下划线的字符（去重，按出现顺序）
+    pub fn untransliterable_chars(input: &str) -> Vec<char> {
+        let mut chars: Vec<char> = Vec::new();
+        for c in input.chars() {
+            let convertible = c.is_ascii()
+                || c.is_whitespace()
+                || (Self::is_chinese_char(c) && c.to_pinyin().is_some())
+                || (Self::is_japanese_kana(c) && Self::hiragana_to_romaji(c).is_some())
+                || (Self::is_russian_cyrillic(c) && Self::cyrillic_to_latin(c).is_some())
+                || Self::remove_spanish_accents(c) != c;
+            if !convertible && !chars.contains(&c) {
+                chars.push(c);
+            }
+        }
+        chars
+    }
+
     /// 判断字符是否为中文字符
     fn is_chinese_char(c: char) -> bool {
         let code = c as u32;
@@ -236,6 +253,38 @@ impl StringUtils {
         }
     }
 
+    /// 按命名方式生成导出文件名（不含扩展名），结果经过与拼音命名相同的 ASCII 安全处理
+    ///
+    /// 模板中的 `{index}` 为从 001 开始的序号，`{tag}`、`{name}` 分别为标签和轨道名称，
+    /// `{class}` 为轨道导出到 CfgMusic 时的配置类名（如 `MyModSong0`，启用标签类名时带标签前缀）。
+    pub fn render_output_name(
+        project: &crate::models::ProjectSettings,
+        track: &crate::models::Track,
+        index: usize,
+        scheme: &crate::models::NamingScheme,
+        tag_class_names: bool,
+    ) -> String {
+        use crate::models::NamingScheme;
+        match scheme {
+            NamingScheme::Pinyin => Self::safe_filename_pinyin(&track.track_name, index),
+            NamingScheme::Original => {
+                let stem = track.path.file_stem().unwrap_or_default().to_string_lossy();
+                Self::safe_filename_pinyin(&stem, index)
+            }
+            NamingScheme::Template(template) => {
+                let rendered = template
+                    .replace("{index}", &format!("{:03}", index + 1))
+                    .replace("{tag}", &track.tag)
+                    .replace("{name}", &track.track_name)
+                    .replace(
+                        "{class}",
+                        &crate::templates::TemplateEngine::config_class_name(project, track, index, tag_class_names),
+                    );
+                Self::safe_filename_pinyin(&rendered, index)
+            }
+        }
+    }
+
     /// 优化文件名，提高可读性
     fn optimize_filename(input: &str) -> String {
         let mut result = input.to_string();
@@ -276,6 +325,25 @@ impl StringUtils {
     }
 
 
+    /// 转换为合法的配置类名片段（仅字母、数字和下划线，不以数字开头）；没有可用字符时为空
+    pub fn class_identifier(input: &str) -> String {
+        let mut result = String::with_capacity(input.len());
+        // 不使用 to_ascii_safe_pinyin：它为空结果返回默认文件名 "track"，会让空标签也生成类名前缀
+        for c in Self::chinese_to_pinyin(input).chars() {
+            if c.is_ascii_alphanumeric() {
+                result.push(c);
+            } else if !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+        }
+        let trimmed = result.trim_end_matches('_');
+        if trimmed.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("T{}", trimmed)
+        } else {
+            trimmed.to_string()
+        }
+    }
+
     /// 生成类名
     pub fn generate_class_name(track_name: &str, base_class: &str, _index: usize) -> String {
         let safe_track_name = Self::to_ascii_safe_pinyin(track_name);
@@ -416,7 +484,38 @@ impl StringUtils {
         if !path.exists() {
             return path;
         }
-        
+
+        // 防止无限循环（最多尝试1000次）
+        (1..=1000)
+            .map(|counter| Self::numbered_path(&path, counter))
+            .find(|new_path| !new_path.exists())
+            // 使用时间戳作为最后的备选方案
+            .unwrap_or_else(|| Self::numbered_path(&path, Self::timestamp_suffix()))
+    }
+
+    /// 以独占方式创建一个空文件占用路径（文件已存在时添加数字后缀），返回占用的路径
+    ///
+    /// 检查与创建是同一个原子操作，多个工作线程同时为同名输出选择路径时不会选中同一个文件；
+    /// 调用方随后直接写入或用 `fs::rename` 覆盖该占位文件，失败时需自行删除。
+    pub fn reserve_unique_path(path: &std::path::Path) -> std::io::Result<std::path::PathBuf> {
+        let candidates = std::iter::once(path.to_path_buf())
+            .chain((1..=1000).map(|counter| Self::numbered_path(path, counter)))
+            .chain(std::iter::once(Self::numbered_path(path, Self::timestamp_suffix())));
+        for candidate in candidates {
+            match std::fs::OpenOptions::new().write(true).create_new(true).open(&candidate) {
+                Ok(_) => return Ok(candidate),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("无法为输出文件找到可用的名称: {:?}", path),
+        ))
+    }
+
+    /// 在文件名（扩展名之前）追加数字后缀：`name.ext` -> `name_N.ext`
+    fn numbered_path(path: &std::path::Path, counter: u64) -> std::path::PathBuf {
         let parent = path.parent().unwrap_or_else(|| std::path::Path::new("."));
         let file_stem = path.file_stem()
             .unwrap_or_default()
@@ -424,36 +523,77 @@ impl StringUtils {
         let extension = path.extension()
             .unwrap_or_default()
             .to_string_lossy();
-        
-        let mut counter = 1;
-        loop {
-            let new_name = if extension.is_empty() {
-                format!("{}_{}", file_stem, counter)
-            } else {
-                format!("{}_{}.{}", file_stem, counter, extension)
-            };
-            
-            let new_path = parent.join(new_name);
-            if !new_path.exists() {
-                return new_path;
-            }
-            
-            counter += 1;
-            
-            // 防止无限循环（最多尝试1000次）
-            if counter > 1000 {
-                // 使用时间戳作为最后的备选方案
-                let timestamp = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
-                let final_name = if extension.is_empty() {
-                    format!("{}_{}", file_stem, timestamp)
-                } else {
-                    format!("{}_{}.{}", file_stem, timestamp, extension)
-                };
-                return parent.join(final_name);
-            }
-        }
+
+        let new_name = if extension.is_empty() {
+            format!("{}_{}", file_stem, counter)
+        } else {
+            format!("{}_{}.{}", file_stem, counter, extension)
+        };
+        parent.join(new_name)
+    }
+
+    /// 当前 Unix 时间戳（秒），用作数字后缀用尽时的备选后缀
+    fn timestamp_suffix() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_class_identifier() {
+        assert_eq!(StringUtils::class_identifier(""), "");
+        assert_eq!(StringUtils::class_identifier("  "), "");
+        assert_eq!(StringUtils::class_identifier("☃"), "");
+        assert_eq!(StringUtils::class_identifier("Combat"), "Combat");
+        assert_eq!(StringUtils::class_identifier("1st wave!"), "T1st_wave");
+        assert_eq!(StringUtils::class_identifier("战斗"), "zhandou");
+    }
+
+    #[test]
+    fn test_render_output_name_for_each_naming_scheme() {
+        use crate::models::{NamingScheme, ProjectSettings, Track};
+
+        let project = ProjectSettings { class_name: "MyMod".to_string(), ..Default::default() };
+        let mut track = Track::new("music/Battle-01.ogg".into(), "Main Theme".to_string(), project.class_name.clone());
+        track.tag = "Combat".to_string();
+        let render = |scheme: &NamingScheme, tag_class_names: bool| {
+            StringUtils::render_output_name(&project, &track, 2, scheme, tag_class_names)
+        };
+
+        assert_eq!(render(&NamingScheme::Pinyin, false), StringUtils::safe_filename_pinyin("Main Theme", 2));
+        assert_eq!(render(&NamingScheme::Original, false), "Battle-01");
+        assert_eq!(render(&NamingScheme::Template("{index}_{name}".to_string()), false), "003_Main Theme");
+        assert_eq!(render(&NamingScheme::Template("{tag}-{name}".to_string()), false), "Combat-Main Theme");
+        // {class} 与 CfgMusic 中生成的配置类名一致，而不是项目类名
+        assert_eq!(render(&NamingScheme::Template("{class}".to_string()), false), "MyModSong2");
+        assert_eq!(render(&NamingScheme::Template("{class}".to_string()), true), "Combat_MyModSong2");
+    }
+
+    #[test]
+    fn test_reserve_unique_path_never_hands_out_the_same_path_twice() {
+        let dir = std::env::temp_dir().join(format!("zmm_reserve_path_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("song.mp3");
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let target = target.clone();
+                std::thread::spawn(move || StringUtils::reserve_unique_path(&target).unwrap())
+            })
+            .collect();
+        let mut reserved: Vec<_> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        reserved.sort();
+        reserved.dedup();
+
+        assert_eq!(reserved.len(), 8);
+        assert!(reserved.contains(&target));
+        assert!(reserved.iter().all(|path| path.exists()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
-}
\ No newline at end of file
+}
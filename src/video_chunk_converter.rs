@@ -11,10 +11,11 @@ use serde::{Serialize, Deserialize};
 use std::fs;
 
 use crate::ffmpeg_plugin::FFmpegPlugin;
-use crate::video_converter::VideoInfo;
+use crate::video_converter::{VideoFormat, VideoInfo};
+use crate::models::VideoConvertOptions;
 
 /// 视频分片配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VideoChunkConfig {
     /// 每个分片的时长（秒）
     pub chunk_duration: u32,
@@ -28,6 +29,9 @@ pub struct VideoChunkConfig {
     pub min_chunk_duration: u32,
     /// 快速转换模式（针对短视频优化）
     pub fast_mode: bool,
+    /// 输出格式（仅 OGV 可用于 Arma 3）
+    #[serde(default)]
+    pub output_format: VideoFormat,
 }
 
 impl Default for VideoChunkConfig {
@@ -39,11 +43,27 @@ impl Default for VideoChunkConfig {
             smart_chunking: true,    // 启用智能分片
             min_chunk_duration: 30,  // 最小30秒
             fast_mode: false,        // 默认不启用快速模式
+            output_format: VideoFormat::Ogv,
         }
     }
 }
 
 impl VideoChunkConfig {
+    /// 分片时长的可调范围（秒）
+    pub const CHUNK_DURATION_RANGE: std::ops::RangeInclusive<u32> = 10..=600;
+    /// 重叠时长的可调范围（秒），需明显小于最短分片时长
+    pub const OVERLAP_DURATION_RANGE: std::ops::RangeInclusive<u32> = 0..=5;
+    /// 最大分片数的可调范围
+    pub const MAX_CHUNKS_RANGE: std::ops::RangeInclusive<usize> = 1..=32;
+
+    /// 限制到设置面板允许的范围（手动修改的配置文件可能越界）
+    pub fn validated(mut self) -> Self {
+        self.chunk_duration = self.chunk_duration.clamp(*Self::CHUNK_DURATION_RANGE.start(), *Self::CHUNK_DURATION_RANGE.end());
+        self.overlap_duration = self.overlap_duration.clamp(*Self::OVERLAP_DURATION_RANGE.start(), *Self::OVERLAP_DURATION_RANGE.end());
+        self.max_chunks = self.max_chunks.clamp(*Self::MAX_CHUNKS_RANGE.start(), *Self::MAX_CHUNKS_RANGE.end());
+        self
+    }
+
     /// 根据视频信息智能调整分片配置
     pub fn adjust_for_video(&mut self, video_info: &VideoInfo) {
         if !self.smart_chunking {
@@ -51,7 +71,14 @@ impl VideoChunkConfig {
         }
 
         let duration = video_info.duration;
-        
+        self.apply_smart_chunking(duration);
+
+        info!("视频分片配置调整: 时长={}s, 分片时长={}s, 最大分片数={}", 
+              duration, self.chunk_duration, self.max_chunks);
+    }
+
+    /// 按视频时长调整分片时长和数量（不检查 `smart_chunking`）
+    fn apply_smart_chunking(&mut self, duration: u32) {
         // 根据视频时长调整分片策略
         if duration <= 120 {
             // 短视频（≤2分钟）：不分片，启用快速模式
@@ -74,9 +101,6 @@ impl VideoChunkConfig {
 
         // 确保分片时长不小于最小值
         self.chunk_duration = self.chunk_duration.max(self.min_chunk_duration);
-        
-        info!("视频分片配置调整: 时长={}s, 分片时长={}s, 最大分片数={}", 
-              duration, self.chunk_duration, self.max_chunks);
     }
 
     /// 计算实际分片数量
@@ -88,6 +112,15 @@ impl VideoChunkConfig {
         let chunks = (video_duration as f32 / self.chunk_duration as f32).ceil() as usize;
         chunks.min(self.max_chunks)
     }
+
+    /// 预计的分片数量（与转换时相同，先按智能分片调整再计算）
+    pub fn predicted_chunk_count(&self, video_duration: u32) -> usize {
+        let mut config = self.clone();
+        if config.smart_chunking {
+            config.apply_smart_chunking(video_duration);
+        }
+        config.calculate_chunk_count(video_duration)
+    }
 }
 
 /// 视频分片信息
@@ -189,7 +222,7 @@ impl VideoChunkConverter {
     }
 
     /// 转换单个分片
-    pub fn convert_chunk(&self, chunk: &VideoChunk, video_quality: u8, audio_quality: u8) -> Result<()> {
+    pub fn convert_chunk(&self, chunk: &VideoChunk, options: VideoConvertOptions) -> Result<()> {
         info!("转换分片 {}: {}s-{}s ({})", 
               chunk.index, chunk.start_time, chunk.start_time + chunk.duration, 
               chunk.input_path.display());
@@ -208,18 +241,19 @@ impl VideoChunkConverter {
         let output_str = chunk.output_path.to_str()
             .ok_or_else(|| anyhow!("分片输出路径包含无效UTF-8字符: {:?}", chunk.output_path))?;
         
+        let format = self.config.output_format;
         if self.config.fast_mode {
             // 快速模式：针对短视频优化
-            cmd.args(&[
+            cmd.args([
                 "-i", input_str,
                 "-ss", &chunk.start_time.to_string(),
                 "-t", &chunk.duration.to_string(),
-                "-c:v", "libtheora",
-                "-q:v", "6",                       // 固定质量，避免计算开销
-                "-speed", "8",
-                "-threads", "0",
-                "-c:a", "libvorbis",
-                "-q:a", "6",                       // 固定质量
+            ]);
+            cmd.args(options.scale_args());
+            cmd.args(format.video_codec_args(6));  // 固定质量，避免计算开销
+            cmd.args(["-threads", "0"]);
+            cmd.args(format.audio_codec_args(6));  // 固定质量
+            cmd.args([
                 "-ac", "2",
                 "-avoid_negative_ts", "make_zero",
                 "-preset", "ultrafast",
@@ -232,16 +266,16 @@ impl VideoChunkConverter {
             ]);
         } else {
             // 标准模式
-            cmd.args(&[
+            cmd.args([
                 "-i", input_str,
                 "-ss", &chunk.start_time.to_string(),
                 "-t", &chunk.duration.to_string(),
-                "-c:v", "libtheora",
-                "-q:v", &video_quality.to_string(),
-                "-speed", "8",
-                "-threads", "0",
-                "-c:a", "libvorbis",
-                "-q:a", &audio_quality.to_string(),
+            ]);
+            cmd.args(options.scale_args());
+            cmd.args(format.video_codec_args(options.video_quality));
+            cmd.args(["-threads", "0"]);
+            cmd.args(format.audio_codec_args(options.audio_quality));
+            cmd.args([
                 "-ac", "2",
                 "-avoid_negative_ts", "make_zero",
                 "-preset", "ultrafast",
@@ -298,8 +332,15 @@ impl VideoChunkConverter {
         }
     }
 
-    /// 合并分片为完整视频
-    pub fn merge_chunks(&self, chunks: &[VideoChunk], output_path: &Path) -> Result<()> {
+    /// 合并分片为完整视频（支持取消检查，取消时删除不完整的输出文件）
+    pub fn merge_chunks<F>(&self, chunks: &[VideoChunk], output_path: &Path, should_cancel: &F) -> Result<()>
+    where
+        F: Fn() -> bool + ?Sized,
+    {
+        if should_cancel() {
+            return Err(anyhow!("合并任务被取消"));
+        }
+
         if chunks.len() == 1 {
             // 只有一个分片，直接复制
             fs::copy(&chunks[0].output_path, output_path)
@@ -328,6 +369,8 @@ impl VideoChunkConverter {
         
         let mut cmd = Command::new(&self.ffmpeg_path);
         cmd.args(&[
+            "-hide_banner",
+            "-loglevel", "error",  // 仅输出错误，避免轮询期间stderr管道写满
             "-f", "concat",
             "-safe", "0",
             "-i", file_list_str,
@@ -346,24 +389,45 @@ impl VideoChunkConverter {
         debug!("执行合并命令: {:?}", cmd);
 
         // 执行合并
-        let child = cmd
-            .stdout(Stdio::piped())
+        let mut child = cmd
+            .stdout(Stdio::null())
             .stderr(Stdio::piped())
             .spawn()
             .context("启动合并进程失败")?;
 
-        let output = child
-            .wait_with_output()
-            .context("等待合并进程完成失败")?;
+        // 等待完成并检查取消
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) => {
+                    if should_cancel() {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        let _ = fs::remove_file(&file_list_path);
+                        // 删除不完整的合并结果，避免被当作成功的输出
+                        let _ = fs::remove_file(output_path);
+                        info!("分片合并被取消，已删除不完整的输出: {:?}", output_path);
+                        return Err(anyhow!("合并任务被取消"));
+                    }
+                    std::thread::sleep(std::time::Duration::from_millis(50));
+                }
+                Err(e) => break Err(e),
+            }
+        };
 
         // 清理临时文件
         let _ = fs::remove_file(&file_list_path);
 
-        if output.status.success() {
+        let status = status.context("等待合并进程完成失败")?;
+        if status.success() {
             info!("分片合并成功: {:?}", output_path);
             Ok(())
         } else {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+            let error_msg = child
+                .wait_with_output()
+                .map(|output| String::from_utf8_lossy(&output.stderr).to_string())
+                .unwrap_or_default();
+            let _ = fs::remove_file(output_path);
             error!("分片合并失败: {}", error_msg);
             Err(anyhow::anyhow!("分片合并失败: {}", error_msg))
         }
@@ -470,7 +534,7 @@ impl VideoChunkConverter {
         
         // 使用安全文件名并确保分片索引正确格式化
         let safe_filename = crate::utils::string_utils::StringUtils::to_ascii_safe_pinyin(&file_stem);
-        let chunk_filename = format!("{}_chunk_{:03}.ogv", safe_filename, chunk_index);
+        let chunk_filename = format!("{}_chunk_{:03}.{}", safe_filename, chunk_index, self.config.output_format.extension());
         
         let mut output_path = output_dir.join(chunk_filename);
         // 确保路径长度在限制内
@@ -525,4 +589,41 @@ impl VideoChunkConversionResult {
     pub fn get_error_message(&self) -> String {
         self.error.clone().unwrap_or_else(|| "未知错误".to_string())
     }
+
+    /// 获取结果列表中的一行说明（失败时包含源文件名和各分片/合并的错误）
+    pub fn get_summary_line(&self) -> String {
+        if self.success {
+            format!("✓ {}", self.get_success_message())
+        } else {
+            let name = self
+                .chunks
+                .first()
+                .and_then(|chunk| chunk.input_path.file_name())
+                .or_else(|| self.output_path.file_name())
+                .unwrap_or_default()
+                .to_string_lossy();
+            format!("✗ {} - {}", name, self.get_error_message())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predicted_chunk_count() {
+        let manual = VideoChunkConfig { smart_chunking: false, chunk_duration: 60, max_chunks: 4, ..VideoChunkConfig::default() };
+        assert_eq!(manual.predicted_chunk_count(30), 1);
+        assert_eq!(manual.predicted_chunk_count(150), 3);
+        assert_eq!(manual.predicted_chunk_count(3600), 4);
+
+        // 智能分片：短视频不分片，中等视频分 3 片
+        let smart = VideoChunkConfig::default();
+        assert_eq!(smart.predicted_chunk_count(90), 1);
+        assert_eq!(smart.predicted_chunk_count(300), 3);
+
+        let invalid = VideoChunkConfig { chunk_duration: 0, overlap_duration: 60, max_chunks: 0, ..VideoChunkConfig::default() }.validated();
+        assert_eq!((invalid.chunk_duration, invalid.overlap_duration, invalid.max_chunks), (10, 5, 1));
+    }
 }
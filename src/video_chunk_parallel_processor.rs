@@ -7,12 +7,14 @@ use anyhow::{Context, Result};
 use crossbeam_channel::{bounded, Receiver, Sender};
 use log::{info, warn, debug};
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::video_chunk_converter::{VideoChunkConverter, VideoChunk, VideoChunkConfig, VideoChunkConversionResult};
 use crate::resource_manager::GlobalResourceManager;
+use crate::models::VideoConvertOptions;
 
 /// 分片转换任务
 #[derive(Debug, Clone)]
@@ -23,10 +25,8 @@ pub struct ChunkConversionTask {
     pub input_path: PathBuf,
     /// 分片信息
     pub chunks: Vec<VideoChunk>,
-    /// 视频质量
-    pub video_quality: u8,
-    /// 音频质量
-    pub audio_quality: u8,
+    /// 分辨率上限与质量
+    pub options: VideoConvertOptions,
     /// 最终输出文件路径
     pub final_output_path: PathBuf,
 }
@@ -36,6 +36,8 @@ pub struct ChunkConversionTask {
 pub struct ChunkConversionTaskResult {
     /// 任务ID
     pub task_id: usize,
+    /// 原始视频文件路径
+    pub input_path: PathBuf,
     /// 转换结果
     pub result: VideoChunkConversionResult,
 }
@@ -82,12 +84,16 @@ pub struct VideoChunkParallelProcessor {
     config: VideoChunkConfig,
     /// 最大并发线程数
     max_threads: usize,
+    /// 用户设置的最大线程数（设置后不再自动选择线程数）
+    max_threads_override: Option<usize>,
     /// 进度更新发送器
     progress_sender: Sender<ChunkProgressUpdate>,
     /// 进度更新接收器
     progress_receiver: Receiver<ChunkProgressUpdate>,
     /// 取消标志
     cancel_flag: Arc<Mutex<bool>>,
+    /// 暂停标志（暂停时工作线程不再领取新视频）
+    pause_flag: Arc<AtomicBool>,
     /// 统计信息
     stats: Arc<Mutex<ChunkConversionStats>>,
     /// 资源管理器
@@ -119,14 +125,37 @@ impl VideoChunkParallelProcessor {
         Self {
             config,
             max_threads,
+            max_threads_override: None,
             progress_sender,
             progress_receiver,
             cancel_flag: Arc::new(Mutex::new(false)),
+            pause_flag: Arc::new(AtomicBool::new(false)),
             stats: Arc::new(Mutex::default()),
             resource_manager: Arc::new(GlobalResourceManager::new()),
         }
     }
 
+    /// 使用外部取消标志（如任务处理器的取消标志），使取消可以中止分片转换和合并
+    pub fn with_cancel_flag(mut self, cancel_flag: Arc<Mutex<bool>>) -> Self {
+        self.cancel_flag = cancel_flag;
+        self
+    }
+
+    /// 使用用户设置的最大线程数（限制在 1..=逻辑核心数），为 None 时自动选择
+    pub fn with_max_threads_override(mut self, max_threads_override: Option<usize>) -> Self {
+        self.max_threads_override = max_threads_override.map(crate::parallel_converter::ParallelConfig::clamp_thread_override);
+        if let Some(threads) = self.max_threads_override {
+            self.max_threads = threads;
+        }
+        self
+    }
+
+    /// 使用外部暂停标志，使界面可以暂停/继续转换
+    pub fn with_pause_flag(mut self, pause_flag: Arc<AtomicBool>) -> Self {
+        self.pause_flag = pause_flag;
+        self
+    }
+
     /// 计算最优线程数
     fn calculate_optimal_threads() -> usize {
         let cpu_count = num_cpus::get();
@@ -140,8 +169,7 @@ impl VideoChunkParallelProcessor {
         &self,
         input_files: Vec<PathBuf>,
         output_dir: PathBuf,
-        video_quality: u8,
-        audio_quality: u8,
+        options: VideoConvertOptions,
     ) -> Result<()> {
         info!("开始分片并行转换 {} 个视频文件", input_files.len());
         
@@ -165,21 +193,30 @@ impl VideoChunkParallelProcessor {
         }
 
         // 创建分片转换任务
-        let tasks = self.create_conversion_tasks(input_files, &output_dir, video_quality, audio_quality)?;
+        let (tasks, skipped) = self.create_conversion_tasks(input_files, &output_dir, options)?;
         
-        if tasks.is_empty() {
+        if tasks.is_empty() && skipped.is_empty() {
             warn!("没有有效的转换任务");
             return Ok(());
         }
 
-        // 更新统计信息
+        // 更新统计信息；无法创建任务的视频在工作线程启动前记为失败，保证每个视频都有结果
         {
             let mut stats = self.stats.lock().unwrap_or_else(|e| {
                 warn!("统计信息Mutex poisoned: {:?}，使用默认值", e);
                 e.into_inner()
             });
-            stats.total_tasks = tasks.len();
+            stats.total_tasks = tasks.len() + skipped.len();
             stats.total_chunks = tasks.iter().map(|t| t.chunks.len()).sum();
+            for result in skipped {
+                let _ = self.progress_sender.send(ChunkProgressUpdate::TaskCompleted {
+                    task_id: result.task_id,
+                    result: result.clone(),
+                });
+                stats.completed_tasks += 1;
+                stats.failed_tasks += 1;
+                stats.task_results.push(result);
+            }
         }
 
         info!("创建了 {} 个转换任务，总计 {} 个分片", tasks.len(), 
@@ -191,21 +228,22 @@ impl VideoChunkParallelProcessor {
         Ok(())
     }
 
-    /// 创建转换任务
+    /// 创建转换任务，返回 (转换任务, 无法创建任务的视频的失败结果)
     fn create_conversion_tasks(
         &self,
         input_files: Vec<PathBuf>,
         output_dir: &std::path::Path,
-        video_quality: u8,
-        audio_quality: u8,
-    ) -> Result<Vec<ChunkConversionTask>> {
+        options: VideoConvertOptions,
+    ) -> Result<(Vec<ChunkConversionTask>, Vec<ChunkConversionTaskResult>)> {
         let mut tasks = Vec::new();
+        let mut skipped = Vec::new();
         let converter = VideoChunkConverter::new(self.config.clone())?;
 
         for (task_id, input_path) in input_files.into_iter().enumerate() {
             // 检查文件是否存在
             if !input_path.exists() {
                 warn!("输入文件不存在，跳过: {}", input_path.display());
+                skipped.push(skipped_result(task_id, input_path, "输入文件不存在".to_string()));
                 continue;
             }
 
@@ -226,7 +264,7 @@ impl VideoChunkParallelProcessor {
                     } else {
                         format!("video_{:03}", task_id)
                     };
-                    let mut final_output_path = output_dir.join(format!("{}.ogv", safe_filename));
+                    let mut final_output_path = output_dir.join(format!("{}.{}", safe_filename, self.config.output_format.extension()));
                     // 确保路径长度在限制内
                     final_output_path = crate::utils::string_utils::StringUtils::ensure_path_length(&final_output_path, 260)
                         .unwrap_or_else(|_| final_output_path.clone());
@@ -238,8 +276,7 @@ impl VideoChunkParallelProcessor {
                         task_id,
                         input_path: input_path.clone(),
                         chunks,
-                        video_quality,
-                        audio_quality,
+                        options,
                         final_output_path,
                     });
 
@@ -248,11 +285,12 @@ impl VideoChunkParallelProcessor {
                 }
                 Err(e) => {
                     warn!("为视频创建分片计划失败: {} - {}", input_path.display(), e);
+                    skipped.push(skipped_result(task_id, input_path, format!("创建分片计划失败: {}", e)));
                 }
             }
         }
 
-        Ok(tasks)
+        Ok((tasks, skipped))
     }
 
     /// 启动并行处理
@@ -271,7 +309,11 @@ impl VideoChunkParallelProcessor {
         // 获取智能线程池
         let thread_pool = self.resource_manager.get_thread_pool();
         thread_pool.adjust_thread_count();
-        let actual_thread_count = thread_pool.get_max_threads().min(self.max_threads);
+        let actual_thread_count = match self.max_threads_override {
+            // 用户设置了最大线程数时直接使用，不受线程池自动上限影响
+            Some(_) => self.max_threads,
+            None => thread_pool.get_max_threads().min(self.max_threads),
+        };
         
         info!("使用 {} 个线程进行分片并行转换", actual_thread_count);
 
@@ -281,6 +323,7 @@ impl VideoChunkParallelProcessor {
             let task_receiver = task_receiver.clone();
             let progress_sender = self.progress_sender.clone();
             let cancel_flag = self.cancel_flag.clone();
+            let pause_flag = self.pause_flag.clone();
             let stats = self.stats.clone();
             let config = self.config.clone();
 
@@ -290,6 +333,7 @@ impl VideoChunkParallelProcessor {
                     task_receiver,
                     progress_sender,
                     cancel_flag,
+                    pause_flag,
                     stats,
                     config,
                 );
@@ -317,8 +361,8 @@ impl VideoChunkParallelProcessor {
                 .map(|start| start.elapsed())
                 .unwrap_or_default();
 
-            // 收集所有任务结果
-            let results = final_stats.task_results.clone();
+            // 工作线程按完成顺序收集结果，这里按任务序号排列
+            let results = ordered_results(&final_stats.task_results);
             let _ = progress_sender.send(ChunkProgressUpdate::AllTasksCompleted {
                 success_count: final_stats.successful_tasks,
                 error_count: final_stats.failed_tasks,
@@ -336,17 +380,20 @@ impl VideoChunkParallelProcessor {
         task_receiver: Receiver<ChunkConversionTask>,
         progress_sender: Sender<ChunkProgressUpdate>,
         cancel_flag: Arc<Mutex<bool>>,
+        pause_flag: Arc<AtomicBool>,
         stats: Arc<Mutex<ChunkConversionStats>>,
         config: VideoChunkConfig,
     ) {
         info!("分片转换工作线程 {} 启动", worker_id);
 
         while let Ok(task) = task_receiver.recv() {
-            // 检查取消标志
-            if *cancel_flag.lock().unwrap_or_else(|e| {
-                warn!("取消标志Mutex poisoned: {:?}，假设任务被取消", e);
-                e.into_inner()
-            }) {
+            // 暂停时等待继续后再开始下一个视频（已在转换的分片不受影响）
+            crate::parallel_converter::wait_while_paused(&pause_flag, || {
+                crate::parallel_converter::is_cancelled(&cancel_flag)
+            });
+
+            // 检查取消标志（中毒时视为已取消）
+            if crate::parallel_converter::is_cancelled(&cancel_flag) {
                 info!("工作线程 {} 收到取消信号，退出", worker_id);
                 break;
             }
@@ -358,40 +405,45 @@ impl VideoChunkParallelProcessor {
                 chunk_count: task.chunks.len(),
             });
 
-            // 执行分片转换
-            match Self::process_single_video(task, &progress_sender, &cancel_flag, &stats, &config) {
-                Ok(result) => {
-                    let _ = progress_sender.send(ChunkProgressUpdate::TaskCompleted {
-                        task_id: result.task_id,
-                        result: result.clone(),
-                    });
-
-                    // 更新统计信息并收集结果
-                    let mut stats = stats.lock().unwrap_or_else(|e| {
-                        warn!("统计信息Mutex poisoned: {:?}，使用默认值", e);
-                        e.into_inner()
-                    });
-                    stats.completed_tasks += 1;
-                    if result.result.success {
-                        stats.successful_tasks += 1;
-                    } else {
-                        stats.failed_tasks += 1;
-                    }
-                    // 收集任务结果
-                    stats.task_results.push(result.clone());
-                }
+            // 执行分片转换（处理出错时同样生成失败结果，保证每个视频都有结果）
+            let task_id = task.task_id;
+            let input_path = task.input_path.clone();
+            let chunks = task.chunks.clone();
+            let final_output_path = task.final_output_path.clone();
+            let result = match Self::process_single_video(task, &progress_sender, &cancel_flag, &stats, &config) {
+                Ok(result) => result,
                 Err(e) => {
                     warn!("处理视频任务失败: {}", e);
-                    
-                    // 更新统计信息
-                    let mut stats = stats.lock().unwrap_or_else(|e| {
-                        warn!("统计信息Mutex poisoned: {:?}，使用默认值", e);
-                        e.into_inner()
-                    });
-                    stats.completed_tasks += 1;
-                    stats.failed_tasks += 1;
+                    ChunkConversionTaskResult {
+                        task_id,
+                        input_path,
+                        result: VideoChunkConversionResult {
+                            output_path: final_output_path,
+                            chunks,
+                            success: false,
+                            error: Some(format!("处理视频任务失败: {}", e)),
+                        },
+                    }
                 }
+            };
+
+            let _ = progress_sender.send(ChunkProgressUpdate::TaskCompleted {
+                task_id: result.task_id,
+                result: result.clone(),
+            });
+
+            // 更新统计信息并收集结果
+            let mut stats = stats.lock().unwrap_or_else(|e| {
+                warn!("统计信息Mutex poisoned: {:?}，使用默认值", e);
+                e.into_inner()
+            });
+            stats.completed_tasks += 1;
+            if result.result.success {
+                stats.successful_tasks += 1;
+            } else {
+                stats.failed_tasks += 1;
             }
+            stats.task_results.push(result);
         }
 
         info!("分片转换工作线程 {} 退出", worker_id);
@@ -418,8 +470,7 @@ impl VideoChunkParallelProcessor {
         let chunk_results = Self::convert_chunks_parallel(
             &converter,
             &task.chunks,
-            task.video_quality,
-            task.audio_quality,
+            task.options,
             progress_sender,
             &task.task_id,
             cancel_flag,
@@ -451,6 +502,25 @@ impl VideoChunkParallelProcessor {
             stats.failed_chunks += failed_chunks;
         }
 
+        // 工作线程崩溃导致取消标志中毒时视为已取消，中止合并
+        let is_cancelled = || crate::parallel_converter::is_cancelled(cancel_flag);
+
+        // 取消后未执行的分片没有输出，不能合并或当作成功
+        if is_cancelled() {
+            info!("视频任务 {} 被取消，跳过合并", task.task_id);
+            converter.cleanup_chunks(&task.chunks);
+            return Ok(ChunkConversionTaskResult {
+                task_id: task.task_id,
+                input_path: task.input_path,
+                result: VideoChunkConversionResult {
+                    output_path: task.final_output_path,
+                    chunks: task.chunks,
+                    success: false,
+                    error: Some("任务被用户取消".to_string()),
+                },
+            });
+        }
+
         // 如果所有分片都成功，合并分片
         let success = failed_chunks == 0;
         let error = if success {
@@ -461,11 +531,12 @@ impl VideoChunkParallelProcessor {
 
         if success && task.chunks.len() > 1 {
             // 合并分片
-            if let Err(e) = converter.merge_chunks(&task.chunks, &task.final_output_path) {
+            if let Err(e) = converter.merge_chunks(&task.chunks, &task.final_output_path, &is_cancelled) {
                 warn!("合并分片失败: {}", e);
-                let _input_path = task.input_path.clone();
+                converter.cleanup_chunks(&task.chunks);
                 return Ok(ChunkConversionTaskResult {
                     task_id: task.task_id,
+                    input_path: task.input_path,
                     result: VideoChunkConversionResult {
                         output_path: task.final_output_path.clone(),
                         chunks: task.chunks,
@@ -481,6 +552,7 @@ impl VideoChunkParallelProcessor {
 
         Ok(ChunkConversionTaskResult {
             task_id: task.task_id,
+            input_path: task.input_path,
             result: VideoChunkConversionResult {
                 output_path: task.final_output_path,
                 chunks: task.chunks,
@@ -494,8 +566,7 @@ impl VideoChunkParallelProcessor {
     fn convert_chunks_parallel(
         converter: &VideoChunkConverter,
         chunks: &[VideoChunk],
-        video_quality: u8,
-        audio_quality: u8,
+        options: VideoConvertOptions,
         progress_sender: &Sender<ChunkProgressUpdate>,
         task_id: &usize,
         cancel_flag: &Arc<Mutex<bool>>,
@@ -530,10 +601,7 @@ impl VideoChunkParallelProcessor {
         thread_pool.scope(|s| {
             while let Ok((chunk_index, chunk)) = chunk_receiver.recv() {
                 // 检查取消标志
-                if *cancel_flag.lock().unwrap_or_else(|e| {
-                    warn!("取消标志Mutex poisoned: {:?}，假设任务被取消", e);
-                    e.into_inner()
-                }) {
+                if crate::parallel_converter::is_cancelled(cancel_flag) {
                     break;
                 }
 
@@ -550,7 +618,7 @@ impl VideoChunkParallelProcessor {
                     });
 
                     // 转换分片
-                    let result = converter.convert_chunk(&chunk, video_quality, audio_quality);
+                    let result = converter.convert_chunk(&chunk, options);
 
                     // 发送分片完成消息
                     let (success, error) = match &result {
@@ -596,4 +664,79 @@ impl VideoChunkParallelProcessor {
 
 }
 
+/// 将按完成顺序收集的任务结果按任务序号排序
+/// 未能开始转换的视频的失败结果（没有分片和输出，摘要中显示源文件名）
+fn skipped_result(task_id: usize, input_path: PathBuf, error: String) -> ChunkConversionTaskResult {
+    ChunkConversionTaskResult {
+        task_id,
+        result: VideoChunkConversionResult {
+            output_path: input_path.clone(),
+            chunks: Vec::new(),
+            success: false,
+            error: Some(error),
+        },
+        input_path,
+    }
+}
+
+fn ordered_results(results: &[ChunkConversionTaskResult]) -> Vec<ChunkConversionTaskResult> {
+    let mut ordered = results.to_vec();
+    ordered.sort_by_key(|result| result.task_id);
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    fn task_result(task_id: usize, name: &str, error: Option<&str>) -> ChunkConversionTaskResult {
+        let input_path = PathBuf::from(format!("{}.mp4", name));
+        ChunkConversionTaskResult {
+            task_id,
+            input_path: input_path.clone(),
+            result: VideoChunkConversionResult {
+                output_path: PathBuf::from(format!("out/{}.ogv", name)),
+                chunks: (0..2)
+                    .map(|index| VideoChunk {
+                        index,
+                        input_path: input_path.clone(),
+                        start_time: index as u32 * 30,
+                        duration: 30,
+                        output_path: PathBuf::from(format!("out/{}_chunk{}.tmp", name, index)),
+                    })
+                    .collect(),
+                success: error.is_none(),
+                error: error.map(str::to_string),
+            },
+        }
+    }
+
+    #[test]
+    fn test_results_ordered_by_task_id() {
+        let results = vec![task_result(2, "c", None), task_result(0, "a", None), task_result(1, "b", Some("x"))];
+        let ids: Vec<usize> = ordered_results(&results).iter().map(|r| r.task_id).collect();
+        assert_eq!(ids, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_failed_merge_reported_as_failure() {
+        let merged = task_result(0, "intro", Some("合并分片失败: concat error"));
+        let line = merged.result.get_summary_line();
+        assert!(line.starts_with("✗ intro.mp4"), "{}", line);
+        assert!(line.contains("合并分片失败: concat error"), "{}", line);
+
+        let chunk_failed = task_result(1, "outro", Some("分片 1 转换失败: boom"));
+        assert!(chunk_failed.result.get_summary_line().contains("分片 1 转换失败"));
+
+        assert!(task_result(2, "ok", None).result.get_summary_line().starts_with('✓'));
+    }
+
+    #[test]
+    fn test_skipped_video_reported_with_source_name() {
+        let skipped = skipped_result(3, PathBuf::from("videos/missing.mp4"), "输入文件不存在".to_string());
+        assert_eq!(skipped.task_id, 3);
+        assert_eq!(skipped.input_path, PathBuf::from("videos/missing.mp4"));
+        assert!(!skipped.result.success);
+        assert_eq!(skipped.result.get_summary_line(), "✗ missing.mp4 - 输入文件不存在");
+    }
+}
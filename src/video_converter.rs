@@ -3,6 +3,84 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use log::{info, error, debug};
 use crate::ffmpeg_plugin::FFmpegPlugin;
+use crate::models::VideoConvertOptions;
+
+/// 视频输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum VideoFormat {
+    /// OGV（Theora + Vorbis），Arma 3 唯一支持的视频格式
+    #[default]
+    Ogv,
+    /// WebM（VP8 + Vorbis），仅用于预览、文档等非 Arma 用途
+    WebmVp8,
+    /// WebM（VP9 + Opus），仅用于预览、文档等非 Arma 用途
+    WebmVp9,
+}
+
+impl VideoFormat {
+    /// 所有可选格式
+    pub const ALL: [VideoFormat; 3] = [VideoFormat::Ogv, VideoFormat::WebmVp8, VideoFormat::WebmVp9];
+
+    /// 输出文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            VideoFormat::Ogv => "ogv",
+            VideoFormat::WebmVp8 | VideoFormat::WebmVp9 => "webm",
+        }
+    }
+
+    /// 显示名称
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            VideoFormat::Ogv => "OGV (Theora) - Arma 3 可用",
+            VideoFormat::WebmVp8 => "WebM (VP8) - 仅预览",
+            VideoFormat::WebmVp9 => "WebM (VP9) - 仅预览",
+        }
+    }
+
+    /// 是否可用于 Arma 3 视频模组
+    pub fn is_arma_compatible(&self) -> bool {
+        matches!(self, VideoFormat::Ogv)
+    }
+
+    /// 相对编码开销（以 OGV 为 1.0），用于估算转换耗时
+    pub fn encode_cost(&self) -> f64 {
+        match self {
+            VideoFormat::Ogv => 1.0,
+            VideoFormat::WebmVp8 => 1.5,
+            VideoFormat::WebmVp9 => 4.0,
+        }
+    }
+
+    /// 视频编码参数（质量 0-10，数值越大质量越高）
+    pub fn video_codec_args(&self, video_quality: u8) -> Vec<String> {
+        // VPX 使用 CRF（数值越小质量越高），由 0-10 的质量等级映射
+        let crf = (50 - u32::from(video_quality.min(10)) * 4).to_string();
+        let args: Vec<&str> = match self {
+            VideoFormat::Ogv => return vec![
+                "-c:v".to_string(), "libtheora".to_string(),
+                "-q:v".to_string(), video_quality.to_string(),
+                "-speed".to_string(), "8".to_string(),
+            ],
+            VideoFormat::WebmVp8 => vec!["-c:v", "libvpx", "-crf", &crf, "-b:v", "2M", "-deadline", "realtime", "-cpu-used", "8"],
+            VideoFormat::WebmVp9 => vec!["-c:v", "libvpx-vp9", "-crf", &crf, "-b:v", "0", "-deadline", "realtime", "-cpu-used", "8", "-row-mt", "1"],
+        };
+        args.into_iter().map(String::from).collect()
+    }
+
+    /// 音频编码参数（质量 0-10，数值越大质量越高）
+    pub fn audio_codec_args(&self, audio_quality: u8) -> Vec<String> {
+        match self {
+            VideoFormat::Ogv | VideoFormat::WebmVp8 => {
+                vec!["-c:a".to_string(), "libvorbis".to_string(), "-q:a".to_string(), audio_quality.to_string()]
+            }
+            VideoFormat::WebmVp9 => {
+                let bitrate = format!("{}k", 64 + u32::from(audio_quality.min(10)) * 16);
+                vec!["-c:a".to_string(), "libopus".to_string(), "-b:a".to_string(), bitrate]
+            }
+        }
+    }
+}
 
 /// 视频转换器
 pub struct VideoConverter {
@@ -26,15 +104,25 @@ impl VideoConverter {
     }
     
     
-    /// 转换视频文件为 OGV 格式（标准模式）
-    pub fn convert_to_ogv(&self, input_path: &Path, output_path: &Path) -> Result<()> {
-        self.convert_to_ogv_with_quality(input_path, output_path, 5, 3)
+    /// 转换视频文件为指定格式（默认质量、原始分辨率）
+    pub fn convert_video(&self, input_path: &Path, output_path: &Path, format: VideoFormat) -> Result<()> {
+        self.convert_video_with_progress(input_path, output_path, format, VideoConvertOptions::default(), &|| false, &|_| {})
     }
 
-
-    /// 转换视频文件为 OGV 格式（自定义质量）
-    fn convert_to_ogv_with_quality(&self, input_path: &Path, output_path: &Path, video_quality: u8, audio_quality: u8) -> Result<()> {
-        info!("开始转换视频: {:?} -> {:?}", input_path, output_path);
+    /// 按转换选项（分辨率上限、质量）转换视频文件，通过 `on_progress` 报告当前文件的进度（0.0-1.0）
+    pub fn convert_video_with_progress<F>(
+        &self,
+        input_path: &Path,
+        output_path: &Path,
+        format: VideoFormat,
+        options: VideoConvertOptions,
+        should_cancel: &F,
+        on_progress: &(dyn Fn(f32) + Sync),
+    ) -> Result<()>
+    where
+        F: Fn() -> bool + ?Sized,
+    {
+        info!("开始转换视频 ({:?}, {:?}): {:?} -> {:?}", format, options, input_path, output_path);
         
         // 确保输出目录存在
         if let Some(parent) = output_path.parent() {
@@ -49,14 +137,13 @@ impl VideoConverter {
             .ok_or_else(|| anyhow!("输出路径包含无效UTF-8字符: {:?}", output_path))?;
         
         let mut cmd = Command::new(&self.ffmpeg_path);
-        cmd.args(&[
-            "-i", input_str,
-            "-c:v", "libtheora",  // 视频编码器：Theora
-            "-q:v", &video_quality.to_string(),  // 视频质量（动态设置）
-            "-speed", "8",        // 编码速度优化（0-10，8为最快速度）
-            "-threads", "0",      // 使用所有可用CPU核心
-            "-c:a", "libvorbis",  // 音频编码器：Vorbis
-            "-q:a", &audio_quality.to_string(),  // 音频质量（动态设置）
+        cmd.args(["-i", input_str]);
+        cmd.args(options.scale_args());                            // 分辨率上限
+        cmd.args(format.video_codec_args(options.video_quality));  // 视频编码器与质量
+        cmd.args(["-threads", "0"]);                               // 使用所有可用CPU核心
+        cmd.args(format.audio_codec_args(options.audio_quality));  // 音频编码器与质量
+        cmd.args(crate::ffmpeg_progress::PROGRESS_ARGS);
+        cmd.args([
             "-ac", "2",           // 立体声音频，减少处理时间
             "-y",                 // 覆盖输出文件
             output_str
@@ -72,7 +159,7 @@ impl VideoConverter {
         debug!("执行 FFmpeg 命令: {:?}", cmd);
         
         // 执行转换
-        let child = cmd
+        let mut child = cmd
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
@@ -89,15 +176,14 @@ impl VideoConverter {
             }
         }
         
-        let output = child
-            .wait_with_output()
-            .context("等待 FFmpeg 进程完成失败")?;
+        let (status, error_msg) =
+            crate::ffmpeg_progress::wait_with_progress(&mut child, should_cancel, on_progress)
+                .context("等待 FFmpeg 进程完成失败")?;
         
-        if output.status.success() {
+        if status.success() {
             info!("视频转换成功: {:?}", output_path);
             Ok(())
         } else {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
             error!("视频转换失败: {}", error_msg);
             Err(anyhow::anyhow!("视频转换失败: {}", error_msg))
         }
@@ -137,6 +223,34 @@ impl VideoConverter {
         self.parse_video_info(&stderr)
     }
     
+    /// 快速读取视频时长（只读取文件头，不解码整个视频）
+    pub fn probe_duration(&self, input_path: &Path) -> Result<u32> {
+        let input_str = input_path.to_str()
+            .ok_or_else(|| anyhow!("输入路径包含无效UTF-8字符: {:?}", input_path))?;
+
+        // 不指定输出时 FFmpeg 打印输入信息后立即退出（退出码非零属正常情况）
+        let mut cmd = Command::new(&self.ffmpeg_path);
+        cmd.args(["-hide_banner", "-i", input_str]);
+
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let output = cmd
+            .stdin(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .with_context(|| format!("无法读取视频时长: {:?}", input_path))?;
+
+        let info = self.parse_video_info(&String::from_utf8_lossy(&output.stderr))?;
+        if info.duration == 0 {
+            return Err(anyhow!("无法读取视频时长: {:?}", input_path));
+        }
+        Ok(info.duration)
+    }
+
     /// 解析 FFmpeg 输出的视频信息
     fn parse_video_info(&self, output: &str) -> Result<VideoInfo> {
         debug!("开始解析FFmpeg输出:\n{}", output);